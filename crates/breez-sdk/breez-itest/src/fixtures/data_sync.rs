@@ -24,6 +24,17 @@ impl Default for DataSyncImageConfig {
     }
 }
 
+/// Configuration building the sync server of this workspace instead of data-sync
+fn sync_server_image_config() -> DataSyncImageConfig {
+    DataSyncImageConfig {
+        // The Dockerfile expects the repository root as context
+        context_path: "../../..".to_string(),
+        dockerfile_path: "crates/breez-sdk/sync-server/Dockerfile".to_string(),
+        image_name: "breez-sync-server-built".to_string(),
+        image_tag: "latest".to_string(),
+    }
+}
+
 pub struct DataSyncFixture {
     pub container: ContainerAsync<GenericImage>,
     pub grpc_url: String,
@@ -33,6 +44,7 @@ impl DataSyncFixture {
     /// Create a new DataSyncFixture using the default configuration.
     ///
     /// If `DATA_SYNC_PATH` is set, builds the Docker image from that local path.
+    /// If `DATA_SYNC_USE_SYNC_SERVER` is set, builds the sync server of this workspace.
     /// Otherwise, builds from the GitHub repository.
     pub async fn new() -> Result<Self> {
         let config = match std::env::var("DATA_SYNC_PATH") {
@@ -43,6 +55,10 @@ impl DataSyncFixture {
                     ..DataSyncImageConfig::default()
                 }
             }
+            Err(_) if std::env::var("DATA_SYNC_USE_SYNC_SERVER").is_ok() => {
+                info!("Using the sync server of this workspace");
+                sync_server_image_config()
+            }
             Err(_) => DataSyncImageConfig::default(),
         };
         Self::with_config(config).await
@@ -58,6 +74,9 @@ impl DataSyncFixture {
             .with_exposed_port(ContainerPort::Tcp(GRPC_PORT))
             .with_wait_for(WaitFor::message_on_stderr("Server listening at"))
             .with_log_consumer(crate::log::TracingConsumer::new("data-sync"))
+            // Used by the sync server of this workspace, an in-memory database keeps tests isolated
+            .with_env_var("BREEZ_SYNC_DB_URL", ":memory:")
+            .with_env_var("BREEZ_SYNC_AUTO_MIGRATE", "true")
             .start()
             .await?;
 
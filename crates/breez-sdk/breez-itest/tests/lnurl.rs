@@ -108,6 +108,13 @@ async fn test_01_register_lightning_address(#[future] bob_sdk: Result<SdkInstanc
         .register_lightning_address(RegisterLightningAddressRequest {
             username: username.to_string(),
             description: Some("Bob's test Lightning address".to_string()),
+            min_sendable: None,
+            max_sendable: None,
+            comment_allowed: None,
+            image: None,
+            long_description: None,
+            nostr_identity: None,
+            publish_bip353: None,
         })
         .await?;
 
@@ -153,6 +160,13 @@ async fn test_02_check_lightning_address_available(
         .register_lightning_address(RegisterLightningAddressRequest {
             username: "takenuser".to_string(),
             description: Some("Test address".to_string()),
+            min_sendable: None,
+            max_sendable: None,
+            comment_allowed: None,
+            image: None,
+            long_description: None,
+            nostr_identity: None,
+            publish_bip353: None,
         })
         .await?;
 
@@ -187,6 +201,13 @@ async fn test_03_get_lightning_address(#[future] bob_sdk: Result<SdkInstance>) -
         .register_lightning_address(RegisterLightningAddressRequest {
             username: username.to_string(),
             description: Some(description.to_string()),
+            min_sendable: None,
+            max_sendable: None,
+            comment_allowed: None,
+            image: None,
+            long_description: None,
+            nostr_identity: None,
+            publish_bip353: None,
         })
         .await?;
 
@@ -232,6 +253,13 @@ async fn test_04_delete_lightning_address(#[future] bob_sdk: Result<SdkInstance>
         .register_lightning_address(RegisterLightningAddressRequest {
             username: username.to_string(),
             description: Some("Address to be deleted".to_string()),
+            min_sendable: None,
+            max_sendable: None,
+            comment_allowed: None,
+            image: None,
+            long_description: None,
+            nostr_identity: None,
+            publish_bip353: None,
         })
         .await?;
 
@@ -291,6 +319,13 @@ async fn test_05_lnurl_payment_flow(
         .register_lightning_address(RegisterLightningAddressRequest {
             username: username.to_string(),
             description: Some(description.to_string()),
+            min_sendable: None,
+            max_sendable: None,
+            comment_allowed: None,
+            image: None,
+            long_description: None,
+            nostr_identity: None,
+            publish_bip353: None,
         })
         .await?;
 
@@ -322,6 +357,7 @@ async fn test_05_lnurl_payment_flow(
             validate_success_action_url: None,
             conversion_options: None,
             fee_policy: None,
+            payer_data: None,
         })
         .await?;
 
@@ -486,6 +522,13 @@ async fn test_06_client_side_zap_receipt(
             .register_lightning_address(RegisterLightningAddressRequest {
                 username: username.to_string(),
                 description: Some(description.to_string()),
+                min_sendable: None,
+                max_sendable: None,
+                comment_allowed: None,
+                image: None,
+                long_description: None,
+                nostr_identity: None,
+                publish_bip353: None,
             })
             .await?;
 
@@ -793,6 +836,13 @@ async fn test_07_lnurl_send_all_payment(
         .register_lightning_address(RegisterLightningAddressRequest {
             username: username.to_string(),
             description: Some(description.to_string()),
+            min_sendable: None,
+            max_sendable: None,
+            comment_allowed: None,
+            image: None,
+            long_description: None,
+            nostr_identity: None,
+            publish_bip353: None,
         })
         .await?;
 
@@ -838,6 +888,7 @@ async fn test_07_lnurl_send_all_payment(
             validate_success_action_url: None,
             conversion_options: None,
             fee_policy: Some(FeePolicy::FeesIncluded),
+            payer_data: None,
         })
         .await?;
 
@@ -930,6 +981,13 @@ async fn test_08_lnurl_send_all_with_fee_overpayment(
         .register_lightning_address(RegisterLightningAddressRequest {
             username: username.to_string(),
             description: Some(description.to_string()),
+            min_sendable: None,
+            max_sendable: None,
+            comment_allowed: None,
+            image: None,
+            long_description: None,
+            nostr_identity: None,
+            publish_bip353: None,
         })
         .await?;
 
@@ -984,6 +1042,7 @@ async fn test_08_lnurl_send_all_with_fee_overpayment(
                 validate_success_action_url: None,
                 conversion_options: None,
                 fee_policy: None,
+                payer_data: None,
             })
             .await?;
         Ok(prepare.fee_sats)
@@ -1126,6 +1185,7 @@ async fn test_08_lnurl_send_all_with_fee_overpayment(
             validate_success_action_url: None,
             conversion_options: None,
             fee_policy: Some(FeePolicy::FeesIncluded),
+            payer_data: None,
         })
         .await?;
 
@@ -1216,6 +1276,13 @@ async fn test_09_invoice_expiry_parameter(#[future] bob_sdk: Result<SdkInstance>
         .register_lightning_address(RegisterLightningAddressRequest {
             username: username.to_string(),
             description: Some("Expiry test address".to_string()),
+            min_sendable: None,
+            max_sendable: None,
+            comment_allowed: None,
+            image: None,
+            long_description: None,
+            nostr_identity: None,
+            publish_bip353: None,
         })
         .await?;
 
@@ -1328,6 +1395,13 @@ async fn test_10_lud21_verify(
         .register_lightning_address(RegisterLightningAddressRequest {
             username: username.to_string(),
             description: Some(description.to_string()),
+            min_sendable: None,
+            max_sendable: None,
+            comment_allowed: None,
+            image: None,
+            long_description: None,
+            nostr_identity: None,
+            publish_bip353: None,
         })
         .await?;
 
@@ -137,6 +137,13 @@ async fn test_01_rtsync_lnurl_info_sync(
         .register_lightning_address(RegisterLightningAddressRequest {
             username: "bob".to_string(),
             description: Some(ln_address_description.clone()),
+            min_sendable: None,
+            max_sendable: None,
+            comment_allowed: None,
+            image: None,
+            long_description: None,
+            nostr_identity: None,
+            publish_bip353: None,
         })
         .await?
         .lightning_address;
@@ -158,6 +165,7 @@ async fn test_01_rtsync_lnurl_info_sync(
             validate_success_action_url: None,
             conversion_options: None,
             fee_policy: None,
+            payer_data: None,
         })
         .await?;
 
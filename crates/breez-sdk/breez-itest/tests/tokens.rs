@@ -629,6 +629,7 @@ async fn test_04_token_freeze_unfreeze(
         .get_token_issuer()
         .freeze_issuer_token(FreezeIssuerTokenRequest {
             address: bob_spark_address.clone(),
+            reason: None,
         })
         .await?;
 
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use breez_sdk_spark::*;
+use rand::RngCore;
+use tempdir::TempDir;
+use tracing::info;
+
+async fn build_wallet_manager(temp_dir: &TempDir) -> Result<WalletManager> {
+    let mut config = default_config(Network::Regtest);
+    config.api_key = None; // Regtest: no API key needed
+    config.lnurl_domain = None; // Avoid lnurl server in tests
+    config.real_time_sync_server_url = None; // Disable real-time sync for tests
+
+    let manager = WalletManagerBuilder::new(config)
+        .with_default_storage(temp_dir.path().to_string_lossy().to_string())
+        .build()
+        .await?;
+    Ok(manager)
+}
+
+fn random_seed() -> Seed {
+    let mut seed = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut seed);
+    Seed::Entropy(seed.to_vec())
+}
+
+fn add_account_request(account_id: &str, seed: Seed) -> AddAccountRequest {
+    AddAccountRequest {
+        account_id: account_id.to_string(),
+        seed,
+        key_set_config: None,
+    }
+}
+
+/// Test 1: Adding an account with the id or the keys of another account fails
+#[test_log::test(tokio::test)]
+async fn test_01_add_duplicate_account() -> Result<()> {
+    info!("=== Starting test_01_add_duplicate_account ===");
+
+    let temp_dir = TempDir::new("wallet-manager")?;
+    let manager = build_wallet_manager(&temp_dir).await?;
+    let seed = random_seed();
+
+    manager
+        .add_account(add_account_request("business", seed.clone()))
+        .await?;
+
+    let result = manager
+        .add_account(add_account_request("business", random_seed()))
+        .await;
+    assert!(
+        matches!(result, Err(SdkError::InvalidInput(_))),
+        "Adding an account with an existing id should fail"
+    );
+
+    let result = manager
+        .add_account(add_account_request("personal", seed.clone()))
+        .await;
+    assert!(
+        matches!(result, Err(SdkError::InvalidInput(_))),
+        "Adding an account with the keys of another account should fail"
+    );
+
+    // The same seed with another account number is another wallet
+    manager
+        .add_account(AddAccountRequest {
+            account_id: "personal".to_string(),
+            seed,
+            key_set_config: Some(KeySetConfig {
+                account_number: Some(1),
+                ..Default::default()
+            }),
+        })
+        .await?;
+    assert_eq!(manager.list_accounts().await, vec!["business", "personal"]);
+
+    manager.disconnect().await?;
+
+    info!("=== Test test_01_add_duplicate_account PASSED ===");
+    Ok(())
+}
+
+/// Test 2: A removed account is no longer listed and can be added again
+#[test_log::test(tokio::test)]
+async fn test_02_remove_account() -> Result<()> {
+    info!("=== Starting test_02_remove_account ===");
+
+    let temp_dir = TempDir::new("wallet-manager")?;
+    let manager = build_wallet_manager(&temp_dir).await?;
+    let seed = random_seed();
+
+    manager
+        .add_account(add_account_request("business", seed.clone()))
+        .await?;
+    assert!(manager.remove_account("business").await?);
+
+    assert!(manager.get_account("business").await.is_none());
+    assert!(manager.list_accounts().await.is_empty());
+    assert!(
+        !manager.remove_account("business").await?,
+        "Removing an unknown account should return false"
+    );
+
+    manager
+        .add_account(add_account_request("business", seed))
+        .await?;
+    assert!(manager.get_account("business").await.is_some());
+
+    manager.disconnect().await?;
+
+    info!("=== Test test_02_remove_account PASSED ===");
+    Ok(())
+}
+
+/// Test 3: Disconnecting stops and removes all accounts
+#[test_log::test(tokio::test)]
+async fn test_03_disconnect() -> Result<()> {
+    info!("=== Starting test_03_disconnect ===");
+
+    let temp_dir = TempDir::new("wallet-manager")?;
+    let manager = build_wallet_manager(&temp_dir).await?;
+
+    manager
+        .add_account(add_account_request("business", random_seed()))
+        .await?;
+    manager
+        .add_account(add_account_request("personal", random_seed()))
+        .await?;
+
+    manager.disconnect().await?;
+
+    assert!(manager.list_accounts().await.is_empty());
+    assert!(manager.get_account("business").await.is_none());
+    assert!(manager.get_account("personal").await.is_none());
+
+    info!("=== Test test_03_disconnect PASSED ===");
+    Ok(())
+}
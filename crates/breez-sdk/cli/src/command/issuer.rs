@@ -1,6 +1,7 @@
 use breez_sdk_spark::{
-    BurnIssuerTokenRequest, CreateIssuerTokenRequest, FreezeIssuerTokenRequest,
-    MintIssuerTokenRequest, TokenIssuer, UnfreezeIssuerTokenRequest,
+    BurnIssuerTokenRequest, CreateIssuerTokenRequest, DistributeIssuerTokenRequest,
+    FreezeIssuerTokenRequest, ListFrozenTokenHoldersRequest, ListIssuerTokenTransactionsRequest,
+    MintIssuerTokenRequest, TokenDistributionRecipient, TokenIssuer, UnfreezeIssuerTokenRequest,
 };
 use clap::{ArgAction, Subcommand};
 
@@ -12,6 +13,17 @@ pub enum IssuerCommand {
     TokenBalance,
     /// Gets the issuer token metadata
     TokenMetadata,
+    /// Gets the minted, burned and circulating supply of the issuer token
+    TokenSupply,
+    /// Lists the mint, burn and transfer transactions of the issuer token
+    TokenTransactions {
+        /// Number of transactions to skip
+        #[arg(short, long)]
+        offset: Option<u32>,
+        /// Maximum number of transactions to return
+        #[arg(short, long)]
+        limit: Option<u32>,
+    },
     /// Creates a new issuer token
     CreateToken {
         /// Name of the token
@@ -40,12 +52,42 @@ pub enum IssuerCommand {
     FreezeToken {
         /// Address holding the tokens to freeze
         address: String,
+        /// Reason for the freeze, recorded in the freeze registry
+        #[arg(short, long)]
+        reason: Option<String>,
     },
     /// Unfreezes issuer tokens held at the specified address
     UnfreezeToken {
         /// Address holding the tokens to unfreeze
         address: String,
     },
+    /// Lists the holders whose issuer tokens were frozen
+    FrozenHolders {
+        /// Whether to include holders that were unfrozen since
+        #[arg(short = 'a', long, action = ArgAction::SetTrue)]
+        include_unfrozen: bool,
+    },
+    /// Distributes issuer tokens to many recipients, minting supply if needed
+    DistributeToken {
+        /// Recipients in the form `<spark address>:<amount>`
+        #[arg(required = true, value_parser = parse_distribution_recipient)]
+        recipients: Vec<TokenDistributionRecipient>,
+        /// Key used to resume a previous distribution. Derived from the recipients if not set
+        #[arg(short = 'k', long)]
+        idempotency_key: Option<String>,
+    },
+}
+
+fn parse_distribution_recipient(value: &str) -> Result<TokenDistributionRecipient, String> {
+    let (address, amount) = value
+        .rsplit_once(':')
+        .ok_or("expected <spark address>:<amount>")?;
+    Ok(TokenDistributionRecipient {
+        address: address.to_string(),
+        amount: amount
+            .parse()
+            .map_err(|_| format!("invalid amount: {amount}"))?,
+    })
 }
 
 pub async fn handle_command(
@@ -63,6 +105,21 @@ pub async fn handle_command(
             print_value(&metadata)?;
             Ok(true)
         }
+        IssuerCommand::TokenSupply => {
+            let supply = token_issuer.get_issuer_token_supply().await?;
+            print_value(&supply)?;
+            Ok(true)
+        }
+        IssuerCommand::TokenTransactions { offset, limit } => {
+            let response = token_issuer
+                .list_issuer_token_transactions(ListIssuerTokenTransactionsRequest {
+                    offset,
+                    limit,
+                })
+                .await?;
+            print_value(&response)?;
+            Ok(true)
+        }
         IssuerCommand::CreateToken {
             name,
             ticker,
@@ -96,9 +153,9 @@ pub async fn handle_command(
             print_value(&payment)?;
             Ok(true)
         }
-        IssuerCommand::FreezeToken { address } => {
+        IssuerCommand::FreezeToken { address, reason } => {
             let response = token_issuer
-                .freeze_issuer_token(FreezeIssuerTokenRequest { address })
+                .freeze_issuer_token(FreezeIssuerTokenRequest { address, reason })
                 .await?;
             print_value(&response)?;
             Ok(true)
@@ -110,5 +167,27 @@ pub async fn handle_command(
             print_value(&response)?;
             Ok(true)
         }
+        IssuerCommand::FrozenHolders { include_unfrozen } => {
+            let response = token_issuer
+                .list_frozen_token_holders(ListFrozenTokenHoldersRequest {
+                    include_unfrozen: Some(include_unfrozen),
+                })
+                .await?;
+            print_value(&response)?;
+            Ok(true)
+        }
+        IssuerCommand::DistributeToken {
+            recipients,
+            idempotency_key,
+        } => {
+            let response = token_issuer
+                .distribute_issuer_token(DistributeIssuerTokenRequest {
+                    recipients,
+                    idempotency_key,
+                })
+                .await?;
+            print_value(&response)?;
+            Ok(true)
+        }
     }
 }
@@ -3,13 +3,16 @@ mod issuer;
 use bitcoin::hashes::{Hash, sha256};
 use breez_sdk_spark::{
     AssetFilter, BreezSdk, BuyBitcoinRequest, CheckLightningAddressRequest, ClaimDepositRequest,
-    ClaimHtlcPaymentRequest, ConversionOptions, ConversionType, Fee, FeePolicy,
-    FetchConversionLimitsRequest, GetInfoRequest, GetPaymentRequest, GetTokensMetadataRequest,
-    InputType, LightningAddressDetails, ListPaymentsRequest, ListUnclaimedDepositsRequest,
-    LnurlPayRequest, LnurlWithdrawRequest, MaxFee, OnchainConfirmationSpeed, PaymentDetailsFilter,
-    PaymentStatus, PaymentType, PrepareLnurlPayRequest, PrepareSendPaymentRequest,
-    ReceivePaymentMethod, ReceivePaymentRequest, RefundDepositRequest,
-    RegisterLightningAddressRequest, SendPaymentMethod, SendPaymentOptions, SendPaymentRequest,
+    ClaimHtlcPaymentRequest, ConversionOptions, ConversionType, CreateLnurlWithdrawLinkRequest,
+    DeleteLnurlWebhookRequest, DeleteLnurlWithdrawLinkRequest, Fee, FeePolicy,
+    FetchConversionLimitsRequest, GetInfoRequest, GetPaymentRequest, GetSyncedValueRequest,
+    GetTokensMetadataRequest, ImportBackupRequest, InputType, LightningAddressDetails,
+    ListConversionsRequest, ListPaymentsRequest, ListUnclaimedDepositsRequest, LnurlPayRequest,
+    LnurlPayerDataOptions, LnurlWebhookEvent, LnurlWithdrawRequest, MaxFee, NostrIdentity,
+    OnchainConfirmationSpeed, PaymentDetailsFilter, PaymentStatus, PaymentType,
+    PrepareLnurlPayRequest, PrepareSendPaymentRequest, ReceivePaymentMethod, ReceivePaymentRequest,
+    RefundDepositRequest, RegisterLightningAddressRequest, RegisterLnurlWebhookRequest,
+    SendPaymentMethod, SendPaymentOptions, SendPaymentRequest, SetSyncedValueRequest,
     SparkHtlcOptions, SparkHtlcStatus, SyncWalletRequest, TokenIssuer, TokenTransactionType,
     UpdateUserSettingsRequest,
 };
@@ -191,6 +194,18 @@ pub enum Command {
         /// If set, fees will be deducted from the specified amount instead of added on top.
         #[arg(long = "fees-included", action = clap::ArgAction::SetTrue)]
         fees_included: bool,
+
+        /// Optional payer name, sent if requested by the LNURL endpoint (LUD-18)
+        #[arg(long)]
+        payer_name: Option<String>,
+
+        /// Optional payer identifier, sent if requested by the LNURL endpoint (LUD-18)
+        #[arg(long)]
+        payer_identifier: Option<String>,
+
+        /// If set, shares the linking key with the LNURL endpoint if requested (LUD-18)
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        share_linking_key: bool,
     },
 
     /// Withdraw using LNURL
@@ -277,8 +292,87 @@ pub enum Command {
 
         /// Description in the lnurl response and the invoice.
         description: Option<String>,
+
+        /// Minimum amount (in millisatoshi) that can be sent
+        #[arg(long)]
+        min_sendable: Option<u64>,
+
+        /// Maximum amount (in millisatoshi) that can be sent
+        #[arg(long)]
+        max_sendable: Option<u64>,
+
+        /// Maximum length of the payer comment
+        #[arg(long)]
+        comment_allowed: Option<u16>,
+
+        /// Base64 encoded PNG image shown by wallets paying to the address
+        #[arg(long)]
+        image: Option<String>,
+
+        /// Long description shown by wallets paying to the address
+        #[arg(long)]
+        long_description: Option<String>,
+
+        /// Hex encoded nostr public key to serve as a NIP-05 identifier
+        #[arg(long, requires = "nostr_proof")]
+        nostr_pubkey: Option<String>,
+
+        /// Relay the nostr public key can be found on. Can be repeated
+        #[arg(long = "nostr-relay", requires = "nostr_pubkey")]
+        nostr_relays: Vec<String>,
+
+        /// JSON of a nostr event signed by the nostr key, proving ownership of the key
+        #[arg(long, requires = "nostr_pubkey")]
+        nostr_proof: Option<String>,
+
+        /// Publish BIP-353 payment instructions for the username in DNS
+        #[arg(long)]
+        publish_bip353: bool,
     },
     DeleteLightningAddress,
+    /// Create an LNURL-withdraw link, paid by this wallet when it syncs
+    CreateLnurlWithdrawLink {
+        /// Minimum amount (in satoshi) that can be withdrawn per use
+        min_withdrawable_sat: u64,
+
+        /// Maximum amount (in satoshi) that can be withdrawn per use
+        max_withdrawable_sat: u64,
+
+        /// Description put in the withdrawer's invoice
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Number of times the link can be used. Unlimited if not set.
+        #[arg(long)]
+        max_uses: Option<u32>,
+
+        /// Unix timestamp (seconds) after which the link can no longer be used
+        #[arg(long)]
+        expires_at: Option<u64>,
+    },
+    /// List the LNURL-withdraw links of this wallet
+    ListLnurlWithdrawLinks,
+    /// Delete an LNURL-withdraw link
+    DeleteLnurlWithdrawLink {
+        /// The id of the withdraw link
+        id: String,
+    },
+    /// Register a webhook notified about invoices of the lightning address
+    RegisterLnurlWebhook {
+        /// The url the LNURL server posts the events to
+        url: String,
+
+        /// Events to notify: invoice_requested, invoice_paid. Can be repeated
+        #[arg(short, long = "event", required = true)]
+        events: Vec<LnurlWebhookEvent>,
+    },
+    /// List the webhooks of the lightning address
+    ListLnurlWebhooks,
+    /// Delete a webhook of the lightning address
+    DeleteLnurlWebhook {
+        /// The id of the webhook
+        id: String,
+    },
     /// List fiat currencies
     ListFiatCurrencies,
     /// List available fiat rates
@@ -297,12 +391,54 @@ pub enum Command {
         /// The token identifier of the token
         token_identifier: String,
     },
+    /// Lists the conversions made by the wallet, with totals per asset pair
+    ListConversions {
+        /// Only include conversions created after this timestamp (inclusive)
+        #[arg(long)]
+        from_timestamp: Option<u64>,
+
+        /// Only include conversions created before this timestamp (exclusive)
+        #[arg(long)]
+        to_timestamp: Option<u64>,
+
+        /// Number of conversions to show
+        #[arg(short, long)]
+        limit: Option<u32>,
+
+        /// Number of conversions to skip
+        #[arg(short, long)]
+        offset: Option<u32>,
+    },
+    /// Export an encrypted backup of the wallet data to a file
+    ExportBackup {
+        /// The file to write the backup to
+        file: String,
+    },
+    /// Import a backup created by `export-backup`
+    ImportBackup {
+        /// The backup file
+        file: String,
+    },
     GetUserSettings,
     SetUserSettings {
         /// Whether spark private mode is enabled.
         #[clap(short = 'p', long = "private")]
         spark_private_mode_enabled: Option<bool>,
     },
+    /// Set a value shared by all instances of the wallet
+    SetSyncedValue {
+        /// The namespace of the key
+        namespace: String,
+        key: String,
+        /// The value to set. Removes the key if not set
+        value: Option<String>,
+    },
+    /// Get a value shared by all instances of the wallet
+    GetSyncedValue {
+        /// The namespace of the key
+        namespace: String,
+        key: String,
+    },
 
     /// Get the status of the Spark network services
     GetSparkStatus,
@@ -656,6 +792,9 @@ pub(crate) async fn execute_command(
             convert_from_token_identifier,
             convert_max_slippage_bps: max_slippage_bps,
             fees_included,
+            payer_name,
+            payer_identifier,
+            share_linking_key,
         } => {
             let conversion_options =
                 convert_from_token_identifier.map(|from_token_identifier| ConversionOptions {
@@ -670,6 +809,11 @@ pub(crate) async fn execute_command(
             } else {
                 None
             };
+            let payer_data = LnurlPayerDataOptions {
+                name: payer_name,
+                identifier: payer_identifier,
+                share_linking_key,
+            };
 
             let input = sdk.parse(&lnurl).await?;
             let res = match input {
@@ -689,6 +833,7 @@ pub(crate) async fn execute_command(
                             validate_success_action_url: validate_success_url,
                             conversion_options,
                             fee_policy,
+                            payer_data: Some(payer_data),
                         })
                         .await?;
 
@@ -799,11 +944,35 @@ pub(crate) async fn execute_command(
         Command::RegisterLightningAddress {
             username,
             description,
+            min_sendable,
+            max_sendable,
+            comment_allowed,
+            image,
+            long_description,
+            nostr_pubkey,
+            nostr_relays,
+            nostr_proof,
+            publish_bip353,
         } => {
+            let nostr_identity = match (nostr_pubkey, nostr_proof) {
+                (Some(pubkey), Some(proof)) => Some(NostrIdentity {
+                    pubkey,
+                    relays: nostr_relays,
+                    proof,
+                }),
+                _ => None,
+            };
             let res = sdk
                 .register_lightning_address(RegisterLightningAddressRequest {
                     username,
                     description,
+                    min_sendable,
+                    max_sendable,
+                    comment_allowed,
+                    image,
+                    long_description,
+                    nostr_identity,
+                    publish_bip353: Some(publish_bip353),
                 })
                 .await?;
             print_value(&res)?;
@@ -813,6 +982,52 @@ pub(crate) async fn execute_command(
             sdk.delete_lightning_address().await?;
             Ok(true)
         }
+        Command::CreateLnurlWithdrawLink {
+            min_withdrawable_sat,
+            max_withdrawable_sat,
+            description,
+            max_uses,
+            expires_at,
+        } => {
+            let res = sdk
+                .create_lnurl_withdraw_link(CreateLnurlWithdrawLinkRequest {
+                    min_withdrawable_sat,
+                    max_withdrawable_sat,
+                    description,
+                    max_uses,
+                    expires_at,
+                })
+                .await?;
+            print_value(&res)?;
+            Ok(true)
+        }
+        Command::ListLnurlWithdrawLinks => {
+            let res = sdk.list_lnurl_withdraw_links().await?;
+            print_value(&res)?;
+            Ok(true)
+        }
+        Command::DeleteLnurlWithdrawLink { id } => {
+            sdk.delete_lnurl_withdraw_link(DeleteLnurlWithdrawLinkRequest { id })
+                .await?;
+            Ok(true)
+        }
+        Command::RegisterLnurlWebhook { url, events } => {
+            let res = sdk
+                .register_lnurl_webhook(RegisterLnurlWebhookRequest { url, events })
+                .await?;
+            print_value(&res)?;
+            Ok(true)
+        }
+        Command::ListLnurlWebhooks => {
+            let res = sdk.list_lnurl_webhooks().await?;
+            print_value(&res)?;
+            Ok(true)
+        }
+        Command::DeleteLnurlWebhook { id } => {
+            sdk.delete_lnurl_webhook(DeleteLnurlWebhookRequest { id })
+                .await?;
+            Ok(true)
+        }
         Command::ListFiatCurrencies => {
             let res = sdk.list_fiat_currencies().await?;
             print_value(&res)?;
@@ -856,6 +1071,35 @@ pub(crate) async fn execute_command(
             print_value(&res)?;
             Ok(true)
         }
+        Command::ListConversions {
+            from_timestamp,
+            to_timestamp,
+            limit,
+            offset,
+        } => {
+            let res = sdk
+                .list_conversions(ListConversionsRequest {
+                    from_timestamp,
+                    to_timestamp,
+                    offset,
+                    limit,
+                })
+                .await?;
+            print_value(&res)?;
+            Ok(true)
+        }
+        Command::ExportBackup { file } => {
+            let res = sdk.export_backup().await?;
+            std::fs::write(&file, res.backup)?;
+            println!("Backup written to {file}");
+            Ok(true)
+        }
+        Command::ImportBackup { file } => {
+            let backup = std::fs::read(&file)?;
+            let res = sdk.import_backup(ImportBackupRequest { backup }).await?;
+            print_value(&res)?;
+            Ok(true)
+        }
         Command::GetUserSettings => {
             let res = sdk.get_user_settings().await?;
             print_value(&res)?;
@@ -870,6 +1114,26 @@ pub(crate) async fn execute_command(
             .await?;
             Ok(true)
         }
+        Command::SetSyncedValue {
+            namespace,
+            key,
+            value,
+        } => {
+            sdk.set_synced_value(SetSyncedValueRequest {
+                namespace,
+                key,
+                value,
+            })
+            .await?;
+            Ok(true)
+        }
+        Command::GetSyncedValue { namespace, key } => {
+            let res = sdk
+                .get_synced_value(GetSyncedValueRequest { namespace, key })
+                .await?;
+            print_value(&res)?;
+            Ok(true)
+        }
         Command::GetSparkStatus => {
             let res = breez_sdk_spark::get_spark_status().await?;
             print_value(&res)?;
@@ -5,15 +5,16 @@ use crate::command::CliHelper;
 use crate::persist::CliPersistence;
 use anyhow::{Result, anyhow};
 use breez_sdk_spark::{
-    EventListener, Network, SdkBuilder, SdkEvent, Seed, StableBalanceConfig, default_config,
-    default_postgres_storage_config,
+    EventListener, KeySetConfig, KeySetType, Network, PostgresStorage, SdkBuilder, SdkEvent, Seed,
+    SqliteStorage, StableBalanceConfig, Storage, default_config, default_external_signer,
+    default_postgres_storage_config, default_storage_path, migrate_storage, signer::ExternalSigner,
 };
-use clap::Parser;
-use command::{Command, execute_command};
+use clap::{Parser, Subcommand};
+use command::{Command, execute_command, print_value};
 use rustyline::Editor;
 use rustyline::error::ReadlineError;
 use rustyline::hint::HistoryHinter;
-use std::{fs, path::PathBuf};
+use std::{fs, path::PathBuf, sync::Arc};
 use tracing::{error, info};
 
 #[derive(Parser)]
@@ -43,6 +44,27 @@ struct Cli {
     /// Stable balance threshold, in sats
     #[arg(long)]
     stable_balance_threshold: Option<u64>,
+
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+}
+
+#[derive(Subcommand)]
+enum CliCommand {
+    /// Copies the wallet data from the configured storage to another storage backend and exits
+    MigrateStorage {
+        /// `PostgreSQL` connection string of the target storage
+        #[arg(
+            long,
+            conflicts_with = "to_data_dir",
+            required_unless_present = "to_data_dir"
+        )]
+        to_postgres_connection_string: Option<String>,
+
+        /// Data directory of the target `SQLite` storage
+        #[arg(long)]
+        to_data_dir: Option<String>,
+    },
 }
 
 fn expand_path(path: &str) -> PathBuf {
@@ -89,6 +111,70 @@ impl EventListener for CliEventListener {
     }
 }
 
+fn key_set_config(account_number: Option<u32>) -> Option<KeySetConfig> {
+    account_number.map(|account_number| KeySetConfig {
+        key_set_type: KeySetType::Default,
+        use_address_index: false,
+        account_number: Some(account_number),
+    })
+}
+
+async fn create_storage(
+    data_dir: &str,
+    postgres_connection_string: Option<String>,
+    network: Network,
+    identity_pub_key: &bitcoin::secp256k1::PublicKey,
+) -> Result<Arc<dyn Storage>> {
+    if let Some(connection_string) = postgres_connection_string {
+        let storage =
+            PostgresStorage::new(default_postgres_storage_config(connection_string)).await?;
+        return Ok(Arc::new(storage));
+    }
+    let db_path = default_storage_path(data_dir, &network, identity_pub_key)?;
+    Ok(Arc::new(SqliteStorage::new(&db_path)?))
+}
+
+async fn run_migrate_storage(
+    data_dir: PathBuf,
+    network: Network,
+    account_number: Option<u32>,
+    postgres_connection_string: Option<String>,
+    to_postgres_connection_string: Option<String>,
+    to_data_dir: Option<String>,
+) -> Result<()> {
+    let persistence = CliPersistence {
+        data_dir: data_dir.clone(),
+    };
+    let mnemonic = persistence.get_or_create_mnemonic()?;
+    let signer = default_external_signer(
+        mnemonic.to_string(),
+        None,
+        network,
+        key_set_config(account_number),
+    )?;
+    let identity_pub_key = signer.identity_public_key()?.to_public_key()?;
+
+    let source = create_storage(
+        &data_dir.to_string_lossy(),
+        postgres_connection_string,
+        network,
+        &identity_pub_key,
+    )
+    .await?;
+    let to_data_dir = to_data_dir.map(|dir| expand_path(&dir));
+    let target = create_storage(
+        &to_data_dir.unwrap_or_default().to_string_lossy(),
+        to_postgres_connection_string,
+        network,
+        &identity_pub_key,
+    )
+    .await?;
+
+    let summary = migrate_storage(source, target).await?;
+    print_value(&summary)?;
+    Ok(())
+}
+
 async fn run_interactive_mode(
     data_dir: PathBuf,
     network: Network,
@@ -132,12 +218,8 @@ async fn run_interactive_mode(
     } else {
         sdk_builder = sdk_builder.with_default_storage(data_dir.to_string_lossy().to_string());
     }
-    if let Some(account_number) = account_number {
-        sdk_builder = sdk_builder.with_key_set(breez_sdk_spark::KeySetConfig {
-            key_set_type: breez_sdk_spark::KeySetType::Default,
-            use_address_index: false,
-            account_number: Some(account_number),
-        });
+    if let Some(key_set_config) = key_set_config(account_number) {
+        sdk_builder = sdk_builder.with_key_set(key_set_config);
     }
 
     let sdk = sdk_builder.build().await?;
@@ -229,6 +311,22 @@ async fn main() -> Result<(), anyhow::Error> {
                 reserved_sats: None,
             });
 
+    if let Some(CliCommand::MigrateStorage {
+        to_postgres_connection_string,
+        to_data_dir,
+    }) = cli.command
+    {
+        return run_migrate_storage(
+            data_dir,
+            network,
+            cli.account_number,
+            cli.postgres_connection_string,
+            to_postgres_connection_string,
+            to_data_dir,
+        )
+        .await;
+    }
+
     Box::pin(run_interactive_mode(
         data_dir,
         network,
@@ -8,6 +8,7 @@ pub mod input;
 pub mod invoice;
 pub mod lnurl;
 pub mod network;
+pub mod remote_signer;
 pub mod sync;
 pub mod tonic_wrap;
 pub mod utils;
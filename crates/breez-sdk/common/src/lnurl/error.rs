@@ -34,6 +34,8 @@ pub enum LnurlError {
     InvalidInvoice(String),
     #[error("lnurl has invalid response: {0}")]
     InvalidResponse(String),
+    #[error("lnurl service requires payer data that was not provided")]
+    MissingPayerData,
 }
 
 impl LnurlError {
@@ -8,6 +8,7 @@ use crate::{
     invoice::{InvoiceError, validate_network},
     lnurl::{
         LnurlErrorDetails,
+        auth::{LnurlAuthSigner, get_derivation_path},
         error::{LnurlError, LnurlResult},
     },
     network::BitcoinNetwork,
@@ -27,6 +28,7 @@ pub async fn validate_lnurl_pay<C: HttpClient + ?Sized>(
     http_client: &C,
     user_amount_msat: u64,
     comment: &Option<String>,
+    payer_data: Option<&LnurlPayerData>,
     pay_request: &LnurlPayRequestDetails,
     network: BitcoinNetwork,
     validate_success_action_url: Option<bool>,
@@ -39,7 +41,7 @@ pub async fn validate_lnurl_pay<C: HttpClient + ?Sized>(
         pay_request.comment_allowed,
     )?;
 
-    let callback_url = build_pay_callback_url(user_amount_msat, comment, pay_request)?;
+    let callback_url = build_pay_callback_url(user_amount_msat, comment, payer_data, pay_request)?;
     let response = http_client.get(callback_url, None).await?;
     if let Ok(err) = response.json::<LnurlErrorDetails>() {
         return Ok(ValidatedCallbackResponse::EndpointError { data: err });
@@ -70,6 +72,7 @@ pub async fn validate_lnurl_pay<C: HttpClient + ?Sized>(
 pub fn build_pay_callback_url(
     user_amount_msat: u64,
     user_comment: &Option<String>,
+    payer_data: Option<&LnurlPayerData>,
     pay_request: &LnurlPayRequestDetails,
 ) -> LnurlResult<String> {
     let amount_msat = user_amount_msat.to_string();
@@ -80,10 +83,81 @@ pub fn build_pay_callback_url(
     if let Some(comment) = user_comment {
         url.query_pairs_mut().append_pair("comment", comment);
     }
+    if let Some(payer_data) = payer_data {
+        let payer_data = serde_json::to_string(payer_data)
+            .map_err(|e| LnurlError::General(format!("failed to serialize payer data: {e}")))?;
+        url.query_pairs_mut().append_pair("payerdata", &payer_data);
+    }
 
     Ok(url.to_string())
 }
 
+/// Builds the payer data to send to an LNURL-pay service, as per
+/// <https://github.com/lnurl/luds/blob/luds/18.md>
+///
+/// Only the fields requested by the service are included. When `share_linking_key` is set, the
+/// LUD-05 linking key for the service domain is used as `pubkey` and to sign the `auth` challenge.
+pub async fn build_payer_data<S: LnurlAuthSigner>(
+    request: &LnurlPayerDataRequest,
+    pay_request_url: &str,
+    name: Option<String>,
+    identifier: Option<String>,
+    share_linking_key: bool,
+    signer: &S,
+) -> LnurlResult<LnurlPayerData> {
+    let mut payer_data = LnurlPayerData {
+        name: request.name.as_ref().and(name),
+        identifier: request.identifier.as_ref().and(identifier),
+        ..Default::default()
+    };
+
+    if share_linking_key && (request.pubkey.is_some() || request.auth.is_some()) {
+        let url = url::Url::parse(pay_request_url)
+            .map_err(|_| LnurlError::invalid_uri("invalid lnurl pay uri"))?;
+        let derivation_path = get_derivation_path(signer, url).await?;
+        let public_key = signer.derive_public_key(&derivation_path).await?;
+        if request.pubkey.is_some() {
+            payer_data.pubkey = Some(public_key.to_string());
+        }
+        if let Some(auth) = &request.auth {
+            let sig = signer
+                .sign_ecdsa(
+                    &hex::decode(&auth.k1).map_err(|_| LnurlError::InvalidK1)?,
+                    &derivation_path,
+                )
+                .await?;
+            payer_data.auth = Some(LnurlPayerDataAuth {
+                key: public_key.to_string(),
+                k1: auth.k1.clone(),
+                sig: hex::encode(sig),
+            });
+        }
+    }
+
+    let mandatory = |field: Option<&LnurlPayerDataField>| field.is_some_and(|f| f.mandatory);
+    let missing = [
+        (mandatory(request.name.as_ref()), payer_data.name.is_none()),
+        (
+            mandatory(request.pubkey.as_ref()),
+            payer_data.pubkey.is_none(),
+        ),
+        (
+            mandatory(request.identifier.as_ref()),
+            payer_data.identifier.is_none(),
+        ),
+        (
+            request.auth.as_ref().is_some_and(|f| f.mandatory),
+            payer_data.auth.is_none(),
+        ),
+    ];
+    ensure_sdk!(
+        !missing.iter().any(|(mandatory, none)| *mandatory && *none),
+        LnurlError::MissingPayerData
+    );
+
+    Ok(payer_data)
+}
+
 pub fn validate_user_input(
     user_amount_msat: u64,
     comment: &Option<String>,
@@ -180,6 +254,57 @@ pub struct LnurlPayRequestDetails {
     /// See <https://github.com/nostr-protocol/nips/blob/master/57.md>
     /// See <https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki>
     pub nostr_pubkey: Option<String>,
+
+    /// The payer data the service accepts or requires, as per LUD-18.
+    ///
+    /// See <https://github.com/lnurl/luds/blob/luds/18.md>
+    #[serde(default)]
+    pub payer_data: Option<LnurlPayerDataRequest>,
+}
+
+/// The payer data fields an LNURL-pay service accepts, as per LUD-18
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LnurlPayerDataRequest {
+    pub name: Option<LnurlPayerDataField>,
+    pub pubkey: Option<LnurlPayerDataField>,
+    pub identifier: Option<LnurlPayerDataField>,
+    pub auth: Option<LnurlPayerDataAuthField>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LnurlPayerDataField {
+    #[serde(default)]
+    pub mandatory: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LnurlPayerDataAuthField {
+    #[serde(default)]
+    pub mandatory: bool,
+    /// Hex encoded 32 bytes challenge to be signed with the LUD-05 linking key
+    pub k1: String,
+}
+
+/// The payer data sent to an LNURL-pay service, as per LUD-18
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LnurlPayerData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pubkey: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<LnurlPayerDataAuth>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LnurlPayerDataAuth {
+    /// The hex encoded linking key
+    pub key: String,
+    pub k1: String,
+    /// The hex encoded DER signature of `k1`
+    pub sig: String,
 }
 
 pub enum ValidatedCallbackResponse {
@@ -426,6 +551,7 @@ pub(crate) mod tests {
             nostr_pubkey: None,
             url: "http://localhost:8080/pay".into(),
             address: None,
+            payer_data: None,
         }
     }
 
@@ -653,4 +779,39 @@ pub(crate) mod tests {
             .is_err()
         );
     }
+
+    #[macros::test_all]
+    fn test_lnurl_pay_payer_data() -> Result<()> {
+        let mut pay_req_data: LnurlPayRequestDetails = serde_json::from_str(
+            r#"{
+                "callback": "http://localhost:8080/callback",
+                "minSendable": 1000,
+                "maxSendable": 100000,
+                "metadata": "[]",
+                "payerData": {
+                    "name": { "mandatory": false },
+                    "auth": { "mandatory": true, "k1": "e2af6254a8df433264fa23f67eb8188635d15ce883e8fc020989d5f82ae6f11e" }
+                }
+            }"#,
+        )?;
+        let payer_data_request = pay_req_data.payer_data.take().unwrap();
+        assert!(!payer_data_request.name.unwrap().mandatory);
+        assert!(payer_data_request.auth.unwrap().mandatory);
+        assert!(payer_data_request.identifier.is_none());
+
+        let payer_data = LnurlPayerData {
+            name: Some("Satoshi".into()),
+            ..Default::default()
+        };
+        let callback_url =
+            build_pay_callback_url(100_000, &None, Some(&payer_data), &pay_req_data)?;
+        let url = url::Url::parse(&callback_url)?;
+        let sent = url
+            .query_pairs()
+            .find(|(key, _)| key == "payerdata")
+            .map(|(_, value)| value.to_string())
+            .unwrap();
+        assert_eq!(sent, r#"{"name":"Satoshi"}"#);
+        Ok(())
+    }
 }
@@ -0,0 +1,59 @@
+use thiserror::Error;
+use tonic::{Code, Status, service::interceptor::InterceptedService};
+
+use crate::{
+    grpc::transport::{GrpcClient, Transport},
+    remote_signer::proto::{SignRequest, remote_signer_client::RemoteSignerClient as ProtoClient},
+    sync::ApiKeyInterceptor,
+};
+
+#[derive(Debug, Error, Clone)]
+pub enum RemoteSignerError {
+    #[error("Denied by the signer policy: {0}")]
+    PolicyViolation(String),
+
+    #[error("Invalid signer request: {0}")]
+    InvalidInput(String),
+
+    #[error("Remote signer error: {0}")]
+    Generic(String),
+}
+
+impl From<Status> for RemoteSignerError {
+    fn from(status: Status) -> Self {
+        match status.code() {
+            Code::PermissionDenied | Code::ResourceExhausted => {
+                RemoteSignerError::PolicyViolation(status.message().to_string())
+            }
+            Code::InvalidArgument => RemoteSignerError::InvalidInput(status.message().to_string()),
+            _ => RemoteSignerError::Generic(status.to_string()),
+        }
+    }
+}
+
+/// Client of a remote signer server, authenticated with an api key.
+#[derive(Clone)]
+pub struct RemoteSignerClient {
+    client: ProtoClient<InterceptedService<Transport, ApiKeyInterceptor>>,
+}
+
+impl RemoteSignerClient {
+    pub fn new(server_url: &str, api_key: &str) -> anyhow::Result<Self> {
+        let client = ProtoClient::with_interceptor(
+            GrpcClient::new(server_url)?.into_inner(),
+            ApiKeyInterceptor::new(Some(api_key))?,
+        );
+        Ok(Self { client })
+    }
+
+    /// Sends a JSON encoded method call to the server and returns the JSON encoded result.
+    pub async fn sign(&self, payload: Vec<u8>) -> Result<Vec<u8>, RemoteSignerError> {
+        let reply = self
+            .client
+            .clone()
+            .sign(SignRequest { payload })
+            .await?
+            .into_inner();
+        Ok(reply.payload)
+    }
+}
@@ -0,0 +1,8 @@
+mod client;
+
+pub use client::*;
+
+#[allow(clippy::doc_markdown)]
+pub mod proto {
+    tonic::include_proto!("remote_signer");
+}
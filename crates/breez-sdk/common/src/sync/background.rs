@@ -1,11 +1,16 @@
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::sync::{Mutex, broadcast, mpsc, watch};
 use tokio_with_wasm::alias as tokio;
 use tracing::{debug, error, warn};
 use web_time::SystemTime;
 
 use crate::sync::{
-    model::{IncomingChange, OutgoingChange, RecordId},
+    merge::{ConflictResolution, FieldConflict, find_conflicts},
+    model::{IncomingChange, OutgoingChange, RecordChange, RecordId, UnversionedRecordChange},
     signing_client::SigningClient,
     storage::SyncStorage,
 };
@@ -16,6 +21,8 @@ use crate::sync::{
 pub trait NewRecordHandler: Send + Sync {
     async fn on_incoming_change(&self, change: IncomingChange) -> anyhow::Result<RecordOutcome>;
     async fn on_replay_outgoing_change(&self, change: OutgoingChange) -> anyhow::Result<()>;
+    /// Resolves a field changed both by an incoming record and by a pending outgoing change.
+    fn resolve_conflict(&self, id: &RecordId, conflict: &FieldConflict) -> ConflictResolution;
     async fn on_sync_completed(
         &self,
         incoming_count: Option<u32>,
@@ -519,8 +526,10 @@ impl SyncProcessor {
         let mut incoming_deferred: u32 = 0;
         let mut incoming_errored: u32 = 0;
 
+        let mut pending_changes = HashMap::new();
         if total_count > 0 {
             debug!("Processing {} incoming records", total_count);
+            pending_changes = self.get_pending_changes_by_record().await?;
         }
 
         for incoming_record in incoming_records {
@@ -535,8 +544,16 @@ impl SyncProcessor {
             // to ensure we'll update the relational database state if we turn off now.
             // We should do this even if we later fail to apply the change (e.g. due to a schema change)
             // to avoid refetching the same record later.
+            let mut change: IncomingChange = (&incoming_record).try_into()?;
+            change.pending_outgoing_changes = pending_changes
+                .remove(&change.new_state.id)
+                .unwrap_or_default();
             let first_time_seen = existing_revision.is_none_or(|old| old < incoming_revision);
             if first_time_seen {
+                // Fields changed on both sides are resolved before the sync state moves on,
+                // because the old state is needed to tell which fields the remote changed.
+                self.resolve_conflicts(&mut change).await?;
+
                 debug!(
                     "Updating sync state from incoming record {:?}, revision {}",
                     incoming_record.new_state.id, incoming_revision
@@ -546,19 +563,14 @@ impl SyncProcessor {
                     .await?;
             }
 
-            // Now notify the relational database to update. Wait for it to be done. Note that this could be improved
-            // in the future to also add actions to the pending outgoing changes for the same record. Like maybe delete
-            // an action, or change its field values. Now it is not necessary yet, because there are only immutable
-            // changes.
+            // Now notify the relational database to update. Wait for it to be done. The change carries the
+            // pending outgoing changes for the same record, so the handler can keep the local edits that were
+            // not pushed yet on top of the incoming state.
             debug!(
                 "Invoking relational database callback for incoming record {:?}, revision {}",
                 incoming_record.new_state.id, incoming_revision
             );
-            match self
-                .new_record_handler
-                .on_incoming_change((&incoming_record).try_into()?)
-                .await
-            {
+            match self.new_record_handler.on_incoming_change(change).await {
                 Ok(RecordOutcome::Completed) => {
                     debug!(
                         "Removing incoming record after processing completion {:?}, revision {}",
@@ -591,6 +603,87 @@ impl SyncProcessor {
         );
         Ok(incoming_applied)
     }
+
+    /// Returns the pending outgoing changes grouped by record, in local queue order.
+    async fn get_pending_changes_by_record(
+        &self,
+    ) -> anyhow::Result<HashMap<RecordId, Vec<RecordChange>>> {
+        let mut pending_changes: HashMap<RecordId, Vec<RecordChange>> = HashMap::new();
+        for pending in self.storage.get_pending_outgoing_changes(u32::MAX).await? {
+            let change: RecordChange = pending.change.try_into()?;
+            pending_changes
+                .entry(change.id.clone())
+                .or_default()
+                .push(change);
+        }
+        Ok(pending_changes)
+    }
+
+    /// Merges the incoming record with the pending outgoing changes for the same record, field by field.
+    ///
+    /// Fields changed only remotely or only locally merge without conflict. For fields changed on both
+    /// sides the handler decides which value wins. Resolutions other than keeping the local value are
+    /// queued as a new outgoing change, so they are both applied locally and pushed to the other instances.
+    async fn resolve_conflicts(&self, change: &mut IncomingChange) -> anyhow::Result<()> {
+        let conflicts = find_conflicts(
+            change.old_state.as_ref(),
+            &change.new_state,
+            &change.pending_outgoing_changes,
+        );
+        let Some(latest_pending) = change.pending_outgoing_changes.last() else {
+            return Ok(());
+        };
+        let schema_version = latest_pending.schema_version.clone();
+
+        let mut updated_fields = HashMap::new();
+        for conflict in conflicts {
+            let resolution = self
+                .new_record_handler
+                .resolve_conflict(&change.new_state.id, &conflict);
+            debug!(
+                "Resolved conflict on field '{}' of record {:?} with {:?}",
+                conflict.field, change.new_state.id, resolution
+            );
+            match resolution {
+                ConflictResolution::KeepLocal => {}
+                ConflictResolution::KeepRemote => match conflict.remote {
+                    Some(value) => {
+                        updated_fields.insert(conflict.field, value);
+                    }
+                    // The remote deleted the field, so the pending local values must not
+                    // restore it when they are applied over the incoming record.
+                    None => {
+                        for pending in &mut change.pending_outgoing_changes {
+                            pending.updated_fields.remove(&conflict.field);
+                        }
+                    }
+                },
+                ConflictResolution::Merged(value) => {
+                    updated_fields.insert(conflict.field, value);
+                }
+            }
+        }
+        if updated_fields.is_empty() {
+            return Ok(());
+        }
+
+        let resolution = UnversionedRecordChange {
+            id: change.new_state.id.clone(),
+            schema_version: schema_version.clone(),
+            updated_fields: updated_fields.clone(),
+        };
+        let local_revision = self
+            .storage
+            .add_outgoing_change(resolution.try_into()?)
+            .await?;
+        change.pending_outgoing_changes.push(RecordChange {
+            id: change.new_state.id.clone(),
+            schema_version,
+            updated_fields,
+            local_revision,
+        });
+        Ok(())
+    }
 }
 
 /// Used for sync backoff timer management
@@ -605,12 +698,13 @@ mod tests {
     use crate::sync::proto::SetRecordReply;
     use crate::sync::storage::{self, MockSyncStorage};
     use crate::sync::{
-        MockNewRecordHandler, MockSyncSigner, MockSyncerClient, RecordId, RecordOutcome,
-        SigningClient, SyncProcessor,
+        ConflictResolution, MockNewRecordHandler, MockSyncSigner, MockSyncerClient, RecordId,
+        RecordOutcome, SigningClient, SyncProcessor,
     };
 
     use anyhow::anyhow;
     use mockall::{Sequence, predicate::eq};
+    use serde_json::json;
     use std::collections::HashMap;
     use std::sync::Arc;
     use std::time::Duration;
@@ -641,6 +735,111 @@ mod tests {
         }
     }
 
+    fn create_record_with_data(
+        id_type: &str,
+        id_data: &str,
+        revision: u64,
+        data: &[(&str, &str)],
+    ) -> crate::sync::storage::Record {
+        crate::sync::storage::Record {
+            id: RecordId::new(id_type, id_data),
+            revision,
+            schema_version: "0.2.6".to_string(),
+            data: data
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect(),
+        }
+    }
+
+    // Helper to create a pending local edit of a record, based on `parent`
+    fn create_pending_edit(
+        parent: &crate::sync::storage::Record,
+        local_revision: u64,
+        fields: &[(&str, &str)],
+    ) -> crate::sync::storage::OutgoingChange {
+        crate::sync::storage::OutgoingChange {
+            change: crate::sync::storage::RecordChange {
+                id: parent.id.clone(),
+                schema_version: "0.2.6".to_string(),
+                updated_fields: fields
+                    .iter()
+                    .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                    .collect(),
+                local_revision,
+            },
+            parent: Some(parent.clone()),
+        }
+    }
+
+    // Sets up a processor where the remote changed the label of a record from "base" to "remote",
+    // while a local edit of the label to "local" was still pending.
+    fn create_label_conflict_processor(
+        resolution: ConflictResolution,
+        expected_resolution_fields: Option<HashMap<String, String>>,
+        expected_label: &'static str,
+    ) -> SyncProcessor {
+        let old_state = create_record_with_data("payment", "123", 5, &[("label", "\"base\"")]);
+        let new_state = create_record_with_data("payment", "123", 6, &[("label", "\"remote\"")]);
+        let pending_edit = create_pending_edit(&old_state, 1, &[("label", "\"local\"")]);
+        let incoming_record = crate::sync::storage::IncomingChange {
+            new_state,
+            old_state: Some(old_state),
+        };
+
+        let mut mock_storage = MockSyncStorage::new();
+        mock_storage
+            .expect_get_incoming_records()
+            .times(1)
+            .with(eq(u32::MAX))
+            .returning(move |_| Ok(vec![incoming_record.clone()]));
+        mock_storage
+            .expect_get_pending_outgoing_changes()
+            .times(1)
+            .with(eq(u32::MAX))
+            .returning(move |_| Ok(vec![pending_edit.clone()]));
+        let resolution_count = usize::from(expected_resolution_fields.is_some());
+        mock_storage
+            .expect_add_outgoing_change()
+            .times(resolution_count)
+            .withf(move |change| {
+                Some(&change.updated_fields) == expected_resolution_fields.as_ref()
+            })
+            .returning(|_| Ok(2));
+        mock_storage
+            .expect_update_record_from_incoming()
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_storage
+            .expect_delete_incoming_record()
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut mock_handler = MockNewRecordHandler::new();
+        mock_handler
+            .expect_resolve_conflict()
+            .times(1)
+            .withf(|_, conflict| {
+                conflict.field == "label"
+                    && conflict.base == Some(json!("base"))
+                    && conflict.local == json!("local")
+                    && conflict.remote == Some(json!("remote"))
+            })
+            .return_const(resolution);
+        mock_handler
+            .expect_on_incoming_change()
+            .times(1)
+            .withf(move |change| {
+                change.pending_outgoing_changes.len() == 1 + resolution_count
+                    && change.merged_state().data.get("label") == Some(&json!(expected_label))
+            })
+            .returning(|_| Ok(RecordOutcome::Completed));
+
+        let (_tx, rx) = broadcast::channel(10);
+        let client = create_signing_client(MockSyncerClient::new(), MockSyncSigner::new());
+        SyncProcessor::new(client, rx, Arc::new(mock_handler), Arc::new(mock_storage))
+    }
+
     // Helper function to create test outgoing changes
     fn create_outgoing_change(
         id_type: &str,
@@ -1010,6 +1209,12 @@ mod tests {
             .with(eq(u32::MAX))
             .returning(move |_| Ok(vec![incoming_record.clone()]));
 
+        mock_storage
+            .expect_get_pending_outgoing_changes()
+            .times(1)
+            .with(eq(u32::MAX))
+            .returning(|_| Ok(Vec::new()));
+
         mock_storage
             .expect_update_record_from_incoming()
             .times(1)
@@ -1057,6 +1262,12 @@ mod tests {
             .with(eq(u32::MAX))
             .returning(move |_| Ok(vec![incoming_record.clone()]));
 
+        mock_storage
+            .expect_get_pending_outgoing_changes()
+            .times(1)
+            .with(eq(u32::MAX))
+            .returning(|_| Ok(Vec::new()));
+
         mock_storage
             .expect_update_record_from_incoming()
             .times(1)
@@ -1102,6 +1313,12 @@ mod tests {
             .with(eq(u32::MAX))
             .returning(move |_| Ok(vec![incoming_record.clone()]));
 
+        mock_storage
+            .expect_get_pending_outgoing_changes()
+            .times(1)
+            .with(eq(u32::MAX))
+            .returning(|_| Ok(Vec::new()));
+
         // No state update should happen when the incoming revision is already reflected.
         mock_storage.expect_update_record_from_incoming().times(0);
 
@@ -1128,6 +1345,192 @@ mod tests {
         assert_eq!(result.unwrap(), 0);
     }
 
+    #[macros::async_test_all]
+    async fn test_pull_sync_once_local_merges_concurrent_edits_of_different_fields() {
+        // Setup: the remote changed the label while the note was edited locally, both offline.
+        let old_state = create_record_with_data(
+            "payment",
+            "123",
+            5,
+            &[("label", "\"base\""), ("note", "\"base\"")],
+        );
+        let new_state = create_record_with_data(
+            "payment",
+            "123",
+            6,
+            &[("label", "\"remote\""), ("note", "\"base\"")],
+        );
+        let pending_edit = create_pending_edit(&old_state, 1, &[("note", "\"local\"")]);
+        let unrelated_edit = create_pending_edit(
+            &create_record_with_data("payment", "456", 4, &[("note", "\"base\"")]),
+            2,
+            &[("note", "\"other\"")],
+        );
+        let incoming_record = crate::sync::storage::IncomingChange {
+            new_state,
+            old_state: Some(old_state),
+        };
+
+        let mut mock_storage = MockSyncStorage::new();
+        mock_storage
+            .expect_get_incoming_records()
+            .times(1)
+            .with(eq(u32::MAX))
+            .returning(move |_| Ok(vec![incoming_record.clone()]));
+        mock_storage
+            .expect_get_pending_outgoing_changes()
+            .times(1)
+            .with(eq(u32::MAX))
+            .returning(move |_| Ok(vec![pending_edit.clone(), unrelated_edit.clone()]));
+        // No conflict, so no resolution is queued.
+        mock_storage.expect_add_outgoing_change().times(0);
+        mock_storage
+            .expect_update_record_from_incoming()
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_storage
+            .expect_delete_incoming_record()
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut mock_handler = MockNewRecordHandler::new();
+        mock_handler.expect_resolve_conflict().times(0);
+        mock_handler
+            .expect_on_incoming_change()
+            .times(1)
+            .withf(|change| {
+                let merged = change.merged_state();
+                change.pending_outgoing_changes.len() == 1
+                    && merged.data.get("label") == Some(&json!("remote"))
+                    && merged.data.get("note") == Some(&json!("local"))
+            })
+            .returning(|_| Ok(RecordOutcome::Completed));
+
+        let (_tx, rx) = broadcast::channel(10);
+        let client = create_signing_client(MockSyncerClient::new(), MockSyncSigner::new());
+        let sync_processor =
+            SyncProcessor::new(client, rx, Arc::new(mock_handler), Arc::new(mock_storage));
+
+        // Execute
+        let result = sync_processor.pull_sync_once_local().await;
+
+        // Verify
+        assert_eq!(result.unwrap(), 1);
+    }
+
+    #[macros::async_test_all]
+    async fn test_pull_sync_once_local_conflict_keep_local() {
+        let sync_processor =
+            create_label_conflict_processor(ConflictResolution::KeepLocal, None, "local");
+
+        let result = sync_processor.pull_sync_once_local().await;
+
+        assert_eq!(result.unwrap(), 1);
+    }
+
+    #[macros::async_test_all]
+    async fn test_pull_sync_once_local_conflict_keep_remote() {
+        let sync_processor = create_label_conflict_processor(
+            ConflictResolution::KeepRemote,
+            Some(HashMap::from([(
+                "label".to_string(),
+                "\"remote\"".to_string(),
+            )])),
+            "remote",
+        );
+
+        let result = sync_processor.pull_sync_once_local().await;
+
+        assert_eq!(result.unwrap(), 1);
+    }
+
+    #[macros::async_test_all]
+    async fn test_pull_sync_once_local_conflict_keep_remote_deleted_field() {
+        // Setup: the remote deleted the label while a local edit of it was still pending.
+        let old_state = create_record_with_data(
+            "payment",
+            "123",
+            5,
+            &[("label", "\"base\""), ("note", "\"base\"")],
+        );
+        let new_state = create_record_with_data("payment", "123", 6, &[("note", "\"base\"")]);
+        let pending_edit = create_pending_edit(
+            &old_state,
+            1,
+            &[("label", "\"local\""), ("note", "\"local\"")],
+        );
+        let incoming_record = crate::sync::storage::IncomingChange {
+            new_state,
+            old_state: Some(old_state),
+        };
+
+        let mut mock_storage = MockSyncStorage::new();
+        mock_storage
+            .expect_get_incoming_records()
+            .times(1)
+            .with(eq(u32::MAX))
+            .returning(move |_| Ok(vec![incoming_record.clone()]));
+        mock_storage
+            .expect_get_pending_outgoing_changes()
+            .times(1)
+            .with(eq(u32::MAX))
+            .returning(move |_| Ok(vec![pending_edit.clone()]));
+        // Nothing is left to set, so no resolution is queued.
+        mock_storage.expect_add_outgoing_change().times(0);
+        mock_storage
+            .expect_update_record_from_incoming()
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_storage
+            .expect_delete_incoming_record()
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut mock_handler = MockNewRecordHandler::new();
+        mock_handler
+            .expect_resolve_conflict()
+            .times(1)
+            .withf(|_, conflict| conflict.field == "label" && conflict.remote.is_none())
+            .return_const(ConflictResolution::KeepRemote);
+        mock_handler
+            .expect_on_incoming_change()
+            .times(1)
+            .withf(|change| {
+                let merged = change.merged_state();
+                change.pending_outgoing_changes.len() == 1
+                    && !merged.data.contains_key("label")
+                    && merged.data.get("note") == Some(&json!("local"))
+            })
+            .returning(|_| Ok(RecordOutcome::Completed));
+
+        let (_tx, rx) = broadcast::channel(10);
+        let client = create_signing_client(MockSyncerClient::new(), MockSyncSigner::new());
+        let sync_processor =
+            SyncProcessor::new(client, rx, Arc::new(mock_handler), Arc::new(mock_storage));
+
+        // Execute
+        let result = sync_processor.pull_sync_once_local().await;
+
+        // Verify
+        assert_eq!(result.unwrap(), 1);
+    }
+
+    #[macros::async_test_all]
+    async fn test_pull_sync_once_local_conflict_merged() {
+        let sync_processor = create_label_conflict_processor(
+            ConflictResolution::Merged(json!("local remote")),
+            Some(HashMap::from([(
+                "label".to_string(),
+                "\"local remote\"".to_string(),
+            )])),
+            "local remote",
+        );
+
+        let result = sync_processor.pull_sync_once_local().await;
+
+        assert_eq!(result.unwrap(), 1);
+    }
+
     #[macros::async_test_all]
     async fn test_push_sync_once_defers_records_with_pending_incoming() {
         // Setup: two outgoing changes, one has a matching pending incoming record
@@ -1317,6 +1720,12 @@ mod tests {
             .with(eq(u32::MAX))
             .in_sequence(&mut incoming_seq)
             .return_once(move |_| Ok(vec![deferred_retry_incoming]));
+
+        old_storage
+            .expect_get_pending_outgoing_changes()
+            .times(2)
+            .with(eq(u32::MAX))
+            .returning(|_| Ok(Vec::new()));
         old_storage
             .expect_update_record_from_incoming()
             .times(1)
@@ -1381,6 +1790,12 @@ mod tests {
             .times(1)
             .with(eq(u32::MAX))
             .return_once(move |_| Ok(vec![deferred_for_upgrade]));
+
+        upgraded_storage
+            .expect_get_pending_outgoing_changes()
+            .times(1)
+            .with(eq(u32::MAX))
+            .returning(|_| Ok(Vec::new()));
         upgraded_storage
             .expect_update_record_from_incoming()
             .times(0);
@@ -1476,6 +1891,12 @@ mod tests {
             .with(eq(u32::MAX))
             .returning(move |_| Ok(vec![pending_incoming.clone()]));
 
+        mock_storage
+            .expect_get_pending_outgoing_changes()
+            .times(1)
+            .with(eq(u32::MAX))
+            .returning(|_| Ok(Vec::new()));
+
         mock_storage
             .expect_update_record_from_incoming()
             .times(1)
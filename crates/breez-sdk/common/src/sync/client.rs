@@ -33,18 +33,9 @@ pub struct BreezSyncerClient {
 impl BreezSyncerClient {
     #[allow(unused)]
     pub fn new(server_url: &str, api_key: Option<&str>) -> anyhow::Result<Self> {
-        let api_key_metadata = match &api_key {
-            Some(key) => Some(
-                format!("Bearer {key}")
-                    .parse()
-                    .map_err(|e| anyhow!("Invalid api key: {e}"))?,
-            ),
-            None => None,
-        };
-
         let client = ProtoSyncerClient::with_interceptor(
             GrpcClient::new(server_url)?.into_inner(),
-            ApiKeyInterceptor { api_key_metadata },
+            ApiKeyInterceptor::new(api_key)?,
         );
         Ok(Self { client })
     }
@@ -78,6 +69,20 @@ pub struct ApiKeyInterceptor {
     api_key_metadata: Option<MetadataValue<Ascii>>,
 }
 
+impl ApiKeyInterceptor {
+    pub fn new(api_key: Option<&str>) -> anyhow::Result<Self> {
+        let api_key_metadata = match api_key {
+            Some(key) => Some(
+                format!("Bearer {key}")
+                    .parse()
+                    .map_err(|e| anyhow!("Invalid api key: {e}"))?,
+            ),
+            None => None,
+        };
+        Ok(Self { api_key_metadata })
+    }
+}
+
 impl Interceptor for ApiKeyInterceptor {
     fn call(&mut self, mut req: Request<()>) -> Result<Request<()>, Status> {
         if let Some(api_key_metadata) = &self.api_key_metadata {
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::sync::model::{Record, RecordChange};
+
+/// A field that was changed both remotely and by a pending local change, to
+/// different values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldConflict {
+    pub field: String,
+    /// The value both sides started from, if the field existed.
+    pub base: Option<Value>,
+    /// The value set by the latest pending local change.
+    pub local: Value,
+    /// The value set by the incoming remote record, if the field still exists.
+    pub remote: Option<Value>,
+}
+
+/// How a [`FieldConflict`] should be resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConflictResolution {
+    /// Keep the local value. It overwrites the remote value once the pending change is pushed.
+    KeepLocal,
+    /// Discard the local value in favor of the remote value. If the remote deleted the field, it is
+    /// dropped from the pending local changes applied over the incoming record. The already queued
+    /// local changes are pushed as they are, so they still restore the field remotely.
+    KeepRemote,
+    /// Replace both values with a merged value.
+    Merged(Value),
+}
+
+/// Returns the fields of `new_state` that differ from `old_state`, with their new value.
+///
+/// A field that exists in `old_state` but not in `new_state` is reported with a `None` value.
+pub(crate) fn changed_fields(
+    old_state: Option<&Record>,
+    new_state: &Record,
+) -> HashMap<String, Option<Value>> {
+    let Some(old_state) = old_state else {
+        return new_state
+            .data
+            .iter()
+            .map(|(k, v)| (k.clone(), Some(v.clone())))
+            .collect();
+    };
+
+    let mut changed: HashMap<String, Option<Value>> = new_state
+        .data
+        .iter()
+        .filter(|(k, v)| old_state.data.get(*k) != Some(*v))
+        .map(|(k, v)| (k.clone(), Some(v.clone())))
+        .collect();
+    for k in old_state.data.keys() {
+        if !new_state.data.contains_key(k) {
+            changed.insert(k.clone(), None);
+        }
+    }
+    changed
+}
+
+/// Three-way merge of the pending local changes for a record against an incoming remote record.
+///
+/// Only fields changed on both sides since `old_state`, to different values, are conflicts.
+/// Fields changed on one side only merge without conflict. Pending changes are expected in
+/// local queue order, so the latest local value of a field wins over earlier ones.
+pub(crate) fn find_conflicts(
+    old_state: Option<&Record>,
+    new_state: &Record,
+    pending_changes: &[RecordChange],
+) -> Vec<FieldConflict> {
+    if pending_changes.is_empty() {
+        return Vec::new();
+    }
+
+    let mut local: HashMap<&String, &Value> = HashMap::new();
+    for change in pending_changes {
+        for (k, v) in &change.updated_fields {
+            local.insert(k, v);
+        }
+    }
+
+    let mut conflicts: Vec<FieldConflict> = changed_fields(old_state, new_state)
+        .into_iter()
+        .filter_map(|(field, remote)| {
+            let local = *local.get(&field)?;
+            if remote.as_ref() == Some(local) {
+                return None;
+            }
+            Some(FieldConflict {
+                base: old_state.and_then(|s| s.data.get(&field).cloned()),
+                local: local.clone(),
+                remote,
+                field,
+            })
+        })
+        .collect();
+    conflicts.sort_by(|a, b| a.field.cmp(&b.field));
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::{Value, json};
+
+    use crate::sync::{
+        FieldConflict, Record, RecordChange, RecordId, SchemaVersion,
+        merge::{changed_fields, find_conflicts},
+    };
+
+    fn record(revision: u64, data: &[(&str, Value)]) -> Record {
+        Record {
+            id: RecordId::new("payment", "invoice123"),
+            revision,
+            schema_version: SchemaVersion::new(1, 0, 0),
+            data: data
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), v.clone()))
+                .collect(),
+        }
+    }
+
+    fn change(local_revision: u64, fields: &[(&str, Value)]) -> RecordChange {
+        RecordChange {
+            id: RecordId::new("payment", "invoice123"),
+            schema_version: SchemaVersion::new(1, 0, 0),
+            updated_fields: fields
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), v.clone()))
+                .collect(),
+            local_revision,
+        }
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let old = record(1, &[("label", json!("a")), ("note", json!("b"))]);
+        let new = record(2, &[("label", json!("a")), ("note", json!("c"))]);
+
+        let changed = changed_fields(Some(&old), &new);
+        assert_eq!(
+            changed,
+            HashMap::from([("note".to_string(), Some(json!("c")))])
+        );
+
+        let changed = changed_fields(None, &new);
+        assert_eq!(changed.len(), 2);
+    }
+
+    #[test]
+    fn test_changed_fields_removed_field() {
+        let old = record(1, &[("label", json!("a")), ("note", json!("b"))]);
+        let new = record(2, &[("label", json!("a"))]);
+
+        let changed = changed_fields(Some(&old), &new);
+        assert_eq!(changed, HashMap::from([("note".to_string(), None)]));
+    }
+
+    #[test]
+    fn test_find_conflicts_different_fields() {
+        let old = record(1, &[("label", json!("a")), ("note", json!("b"))]);
+        let new = record(2, &[("label", json!("remote")), ("note", json!("b"))]);
+        let pending = vec![change(1, &[("note", json!("local"))])];
+
+        assert!(find_conflicts(Some(&old), &new, &pending).is_empty());
+    }
+
+    #[test]
+    fn test_find_conflicts_same_field() {
+        let old = record(1, &[("label", json!("a"))]);
+        let new = record(2, &[("label", json!("remote"))]);
+        let pending = vec![
+            change(1, &[("label", json!("first"))]),
+            change(2, &[("label", json!("second"))]),
+        ];
+
+        let conflicts = find_conflicts(Some(&old), &new, &pending);
+        assert_eq!(
+            conflicts,
+            vec![FieldConflict {
+                field: "label".to_string(),
+                base: Some(json!("a")),
+                local: json!("second"),
+                remote: Some(json!("remote")),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_conflicts_same_value() {
+        let old = record(1, &[("label", json!("a"))]);
+        let new = record(2, &[("label", json!("same"))]);
+        let pending = vec![change(1, &[("label", json!("same"))])];
+
+        assert!(find_conflicts(Some(&old), &new, &pending).is_empty());
+    }
+}
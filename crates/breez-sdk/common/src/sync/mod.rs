@@ -1,12 +1,13 @@
 mod background;
 mod client;
+mod merge;
 mod model;
 mod service;
 mod signer;
 mod signing_client;
 pub mod storage;
 
-pub use {background::*, client::*, model::*, service::*, signer::*, signing_client::*};
+pub use {background::*, client::*, merge::*, model::*, service::*, signer::*, signing_client::*};
 
 #[allow(clippy::doc_markdown)]
 pub mod proto {
@@ -152,11 +152,26 @@ pub struct IncomingChange {
 
     /// The current already existing sync state for this record.
     pub old_state: Option<Record>,
-    // Pending outgoing changes are changes that have been applied to the relational data store already, but not to the parent.
-    // The incoming change will come _before_ these outgoing changes, so to do things perfectly these changes have to be rolled back
-    // in reverse order, then the incoming change applied, then the outgoing changes reapplied in forward order.
-    // commented out because unneeded at the moment.
-    // pub pending_outgoing_changes: Vec<RecordChange>,
+
+    /// Pending outgoing changes for this record, in local queue order. These have been applied
+    /// to the relational data store already, but not to the parent. They will be pushed _after_
+    /// the incoming change, so their fields take precedence over the incoming ones.
+    pub pending_outgoing_changes: Vec<RecordChange>,
+}
+
+impl IncomingChange {
+    /// Returns the incoming record with the pending outgoing changes applied on top, which is
+    /// the state the relational data store should end up in.
+    #[must_use]
+    pub fn merged_state(&self) -> Record {
+        let mut record = self.new_state.clone();
+        for change in &self.pending_outgoing_changes {
+            for (k, v) in &change.updated_fields {
+                record.data.insert(k.clone(), v.clone());
+            }
+        }
+        record
+    }
 }
 
 pub struct RecordChange {
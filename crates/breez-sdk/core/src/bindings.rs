@@ -4,7 +4,7 @@ use tokio::sync::Mutex;
 
 use crate::{
     BitcoinChainService, BreezSdk, Config, Credentials, FiatService, KeySetConfig, PaymentObserver,
-    RestClient, SdkError, Seed, Storage, chain::rest_client::ChainApiType,
+    RestClient, SdkError, Seed, Storage, chain::rest_client::ChainApiType, signer::SigningAuditLog,
 };
 
 /// Builder for creating `BreezSdk` instances with customizable components.
@@ -100,6 +100,14 @@ impl SdkBuilder {
         *builder = builder.clone().with_payment_observer(payment_observer);
     }
 
+    /// Sets the signing audit log to be used by the SDK.
+    /// Arguments:
+    /// - `signing_audit_log`: The signing audit log to be used.
+    pub async fn with_signing_audit_log(&self, signing_audit_log: Arc<dyn SigningAuditLog>) {
+        let mut builder = self.inner.lock().await;
+        *builder = builder.clone().with_signing_audit_log(signing_audit_log);
+    }
+
     /// Builds the `BreezSdk` instance with the configured components.
     pub async fn build(&self) -> Result<BreezSdk, SdkError> {
         self.inner.lock().await.clone().build().await
@@ -124,3 +132,90 @@ impl SdkBuilder {
         *builder = builder.clone().with_postgres_storage(config);
     }
 }
+
+#[cfg(all(
+    feature = "sqlcipher",
+    not(all(target_family = "wasm", target_os = "unknown"))
+))]
+#[cfg_attr(feature = "uniffi", uniffi::export(async_runtime = "tokio"))]
+impl SdkBuilder {
+    /// Encrypts the default storage at rest, with a key derived from the wallet seed through the
+    /// signer. An existing plaintext database is encrypted when it's opened.
+    /// Arguments:
+    /// - `key_version`: The version of the encryption key. Increasing it rotates the key, the
+    ///   database is re-encrypted with the new key when it's opened.
+    pub async fn with_default_storage_encryption(&self, key_version: u32) {
+        let mut builder = self.inner.lock().await;
+        *builder = builder.clone().with_default_storage_encryption(key_version);
+    }
+}
+
+/// Builder for creating `WalletManager` instances with customizable components.
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Object))]
+pub struct WalletManagerBuilder {
+    inner: Mutex<crate::WalletManagerBuilder>,
+}
+
+/// Builder for creating `WalletManager` instances with customizable components.
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+#[cfg_attr(feature = "uniffi", uniffi::export(async_runtime = "tokio"))]
+impl WalletManagerBuilder {
+    /// Creates a new `WalletManagerBuilder` with the configuration shared by all accounts.
+    /// Arguments:
+    /// - `config`: The configuration to be used.
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn new(config: Config) -> Self {
+        WalletManagerBuilder {
+            inner: Mutex::new(crate::WalletManagerBuilder::new(config)),
+        }
+    }
+
+    /// Sets the root storage directory to initialize the default storage of each account with.
+    /// Arguments:
+    /// - `storage_dir`: The data directory for storage.
+    pub async fn with_default_storage(&self, storage_dir: String) {
+        let mut builder = self.inner.lock().await;
+        *builder = builder.clone().with_default_storage(storage_dir);
+    }
+
+    /// Sets the chain service to be shared by all accounts.
+    /// Arguments:
+    /// - `chain_service`: The chain service to be used.
+    pub async fn with_chain_service(&self, chain_service: Arc<dyn BitcoinChainService>) {
+        let mut builder = self.inner.lock().await;
+        *builder = builder.clone().with_chain_service(chain_service);
+    }
+
+    /// Sets the fiat service to be shared by all accounts.
+    /// Arguments:
+    /// - `fiat_service`: The fiat service to be used.
+    pub async fn with_fiat_service(&self, fiat_service: Arc<dyn FiatService>) {
+        let mut builder = self.inner.lock().await;
+        *builder = builder.clone().with_fiat_service(fiat_service);
+    }
+
+    /// Builds the `WalletManager` instance. Accounts are added to it afterwards.
+    pub async fn build(&self) -> Result<crate::WalletManager, SdkError> {
+        self.inner.lock().await.clone().build().await
+    }
+}
+
+#[cfg(all(
+    feature = "postgres",
+    not(all(target_family = "wasm", target_os = "unknown"))
+))]
+#[cfg_attr(feature = "uniffi", uniffi::export(async_runtime = "tokio"))]
+impl WalletManagerBuilder {
+    /// Sets `PostgreSQL` storage to be shared by all accounts, each scoped to its own tenant.
+    /// The storage instance will be created during `build()`.
+    /// Arguments:
+    /// - `config`: The `PostgreSQL` storage configuration.
+    pub async fn with_postgres_storage(
+        &self,
+        config: crate::persist::postgres::PostgresStorageConfig,
+    ) {
+        let mut builder = self.inner.lock().await;
+        *builder = builder.clone().with_postgres_storage(config);
+    }
+}
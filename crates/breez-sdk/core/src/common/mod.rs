@@ -2,3 +2,4 @@ pub mod fiat;
 pub mod models;
 pub mod rest;
 pub mod sync_storage;
+pub mod token_registry;
@@ -78,6 +78,8 @@ pub enum LnurlError {
     InvalidInvoice(String),
     #[error("lnurl has invalid response: {0}")]
     InvalidResponse(String),
+    #[error("lnurl service requires payer data that was not provided")]
+    MissingPayerData,
 }
 
 #[derive(Clone, Debug, Error)]
@@ -323,6 +325,67 @@ pub struct LnurlPayRequestDetails {
     /// See <https://github.com/nostr-protocol/nips/blob/master/57.md>
     /// See <https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki>
     pub nostr_pubkey: Option<String>,
+
+    /// The payer data the service accepts or requires, as per LUD-18.
+    ///
+    /// See <https://github.com/lnurl/luds/blob/luds/18.md>
+    #[serde(default)]
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub payer_data: Option<LnurlPayerDataRequest>,
+}
+
+/// The payer data fields an LNURL-pay service accepts, as per LUD-18
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[macros::derive_from(breez_sdk_common::lnurl::pay::LnurlPayerDataRequest)]
+#[macros::derive_into(breez_sdk_common::lnurl::pay::LnurlPayerDataRequest)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct LnurlPayerDataRequest {
+    pub name: Option<LnurlPayerDataField>,
+    pub pubkey: Option<LnurlPayerDataField>,
+    pub identifier: Option<LnurlPayerDataField>,
+    pub auth: Option<LnurlPayerDataAuthField>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[macros::derive_from(breez_sdk_common::lnurl::pay::LnurlPayerDataField)]
+#[macros::derive_into(breez_sdk_common::lnurl::pay::LnurlPayerDataField)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct LnurlPayerDataField {
+    pub mandatory: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[macros::derive_from(breez_sdk_common::lnurl::pay::LnurlPayerDataAuthField)]
+#[macros::derive_into(breez_sdk_common::lnurl::pay::LnurlPayerDataAuthField)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct LnurlPayerDataAuthField {
+    pub mandatory: bool,
+    /// Hex encoded 32 bytes challenge to be signed with the LUD-05 linking key
+    pub k1: String,
+}
+
+/// The payer data sent to an LNURL-pay service, as per LUD-18
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[macros::derive_from(breez_sdk_common::lnurl::pay::LnurlPayerData)]
+#[macros::derive_into(breez_sdk_common::lnurl::pay::LnurlPayerData)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct LnurlPayerData {
+    pub name: Option<String>,
+    pub pubkey: Option<String>,
+    pub identifier: Option<String>,
+    pub auth: Option<LnurlPayerDataAuth>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[macros::derive_from(breez_sdk_common::lnurl::pay::LnurlPayerDataAuth)]
+#[macros::derive_into(breez_sdk_common::lnurl::pay::LnurlPayerDataAuth)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct LnurlPayerDataAuth {
+    /// The hex encoded linking key
+    pub key: String,
+    pub k1: String,
+    /// The hex encoded DER signature of `k1`
+    pub sig: String,
 }
 
 /// Wrapped in a [`InputType::LnurlAuth`], this is the result of [`parse`](breez_sdk_common::input::parse) when given a LNURL-auth endpoint.
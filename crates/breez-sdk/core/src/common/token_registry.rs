@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    ServiceConnectivityError, TokenMetadata,
+    error::SdkError,
+    persist::{CachedTokenRegistryInfo, ObjectCacheRepository, Storage},
+};
+
+/// How long a registry entry is served from the cache before it is fetched again.
+pub(crate) const TOKEN_REGISTRY_CACHE_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Trait covering token registry functionality
+#[cfg_attr(feature = "uniffi", uniffi::export(with_foreign))]
+#[macros::async_trait]
+pub trait TokenRegistryService: Send + Sync {
+    /// Fetch the registry entries of the given token identifiers.
+    ///
+    /// Tokens unknown to the registry are omitted from the result.
+    async fn fetch_token_registry_entries(
+        &self,
+        token_identifiers: Vec<String>,
+    ) -> Result<Vec<TokenRegistryEntry>, ServiceConnectivityError>;
+}
+
+/// Registry entry of a token
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct TokenRegistryEntry {
+    pub identifier: String,
+    pub info: TokenRegistryInfo,
+}
+
+/// Information about a token published by a token registry
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct TokenRegistryInfo {
+    pub icon_url: Option<String>,
+    pub issuer_name: Option<String>,
+    pub issuer_url: Option<String>,
+    /// Whether the registry has verified the token's issuer
+    pub verified: bool,
+}
+
+/// Resolves token registry info, caching the results in storage.
+pub(crate) struct TokenRegistry {
+    service: Arc<dyn TokenRegistryService>,
+    object_repository: ObjectCacheRepository,
+}
+
+impl TokenRegistry {
+    pub fn new(service: Arc<dyn TokenRegistryService>, storage: Arc<dyn Storage>) -> Self {
+        TokenRegistry {
+            service,
+            object_repository: ObjectCacheRepository::new(storage),
+        }
+    }
+
+    /// Sets the registry info of the given tokens metadata.
+    ///
+    /// Fresh cache entries are used as is. Stale or missing entries are fetched
+    /// from the registry service. If fetching fails, stale entries are used instead.
+    pub async fn enrich(&self, tokens_metadata: &mut [TokenMetadata]) -> Result<(), SdkError> {
+        let now = now_secs();
+        let mut cached = HashMap::new();
+        let mut to_fetch = Vec::new();
+        for metadata in tokens_metadata.iter() {
+            let entry = self
+                .object_repository
+                .fetch_token_registry_info(&metadata.identifier)
+                .await?;
+            if !entry.as_ref().is_some_and(|e| e.is_fresh(now)) {
+                to_fetch.push(metadata.identifier.clone());
+            }
+            if let Some(entry) = entry {
+                cached.insert(metadata.identifier.clone(), entry.info);
+            }
+        }
+
+        if !to_fetch.is_empty() {
+            match self
+                .service
+                .fetch_token_registry_entries(to_fetch.clone())
+                .await
+            {
+                Ok(entries) => {
+                    let mut fetched: HashMap<_, _> = entries
+                        .into_iter()
+                        .map(|e| (e.identifier, e.info))
+                        .collect();
+                    for identifier in to_fetch {
+                        // Tokens missing from the registry are cached too, so they are not refetched until the TTL expires
+                        let info = fetched.remove(&identifier);
+                        self.object_repository
+                            .save_token_registry_info(
+                                &identifier,
+                                &CachedTokenRegistryInfo {
+                                    info: info.clone(),
+                                    fetched_at: now,
+                                },
+                            )
+                            .await?;
+                        cached.insert(identifier, info);
+                    }
+                }
+                Err(e) => warn!("Failed to fetch token registry entries, using cache: {e:?}"),
+            }
+        }
+
+        for metadata in tokens_metadata.iter_mut() {
+            metadata.registry_info = cached.get(&metadata.identifier).cloned().flatten();
+        }
+        Ok(())
+    }
+}
+
+impl CachedTokenRegistryInfo {
+    fn is_fresh(&self, now: u64) -> bool {
+        now.saturating_sub(self.fetched_at) < TOKEN_REGISTRY_CACHE_TTL_SECS
+    }
+}
+
+fn now_secs() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::persist::sqlite::SqliteStorage;
+
+    struct MockRegistry {
+        entries: Vec<TokenRegistryEntry>,
+        fail: bool,
+        calls: Mutex<Vec<Vec<String>>>,
+    }
+
+    #[macros::async_trait]
+    impl TokenRegistryService for MockRegistry {
+        async fn fetch_token_registry_entries(
+            &self,
+            token_identifiers: Vec<String>,
+        ) -> Result<Vec<TokenRegistryEntry>, ServiceConnectivityError> {
+            self.calls.lock().unwrap().push(token_identifiers);
+            if self.fail {
+                return Err(ServiceConnectivityError::Other("offline".to_string()));
+            }
+            Ok(self.entries.clone())
+        }
+    }
+
+    fn metadata(identifier: &str) -> TokenMetadata {
+        TokenMetadata {
+            identifier: identifier.to_string(),
+            issuer_public_key: String::new(),
+            name: String::new(),
+            ticker: String::new(),
+            decimals: 0,
+            max_supply: 0,
+            is_freezable: false,
+            registry_info: None,
+        }
+    }
+
+    fn info(verified: bool) -> TokenRegistryInfo {
+        TokenRegistryInfo {
+            icon_url: Some("https://example.com/icon.png".to_string()),
+            issuer_name: Some("Issuer".to_string()),
+            issuer_url: None,
+            verified,
+        }
+    }
+
+    fn create_temp_storage() -> Arc<dyn Storage> {
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "breez-test-token-registry-{}",
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(&path).unwrap();
+        Arc::new(SqliteStorage::new(&path).unwrap())
+    }
+
+    fn registry(storage: &Arc<dyn Storage>, fail: bool) -> (Arc<MockRegistry>, TokenRegistry) {
+        let service = Arc::new(MockRegistry {
+            entries: vec![TokenRegistryEntry {
+                identifier: "known".to_string(),
+                info: info(true),
+            }],
+            fail,
+            calls: Mutex::new(vec![]),
+        });
+        let registry = TokenRegistry::new(service.clone(), Arc::clone(storage));
+        (service, registry)
+    }
+
+    #[tokio::test]
+    async fn test_enrich_caches_entries() {
+        let storage = create_temp_storage();
+        let (service, registry) = registry(&storage, false);
+
+        let mut tokens = vec![metadata("known"), metadata("unknown")];
+        registry.enrich(&mut tokens).await.unwrap();
+        assert_eq!(tokens[0].registry_info, Some(info(true)));
+        assert_eq!(tokens[1].registry_info, None);
+
+        // Both entries, including the unknown token, are now served from the cache
+        let mut tokens = vec![metadata("known"), metadata("unknown")];
+        registry.enrich(&mut tokens).await.unwrap();
+        assert_eq!(tokens[0].registry_info, Some(info(true)));
+        assert_eq!(service.calls.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    #[allow(clippy::arithmetic_side_effects)]
+    async fn test_enrich_falls_back_to_stale_cache() {
+        let storage = create_temp_storage();
+        ObjectCacheRepository::new(Arc::clone(&storage))
+            .save_token_registry_info(
+                "known",
+                &CachedTokenRegistryInfo {
+                    info: Some(info(false)),
+                    fetched_at: now_secs() - TOKEN_REGISTRY_CACHE_TTL_SECS - 1,
+                },
+            )
+            .await
+            .unwrap();
+
+        let (service, registry) = registry(&storage, true);
+        let mut tokens = vec![metadata("known"), metadata("uncached")];
+        registry.enrich(&mut tokens).await.unwrap();
+
+        assert_eq!(
+            *service.calls.lock().unwrap(),
+            vec![vec!["known".to_string(), "uncached".to_string()]]
+        );
+        assert_eq!(tokens[0].registry_info, Some(info(false)));
+        assert_eq!(tokens[1].registry_info, None);
+    }
+}
@@ -22,6 +22,10 @@ pub enum SdkError {
     #[error("Insufficient funds")]
     InsufficientFunds,
 
+    /// The token outputs of the wallet are frozen by the token issuer
+    #[error("Token outputs are frozen by the issuer")]
+    TokenOutputsFrozen,
+
     #[error("Invalid UUID: {0}")]
     InvalidUuid(String),
 
@@ -113,6 +117,9 @@ impl From<crate::token_conversion::ConversionError> for SdkError {
             ConversionError::ConversionFailed(msg)
             | ConversionError::ValidationFailed(msg)
             | ConversionError::RefundFailed(msg) => SdkError::Generic(msg),
+            ConversionError::Wallet(SparkWalletError::TokenOutputsFrozen) => {
+                SdkError::TokenOutputsFrozen
+            }
             ConversionError::Sdk(e) => e,
             ConversionError::Storage(e) => SdkError::StorageError(e.to_string()),
             ConversionError::Wallet(e) => SdkError::SparkError(e.to_string()),
@@ -166,6 +173,7 @@ impl From<SparkWalletError> for SdkError {
     fn from(e: SparkWalletError) -> Self {
         match e {
             SparkWalletError::InsufficientFunds => SdkError::InsufficientFunds,
+            SparkWalletError::TokenOutputsFrozen => SdkError::TokenOutputsFrozen,
             _ => SdkError::SparkError(e.to_string()),
         }
     }
@@ -284,6 +292,9 @@ pub enum SignerError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    #[error("Denied by signer policy: {0}")]
+    PolicyViolation(String),
+
     #[error("Generic signer error: {0}")]
     Generic(String),
 }
@@ -148,6 +148,23 @@ pub trait EventListener: Send + Sync {
     async fn on_event(&self, event: SdkEvent);
 }
 
+/// An event emitted by one of the accounts of a `WalletManager`
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct WalletEvent {
+    /// The account that emitted the event
+    pub account_id: String,
+    pub event: SdkEvent,
+}
+
+/// Trait for listeners of the events of all accounts of a `WalletManager`
+#[cfg_attr(feature = "uniffi", uniffi::export(callback_interface))]
+#[macros::async_trait]
+pub trait WalletEventListener: Send + Sync {
+    /// Called when an account emits an event
+    async fn on_event(&self, event: WalletEvent);
+}
+
 /// Event publisher that manages event listeners
 pub struct EventEmitter {
     has_real_time_sync: bool,
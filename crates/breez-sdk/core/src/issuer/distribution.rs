@@ -0,0 +1,482 @@
+use std::collections::BTreeSet;
+
+use std::sync::Arc;
+
+use bitcoin::hashes::{Hash, HashEngine, sha256};
+use spark_wallet::{
+    MAX_TOKEN_TX_OUTPUTS, TokenTransaction, TokenTransactionObserver, TokenTransactionStatus,
+    TransferObserverError,
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    Storage, TokenDistributionRecipient, TokenDistributionRecipientResult, TokenDistributionStatus,
+    persist::{
+        CachedDistributionRecipient, CachedDistributionState, CachedTokenDistribution,
+        ObjectCacheRepository,
+    },
+};
+
+/// Maximum number of recipients packed in a single token transaction. One output is kept free for
+/// the change returned to the issuer.
+pub(crate) const MAX_RECIPIENTS_PER_TRANSACTION: usize = MAX_TOKEN_TX_OUTPUTS - 1;
+
+/// Derives a stable idempotency key from the token identifier and the recipients list.
+pub(crate) fn derive_idempotency_key(
+    token_identifier: &str,
+    recipients: &[TokenDistributionRecipient],
+) -> String {
+    let mut engine = sha256::Hash::engine();
+    engine.input(token_identifier.as_bytes());
+    for recipient in recipients {
+        engine.input(format!("\n{}:{}", recipient.address, recipient.amount).as_bytes());
+    }
+    sha256::Hash::from_engine(engine).to_string()
+}
+
+impl CachedTokenDistribution {
+    pub(crate) fn new(token_identifier: String, recipients: &[TokenDistributionRecipient]) -> Self {
+        Self {
+            token_identifier,
+            recipients: recipients
+                .iter()
+                .map(|r| CachedDistributionRecipient {
+                    address: r.address.clone(),
+                    amount: r.amount,
+                    state: CachedDistributionState::Pending,
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns true if this distribution was created for the given token and recipients.
+    pub(crate) fn matches(
+        &self,
+        token_identifier: &str,
+        recipients: &[TokenDistributionRecipient],
+    ) -> bool {
+        self.token_identifier == token_identifier
+            && self.recipients.len() == recipients.len()
+            && self
+                .recipients
+                .iter()
+                .zip(recipients)
+                .all(|(cached, r)| cached.address == r.address && cached.amount == r.amount)
+    }
+
+    pub(crate) fn has_in_flight(&self) -> bool {
+        self.recipients
+            .iter()
+            .any(|r| matches!(r.state, CachedDistributionState::InFlight { .. }))
+    }
+
+    /// Sum of the amounts that still have to be sent.
+    pub(crate) fn outstanding_amount(&self) -> u128 {
+        self.recipients
+            .iter()
+            .filter(|r| r.state == CachedDistributionState::Pending)
+            .map(|r| r.amount)
+            .sum()
+    }
+
+    /// Splits the pending recipients into batches of recipient indices, each fitting in a single
+    /// token transaction. Recipients still in flight are left out, as they may have been paid.
+    pub(crate) fn unsent_batches(&self) -> Vec<Vec<usize>> {
+        let unsent = self
+            .recipients
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.state == CachedDistributionState::Pending)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        unsent
+            .chunks(MAX_RECIPIENTS_PER_TRANSACTION)
+            .map(<[usize]>::to_vec)
+            .collect()
+    }
+
+    pub(crate) fn next_batch_id(&self) -> u32 {
+        self.recipients
+            .iter()
+            .filter_map(|r| match r.state {
+                CachedDistributionState::InFlight { batch_id, .. } => {
+                    Some(batch_id.saturating_add(1))
+                }
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn set_state(&mut self, indices: &[usize], state: &CachedDistributionState) {
+        for i in indices {
+            if let Some(recipient) = self.recipients.get_mut(*i) {
+                recipient.state = state.clone();
+            }
+        }
+    }
+
+    /// Records the hash of a token transaction of the batch, before it is committed.
+    pub(crate) fn record_tx_hash(&mut self, batch_id: u32, tx_hash: &str) {
+        for recipient in &mut self.recipients {
+            if let CachedDistributionState::InFlight {
+                batch_id: id,
+                tx_hashes,
+                ..
+            } = &mut recipient.state
+                && *id == batch_id
+            {
+                tx_hashes.push(tx_hash.to_string());
+            }
+        }
+    }
+
+    /// Records the error a transfer of the batch returned, keeping the batch in flight.
+    pub(crate) fn set_batch_error(&mut self, batch_id: u32, error: &str) {
+        for recipient in &mut self.recipients {
+            if let CachedDistributionState::InFlight {
+                batch_id: id,
+                error: batch_error,
+                ..
+            } = &mut recipient.state
+                && *id == batch_id
+            {
+                *batch_error = Some(error.to_string());
+            }
+        }
+    }
+
+    /// Hashes of all token transactions of the batches left in flight.
+    pub(crate) fn in_flight_tx_hashes(&self) -> Vec<String> {
+        self.recipients
+            .iter()
+            .filter_map(|r| match &r.state {
+                CachedDistributionState::InFlight { tx_hashes, .. } => Some(tx_hashes),
+                _ => None,
+            })
+            .flatten()
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Resolves recipients left in flight by a previous attempt.
+    ///
+    /// The hash of every token transaction of a batch is recorded before the transaction is
+    /// committed, so `transactions` are the transactions the operators know for those hashes.
+    /// A batch is sent if one of its transactions was committed. It is reset to pending if none
+    /// was: either no transaction was recorded, so nothing was committed, or all of them are
+    /// unknown to the operators or cancelled. Otherwise the outcome is not known yet and the
+    /// batch is left in flight.
+    pub(crate) fn reconcile_in_flight(&mut self, transactions: &[TokenTransaction]) {
+        let batch_ids = self
+            .recipients
+            .iter()
+            .filter_map(|r| match r.state {
+                CachedDistributionState::InFlight { batch_id, .. } => Some(batch_id),
+                _ => None,
+            })
+            .collect::<BTreeSet<_>>();
+
+        for batch_id in batch_ids {
+            let mut tx_hashes = Vec::new();
+            let indices = self
+                .recipients
+                .iter()
+                .enumerate()
+                .filter_map(|(i, r)| match &r.state {
+                    CachedDistributionState::InFlight {
+                        batch_id: id,
+                        tx_hashes: hashes,
+                        ..
+                    } if *id == batch_id => {
+                        tx_hashes.clone_from(hashes);
+                        Some(i)
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            let statuses = tx_hashes
+                .iter()
+                .filter_map(|hash| transactions.iter().find(|tx| &tx.hash == hash))
+                .map(|tx| &tx.status)
+                .collect::<Vec<_>>();
+            let committed = tx_hashes.iter().find(|hash| {
+                transactions.iter().any(|tx| {
+                    &tx.hash == *hash
+                        && matches!(
+                            tx.status,
+                            TokenTransactionStatus::Signed
+                                | TokenTransactionStatus::Revealed
+                                | TokenTransactionStatus::Finalized
+                        )
+                })
+            });
+            let unresolved = statuses.iter().any(|status| {
+                matches!(
+                    status,
+                    TokenTransactionStatus::Started | TokenTransactionStatus::Unknown
+                )
+            });
+
+            match committed {
+                Some(tx_hash) => self.set_state(
+                    &indices,
+                    &CachedDistributionState::Sent {
+                        tx_hash: tx_hash.clone(),
+                    },
+                ),
+                None if unresolved => {}
+                None => self.set_state(&indices, &CachedDistributionState::Pending),
+            }
+        }
+    }
+
+    pub(crate) fn results(&self) -> Vec<TokenDistributionRecipientResult> {
+        self.recipients
+            .iter()
+            .map(|r| {
+                let (status, tx_hash, error) = match &r.state {
+                    CachedDistributionState::Sent { tx_hash } => {
+                        (TokenDistributionStatus::Sent, Some(tx_hash.clone()), None)
+                    }
+                    CachedDistributionState::InFlight { error, .. } => {
+                        (TokenDistributionStatus::Unknown, None, error.clone())
+                    }
+                    CachedDistributionState::Pending => {
+                        (TokenDistributionStatus::Failed, None, None)
+                    }
+                };
+                TokenDistributionRecipientResult {
+                    address: r.address.clone(),
+                    amount: r.amount,
+                    status,
+                    tx_hash,
+                    error,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Persists the hash of each token transaction of a batch before it is committed, so the batch
+/// can be looked up by hash if the outcome of the transfer is unknown.
+pub(crate) struct BatchTxHashRecorder {
+    object_repository: ObjectCacheRepository,
+    idempotency_key: String,
+    batch_id: u32,
+    distribution: Mutex<CachedTokenDistribution>,
+}
+
+impl BatchTxHashRecorder {
+    pub(crate) fn new(
+        storage: Arc<dyn Storage>,
+        idempotency_key: String,
+        batch_id: u32,
+        distribution: CachedTokenDistribution,
+    ) -> Self {
+        Self {
+            object_repository: ObjectCacheRepository::new(storage),
+            idempotency_key,
+            batch_id,
+            distribution: Mutex::new(distribution),
+        }
+    }
+
+    pub(crate) fn into_distribution(self) -> CachedTokenDistribution {
+        self.distribution.into_inner()
+    }
+}
+
+#[macros::async_trait]
+impl TokenTransactionObserver for BatchTxHashRecorder {
+    async fn before_commit_token_transaction(
+        &self,
+        tx_hash: &str,
+    ) -> Result<(), TransferObserverError> {
+        let mut distribution = self.distribution.lock().await;
+        distribution.record_tx_hash(self.batch_id, tx_hash);
+        // The transaction is only committed once its hash is persisted.
+        self.object_repository
+            .save_token_distribution(&self.idempotency_key, &distribution)
+            .await
+            .map_err(|e| TransferObserverError::Generic(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spark_wallet::{TokenInputs, TokenTransaction, TokenTransactionStatus};
+    use web_time::UNIX_EPOCH;
+
+    use super::*;
+
+    #[allow(clippy::arithmetic_side_effects)]
+    fn recipients(count: usize) -> Vec<TokenDistributionRecipient> {
+        (0..count)
+            .map(|i| TokenDistributionRecipient {
+                address: format!("address{i}"),
+                amount: 100 + i as u128,
+            })
+            .collect()
+    }
+
+    fn transaction(hash: &str, status: TokenTransactionStatus) -> TokenTransaction {
+        TokenTransaction {
+            hash: hash.to_string(),
+            inputs: TokenInputs::Transfer(spark_wallet::TokenTransferInput {
+                outputs_to_spend: vec![],
+            }),
+            outputs: vec![],
+            status,
+            created_timestamp: UNIX_EPOCH,
+            fulfilled_invoices: vec![],
+        }
+    }
+
+    fn in_flight(batch_id: u32, tx_hashes: &[&str]) -> CachedDistributionState {
+        CachedDistributionState::InFlight {
+            batch_id,
+            tx_hashes: tx_hashes.iter().map(ToString::to_string).collect(),
+            error: Some("timeout".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_idempotency_key_depends_on_recipients() {
+        let a = recipients(3);
+        let mut b = recipients(3);
+        assert_eq!(
+            derive_idempotency_key("token", &a),
+            derive_idempotency_key("token", &recipients(3))
+        );
+        b[1].amount += 1;
+        assert_ne!(
+            derive_idempotency_key("token", &a),
+            derive_idempotency_key("token", &b)
+        );
+        assert_ne!(
+            derive_idempotency_key("token", &a),
+            derive_idempotency_key("other", &a)
+        );
+    }
+
+    #[test]
+    fn test_unsent_batches_skip_sent_recipients() {
+        let count = MAX_RECIPIENTS_PER_TRANSACTION * 2 + 1;
+        let mut distribution =
+            CachedTokenDistribution::new("token".to_string(), &recipients(count));
+        distribution.set_state(
+            &[0, 1],
+            &CachedDistributionState::Sent {
+                tx_hash: "tx".to_string(),
+            },
+        );
+
+        let batches = distribution.unsent_batches();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].len(), MAX_RECIPIENTS_PER_TRANSACTION);
+        assert_eq!(batches[0][0], 2);
+        assert_eq!(batches[1].len(), count - 2 - MAX_RECIPIENTS_PER_TRANSACTION);
+        assert_eq!(
+            distribution.outstanding_amount(),
+            recipients(count)
+                .iter()
+                .skip(2)
+                .map(|r| r.amount)
+                .sum::<u128>()
+        );
+    }
+
+    #[test]
+    fn test_reconcile_marks_committed_batch_as_sent() {
+        let recipients = recipients(3);
+        let mut distribution = CachedTokenDistribution::new("token".to_string(), &recipients);
+        distribution.set_state(&[0, 1], &in_flight(0, &["preempted", "committed"]));
+        distribution.set_state(&[2], &in_flight(1, &["unknown"]));
+        assert_eq!(distribution.next_batch_id(), 2);
+        assert_eq!(
+            distribution.in_flight_tx_hashes(),
+            vec!["committed", "preempted", "unknown"]
+        );
+
+        // Only the second transaction of the first batch was committed, the transaction of the
+        // second batch never reached the operators.
+        let transactions = vec![
+            transaction("preempted", TokenTransactionStatus::StartedCancelled),
+            transaction("committed", TokenTransactionStatus::Signed),
+        ];
+        distribution.reconcile_in_flight(&transactions);
+
+        assert!(!distribution.has_in_flight());
+        let results = distribution.results();
+        assert_eq!(results[0].status, TokenDistributionStatus::Sent);
+        assert_eq!(results[0].tx_hash.as_deref(), Some("committed"));
+        assert_eq!(results[1].status, TokenDistributionStatus::Sent);
+        assert_eq!(
+            distribution.recipients[2].state,
+            CachedDistributionState::Pending
+        );
+        assert_eq!(distribution.outstanding_amount(), 102);
+    }
+
+    #[test]
+    fn test_reconcile_resets_batch_without_recorded_transaction() {
+        let mut distribution = CachedTokenDistribution::new("token".to_string(), &recipients(2));
+        distribution.set_state(&[0, 1], &in_flight(0, &[]));
+
+        distribution.reconcile_in_flight(&[]);
+
+        assert_eq!(distribution.unsent_batches(), vec![vec![0, 1]]);
+    }
+
+    #[test]
+    fn test_reconcile_keeps_unresolved_batch_in_flight() {
+        let mut distribution = CachedTokenDistribution::new("token".to_string(), &recipients(2));
+        distribution.set_state(&[0, 1], &in_flight(0, &["started"]));
+
+        distribution
+            .reconcile_in_flight(&[transaction("started", TokenTransactionStatus::Started)]);
+
+        // The transaction may still be committed, so the batch must not be sent again.
+        assert!(distribution.has_in_flight());
+        assert!(distribution.unsent_batches().is_empty());
+        assert_eq!(distribution.outstanding_amount(), 0);
+        assert!(
+            distribution
+                .results()
+                .iter()
+                .all(|r| r.status == TokenDistributionStatus::Unknown)
+        );
+    }
+
+    #[test]
+    fn test_record_tx_hash_only_updates_batch() {
+        let mut distribution = CachedTokenDistribution::new("token".to_string(), &recipients(3));
+        distribution.set_state(&[0], &in_flight(0, &[]));
+        distribution.set_state(&[1], &in_flight(1, &[]));
+
+        distribution.record_tx_hash(1, "hash");
+        distribution.set_batch_error(1, "failed");
+
+        assert_eq!(distribution.recipients[0].state, in_flight(0, &[]));
+        assert_eq!(
+            distribution.recipients[1].state,
+            CachedDistributionState::InFlight {
+                batch_id: 1,
+                tx_hashes: vec!["hash".to_string()],
+                error: Some("failed".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_matches_detects_different_requests() {
+        let distribution = CachedTokenDistribution::new("token".to_string(), &recipients(2));
+        assert!(distribution.matches("token", &recipients(2)));
+        assert!(!distribution.matches("token", &recipients(3)));
+        assert!(!distribution.matches("other", &recipients(2)));
+    }
+}
@@ -1,4 +1,6 @@
+mod distribution;
 mod models;
+mod reporting;
 mod sdk;
 
 pub use models::*;
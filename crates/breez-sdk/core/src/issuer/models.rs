@@ -1,4 +1,6 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use crate::{Payment, PaymentStatus, TokenTransactionType};
 
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 #[derive(Debug, Serialize)]
@@ -26,6 +28,9 @@ pub struct BurnIssuerTokenRequest {
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 pub struct FreezeIssuerTokenRequest {
     pub address: String,
+    /// The reason for the freeze, recorded in the freeze registry
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -65,3 +70,140 @@ impl From<spark_wallet::FreezeIssuerTokenResponse> for UnfreezeIssuerTokenRespon
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct TokenDistributionRecipient {
+    /// The spark address of the recipient
+    pub address: String,
+    /// The amount of the issuer token to send to the recipient
+    pub amount: u128,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct DistributeIssuerTokenRequest {
+    pub recipients: Vec<TokenDistributionRecipient>,
+    /// Key identifying the distribution so that it can be resumed after a partial failure.
+    /// If not provided, it is derived from the recipients list, so calling again with the
+    /// same recipients resumes the same distribution.
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum TokenDistributionStatus {
+    /// The tokens were sent to the recipient
+    Sent,
+    /// Sending to the recipient failed. Calling again with the same idempotency key retries it.
+    Failed,
+    /// The outcome of the token transaction sending to the recipient is not known yet, it may
+    /// still be committed. Calling again with the same idempotency key resolves it, and only
+    /// retries the recipient if the transaction wasn't committed.
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct TokenDistributionRecipientResult {
+    pub address: String,
+    pub amount: u128,
+    pub status: TokenDistributionStatus,
+    /// The hash of the token transaction that paid the recipient
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct DistributeIssuerTokenResponse {
+    /// The key under which the distribution progress is persisted
+    pub idempotency_key: String,
+    /// The payment representing the supply minted to cover the distribution, if any
+    pub mint_payment: Option<Payment>,
+    /// One result per requested recipient, in request order
+    pub recipients: Vec<TokenDistributionRecipientResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct IssuerTokenSupply {
+    /// The maximum supply of the token, 0 if unlimited
+    pub max_supply: u128,
+    /// The amount minted over the lifetime of the token
+    pub total_minted: u128,
+    /// The amount burned over the lifetime of the token
+    pub total_burned: u128,
+    /// The amount in existence: minted minus burned
+    pub total_supply: u128,
+    /// The part of the total supply held by the issuer
+    pub issuer_balance: u128,
+    /// The part of the total supply held by anyone other than the issuer
+    pub circulating_supply: u128,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ListIssuerTokenTransactionsRequest {
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub offset: Option<u32>,
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ListIssuerTokenTransactionsResponse {
+    /// The transactions of the issuer token, most recent first
+    pub transactions: Vec<IssuerTokenTransaction>,
+}
+
+/// A mint, burn or transfer transaction of the issuer token, between any holders
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct IssuerTokenTransaction {
+    pub tx_hash: String,
+    pub tx_type: TokenTransactionType,
+    pub status: PaymentStatus,
+    pub timestamp: u64,
+    pub outputs: Vec<IssuerTokenTransactionOutput>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct IssuerTokenTransactionOutput {
+    /// Hex representation of the owner public key
+    pub owner_public_key: String,
+    pub amount: u128,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ListFrozenTokenHoldersRequest {
+    /// Whether to include holders that were unfrozen since. Defaults to false.
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub include_unfrozen: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ListFrozenTokenHoldersResponse {
+    pub holders: Vec<FrozenTokenHolder>,
+}
+
+/// An entry of the freeze registry, recorded when the issuer freezes tokens held at an address
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct FrozenTokenHolder {
+    pub address: String,
+    /// Hex representation of the owner public key
+    pub owner_public_key: String,
+    pub reason: Option<String>,
+    /// The time the tokens were frozen, in seconds since the UNIX epoch
+    pub frozen_at: u64,
+    pub impacted_output_ids: Vec<String>,
+    pub impacted_token_amount: u128,
+    /// The time the tokens were unfrozen, if they were
+    pub unfrozen_at: Option<u64>,
+}
@@ -0,0 +1,233 @@
+use spark_wallet::{BURN_PUBLIC_KEY, PublicKey, TokenInputs, TokenTransaction};
+use web_time::UNIX_EPOCH;
+
+use crate::{
+    FrozenTokenHolder, IssuerTokenTransaction, IssuerTokenTransactionOutput, PaymentStatus,
+    SdkError, TokenTransactionType,
+};
+
+fn burn_public_key() -> Result<PublicKey, SdkError> {
+    PublicKey::from_slice(BURN_PUBLIC_KEY)
+        .map_err(|e| SdkError::Generic(format!("Invalid burn public key: {e}")))
+}
+
+fn token_transaction_type(
+    transaction: &TokenTransaction,
+    burn_public_key: &PublicKey,
+) -> TokenTransactionType {
+    match transaction.inputs {
+        TokenInputs::Mint(..) => TokenTransactionType::Mint,
+        _ if transaction
+            .outputs
+            .iter()
+            .any(|o| o.owner_public_key == *burn_public_key) =>
+        {
+            TokenTransactionType::Burn
+        }
+        _ => TokenTransactionType::Transfer,
+    }
+}
+
+impl TryFrom<&TokenTransaction> for IssuerTokenTransaction {
+    type Error = SdkError;
+
+    fn try_from(transaction: &TokenTransaction) -> Result<Self, Self::Error> {
+        let timestamp = transaction
+            .created_timestamp
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| {
+                SdkError::Generic(
+                    "Token transaction created timestamp is before UNIX_EPOCH".to_string(),
+                )
+            })?
+            .as_secs();
+        Ok(Self {
+            tx_hash: transaction.hash.clone(),
+            tx_type: token_transaction_type(transaction, &burn_public_key()?),
+            status: PaymentStatus::from_token_transaction_status(
+                transaction.status,
+                matches!(transaction.inputs, TokenInputs::Transfer(..)),
+            ),
+            timestamp,
+            outputs: transaction
+                .outputs
+                .iter()
+                .map(|o| IssuerTokenTransactionOutput {
+                    owner_public_key: o.owner_public_key.to_string(),
+                    amount: o.token_amount,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Returns the amounts minted and burned by the given transactions.
+///
+/// Only completed transactions are taken into account.
+pub(crate) fn minted_and_burned(
+    transactions: &[TokenTransaction],
+) -> Result<(u128, u128), SdkError> {
+    let burn_public_key = burn_public_key()?;
+    let mut minted: u128 = 0;
+    let mut burned: u128 = 0;
+    for transaction in transactions {
+        let is_transfer = matches!(transaction.inputs, TokenInputs::Transfer(..));
+        if PaymentStatus::from_token_transaction_status(transaction.status, is_transfer)
+            != PaymentStatus::Completed
+        {
+            continue;
+        }
+        match transaction.inputs {
+            TokenInputs::Mint(..) => {
+                minted = transaction
+                    .outputs
+                    .iter()
+                    .fold(minted, |acc, o| acc.saturating_add(o.token_amount));
+            }
+            TokenInputs::Transfer(..) => {
+                burned = transaction
+                    .outputs
+                    .iter()
+                    .filter(|o| o.owner_public_key == burn_public_key)
+                    .fold(burned, |acc, o| acc.saturating_add(o.token_amount));
+            }
+            TokenInputs::Create(..) => {}
+        }
+    }
+    Ok((minted, burned))
+}
+
+/// Marks the active freeze registry entries of the owner as unfrozen.
+pub(crate) fn record_unfreeze(
+    registry: &mut [FrozenTokenHolder],
+    owner_public_key: &str,
+    unfrozen_at: u64,
+) {
+    for holder in registry
+        .iter_mut()
+        .filter(|h| h.owner_public_key == owner_public_key && h.unfrozen_at.is_none())
+    {
+        holder.unfrozen_at = Some(unfrozen_at);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bitcoin::secp256k1::{Secp256k1, SecretKey};
+    use spark_wallet::{TokenOutput, TokenTransactionStatus, TokenTransferInput};
+
+    use super::*;
+
+    fn public_key(byte: u8) -> PublicKey {
+        let secret_key = SecretKey::from_slice(&[byte; 32]).unwrap();
+        PublicKey::from_secret_key(&Secp256k1::new(), &secret_key)
+    }
+
+    fn transaction(
+        inputs: TokenInputs,
+        status: TokenTransactionStatus,
+        outputs: &[(PublicKey, u128)],
+    ) -> TokenTransaction {
+        TokenTransaction {
+            hash: "hash".to_string(),
+            inputs,
+            outputs: outputs
+                .iter()
+                .map(|(owner, amount)| TokenOutput {
+                    id: "id".to_string(),
+                    owner_public_key: *owner,
+                    revocation_commitment: String::new(),
+                    withdraw_bond_sats: 0,
+                    withdraw_relative_block_locktime: 0,
+                    token_public_key: None,
+                    token_identifier: "token".to_string(),
+                    token_amount: *amount,
+                })
+                .collect(),
+            status,
+            created_timestamp: UNIX_EPOCH.checked_add(Duration::from_secs(10)).unwrap(),
+            fulfilled_invoices: vec![],
+        }
+    }
+
+    fn mint(status: TokenTransactionStatus, amount: u128) -> TokenTransaction {
+        transaction(
+            TokenInputs::Mint(spark_wallet::TokenMintInput {
+                issuer_public_key: public_key(1),
+                token_id: None,
+            }),
+            status,
+            &[(public_key(1), amount)],
+        )
+    }
+
+    fn transfer(outputs: &[(PublicKey, u128)]) -> TokenTransaction {
+        transaction(
+            TokenInputs::Transfer(TokenTransferInput {
+                outputs_to_spend: vec![],
+            }),
+            TokenTransactionStatus::Finalized,
+            outputs,
+        )
+    }
+
+    #[test]
+    fn test_minted_and_burned() {
+        let transactions = vec![
+            mint(TokenTransactionStatus::Finalized, 1_000),
+            mint(TokenTransactionStatus::Signed, 500),
+            mint(TokenTransactionStatus::StartedCancelled, 10_000),
+            transfer(&[(burn_public_key().unwrap(), 200), (public_key(1), 300)]),
+            transfer(&[(public_key(2), 400)]),
+        ];
+
+        assert_eq!(minted_and_burned(&transactions).unwrap(), (1_500, 200));
+    }
+
+    #[test]
+    fn test_issuer_token_transaction_types() {
+        let burn = transfer(&[(burn_public_key().unwrap(), 200), (public_key(1), 300)]);
+        let mapped = IssuerTokenTransaction::try_from(&burn).unwrap();
+        assert_eq!(mapped.tx_type, TokenTransactionType::Burn);
+        assert_eq!(mapped.timestamp, 10);
+        assert_eq!(mapped.outputs.len(), 2);
+
+        let mapped =
+            IssuerTokenTransaction::try_from(&mint(TokenTransactionStatus::Finalized, 1)).unwrap();
+        assert_eq!(mapped.tx_type, TokenTransactionType::Mint);
+        assert_eq!(mapped.status, PaymentStatus::Completed);
+
+        let mapped = IssuerTokenTransaction::try_from(&transfer(&[(public_key(2), 1)])).unwrap();
+        assert_eq!(mapped.tx_type, TokenTransactionType::Transfer);
+        assert_eq!(
+            mapped.outputs[0].owner_public_key,
+            public_key(2).to_string()
+        );
+    }
+
+    #[test]
+    fn test_record_unfreeze_only_touches_active_entries() {
+        let holder = |owner: &str, frozen_at, unfrozen_at| FrozenTokenHolder {
+            address: format!("address-{owner}"),
+            owner_public_key: owner.to_string(),
+            reason: None,
+            frozen_at,
+            impacted_output_ids: vec![],
+            impacted_token_amount: 0,
+            unfrozen_at,
+        };
+        let mut registry = vec![
+            holder("a", 1, Some(2)),
+            holder("a", 3, None),
+            holder("b", 4, None),
+        ];
+
+        record_unfreeze(&mut registry, "a", 5);
+
+        assert_eq!(registry[0].unfrozen_at, Some(2));
+        assert_eq!(registry[1].unfrozen_at, Some(5));
+        assert_eq!(registry[2].unfrozen_at, None);
+    }
+}
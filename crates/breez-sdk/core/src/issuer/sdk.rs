@@ -1,14 +1,30 @@
 use std::sync::Arc;
 
-use spark_wallet::{SparkAddress, SparkWallet};
+use spark_wallet::{
+    ListTokenTransactionsRequest, Order, PagingFilter, SparkAddress, SparkWallet,
+    TransferTokenOutput,
+};
+use tracing::{info, warn};
+use web_time::{SystemTime, UNIX_EPOCH};
 
 use crate::{
-    BurnIssuerTokenRequest, CreateIssuerTokenRequest, FreezeIssuerTokenRequest,
-    FreezeIssuerTokenResponse, MintIssuerTokenRequest, Payment, SdkError, Storage, TokenBalance,
-    TokenMetadata, UnfreezeIssuerTokenRequest, UnfreezeIssuerTokenResponse,
+    BurnIssuerTokenRequest, CreateIssuerTokenRequest, DistributeIssuerTokenRequest,
+    DistributeIssuerTokenResponse, FreezeIssuerTokenRequest, FreezeIssuerTokenResponse,
+    FrozenTokenHolder, IssuerTokenSupply, IssuerTokenTransaction, ListFrozenTokenHoldersRequest,
+    ListFrozenTokenHoldersResponse, ListIssuerTokenTransactionsRequest,
+    ListIssuerTokenTransactionsResponse, MintIssuerTokenRequest, Payment, SdkError, Storage,
+    TokenBalance, TokenMetadata, UnfreezeIssuerTokenRequest, UnfreezeIssuerTokenResponse,
+    issuer::{
+        distribution::{BatchTxHashRecorder, derive_idempotency_key},
+        reporting::{minted_and_burned, record_unfreeze},
+    },
+    persist::{CachedDistributionState, CachedTokenDistribution, ObjectCacheRepository},
     utils::token::map_and_persist_token_transaction,
 };
 
+/// Maximum number of token transaction hashes the operators accept in a single query.
+const TOKEN_TX_HASHES_PER_QUERY: usize = 100;
+
 #[cfg_attr(feature = "uniffi", derive(uniffi::Object))]
 pub struct TokenIssuer {
     spark_wallet: Arc<SparkWallet>,
@@ -137,11 +153,30 @@ impl TokenIssuer {
             .address
             .parse::<SparkAddress>()
             .map_err(|_| SdkError::InvalidInput("Invalid spark address".to_string()))?;
-        Ok(self
+        let token_identifier = self.get_issuer_token_metadata().await?.identifier;
+        let response = self
             .spark_wallet
             .freeze_issuer_token(&spark_address)
-            .await?
-            .into())
+            .await?;
+
+        let object_repository = ObjectCacheRepository::new(self.storage.clone());
+        let mut registry = object_repository
+            .fetch_token_freeze_registry(&token_identifier)
+            .await?;
+        registry.push(FrozenTokenHolder {
+            address: request.address,
+            owner_public_key: spark_address.identity_public_key.to_string(),
+            reason: request.reason,
+            frozen_at: now_secs()?,
+            impacted_output_ids: response.impacted_output_ids.clone(),
+            impacted_token_amount: response.impacted_token_amount,
+            unfrozen_at: None,
+        });
+        object_repository
+            .save_token_freeze_registry(&token_identifier, &registry)
+            .await?;
+
+        Ok(response.into())
     }
 
     /// Unfreezes tokens held at the specified address
@@ -163,10 +198,324 @@ impl TokenIssuer {
             .address
             .parse::<SparkAddress>()
             .map_err(|_| SdkError::InvalidInput("Invalid spark address".to_string()))?;
-        Ok(self
+        let token_identifier = self.get_issuer_token_metadata().await?.identifier;
+        let response = self
             .spark_wallet
             .unfreeze_issuer_token(&spark_address)
+            .await?;
+
+        let object_repository = ObjectCacheRepository::new(self.storage.clone());
+        let mut registry = object_repository
+            .fetch_token_freeze_registry(&token_identifier)
+            .await?;
+        record_unfreeze(
+            &mut registry,
+            &spark_address.identity_public_key.to_string(),
+            now_secs()?,
+        );
+        object_repository
+            .save_token_freeze_registry(&token_identifier, &registry)
+            .await?;
+
+        Ok(response.into())
+    }
+
+    /// Lists the holders whose issuer tokens were frozen, as recorded by
+    /// [`TokenIssuer::freeze_issuer_token`]
+    ///
+    /// # Arguments
+    ///
+    /// * `request`: The request specifying whether unfrozen holders are included
+    ///
+    /// # Returns
+    ///
+    /// Result containing either:
+    /// * `ListFrozenTokenHoldersResponse` - The freeze registry entries, oldest first
+    /// * `SdkError` - If there was an error reading the registry
+    pub async fn list_frozen_token_holders(
+        &self,
+        request: ListFrozenTokenHoldersRequest,
+    ) -> Result<ListFrozenTokenHoldersResponse, SdkError> {
+        let token_identifier = self.get_issuer_token_metadata().await?.identifier;
+        let mut holders = ObjectCacheRepository::new(self.storage.clone())
+            .fetch_token_freeze_registry(&token_identifier)
+            .await?;
+        if !request.include_unfrozen.unwrap_or(false) {
+            holders.retain(|h| h.unfrozen_at.is_none());
+        }
+        Ok(ListFrozenTokenHoldersResponse { holders })
+    }
+
+    /// Gets the supply of the issuer token
+    ///
+    /// The minted and burned amounts are computed from the issuer token transactions.
+    ///
+    /// # Returns
+    ///
+    /// Result containing either:
+    /// * `IssuerTokenSupply` - The supply breakdown of the issuer token
+    /// * `SdkError` - If there was an error during the retrieval or no issuer token exists
+    pub async fn get_issuer_token_supply(&self) -> Result<IssuerTokenSupply, SdkError> {
+        let metadata = self.get_issuer_token_metadata().await?;
+        let transactions = self
+            .spark_wallet
+            .list_token_transactions(ListTokenTransactionsRequest {
+                // Without paging, the whole history is fetched page by page
+                paging: None,
+                // No owner filter, so burns by any holder are counted
+                owner_public_keys: Some(Vec::new()),
+                token_ids: vec![metadata.identifier.clone()],
+                ..Default::default()
+            })
+            .await?;
+        let (total_minted, total_burned) = minted_and_burned(&transactions.items)?;
+        let total_supply = total_minted.saturating_sub(total_burned);
+        let issuer_balance = self
+            .spark_wallet
+            .get_token_balances()
+            .await?
+            .get(&metadata.identifier)
+            .map_or(0, |b| b.balance);
+
+        Ok(IssuerTokenSupply {
+            max_supply: metadata.max_supply,
+            total_minted,
+            total_burned,
+            total_supply,
+            issuer_balance,
+            circulating_supply: total_supply.saturating_sub(issuer_balance),
+        })
+    }
+
+    /// Lists the mint, burn and transfer transactions of the issuer token between any holders
+    ///
+    /// # Arguments
+    ///
+    /// * `request`: The request containing the pagination parameters
+    ///
+    /// # Returns
+    ///
+    /// Result containing either:
+    /// * `ListIssuerTokenTransactionsResponse` - The transactions, most recent first
+    /// * `SdkError` - If there was an error during the retrieval or no issuer token exists
+    pub async fn list_issuer_token_transactions(
+        &self,
+        request: ListIssuerTokenTransactionsRequest,
+    ) -> Result<ListIssuerTokenTransactionsResponse, SdkError> {
+        let token_identifier = self.get_issuer_token_metadata().await?.identifier;
+        let transactions = self
+            .spark_wallet
+            .list_token_transactions(ListTokenTransactionsRequest {
+                paging: Some(PagingFilter::new(
+                    request.offset.map(u64::from),
+                    request.limit.map(u64::from),
+                    Some(Order::Descending),
+                )),
+                // No owner filter, so transactions between any holders are returned
+                owner_public_keys: Some(Vec::new()),
+                token_ids: vec![token_identifier],
+                ..Default::default()
+            })
+            .await?;
+
+        Ok(ListIssuerTokenTransactionsResponse {
+            transactions: transactions
+                .items
+                .iter()
+                .map(IssuerTokenTransaction::try_from)
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+
+    /// Distributes the issuer token to many recipients
+    ///
+    /// Mints the missing supply if the issuer balance does not cover the distribution, then
+    /// packs the recipients into as few token transactions as possible. Progress is persisted
+    /// under the idempotency key, so calling again after a partial failure only sends to the
+    /// recipients that were not paid yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `request`: The request containing the recipients and an optional idempotency key
+    ///
+    /// # Returns
+    ///
+    /// Result containing either:
+    /// * `DistributeIssuerTokenResponse` - The per-recipient report of the distribution
+    /// * `SdkError` - If the request is invalid or the distribution could not be started
+    #[allow(clippy::too_many_lines)]
+    pub async fn distribute_issuer_token(
+        &self,
+        request: DistributeIssuerTokenRequest,
+    ) -> Result<DistributeIssuerTokenResponse, SdkError> {
+        if request.recipients.is_empty() {
+            return Err(SdkError::InvalidInput("No recipients provided".to_string()));
+        }
+        let mut receiver_addresses = Vec::with_capacity(request.recipients.len());
+        for (i, recipient) in request.recipients.iter().enumerate() {
+            let address = recipient.address.parse::<SparkAddress>().map_err(|_| {
+                SdkError::InvalidInput(format!("Invalid spark address for recipient {i}"))
+            })?;
+            if recipient.amount == 0 {
+                return Err(SdkError::InvalidInput(format!(
+                    "Amount must be greater than zero for recipient {i}"
+                )));
+            }
+            receiver_addresses.push(address);
+        }
+
+        let token_identifier = self.get_issuer_token_metadata().await?.identifier;
+        let idempotency_key = request
+            .idempotency_key
+            .unwrap_or_else(|| derive_idempotency_key(&token_identifier, &request.recipients));
+        let object_repository = ObjectCacheRepository::new(self.storage.clone());
+        let mut distribution = match object_repository
+            .fetch_token_distribution(&idempotency_key)
             .await?
-            .into())
+        {
+            Some(distribution) => {
+                if !distribution.matches(&token_identifier, &request.recipients) {
+                    return Err(SdkError::InvalidInput(
+                        "Idempotency key was already used for a different distribution".to_string(),
+                    ));
+                }
+                distribution
+            }
+            None => CachedTokenDistribution::new(token_identifier.clone(), &request.recipients),
+        };
+
+        if distribution.has_in_flight() {
+            // Batches are looked up by the hashes recorded before their transactions were
+            // committed, so the lookup doesn't depend on clocks or on how busy the token is.
+            let mut transactions = Vec::new();
+            for hashes in distribution
+                .in_flight_tx_hashes()
+                .chunks(TOKEN_TX_HASHES_PER_QUERY)
+            {
+                transactions.extend(
+                    self.spark_wallet
+                        .get_token_transactions_by_hashes(hashes.to_vec())
+                        .await?,
+                );
+            }
+            distribution.reconcile_in_flight(&transactions);
+            object_repository
+                .save_token_distribution(&idempotency_key, &distribution)
+                .await?;
+        }
+
+        let mint_payment = self
+            .mint_distribution_deficit(&token_identifier, distribution.outstanding_amount())
+            .await?;
+
+        for batch in distribution.unsent_batches() {
+            let batch_id = distribution.next_batch_id();
+            distribution.set_state(
+                &batch,
+                &CachedDistributionState::InFlight {
+                    batch_id,
+                    tx_hashes: Vec::new(),
+                    error: None,
+                },
+            );
+            object_repository
+                .save_token_distribution(&idempotency_key, &distribution)
+                .await?;
+
+            let outputs = batch
+                .iter()
+                .map(|i| TransferTokenOutput {
+                    token_id: token_identifier.clone(),
+                    amount: request.recipients[*i].amount,
+                    receiver_address: receiver_addresses[*i].clone(),
+                    spark_invoice: None,
+                })
+                .collect();
+            let recorder = BatchTxHashRecorder::new(
+                self.storage.clone(),
+                idempotency_key.clone(),
+                batch_id,
+                distribution,
+            );
+            let result = self
+                .spark_wallet
+                .transfer_tokens_with_observer(outputs, None, None, Some(&recorder))
+                .await;
+            distribution = recorder.into_distribution();
+            match result {
+                Ok(token_transaction) => {
+                    if let Err(e) = map_and_persist_token_transaction(
+                        &self.spark_wallet,
+                        &self.storage,
+                        &token_transaction,
+                    )
+                    .await
+                    {
+                        warn!("Failed to persist distribution payments: {e:?}");
+                    }
+                    info!(
+                        "Distribution batch {batch_id} sent to {} recipients in {}",
+                        batch.len(),
+                        token_transaction.hash
+                    );
+                    distribution.set_state(
+                        &batch,
+                        &CachedDistributionState::Sent {
+                            tx_hash: token_transaction.hash,
+                        },
+                    );
+                }
+                Err(e) => {
+                    warn!("Distribution batch {batch_id} failed: {e:?}");
+                    // The transaction may still have been committed, so keep the batch in
+                    // flight to be reconciled when the distribution is resumed.
+                    distribution.set_batch_error(batch_id, &e.to_string());
+                }
+            }
+            object_repository
+                .save_token_distribution(&idempotency_key, &distribution)
+                .await?;
+        }
+
+        Ok(DistributeIssuerTokenResponse {
+            idempotency_key,
+            mint_payment,
+            recipients: distribution.results(),
+        })
     }
 }
+
+impl TokenIssuer {
+    /// Mints the part of `required_amount` not covered by the available issuer token balance.
+    /// Outputs reserved for other transfers or swaps can't be spent by the distribution, so they
+    /// don't count.
+    async fn mint_distribution_deficit(
+        &self,
+        token_identifier: &str,
+        required_amount: u128,
+    ) -> Result<Option<Payment>, SdkError> {
+        let balance = self
+            .spark_wallet
+            .get_token_balances()
+            .await?
+            .get(token_identifier)
+            .map_or(0, |b| b.available_balance);
+        if balance >= required_amount {
+            return Ok(None);
+        }
+
+        let payment = self
+            .mint_issuer_token(MintIssuerTokenRequest {
+                amount: required_amount.saturating_sub(balance),
+            })
+            .await?;
+        Ok(Some(payment))
+    }
+}
+
+fn now_secs() -> Result<u64, SdkError> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| SdkError::Generic("System time is before UNIX_EPOCH".to_string()))?
+        .as_secs())
+}
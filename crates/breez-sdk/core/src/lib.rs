@@ -17,20 +17,26 @@ mod stable_balance;
 mod sync;
 pub mod token_conversion;
 mod utils;
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+mod wallet_manager;
 
 pub use chain::{
     BitcoinChainService, ChainServiceError, RecommendedFees, TxStatus, Utxo,
     rest_client::{ChainApiType, RestClientChainService},
 };
 pub use common::rest::{RestClient, RestResponse};
-pub use common::{fiat::*, models::*, sync_storage};
+pub use common::{fiat::*, models::*, sync_storage, token_registry::*};
 pub use error::{DepositClaimError, SdkError, SignerError};
-pub use events::{EventEmitter, EventListener, OptimizationEvent, SdkEvent};
+pub use events::{
+    EventEmitter, EventListener, OptimizationEvent, SdkEvent, WalletEvent, WalletEventListener,
+};
 pub use issuer::*;
 pub use models::*;
 pub use persist::{
-    PaymentMetadata, SetLnurlMetadataItem, Storage, StorageError, StorageListPaymentsRequest,
-    StoragePaymentDetailsFilter, UpdateDepositPayload, path::default_storage_path,
+    PaymentMetadata, SetLnurlMetadataItem, Storage, StorageConversionFilter, StorageError,
+    StorageListPaymentsRequest, StoragePaymentDetailsFilter, UpdateDepositPayload,
+    migration::{StorageMigrationSummary, migrate_storage},
+    path::default_storage_path,
 };
 pub use sdk::{BreezSdk, default_config, get_spark_status, init_logging, parse_input};
 pub use sdk_builder::SdkBuilder;
@@ -41,16 +47,23 @@ pub use spark_wallet::KeySet;
     not(all(target_family = "wasm", target_os = "unknown"))
 ))]
 pub use persist::postgres::{
-    PoolQueueMode, PostgresStorageConfig, default_postgres_storage_config,
+    PoolQueueMode, PostgresStorage, PostgresStorageConfig, default_postgres_storage_config,
 };
 
+#[cfg(all(
+    feature = "sqlcipher",
+    not(all(target_family = "wasm", target_os = "unknown"))
+))]
+pub use persist::sqlite::SqliteEncryptionKey;
+
 #[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
 pub use {
     persist::sqlite::SqliteStorage,
     sdk::{connect, connect_with_signer},
+    wallet_manager::{WalletManager, WalletManagerBuilder},
 };
 
-pub use sdk::default_external_signer;
+pub use sdk::{default_external_signer, remote_signer};
 
 #[cfg(feature = "test-utils")]
 pub use persist::tests as storage_tests;
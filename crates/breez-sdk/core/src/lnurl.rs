@@ -1,9 +1,14 @@
 use bitcoin::hex::DisplayHex;
 use lnurl_models::{
-    CheckUsernameAvailableResponse, InvoicePaidRequest, ListMetadataResponse,
+    CheckUsernameAvailableResponse, CompleteWithdrawRequest, CreateWebhookRequest,
+    CreateWebhookResponse, CreateWithdrawLinkRequest as ModelCreateWithdrawLinkRequest,
+    CreateWithdrawLinkResponse, DeleteWebhookRequest, DeleteWithdrawLinkRequest,
+    InvoicePaidRequest, ListMetadataResponse, ListWebhooksResponse, ListWithdrawLinksResponse,
+    ListWithdrawRequestsResponse, LnurlPaySettings, NostrIdentity, PendingWithdrawRequest,
     PublishZapReceiptRequest as ModelPublishZapReceiptRequest, PublishZapReceiptResponse,
     RecoverLnurlPayRequest, RecoverLnurlPayResponse, RegisterLnurlPayRequest,
-    RegisterLnurlPayResponse, UnregisterLnurlPayRequest,
+    RegisterLnurlPayResponse, UnregisterLnurlPayRequest, Webhook, WebhookEvent, WithdrawLink,
+    webhook_signing_message,
 };
 use platform_utils::{ContentType, HttpClient, add_content_type_header};
 use std::collections::HashMap;
@@ -45,6 +50,10 @@ pub struct RegisterLightningAddressRequest {
     /// When true, the SDK operates in LNURL private mode: the LNURL server won't track
     /// invoice payments for this user (LUD-21 and NIP-57 disabled).
     pub lnurl_private_mode_enabled: bool,
+    pub pay_settings: LnurlPaySettings,
+    pub nostr: Option<NostrIdentity>,
+    /// When true, the server publishes BIP-353 payment instructions for the username
+    pub publish_bip353: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -65,6 +74,15 @@ pub struct PublishZapReceiptRequest {
     pub zap_receipt: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct CreateWithdrawLinkRequest {
+    pub description: String,
+    pub min_withdrawable: u64,
+    pub max_withdrawable: u64,
+    pub max_uses: Option<u32>,
+    pub expires_at: Option<u64>,
+}
+
 #[macros::async_trait]
 pub trait LnurlServerClient: Send + Sync {
     fn domain(&self) -> &str;
@@ -91,6 +109,31 @@ pub trait LnurlServerClient: Send + Sync {
     /// Notify the server that an invoice has been paid with the given preimage.
     /// This is used for LUD-21 and NIP-57 invoice tracking.
     async fn notify_invoice_paid(&self, preimage: &str) -> Result<(), LnurlServerError>;
+    async fn create_withdraw_link(
+        &self,
+        request: &CreateWithdrawLinkRequest,
+    ) -> Result<WithdrawLink, LnurlServerError>;
+    async fn list_withdraw_links(&self) -> Result<Vec<WithdrawLink>, LnurlServerError>;
+    async fn delete_withdraw_link(&self, id: &str) -> Result<(), LnurlServerError>;
+    /// List the invoices submitted through withdraw links that are waiting to be paid.
+    async fn list_withdraw_requests(&self)
+    -> Result<Vec<PendingWithdrawRequest>, LnurlServerError>;
+    /// Notify the server about the outcome of a withdraw request. The preimage is set if the
+    /// invoice was paid.
+    async fn complete_withdraw_request(
+        &self,
+        payment_hash: &str,
+        preimage: Option<&str>,
+    ) -> Result<(), LnurlServerError>;
+    /// Register a webhook notified about the user's invoices. Returns the webhook together with
+    /// the secret its deliveries are signed with.
+    async fn create_webhook(
+        &self,
+        url: &str,
+        events: &[WebhookEvent],
+    ) -> Result<CreateWebhookResponse, LnurlServerError>;
+    async fn list_webhooks(&self) -> Result<Vec<Webhook>, LnurlServerError>;
+    async fn delete_webhook(&self, id: &str) -> Result<(), LnurlServerError>;
 }
 
 /// Default `LnurlServerClient` implementation using `HttpClient` abstraction.
@@ -247,6 +290,11 @@ impl LnurlServerClient for DefaultLnurlServerClient {
             signature,
             timestamp: Some(timestamp),
             lnurl_private_mode_enabled: request.lnurl_private_mode_enabled,
+            pay_settings: request.pay_settings.clone(),
+            nostr: request.nostr.clone(),
+            publish_bip353: request.publish_bip353,
+            // The SDK can't receive payments to BOLT12 offers
+            bolt12_offer: None,
         };
 
         let url = format!("{}/lnurlpay/{}", self.base_url(), pubkey);
@@ -393,4 +441,239 @@ impl LnurlServerClient for DefaultLnurlServerClient {
             }),
         }
     }
+
+    async fn create_withdraw_link(
+        &self,
+        request: &CreateWithdrawLinkRequest,
+    ) -> Result<WithdrawLink, LnurlServerError> {
+        let pubkey = self.wallet.get_identity_public_key();
+
+        let (signature, timestamp) = self.sign_message(&request.description).await?;
+
+        let payload = ModelCreateWithdrawLinkRequest {
+            signature,
+            timestamp: Some(timestamp),
+            description: request.description.clone(),
+            min_withdrawable: request.min_withdrawable,
+            max_withdrawable: request.max_withdrawable,
+            max_uses: request.max_uses,
+            expires_at: request.expires_at,
+        };
+
+        let url = format!("{}/lnurlpay/{}/withdraw-links", self.base_url(), pubkey);
+        let body = serde_json::to_string(&payload)
+            .map_err(|e| LnurlServerError::RequestFailure(e.to_string()))?;
+
+        let response = self
+            .http_client
+            .post(url, Some(self.get_post_headers()), Some(body))
+            .await
+            .map_err(|e| LnurlServerError::RequestFailure(e.to_string()))?;
+
+        let result: CreateWithdrawLinkResponse =
+            Self::handle_response(response.status, &response.body)?;
+        Ok(result.link)
+    }
+
+    async fn list_withdraw_links(&self) -> Result<Vec<WithdrawLink>, LnurlServerError> {
+        let pubkey = self.wallet.get_identity_public_key();
+
+        let (signature, timestamp) = self.sign_message(&pubkey.to_string()).await?;
+
+        let url = format!(
+            "{}/lnurlpay/{pubkey}/withdraw-links?signature={signature}&timestamp={timestamp}",
+            self.base_url(),
+        );
+
+        let response = self
+            .http_client
+            .get(url, Some(self.get_common_headers()))
+            .await
+            .map_err(|e| LnurlServerError::RequestFailure(e.to_string()))?;
+
+        let result: ListWithdrawLinksResponse =
+            Self::handle_response(response.status, &response.body)?;
+        Ok(result.links)
+    }
+
+    async fn delete_withdraw_link(&self, id: &str) -> Result<(), LnurlServerError> {
+        let pubkey = self.wallet.get_identity_public_key();
+
+        let (signature, timestamp) = self.sign_message(id).await?;
+
+        let payload = DeleteWithdrawLinkRequest {
+            signature,
+            timestamp: Some(timestamp),
+        };
+
+        let url = format!(
+            "{}/lnurlpay/{}/withdraw-links/{}",
+            self.base_url(),
+            pubkey,
+            id
+        );
+        let body = serde_json::to_string(&payload)
+            .map_err(|e| LnurlServerError::RequestFailure(e.to_string()))?;
+
+        let response = self
+            .http_client
+            .delete(url, Some(self.get_post_headers()), Some(body))
+            .await
+            .map_err(|e| LnurlServerError::RequestFailure(e.to_string()))?;
+
+        match response.status {
+            401 => Err(LnurlServerError::InvalidApiKey),
+            404 => Ok(()),
+            s if (200..300).contains(&s) => Ok(()),
+            other => Err(LnurlServerError::Network {
+                statuscode: other,
+                message: Some(response.body),
+            }),
+        }
+    }
+
+    async fn list_withdraw_requests(
+        &self,
+    ) -> Result<Vec<PendingWithdrawRequest>, LnurlServerError> {
+        let pubkey = self.wallet.get_identity_public_key();
+
+        let (signature, timestamp) = self.sign_message(&pubkey.to_string()).await?;
+
+        let url = format!(
+            "{}/lnurlpay/{pubkey}/withdraw-requests?signature={signature}&timestamp={timestamp}",
+            self.base_url(),
+        );
+
+        let response = self
+            .http_client
+            .get(url, Some(self.get_common_headers()))
+            .await
+            .map_err(|e| LnurlServerError::RequestFailure(e.to_string()))?;
+
+        let result: ListWithdrawRequestsResponse =
+            Self::handle_response(response.status, &response.body)?;
+        Ok(result.requests)
+    }
+
+    async fn complete_withdraw_request(
+        &self,
+        payment_hash: &str,
+        preimage: Option<&str>,
+    ) -> Result<(), LnurlServerError> {
+        let pubkey = self.wallet.get_identity_public_key();
+
+        let (signature, timestamp) = self.sign_message(payment_hash).await?;
+
+        let payload = CompleteWithdrawRequest {
+            signature,
+            timestamp: Some(timestamp),
+            preimage: preimage.map(ToString::to_string),
+        };
+
+        let url = format!(
+            "{}/lnurlpay/{}/withdraw-requests/{}",
+            self.base_url(),
+            pubkey,
+            payment_hash
+        );
+        let body = serde_json::to_string(&payload)
+            .map_err(|e| LnurlServerError::RequestFailure(e.to_string()))?;
+
+        let response = self
+            .http_client
+            .post(url, Some(self.get_post_headers()), Some(body))
+            .await
+            .map_err(|e| LnurlServerError::RequestFailure(e.to_string()))?;
+
+        match response.status {
+            401 => Err(LnurlServerError::InvalidApiKey),
+            s if (200..300).contains(&s) => Ok(()),
+            other => Err(LnurlServerError::Network {
+                statuscode: other,
+                message: Some(response.body),
+            }),
+        }
+    }
+
+    async fn create_webhook(
+        &self,
+        url: &str,
+        events: &[WebhookEvent],
+    ) -> Result<CreateWebhookResponse, LnurlServerError> {
+        let pubkey = self.wallet.get_identity_public_key();
+
+        let (signature, timestamp) = self
+            .sign_message(&webhook_signing_message(url, events))
+            .await?;
+
+        let payload = CreateWebhookRequest {
+            signature,
+            timestamp: Some(timestamp),
+            url: url.to_string(),
+            events: events.to_vec(),
+        };
+
+        let url = format!("{}/lnurlpay/{}/webhooks", self.base_url(), pubkey);
+        let body = serde_json::to_string(&payload)
+            .map_err(|e| LnurlServerError::RequestFailure(e.to_string()))?;
+
+        let response = self
+            .http_client
+            .post(url, Some(self.get_post_headers()), Some(body))
+            .await
+            .map_err(|e| LnurlServerError::RequestFailure(e.to_string()))?;
+
+        Self::handle_response(response.status, &response.body)
+    }
+
+    async fn list_webhooks(&self) -> Result<Vec<Webhook>, LnurlServerError> {
+        let pubkey = self.wallet.get_identity_public_key();
+
+        let (signature, timestamp) = self.sign_message(&pubkey.to_string()).await?;
+
+        let url = format!(
+            "{}/lnurlpay/{pubkey}/webhooks?signature={signature}&timestamp={timestamp}",
+            self.base_url(),
+        );
+
+        let response = self
+            .http_client
+            .get(url, Some(self.get_common_headers()))
+            .await
+            .map_err(|e| LnurlServerError::RequestFailure(e.to_string()))?;
+
+        let result: ListWebhooksResponse = Self::handle_response(response.status, &response.body)?;
+        Ok(result.webhooks)
+    }
+
+    async fn delete_webhook(&self, id: &str) -> Result<(), LnurlServerError> {
+        let pubkey = self.wallet.get_identity_public_key();
+
+        let (signature, timestamp) = self.sign_message(id).await?;
+
+        let payload = DeleteWebhookRequest {
+            signature,
+            timestamp: Some(timestamp),
+        };
+
+        let url = format!("{}/lnurlpay/{}/webhooks/{}", self.base_url(), pubkey, id);
+        let body = serde_json::to_string(&payload)
+            .map_err(|e| LnurlServerError::RequestFailure(e.to_string()))?;
+
+        let response = self
+            .http_client
+            .delete(url, Some(self.get_post_headers()), Some(body))
+            .await
+            .map_err(|e| LnurlServerError::RequestFailure(e.to_string()))?;
+
+        match response.status {
+            401 => Err(LnurlServerError::InvalidApiKey),
+            404 => Ok(()),
+            s if (200..300).contains(&s) => Ok(()),
+            other => Err(LnurlServerError::Network {
+                statuscode: other,
+                message: Some(response.body),
+            }),
+        }
+    }
 }
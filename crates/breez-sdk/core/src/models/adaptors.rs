@@ -388,6 +388,10 @@ impl From<spark_wallet::TokenBalance> for TokenBalance {
     fn from(value: spark_wallet::TokenBalance) -> Self {
         Self {
             balance: value.balance,
+            available_balance: value.available_balance,
+            pending_outgoing_balance: value.pending_outgoing_balance,
+            reserved_for_conversion_balance: value.reserved_for_swap_balance,
+            frozen_balance: 0,
             token_metadata: value.token_metadata.into(),
         }
     }
@@ -403,6 +407,7 @@ impl From<spark_wallet::TokenMetadata> for TokenMetadata {
             decimals: value.decimals,
             max_supply: value.max_supply,
             is_freezable: value.is_freezable,
+            registry_info: None,
         }
     }
 }
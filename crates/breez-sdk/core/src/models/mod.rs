@@ -4,8 +4,9 @@ pub use payment_observer::*;
 
 // Re-export public conversion types from the conversion module
 pub use crate::token_conversion::{
-    ConversionEstimate, ConversionInfo, ConversionOptions, ConversionPurpose, ConversionStatus,
-    ConversionType, FetchConversionLimitsRequest, FetchConversionLimitsResponse,
+    Conversion, ConversionEstimate, ConversionInfo, ConversionOptions, ConversionPurpose,
+    ConversionStatus, ConversionSummary, ConversionType, FetchConversionLimitsRequest,
+    FetchConversionLimitsResponse, ListConversionsRequest, ListConversionsResponse,
 };
 
 use core::fmt;
@@ -16,8 +17,9 @@ use std::{collections::HashMap, fmt::Display, str::FromStr};
 
 use crate::{
     BitcoinAddressDetails, BitcoinChainService, BitcoinNetwork, Bolt11InvoiceDetails,
-    ExternalInputParser, FiatCurrency, LnurlPayRequestDetails, LnurlWithdrawRequestDetails, Rate,
-    SdkError, SparkInvoiceDetails, SuccessAction, SuccessActionProcessed, error::DepositClaimError,
+    ExternalInputParser, FiatCurrency, LnurlPayRequestDetails, LnurlPayerData,
+    LnurlWithdrawRequestDetails, Rate, SdkError, SparkInvoiceDetails, SuccessAction,
+    SuccessActionProcessed, TokenRegistryInfo, error::DepositClaimError,
 };
 
 /// A list of external input parsers that are used by default.
@@ -88,6 +90,18 @@ pub struct ConnectWithSignerRequest {
     pub storage_dir: String,
 }
 
+/// Request object for adding an account to a `WalletManager`.
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct AddAccountRequest {
+    /// Identifies the account in the manager and in its events, e.g. "business"
+    pub account_id: String,
+    pub seed: Seed,
+    /// Key set of the account. Accounts sharing a seed need different account numbers.
+    pub key_set_config: Option<KeySetConfig>,
+}
+
 /// The type of payment
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
@@ -848,7 +862,22 @@ pub struct GetInfoResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 pub struct TokenBalance {
+    /// The total balance, including amounts reserved for conversions and frozen amounts
     pub balance: u128,
+    /// The amount that can currently be spent
+    #[serde(default)]
+    pub available_balance: u128,
+    /// The amount locked in outgoing payments that are not yet completed
+    #[serde(default)]
+    pub pending_outgoing_balance: u128,
+    /// The amount reserved for in-progress conversions, returned once the conversion completes
+    #[serde(default)]
+    pub reserved_for_conversion_balance: u128,
+    /// The amount frozen by the token issuer, which can't be spent. Best-effort: Spark doesn't
+    /// report frozen outputs, so this is only set for a while after a send of the token failed
+    /// because its outputs are frozen, and cleared once a send succeeds.
+    #[serde(default)]
+    pub frozen_balance: u128,
     pub token_metadata: TokenMetadata,
 }
 
@@ -864,6 +893,12 @@ pub struct TokenMetadata {
     pub decimals: u32,
     pub max_supply: u128,
     pub is_freezable: bool,
+    /// Information from the configured token registry, if any. Only set on responses of
+    /// [`BreezSdk::get_info`](crate::BreezSdk::get_info) and
+    /// [`BreezSdk::get_tokens_metadata`](crate::BreezSdk::get_tokens_metadata).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "uniffi", uniffi(default = None))]
+    pub registry_info: Option<TokenRegistryInfo>,
 }
 
 /// Request to sync the wallet with the Spark network
@@ -990,6 +1025,26 @@ pub struct PrepareLnurlPayRequest {
     /// How fees should be handled. Defaults to `FeesExcluded` (fees added on top).
     #[cfg_attr(feature = "uniffi", uniffi(default=None))]
     pub fee_policy: Option<FeePolicy>,
+    /// The payer data to share with the service, if it requests it (LUD-18)
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub payer_data: Option<LnurlPayerDataOptions>,
+}
+
+/// The payer data a sender is willing to share with an LNURL-pay service, as per LUD-18.
+///
+/// Only the fields requested by the service are sent.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct LnurlPayerDataOptions {
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub name: Option<String>,
+    /// An identifier of the payer, such as an email address or a lightning address
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub identifier: Option<String>,
+    /// Whether to share the LUD-05 linking key for the service domain as `pubkey` and use it to
+    /// sign the `auth` challenge
+    #[cfg_attr(feature = "uniffi", uniffi(default = false))]
+    pub share_linking_key: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -1008,6 +1063,8 @@ pub struct PrepareLnurlPayResponse {
     pub conversion_estimate: Option<ConversionEstimate>,
     /// How fees are handled for this payment.
     pub fee_policy: FeePolicy,
+    /// The payer data sent to the service (LUD-18)
+    pub payer_data: Option<LnurlPayerData>,
 }
 
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
@@ -1326,6 +1383,62 @@ pub struct RegisterLightningAddressRequest {
     pub username: String,
     #[cfg_attr(feature = "uniffi", uniffi(default=None))]
     pub description: Option<String>,
+    /// Minimum amount (in millisatoshi) that can be sent. Defaults to the server's minimum.
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub min_sendable: Option<u64>,
+    /// Maximum amount (in millisatoshi) that can be sent. Defaults to the server's maximum.
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub max_sendable: Option<u64>,
+    /// Maximum length of the payer comment (LUD-12). Defaults to the server's maximum.
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub comment_allowed: Option<u16>,
+    /// Base64 encoded PNG image shown by wallets paying to the address
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub image: Option<String>,
+    /// Long description shown by wallets paying to the address
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub long_description: Option<String>,
+    /// Nostr identity to serve for the username as a NIP-05 identifier
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub nostr_identity: Option<NostrIdentity>,
+    /// Whether to publish BIP-353 payment instructions for the username in DNS, so it can also be
+    /// paid to as `₿username@domain`. Requires support by the LNURL server. Defaults to false.
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub publish_bip353: Option<bool>,
+}
+
+/// A nostr identity linked to a lightning address, making the address a NIP-05 identifier too.
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NostrIdentity {
+    /// Hex encoded nostr public key
+    pub pubkey: String,
+    /// Relays the nostr public key can be found on
+    pub relays: Vec<String>,
+    /// JSON of a nostr event signed by the nostr key, proving ownership of the key. The content
+    /// of the event must be `nip05:<lightning address>:<identity pubkey>`, where the identity
+    /// pubkey is the one returned by `get_info`.
+    pub proof: String,
+}
+
+impl From<NostrIdentity> for lnurl_models::NostrIdentity {
+    fn from(identity: NostrIdentity) -> Self {
+        lnurl_models::NostrIdentity {
+            pubkey: identity.pubkey,
+            relays: identity.relays,
+            proof: identity.proof,
+        }
+    }
+}
+
+impl From<lnurl_models::NostrIdentity> for NostrIdentity {
+    fn from(identity: lnurl_models::NostrIdentity) -> Self {
+        NostrIdentity {
+            pubkey: identity.pubkey,
+            relays: identity.relays,
+            proof: identity.proof,
+        }
+    }
 }
 
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
@@ -1350,6 +1463,21 @@ pub struct LightningAddressInfo {
     pub lightning_address: String,
     pub lnurl: LnurlInfo,
     pub username: String,
+    #[serde(default)]
+    pub min_sendable: Option<u64>,
+    #[serde(default)]
+    pub max_sendable: Option<u64>,
+    #[serde(default)]
+    pub comment_allowed: Option<u16>,
+    #[serde(default)]
+    pub image: Option<String>,
+    #[serde(default)]
+    pub long_description: Option<String>,
+    #[serde(default)]
+    pub nostr_identity: Option<NostrIdentity>,
+    /// The BIP-353 address payment instructions are published at, if any
+    #[serde(default)]
+    pub bip353_address: Option<String>,
 }
 
 impl From<RecoverLnurlPayResponse> for LightningAddressInfo {
@@ -1359,6 +1487,176 @@ impl From<RecoverLnurlPayResponse> for LightningAddressInfo {
             lightning_address: resp.lightning_address,
             lnurl: LnurlInfo::new(resp.lnurl),
             username: resp.username,
+            min_sendable: resp.pay_settings.min_sendable,
+            max_sendable: resp.pay_settings.max_sendable,
+            comment_allowed: resp.pay_settings.comment_allowed,
+            image: resp.pay_settings.image,
+            long_description: resp.pay_settings.long_description,
+            nostr_identity: resp.nostr.map(Into::into),
+            bip353_address: resp.bip353_address,
+        }
+    }
+}
+
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateLnurlWithdrawLinkRequest {
+    /// Minimum amount (in satoshi) that can be withdrawn per use
+    pub min_withdrawable_sat: u64,
+    /// Maximum amount (in satoshi) that can be withdrawn per use
+    pub max_withdrawable_sat: u64,
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub description: Option<String>,
+    /// Number of times the link can be used, e.g. 1 for a single-use link. Unlimited if not set.
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub max_uses: Option<u32>,
+    /// Unix timestamp (seconds) after which the link can no longer be used
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub expires_at: Option<u64>,
+}
+
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateLnurlWithdrawLinkResponse {
+    pub link: LnurlWithdrawLink,
+}
+
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListLnurlWithdrawLinksResponse {
+    pub links: Vec<LnurlWithdrawLink>,
+}
+
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteLnurlWithdrawLinkRequest {
+    pub id: String,
+}
+
+/// A withdraw link issued on the LNURL server. Invoices submitted to the link are paid by
+/// the wallet when it syncs.
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LnurlWithdrawLink {
+    pub id: String,
+    pub lnurl: LnurlInfo,
+    pub description: String,
+    pub min_withdrawable_sat: u64,
+    pub max_withdrawable_sat: u64,
+    /// Number of times the link can be used. Unlimited if not set.
+    pub max_uses: Option<u32>,
+    /// Number of times the link has been used, including withdrawals still being paid
+    pub uses: u32,
+    /// Unix timestamp (seconds) after which the link can no longer be used
+    pub expires_at: Option<u64>,
+    /// Unix timestamp (seconds) when the link was created
+    pub created_at: u64,
+}
+
+impl From<lnurl_models::WithdrawLink> for LnurlWithdrawLink {
+    fn from(link: lnurl_models::WithdrawLink) -> Self {
+        Self {
+            id: link.id,
+            lnurl: LnurlInfo::new(link.lnurl),
+            description: link.description,
+            min_withdrawable_sat: link.min_withdrawable / 1000,
+            max_withdrawable_sat: link.max_withdrawable / 1000,
+            max_uses: link.max_uses,
+            uses: link.uses,
+            expires_at: link.expires_at,
+            created_at: u64::try_from(link.created_at / 1000).unwrap_or_default(),
+        }
+    }
+}
+
+/// Events an LNURL webhook can be notified about.
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LnurlWebhookEvent {
+    /// An invoice was requested from the lightning address
+    InvoiceRequested,
+    /// An invoice of the lightning address was paid
+    InvoicePaid,
+}
+
+impl FromStr for LnurlWebhookEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "invoice_requested" => LnurlWebhookEvent::InvoiceRequested,
+            "invoice_paid" => LnurlWebhookEvent::InvoicePaid,
+            _ => return Err(format!("Invalid webhook event '{s}'")),
+        })
+    }
+}
+
+impl From<LnurlWebhookEvent> for lnurl_models::WebhookEvent {
+    fn from(event: LnurlWebhookEvent) -> Self {
+        match event {
+            LnurlWebhookEvent::InvoiceRequested => lnurl_models::WebhookEvent::InvoiceRequested,
+            LnurlWebhookEvent::InvoicePaid => lnurl_models::WebhookEvent::InvoicePaid,
+        }
+    }
+}
+
+impl From<lnurl_models::WebhookEvent> for LnurlWebhookEvent {
+    fn from(event: lnurl_models::WebhookEvent) -> Self {
+        match event {
+            lnurl_models::WebhookEvent::InvoiceRequested => LnurlWebhookEvent::InvoiceRequested,
+            lnurl_models::WebhookEvent::InvoicePaid => LnurlWebhookEvent::InvoicePaid,
+        }
+    }
+}
+
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterLnurlWebhookRequest {
+    /// The url the LNURL server posts the events to
+    pub url: String,
+    pub events: Vec<LnurlWebhookEvent>,
+}
+
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterLnurlWebhookResponse {
+    pub webhook: LnurlWebhook,
+    /// Hex encoded secret the deliveries are signed with. Keep it on the receiving backend to
+    /// verify the deliveries, it can't be retrieved again.
+    pub secret: String,
+}
+
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListLnurlWebhooksResponse {
+    pub webhooks: Vec<LnurlWebhook>,
+}
+
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteLnurlWebhookRequest {
+    pub id: String,
+}
+
+/// A webhook registered on the LNURL server, notified about the invoices of the lightning
+/// address.
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LnurlWebhook {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<LnurlWebhookEvent>,
+    /// Unix timestamp (seconds) when the webhook was registered
+    pub created_at: u64,
+}
+
+impl From<lnurl_models::Webhook> for LnurlWebhook {
+    fn from(webhook: lnurl_models::Webhook) -> Self {
+        Self {
+            id: webhook.id,
+            url: webhook.url,
+            events: webhook.events.into_iter().map(Into::into).collect(),
+            created_at: u64::try_from(webhook.created_at / 1000).unwrap_or_default(),
         }
     }
 }
@@ -1480,6 +1778,29 @@ pub struct GetTokensMetadataResponse {
     pub tokens_metadata: Vec<TokenMetadata>,
 }
 
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ExportBackupResponse {
+    /// The encrypted backup, only readable by the same wallet
+    pub backup: Vec<u8>,
+}
+
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ImportBackupRequest {
+    /// A backup created by [`BreezSdk::export_backup`](crate::BreezSdk::export_backup)
+    pub backup: Vec<u8>,
+}
+
+/// The number of items imported from a backup. Items already in the storage aren't counted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ImportBackupResponse {
+    pub payments: u32,
+    pub payment_metadata: u32,
+    pub deposits: u32,
+    pub lnurl_metadata: u32,
+    pub cached_items: u32,
+}
+
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 pub struct SignMessageRequest {
     pub message: String,
@@ -1520,6 +1841,27 @@ pub struct UpdateUserSettingsRequest {
     pub spark_private_mode_enabled: Option<bool>,
 }
 
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct SetSyncedValueRequest {
+    /// Groups the keys of an app, must not contain `/`
+    pub namespace: String,
+    pub key: String,
+    /// The value to set, or `None` to remove the key
+    pub value: Option<String>,
+}
+
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct GetSyncedValueRequest {
+    pub namespace: String,
+    pub key: String,
+}
+
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, Serialize)]
+pub struct GetSyncedValueResponse {
+    pub value: Option<String>,
+}
+
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 pub struct ClaimHtlcPaymentRequest {
     pub preimage: String,
@@ -1536,6 +1878,9 @@ pub struct LnurlReceiveMetadata {
     pub nostr_zap_request: Option<String>,
     pub nostr_zap_receipt: Option<String>,
     pub sender_comment: Option<String>,
+    /// The payer data sent by the payer (LUD-18)
+    #[serde(default)]
+    pub payer_data: Option<LnurlPayerData>,
 }
 
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
@@ -0,0 +1,185 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    FROZEN_TOKENS_KEY, LNURL_WITHDRAW_LINKS_KEY, PaymentMetadata, SetLnurlMetadataItem, Storage,
+    StorageError, TOKEN_DISTRIBUTION_KEY_PREFIX, TOKEN_FREEZE_REGISTRY_KEY_PREFIX,
+    UpdateDepositPayload, migration::list_all_payments,
+};
+use crate::{DepositInfo, ImportBackupResponse, Payment};
+
+/// A snapshot of the wallet data that can't be fully recovered from the Spark operators.
+/// Cached items a fresh install rebuilds and the real-time sync state are left out.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct StorageBackup {
+    /// Unix timestamp (seconds) when the backup was created
+    pub created_at: u64,
+    /// Payments, including child payments
+    pub payments: Vec<Payment>,
+    pub payment_metadata: HashMap<String, PaymentMetadata>,
+    /// Unclaimed deposits, including their claim errors and refunds
+    pub deposits: Vec<DepositInfo>,
+    pub lnurl_metadata: Vec<SetLnurlMetadataItem>,
+    /// Cached items that can't be rebuilt, see [`is_backed_up_cached_item`]
+    #[serde(default)]
+    pub cached_items: HashMap<String, String>,
+}
+
+impl StorageBackup {
+    pub(crate) async fn from_storage(
+        storage: &dyn Storage,
+        created_at: u64,
+    ) -> Result<Self, StorageError> {
+        Ok(Self {
+            created_at,
+            payments: list_all_payments(storage).await?,
+            payment_metadata: storage.list_payment_metadata().await?,
+            deposits: storage.list_deposits().await?,
+            lnurl_metadata: storage.list_lnurl_metadata().await?,
+            cached_items: storage
+                .list_cached_items()
+                .await?
+                .into_iter()
+                .filter(|(key, _)| is_backed_up_cached_item(key))
+                .collect(),
+        })
+    }
+
+    /// Merges the backup into the storage. Data already in the storage takes precedence, the
+    /// backup only fills in what's missing.
+    pub(crate) async fn merge_into(
+        self,
+        storage: &dyn Storage,
+    ) -> Result<ImportBackupResponse, StorageError> {
+        let mut response = ImportBackupResponse::default();
+
+        // Merged before the payments, so child payments are recognized as such
+        let existing_metadata = storage.list_payment_metadata().await?;
+        for (payment_id, metadata) in self.payment_metadata {
+            let metadata = match existing_metadata.get(&payment_id) {
+                Some(existing) => missing_metadata(existing, metadata),
+                None => Some(metadata),
+            };
+            if let Some(metadata) = metadata {
+                storage
+                    .insert_payment_metadata(payment_id, metadata)
+                    .await?;
+                response.payment_metadata = response.payment_metadata.saturating_add(1);
+            }
+        }
+
+        let existing_payments: HashSet<String> = list_all_payments(storage)
+            .await?
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+        for payment in self.payments {
+            if existing_payments.contains(&payment.id) {
+                continue;
+            }
+            storage.insert_payment(payment).await?;
+            response.payments = response.payments.saturating_add(1);
+        }
+
+        let existing_deposits: HashMap<(String, u32), DepositInfo> = storage
+            .list_deposits()
+            .await?
+            .into_iter()
+            .map(|d| ((d.txid.clone(), d.vout), d))
+            .collect();
+        for deposit in self.deposits {
+            let existing = existing_deposits.get(&(deposit.txid.clone(), deposit.vout));
+            if existing.is_some_and(|d| d.refund_tx_id.is_some() || d.claim_error.is_some()) {
+                continue;
+            }
+            let payload = match (deposit.refund_tx, deposit.refund_tx_id, deposit.claim_error) {
+                (Some(refund_tx), Some(refund_txid), _) => Some(UpdateDepositPayload::Refund {
+                    refund_txid,
+                    refund_tx,
+                }),
+                (_, _, Some(error)) => Some(UpdateDepositPayload::ClaimError { error }),
+                _ => None,
+            };
+            if existing.is_some() && payload.is_none() {
+                continue;
+            }
+            storage
+                .add_deposit(deposit.txid.clone(), deposit.vout, deposit.amount_sats)
+                .await?;
+            if let Some(payload) = payload {
+                storage
+                    .update_deposit(deposit.txid, deposit.vout, payload)
+                    .await?;
+            }
+            response.deposits = response.deposits.saturating_add(1);
+        }
+
+        let existing_lnurl_metadata: HashSet<String> = storage
+            .list_lnurl_metadata()
+            .await?
+            .into_iter()
+            .map(|m| m.payment_hash)
+            .collect();
+        let lnurl_metadata: Vec<SetLnurlMetadataItem> = self
+            .lnurl_metadata
+            .into_iter()
+            .filter(|m| !existing_lnurl_metadata.contains(&m.payment_hash))
+            .collect();
+        response.lnurl_metadata = u32::try_from(lnurl_metadata.len())?;
+        if !lnurl_metadata.is_empty() {
+            storage.set_lnurl_metadata(lnurl_metadata).await?;
+        }
+
+        for (key, value) in self.cached_items {
+            if !is_backed_up_cached_item(&key)
+                || storage.get_cached_item(key.clone()).await?.is_some()
+            {
+                continue;
+            }
+            storage.set_cached_item(key, value).await?;
+            response.cached_items = response.cached_items.saturating_add(1);
+        }
+
+        Ok(response)
+    }
+}
+
+/// Whether the cached item is backed up: the withdraw links paid by the wallet, the progress of
+/// token distributions and the token freezes, which can't be recovered from the Spark operators.
+fn is_backed_up_cached_item(key: &str) -> bool {
+    key == LNURL_WITHDRAW_LINKS_KEY
+        || key == FROZEN_TOKENS_KEY
+        || key.starts_with(TOKEN_DISTRIBUTION_KEY_PREFIX)
+        || key.starts_with(TOKEN_FREEZE_REGISTRY_KEY_PREFIX)
+}
+
+/// The fields of the backed up metadata that aren't set in the existing metadata, if any.
+fn missing_metadata(
+    existing: &PaymentMetadata,
+    backup: PaymentMetadata,
+) -> Option<PaymentMetadata> {
+    let missing = PaymentMetadata {
+        parent_payment_id: backup
+            .parent_payment_id
+            .filter(|_| existing.parent_payment_id.is_none()),
+        lnurl_pay_info: backup
+            .lnurl_pay_info
+            .filter(|_| existing.lnurl_pay_info.is_none()),
+        lnurl_withdraw_info: backup
+            .lnurl_withdraw_info
+            .filter(|_| existing.lnurl_withdraw_info.is_none()),
+        lnurl_description: backup
+            .lnurl_description
+            .filter(|_| existing.lnurl_description.is_none()),
+        conversion_info: backup
+            .conversion_info
+            .filter(|_| existing.conversion_info.is_none()),
+    };
+    let is_empty = missing.parent_payment_id.is_none()
+        && missing.lnurl_pay_info.is_none()
+        && missing.lnurl_withdraw_info.is_none()
+        && missing.lnurl_description.is_none()
+        && missing.conversion_info.is_none();
+    (!is_empty).then_some(missing)
+}
@@ -0,0 +1,469 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::{Storage, StorageError, StorageListPaymentsRequest, UpdateDepositPayload};
+use crate::{Payment, sync_storage::UnversionedRecordChange};
+
+/// Cached item in the target storage tracking the progress of a migration, so an interrupted
+/// migration resumes where it stopped.
+pub(crate) const MIGRATION_PROGRESS_KEY: &str = "storage_migration_progress";
+const PAYMENTS_PAGE_SIZE: u32 = 100;
+
+/// The number of items copied by [`migrate_storage`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct StorageMigrationSummary {
+    /// Payments, including child payments
+    pub payments: u32,
+    pub payment_metadata: u32,
+    /// Unclaimed deposits, including their claim errors and refunds
+    pub deposits: u32,
+    pub lnurl_metadata: u32,
+    pub cached_items: u32,
+    /// Records of the real-time sync state
+    pub sync_records: u32,
+    /// Records received by real-time sync but not processed yet
+    pub sync_incoming_records: u32,
+    /// Changes not pushed by real-time sync yet
+    pub sync_outgoing_changes: u32,
+}
+
+/// The steps of a migration, in the order they are run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+enum MigrationStep {
+    #[default]
+    CachedItems,
+    PaymentMetadata,
+    Payments,
+    Deposits,
+    LnurlMetadata,
+    SyncState,
+    SyncIncoming,
+    SyncOutgoing,
+    Verify,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct MigrationProgress {
+    next_step: MigrationStep,
+    /// The number of top-level payments copied so far
+    payments_offset: u32,
+}
+
+/// Copies the complete state of a wallet from `source` to `target`, for example to move a wallet
+/// from the default `SQLite` storage to `PostgreSQL`. The SDK must not run on either storage
+/// during the migration.
+///
+/// The target has to be empty, unless it holds an interrupted migration, which is then resumed.
+/// Once copied, the content of both storages is compared, and the migration fails if they differ.
+/// A failed verification can be fixed by running the migration again.
+///
+/// # Arguments
+///
+/// * `source` - The storage to copy from. It is not modified.
+/// * `target` - The storage to copy to
+///
+/// # Returns
+///
+/// The number of items copied or a `StorageError`
+#[cfg_attr(feature = "uniffi", uniffi::export(async_runtime = "tokio"))]
+pub async fn migrate_storage(
+    source: Arc<dyn Storage>,
+    target: Arc<dyn Storage>,
+) -> Result<StorageMigrationSummary, StorageError> {
+    StorageMigration {
+        source: source.as_ref(),
+        target: target.as_ref(),
+    }
+    .run()
+    .await
+}
+
+struct StorageMigration<'a> {
+    source: &'a dyn Storage,
+    target: &'a dyn Storage,
+}
+
+impl StorageMigration<'_> {
+    async fn run(&self) -> Result<StorageMigrationSummary, StorageError> {
+        let mut progress = match self.load_progress().await? {
+            Some(progress) => {
+                info!("Resuming storage migration at {:?}", progress.next_step);
+                progress
+            }
+            None => {
+                self.ensure_target_empty().await?;
+                MigrationProgress::default()
+            }
+        };
+
+        while progress.next_step < MigrationStep::Verify {
+            match progress.next_step {
+                MigrationStep::CachedItems => self.copy_cached_items().await?,
+                MigrationStep::PaymentMetadata => self.copy_payment_metadata().await?,
+                MigrationStep::Payments => self.copy_payments(&mut progress).await?,
+                MigrationStep::Deposits => self.copy_deposits().await?,
+                MigrationStep::LnurlMetadata => self.copy_lnurl_metadata().await?,
+                MigrationStep::SyncState => self.copy_sync_state().await?,
+                MigrationStep::SyncIncoming => self.copy_sync_incoming().await?,
+                MigrationStep::SyncOutgoing => self.copy_sync_outgoing().await?,
+                MigrationStep::Verify => {}
+            }
+            progress.next_step = next_step(progress.next_step);
+            self.save_progress(&progress).await?;
+        }
+
+        let summary = match self.verify().await {
+            Ok(summary) => summary,
+            Err(e) => {
+                // Start over on the next run. Copying again is harmless, every step is idempotent.
+                self.save_progress(&MigrationProgress::default()).await?;
+                return Err(e);
+            }
+        };
+        self.target
+            .delete_cached_item(MIGRATION_PROGRESS_KEY.to_string())
+            .await?;
+        info!("Storage migration completed: {summary:?}");
+        Ok(summary)
+    }
+
+    async fn load_progress(&self) -> Result<Option<MigrationProgress>, StorageError> {
+        let value = self
+            .target
+            .get_cached_item(MIGRATION_PROGRESS_KEY.to_string())
+            .await?;
+        match value {
+            Some(value) => Ok(Some(serde_json::from_str(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn save_progress(&self, progress: &MigrationProgress) -> Result<(), StorageError> {
+        self.target
+            .set_cached_item(
+                MIGRATION_PROGRESS_KEY.to_string(),
+                serde_json::to_string(progress)?,
+            )
+            .await
+    }
+
+    async fn ensure_target_empty(&self) -> Result<(), StorageError> {
+        let payments = self
+            .target
+            .list_payments(StorageListPaymentsRequest {
+                limit: Some(1),
+                ..Default::default()
+            })
+            .await?;
+        let is_empty = payments.is_empty()
+            && self.target.list_deposits().await?.is_empty()
+            && self.target.get_sync_state_records().await?.is_empty()
+            && self
+                .target
+                .get_pending_outgoing_changes(1)
+                .await?
+                .is_empty();
+        if !is_empty {
+            return Err(StorageError::Implementation(
+                "Storage migration target is not empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn copy_cached_items(&self) -> Result<(), StorageError> {
+        for (key, value) in self.source.list_cached_items().await? {
+            if key == MIGRATION_PROGRESS_KEY {
+                continue;
+            }
+            self.target.set_cached_item(key, value).await?;
+        }
+        Ok(())
+    }
+
+    async fn copy_payment_metadata(&self) -> Result<(), StorageError> {
+        // Copied before the payments, so child payments are recognized as such in the target
+        for (payment_id, metadata) in self.source.list_payment_metadata().await? {
+            self.target
+                .insert_payment_metadata(payment_id, metadata)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn copy_payments(&self, progress: &mut MigrationProgress) -> Result<(), StorageError> {
+        loop {
+            let payments = list_payments_page(self.source, progress.payments_offset).await?;
+            if payments.is_empty() {
+                return Ok(());
+            }
+            let page_len = u32::try_from(payments.len())?;
+            let parent_ids = payments.iter().map(|p| p.id.clone()).collect();
+            let children = self.source.get_payments_by_parent_ids(parent_ids).await?;
+            for payment in payments.into_iter().chain(children.into_values().flatten()) {
+                self.target.insert_payment(payment).await?;
+            }
+
+            progress.payments_offset = progress.payments_offset.saturating_add(page_len);
+            self.save_progress(progress).await?;
+            if page_len < PAYMENTS_PAGE_SIZE {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn copy_deposits(&self) -> Result<(), StorageError> {
+        for deposit in self.source.list_deposits().await? {
+            self.target
+                .add_deposit(deposit.txid.clone(), deposit.vout, deposit.amount_sats)
+                .await?;
+            let payload = match (deposit.refund_tx, deposit.refund_tx_id, deposit.claim_error) {
+                (Some(refund_tx), Some(refund_txid), _) => Some(UpdateDepositPayload::Refund {
+                    refund_txid,
+                    refund_tx,
+                }),
+                (_, _, Some(error)) => Some(UpdateDepositPayload::ClaimError { error }),
+                _ => None,
+            };
+            if let Some(payload) = payload {
+                self.target
+                    .update_deposit(deposit.txid, deposit.vout, payload)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn copy_lnurl_metadata(&self) -> Result<(), StorageError> {
+        let metadata = self.source.list_lnurl_metadata().await?;
+        self.target.set_lnurl_metadata(metadata).await
+    }
+
+    async fn copy_sync_state(&self) -> Result<(), StorageError> {
+        // Also brings the last revision of the target up to date
+        for record in self.source.get_sync_state_records().await? {
+            self.target.update_record_from_incoming(record).await?;
+        }
+        Ok(())
+    }
+
+    async fn copy_sync_incoming(&self) -> Result<(), StorageError> {
+        let records = self
+            .source
+            .get_incoming_records(u32::MAX)
+            .await?
+            .into_iter()
+            .map(|change| change.new_state)
+            .collect();
+        self.target.insert_incoming_records(records).await
+    }
+
+    async fn copy_sync_outgoing(&self) -> Result<(), StorageError> {
+        // Adding a change isn't idempotent. The changes are copied in order, so the ones already in
+        // the target are the first ones of the source.
+        let copied = self.target.get_pending_outgoing_changes(u32::MAX).await?;
+        let changes = self.source.get_pending_outgoing_changes(u32::MAX).await?;
+        for change in changes.into_iter().skip(copied.len()) {
+            self.target
+                .add_outgoing_change(UnversionedRecordChange {
+                    id: change.change.id,
+                    schema_version: change.change.schema_version,
+                    updated_fields: change.change.updated_fields,
+                })
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn verify(&self) -> Result<StorageMigrationSummary, StorageError> {
+        let payments = payment_ids(self.source).await?;
+        ensure_equal("payments", &payments, &payment_ids(self.target).await?)?;
+
+        let payment_metadata = self.source.list_payment_metadata().await?;
+        ensure_equal(
+            "payment metadata",
+            &serialized(&payment_metadata)?,
+            &serialized(&self.target.list_payment_metadata().await?)?,
+        )?;
+
+        let deposit_key = |d: &crate::DepositInfo| {
+            (
+                d.txid.clone(),
+                d.vout,
+                d.refund_tx_id.clone(),
+                d.claim_error.is_some(),
+            )
+        };
+        let deposits: HashSet<_> = self
+            .source
+            .list_deposits()
+            .await?
+            .iter()
+            .map(deposit_key)
+            .collect();
+        let target_deposits = self.target.list_deposits().await?;
+        ensure_equal(
+            "deposits",
+            &deposits,
+            &target_deposits.iter().map(deposit_key).collect(),
+        )?;
+
+        let lnurl_metadata = serialized(&self.source.list_lnurl_metadata().await?)?;
+        ensure_equal(
+            "lnurl metadata",
+            &lnurl_metadata,
+            &serialized(&self.target.list_lnurl_metadata().await?)?,
+        )?;
+
+        let mut cached_items = self.source.list_cached_items().await?;
+        cached_items.remove(MIGRATION_PROGRESS_KEY);
+        let mut target_cached_items = self.target.list_cached_items().await?;
+        target_cached_items.remove(MIGRATION_PROGRESS_KEY);
+        if cached_items != target_cached_items {
+            return Err(verification_error("cached items"));
+        }
+
+        let record_key = |r: crate::sync_storage::Record| (r.id.r#type, r.id.data_id, r.revision);
+        let sync_records: HashSet<_> = self
+            .source
+            .get_sync_state_records()
+            .await?
+            .into_iter()
+            .map(record_key)
+            .collect();
+        let target_sync_records = self.target.get_sync_state_records().await?;
+        ensure_equal(
+            "sync records",
+            &sync_records,
+            &target_sync_records.into_iter().map(record_key).collect(),
+        )?;
+        if self.source.get_last_revision().await? != self.target.get_last_revision().await? {
+            return Err(verification_error("sync revisions"));
+        }
+
+        let sync_incoming_records = self.source.get_incoming_records(u32::MAX).await?.len();
+        if sync_incoming_records != self.target.get_incoming_records(u32::MAX).await?.len() {
+            return Err(verification_error("incoming sync records"));
+        }
+        let sync_outgoing_changes = self
+            .source
+            .get_pending_outgoing_changes(u32::MAX)
+            .await?
+            .len();
+        if sync_outgoing_changes
+            != self
+                .target
+                .get_pending_outgoing_changes(u32::MAX)
+                .await?
+                .len()
+        {
+            return Err(verification_error("outgoing sync changes"));
+        }
+
+        Ok(StorageMigrationSummary {
+            payments: u32::try_from(payments.len())?,
+            payment_metadata: u32::try_from(payment_metadata.len())?,
+            deposits: u32::try_from(deposits.len())?,
+            lnurl_metadata: u32::try_from(lnurl_metadata.len())?,
+            cached_items: u32::try_from(cached_items.len())?,
+            sync_records: u32::try_from(sync_records.len())?,
+            sync_incoming_records: u32::try_from(sync_incoming_records)?,
+            sync_outgoing_changes: u32::try_from(sync_outgoing_changes)?,
+        })
+    }
+}
+
+fn next_step(step: MigrationStep) -> MigrationStep {
+    match step {
+        MigrationStep::CachedItems => MigrationStep::PaymentMetadata,
+        MigrationStep::PaymentMetadata => MigrationStep::Payments,
+        MigrationStep::Payments => MigrationStep::Deposits,
+        MigrationStep::Deposits => MigrationStep::LnurlMetadata,
+        MigrationStep::LnurlMetadata => MigrationStep::SyncState,
+        MigrationStep::SyncState => MigrationStep::SyncIncoming,
+        MigrationStep::SyncIncoming => MigrationStep::SyncOutgoing,
+        MigrationStep::SyncOutgoing | MigrationStep::Verify => MigrationStep::Verify,
+    }
+}
+
+async fn list_payments_page(
+    storage: &dyn Storage,
+    offset: u32,
+) -> Result<Vec<Payment>, StorageError> {
+    storage
+        .list_payments(StorageListPaymentsRequest {
+            offset: Some(offset),
+            limit: Some(PAYMENTS_PAGE_SIZE),
+            sort_ascending: Some(true),
+            ..Default::default()
+        })
+        .await
+}
+
+/// The ids of all payments, including child payments
+async fn payment_ids(storage: &dyn Storage) -> Result<HashSet<String>, StorageError> {
+    Ok(list_all_payments(storage)
+        .await?
+        .into_iter()
+        .map(|p| p.id)
+        .collect())
+}
+
+/// All payments, including child payments
+pub(super) async fn list_all_payments(storage: &dyn Storage) -> Result<Vec<Payment>, StorageError> {
+    let mut all_payments = Vec::new();
+    let mut offset = 0;
+    loop {
+        let payments = list_payments_page(storage, offset).await?;
+        if payments.is_empty() {
+            return Ok(all_payments);
+        }
+        let page_len = u32::try_from(payments.len())?;
+        let parent_ids = payments.iter().map(|p| p.id.clone()).collect();
+        let children = storage.get_payments_by_parent_ids(parent_ids).await?;
+        all_payments.extend(payments);
+        all_payments.extend(children.into_values().flatten());
+
+        offset = offset.saturating_add(page_len);
+        if page_len < PAYMENTS_PAGE_SIZE {
+            return Ok(all_payments);
+        }
+    }
+}
+
+fn ensure_equal<T: Eq + std::hash::Hash>(
+    name: &str,
+    source: &HashSet<T>,
+    target: &HashSet<T>,
+) -> Result<(), StorageError> {
+    if source != target {
+        warn!(
+            "Storage migration verification: {} {name} in source, {} in target",
+            source.len(),
+            target.len()
+        );
+        return Err(verification_error(name));
+    }
+    Ok(())
+}
+
+/// Serializes the items, so their contents can be compared. Json objects are serialized with
+/// their keys sorted, so equal items serialize the same.
+fn serialized<T: Serialize>(
+    items: impl IntoIterator<Item = T>,
+) -> Result<HashSet<String>, StorageError> {
+    items
+        .into_iter()
+        .map(|item| Ok(serde_json::to_value(item)?.to_string()))
+        .collect()
+}
+
+fn verification_error(name: &str) -> StorageError {
+    StorageError::Implementation(format!(
+        "Storage migration verification failed: {name} differ between source and target"
+    ))
+}
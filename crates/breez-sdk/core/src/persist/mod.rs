@@ -1,3 +1,5 @@
+pub(crate) mod backup;
+pub(crate) mod migration;
 pub(crate) mod path;
 #[cfg(all(
     feature = "postgres",
@@ -14,23 +16,30 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
-    AssetFilter, ConversionInfo, DepositClaimError, DepositInfo, LightningAddressInfo,
-    ListPaymentsRequest, LnurlPayInfo, LnurlWithdrawInfo, PaymentDetailsFilter, PaymentStatus,
-    PaymentType, SparkHtlcStatus, TokenBalance, TokenMetadata, TokenTransactionType,
+    AssetFilter, ConversionInfo, DepositClaimError, DepositInfo, FrozenTokenHolder,
+    LightningAddressInfo, ListPaymentsRequest, LnurlPayInfo, LnurlWithdrawInfo,
+    PaymentDetailsFilter, PaymentStatus, PaymentType, SparkHtlcStatus, TokenBalance, TokenMetadata,
+    TokenRegistryInfo, TokenTransactionType,
     models::Payment,
     sync_storage::{IncomingChange, OutgoingChange, Record, UnversionedRecordChange},
 };
 
 const ACCOUNT_INFO_KEY: &str = "account_info";
 const LAST_SYNC_TIME_KEY: &str = "last_sync_time";
-const LIGHTNING_ADDRESS_KEY: &str = "lightning_address";
+pub(crate) const LIGHTNING_ADDRESS_KEY: &str = "lightning_address";
 const LNURL_METADATA_UPDATED_AFTER_KEY: &str = "lnurl_metadata_updated_after";
+const LNURL_WITHDRAW_LINKS_KEY: &str = "lnurl_withdraw_links";
 const SYNC_OFFSET_KEY: &str = "sync_offset";
 const TX_CACHE_KEY: &str = "tx_cache";
 const STATIC_DEPOSIT_ADDRESS_CACHE_KEY: &str = "static_deposit_address";
 const TOKEN_METADATA_KEY_PREFIX: &str = "token_metadata_";
 const PAYMENT_METADATA_KEY_PREFIX: &str = "payment_metadata";
 const SPARK_PRIVATE_MODE_INITIALIZED_KEY: &str = "spark_private_mode_initialized";
+const FROZEN_TOKENS_KEY: &str = "frozen_tokens";
+const TOKEN_DISTRIBUTION_KEY_PREFIX: &str = "token_distribution_";
+const TOKEN_FREEZE_REGISTRY_KEY_PREFIX: &str = "token_freeze_registry_";
+const TOKEN_REGISTRY_INFO_KEY_PREFIX: &str = "token_registry_info_";
+pub(crate) const SYNCED_VALUE_KEY_PREFIX: &str = "synced_value_";
 
 #[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
 pub enum UpdateDepositPayload {
@@ -43,6 +52,7 @@ pub enum UpdateDepositPayload {
     },
 }
 
+#[derive(Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 pub struct SetLnurlMetadataItem {
     pub payment_hash: String,
@@ -50,6 +60,8 @@ pub struct SetLnurlMetadataItem {
     pub nostr_zap_request: Option<String>,
     pub nostr_zap_receipt: Option<String>,
     pub preimage: Option<String>,
+    /// The raw payer data json sent by the payer (LUD-18)
+    pub payer_data: Option<String>,
 }
 
 impl From<lnurl_models::ListMetadataMetadata> for SetLnurlMetadataItem {
@@ -60,6 +72,7 @@ impl From<lnurl_models::ListMetadataMetadata> for SetLnurlMetadataItem {
             nostr_zap_request: value.nostr_zap_request,
             nostr_zap_receipt: value.nostr_zap_receipt,
             preimage: value.preimage,
+            payer_data: value.payer_data,
         }
     }
 }
@@ -172,6 +185,16 @@ impl From<StoragePaymentDetailsFilter> for PaymentDetailsFilter {
     }
 }
 
+/// Restricts a payment listing to conversion payments, including the ones made
+/// as part of another payment and stored as its child payments.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct StorageConversionFilter {
+    /// Only include the payments of these conversions
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub conversion_ids: Option<Vec<String>>,
+}
+
 /// Storage-internal variant of [`ListPaymentsRequest`] that uses
 /// [`StoragePaymentDetailsFilter`] instead of the public [`PaymentDetailsFilter`].
 #[derive(Debug, Clone, Default)]
@@ -186,6 +209,8 @@ pub struct StorageListPaymentsRequest {
     #[cfg_attr(feature = "uniffi", uniffi(default=None))]
     pub payment_details_filter: Option<Vec<StoragePaymentDetailsFilter>>,
     #[cfg_attr(feature = "uniffi", uniffi(default=None))]
+    pub conversion_filter: Option<StorageConversionFilter>,
+    #[cfg_attr(feature = "uniffi", uniffi(default=None))]
     pub from_timestamp: Option<u64>,
     #[cfg_attr(feature = "uniffi", uniffi(default=None))]
     pub to_timestamp: Option<u64>,
@@ -206,6 +231,7 @@ impl From<ListPaymentsRequest> for StorageListPaymentsRequest {
             payment_details_filter: request
                 .payment_details_filter
                 .map(|filters| filters.into_iter().map(Into::into).collect()),
+            conversion_filter: None,
             from_timestamp: request.from_timestamp,
             to_timestamp: request.to_timestamp,
             offset: request.offset,
@@ -250,12 +276,25 @@ pub struct PaymentMetadata {
 }
 
 /// Trait for persistent storage
+///
+/// The listing methods used by storage migrations and backups are optional. In Rust they default
+/// to returning an error. Implementations through the language bindings have to implement every
+/// method, and can return an error from the optional ones.
 #[cfg_attr(feature = "uniffi", uniffi::export(with_foreign))]
 #[async_trait]
 pub trait Storage: Send + Sync {
     async fn delete_cached_item(&self, key: String) -> Result<(), StorageError>;
     async fn get_cached_item(&self, key: String) -> Result<Option<String>, StorageError>;
     async fn set_cached_item(&self, key: String, value: String) -> Result<(), StorageError>;
+    /// Lists all cached items. Only used by storage migrations and backups, the default
+    /// implementation returns an error.
+    ///
+    /// # Returns
+    ///
+    /// A map of key -> value or a `StorageError`
+    async fn list_cached_items(&self) -> Result<HashMap<String, String>, StorageError> {
+        Err(unsupported("list_cached_items"))
+    }
     /// Lists payments with optional filters and pagination
     ///
     /// # Arguments
@@ -297,6 +336,18 @@ pub trait Storage: Send + Sync {
         metadata: PaymentMetadata,
     ) -> Result<(), StorageError>;
 
+    /// Lists the metadata of all payments, including payments not in storage yet. Only used by
+    /// storage migrations and backups, the default implementation returns an error.
+    ///
+    /// # Returns
+    ///
+    /// A map of `payment_id` -> `PaymentMetadata` or a `StorageError`
+    async fn list_payment_metadata(
+        &self,
+    ) -> Result<HashMap<String, PaymentMetadata>, StorageError> {
+        Err(unsupported("list_payment_metadata"))
+    }
+
     /// Gets a payment by its ID
     /// # Arguments
     ///
@@ -390,6 +441,12 @@ pub trait Storage: Send + Sync {
         metadata: Vec<SetLnurlMetadataItem>,
     ) -> Result<(), StorageError>;
 
+    /// Lists all LNURL receive metadata, including metadata of payments not in storage yet. Only
+    /// used by storage migrations and backups, the default implementation returns an error.
+    async fn list_lnurl_metadata(&self) -> Result<Vec<SetLnurlMetadataItem>, StorageError> {
+        Err(unsupported("list_lnurl_metadata"))
+    }
+
     // Sync storage methods
     async fn add_outgoing_change(
         &self,
@@ -427,6 +484,17 @@ pub trait Storage: Send + Sync {
 
     /// Update the sync state record from an incoming record
     async fn update_record_from_incoming(&self, record: Record) -> Result<(), StorageError>;
+
+    /// Get the sync state: the last committed version of every record. Only used by storage
+    /// migrations, the default implementation returns an error.
+    async fn get_sync_state_records(&self) -> Result<Vec<Record>, StorageError> {
+        Err(unsupported("get_sync_state_records"))
+    }
+}
+
+/// The error returned by the optional `Storage` methods a storage doesn't implement.
+fn unsupported(method: &str) -> StorageError {
+    StorageError::Implementation(format!("{method} is not supported by this storage"))
 }
 
 pub(crate) struct ObjectCacheRepository {
@@ -540,7 +608,8 @@ impl ObjectCacheRepository {
     }
 
     /// Marks the lightning address as "recovered, no address registered" by storing `null`.
-    pub(crate) async fn delete_lightning_address(&self) -> Result<(), StorageError> {
+    /// The marker isn't synced to the other instances of the wallet.
+    pub(crate) async fn mark_lightning_address_not_registered(&self) -> Result<(), StorageError> {
         self.storage
             .set_cached_item(
                 LIGHTNING_ADDRESS_KEY.to_string(),
@@ -550,6 +619,14 @@ impl ObjectCacheRepository {
         Ok(())
     }
 
+    /// Removes the lightning address after it was unregistered. The removal is synced, the
+    /// instances of the wallet recover the address again when it's next fetched.
+    pub(crate) async fn delete_lightning_address(&self) -> Result<(), StorageError> {
+        self.storage
+            .delete_cached_item(LIGHTNING_ADDRESS_KEY.to_string())
+            .await
+    }
+
     /// Returns:
     /// - `Ok(None)` — key absent, never recovered
     /// - `Ok(Some(None))` — recovered, no address registered
@@ -567,6 +644,57 @@ impl ObjectCacheRepository {
         }
     }
 
+    /// Sets the value of a synced key, or removes it if `value` is `None`. The key is replicated
+    /// to the other instances of the wallet when real-time sync is enabled.
+    pub(crate) async fn save_synced_value(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: Option<String>,
+    ) -> Result<(), StorageError> {
+        let cache_key = format!("{SYNCED_VALUE_KEY_PREFIX}{namespace}/{key}");
+        match value {
+            Some(value) => self.storage.set_cached_item(cache_key, value).await,
+            None => self.storage.delete_cached_item(cache_key).await,
+        }
+    }
+
+    pub(crate) async fn fetch_synced_value(
+        &self,
+        namespace: &str,
+        key: &str,
+    ) -> Result<Option<String>, StorageError> {
+        self.storage
+            .get_cached_item(format!("{SYNCED_VALUE_KEY_PREFIX}{namespace}/{key}"))
+            .await
+    }
+
+    pub(crate) async fn save_lnurl_withdraw_links(
+        &self,
+        value: &[CachedLnurlWithdrawLink],
+    ) -> Result<(), StorageError> {
+        self.storage
+            .set_cached_item(
+                LNURL_WITHDRAW_LINKS_KEY.to_string(),
+                serde_json::to_string(value)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn fetch_lnurl_withdraw_links(
+        &self,
+    ) -> Result<Vec<CachedLnurlWithdrawLink>, StorageError> {
+        let value = self
+            .storage
+            .get_cached_item(LNURL_WITHDRAW_LINKS_KEY.to_string())
+            .await?;
+        match value {
+            Some(value) => Ok(serde_json::from_str(&value)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
     pub(crate) async fn save_token_metadata(
         &self,
         value: &TokenMetadata,
@@ -679,6 +807,117 @@ impl ObjectCacheRepository {
         }
     }
 
+    pub(crate) async fn save_token_distribution(
+        &self,
+        idempotency_key: &str,
+        value: &CachedTokenDistribution,
+    ) -> Result<(), StorageError> {
+        self.storage
+            .set_cached_item(
+                format!("{TOKEN_DISTRIBUTION_KEY_PREFIX}{idempotency_key}"),
+                serde_json::to_string(value)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn fetch_token_distribution(
+        &self,
+        idempotency_key: &str,
+    ) -> Result<Option<CachedTokenDistribution>, StorageError> {
+        let value = self
+            .storage
+            .get_cached_item(format!("{TOKEN_DISTRIBUTION_KEY_PREFIX}{idempotency_key}"))
+            .await?;
+        match value {
+            Some(value) => Ok(Some(serde_json::from_str(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) async fn save_token_freeze_registry(
+        &self,
+        token_identifier: &str,
+        value: &[FrozenTokenHolder],
+    ) -> Result<(), StorageError> {
+        self.storage
+            .set_cached_item(
+                format!("{TOKEN_FREEZE_REGISTRY_KEY_PREFIX}{token_identifier}"),
+                serde_json::to_string(value)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn fetch_token_freeze_registry(
+        &self,
+        token_identifier: &str,
+    ) -> Result<Vec<FrozenTokenHolder>, StorageError> {
+        let value = self
+            .storage
+            .get_cached_item(format!(
+                "{TOKEN_FREEZE_REGISTRY_KEY_PREFIX}{token_identifier}"
+            ))
+            .await?;
+        match value {
+            Some(value) => Ok(serde_json::from_str(&value)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub(crate) async fn save_token_registry_info(
+        &self,
+        token_identifier: &str,
+        value: &CachedTokenRegistryInfo,
+    ) -> Result<(), StorageError> {
+        self.storage
+            .set_cached_item(
+                format!("{TOKEN_REGISTRY_INFO_KEY_PREFIX}{token_identifier}"),
+                serde_json::to_string(value)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn fetch_token_registry_info(
+        &self,
+        token_identifier: &str,
+    ) -> Result<Option<CachedTokenRegistryInfo>, StorageError> {
+        let value = self
+            .storage
+            .get_cached_item(format!(
+                "{TOKEN_REGISTRY_INFO_KEY_PREFIX}{token_identifier}"
+            ))
+            .await?;
+        match value {
+            Some(value) => Ok(Some(serde_json::from_str(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Saves the tokens found frozen by their issuer, keyed by token identifier
+    /// with the time they were found frozen.
+    pub(crate) async fn save_frozen_tokens(
+        &self,
+        value: &HashMap<String, u64>,
+    ) -> Result<(), StorageError> {
+        self.storage
+            .set_cached_item(FROZEN_TOKENS_KEY.to_string(), serde_json::to_string(value)?)
+            .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn fetch_frozen_tokens(&self) -> Result<HashMap<String, u64>, StorageError> {
+        let value = self
+            .storage
+            .get_cached_item(FROZEN_TOKENS_KEY.to_string())
+            .await?;
+        match value {
+            Some(value) => Ok(serde_json::from_str(&value)?),
+            None => Ok(HashMap::new()),
+        }
+    }
+
     pub(crate) async fn get_last_sync_time(&self) -> Result<Option<u64>, StorageError> {
         let value = self
             .storage
@@ -722,5 +961,57 @@ pub(crate) struct StaticDepositAddress {
     pub(crate) address: String,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub(crate) struct CachedTokenDistribution {
+    pub(crate) token_identifier: String,
+    pub(crate) recipients: Vec<CachedDistributionRecipient>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub(crate) struct CachedDistributionRecipient {
+    pub(crate) address: String,
+    pub(crate) amount: u128,
+    pub(crate) state: CachedDistributionState,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub(crate) enum CachedDistributionState {
+    /// Not yet attempted
+    Pending,
+    /// Included in a token transaction whose outcome is unknown. The error is set when the
+    /// transfer returned one, as the transaction may still have been committed.
+    InFlight {
+        batch_id: u32,
+        /// Hashes of the token transactions of the batch, recorded before each is committed
+        #[serde(default)]
+        tx_hashes: Vec<String>,
+        error: Option<String>,
+    },
+    Sent {
+        tx_hash: String,
+    },
+}
+
+/// The limits of a withdraw link created by this wallet. Invoices submitted through the LNURL
+/// server are checked against them before being paid.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct CachedLnurlWithdrawLink {
+    pub(crate) id: String,
+    pub(crate) min_withdrawable: u64,
+    pub(crate) max_withdrawable: u64,
+    pub(crate) max_uses: Option<u32>,
+    pub(crate) expires_at: Option<u64>,
+    /// Payment hashes of the invoices paid, or being paid, through this link
+    #[serde(default)]
+    pub(crate) payment_hashes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct CachedTokenRegistryInfo {
+    /// Not set when the token is unknown to the registry
+    pub(crate) info: Option<TokenRegistryInfo>,
+    pub(crate) fetched_at: u64,
+}
+
 #[cfg(feature = "test-utils")]
 pub mod tests;
@@ -11,6 +11,7 @@ use rustls::server::ParsedCertificate;
 use rustls::{
     ClientConfig, DigitallySignedStruct, Error as RustlsError, RootCertStore, SignatureScheme,
 };
+use spark_wallet::PublicKey;
 use tokio_postgres::{Config as PgConfig, Row, types::ToSql};
 use tokio_postgres_rustls::MakeRustlsConnect;
 use webpki_roots::TLS_SERVER_ROOTS;
@@ -36,6 +37,22 @@ use super::{Payment, Storage, StorageError};
 /// Derived from ASCII bytes of "MIGR" (`0x4D49_4752`).
 const MIGRATION_LOCK_ID: i64 = 0x4D49_4752;
 
+/// Tables holding the data of a tenant, cleared when the tenant is deleted.
+const TENANT_TABLES: [&str; 12] = [
+    "payments",
+    "settings",
+    "unclaimed_deposits",
+    "payment_metadata",
+    "payment_details_lightning",
+    "payment_details_token",
+    "payment_details_spark",
+    "lnurl_receive_metadata",
+    "sync_revision",
+    "sync_outgoing",
+    "sync_state",
+    "sync_incoming",
+];
+
 /// Creates a `PostgresStorageConfig` with the given connection string and default pool settings.
 ///
 /// This is a convenience function for creating a config with sensible defaults from deadpool.
@@ -342,9 +359,19 @@ fn make_tls_config_verifying(
     Ok(config)
 }
 
+/// Tenant of the storage when it isn't scoped to a wallet. Rows written before tenant scoping was
+/// added belong to this tenant.
+const DEFAULT_TENANT_ID: &str = "";
+
 /// PostgreSQL-based storage implementation using connection pooling
+///
+/// All rows are scoped to a tenant, so many wallets can share the same tables. A storage created
+/// with [`PostgresStorage::new`] uses the default tenant, [`PostgresStorage::for_tenant`] scopes it
+/// to a wallet identity public key and shares the connection pool.
+#[derive(Clone)]
 pub struct PostgresStorage {
     pool: Pool,
+    tenant_id: String,
 }
 
 /// Creates a rustls `ClientConfig` that accepts any server certificate.
@@ -486,11 +513,67 @@ impl PostgresStorage {
             }
         };
 
-        let storage = Self { pool };
+        let storage = Self {
+            pool,
+            tenant_id: DEFAULT_TENANT_ID.to_string(),
+        };
         storage.migrate().await?;
         Ok(storage)
     }
 
+    /// Returns a storage scoped to the wallet with the given identity public key, sharing the
+    /// connection pool of this storage. The tenant is registered if it doesn't exist yet.
+    pub async fn for_tenant(&self, identity_pub_key: &PublicKey) -> Result<Self, StorageError> {
+        let tenant_id = identity_pub_key.to_string();
+        let client = self.pool.get().await.map_err(map_pool_error)?;
+        client
+            .execute(
+                "INSERT INTO tenants (tenant_id) VALUES ($1) ON CONFLICT(tenant_id) DO NOTHING",
+                &[&tenant_id],
+            )
+            .await?;
+        Ok(Self {
+            pool: self.pool.clone(),
+            tenant_id,
+        })
+    }
+
+    /// Lists the identity public keys of all tenants registered in the database.
+    pub async fn list_tenants(&self) -> Result<Vec<PublicKey>, StorageError> {
+        let client = self.pool.get().await.map_err(map_pool_error)?;
+        let rows = client
+            .query("SELECT tenant_id FROM tenants ORDER BY created_at ASC", &[])
+            .await?;
+        rows.iter()
+            .map(|row| {
+                row.get::<_, String>(0)
+                    .parse()
+                    .map_err(|e: bitcoin::secp256k1::Error| {
+                        StorageError::Serialization(e.to_string())
+                    })
+            })
+            .collect()
+    }
+
+    /// Deletes all data of a tenant, including its sync state. The storages scoped to the tenant
+    /// must not be used anymore.
+    pub async fn delete_tenant(&self, identity_pub_key: &PublicKey) -> Result<(), StorageError> {
+        let tenant_id = identity_pub_key.to_string();
+        let mut client = self.pool.get().await.map_err(map_pool_error)?;
+        let tx = client.transaction().await.map_err(map_db_error)?;
+        for table in TENANT_TABLES {
+            tx.execute(
+                &format!("DELETE FROM {table} WHERE tenant_id = $1"),
+                &[&tenant_id],
+            )
+            .await?;
+        }
+        tx.execute("DELETE FROM tenants WHERE tenant_id = $1", &[&tenant_id])
+            .await?;
+        tx.commit().await.map_err(map_db_error)?;
+        Ok(())
+    }
+
     #[allow(clippy::arithmetic_side_effects)]
     async fn migrate(&self) -> Result<(), StorageError> {
         let mut client = self.pool.get().await.map_err(map_pool_error)?;
@@ -707,6 +790,73 @@ impl PostgresStorage {
             &[
                 "CREATE INDEX IF NOT EXISTS idx_payment_details_lightning_payment_hash ON payment_details_lightning(payment_hash)",
             ],
+            // Migration 11: Add payer_data column for LUD-18 support
+            &[
+                "ALTER TABLE lnurl_receive_metadata ADD COLUMN IF NOT EXISTS payer_data TEXT",
+            ],
+            // Migration 12: Scope all rows to a tenant. Existing rows belong to the default tenant.
+            // The column has no default afterwards, so every query has to set the tenant.
+            &[
+                "CREATE TABLE IF NOT EXISTS tenants (
+                    tenant_id TEXT PRIMARY KEY,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                )",
+                "ALTER TABLE payments ADD COLUMN tenant_id TEXT NOT NULL DEFAULT ''",
+                "ALTER TABLE payments DROP CONSTRAINT payments_pkey, ADD PRIMARY KEY (tenant_id, id)",
+                "ALTER TABLE settings ADD COLUMN tenant_id TEXT NOT NULL DEFAULT ''",
+                "ALTER TABLE settings DROP CONSTRAINT settings_pkey, ADD PRIMARY KEY (tenant_id, key)",
+                "ALTER TABLE unclaimed_deposits ADD COLUMN tenant_id TEXT NOT NULL DEFAULT ''",
+                "ALTER TABLE unclaimed_deposits DROP CONSTRAINT unclaimed_deposits_pkey, ADD PRIMARY KEY (tenant_id, txid, vout)",
+                "ALTER TABLE payment_metadata ADD COLUMN tenant_id TEXT NOT NULL DEFAULT ''",
+                "ALTER TABLE payment_metadata DROP CONSTRAINT payment_metadata_pkey, ADD PRIMARY KEY (tenant_id, payment_id)",
+                "ALTER TABLE payment_details_lightning ADD COLUMN tenant_id TEXT NOT NULL DEFAULT ''",
+                "ALTER TABLE payment_details_lightning DROP CONSTRAINT payment_details_lightning_pkey, ADD PRIMARY KEY (tenant_id, payment_id)",
+                "ALTER TABLE payment_details_token ADD COLUMN tenant_id TEXT NOT NULL DEFAULT ''",
+                "ALTER TABLE payment_details_token DROP CONSTRAINT payment_details_token_pkey, ADD PRIMARY KEY (tenant_id, payment_id)",
+                "ALTER TABLE payment_details_spark ADD COLUMN tenant_id TEXT NOT NULL DEFAULT ''",
+                "ALTER TABLE payment_details_spark DROP CONSTRAINT payment_details_spark_pkey, ADD PRIMARY KEY (tenant_id, payment_id)",
+                "ALTER TABLE lnurl_receive_metadata ADD COLUMN tenant_id TEXT NOT NULL DEFAULT ''",
+                "ALTER TABLE lnurl_receive_metadata DROP CONSTRAINT lnurl_receive_metadata_pkey, ADD PRIMARY KEY (tenant_id, payment_hash)",
+                // Dropping the id column also drops the single row check
+                "ALTER TABLE sync_revision ADD COLUMN tenant_id TEXT NOT NULL DEFAULT ''",
+                "ALTER TABLE sync_revision DROP COLUMN id",
+                "ALTER TABLE sync_revision ADD PRIMARY KEY (tenant_id)",
+                "ALTER TABLE sync_outgoing ADD COLUMN tenant_id TEXT NOT NULL DEFAULT ''",
+                "ALTER TABLE sync_state ADD COLUMN tenant_id TEXT NOT NULL DEFAULT ''",
+                "ALTER TABLE sync_state DROP CONSTRAINT sync_state_pkey, ADD PRIMARY KEY (tenant_id, record_type, data_id)",
+                "ALTER TABLE sync_incoming ADD COLUMN tenant_id TEXT NOT NULL DEFAULT ''",
+                "ALTER TABLE sync_incoming DROP CONSTRAINT sync_incoming_pkey, ADD PRIMARY KEY (tenant_id, record_type, data_id, revision)",
+                "ALTER TABLE payments ALTER COLUMN tenant_id DROP DEFAULT",
+                "ALTER TABLE settings ALTER COLUMN tenant_id DROP DEFAULT",
+                "ALTER TABLE unclaimed_deposits ALTER COLUMN tenant_id DROP DEFAULT",
+                "ALTER TABLE payment_metadata ALTER COLUMN tenant_id DROP DEFAULT",
+                "ALTER TABLE payment_details_lightning ALTER COLUMN tenant_id DROP DEFAULT",
+                "ALTER TABLE payment_details_token ALTER COLUMN tenant_id DROP DEFAULT",
+                "ALTER TABLE payment_details_spark ALTER COLUMN tenant_id DROP DEFAULT",
+                "ALTER TABLE lnurl_receive_metadata ALTER COLUMN tenant_id DROP DEFAULT",
+                "ALTER TABLE sync_revision ALTER COLUMN tenant_id DROP DEFAULT",
+                "ALTER TABLE sync_outgoing ALTER COLUMN tenant_id DROP DEFAULT",
+                "ALTER TABLE sync_state ALTER COLUMN tenant_id DROP DEFAULT",
+                "ALTER TABLE sync_incoming ALTER COLUMN tenant_id DROP DEFAULT",
+                // Indexes lead with the tenant, queries always filter by it
+                "DROP INDEX IF EXISTS idx_payments_timestamp",
+                "DROP INDEX IF EXISTS idx_payments_payment_type",
+                "DROP INDEX IF EXISTS idx_payments_status",
+                "DROP INDEX IF EXISTS idx_payment_details_lightning_invoice",
+                "DROP INDEX IF EXISTS idx_payment_details_lightning_payment_hash",
+                "DROP INDEX IF EXISTS idx_payment_metadata_parent",
+                "DROP INDEX IF EXISTS idx_sync_outgoing_data_id_record_type",
+                "DROP INDEX IF EXISTS idx_sync_incoming_revision",
+                "CREATE INDEX IF NOT EXISTS idx_payments_tenant_timestamp ON payments(tenant_id, timestamp)",
+                "CREATE INDEX IF NOT EXISTS idx_payments_tenant_payment_type ON payments(tenant_id, payment_type)",
+                "CREATE INDEX IF NOT EXISTS idx_payments_tenant_status ON payments(tenant_id, status)",
+                "CREATE INDEX IF NOT EXISTS idx_payment_details_lightning_tenant_invoice ON payment_details_lightning(tenant_id, invoice)",
+                "CREATE INDEX IF NOT EXISTS idx_payment_details_lightning_tenant_payment_hash ON payment_details_lightning(tenant_id, payment_hash)",
+                "CREATE INDEX IF NOT EXISTS idx_payment_metadata_tenant_parent ON payment_metadata(tenant_id, parent_payment_id)",
+                "CREATE INDEX IF NOT EXISTS idx_sync_outgoing_tenant_record_type_data_id ON sync_outgoing(tenant_id, record_type, data_id)",
+                "CREATE INDEX IF NOT EXISTS idx_sync_outgoing_tenant_revision ON sync_outgoing(tenant_id, revision)",
+                "CREATE INDEX IF NOT EXISTS idx_sync_incoming_tenant_revision ON sync_incoming(tenant_id, revision)",
+            ],
         ]
     }
 }
@@ -772,9 +922,9 @@ impl Storage for PostgresStorage {
         let client = self.pool.get().await.map_err(map_pool_error)?;
 
         // Build WHERE clauses based on filters
-        let mut where_clauses = Vec::new();
-        let mut params: Vec<Box<dyn ToSql + Sync + Send>> = Vec::new();
-        let mut param_idx = 1;
+        let mut where_clauses = vec!["p.tenant_id = $1".to_string()];
+        let mut params: Vec<Box<dyn ToSql + Sync + Send>> = vec![Box::new(self.tenant_id.clone())];
+        let mut param_idx = 2;
 
         // Filter by payment type
         if let Some(ref type_filter) = request.type_filter
@@ -955,8 +1105,32 @@ impl Storage for PostgresStorage {
             }
         }
 
-        // Exclude child payments
-        where_clauses.push("pm.parent_payment_id IS NULL".to_string());
+        if let Some(ref conversion_filter) = request.conversion_filter {
+            // Conversions made for a payment are its child payments, so they are included
+            where_clauses.push("pm.conversion_info IS NOT NULL".to_string());
+            if let Some(ref conversion_ids) = conversion_filter.conversion_ids
+                && !conversion_ids.is_empty()
+            {
+                let placeholders: Vec<String> = conversion_ids
+                    .iter()
+                    .map(|_| {
+                        let placeholder = format!("${param_idx}");
+                        param_idx += 1;
+                        placeholder
+                    })
+                    .collect();
+                where_clauses.push(format!(
+                    "pm.conversion_info::jsonb->>'conversion_id' IN ({})",
+                    placeholders.join(", ")
+                ));
+                for conversion_id in conversion_ids {
+                    params.push(Box::new(conversion_id.clone()));
+                }
+            }
+        } else {
+            // Exclude child payments
+            where_clauses.push("pm.parent_payment_id IS NULL".to_string());
+        }
 
         // Build the WHERE clause
         let where_sql = if where_clauses.is_empty() {
@@ -1017,9 +1191,9 @@ impl Storage for PostgresStorage {
 
         // Insert or update main payment record (including detail columns atomically)
         tx.execute(
-            "INSERT INTO payments (id, payment_type, status, amount, fees, timestamp, method, withdraw_tx_id, deposit_tx_id, spark)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-                 ON CONFLICT(id) DO UPDATE SET
+            "INSERT INTO payments (tenant_id, id, payment_type, status, amount, fees, timestamp, method, withdraw_tx_id, deposit_tx_id, spark)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                 ON CONFLICT(tenant_id, id) DO UPDATE SET
                     payment_type = EXCLUDED.payment_type,
                     status = EXCLUDED.status,
                     amount = EXCLUDED.amount,
@@ -1030,6 +1204,7 @@ impl Storage for PostgresStorage {
                     deposit_tx_id = EXCLUDED.deposit_tx_id,
                     spark = EXCLUDED.spark",
             &[
+                &self.tenant_id,
                 &payment.id,
                 &payment.payment_type.to_string(),
                 &payment.status.to_string(),
@@ -1054,12 +1229,12 @@ impl Storage for PostgresStorage {
                     let invoice_json = to_json_opt(invoice_details.as_ref())?;
                     let htlc_json = to_json_opt(htlc_details.as_ref())?;
                     tx.execute(
-                        "INSERT INTO payment_details_spark (payment_id, invoice_details, htlc_details)
-                             VALUES ($1, $2, $3)
-                             ON CONFLICT(payment_id) DO UPDATE SET
+                        "INSERT INTO payment_details_spark (tenant_id, payment_id, invoice_details, htlc_details)
+                             VALUES ($1, $2, $3, $4)
+                             ON CONFLICT(tenant_id, payment_id) DO UPDATE SET
                                 invoice_details = COALESCE(EXCLUDED.invoice_details, payment_details_spark.invoice_details),
                                 htlc_details = COALESCE(EXCLUDED.htlc_details, payment_details_spark.htlc_details)",
-                        &[&payment.id, &invoice_json, &htlc_json],
+                        &[&self.tenant_id, &payment.id, &invoice_json, &htlc_json],
                     )
                     .await?;
                 }
@@ -1075,14 +1250,14 @@ impl Storage for PostgresStorage {
                     .map_err(|e| StorageError::Serialization(e.to_string()))?;
                 let invoice_json = to_json_opt(invoice_details.as_ref())?;
                 tx.execute(
-                    "INSERT INTO payment_details_token (payment_id, metadata, tx_hash, tx_type, invoice_details)
-                         VALUES ($1, $2, $3, $4, $5)
-                         ON CONFLICT(payment_id) DO UPDATE SET
+                    "INSERT INTO payment_details_token (tenant_id, payment_id, metadata, tx_hash, tx_type, invoice_details)
+                         VALUES ($1, $2, $3, $4, $5, $6)
+                         ON CONFLICT(tenant_id, payment_id) DO UPDATE SET
                             metadata = EXCLUDED.metadata,
                             tx_hash = EXCLUDED.tx_hash,
                             tx_type = EXCLUDED.tx_type,
                             invoice_details = COALESCE(EXCLUDED.invoice_details, payment_details_token.invoice_details)",
-                    &[&payment.id, &metadata_json, &tx_hash, &tx_type.to_string(), &invoice_json],
+                    &[&self.tenant_id, &payment.id, &metadata_json, &tx_hash, &tx_type.to_string(), &invoice_json],
                 )
                 .await?;
             }
@@ -1098,9 +1273,9 @@ impl Storage for PostgresStorage {
                 let htlc_status = htlc_details.status.to_string();
                 let htlc_expiry_time = i64::try_from(htlc_details.expiry_time)?;
                 tx.execute(
-                    "INSERT INTO payment_details_lightning (payment_id, invoice, payment_hash, destination_pubkey, description, preimage, htlc_status, htlc_expiry_time)
-                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-                         ON CONFLICT(payment_id) DO UPDATE SET
+                    "INSERT INTO payment_details_lightning (tenant_id, payment_id, invoice, payment_hash, destination_pubkey, description, preimage, htlc_status, htlc_expiry_time)
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                         ON CONFLICT(tenant_id, payment_id) DO UPDATE SET
                             invoice = EXCLUDED.invoice,
                             payment_hash = EXCLUDED.payment_hash,
                             destination_pubkey = EXCLUDED.destination_pubkey,
@@ -1108,7 +1283,7 @@ impl Storage for PostgresStorage {
                             preimage = COALESCE(EXCLUDED.preimage, payment_details_lightning.preimage),
                             htlc_status = COALESCE(EXCLUDED.htlc_status, payment_details_lightning.htlc_status),
                             htlc_expiry_time = COALESCE(EXCLUDED.htlc_expiry_time, payment_details_lightning.htlc_expiry_time)",
-                    &[&payment.id, &invoice, payment_hash, &destination_pubkey, &description, preimage, &htlc_status, &htlc_expiry_time],
+                    &[&self.tenant_id, &payment.id, &invoice, payment_hash, &destination_pubkey, &description, preimage, &htlc_status, &htlc_expiry_time],
                 )
                 .await?;
             }
@@ -1134,15 +1309,16 @@ impl Storage for PostgresStorage {
 
         client
             .execute(
-                "INSERT INTO payment_metadata (payment_id, parent_payment_id, lnurl_pay_info, lnurl_withdraw_info, lnurl_description, conversion_info)
-                 VALUES ($1, $2, $3, $4, $5, $6)
-                 ON CONFLICT(payment_id) DO UPDATE SET
+                "INSERT INTO payment_metadata (tenant_id, payment_id, parent_payment_id, lnurl_pay_info, lnurl_withdraw_info, lnurl_description, conversion_info)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT(tenant_id, payment_id) DO UPDATE SET
                     parent_payment_id = COALESCE(EXCLUDED.parent_payment_id, payment_metadata.parent_payment_id),
                     lnurl_pay_info = COALESCE(EXCLUDED.lnurl_pay_info, payment_metadata.lnurl_pay_info),
                     lnurl_withdraw_info = COALESCE(EXCLUDED.lnurl_withdraw_info, payment_metadata.lnurl_withdraw_info),
                     lnurl_description = COALESCE(EXCLUDED.lnurl_description, payment_metadata.lnurl_description),
                     conversion_info = COALESCE(EXCLUDED.conversion_info, payment_metadata.conversion_info)",
                 &[
+                    &self.tenant_id,
                     &payment_id,
                     &metadata.parent_payment_id,
                     &lnurl_pay_info_json,
@@ -1156,14 +1332,43 @@ impl Storage for PostgresStorage {
         Ok(())
     }
 
+    async fn list_payment_metadata(
+        &self,
+    ) -> Result<HashMap<String, PaymentMetadata>, StorageError> {
+        let client = self.pool.get().await.map_err(map_pool_error)?;
+
+        let rows = client
+            .query(
+                "SELECT payment_id, parent_payment_id, lnurl_pay_info, lnurl_withdraw_info, lnurl_description, conversion_info
+                 FROM payment_metadata WHERE tenant_id = $1",
+                &[&self.tenant_id],
+            )
+            .await?;
+
+        let mut metadata = HashMap::new();
+        for row in rows {
+            metadata.insert(
+                row.get(0),
+                PaymentMetadata {
+                    parent_payment_id: row.get(1),
+                    lnurl_pay_info: from_json_opt(row.get(2))?,
+                    lnurl_withdraw_info: from_json_opt(row.get(3))?,
+                    lnurl_description: row.get(4),
+                    conversion_info: from_json_opt(row.get(5))?,
+                },
+            );
+        }
+        Ok(metadata)
+    }
+
     async fn set_cached_item(&self, key: String, value: String) -> Result<(), StorageError> {
         let client = self.pool.get().await.map_err(map_pool_error)?;
 
         client
             .execute(
-                "INSERT INTO settings (key, value) VALUES ($1, $2)
-                 ON CONFLICT(key) DO UPDATE SET value = EXCLUDED.value",
-                &[&key, &value],
+                "INSERT INTO settings (tenant_id, key, value) VALUES ($1, $2, $3)
+                 ON CONFLICT(tenant_id, key) DO UPDATE SET value = EXCLUDED.value",
+                &[&self.tenant_id, &key, &value],
             )
             .await?;
 
@@ -1174,7 +1379,10 @@ impl Storage for PostgresStorage {
         let client = self.pool.get().await.map_err(map_pool_error)?;
 
         let row = client
-            .query_opt("SELECT value FROM settings WHERE key = $1", &[&key])
+            .query_opt(
+                "SELECT value FROM settings WHERE tenant_id = $1 AND key = $2",
+                &[&self.tenant_id, &key],
+            )
             .await?;
 
         Ok(row.map(|r| r.get(0)))
@@ -1184,17 +1392,33 @@ impl Storage for PostgresStorage {
         let client = self.pool.get().await.map_err(map_pool_error)?;
 
         client
-            .execute("DELETE FROM settings WHERE key = $1", &[&key])
+            .execute(
+                "DELETE FROM settings WHERE tenant_id = $1 AND key = $2",
+                &[&self.tenant_id, &key],
+            )
             .await?;
 
         Ok(())
     }
 
+    async fn list_cached_items(&self) -> Result<HashMap<String, String>, StorageError> {
+        let client = self.pool.get().await.map_err(map_pool_error)?;
+
+        let rows = client
+            .query(
+                "SELECT key, value FROM settings WHERE tenant_id = $1",
+                &[&self.tenant_id],
+            )
+            .await?;
+
+        Ok(rows.into_iter().map(|r| (r.get(0), r.get(1))).collect())
+    }
+
     async fn get_payment_by_id(&self, id: String) -> Result<Payment, StorageError> {
         let client = self.pool.get().await.map_err(map_pool_error)?;
-        let query = format!("{SELECT_PAYMENT_SQL} WHERE p.id = $1");
+        let query = format!("{SELECT_PAYMENT_SQL} WHERE p.tenant_id = $1 AND p.id = $2");
         let row = client
-            .query_one(&query, &[&id])
+            .query_one(&query, &[&self.tenant_id, &id])
             .await
             .map_err(map_db_error)?;
         map_payment(&row)
@@ -1205,8 +1429,10 @@ impl Storage for PostgresStorage {
         invoice: String,
     ) -> Result<Option<Payment>, StorageError> {
         let client = self.pool.get().await.map_err(map_pool_error)?;
-        let query = format!("{SELECT_PAYMENT_SQL} WHERE l.invoice = $1");
-        let row = client.query_opt(&query, &[&invoice]).await?;
+        let query = format!("{SELECT_PAYMENT_SQL} WHERE p.tenant_id = $1 AND l.invoice = $2");
+        let row = client
+            .query_opt(&query, &[&self.tenant_id, &invoice])
+            .await?;
 
         match row {
             Some(r) => Ok(Some(map_payment(&r)?)),
@@ -1228,8 +1454,8 @@ impl Storage for PostgresStorage {
         // Early exit if no related payments exist
         let has_related: bool = client
             .query_one(
-                "SELECT EXISTS(SELECT 1 FROM payment_metadata WHERE parent_payment_id IS NOT NULL LIMIT 1)",
-                &[],
+                "SELECT EXISTS(SELECT 1 FROM payment_metadata WHERE tenant_id = $1 AND parent_payment_id IS NOT NULL LIMIT 1)",
+                &[&self.tenant_id],
             )
             .await
             .map(|row| row.get(0))
@@ -1243,16 +1469,16 @@ impl Storage for PostgresStorage {
         let placeholders: Vec<String> = parent_payment_ids
             .iter()
             .enumerate()
-            .map(|(i, _)| format!("${}", i + 1))
+            .map(|(i, _)| format!("${}", i + 2))
             .collect();
         let in_clause = placeholders.join(", ");
 
         let query = format!(
-            "{SELECT_PAYMENT_SQL} WHERE pm.parent_payment_id IN ({in_clause}) ORDER BY p.timestamp ASC"
+            "{SELECT_PAYMENT_SQL} WHERE p.tenant_id = $1 AND pm.parent_payment_id IN ({in_clause}) ORDER BY p.timestamp ASC"
         );
 
-        let params: Vec<&(dyn ToSql + Sync)> = parent_payment_ids
-            .iter()
+        let params: Vec<&(dyn ToSql + Sync)> = std::iter::once(&self.tenant_id)
+            .chain(parent_payment_ids.iter())
             .map(|id| id as &(dyn ToSql + Sync))
             .collect();
 
@@ -1277,10 +1503,15 @@ impl Storage for PostgresStorage {
         let client = self.pool.get().await.map_err(map_pool_error)?;
         client
             .execute(
-                "INSERT INTO unclaimed_deposits (txid, vout, amount_sats)
-                 VALUES ($1, $2, $3)
-                 ON CONFLICT(txid, vout) DO NOTHING",
-                &[&txid, &i32::try_from(vout)?, &i64::try_from(amount_sats)?],
+                "INSERT INTO unclaimed_deposits (tenant_id, txid, vout, amount_sats)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT(tenant_id, txid, vout) DO NOTHING",
+                &[
+                    &self.tenant_id,
+                    &txid,
+                    &i32::try_from(vout)?,
+                    &i64::try_from(amount_sats)?,
+                ],
             )
             .await?;
         Ok(())
@@ -1290,8 +1521,8 @@ impl Storage for PostgresStorage {
         let client = self.pool.get().await.map_err(map_pool_error)?;
         client
             .execute(
-                "DELETE FROM unclaimed_deposits WHERE txid = $1 AND vout = $2",
-                &[&txid, &i32::try_from(vout)?],
+                "DELETE FROM unclaimed_deposits WHERE tenant_id = $1 AND txid = $2 AND vout = $3",
+                &[&self.tenant_id, &txid, &i32::try_from(vout)?],
             )
             .await?;
         Ok(())
@@ -1301,8 +1532,8 @@ impl Storage for PostgresStorage {
         let client = self.pool.get().await.map_err(map_pool_error)?;
         let rows = client
             .query(
-                "SELECT txid, vout, amount_sats, claim_error, refund_tx, refund_tx_id FROM unclaimed_deposits",
-                &[],
+                "SELECT txid, vout, amount_sats, claim_error, refund_tx, refund_tx_id FROM unclaimed_deposits WHERE tenant_id = $1",
+                &[&self.tenant_id],
             )
             .await?;
 
@@ -1340,8 +1571,8 @@ impl Storage for PostgresStorage {
                     .map_err(|e| StorageError::Serialization(e.to_string()))?;
                 client
                     .execute(
-                        "UPDATE unclaimed_deposits SET claim_error = $1, refund_tx = NULL, refund_tx_id = NULL WHERE txid = $2 AND vout = $3",
-                        &[&error_json, &txid, &i32::try_from(vout)?],
+                        "UPDATE unclaimed_deposits SET claim_error = $1, refund_tx = NULL, refund_tx_id = NULL WHERE tenant_id = $2 AND txid = $3 AND vout = $4",
+                        &[&error_json, &self.tenant_id, &txid, &i32::try_from(vout)?],
                     )
                     .await?;
             }
@@ -1351,8 +1582,8 @@ impl Storage for PostgresStorage {
             } => {
                 client
                     .execute(
-                        "UPDATE unclaimed_deposits SET refund_tx = $1, refund_tx_id = $2, claim_error = NULL WHERE txid = $3 AND vout = $4",
-                        &[&refund_tx, &refund_txid, &txid, &i32::try_from(vout)?],
+                        "UPDATE unclaimed_deposits SET refund_tx = $1, refund_tx_id = $2, claim_error = NULL WHERE tenant_id = $3 AND txid = $4 AND vout = $5",
+                        &[&refund_tx, &refund_txid, &self.tenant_id, &txid, &i32::try_from(vout)?],
                     )
                     .await?;
             }
@@ -1368,20 +1599,44 @@ impl Storage for PostgresStorage {
         for m in metadata {
             client
                 .execute(
-                    "INSERT INTO lnurl_receive_metadata (payment_hash, nostr_zap_request, nostr_zap_receipt, sender_comment, preimage)
-                     VALUES ($1, $2, $3, $4, $5)
-                     ON CONFLICT(payment_hash) DO UPDATE SET
+                    "INSERT INTO lnurl_receive_metadata (tenant_id, payment_hash, nostr_zap_request, nostr_zap_receipt, sender_comment, preimage, payer_data)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7)
+                     ON CONFLICT(tenant_id, payment_hash) DO UPDATE SET
                         nostr_zap_request = EXCLUDED.nostr_zap_request,
                         nostr_zap_receipt = EXCLUDED.nostr_zap_receipt,
                         sender_comment = EXCLUDED.sender_comment,
-                        preimage = EXCLUDED.preimage",
-                    &[&m.payment_hash, &m.nostr_zap_request, &m.nostr_zap_receipt, &m.sender_comment, &m.preimage],
+                        preimage = EXCLUDED.preimage,
+                        payer_data = EXCLUDED.payer_data",
+                    &[&self.tenant_id, &m.payment_hash, &m.nostr_zap_request, &m.nostr_zap_receipt, &m.sender_comment, &m.preimage, &m.payer_data],
                 )
                 .await?;
         }
         Ok(())
     }
 
+    async fn list_lnurl_metadata(&self) -> Result<Vec<SetLnurlMetadataItem>, StorageError> {
+        let client = self.pool.get().await.map_err(map_pool_error)?;
+        let rows = client
+            .query(
+                "SELECT payment_hash, sender_comment, nostr_zap_request, nostr_zap_receipt, preimage, payer_data
+                 FROM lnurl_receive_metadata WHERE tenant_id = $1",
+                &[&self.tenant_id],
+            )
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| SetLnurlMetadataItem {
+                payment_hash: row.get(0),
+                sender_comment: row.get(1),
+                nostr_zap_request: row.get(2),
+                nostr_zap_receipt: row.get(3),
+                preimage: row.get(4),
+                payer_data: row.get(5),
+            })
+            .collect())
+    }
+
     async fn add_outgoing_change(
         &self,
         record: UnversionedRecordChange,
@@ -1396,8 +1651,8 @@ impl Storage for PostgresStorage {
         // This revision is a local queue id for pending rows, not a server revision.
         let local_revision: i64 = tx
             .query_one(
-                "SELECT COALESCE(MAX(revision), 0) + 1 FROM sync_outgoing",
-                &[],
+                "SELECT COALESCE(MAX(revision), 0) + 1 FROM sync_outgoing WHERE tenant_id = $1",
+                &[&self.tenant_id],
             )
             .await
             .map_err(|e| StorageError::Connection(e.to_string()))?
@@ -1408,9 +1663,10 @@ impl Storage for PostgresStorage {
         let commit_time = chrono::Utc::now().timestamp();
 
         tx.execute(
-            "INSERT INTO sync_outgoing (record_type, data_id, schema_version, commit_time, updated_fields_json, revision)
-                 VALUES ($1, $2, $3, $4, $5, $6)",
+            "INSERT INTO sync_outgoing (tenant_id, record_type, data_id, schema_version, commit_time, updated_fields_json, revision)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
             &[
+                &self.tenant_id,
                 &record.id.r#type,
                 &record.id.data_id,
                 &record.schema_version,
@@ -1443,8 +1699,9 @@ impl Storage for PostgresStorage {
 
         let rows_deleted = tx
             .execute(
-                "DELETE FROM sync_outgoing WHERE record_type = $1 AND data_id = $2 AND revision = $3",
+                "DELETE FROM sync_outgoing WHERE tenant_id = $1 AND record_type = $2 AND data_id = $3 AND revision = $4",
                 &[
+                    &self.tenant_id,
                     &record.id.r#type,
                     &record.id.data_id,
                     &i64::try_from(local_revision)?,
@@ -1466,14 +1723,15 @@ impl Storage for PostgresStorage {
         let commit_time = chrono::Utc::now().timestamp();
 
         tx.execute(
-            "INSERT INTO sync_state (record_type, data_id, schema_version, commit_time, data, revision)
-                 VALUES ($1, $2, $3, $4, $5, $6)
-                 ON CONFLICT(record_type, data_id) DO UPDATE SET
+            "INSERT INTO sync_state (tenant_id, record_type, data_id, schema_version, commit_time, data, revision)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT(tenant_id, record_type, data_id) DO UPDATE SET
                     schema_version = EXCLUDED.schema_version,
                     commit_time = EXCLUDED.commit_time,
                     data = EXCLUDED.data,
                     revision = EXCLUDED.revision",
             &[
+                &self.tenant_id,
                 &record.id.r#type,
                 &record.id.data_id,
                 &record.schema_version,
@@ -1486,8 +1744,10 @@ impl Storage for PostgresStorage {
         .map_err(|e| StorageError::Connection(e.to_string()))?;
 
         tx.execute(
-            "UPDATE sync_revision SET revision = GREATEST(revision, $1)",
-            &[&i64::try_from(record.revision)?],
+            "INSERT INTO sync_revision (tenant_id, revision) VALUES ($1, $2)
+                 ON CONFLICT(tenant_id) DO UPDATE SET
+                    revision = GREATEST(sync_revision.revision, EXCLUDED.revision)",
+            &[&self.tenant_id, &i64::try_from(record.revision)?],
         )
         .await
         .map_err(|e| StorageError::Connection(e.to_string()))?;
@@ -1510,10 +1770,11 @@ impl Storage for PostgresStorage {
                 "SELECT o.record_type, o.data_id, o.schema_version, o.commit_time, o.updated_fields_json, o.revision,
                         e.schema_version AS existing_schema_version, e.commit_time AS existing_commit_time, e.data AS existing_data, e.revision AS existing_revision
                  FROM sync_outgoing o
-                 LEFT JOIN sync_state e ON o.record_type = e.record_type AND o.data_id = e.data_id
+                 LEFT JOIN sync_state e ON o.tenant_id = e.tenant_id AND o.record_type = e.record_type AND o.data_id = e.data_id
+                 WHERE o.tenant_id = $1
                  ORDER BY o.revision ASC
-                 LIMIT $1",
-                &[&i64::from(limit)],
+                 LIMIT $2",
+                &[&self.tenant_id, &i64::from(limit)],
             )
             .await
             .map_err(|e| StorageError::Connection(e.to_string()))?;
@@ -1547,11 +1808,15 @@ impl Storage for PostgresStorage {
     async fn get_last_revision(&self) -> Result<u64, StorageError> {
         let client = self.pool.get().await.map_err(map_pool_error)?;
 
+        // Tenants without a row haven't synced any record yet
         let revision: i64 = client
-            .query_one("SELECT revision FROM sync_revision", &[])
+            .query_opt(
+                "SELECT revision FROM sync_revision WHERE tenant_id = $1",
+                &[&self.tenant_id],
+            )
             .await
             .map_err(|e| StorageError::Connection(e.to_string()))?
-            .get(0);
+            .map_or(0, |row| row.get(0));
 
         Ok(u64::try_from(revision)?)
     }
@@ -1569,13 +1834,14 @@ impl Storage for PostgresStorage {
                 .map_err(|e| StorageError::Serialization(e.to_string()))?;
             client
                 .execute(
-                    "INSERT INTO sync_incoming (record_type, data_id, schema_version, commit_time, data, revision)
-                     VALUES ($1, $2, $3, $4, $5, $6)
-                     ON CONFLICT(record_type, data_id, revision) DO UPDATE SET
+                    "INSERT INTO sync_incoming (tenant_id, record_type, data_id, schema_version, commit_time, data, revision)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7)
+                     ON CONFLICT(tenant_id, record_type, data_id, revision) DO UPDATE SET
                         schema_version = EXCLUDED.schema_version,
                         commit_time = EXCLUDED.commit_time,
                         data = EXCLUDED.data",
                     &[
+                        &self.tenant_id,
                         &record.id.r#type,
                         &record.id.data_id,
                         &record.schema_version,
@@ -1596,8 +1862,9 @@ impl Storage for PostgresStorage {
 
         client
             .execute(
-                "DELETE FROM sync_incoming WHERE record_type = $1 AND data_id = $2 AND revision = $3",
+                "DELETE FROM sync_incoming WHERE tenant_id = $1 AND record_type = $2 AND data_id = $3 AND revision = $4",
                 &[
+                    &self.tenant_id,
                     &record.id.r#type,
                     &record.id.data_id,
                     &i64::try_from(record.revision)?,
@@ -1617,10 +1884,11 @@ impl Storage for PostgresStorage {
                 "SELECT i.record_type, i.data_id, i.schema_version, i.data, i.revision,
                         e.schema_version AS existing_schema_version, e.commit_time AS existing_commit_time, e.data AS existing_data, e.revision AS existing_revision
                  FROM sync_incoming i
-                 LEFT JOIN sync_state e ON i.record_type = e.record_type AND i.data_id = e.data_id
+                 LEFT JOIN sync_state e ON i.tenant_id = e.tenant_id AND i.record_type = e.record_type AND i.data_id = e.data_id
+                 WHERE i.tenant_id = $1
                  ORDER BY i.revision ASC
-                 LIMIT $1",
-                &[&i64::from(limit)],
+                 LIMIT $2",
+                &[&self.tenant_id, &i64::from(limit)],
             )
             .await
             .map_err(|e| StorageError::Connection(e.to_string()))?;
@@ -1663,10 +1931,11 @@ impl Storage for PostgresStorage {
                 "SELECT o.record_type, o.data_id, o.schema_version, o.commit_time, o.updated_fields_json, o.revision,
                         e.schema_version AS existing_schema_version, e.commit_time AS existing_commit_time, e.data AS existing_data, e.revision AS existing_revision
                  FROM sync_outgoing o
-                 LEFT JOIN sync_state e ON o.record_type = e.record_type AND o.data_id = e.data_id
+                 LEFT JOIN sync_state e ON o.tenant_id = e.tenant_id AND o.record_type = e.record_type AND o.data_id = e.data_id
+                 WHERE o.tenant_id = $1
                  ORDER BY o.revision DESC
                  LIMIT 1",
-                &[],
+                &[&self.tenant_id],
             )
             .await
             .map_err(|e| StorageError::Connection(e.to_string()))?;
@@ -1709,14 +1978,15 @@ impl Storage for PostgresStorage {
         let commit_time = chrono::Utc::now().timestamp();
 
         tx.execute(
-            "INSERT INTO sync_state (record_type, data_id, schema_version, commit_time, data, revision)
-                 VALUES ($1, $2, $3, $4, $5, $6)
-                 ON CONFLICT(record_type, data_id) DO UPDATE SET
+            "INSERT INTO sync_state (tenant_id, record_type, data_id, schema_version, commit_time, data, revision)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT(tenant_id, record_type, data_id) DO UPDATE SET
                     schema_version = EXCLUDED.schema_version,
                     commit_time = EXCLUDED.commit_time,
                     data = EXCLUDED.data,
                     revision = EXCLUDED.revision",
             &[
+                &self.tenant_id,
                 &record.id.r#type,
                 &record.id.data_id,
                 &record.schema_version,
@@ -1729,8 +1999,10 @@ impl Storage for PostgresStorage {
         .map_err(|e| StorageError::Connection(e.to_string()))?;
 
         tx.execute(
-            "UPDATE sync_revision SET revision = GREATEST(revision, $1)",
-            &[&i64::try_from(record.revision)?],
+            "INSERT INTO sync_revision (tenant_id, revision) VALUES ($1, $2)
+                 ON CONFLICT(tenant_id) DO UPDATE SET
+                    revision = GREATEST(sync_revision.revision, EXCLUDED.revision)",
+            &[&self.tenant_id, &i64::try_from(record.revision)?],
         )
         .await
         .map_err(|e| StorageError::Connection(e.to_string()))?;
@@ -1741,6 +2013,34 @@ impl Storage for PostgresStorage {
 
         Ok(())
     }
+
+    async fn get_sync_state_records(&self) -> Result<Vec<Record>, StorageError> {
+        let client = self.pool.get().await.map_err(map_pool_error)?;
+
+        let rows = client
+            .query(
+                "SELECT record_type, data_id, schema_version, data, revision
+                 FROM sync_state
+                 WHERE tenant_id = $1
+                 ORDER BY revision ASC",
+                &[&self.tenant_id],
+            )
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(Record {
+                id: RecordId::new(row.get(0), row.get(1)),
+                schema_version: row.get(2),
+                data: serde_json::from_value(row.get::<_, serde_json::Value>(3))
+                    .map_err(|e| StorageError::Serialization(e.to_string()))?,
+                revision: u64::try_from(row.get::<_, i64>(4))?,
+            });
+        }
+
+        Ok(results)
+    }
 }
 
 /// Base query for payment lookups.
@@ -1776,13 +2076,14 @@ const SELECT_PAYMENT_SQL: &str = "
            lrm.nostr_zap_receipt AS lnurl_nostr_zap_receipt,
            lrm.sender_comment AS lnurl_sender_comment,
            lrm.payment_hash AS lnurl_payment_hash,
-           pm.parent_payment_id
+           pm.parent_payment_id,
+           lrm.payer_data AS lnurl_payer_data
       FROM payments p
-      LEFT JOIN payment_details_lightning l ON p.id = l.payment_id
-      LEFT JOIN payment_details_token t ON p.id = t.payment_id
-      LEFT JOIN payment_details_spark s ON p.id = s.payment_id
-      LEFT JOIN payment_metadata pm ON p.id = pm.payment_id
-      LEFT JOIN lnurl_receive_metadata lrm ON l.payment_hash = lrm.payment_hash";
+      LEFT JOIN payment_details_lightning l ON p.tenant_id = l.tenant_id AND p.id = l.payment_id
+      LEFT JOIN payment_details_token t ON p.tenant_id = t.tenant_id AND p.id = t.payment_id
+      LEFT JOIN payment_details_spark s ON p.tenant_id = s.tenant_id AND p.id = s.payment_id
+      LEFT JOIN payment_metadata pm ON p.tenant_id = pm.tenant_id AND p.id = pm.payment_id
+      LEFT JOIN lnurl_receive_metadata lrm ON l.tenant_id = lrm.tenant_id AND l.payment_hash = lrm.payment_hash";
 
 #[allow(clippy::too_many_lines)]
 fn map_payment(row: &Row) -> Result<Payment, StorageError> {
@@ -1828,6 +2129,7 @@ fn map_payment(row: &Row) -> Result<Payment, StorageError> {
             let lnurl_nostr_zap_receipt: Option<String> = row.get(27);
             let lnurl_sender_comment: Option<String> = row.get(28);
             let lnurl_payment_hash: Option<String> = row.get(29);
+            let lnurl_payer_data: Option<String> = row.get(31);
 
             let lnurl_pay_info: Option<LnurlPayInfo> = from_json_opt(lnurl_pay_info_json)?;
             let lnurl_withdraw_info: Option<LnurlWithdrawInfo> =
@@ -1838,6 +2140,8 @@ fn map_payment(row: &Row) -> Result<Payment, StorageError> {
                     nostr_zap_request: lnurl_nostr_zap_request,
                     nostr_zap_receipt: lnurl_nostr_zap_receipt,
                     sender_comment: lnurl_sender_comment,
+                    payer_data: lnurl_payer_data
+                        .and_then(|payer_data| serde_json::from_str(&payer_data).ok()),
                 })
             } else {
                 None
@@ -2029,6 +2333,12 @@ mod tests {
             .await;
     }
 
+    #[tokio::test]
+    async fn test_conversion_filtering() {
+        let fixture = PostgresTestFixture::new().await;
+        crate::persist::tests::test_conversion_filtering(Box::new(fixture.storage)).await;
+    }
+
     #[tokio::test]
     async fn test_token_transaction_type_filtering() {
         let fixture = PostgresTestFixture::new().await;
@@ -2079,6 +2389,241 @@ mod tests {
         crate::persist::tests::test_sync_storage(Box::new(fixture.storage)).await;
     }
 
+    #[tokio::test]
+    async fn test_migrate_from_sqlite() {
+        let fixture = PostgresTestFixture::new().await;
+        let mut source_dir = std::env::temp_dir();
+        source_dir.push(format!("breez-test-migrate-{}", uuid::Uuid::new_v4()));
+        let source = crate::SqliteStorage::new(&source_dir).unwrap();
+
+        crate::persist::tests::test_migrate_storage(Box::new(source), Box::new(fixture.storage))
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_migrate_to_sqlite() {
+        let fixture = PostgresTestFixture::new().await;
+        let mut target_dir = std::env::temp_dir();
+        target_dir.push(format!("breez-test-migrate-{}", uuid::Uuid::new_v4()));
+        let target = crate::SqliteStorage::new(&target_dir).unwrap();
+
+        crate::persist::tests::test_resume_migrate_storage(
+            Box::new(fixture.storage),
+            Box::new(target),
+        )
+        .await;
+    }
+
+    fn test_identity_pub_key(secret: u8) -> PublicKey {
+        let secret_key = bitcoin::secp256k1::SecretKey::from_slice(&[secret; 32]).unwrap();
+        PublicKey::from_secret_key(&bitcoin::secp256k1::Secp256k1::new(), &secret_key)
+    }
+
+    #[tokio::test]
+    async fn test_postgres_tenant_storage() {
+        let fixture = PostgresTestFixture::new().await;
+        let storage = fixture
+            .storage
+            .for_tenant(&test_identity_pub_key(1))
+            .await
+            .unwrap();
+        Box::pin(crate::persist::tests::test_storage(Box::new(storage))).await;
+    }
+
+    #[tokio::test]
+    async fn test_postgres_tenant_sync_storage() {
+        let fixture = PostgresTestFixture::new().await;
+        let storage = fixture
+            .storage
+            .for_tenant(&test_identity_pub_key(1))
+            .await
+            .unwrap();
+        crate::persist::tests::test_sync_storage(Box::new(storage)).await;
+    }
+
+    #[tokio::test]
+    #[allow(clippy::too_many_lines)]
+    async fn test_tenant_isolation() {
+        use crate::{PaymentStatus, PaymentType};
+
+        let fixture = PostgresTestFixture::new().await;
+        let pub_key_a = test_identity_pub_key(1);
+        let pub_key_b = test_identity_pub_key(2);
+        let tenant_a = fixture.storage.for_tenant(&pub_key_a).await.unwrap();
+        let tenant_b = fixture.storage.for_tenant(&pub_key_b).await.unwrap();
+
+        // The same payment id is stored separately for each tenant
+        for (storage, amount) in [(&tenant_a, 1000), (&tenant_b, 2000)] {
+            storage
+                .insert_payment(Payment {
+                    id: "payment".to_string(),
+                    payment_type: PaymentType::Receive,
+                    status: PaymentStatus::Completed,
+                    amount,
+                    fees: 0,
+                    timestamp: 1000,
+                    method: PaymentMethod::Lightning,
+                    details: Some(PaymentDetails::Lightning {
+                        invoice: "lnbc1".to_string(),
+                        destination_pubkey: "pubkey".to_string(),
+                        description: None,
+                        htlc_details: SparkHtlcDetails {
+                            payment_hash: "hash".to_string(),
+                            preimage: None,
+                            expiry_time: 0,
+                            status: SparkHtlcStatus::PreimageShared,
+                        },
+                        lnurl_pay_info: None,
+                        lnurl_withdraw_info: None,
+                        lnurl_receive_metadata: None,
+                    }),
+                    conversion_details: None,
+                })
+                .await
+                .unwrap();
+            storage
+                .set_cached_item("key".to_string(), amount.to_string())
+                .await
+                .unwrap();
+            storage
+                .add_deposit("txid".to_string(), 0, u64::try_from(amount).unwrap())
+                .await
+                .unwrap();
+        }
+        tenant_a
+            .set_lnurl_metadata(vec![SetLnurlMetadataItem {
+                payment_hash: "hash".to_string(),
+                sender_comment: Some("comment".to_string()),
+                nostr_zap_request: None,
+                nostr_zap_receipt: None,
+                preimage: None,
+                payer_data: None,
+            }])
+            .await
+            .unwrap();
+
+        let payment_a = tenant_a
+            .get_payment_by_id("payment".to_string())
+            .await
+            .unwrap();
+        let payment_b = tenant_b
+            .get_payment_by_id("payment".to_string())
+            .await
+            .unwrap();
+        assert_eq!(payment_a.amount, 1000);
+        assert_eq!(payment_b.amount, 2000);
+        match (&payment_a.details, &payment_b.details) {
+            (
+                Some(PaymentDetails::Lightning {
+                    lnurl_receive_metadata: metadata_a,
+                    ..
+                }),
+                Some(PaymentDetails::Lightning {
+                    lnurl_receive_metadata: metadata_b,
+                    ..
+                }),
+            ) => {
+                assert!(metadata_a.is_some());
+                assert!(metadata_b.is_none());
+            }
+            _ => panic!("Expected Lightning payment details"),
+        }
+        assert_eq!(
+            tenant_b
+                .list_payments(StorageListPaymentsRequest::default())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            tenant_a.get_cached_item("key".to_string()).await.unwrap(),
+            Some("1000".to_string())
+        );
+        assert_eq!(
+            fixture
+                .storage
+                .get_cached_item("key".to_string())
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(tenant_b.list_deposits().await.unwrap()[0].amount_sats, 2000);
+
+        // Sync revisions are tracked per tenant
+        let record = Record {
+            id: RecordId::new("type".to_string(), "id".to_string()),
+            revision: 5,
+            schema_version: "1.0.0".to_string(),
+            data: HashMap::new(),
+        };
+        tenant_a
+            .update_record_from_incoming(record.clone())
+            .await
+            .unwrap();
+        tenant_b
+            .add_outgoing_change(UnversionedRecordChange {
+                id: record.id.clone(),
+                schema_version: record.schema_version.clone(),
+                updated_fields: HashMap::new(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(tenant_a.get_last_revision().await.unwrap(), 5);
+        assert_eq!(tenant_b.get_last_revision().await.unwrap(), 0);
+        assert!(
+            tenant_a
+                .get_pending_outgoing_changes(10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let pending_b = tenant_b.get_pending_outgoing_changes(10).await.unwrap();
+        assert_eq!(pending_b.len(), 1);
+        assert!(pending_b[0].parent.is_none());
+
+        let mut tenants = fixture.storage.list_tenants().await.unwrap();
+        tenants.sort();
+        let mut expected = vec![pub_key_a, pub_key_b];
+        expected.sort();
+        assert_eq!(tenants, expected);
+
+        // Deleting a tenant leaves the other tenants untouched
+        fixture.storage.delete_tenant(&pub_key_a).await.unwrap();
+        assert_eq!(
+            fixture.storage.list_tenants().await.unwrap(),
+            vec![pub_key_b]
+        );
+        assert!(
+            tenant_a
+                .get_payment_by_id("payment".to_string())
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            tenant_a.get_cached_item("key".to_string()).await.unwrap(),
+            None
+        );
+        assert_eq!(tenant_a.get_last_revision().await.unwrap(), 0);
+        assert!(tenant_a.list_deposits().await.unwrap().is_empty());
+        assert_eq!(
+            tenant_b
+                .get_payment_by_id("payment".to_string())
+                .await
+                .unwrap()
+                .amount,
+            2000
+        );
+        assert_eq!(
+            tenant_b
+                .get_pending_outgoing_changes(10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
     /// Generates a self-signed CA certificate in PEM format for testing.
     fn generate_test_ca_pem(common_name: &str) -> String {
         let mut params = rcgen::CertificateParams::new(vec![]).expect("valid params");
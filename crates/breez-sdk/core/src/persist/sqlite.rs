@@ -28,9 +28,20 @@ use tracing::warn;
 use super::{Payment, Storage, StorageError};
 
 const DEFAULT_DB_FILENAME: &str = "storage.sql";
+/// Temporary file a plaintext database is exported to, before it replaces the original file
+#[cfg(feature = "sqlcipher")]
+const ENCRYPTED_EXPORT_DB_FILENAME: &str = "storage.sql.encrypted";
+
+/// A 256-bit key encrypting the `SQLite` database file.
+#[cfg(feature = "sqlcipher")]
+pub type SqliteEncryptionKey = [u8; 32];
+
 /// SQLite-based storage implementation
 pub struct SqliteStorage {
     db_dir: PathBuf,
+    /// Key the database file is encrypted with using `SQLCipher`, if encryption is enabled
+    #[cfg(feature = "sqlcipher")]
+    encryption_key: Option<SqliteEncryptionKey>,
 }
 
 impl SqliteStorage {
@@ -46,6 +57,8 @@ impl SqliteStorage {
     pub fn new(path: &Path) -> Result<Self, StorageError> {
         let storage = Self {
             db_dir: path.to_path_buf(),
+            #[cfg(feature = "sqlcipher")]
+            encryption_key: None,
         };
 
         #[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
@@ -56,8 +69,102 @@ impl SqliteStorage {
         Ok(storage)
     }
 
+    /// Creates a new `SQLite` storage encrypted at rest with `SQLCipher`
+    ///
+    /// An existing plaintext database is encrypted in place. An existing database encrypted with
+    /// one of `previous_keys` is re-encrypted with `key`, which allows rotating the key.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the `SQLite` database file
+    /// * `key` - Key to encrypt the database with
+    /// * `previous_keys` - Keys the database may still be encrypted with
+    ///
+    /// # Returns
+    ///
+    /// A new `SqliteStorage` instance or an error
+    #[cfg(feature = "sqlcipher")]
+    pub fn new_encrypted(
+        path: &Path,
+        key: SqliteEncryptionKey,
+        previous_keys: &[SqliteEncryptionKey],
+    ) -> Result<Self, StorageError> {
+        let storage = Self {
+            db_dir: path.to_path_buf(),
+            encryption_key: Some(key),
+        };
+
+        std::fs::create_dir_all(path)
+            .map_err(|e| StorageError::InitializationError(e.to_string()))?;
+
+        storage.prepare_encrypted_db(&key, previous_keys)?;
+        storage.migrate()?;
+        Ok(storage)
+    }
+
     pub(crate) fn get_connection(&self) -> Result<Connection, StorageError> {
-        Ok(Connection::open(self.get_db_path())?)
+        let conn = Connection::open(self.get_db_path())?;
+        #[cfg(feature = "sqlcipher")]
+        if let Some(key) = &self.encryption_key {
+            // The key has to be set before anything else is read from the database
+            apply_key(&conn, "key", key)?;
+        }
+        Ok(conn)
+    }
+
+    /// Makes sure the database file is encrypted with `key`, encrypting a plaintext database or
+    /// re-encrypting a database encrypted with a previous key.
+    #[cfg(feature = "sqlcipher")]
+    fn prepare_encrypted_db(
+        &self,
+        key: &SqliteEncryptionKey,
+        previous_keys: &[SqliteEncryptionKey],
+    ) -> Result<(), StorageError> {
+        let db_path = self.get_db_path();
+        if !db_path.exists() {
+            // A new database is encrypted from the start
+            return Ok(());
+        }
+        if open_with_key(&db_path, Some(key)).is_ok() {
+            return Ok(());
+        }
+
+        for previous_key in previous_keys {
+            if let Ok(conn) = open_with_key(&db_path, Some(previous_key)) {
+                apply_key(&conn, "rekey", key)?;
+                tracing::info!("Rotated the storage encryption key");
+                return Ok(());
+            }
+        }
+
+        let Ok(conn) = open_with_key(&db_path, None) else {
+            return Err(StorageError::InitializationError(
+                "Failed to decrypt the database with the given encryption keys".to_string(),
+            ));
+        };
+
+        // Export the plaintext database into a new encrypted database and swap the files.
+        // The original file is only replaced once the export completed.
+        let export_path = self.db_dir.join(ENCRYPTED_EXPORT_DB_FILENAME);
+        if export_path.exists() {
+            std::fs::remove_file(&export_path)
+                .map_err(|e| StorageError::InitializationError(e.to_string()))?;
+        }
+        let user_version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        conn.execute(
+            "ATTACH DATABASE ? AS encrypted KEY ?",
+            params![export_path.to_string_lossy(), key_literal(key)],
+        )?;
+        conn.execute_batch(&format!(
+            "SELECT sqlcipher_export('encrypted');
+             PRAGMA encrypted.user_version = {user_version};
+             DETACH DATABASE encrypted;"
+        ))?;
+        drop(conn);
+        std::fs::rename(&export_path, &db_path)
+            .map_err(|e| StorageError::InitializationError(e.to_string()))?;
+        tracing::info!("Encrypted the existing plaintext database");
+        Ok(())
     }
 
     fn get_db_path(&self) -> PathBuf {
@@ -313,10 +420,44 @@ impl SqliteStorage {
             "DELETE FROM settings WHERE key = 'lightning_address';",
             // Add index on payment_hash for JOIN with lnurl_receive_metadata
             "CREATE INDEX IF NOT EXISTS idx_payment_details_lightning_payment_hash ON payment_details_lightning(payment_hash);",
+            // Add payer_data column for LUD-18 support
+            "ALTER TABLE lnurl_receive_metadata ADD COLUMN payer_data TEXT;",
         ]
     }
 }
 
+/// Formats a key as a raw `SQLCipher` key, which is used as is instead of being derived from a
+/// passphrase.
+#[cfg(feature = "sqlcipher")]
+fn key_literal(key: &SqliteEncryptionKey) -> String {
+    format!("x'{}'", hex::encode(key))
+}
+
+/// Sets the key of a connection with `pragma` `key`, or changes it with `rekey`.
+#[cfg(feature = "sqlcipher")]
+fn apply_key(
+    conn: &Connection,
+    pragma: &str,
+    key: &SqliteEncryptionKey,
+) -> Result<(), StorageError> {
+    conn.execute_batch(&format!("PRAGMA {pragma} = \"{}\";", key_literal(key)))?;
+    Ok(())
+}
+
+/// Opens a database with the given key, or without a key, and checks it can be read.
+#[cfg(feature = "sqlcipher")]
+fn open_with_key(
+    db_path: &Path,
+    key: Option<&SqliteEncryptionKey>,
+) -> Result<Connection, StorageError> {
+    let conn = Connection::open(db_path)?;
+    if let Some(key) = key {
+        apply_key(&conn, "key", key)?;
+    }
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))?;
+    Ok(conn)
+}
+
 /// Maps a rusqlite error to the appropriate `StorageError`.
 /// Database busy/locked errors are mapped to `Connection` (transient),
 /// other errors are mapped to `Implementation`.
@@ -523,9 +664,29 @@ impl Storage for SqliteStorage {
             }
         }
 
-        // Exclude child payments (those with a parent_payment_id)
-        // Child payments are accessed via the parent's related_payments field
-        where_clauses.push("pm.parent_payment_id IS NULL".to_string());
+        if let Some(ref conversion_filter) = request.conversion_filter {
+            // Conversions made for a payment are its child payments, so they are included
+            where_clauses.push("pm.conversion_info IS NOT NULL".to_string());
+            if let Some(ref conversion_ids) = conversion_filter.conversion_ids
+                && !conversion_ids.is_empty()
+            {
+                let placeholders = conversion_ids
+                    .iter()
+                    .map(|_| "?")
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                where_clauses.push(format!(
+                    "json_extract(pm.conversion_info, '$.conversion_id') IN ({placeholders})"
+                ));
+                for conversion_id in conversion_ids {
+                    params.push(Box::new(conversion_id.clone()));
+                }
+            }
+        } else {
+            // Exclude child payments (those with a parent_payment_id)
+            // Child payments are accessed via the parent's related_payments field
+            where_clauses.push("pm.parent_payment_id IS NULL".to_string());
+        }
 
         // Build the WHERE clause
         let where_sql = if where_clauses.is_empty() {
@@ -709,6 +870,35 @@ impl Storage for SqliteStorage {
         Ok(())
     }
 
+    async fn list_payment_metadata(
+        &self,
+    ) -> Result<HashMap<String, PaymentMetadata>, StorageError> {
+        let connection = self.get_connection()?;
+
+        let mut stmt = connection.prepare(
+            "SELECT payment_id, parent_payment_id, lnurl_pay_info, lnurl_withdraw_info, lnurl_description, conversion_info
+             FROM payment_metadata",
+        )?;
+        let rows = stmt.query_map(params![], |row| {
+            let payment_id: String = row.get(0)?;
+            let conversion_info_str: Option<String> = row.get(5)?;
+            Ok((
+                payment_id,
+                PaymentMetadata {
+                    parent_payment_id: row.get(1)?,
+                    lnurl_pay_info: row.get(2)?,
+                    lnurl_withdraw_info: row.get(3)?,
+                    lnurl_description: row.get(4)?,
+                    conversion_info: conversion_info_str
+                        .map(|s| serde_json_from_str(&s, 5))
+                        .transpose()?,
+                },
+            ))
+        })?;
+        let metadata = rows.collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(metadata)
+    }
+
     async fn set_cached_item(&self, key: String, value: String) -> Result<(), StorageError> {
         let connection = self.get_connection()?;
 
@@ -745,6 +935,19 @@ impl Storage for SqliteStorage {
         Ok(())
     }
 
+    async fn list_cached_items(&self) -> Result<HashMap<String, String>, StorageError> {
+        let connection = self.get_connection()?;
+
+        let mut stmt = connection.prepare("SELECT key, value FROM settings")?;
+        let rows = stmt.query_map(params![], |row| {
+            let key: String = row.get(0)?;
+            let value: String = row.get(1)?;
+            Ok((key, value))
+        })?;
+        let items = rows.collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(items)
+    }
+
     async fn get_payment_by_id(&self, id: String) -> Result<Payment, StorageError> {
         let connection = self.get_connection()?;
         let query = format!("{SELECT_PAYMENT_SQL} WHERE p.id = ?");
@@ -895,20 +1098,41 @@ impl Storage for SqliteStorage {
         let connection = self.get_connection()?;
         for metadata in metadata {
             connection.execute(
-                "INSERT OR REPLACE INTO lnurl_receive_metadata (payment_hash, nostr_zap_request, nostr_zap_receipt, sender_comment, preimage)
-                 VALUES (?, ?, ?, ?, ?)",
+                "INSERT OR REPLACE INTO lnurl_receive_metadata (payment_hash, nostr_zap_request, nostr_zap_receipt, sender_comment, preimage, payer_data)
+                 VALUES (?, ?, ?, ?, ?, ?)",
                 params![
                     metadata.payment_hash,
                     metadata.nostr_zap_request,
                     metadata.nostr_zap_receipt,
                     metadata.sender_comment,
                     metadata.preimage,
+                    metadata.payer_data,
                 ],
             )?;
         }
         Ok(())
     }
 
+    async fn list_lnurl_metadata(&self) -> Result<Vec<SetLnurlMetadataItem>, StorageError> {
+        let connection = self.get_connection()?;
+        let mut stmt = connection.prepare(
+            "SELECT payment_hash, sender_comment, nostr_zap_request, nostr_zap_receipt, preimage, payer_data
+             FROM lnurl_receive_metadata",
+        )?;
+        let rows = stmt.query_map(params![], |row| {
+            Ok(SetLnurlMetadataItem {
+                payment_hash: row.get(0)?,
+                sender_comment: row.get(1)?,
+                nostr_zap_request: row.get(2)?,
+                nostr_zap_receipt: row.get(3)?,
+                preimage: row.get(4)?,
+                payer_data: row.get(5)?,
+            })
+        })?;
+        let metadata = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok(metadata)
+    }
+
     async fn add_outgoing_change(
         &self,
         record: UnversionedRecordChange,
@@ -1275,6 +1499,38 @@ impl Storage for SqliteStorage {
         tx.commit().map_err(map_sqlite_error)?;
         Ok(())
     }
+
+    async fn get_sync_state_records(&self) -> Result<Vec<Record>, StorageError> {
+        let connection = self.get_connection()?;
+
+        let mut stmt = connection
+            .prepare(
+                "SELECT record_type
+            ,       data_id
+            ,       schema_version
+            ,       data
+            ,       revision
+             FROM sync_state
+             ORDER BY revision ASC",
+            )
+            .map_err(map_sqlite_error)?;
+
+        let mut rows = stmt.query([]).map_err(map_sqlite_error)?;
+        let mut results = Vec::new();
+        while let Some(row) = rows.next().map_err(map_sqlite_error)? {
+            results.push(Record {
+                id: RecordId::new(
+                    row.get::<_, String>(0).map_err(map_sqlite_error)?,
+                    row.get::<_, String>(1).map_err(map_sqlite_error)?,
+                ),
+                schema_version: row.get(2).map_err(map_sqlite_error)?,
+                data: serde_json::from_str(&row.get::<_, String>(3).map_err(map_sqlite_error)?)?,
+                revision: row.get(4).map_err(map_sqlite_error)?,
+            });
+        }
+
+        Ok(results)
+    }
 }
 
 /// Base query for payment lookups.
@@ -1310,7 +1566,8 @@ const SELECT_PAYMENT_SQL: &str = "
            lrm.nostr_zap_receipt AS lnurl_nostr_zap_receipt,
            lrm.sender_comment AS lnurl_sender_comment,
            lrm.payment_hash AS lnurl_payment_hash,
-           pm.parent_payment_id
+           pm.parent_payment_id,
+           lrm.payer_data AS lnurl_payer_data
       FROM payments p
       LEFT JOIN payment_details_lightning l ON p.id = l.payment_id
       LEFT JOIN payment_details_token t ON p.id = t.payment_id
@@ -1358,11 +1615,14 @@ fn map_payment(row: &Row<'_>) -> Result<Payment, rusqlite::Error> {
             let lnurl_nostr_zap_receipt: Option<String> = row.get(27)?;
             let lnurl_sender_comment: Option<String> = row.get(28)?;
             let lnurl_payment_hash: Option<String> = row.get(29)?;
+            let lnurl_payer_data: Option<String> = row.get(31)?;
             let lnurl_receive_metadata = if lnurl_payment_hash.is_some() {
                 Some(LnurlReceiveMetadata {
                     nostr_zap_request: lnurl_nostr_zap_request,
                     nostr_zap_receipt: lnurl_nostr_zap_receipt,
                     sender_comment: lnurl_sender_comment,
+                    payer_data: lnurl_payer_data
+                        .and_then(|payer_data| serde_json::from_str(&payer_data).ok()),
                 })
             } else {
                 None
@@ -1698,6 +1958,14 @@ mod tests {
         crate::persist::tests::test_conversion_refund_needed_filtering(Box::new(storage)).await;
     }
 
+    #[tokio::test]
+    async fn test_conversion_filtering() {
+        let temp_dir = create_temp_dir("sqlite_storage_conversion_filter");
+        let storage = SqliteStorage::new(&temp_dir).unwrap();
+
+        crate::persist::tests::test_conversion_filtering(Box::new(storage)).await;
+    }
+
     #[tokio::test]
     async fn test_token_transaction_type_filtering() {
         let temp_dir = create_temp_dir("sqlite_storage_token_transaction_type_filter");
@@ -1754,6 +2022,43 @@ mod tests {
         crate::persist::tests::test_sync_storage(Box::new(storage)).await;
     }
 
+    #[tokio::test]
+    async fn test_migrate_storage() {
+        let source = SqliteStorage::new(&create_temp_dir("sqlite_migrate_source")).unwrap();
+        let target = SqliteStorage::new(&create_temp_dir("sqlite_migrate_target")).unwrap();
+
+        crate::persist::tests::test_migrate_storage(Box::new(source), Box::new(target)).await;
+    }
+
+    #[tokio::test]
+    async fn test_resume_migrate_storage() {
+        let source = SqliteStorage::new(&create_temp_dir("sqlite_resume_source")).unwrap();
+        let target = SqliteStorage::new(&create_temp_dir("sqlite_resume_target")).unwrap();
+
+        crate::persist::tests::test_resume_migrate_storage(Box::new(source), Box::new(target))
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_migrate_storage_verifies_metadata() {
+        let source = SqliteStorage::new(&create_temp_dir("sqlite_verify_source")).unwrap();
+        let target = SqliteStorage::new(&create_temp_dir("sqlite_verify_target")).unwrap();
+
+        crate::persist::tests::test_migrate_storage_verifies_metadata(
+            Box::new(source),
+            Box::new(target),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_backup_merge() {
+        let source = SqliteStorage::new(&create_temp_dir("sqlite_backup_source")).unwrap();
+        let target = SqliteStorage::new(&create_temp_dir("sqlite_backup_target")).unwrap();
+
+        crate::persist::tests::test_backup_merge(Box::new(source), Box::new(target)).await;
+    }
+
     #[tokio::test]
     async fn test_payment_metadata_merge() {
         let temp_dir = create_temp_dir("sqlite_payment_metadata_merge");
@@ -1762,6 +2067,68 @@ mod tests {
         crate::persist::tests::test_payment_metadata_merge(Box::new(storage)).await;
     }
 
+    #[cfg(feature = "sqlcipher")]
+    #[tokio::test]
+    async fn test_encrypted_storage() {
+        let temp_dir = create_temp_dir("sqlite_encrypted_storage");
+        let storage = SqliteStorage::new_encrypted(&temp_dir, [1; 32], &[]).unwrap();
+
+        Box::pin(crate::persist::tests::test_storage(Box::new(storage))).await;
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[tokio::test]
+    async fn test_encrypt_existing_database() {
+        use crate::Storage;
+
+        let temp_dir = create_temp_dir("sqlite_encrypt_existing");
+        let storage = SqliteStorage::new(&temp_dir).unwrap();
+        storage
+            .set_cached_item("key".to_string(), "value".to_string())
+            .await
+            .unwrap();
+        drop(storage);
+
+        // The plaintext database is encrypted in place and keeps its data
+        let storage = SqliteStorage::new_encrypted(&temp_dir, [1; 32], &[]).unwrap();
+        assert_eq!(
+            storage.get_cached_item("key".to_string()).await.unwrap(),
+            Some("value".to_string())
+        );
+        assert!(!temp_dir.join(super::ENCRYPTED_EXPORT_DB_FILENAME).exists());
+        drop(storage);
+
+        let db_path = temp_dir.join(super::DEFAULT_DB_FILENAME);
+        assert!(super::open_with_key(&db_path, None).is_err());
+        assert!(SqliteStorage::new(&temp_dir).is_err());
+        assert!(SqliteStorage::new_encrypted(&temp_dir, [2; 32], &[]).is_err());
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[tokio::test]
+    async fn test_rotate_encryption_key() {
+        use crate::Storage;
+
+        let temp_dir = create_temp_dir("sqlite_rotate_key");
+        let storage = SqliteStorage::new_encrypted(&temp_dir, [1; 32], &[]).unwrap();
+        storage
+            .set_cached_item("key".to_string(), "value".to_string())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let storage = SqliteStorage::new_encrypted(&temp_dir, [2; 32], &[[1; 32]]).unwrap();
+        assert_eq!(
+            storage.get_cached_item("key".to_string()).await.unwrap(),
+            Some("value".to_string())
+        );
+        drop(storage);
+
+        // The previous key doesn't open the database anymore
+        assert!(SqliteStorage::new_encrypted(&temp_dir, [1; 32], &[]).is_err());
+        assert!(SqliteStorage::new_encrypted(&temp_dir, [2; 32], &[]).is_ok());
+    }
+
     #[tokio::test]
     #[allow(clippy::too_many_lines)]
     async fn test_migration_tx_type() {
@@ -1888,6 +2255,7 @@ mod tests {
                     decimals: 6,
                     max_supply: 2_000_000,
                     is_freezable: true,
+                    registry_info: None,
                 },
                 tx_hash: "0x1111222233334444".to_string(),
                 tx_type: TokenTransactionType::Mint,
@@ -1905,6 +2273,7 @@ mod tests {
             status_filter: None,
             asset_filter: None,
             payment_details_filter: None,
+            conversion_filter: None,
             from_timestamp: None,
             to_timestamp: None,
             offset: None,
@@ -1949,6 +2318,7 @@ mod tests {
                 tx_hash: None,
                 tx_type: Some(TokenTransactionType::Transfer),
             }]),
+            conversion_filter: None,
             from_timestamp: None,
             to_timestamp: None,
             offset: None,
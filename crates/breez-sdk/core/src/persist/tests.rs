@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use chrono::Utc;
 
@@ -392,6 +392,7 @@ pub async fn test_storage(storage: Box<dyn Storage>) {
         decimals: 8,
         max_supply: 21_000_000,
         is_freezable: false,
+        registry_info: None,
     };
     let token_transfer_payment = Payment {
         id: "token_transfer_pmt456".to_string(),
@@ -568,6 +569,12 @@ pub async fn test_storage(storage: Box<dyn Storage>) {
         sender_comment: Some("Test sender comment".to_string()),
         nostr_zap_request: Some(r#"{"kind":9734,"content":"test zap"}"#.to_string()),
         nostr_zap_receipt: Some(r#"{"kind":9735,"content":"test receipt"}"#.to_string()),
+        payer_data: Some(crate::LnurlPayerData {
+            name: Some("Satoshi".to_string()),
+            pubkey: None,
+            identifier: Some("satoshi@example.com".to_string()),
+            auth: None,
+        }),
     };
     let lightning_lnurl_receive_payment = Payment {
         id: "lightning_lnurl_receive_pmt".to_string(),
@@ -641,6 +648,7 @@ pub async fn test_storage(storage: Box<dyn Storage>) {
             status: crate::ConversionStatus::Completed,
             fee: Some(21),
             purpose: None,
+            expected_amount_out: None,
         }),
         ..Default::default()
     };
@@ -708,6 +716,7 @@ pub async fn test_storage(storage: Box<dyn Storage>) {
             status: crate::ConversionStatus::Refunded,
             fee: None,
             purpose: None,
+            expected_amount_out: None,
         }),
         ..Default::default()
     };
@@ -737,6 +746,7 @@ pub async fn test_storage(storage: Box<dyn Storage>) {
             status: crate::ConversionStatus::RefundNeeded,
             fee: None,
             purpose: None,
+            expected_amount_out: None,
         }),
         ..Default::default()
     };
@@ -808,6 +818,9 @@ pub async fn test_storage(storage: Box<dyn Storage>) {
             preimage: Some(
                 "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef".to_string(),
             ),
+            payer_data: Some(
+                r#"{"name":"Satoshi","identifier":"satoshi@example.com"}"#.to_string(),
+            ),
         }])
         .await
         .unwrap();
@@ -999,6 +1012,7 @@ pub async fn test_storage(storage: Box<dyn Storage>) {
                     (Some(r_info), Some(e_info)) => {
                         assert_eq!(r_info.nostr_zap_request, e_info.nostr_zap_request);
                         assert_eq!(r_info.sender_comment, e_info.sender_comment);
+                        assert_eq!(r_info.payer_data, e_info.payer_data);
                     }
                     (None, None) => {}
                     _ => panic!(
@@ -1103,6 +1117,7 @@ pub async fn test_storage(storage: Box<dyn Storage>) {
                 r#"{"kind":9735,"content":"zap receipt","tags":[]}"#.to_string(),
             ),
             preimage: None,
+            payer_data: None,
         }])
         .await
         .unwrap();
@@ -1196,6 +1211,7 @@ pub async fn test_storage(storage: Box<dyn Storage>) {
                 nostr_zap_request: None,
                 nostr_zap_receipt: None,
                 preimage: None,
+                payer_data: None,
             },
             SetLnurlMetadataItem {
                 payment_hash: "zaphash3".to_string(),
@@ -1203,6 +1219,7 @@ pub async fn test_storage(storage: Box<dyn Storage>) {
                 nostr_zap_request: Some(r#"{"kind":9734,"content":"zap3"}"#.to_string()),
                 nostr_zap_receipt: None,
                 preimage: None,
+                payer_data: None,
             },
         ])
         .await
@@ -1609,6 +1626,7 @@ pub async fn test_asset_filtering(storage: Box<dyn Storage>) {
                 decimals: 8,
                 max_supply: 1_000_000,
                 is_freezable: false,
+                registry_info: None,
             },
             tx_hash: "tx_hash_1".to_string(),
             tx_type: TokenTransactionType::Transfer,
@@ -1882,6 +1900,7 @@ pub async fn test_conversion_refund_needed_filtering(storage: Box<dyn Storage>)
             status: crate::ConversionStatus::Refunded,
             fee: None,
             purpose: None,
+            expected_amount_out: None,
         }),
         ..Default::default()
     };
@@ -1902,6 +1921,7 @@ pub async fn test_conversion_refund_needed_filtering(storage: Box<dyn Storage>)
                 decimals: 8,
                 max_supply: 1_000_000_000,
                 is_freezable: false,
+                registry_info: None,
             },
             tx_hash: "txhash1".to_string(),
             tx_type: TokenTransactionType::Transfer,
@@ -1918,6 +1938,7 @@ pub async fn test_conversion_refund_needed_filtering(storage: Box<dyn Storage>)
             status: crate::ConversionStatus::Completed,
             fee: Some(100),
             purpose: None,
+            expected_amount_out: None,
         }),
         ..Default::default()
     };
@@ -1944,6 +1965,7 @@ pub async fn test_conversion_refund_needed_filtering(storage: Box<dyn Storage>)
             status: crate::ConversionStatus::RefundNeeded,
             fee: None,
             purpose: None,
+            expected_amount_out: None,
         }),
         ..Default::default()
     };
@@ -2084,6 +2106,129 @@ pub async fn test_conversion_refund_needed_filtering(storage: Box<dyn Storage>)
     assert_eq!(all_payments_filter.len(), 3);
 }
 
+pub async fn test_conversion_filtering(storage: Box<dyn Storage>) {
+    let spark_payment = |id: &str, payment_type: PaymentType, timestamp: u64| Payment {
+        id: id.to_string(),
+        payment_type,
+        status: PaymentStatus::Completed,
+        amount: 10_000,
+        fees: 0,
+        timestamp,
+        method: PaymentMethod::Spark,
+        details: Some(PaymentDetails::Spark {
+            invoice_details: None,
+            htlc_details: None,
+            conversion_info: None,
+        }),
+        conversion_details: None,
+    };
+    let conversion_metadata =
+        |conversion_id: &str, parent_payment_id: Option<&str>| PaymentMetadata {
+            parent_payment_id: parent_payment_id.map(ToString::to_string),
+            conversion_info: Some(crate::ConversionInfo {
+                pool_id: "pool1".to_string(),
+                conversion_id: conversion_id.to_string(),
+                status: crate::ConversionStatus::Completed,
+                fee: None,
+                purpose: None,
+                expected_amount_out: None,
+            }),
+            ..Default::default()
+        };
+
+    // A payment made after a conversion, stored with the conversion as its children
+    storage
+        .insert_payment(spark_payment("parent", PaymentType::Send, 1300))
+        .await
+        .unwrap();
+    for (id, payment_type, timestamp) in [
+        ("child_send", PaymentType::Send, 1000),
+        ("child_receive", PaymentType::Receive, 1100),
+    ] {
+        storage
+            .insert_payment(spark_payment(id, payment_type, timestamp))
+            .await
+            .unwrap();
+        storage
+            .insert_payment_metadata(
+                id.to_string(),
+                conversion_metadata("child_conversion", Some("parent")),
+            )
+            .await
+            .unwrap();
+    }
+    // A standalone conversion
+    for (id, payment_type, timestamp) in [
+        ("send", PaymentType::Send, 2000),
+        ("receive", PaymentType::Receive, 2100),
+    ] {
+        storage
+            .insert_payment(spark_payment(id, payment_type, timestamp))
+            .await
+            .unwrap();
+        storage
+            .insert_payment_metadata(id.to_string(), conversion_metadata("conversion", None))
+            .await
+            .unwrap();
+    }
+    // A payment unrelated to any conversion
+    storage
+        .insert_payment(spark_payment("unrelated", PaymentType::Receive, 3000))
+        .await
+        .unwrap();
+
+    // Child payments are excluded without the conversion filter
+    let payments = storage
+        .list_payments(StorageListPaymentsRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(payments.len(), 4);
+
+    // The conversion filter includes child payments and excludes other payments
+    let conversion_payments = storage
+        .list_payments(StorageListPaymentsRequest {
+            conversion_filter: Some(crate::StorageConversionFilter::default()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let ids = conversion_payments
+        .iter()
+        .map(|p| p.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["receive", "send", "child_receive", "child_send"]);
+
+    // The offset and limit apply to the filtered payments
+    let sent_payments = storage
+        .list_payments(StorageListPaymentsRequest {
+            type_filter: Some(vec![PaymentType::Send]),
+            conversion_filter: Some(crate::StorageConversionFilter::default()),
+            offset: Some(1),
+            limit: Some(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(sent_payments.len(), 1);
+    assert_eq!(sent_payments[0].id, "child_send");
+
+    // Filter by conversion id
+    let child_conversion_payments = storage
+        .list_payments(StorageListPaymentsRequest {
+            conversion_filter: Some(crate::StorageConversionFilter {
+                conversion_ids: Some(vec!["child_conversion".to_string()]),
+            }),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let ids = child_conversion_payments
+        .iter()
+        .map(|p| p.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["child_receive", "child_send"]);
+}
+
 #[allow(clippy::too_many_lines)]
 pub async fn test_token_transaction_type_filtering(storage: Box<dyn Storage>) {
     let token_metadata = TokenMetadata {
@@ -2095,6 +2240,7 @@ pub async fn test_token_transaction_type_filtering(storage: Box<dyn Storage>) {
         decimals: 8,
         max_supply: 21_000_000,
         is_freezable: false,
+        registry_info: None,
     };
     // Create payments with different transaction types
     let payment1 = Payment {
@@ -2664,6 +2810,7 @@ pub async fn test_payment_metadata_merge(storage: Box<dyn Storage>) {
             status: crate::ConversionStatus::Completed,
             fee: Some(100),
             purpose: None,
+            expected_amount_out: None,
         }),
         ..Default::default()
     };
@@ -3097,6 +3244,7 @@ pub async fn test_pending_lnurl_preimages(storage: Box<dyn Storage>) {
             nostr_zap_request: Some(r#"{"kind":9734}"#.to_string()),
             nostr_zap_receipt: None,
             preimage: None, // No preimage - should be pending
+            payer_data: None,
         }])
         .await
         .unwrap();
@@ -3109,6 +3257,7 @@ pub async fn test_pending_lnurl_preimages(storage: Box<dyn Storage>) {
             nostr_zap_request: Some(r#"{"kind":9734}"#.to_string()),
             nostr_zap_receipt: Some(r#"{"kind":9735}"#.to_string()),
             preimage: Some(preimage_2.clone()), // Has preimage - already sent
+            payer_data: None,
         }])
         .await
         .unwrap();
@@ -3121,6 +3270,7 @@ pub async fn test_pending_lnurl_preimages(storage: Box<dyn Storage>) {
             nostr_zap_request: None,
             nostr_zap_receipt: None,
             preimage: None,
+            payer_data: None,
         }])
         .await
         .unwrap();
@@ -3133,6 +3283,7 @@ pub async fn test_pending_lnurl_preimages(storage: Box<dyn Storage>) {
             nostr_zap_request: None,
             nostr_zap_receipt: None,
             preimage: None,
+            payer_data: None,
         }])
         .await
         .unwrap();
@@ -3145,6 +3296,7 @@ pub async fn test_pending_lnurl_preimages(storage: Box<dyn Storage>) {
             nostr_zap_request: None,
             nostr_zap_receipt: None,
             preimage: None,
+            payer_data: None,
         }])
         .await
         .unwrap();
@@ -3209,6 +3361,7 @@ pub async fn test_pending_lnurl_preimages(storage: Box<dyn Storage>) {
             nostr_zap_request: Some(r#"{"kind":9734}"#.to_string()),
             nostr_zap_receipt: Some(r#"{"kind":9735}"#.to_string()),
             preimage: Some(preimage_1), // Now has preimage
+            payer_data: None,
         }])
         .await
         .unwrap();
@@ -3233,3 +3386,480 @@ pub async fn test_pending_lnurl_preimages(storage: Box<dyn Storage>) {
         pending_after.len()
     );
 }
+
+/// Fills a storage with a bit of everything copied by a storage migration
+#[allow(clippy::too_many_lines)]
+async fn populate_migration_source(storage: &dyn Storage) {
+    use crate::SetLnurlMetadataItem;
+
+    let lightning_payment = Payment {
+        id: "migrate_lightning".to_string(),
+        payment_type: PaymentType::Receive,
+        status: PaymentStatus::Completed,
+        amount: 50_000,
+        fees: 0,
+        timestamp: 1_700_000_000,
+        method: PaymentMethod::Lightning,
+        details: Some(PaymentDetails::Lightning {
+            description: Some("Migration test".to_string()),
+            invoice: "lnbc500n1migrate".to_string(),
+            destination_pubkey: "03migratepubkey".to_string(),
+            htlc_details: test_lightning_htlc("migratehash"),
+            lnurl_pay_info: None,
+            lnurl_withdraw_info: None,
+            lnurl_receive_metadata: None,
+        }),
+        conversion_details: None,
+    };
+    let parent_payment = Payment {
+        id: "migrate_parent".to_string(),
+        payment_type: PaymentType::Send,
+        status: PaymentStatus::Completed,
+        amount: 2000,
+        fees: 20,
+        timestamp: 1_700_000_001,
+        method: PaymentMethod::Spark,
+        details: Some(PaymentDetails::Spark {
+            invoice_details: None,
+            htlc_details: None,
+            conversion_info: None,
+        }),
+        conversion_details: None,
+    };
+    let child_payment = Payment {
+        id: "migrate_child".to_string(),
+        timestamp: 1_700_000_002,
+        ..parent_payment.clone()
+    };
+    for payment in [lightning_payment, parent_payment, child_payment] {
+        storage.insert_payment(payment).await.unwrap();
+    }
+    storage
+        .insert_payment_metadata(
+            "migrate_child".to_string(),
+            PaymentMetadata {
+                parent_payment_id: Some("migrate_parent".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    // Metadata of a payment that isn't in storage yet
+    storage
+        .insert_payment_metadata(
+            "migrate_pending".to_string(),
+            PaymentMetadata {
+                lnurl_description: Some("Pending".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    storage
+        .set_lnurl_metadata(vec![
+            SetLnurlMetadataItem {
+                payment_hash: "migratehash".to_string(),
+                sender_comment: Some("Thanks".to_string()),
+                nostr_zap_request: None,
+                nostr_zap_receipt: None,
+                preimage: None,
+                payer_data: None,
+            },
+            SetLnurlMetadataItem {
+                payment_hash: "otherhash".to_string(),
+                sender_comment: Some("Not received yet".to_string()),
+                nostr_zap_request: None,
+                nostr_zap_receipt: None,
+                preimage: None,
+                payer_data: None,
+            },
+        ])
+        .await
+        .unwrap();
+
+    storage
+        .add_deposit("migrate_refund_tx".to_string(), 0, 10_000)
+        .await
+        .unwrap();
+    storage
+        .update_deposit(
+            "migrate_refund_tx".to_string(),
+            0,
+            UpdateDepositPayload::Refund {
+                refund_txid: "migrate_refund_txid".to_string(),
+                refund_tx: "0200000001migrate".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+    storage
+        .add_deposit("migrate_error_tx".to_string(), 1, 20_000)
+        .await
+        .unwrap();
+    storage
+        .update_deposit(
+            "migrate_error_tx".to_string(),
+            1,
+            UpdateDepositPayload::ClaimError {
+                error: DepositClaimError::Generic {
+                    message: "claim failed".to_string(),
+                },
+            },
+        )
+        .await
+        .unwrap();
+
+    storage
+        .set_cached_item("migrate_key".to_string(), "migrate_value".to_string())
+        .await
+        .unwrap();
+
+    let record = |data_id: &str, revision| Record {
+        id: RecordId::new("PaymentMetadata".to_string(), data_id.to_string()),
+        revision,
+        schema_version: "1.0.0".to_string(),
+        data: HashMap::from([("lnurl_description".to_string(), "\"desc\"".to_string())]),
+    };
+    storage
+        .update_record_from_incoming(record("migrate_synced", 5))
+        .await
+        .unwrap();
+    storage
+        .insert_incoming_records(vec![record("migrate_incoming", 6)])
+        .await
+        .unwrap();
+    for data_id in ["migrate_synced", "migrate_outgoing"] {
+        storage
+            .add_outgoing_change(UnversionedRecordChange {
+                id: RecordId::new("PaymentMetadata".to_string(), data_id.to_string()),
+                schema_version: "1.0.0".to_string(),
+                updated_fields: HashMap::from([(
+                    "lnurl_description".to_string(),
+                    format!("\"{data_id}\""),
+                )]),
+            })
+            .await
+            .unwrap();
+    }
+}
+
+/// Checks a storage migrated from [`populate_migration_source`]
+async fn verify_migration_target(storage: &dyn Storage) {
+    let payments = storage
+        .list_payments(StorageListPaymentsRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(payments.len(), 2, "Child payments should stay hidden");
+    let children = storage
+        .get_payments_by_parent_ids(vec!["migrate_parent".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(children["migrate_parent"][0].id, "migrate_child");
+
+    let lightning_payment = storage
+        .get_payment_by_id("migrate_lightning".to_string())
+        .await
+        .unwrap();
+    let Some(PaymentDetails::Lightning {
+        lnurl_receive_metadata: Some(lnurl_receive_metadata),
+        ..
+    }) = lightning_payment.details
+    else {
+        panic!("Expected Lightning payment details with LNURL receive metadata");
+    };
+    assert_eq!(
+        lnurl_receive_metadata.sender_comment,
+        Some("Thanks".to_string())
+    );
+    assert_eq!(storage.list_lnurl_metadata().await.unwrap().len(), 2);
+
+    let metadata = storage.list_payment_metadata().await.unwrap();
+    assert_eq!(metadata.len(), 2);
+    assert_eq!(
+        metadata["migrate_pending"].lnurl_description,
+        Some("Pending".to_string())
+    );
+
+    let mut deposits = storage.list_deposits().await.unwrap();
+    deposits.sort_by(|a, b| a.txid.cmp(&b.txid));
+    assert_eq!(deposits.len(), 2);
+    assert!(deposits[0].claim_error.is_some());
+    assert_eq!(
+        deposits[1].refund_tx_id,
+        Some("migrate_refund_txid".to_string())
+    );
+    assert_eq!(deposits[1].refund_tx, Some("0200000001migrate".to_string()));
+
+    let cached_items = storage.list_cached_items().await.unwrap();
+    assert_eq!(
+        cached_items.len(),
+        1,
+        "The migration progress should be removed"
+    );
+    assert_eq!(cached_items["migrate_key"], "migrate_value");
+
+    assert_eq!(storage.get_last_revision().await.unwrap(), 5);
+    let sync_records = storage.get_sync_state_records().await.unwrap();
+    assert_eq!(sync_records.len(), 1);
+    assert_eq!(sync_records[0].id.data_id, "migrate_synced");
+    let incoming = storage.get_incoming_records(10).await.unwrap();
+    assert_eq!(incoming.len(), 1);
+    assert_eq!(incoming[0].new_state.revision, 6);
+    let outgoing = storage.get_pending_outgoing_changes(10).await.unwrap();
+    assert_eq!(outgoing.len(), 2);
+    assert_eq!(outgoing[0].change.id.data_id, "migrate_synced");
+    assert!(outgoing[0].parent.is_some());
+    assert_eq!(outgoing[1].change.id.data_id, "migrate_outgoing");
+}
+
+pub async fn test_migrate_storage(source: Box<dyn Storage>, target: Box<dyn Storage>) {
+    use crate::persist::migration::migrate_storage;
+
+    let source: Arc<dyn Storage> = source.into();
+    let target: Arc<dyn Storage> = target.into();
+    populate_migration_source(source.as_ref()).await;
+
+    let summary = migrate_storage(source.clone(), target.clone())
+        .await
+        .unwrap();
+    assert_eq!(summary.payments, 3);
+    assert_eq!(summary.payment_metadata, 2);
+    assert_eq!(summary.deposits, 2);
+    assert_eq!(summary.lnurl_metadata, 2);
+    assert_eq!(summary.cached_items, 1);
+    assert_eq!(summary.sync_records, 1);
+    assert_eq!(summary.sync_incoming_records, 1);
+    assert_eq!(summary.sync_outgoing_changes, 2);
+    verify_migration_target(target.as_ref()).await;
+
+    // The target isn't empty anymore
+    assert!(migrate_storage(source, target).await.is_err());
+}
+
+pub async fn test_resume_migrate_storage(source: Box<dyn Storage>, target: Box<dyn Storage>) {
+    use crate::persist::migration::{MIGRATION_PROGRESS_KEY, migrate_storage};
+
+    let source: Arc<dyn Storage> = source.into();
+    let target: Arc<dyn Storage> = target.into();
+    populate_migration_source(source.as_ref()).await;
+
+    // Simulate a migration interrupted after copying the first payment, with an outgoing change
+    // already in the target
+    for (key, value) in source.list_cached_items().await.unwrap() {
+        target.set_cached_item(key, value).await.unwrap();
+    }
+    for (payment_id, metadata) in source.list_payment_metadata().await.unwrap() {
+        target
+            .insert_payment_metadata(payment_id, metadata)
+            .await
+            .unwrap();
+    }
+    let first_payment = source
+        .get_payment_by_id("migrate_lightning".to_string())
+        .await
+        .unwrap();
+    target.insert_payment(first_payment).await.unwrap();
+    let outgoing = source.get_pending_outgoing_changes(1).await.unwrap();
+    target
+        .add_outgoing_change(UnversionedRecordChange {
+            id: outgoing[0].change.id.clone(),
+            schema_version: outgoing[0].change.schema_version.clone(),
+            updated_fields: outgoing[0].change.updated_fields.clone(),
+        })
+        .await
+        .unwrap();
+    target
+        .set_cached_item(
+            MIGRATION_PROGRESS_KEY.to_string(),
+            r#"{"next_step":"Payments","payments_offset":1}"#.to_string(),
+        )
+        .await
+        .unwrap();
+
+    migrate_storage(source, target.clone()).await.unwrap();
+    verify_migration_target(target.as_ref()).await;
+}
+
+pub async fn test_migrate_storage_verifies_metadata(
+    source: Box<dyn Storage>,
+    target: Box<dyn Storage>,
+) {
+    use crate::SetLnurlMetadataItem;
+    use crate::persist::migration::{MIGRATION_PROGRESS_KEY, migrate_storage};
+
+    // Resumes the completed migration at its verification step, returning the error
+    async fn verify_again(source: &Arc<dyn Storage>, target: &Arc<dyn Storage>) -> String {
+        target
+            .set_cached_item(
+                MIGRATION_PROGRESS_KEY.to_string(),
+                r#"{"next_step":"Verify","payments_offset":0}"#.to_string(),
+            )
+            .await
+            .unwrap();
+        migrate_storage(source.clone(), target.clone())
+            .await
+            .unwrap_err()
+            .to_string()
+    }
+
+    let source: Arc<dyn Storage> = source.into();
+    let target: Arc<dyn Storage> = target.into();
+    populate_migration_source(source.as_ref()).await;
+    migrate_storage(source.clone(), target.clone())
+        .await
+        .unwrap();
+
+    // Metadata with the same keys but different contents fails the verification
+    target
+        .set_lnurl_metadata(vec![SetLnurlMetadataItem {
+            payment_hash: "migratehash".to_string(),
+            sender_comment: Some("Changed".to_string()),
+            nostr_zap_request: None,
+            nostr_zap_receipt: None,
+            preimage: None,
+            payer_data: None,
+        }])
+        .await
+        .unwrap();
+    assert!(
+        verify_again(&source, &target)
+            .await
+            .contains("lnurl metadata")
+    );
+
+    target
+        .insert_payment_metadata(
+            "migrate_pending".to_string(),
+            PaymentMetadata {
+                lnurl_description: Some("Changed".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(
+        verify_again(&source, &target)
+            .await
+            .contains("payment metadata")
+    );
+}
+
+pub async fn test_backup_merge(source: Box<dyn Storage>, target: Box<dyn Storage>) {
+    use crate::{SetLnurlMetadataItem, persist::backup::StorageBackup};
+
+    populate_migration_source(source.as_ref()).await;
+    for (key, value) in [
+        ("lnurl_withdraw_links", "[]"),
+        ("token_distribution_key", "backup"),
+        ("frozen_tokens", "{}"),
+        ("account_info", "{}"),
+    ] {
+        source
+            .set_cached_item(key.to_string(), value.to_string())
+            .await
+            .unwrap();
+    }
+
+    // Data already in the target takes precedence over the backup
+    target
+        .set_cached_item("token_distribution_key".to_string(), "existing".to_string())
+        .await
+        .unwrap();
+    target
+        .insert_payment_metadata(
+            "migrate_pending".to_string(),
+            PaymentMetadata {
+                lnurl_description: Some("Existing".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    target
+        .add_deposit("migrate_refund_tx".to_string(), 0, 10_000)
+        .await
+        .unwrap();
+    target
+        .set_lnurl_metadata(vec![SetLnurlMetadataItem {
+            payment_hash: "otherhash".to_string(),
+            sender_comment: Some("Existing".to_string()),
+            nostr_zap_request: None,
+            nostr_zap_receipt: None,
+            preimage: None,
+            payer_data: None,
+        }])
+        .await
+        .unwrap();
+
+    let backup = StorageBackup::from_storage(source.as_ref(), 1_700_000_100)
+        .await
+        .unwrap();
+    assert_eq!(backup.payments.len(), 3);
+    // Cached items that are rebuilt on a fresh install are left out
+    assert_eq!(backup.cached_items.len(), 3);
+    let serialized = serde_json::to_vec(&backup).unwrap();
+    let backup: StorageBackup = serde_json::from_slice(&serialized).unwrap();
+
+    let response = backup.merge_into(target.as_ref()).await.unwrap();
+    assert_eq!(response.payments, 3);
+    assert_eq!(response.payment_metadata, 1);
+    assert_eq!(response.deposits, 2);
+    assert_eq!(response.lnurl_metadata, 1);
+    assert_eq!(response.cached_items, 2);
+
+    let children = target
+        .get_payments_by_parent_ids(vec!["migrate_parent".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(children["migrate_parent"][0].id, "migrate_child");
+    let metadata = target.list_payment_metadata().await.unwrap();
+    assert_eq!(
+        metadata["migrate_pending"].lnurl_description.as_deref(),
+        Some("Existing")
+    );
+    let deposits = target.list_deposits().await.unwrap();
+    assert_eq!(deposits.len(), 2);
+    let refunded = deposits
+        .iter()
+        .find(|d| d.txid == "migrate_refund_tx")
+        .unwrap();
+    assert_eq!(
+        refunded.refund_tx_id.as_deref(),
+        Some("migrate_refund_txid")
+    );
+    let lnurl_metadata = target.list_lnurl_metadata().await.unwrap();
+    assert_eq!(lnurl_metadata.len(), 2);
+    let other = lnurl_metadata
+        .iter()
+        .find(|m| m.payment_hash == "otherhash")
+        .unwrap();
+    assert_eq!(other.sender_comment.as_deref(), Some("Existing"));
+    assert_eq!(
+        target
+            .get_cached_item("lnurl_withdraw_links".to_string())
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("[]")
+    );
+    assert_eq!(
+        target
+            .get_cached_item("token_distribution_key".to_string())
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("existing")
+    );
+    assert!(
+        target
+            .get_cached_item("account_info".to_string())
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Importing the same backup again has no effect
+    let backup: StorageBackup = serde_json::from_slice(&serialized).unwrap();
+    let response = backup.merge_into(target.as_ref()).await.unwrap();
+    assert_eq!(response, crate::ImportBackupResponse::default());
+}
@@ -6,7 +6,7 @@ use std::{
 };
 
 use breez_sdk_common::sync::{
-    IncomingChange as CommonIncomingChange, NewRecordHandler,
+    ConflictResolution, FieldConflict, IncomingChange as CommonIncomingChange, NewRecordHandler,
     OutgoingChange as CommonOutgoingChange, RecordChangeRequest, RecordId, RecordOutcome,
     SchemaVersion, SyncService,
 };
@@ -17,34 +17,73 @@ use crate::{
     DepositInfo, EventEmitter, Payment, PaymentDetails, PaymentMetadata, Storage, StorageError,
     UpdateDepositPayload,
     events::InternalSyncedEvent,
-    persist::StorageListPaymentsRequest,
+    persist::{LIGHTNING_ADDRESS_KEY, SYNCED_VALUE_KEY_PREFIX, StorageListPaymentsRequest},
     sync_storage::{IncomingChange, OutgoingChange, Record, UnversionedRecordChange},
 };
 use tokio_with_wasm::alias as tokio;
 
-const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion::new(1, 0, 0);
+/// 1.1.0 added the `LightningAddress` and `SyncedValue` record types. Older instances defer
+/// records of these types until they're upgraded.
+const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion::new(1, 1, 0);
 
 const INITIAL_SYNC_CACHE_KEY: &str = "sync_initial_complete";
+const INITIAL_CACHED_ITEMS_SYNC_CACHE_KEY: &str = "sync_initial_cached_items_complete";
+
+/// The field holding the value of a synced cached item. A null value removes the item.
+const CACHED_ITEM_VALUE_FIELD: &str = "value";
 
 enum RecordType {
     PaymentMetadata,
+    LightningAddress,
+    SyncedValue,
+}
+
+/// Whether the value is the marker of a lightning address recovered as not registered. The
+/// recovery could be stale, so the marker isn't synced: it must not overwrite an address
+/// registered by another instance.
+fn is_not_registered_lightning_address(key: &str, value: &str) -> bool {
+    key == LIGHTNING_ADDRESS_KEY && value == "null"
 }
 
 impl Display for RecordType {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         let s = match self {
             RecordType::PaymentMetadata => "PaymentMetadata",
+            RecordType::LightningAddress => "LightningAddress",
+            RecordType::SyncedValue => "SyncedValue",
         };
         write!(f, "{s}")
     }
 }
 
+impl RecordType {
+    /// The record id of a cached item that is replicated through real-time sync, if it is one.
+    fn cached_item_record_id(key: &str) -> Option<RecordId> {
+        if key == LIGHTNING_ADDRESS_KEY {
+            return Some(RecordId::new(RecordType::LightningAddress.to_string(), key));
+        }
+        key.strip_prefix(SYNCED_VALUE_KEY_PREFIX)
+            .map(|data_id| RecordId::new(RecordType::SyncedValue.to_string(), data_id))
+    }
+
+    /// The key of the cached item a record of this type is stored in.
+    fn cached_item_key(&self, data_id: &str) -> Option<String> {
+        match self {
+            RecordType::PaymentMetadata => None,
+            RecordType::LightningAddress => Some(LIGHTNING_ADDRESS_KEY.to_string()),
+            RecordType::SyncedValue => Some(format!("{SYNCED_VALUE_KEY_PREFIX}{data_id}")),
+        }
+    }
+}
+
 impl FromStr for RecordType {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "PaymentMetadata" => Ok(RecordType::PaymentMetadata),
+            "LightningAddress" => Ok(RecordType::LightningAddress),
+            "SyncedValue" => Ok(RecordType::SyncedValue),
             _ => Err(format!("Unknown record type: {s}")),
         }
     }
@@ -69,6 +108,16 @@ impl NewRecordHandler for SyncedStorage {
         self.handle_outgoing_change(change).await
     }
 
+    fn resolve_conflict(&self, id: &RecordId, conflict: &FieldConflict) -> ConflictResolution {
+        // The local change is pushed after the incoming one, so keeping it makes the last write win.
+        // None of the synced fields are user-editable text yet, which would need a smarter merge.
+        debug!(
+            "Keeping local value of field '{}' of record {:?} over remote value",
+            conflict.field, id
+        );
+        ConflictResolution::KeepLocal
+    }
+
     async fn on_sync_completed(
         &self,
         incoming_count: Option<u32>,
@@ -115,6 +164,9 @@ impl SyncedStorage {
                 if let Err(e) = clone.feed_existing_payment_metadata().await {
                     error!("Failed to feed existing payment metadata for sync: {}", e);
                 }
+                if let Err(e) = clone.feed_existing_cached_items().await {
+                    error!("Failed to feed existing cached items for sync: {}", e);
+                }
             }
             .instrument(span),
         );
@@ -190,6 +242,35 @@ impl SyncedStorage {
         Ok(())
     }
 
+    /// Feed the synced cached items stored before they were synced into sync storage, once.
+    async fn feed_existing_cached_items(&self) -> anyhow::Result<()> {
+        if self
+            .get_cached_item(INITIAL_CACHED_ITEMS_SYNC_CACHE_KEY.to_string())
+            .await?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        for (key, value) in self.inner.list_cached_items().await? {
+            let Some(record_id) = RecordType::cached_item_record_id(&key) else {
+                continue;
+            };
+            if is_not_registered_lightning_address(&key, &value) {
+                continue;
+            }
+            self.set_outgoing_cached_item(record_id, Some(value))
+                .await?;
+        }
+
+        self.set_cached_item(
+            INITIAL_CACHED_ITEMS_SYNC_CACHE_KEY.to_string(),
+            "true".to_string(),
+        )
+        .await?;
+        Ok(())
+    }
+
     async fn handle_incoming_change(
         &self,
         change: CommonIncomingChange,
@@ -209,6 +290,8 @@ impl SyncedStorage {
             return Ok(RecordOutcome::Deferred);
         }
 
+        // Local changes that weren't pushed yet are kept on top of the incoming state.
+        let merged_state = change.merged_state();
         let Ok(record_type) = RecordType::from_str(&change.new_state.id.r#type) else {
             warn!(
                 "Deferring incoming record with unknown type '{}' at schema version {}",
@@ -218,9 +301,15 @@ impl SyncedStorage {
         };
         match record_type {
             RecordType::PaymentMetadata => {
-                self.handle_payment_metadata_update(
-                    change.new_state.data,
-                    change.new_state.id.data_id,
+                self.handle_payment_metadata_update(merged_state.data, merged_state.id.data_id)
+                    .await?;
+                Ok(RecordOutcome::Completed)
+            }
+            RecordType::LightningAddress | RecordType::SyncedValue => {
+                self.handle_cached_item_update(
+                    &record_type,
+                    &merged_state.data,
+                    &merged_state.id.data_id,
                 )
                 .await?;
                 Ok(RecordOutcome::Completed)
@@ -240,6 +329,14 @@ impl SyncedStorage {
                 )
                 .await
             }
+            RecordType::LightningAddress | RecordType::SyncedValue => {
+                self.handle_cached_item_update(
+                    &record_type,
+                    &change.change.updated_fields,
+                    &change.change.id.data_id,
+                )
+                .await
+            }
         }
     }
 
@@ -259,19 +356,69 @@ impl SyncedStorage {
             .await?;
         Ok(())
     }
+
+    /// Applies a synced cached item. The record holds the whole value, so the last write wins.
+    async fn handle_cached_item_update(
+        &self,
+        record_type: &RecordType,
+        fields: &HashMap<String, Value>,
+        data_id: &str,
+    ) -> anyhow::Result<()> {
+        let Some(key) = record_type.cached_item_key(data_id) else {
+            return Err(anyhow::anyhow!(
+                "Record type {record_type} is not a cached item"
+            ));
+        };
+        match fields.get(CACHED_ITEM_VALUE_FIELD).and_then(Value::as_str) {
+            Some(value) => self.inner.set_cached_item(key, value.to_string()).await?,
+            None => self.inner.delete_cached_item(key).await?,
+        }
+        Ok(())
+    }
+
+    async fn set_outgoing_cached_item(
+        &self,
+        id: RecordId,
+        value: Option<String>,
+    ) -> Result<(), StorageError> {
+        self.sync_service
+            .set_outgoing_record(&RecordChangeRequest {
+                id,
+                schema_version: CURRENT_SCHEMA_VERSION,
+                updated_fields: HashMap::from([(
+                    CACHED_ITEM_VALUE_FIELD.to_string(),
+                    value.map_or(Value::Null, Value::String),
+                )]),
+            })
+            .await
+            .map_err(|e| StorageError::Implementation(e.to_string()))
+    }
 }
 
 #[macros::async_trait]
 impl Storage for SyncedStorage {
     async fn delete_cached_item(&self, key: String) -> Result<(), StorageError> {
+        if let Some(record_id) = RecordType::cached_item_record_id(&key) {
+            self.set_outgoing_cached_item(record_id, None).await?;
+        }
         self.inner.delete_cached_item(key).await
     }
     async fn get_cached_item(&self, key: String) -> Result<Option<String>, StorageError> {
         self.inner.get_cached_item(key).await
     }
     async fn set_cached_item(&self, key: String, value: String) -> Result<(), StorageError> {
+        // Set the outgoing record for sync before updating local storage.
+        if let Some(record_id) = RecordType::cached_item_record_id(&key)
+            && !is_not_registered_lightning_address(&key, &value)
+        {
+            self.set_outgoing_cached_item(record_id, Some(value.clone()))
+                .await?;
+        }
         self.inner.set_cached_item(key, value).await
     }
+    async fn list_cached_items(&self) -> Result<HashMap<String, String>, StorageError> {
+        self.inner.list_cached_items().await
+    }
     async fn list_payments(
         &self,
         request: StorageListPaymentsRequest,
@@ -306,6 +453,12 @@ impl Storage for SyncedStorage {
             .await
     }
 
+    async fn list_payment_metadata(
+        &self,
+    ) -> Result<HashMap<String, PaymentMetadata>, StorageError> {
+        self.inner.list_payment_metadata().await
+    }
+
     async fn get_payment_by_id(&self, id: String) -> Result<Payment, StorageError> {
         self.inner.get_payment_by_id(id).await
     }
@@ -359,6 +512,12 @@ impl Storage for SyncedStorage {
         self.inner.set_lnurl_metadata(metadata).await
     }
 
+    async fn list_lnurl_metadata(
+        &self,
+    ) -> Result<Vec<crate::persist::SetLnurlMetadataItem>, StorageError> {
+        self.inner.list_lnurl_metadata().await
+    }
+
     async fn add_outgoing_change(
         &self,
         record: UnversionedRecordChange,
@@ -406,4 +565,192 @@ impl Storage for SyncedStorage {
     async fn update_record_from_incoming(&self, record: Record) -> Result<(), StorageError> {
         self.inner.update_record_from_incoming(record).await
     }
+
+    async fn get_sync_state_records(&self) -> Result<Vec<Record>, StorageError> {
+        self.inner.get_sync_state_records().await
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use breez_sdk_common::sync::{
+        IncomingChange, NewRecordHandler, Record, RecordId, RecordOutcome, SchemaVersion,
+        SyncService,
+    };
+    use serde_json::Value;
+
+    use crate::{
+        EventEmitter,
+        persist::{ObjectCacheRepository, Storage, sqlite::SqliteStorage},
+        sync_storage::SyncStorageWrapper,
+    };
+
+    use super::{CURRENT_SCHEMA_VERSION, SyncedStorage};
+
+    fn create_synced_storage(name: &str) -> (Arc<SyncedStorage>, Arc<SqliteStorage>) {
+        let mut path = std::env::temp_dir();
+        path.push(format!("breez-test-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&path).unwrap();
+        let inner = Arc::new(SqliteStorage::new(&path).expect("Failed to create storage"));
+        let sync_service = Arc::new(SyncService::new(Arc::new(SyncStorageWrapper::new(
+            inner.clone(),
+        ))));
+        let synced = Arc::new(SyncedStorage::new(
+            inner.clone(),
+            sync_service,
+            Arc::new(EventEmitter::new(true)),
+        ));
+        (synced, inner)
+    }
+
+    fn incoming_change(
+        r#type: &str,
+        data_id: &str,
+        schema_version: SchemaVersion,
+        value: Value,
+    ) -> IncomingChange {
+        IncomingChange {
+            new_state: Record {
+                id: RecordId::new(r#type, data_id),
+                revision: 1,
+                schema_version,
+                data: HashMap::from([("value".to_string(), value)]),
+            },
+            old_state: None,
+            pending_outgoing_changes: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_synced_value_replication() {
+        let (synced_a, inner_a) = create_synced_storage("synced_value_a");
+        let (synced_b, inner_b) = create_synced_storage("synced_value_b");
+
+        // Setting a value on one instance queues it for sync
+        ObjectCacheRepository::new(synced_a)
+            .save_synced_value("app", "currency", Some("EUR".to_string()))
+            .await
+            .unwrap();
+        let pending = inner_a.get_pending_outgoing_changes(10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].change.id.r#type, "SyncedValue");
+        assert_eq!(pending[0].change.id.data_id, "app/currency");
+        assert_eq!(pending[0].change.updated_fields["value"], "\"EUR\"");
+
+        // The other instance stores the incoming value without syncing it back
+        let outcome = synced_b
+            .on_incoming_change(incoming_change(
+                "SyncedValue",
+                "app/currency",
+                CURRENT_SCHEMA_VERSION,
+                Value::String("EUR".to_string()),
+            ))
+            .await
+            .unwrap();
+        assert!(matches!(outcome, RecordOutcome::Completed));
+        let cache_b = ObjectCacheRepository::new(synced_b.clone());
+        assert_eq!(
+            cache_b.fetch_synced_value("app", "currency").await.unwrap(),
+            Some("EUR".to_string())
+        );
+        assert!(
+            inner_b
+                .get_pending_outgoing_changes(10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // The last write wins, a null value removes the key
+        synced_b
+            .on_incoming_change(incoming_change(
+                "SyncedValue",
+                "app/currency",
+                CURRENT_SCHEMA_VERSION,
+                Value::Null,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            cache_b.fetch_synced_value("app", "currency").await.unwrap(),
+            None
+        );
+
+        // Values written by a newer major schema version are deferred
+        let outcome = synced_b
+            .on_incoming_change(incoming_change(
+                "SyncedValue",
+                "app/currency",
+                SchemaVersion::new(CURRENT_SCHEMA_VERSION.major + 1, 0, 0),
+                Value::String("USD".to_string()),
+            ))
+            .await
+            .unwrap();
+        assert!(matches!(outcome, RecordOutcome::Deferred));
+        assert_eq!(
+            cache_b.fetch_synced_value("app", "currency").await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lightning_address_replication() {
+        let (synced_a, inner_a) = create_synced_storage("lightning_address_a");
+        let (synced_b, _inner_b) = create_synced_storage("lightning_address_b");
+
+        synced_a
+            .set_cached_item("lightning_address".to_string(), "{}".to_string())
+            .await
+            .unwrap();
+        let pending = inner_a.get_pending_outgoing_changes(10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].change.id.r#type, "LightningAddress");
+
+        synced_b
+            .on_incoming_change(incoming_change(
+                "LightningAddress",
+                "lightning_address",
+                CURRENT_SCHEMA_VERSION,
+                Value::String("{}".to_string()),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            synced_b
+                .get_cached_item("lightning_address".to_string())
+                .await
+                .unwrap(),
+            Some("{}".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lightning_address_not_registered_marker_is_not_synced() {
+        let (synced, inner) = create_synced_storage("lightning_address_marker");
+        let cache = ObjectCacheRepository::new(synced);
+
+        // A recovery without address only marks the local instance as recovered
+        cache.mark_lightning_address_not_registered().await.unwrap();
+        assert!(matches!(
+            cache.fetch_lightning_address().await.unwrap(),
+            Some(None)
+        ));
+        assert!(
+            inner
+                .get_pending_outgoing_changes(10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Unregistering is synced as a removal
+        cache.delete_lightning_address().await.unwrap();
+        let pending = inner.get_pending_outgoing_changes(10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].change.id.r#type, "LightningAddress");
+        assert_eq!(pending[0].change.updated_fields["value"], "null");
+    }
 }
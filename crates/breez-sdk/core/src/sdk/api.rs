@@ -1,11 +1,13 @@
 use bitcoin::secp256k1::{PublicKey, ecdsa::Signature};
 use std::str::FromStr;
 use tracing::{error, info};
+use web_time::{SystemTime, UNIX_EPOCH};
 
 use crate::{
     BuyBitcoinRequest, BuyBitcoinResponse, CheckMessageRequest, CheckMessageResponse,
-    GetTokensMetadataRequest, GetTokensMetadataResponse, InputType, ListFiatCurrenciesResponse,
-    ListFiatRatesResponse, OptimizationProgress, SignMessageRequest, SignMessageResponse,
+    GetSyncedValueRequest, GetSyncedValueResponse, GetTokensMetadataRequest,
+    GetTokensMetadataResponse, InputType, ListFiatCurrenciesResponse, ListFiatRatesResponse,
+    OptimizationProgress, SetSyncedValueRequest, SignMessageRequest, SignMessageResponse,
     UpdateUserSettingsRequest, UserSettings,
     chain::RecommendedFees,
     error::SdkError,
@@ -13,10 +15,17 @@ use crate::{
     issuer::TokenIssuer,
     models::{GetInfoRequest, GetInfoResponse},
     persist::ObjectCacheRepository,
-    utils::token::get_tokens_metadata_cached_or_query,
+    utils::token::{apply_frozen_tokens, get_tokens_metadata_cached_or_query},
 };
 
-use super::{BreezSdk, helpers::get_or_create_deposit_address, parse_input};
+use super::{
+    BreezSdk,
+    helpers::{get_or_create_deposit_address, set_token_registry_info},
+    parse_input,
+};
+
+/// Synced values are meant for small preferences, every change is a real-time sync record.
+const MAX_SYNCED_VALUE_LENGTH: usize = 4096;
 
 #[cfg_attr(feature = "uniffi", uniffi::export(async_runtime = "tokio"))]
 #[allow(clippy::needless_pass_by_value)]
@@ -87,10 +96,31 @@ impl BreezSdk {
             .fetch_account_info()
             .await?
             .unwrap_or_default();
+        let mut token_balances = account_info.token_balances;
+        apply_frozen_tokens(
+            &mut token_balances,
+            &object_repository.fetch_frozen_tokens().await?,
+            SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        );
+        let mut tokens_metadata: Vec<_> = token_balances
+            .values()
+            .map(|b| b.token_metadata.clone())
+            .collect();
+        set_token_registry_info(
+            self.token_registry_service.as_ref(),
+            self.storage.clone(),
+            &mut tokens_metadata,
+        )
+        .await?;
+        for metadata in tokens_metadata {
+            if let Some(balance) = token_balances.get_mut(&metadata.identifier) {
+                balance.token_metadata = metadata;
+            }
+        }
         Ok(GetInfoResponse {
             identity_pubkey: self.spark_wallet.get_identity_public_key().to_string(),
             balance_sats: account_info.balance_sats,
-            token_balances: account_info.token_balances,
+            token_balances,
         })
     }
 
@@ -130,11 +160,13 @@ impl BreezSdk {
     ///
     /// If the metadata is not found locally in cache, it will be queried from
     /// the Spark network and then cached.
+    ///
+    /// If a token registry service is configured, the registry info of each token is included.
     pub async fn get_tokens_metadata(
         &self,
         request: GetTokensMetadataRequest,
     ) -> Result<GetTokensMetadataResponse, SdkError> {
-        let metadata = get_tokens_metadata_cached_or_query(
+        let mut metadata = get_tokens_metadata_cached_or_query(
             &self.spark_wallet,
             &ObjectCacheRepository::new(self.storage.clone()),
             &request
@@ -144,6 +176,12 @@ impl BreezSdk {
                 .collect::<Vec<_>>(),
         )
         .await?;
+        set_token_registry_info(
+            self.token_registry_service.as_ref(),
+            self.storage.clone(),
+            &mut metadata,
+        )
+        .await?;
         Ok(GetTokensMetadataResponse {
             tokens_metadata: metadata,
         })
@@ -204,10 +242,10 @@ impl BreezSdk {
         // Ensure spark private mode is initialized to avoid race conditions with the initialization task.
         self.ensure_spark_private_mode_initialized().await?;
 
+        // The settings are stored by the Spark operators, so all instances of the wallet already
+        // share them without real-time sync.
         let spark_user_settings = self.spark_wallet.query_wallet_settings().await?;
 
-        // We may in the future have user settings that are stored locally and synced using real-time sync.
-
         Ok(UserSettings {
             spark_private_mode_enabled: spark_user_settings.private_enabled,
         })
@@ -240,6 +278,13 @@ impl BreezSdk {
                 .register_lightning_address_internal(crate::RegisterLightningAddressRequest {
                     username: lightning_address.username,
                     description: Some(lightning_address.description),
+                    min_sendable: lightning_address.min_sendable,
+                    max_sendable: lightning_address.max_sendable,
+                    comment_allowed: lightning_address.comment_allowed,
+                    image: lightning_address.image,
+                    long_description: lightning_address.long_description,
+                    nostr_identity: lightning_address.nostr_identity,
+                    publish_bip353: Some(lightning_address.bip353_address.is_some()),
                 })
                 .await
             {
@@ -249,6 +294,38 @@ impl BreezSdk {
         Ok(())
     }
 
+    /// Sets a small value, like an app preference, that is shared by all instances of the wallet.
+    ///
+    /// The value is replicated through real-time sync, and stored locally only when real-time
+    /// sync is disabled. When instances set the same key concurrently, the last write wins.
+    pub async fn set_synced_value(&self, request: SetSyncedValueRequest) -> Result<(), SdkError> {
+        validate_synced_key(&request.namespace, &request.key)?;
+        if let Some(value) = &request.value
+            && value.len() > MAX_SYNCED_VALUE_LENGTH
+        {
+            return Err(SdkError::InvalidInput(format!(
+                "Synced value exceeds the maximum length of {MAX_SYNCED_VALUE_LENGTH} bytes"
+            )));
+        }
+
+        ObjectCacheRepository::new(self.storage.clone())
+            .save_synced_value(&request.namespace, &request.key, request.value)
+            .await?;
+        Ok(())
+    }
+
+    /// Returns a value set with [`BreezSdk::set_synced_value`] by any instance of the wallet.
+    pub async fn get_synced_value(
+        &self,
+        request: GetSyncedValueRequest,
+    ) -> Result<GetSyncedValueResponse, SdkError> {
+        validate_synced_key(&request.namespace, &request.key)?;
+        let value = ObjectCacheRepository::new(self.storage.clone())
+            .fetch_synced_value(&request.namespace, &request.key)
+            .await?;
+        Ok(GetSyncedValueResponse { value })
+    }
+
     /// Returns an instance of the [`TokenIssuer`] for managing token issuance.
     pub fn get_token_issuer(&self) -> TokenIssuer {
         TokenIssuer::new(self.spark_wallet.clone(), self.storage.clone())
@@ -310,3 +387,17 @@ impl BreezSdk {
         Ok(BuyBitcoinResponse { url })
     }
 }
+
+fn validate_synced_key(namespace: &str, key: &str) -> Result<(), SdkError> {
+    if namespace.is_empty() || key.is_empty() {
+        return Err(SdkError::InvalidInput(
+            "Synced value namespace and key must not be empty".to_string(),
+        ));
+    }
+    if namespace.contains('/') {
+        return Err(SdkError::InvalidInput(
+            "Synced value namespace must not contain '/'".to_string(),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use tracing::info;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    ExportBackupResponse, ImportBackupRequest, ImportBackupResponse, error::SdkError,
+    persist::backup::StorageBackup,
+};
+
+use super::{BreezSdk, SyncType};
+
+/// Version of the backup format, the first byte of a backup. The rest is the encrypted json
+/// snapshot.
+const BACKUP_VERSION: u8 = 1;
+
+#[cfg_attr(feature = "uniffi", uniffi::export(async_runtime = "tokio"))]
+#[allow(clippy::needless_pass_by_value)]
+impl BreezSdk {
+    /// Exports an encrypted backup of the wallet data that can't be recovered from the Spark
+    /// operators, like payment metadata, lightning address comments, deposit refunds, withdraw
+    /// links and token distributions.
+    ///
+    /// The backup is encrypted with a key derived from the wallet seed, so it can be stored
+    /// anywhere. Restore it with [`BreezSdk::import_backup`].
+    pub async fn export_backup(&self) -> Result<ExportBackupResponse, SdkError> {
+        let created_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let snapshot = StorageBackup::from_storage(self.storage.as_ref(), created_at).await?;
+        let encrypted = self
+            .backup_signer
+            .encrypt(&serde_json::to_vec(&snapshot)?)
+            .await?;
+
+        let mut backup = Vec::with_capacity(encrypted.len().saturating_add(1));
+        backup.push(BACKUP_VERSION);
+        backup.extend(encrypted);
+        info!(
+            "Exported backup with {} payments, {} payment metadata, {} deposits, {} lnurl metadata and {} cached items",
+            snapshot.payments.len(),
+            snapshot.payment_metadata.len(),
+            snapshot.deposits.len(),
+            snapshot.lnurl_metadata.len(),
+            snapshot.cached_items.len()
+        );
+        Ok(ExportBackupResponse { backup })
+    }
+
+    /// Imports a backup created by [`BreezSdk::export_backup`] of the same wallet.
+    ///
+    /// The backup is merged into the wallet data: data already stored takes precedence, the
+    /// backup only fills in what's missing. Importing the same backup twice has no effect.
+    pub async fn import_backup(
+        &self,
+        request: ImportBackupRequest,
+    ) -> Result<ImportBackupResponse, SdkError> {
+        let Some((version, encrypted)) = request.backup.split_first() else {
+            return Err(SdkError::InvalidInput("Empty backup".to_string()));
+        };
+        if *version != BACKUP_VERSION {
+            return Err(SdkError::InvalidInput(format!(
+                "Unsupported backup version: {version}"
+            )));
+        }
+        let decrypted = self.backup_signer.decrypt(encrypted).await.map_err(|_| {
+            SdkError::InvalidInput(
+                "Failed to decrypt backup, it was created by another wallet or network".to_string(),
+            )
+        })?;
+        let snapshot: StorageBackup = serde_json::from_slice(&decrypted)?;
+
+        let response = snapshot.merge_into(self.storage.as_ref()).await?;
+        info!("Imported backup: {response:?}");
+        self.sync_coordinator
+            .trigger_sync_no_wait(SyncType::Full, true)
+            .await;
+        Ok(response)
+    }
+}
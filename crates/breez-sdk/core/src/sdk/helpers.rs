@@ -11,7 +11,8 @@ use tracing::{error, info};
 use x509_parser::parse_x509_certificate;
 
 use crate::{
-    PaymentDetails, WaitForPaymentIdentifier,
+    PaymentDetails, TokenMetadata, TokenRegistryService, WaitForPaymentIdentifier,
+    common::token_registry::TokenRegistry,
     error::SdkError,
     events::{EventListener, SdkEvent},
     models::Payment,
@@ -107,6 +108,20 @@ pub(crate) async fn update_balances(
     Ok(())
 }
 
+/// Sets the registry info of the given tokens metadata if a token registry service is configured.
+pub(crate) async fn set_token_registry_info(
+    token_registry_service: Option<&Arc<dyn TokenRegistryService>>,
+    storage: Arc<dyn Storage>,
+    tokens_metadata: &mut [TokenMetadata],
+) -> Result<(), SdkError> {
+    let Some(service) = token_registry_service else {
+        return Ok(());
+    };
+    TokenRegistry::new(Arc::clone(service), storage)
+        .enrich(tokens_metadata)
+        .await
+}
+
 pub(crate) struct InternalEventListener {
     tx: mpsc::Sender<SdkEvent>,
 }
@@ -68,13 +68,16 @@ impl BreezSdk {
             storage: params.storage,
             chain_service: params.chain_service,
             fiat_service: params.fiat_service,
+            token_registry_service: params.token_registry_service,
             lnurl_client: params.lnurl_client,
             lnurl_server_client: params.lnurl_server_client,
             lnurl_auth_signer: params.lnurl_auth_signer,
+            backup_signer: params.backup_signer,
             event_emitter: params.event_emitter,
             shutdown_sender: params.shutdown_sender,
             sync_coordinator,
             lnurl_preimage_trigger: tokio::sync::broadcast::channel(10).0,
+            lnurl_withdraw_trigger: tokio::sync::broadcast::channel(10).0,
             initial_synced_watcher,
             external_input_parsers,
             spark_private_mode_initialized: Arc::new(OnceCell::new()),
@@ -94,11 +97,13 @@ impl BreezSdk {
     /// 2. `periodic_sync`: syncs the wallet with the Spark network
     /// 3. `try_recover_lightning_address`: recovers the lightning address on startup
     /// 4. `spawn_lnurl_preimage_publisher`: publishes lnurl preimages for completed payments
+    /// 5. `spawn_lnurl_withdraw_processor`: pays invoices submitted to lnurl withdraw links
     pub(super) fn start(&self, initial_synced_sender: watch::Sender<bool>) {
         self.spawn_spark_private_mode_initialization();
         self.periodic_sync(initial_synced_sender);
         self.try_recover_lightning_address();
         self.spawn_lnurl_preimage_publisher();
+        self.spawn_lnurl_withdraw_processor();
     }
 
     fn spawn_spark_private_mode_initialization(&self) {
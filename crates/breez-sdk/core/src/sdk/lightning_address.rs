@@ -1,4 +1,4 @@
-use lnurl_models::sanitize_username;
+use lnurl_models::{LnurlPaySettings, sanitize_username};
 
 use crate::{
     CheckLightningAddressRequest, LightningAddressInfo, LnurlInfo, RegisterLightningAddressRequest,
@@ -86,7 +86,7 @@ impl BreezSdk {
             cache.save_lightning_address(&address_info).await?;
             Some(address_info)
         } else {
-            cache.delete_lightning_address().await?;
+            cache.mark_lightning_address_not_registered().await?;
             None
         };
 
@@ -111,10 +111,20 @@ impl BreezSdk {
             None => format!("Pay to {}@{}", username, client.domain()),
         };
 
+        let pay_settings = LnurlPaySettings {
+            min_sendable: request.min_sendable,
+            max_sendable: request.max_sendable,
+            comment_allowed: request.comment_allowed,
+            image: request.image,
+            long_description: request.long_description,
+        };
         let params = crate::lnurl::RegisterLightningAddressRequest {
             username: username.clone(),
             description: description.clone(),
             lnurl_private_mode_enabled: !self.config.support_lnurl_verify,
+            pay_settings: pay_settings.clone(),
+            nostr: request.nostr_identity.clone().map(Into::into),
+            publish_bip353: request.publish_bip353.unwrap_or(false),
         };
 
         let response = client.register_lightning_address(&params).await?;
@@ -123,6 +133,13 @@ impl BreezSdk {
             description,
             lnurl: LnurlInfo::new(response.lnurl),
             username,
+            min_sendable: pay_settings.min_sendable,
+            max_sendable: pay_settings.max_sendable,
+            comment_allowed: pay_settings.comment_allowed,
+            image: pay_settings.image,
+            long_description: pay_settings.long_description,
+            nostr_identity: request.nostr_identity,
+            bip353_address: response.bip353_address,
         };
         cache.save_lightning_address(&address_info).await?;
         Ok(address_info)
@@ -159,6 +176,13 @@ mod tests {
             username: "test".to_string(),
             description: "Test address".to_string(),
             lnurl: LnurlInfo::new("https://example.com/.well-known/lnurlp/test".to_string()),
+            min_sendable: None,
+            max_sendable: None,
+            comment_allowed: None,
+            image: None,
+            long_description: None,
+            nostr_identity: None,
+            bip353_address: None,
         }
     }
 
@@ -173,25 +197,41 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_fetch_returns_some_none_after_delete() {
-        let (storage, _dir) = create_temp_storage("after_delete");
+    async fn test_fetch_returns_some_none_after_mark_not_registered() {
+        let (storage, _dir) = create_temp_storage("after_mark_not_registered");
         let cache = ObjectCacheRepository::new(storage as Arc<_>);
 
-        // Save an address, then delete it
+        // Save an address, then recover none
         cache
             .save_lightning_address(&sample_address_info())
             .await
             .unwrap();
-        cache.delete_lightning_address().await.unwrap();
+        cache.mark_lightning_address_not_registered().await.unwrap();
 
         // Key present, value null -> Some(None) (recovered, no address)
         let result = cache.fetch_lightning_address().await.unwrap();
         assert!(
             matches!(result, Some(None)),
-            "Expected Some(None) after delete"
+            "Expected Some(None) after marking not registered"
         );
     }
 
+    #[tokio::test]
+    async fn test_fetch_returns_none_after_delete() {
+        let (storage, _dir) = create_temp_storage("after_delete");
+        let cache = ObjectCacheRepository::new(storage as Arc<_>);
+
+        cache
+            .save_lightning_address(&sample_address_info())
+            .await
+            .unwrap();
+        cache.delete_lightning_address().await.unwrap();
+
+        // Key absent -> None, the address is recovered again
+        let result = cache.fetch_lightning_address().await.unwrap();
+        assert!(result.is_none(), "Expected None after delete");
+    }
+
     #[tokio::test]
     async fn test_fetch_returns_some_some_after_save() {
         let (storage, _dir) = create_temp_storage("after_save");
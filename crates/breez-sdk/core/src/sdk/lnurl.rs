@@ -44,10 +44,12 @@ impl BreezSdk {
                 .await;
         }
 
+        let payer_data = self.build_lnurl_payer_data(&request).await?;
         let success_data = match validate_lnurl_pay(
             self.lnurl_client.as_ref(),
             amount_sats.saturating_mul(1_000),
             &request.comment,
+            payer_data.as_ref(),
             &request.pay_request.clone().into(),
             self.config.network.into(),
             request.validate_success_action_url,
@@ -90,6 +92,7 @@ impl BreezSdk {
             success_action: success_data.success_action.map(From::from),
             conversion_estimate: prepare_response.conversion_estimate,
             fee_policy,
+            payer_data: payer_data.map(From::from),
         })
     }
 
@@ -403,10 +406,12 @@ impl BreezSdk {
 
         // 2. First query: get invoice for full amount to estimate fees
         // Note: We don't intend to pay this invoice. It's only for fee estimation.
+        let payer_data = self.build_lnurl_payer_data(&request).await?;
         let first_invoice = validate_lnurl_pay(
             self.lnurl_client.as_ref(),
             amount_sats.saturating_mul(1_000), // convert to msats
             &request.comment,
+            payer_data.as_ref(),
             &request.pay_request.clone().into(),
             self.config.network.into(),
             request.validate_success_action_url,
@@ -441,6 +446,7 @@ impl BreezSdk {
             self.lnurl_client.as_ref(),
             actual_amount.saturating_mul(1_000),
             &request.comment,
+            payer_data.as_ref(),
             &request.pay_request.clone().into(),
             self.config.network.into(),
             request.validate_success_action_url,
@@ -487,9 +493,35 @@ impl BreezSdk {
             success_action: success_data.success_action.map(From::from),
             conversion_estimate: None,
             fee_policy: FeePolicy::FeesIncluded,
+            payer_data: payer_data.map(From::from),
         })
     }
 
+    /// Builds the LUD-18 payer data to send, if the service requests it.
+    async fn build_lnurl_payer_data(
+        &self,
+        request: &PrepareLnurlPayRequest,
+    ) -> Result<Option<lnurl::pay::LnurlPayerData>, SdkError> {
+        let Some(payer_data_request) = &request.pay_request.payer_data else {
+            return Ok(None);
+        };
+        let options = request.payer_data.clone().unwrap_or_default();
+        let payer_data = lnurl::pay::build_payer_data(
+            &payer_data_request.clone().into(),
+            &request.pay_request.url,
+            options.name,
+            options.identifier,
+            options.share_linking_key,
+            self.lnurl_auth_signer.as_ref(),
+        )
+        .await
+        .map_err(|e| match e {
+            LnurlError::MissingPayerData => SdkError::InvalidInput(e.to_string()),
+            _ => e.into(),
+        })?;
+        Ok(Some(payer_data))
+    }
+
     /// Background task that publishes lnurl preimages for received lnurl payments for nostr zaps
     /// and LNURL verify. Triggered on startup and after syncing lnurl metadata.
     pub(super) fn spawn_lnurl_preimage_publisher(&self) {
@@ -593,6 +625,9 @@ impl BreezSdk {
                         nostr_zap_request: metadata.nostr_zap_request,
                         nostr_zap_receipt: metadata.nostr_zap_receipt,
                         preimage: Some(preimage),
+                        payer_data: metadata
+                            .payer_data
+                            .and_then(|payer_data| serde_json::to_string(&payer_data).ok()),
                     }])
                     .await
                 {
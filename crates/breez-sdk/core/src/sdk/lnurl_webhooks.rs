@@ -0,0 +1,72 @@
+use crate::{
+    DeleteLnurlWebhookRequest, ListLnurlWebhooksResponse, RegisterLnurlWebhookRequest,
+    RegisterLnurlWebhookResponse, error::SdkError,
+};
+
+use super::BreezSdk;
+
+#[cfg_attr(feature = "uniffi", uniffi::export(async_runtime = "tokio"))]
+#[allow(clippy::needless_pass_by_value)]
+impl BreezSdk {
+    /// Registers a webhook on the LNURL server, notified when an invoice is requested from or
+    /// paid to the lightning address. Deliveries are signed with the returned secret and retried
+    /// until the webhook responds with a 2xx status.
+    ///
+    /// A lightning address must be registered before registering webhooks.
+    pub async fn register_lnurl_webhook(
+        &self,
+        request: RegisterLnurlWebhookRequest,
+    ) -> Result<RegisterLnurlWebhookResponse, SdkError> {
+        let Some(client) = &self.lnurl_server_client else {
+            return Err(SdkError::Generic(
+                "LNURL server is not configured".to_string(),
+            ));
+        };
+
+        if request.events.is_empty() {
+            return Err(SdkError::InvalidInput(
+                "At least one webhook event is required".to_string(),
+            ));
+        }
+
+        let events = request
+            .events
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<lnurl_models::WebhookEvent>>();
+        let response = client.create_webhook(&request.url, &events).await?;
+        Ok(RegisterLnurlWebhookResponse {
+            webhook: response.webhook.into(),
+            secret: response.secret,
+        })
+    }
+
+    /// Lists the webhooks registered on the LNURL server.
+    pub async fn list_lnurl_webhooks(&self) -> Result<ListLnurlWebhooksResponse, SdkError> {
+        let Some(client) = &self.lnurl_server_client else {
+            return Err(SdkError::Generic(
+                "LNURL server is not configured".to_string(),
+            ));
+        };
+
+        let webhooks = client.list_webhooks().await?;
+        Ok(ListLnurlWebhooksResponse {
+            webhooks: webhooks.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Deletes a webhook from the LNURL server. Pending deliveries to it are dropped.
+    pub async fn delete_lnurl_webhook(
+        &self,
+        request: DeleteLnurlWebhookRequest,
+    ) -> Result<(), SdkError> {
+        let Some(client) = &self.lnurl_server_client else {
+            return Err(SdkError::Generic(
+                "LNURL server is not configured".to_string(),
+            ));
+        };
+
+        client.delete_webhook(&request.id).await?;
+        Ok(())
+    }
+}
@@ -1,12 +1,15 @@
 mod api;
+mod backup;
 mod deposits;
 mod helpers;
 mod init;
 mod lightning_address;
 mod lnurl;
+mod lnurl_webhooks;
 mod payments;
 mod sync;
 mod sync_coordinator;
+mod withdraw_links;
 
 pub(crate) use sync_coordinator::SyncCoordinator;
 
@@ -20,8 +23,15 @@ use tokio_with_wasm::alias as tokio;
 
 use crate::{
     BitcoinChainService, ExternalInputParser, InputType, Logger, Network, OptimizationConfig,
-    error::SdkError, events::EventEmitter, lnurl::LnurlServerClient, logger, models::Config,
-    persist::Storage, signer::lnurl_auth::LnurlAuthSignerAdapter, stable_balance::StableBalance,
+    TokenRegistryService,
+    error::SdkError,
+    events::EventEmitter,
+    lnurl::LnurlServerClient,
+    logger,
+    models::Config,
+    persist::Storage,
+    signer::{backup::BackupSigner, lnurl_auth::LnurlAuthSignerAdapter},
+    stable_balance::StableBalance,
     token_conversion::TokenConverter,
 };
 
@@ -79,14 +89,17 @@ pub struct BreezSdk {
     pub(crate) storage: Arc<dyn Storage>,
     pub(crate) chain_service: Arc<dyn BitcoinChainService>,
     pub(crate) fiat_service: Arc<dyn FiatService>,
+    pub(crate) token_registry_service: Option<Arc<dyn TokenRegistryService>>,
     pub(crate) lnurl_client: Arc<dyn HttpClient>,
     pub(crate) lnurl_server_client: Option<Arc<dyn LnurlServerClient>>,
     pub(crate) lnurl_auth_signer: Arc<LnurlAuthSignerAdapter>,
+    pub(crate) backup_signer: Arc<BackupSigner>,
     pub(crate) event_emitter: Arc<EventEmitter>,
     pub(crate) shutdown_sender: watch::Sender<()>,
     /// Coordinator for coalescing duplicate sync requests
     pub(crate) sync_coordinator: SyncCoordinator,
     pub(crate) lnurl_preimage_trigger: tokio::sync::broadcast::Sender<()>,
+    pub(crate) lnurl_withdraw_trigger: tokio::sync::broadcast::Sender<()>,
     pub(crate) initial_synced_watcher: watch::Receiver<bool>,
     pub(crate) external_input_parsers: Vec<ExternalInputParser>,
     pub(crate) spark_private_mode_initialized: Arc<OnceCell<()>>,
@@ -100,9 +113,11 @@ pub(crate) struct BreezSdkParams {
     pub storage: Arc<dyn Storage>,
     pub chain_service: Arc<dyn BitcoinChainService>,
     pub fiat_service: Arc<dyn FiatService>,
+    pub token_registry_service: Option<Arc<dyn TokenRegistryService>>,
     pub lnurl_client: Arc<dyn HttpClient>,
     pub lnurl_server_client: Option<Arc<dyn LnurlServerClient>>,
     pub lnurl_auth_signer: Arc<LnurlAuthSignerAdapter>,
+    pub backup_signer: Arc<BackupSigner>,
     pub shutdown_sender: watch::Sender<()>,
     pub spark_wallet: Arc<SparkWallet>,
     pub event_emitter: Arc<EventEmitter>,
@@ -236,6 +251,28 @@ pub fn default_external_signer(
     Ok(Arc::new(signer))
 }
 
+/// Connects to a remote signer server and returns it as an external signer.
+///
+/// The seed stays on the signer host, which can enforce its own signing policy. The returned
+/// signer can be used with `connect_with_signer` or `SdkBuilder::new_with_signer`.
+///
+/// # Arguments
+///
+/// * `config` - The URL and api key of the remote signer server
+///
+/// # Returns
+///
+/// Result containing the signer as `Arc<dyn ExternalSigner>`
+#[cfg_attr(feature = "uniffi", uniffi::export(async_runtime = "tokio"))]
+pub async fn remote_signer(
+    config: crate::signer::RemoteSignerConfig,
+) -> Result<Arc<dyn crate::signer::ExternalSigner>, SdkError> {
+    let signer = crate::signer::RemoteSigner::connect(config)
+        .await
+        .map_err(|e| SdkError::Generic(e.to_string()))?;
+    Ok(Arc::new(signer))
+}
+
 /// Fetches the current status of Spark network services relevant to the SDK.
 ///
 /// This function queries the Spark status API and returns the worst status
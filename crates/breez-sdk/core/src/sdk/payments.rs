@@ -11,9 +11,9 @@ use crate::{
     BitcoinAddressDetails, Bolt11InvoiceDetails, ClaimHtlcPaymentRequest, ClaimHtlcPaymentResponse,
     ConversionEstimate, ConversionOptions, ConversionPurpose, ConversionType, FeePolicy,
     FetchConversionLimitsRequest, FetchConversionLimitsResponse, GetPaymentRequest,
-    GetPaymentResponse, InputType, OnchainConfirmationSpeed, PaymentStatus, SendOnchainFeeQuote,
-    SendPaymentMethod, SendPaymentOptions, SparkHtlcOptions, SparkInvoiceDetails,
-    WaitForPaymentIdentifier,
+    GetPaymentResponse, InputType, ListConversionsRequest, ListConversionsResponse,
+    OnchainConfirmationSpeed, PaymentStatus, PaymentType, SendOnchainFeeQuote, SendPaymentMethod,
+    SendPaymentOptions, SparkHtlcOptions, SparkInvoiceDetails, WaitForPaymentIdentifier,
     error::SdkError,
     events::SdkEvent,
     models::{
@@ -21,19 +21,22 @@ use crate::{
         PrepareSendPaymentRequest, PrepareSendPaymentResponse, ReceivePaymentMethod,
         ReceivePaymentRequest, ReceivePaymentResponse, SendPaymentRequest, SendPaymentResponse,
     },
-    persist::PaymentMetadata,
+    persist::{
+        ObjectCacheRepository, PaymentMetadata, StorageConversionFilter, StorageListPaymentsRequest,
+    },
     token_conversion::{
         ConversionAmount, DEFAULT_CONVERSION_TIMEOUT_SECS, TokenConversionResponse,
+        conversion_info, conversions_from_payments, summarize_conversions,
     },
     utils::{
         send_payment_validation::validate_prepare_send_payment_request,
-        token::map_and_persist_token_transaction,
+        token::{is_recently_frozen, map_and_persist_token_transaction},
     },
 };
 use bitcoin::secp256k1::PublicKey;
 use spark_wallet::{InvoiceDescription, Preimage};
 use tokio_with_wasm::alias as tokio;
-use web_time::SystemTime;
+use web_time::{SystemTime, UNIX_EPOCH};
 
 use super::{
     BreezSdk, SyncType,
@@ -423,6 +426,61 @@ impl BreezSdk {
         Ok(ListPaymentsResponse { payments })
     }
 
+    /// Lists the conversions made by the wallet, including those made automatically
+    /// or as part of a payment, with totals per asset pair. Conversions are paginated
+    /// with the offset and limit, most recent first.
+    pub async fn list_conversions(
+        &self,
+        request: ListConversionsRequest,
+    ) -> Result<ListConversionsResponse, SdkError> {
+        // Each conversion has a single sent payment, so paging them pages the conversions
+        let sent_payments = self
+            .storage
+            .list_payments(StorageListPaymentsRequest {
+                type_filter: Some(vec![PaymentType::Send]),
+                conversion_filter: Some(StorageConversionFilter::default()),
+                from_timestamp: request.from_timestamp,
+                to_timestamp: request.to_timestamp,
+                offset: request.offset,
+                limit: request.limit,
+                ..Default::default()
+            })
+            .await?;
+        let conversion_ids = sent_payments
+            .iter()
+            .filter_map(|p| conversion_info(p).map(|i| i.conversion_id.clone()))
+            .collect::<Vec<_>>();
+        let conversions = if conversion_ids.is_empty() {
+            Vec::new()
+        } else {
+            let payments = self
+                .storage
+                .list_payments(StorageListPaymentsRequest {
+                    conversion_filter: Some(StorageConversionFilter {
+                        conversion_ids: Some(conversion_ids),
+                    }),
+                    ..Default::default()
+                })
+                .await?;
+            conversions_from_payments(&payments)
+        };
+
+        let all_payments = self
+            .storage
+            .list_payments(StorageListPaymentsRequest {
+                conversion_filter: Some(StorageConversionFilter::default()),
+                from_timestamp: request.from_timestamp,
+                to_timestamp: request.to_timestamp,
+                ..Default::default()
+            })
+            .await?;
+        let summaries = summarize_conversions(&conversions_from_payments(&all_payments));
+        Ok(ListConversionsResponse {
+            conversions,
+            summaries,
+        })
+    }
+
     pub async fn get_payment(
         &self,
         request: GetPaymentRequest,
@@ -450,6 +508,34 @@ impl BreezSdk {
 
 // Private payment methods
 impl BreezSdk {
+    /// Fails with [`SdkError::TokenOutputsFrozen`] if the token was recently found frozen.
+    async fn ensure_token_not_frozen(&self, token_identifier: &str) -> Result<(), SdkError> {
+        let frozen_tokens = ObjectCacheRepository::new(self.storage.clone())
+            .fetch_frozen_tokens()
+            .await?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        if is_recently_frozen(&frozen_tokens, token_identifier, now) {
+            return Err(SdkError::TokenOutputsFrozen);
+        }
+        Ok(())
+    }
+
+    async fn set_token_frozen(&self, token_identifier: &str, frozen: bool) -> Result<(), SdkError> {
+        let object_repository = ObjectCacheRepository::new(self.storage.clone());
+        let mut frozen_tokens = object_repository.fetch_frozen_tokens().await?;
+        let changed = if frozen {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            frozen_tokens.insert(token_identifier.to_string(), now);
+            true
+        } else {
+            frozen_tokens.remove(token_identifier).is_some()
+        };
+        if changed {
+            object_repository.save_frozen_tokens(&frozen_tokens).await?;
+        }
+        Ok(())
+    }
+
     async fn receive_bolt11_invoice(
         &self,
         description: String,
@@ -512,6 +598,9 @@ impl BreezSdk {
                 return Ok(SendPaymentResponse { payment });
             }
         }
+        if let Some(token_identifier) = &token_identifier {
+            self.ensure_token_not_frozen(token_identifier).await?;
+        }
         // Perform the send payment, with conversion if requested
         let res = if let Some(ConversionEstimate {
             options: conversion_options,
@@ -527,6 +616,14 @@ impl BreezSdk {
         } else {
             Box::pin(self.send_payment_internal(&request, amount_override)).await
         };
+        if let Some(token_identifier) = &token_identifier {
+            let frozen = matches!(res, Err(SdkError::TokenOutputsFrozen));
+            if (frozen || res.is_ok())
+                && let Err(e) = self.set_token_frozen(token_identifier, frozen).await
+            {
+                error!("Failed to update frozen state of token {token_identifier}: {e:?}");
+            }
+        }
         // Emit payment status event and trigger wallet state sync
         if let Ok(response) = &res {
             if !suppress_payment_event {
@@ -525,6 +525,8 @@ impl BreezSdk {
             }
         }
 
+        // Invoices submitted to withdraw links are picked up together with the metadata
+        let _ = self.lnurl_withdraw_trigger.send(());
         Ok(())
     }
 
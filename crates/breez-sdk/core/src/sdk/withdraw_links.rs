@@ -0,0 +1,344 @@
+use breez_sdk_common::input::parse_invoice;
+use lnurl_models::PendingWithdrawRequest;
+use tokio_with_wasm::alias as tokio;
+use tracing::{Instrument, debug, error, info, warn};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    CreateLnurlWithdrawLinkRequest, CreateLnurlWithdrawLinkResponse,
+    DeleteLnurlWithdrawLinkRequest, ListLnurlWithdrawLinksResponse, PaymentDetails, PaymentStatus,
+    PrepareSendPaymentRequest, SendPaymentMethod, SendPaymentOptions, SendPaymentRequest,
+    error::SdkError,
+    lnurl::CreateWithdrawLinkRequest,
+    persist::{CachedLnurlWithdrawLink, ObjectCacheRepository},
+};
+
+use super::BreezSdk;
+
+/// How long to wait for a withdraw payment to complete before leaving it to the next run.
+const WITHDRAW_PAYMENT_TIMEOUT_SECS: u32 = 60;
+
+enum WithdrawOutcome {
+    /// The payment is still pending or its outcome is unknown, it is reported on a later run
+    InFlight,
+    Paid {
+        preimage: String,
+    },
+    Failed,
+}
+
+#[cfg_attr(feature = "uniffi", uniffi::export(async_runtime = "tokio"))]
+#[allow(clippy::needless_pass_by_value)]
+impl BreezSdk {
+    /// Creates an LNURL-withdraw link on the LNURL server. Invoices submitted to the link are
+    /// paid by this wallet when it syncs, within the limits of the link.
+    ///
+    /// A lightning address must be registered before creating withdraw links.
+    pub async fn create_lnurl_withdraw_link(
+        &self,
+        request: CreateLnurlWithdrawLinkRequest,
+    ) -> Result<CreateLnurlWithdrawLinkResponse, SdkError> {
+        let Some(client) = &self.lnurl_server_client else {
+            return Err(SdkError::Generic(
+                "LNURL server is not configured".to_string(),
+            ));
+        };
+
+        if request.min_withdrawable_sat == 0 {
+            return Err(SdkError::InvalidInput(
+                "Minimum withdrawable amount must be positive".to_string(),
+            ));
+        }
+        if request.min_withdrawable_sat > request.max_withdrawable_sat {
+            return Err(SdkError::InvalidInput(
+                "Minimum withdrawable amount exceeds the maximum".to_string(),
+            ));
+        }
+        if request.max_uses == Some(0) {
+            return Err(SdkError::InvalidInput(
+                "Maximum uses must be positive".to_string(),
+            ));
+        }
+
+        let params = CreateWithdrawLinkRequest {
+            description: request
+                .description
+                .unwrap_or_else(|| format!("Withdraw from {}", client.domain())),
+            min_withdrawable: request.min_withdrawable_sat.saturating_mul(1000),
+            max_withdrawable: request.max_withdrawable_sat.saturating_mul(1000),
+            max_uses: request.max_uses,
+            expires_at: request.expires_at,
+        };
+        let link = client.create_withdraw_link(&params).await?;
+
+        // Keep the limits locally, so the invoices the server hands over can be verified
+        let cache = ObjectCacheRepository::new(self.storage.clone());
+        let mut cached_links = cache.fetch_lnurl_withdraw_links().await?;
+        cached_links.push(CachedLnurlWithdrawLink {
+            id: link.id.clone(),
+            min_withdrawable: link.min_withdrawable,
+            max_withdrawable: link.max_withdrawable,
+            max_uses: link.max_uses,
+            expires_at: link.expires_at,
+            payment_hashes: Vec::new(),
+        });
+        cache.save_lnurl_withdraw_links(&cached_links).await?;
+
+        Ok(CreateLnurlWithdrawLinkResponse { link: link.into() })
+    }
+
+    pub async fn list_lnurl_withdraw_links(
+        &self,
+    ) -> Result<ListLnurlWithdrawLinksResponse, SdkError> {
+        let Some(client) = &self.lnurl_server_client else {
+            return Err(SdkError::Generic(
+                "LNURL server is not configured".to_string(),
+            ));
+        };
+
+        let links = client.list_withdraw_links().await?;
+        Ok(ListLnurlWithdrawLinksResponse {
+            links: links.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Deletes an LNURL-withdraw link. Invoices already submitted to the link are no longer paid.
+    pub async fn delete_lnurl_withdraw_link(
+        &self,
+        request: DeleteLnurlWithdrawLinkRequest,
+    ) -> Result<(), SdkError> {
+        let Some(client) = &self.lnurl_server_client else {
+            return Err(SdkError::Generic(
+                "LNURL server is not configured".to_string(),
+            ));
+        };
+
+        client.delete_withdraw_link(&request.id).await?;
+
+        let cache = ObjectCacheRepository::new(self.storage.clone());
+        let mut cached_links = cache.fetch_lnurl_withdraw_links().await?;
+        cached_links.retain(|link| link.id != request.id);
+        cache.save_lnurl_withdraw_links(&cached_links).await?;
+        Ok(())
+    }
+}
+
+// Private withdraw link methods
+impl BreezSdk {
+    /// Background task that pays the invoices submitted to this wallet's withdraw links.
+    /// Triggered on startup and after syncing lnurl metadata.
+    pub(super) fn spawn_lnurl_withdraw_processor(&self) {
+        let sdk = self.clone();
+        let mut shutdown_receiver = sdk.shutdown_sender.subscribe();
+        let mut trigger_receiver = sdk.lnurl_withdraw_trigger.clone().subscribe();
+        let span = tracing::Span::current();
+
+        tokio::spawn(
+            async move {
+                if let Err(e) = sdk.process_lnurl_withdraw_requests().await {
+                    error!("Failed to process LNURL withdraw requests on startup: {e:?}");
+                }
+
+                loop {
+                    tokio::select! {
+                        _ = shutdown_receiver.changed() => {
+                            info!("LNURL withdraw processor shutdown signal received");
+                            return;
+                        }
+                        _ = trigger_receiver.recv() => {
+                            if let Err(e) = sdk.process_lnurl_withdraw_requests().await {
+                                error!("Failed to process LNURL withdraw requests: {e:?}");
+                            }
+                        }
+                    }
+                }
+            }
+            .instrument(span),
+        );
+    }
+
+    async fn process_lnurl_withdraw_requests(&self) -> Result<(), SdkError> {
+        let Some(client) = self.lnurl_server_client.clone() else {
+            return Ok(());
+        };
+
+        let cache = ObjectCacheRepository::new(self.storage.clone());
+        if cache.fetch_lnurl_withdraw_links().await?.is_empty() {
+            return Ok(());
+        }
+
+        let requests = client.list_withdraw_requests().await?;
+        debug!("Got {} pending lnurl withdraw requests", requests.len());
+        for request in requests {
+            let preimage = match self.process_lnurl_withdraw_request(&request).await {
+                Ok(WithdrawOutcome::InFlight) => continue,
+                Ok(WithdrawOutcome::Paid { preimage }) => Some(preimage),
+                Ok(WithdrawOutcome::Failed) => None,
+                Err(e) => {
+                    error!(
+                        "Failed to process withdraw request for payment_hash {}: {e:?}",
+                        request.payment_hash
+                    );
+                    continue;
+                }
+            };
+
+            if let Err(e) = client
+                .complete_withdraw_request(&request.payment_hash, preimage.as_deref())
+                .await
+            {
+                error!(
+                    "Failed to complete withdraw request for payment_hash {}: {}",
+                    request.payment_hash, e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the withdraw request against the limits of the link and pays it.
+    async fn process_lnurl_withdraw_request(
+        &self,
+        request: &PendingWithdrawRequest,
+    ) -> Result<WithdrawOutcome, SdkError> {
+        let cache = ObjectCacheRepository::new(self.storage.clone());
+        let mut cached_links = cache.fetch_lnurl_withdraw_links().await?;
+        let Some(link) = cached_links
+            .iter_mut()
+            .find(|link| link.id == request.link_id)
+        else {
+            warn!(
+                "Rejecting withdraw request {} for unknown link {}",
+                request.payment_hash, request.link_id
+            );
+            return Ok(WithdrawOutcome::Failed);
+        };
+
+        // A previous run already started paying this invoice
+        if link.payment_hashes.contains(&request.payment_hash) {
+            return self.withdraw_payment_outcome(&request.invoice).await;
+        }
+
+        let prepare_response = match self
+            .prepare_send_payment(PrepareSendPaymentRequest {
+                payment_request: request.invoice.clone(),
+                amount: None,
+                token_identifier: None,
+                conversion_options: None,
+                fee_policy: None,
+            })
+            .await
+        {
+            Ok(prepare_response) => prepare_response,
+            Err(e) => {
+                warn!("Rejecting withdraw request {}: {e:?}", request.payment_hash);
+                return Ok(WithdrawOutcome::Failed);
+            }
+        };
+        let SendPaymentMethod::Bolt11Invoice {
+            invoice_details, ..
+        } = &prepare_response.payment_method
+        else {
+            warn!(
+                "Rejecting withdraw request {}: not a bolt11 invoice",
+                request.payment_hash
+            );
+            return Ok(WithdrawOutcome::Failed);
+        };
+
+        let submitted_at = u64::try_from(request.created_at / 1000).unwrap_or_default();
+        let uses = u32::try_from(link.payment_hashes.len()).unwrap_or(u32::MAX);
+        let within_limits = invoice_details.payment_hash == request.payment_hash
+            && invoice_details.amount_msat == Some(request.amount_msat)
+            && request.amount_msat >= link.min_withdrawable
+            && request.amount_msat <= link.max_withdrawable
+            && link
+                .expires_at
+                .is_none_or(|expires_at| submitted_at < expires_at)
+            && link.max_uses.is_none_or(|max_uses| uses < max_uses);
+        if !within_limits {
+            warn!(
+                "Rejecting withdraw request {}: outside the limits of link {}",
+                request.payment_hash, request.link_id
+            );
+            return Ok(WithdrawOutcome::Failed);
+        }
+
+        // Record the invoice before paying, so it is never paid twice
+        link.payment_hashes.push(request.payment_hash.clone());
+        cache.save_lnurl_withdraw_links(&cached_links).await?;
+
+        let result = self
+            .send_payment(SendPaymentRequest {
+                prepare_response,
+                options: Some(SendPaymentOptions::Bolt11Invoice {
+                    prefer_spark: false,
+                    completion_timeout_secs: Some(WITHDRAW_PAYMENT_TIMEOUT_SECS),
+                }),
+                idempotency_key: None,
+            })
+            .await;
+        match result {
+            Ok(response) => {
+                debug!(
+                    "Paid withdraw request {} with status {:?}",
+                    request.payment_hash, response.payment.status
+                );
+                self.withdraw_payment_outcome(&request.invoice).await
+            }
+            // Other errors don't tell whether the payment was sent, its outcome is looked up on
+            // the next run
+            Err(e) if !matches!(e, SdkError::InsufficientFunds | SdkError::InvalidInput(_)) => {
+                warn!(
+                    "Failed to pay withdraw request {}, checking again later: {e:?}",
+                    request.payment_hash
+                );
+                Ok(WithdrawOutcome::InFlight)
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to pay withdraw request {}: {e:?}",
+                    request.payment_hash
+                );
+                // Give the use back to the link
+                let mut cached_links = cache.fetch_lnurl_withdraw_links().await?;
+                for link in &mut cached_links {
+                    link.payment_hashes
+                        .retain(|payment_hash| payment_hash != &request.payment_hash);
+                }
+                cache.save_lnurl_withdraw_links(&cached_links).await?;
+                Ok(WithdrawOutcome::Failed)
+            }
+        }
+    }
+
+    /// Looks up the outcome of the payment of a withdraw invoice.
+    async fn withdraw_payment_outcome(&self, invoice: &str) -> Result<WithdrawOutcome, SdkError> {
+        let Some(payment) = self
+            .storage
+            .get_payment_by_invoice(invoice.to_string())
+            .await?
+        else {
+            // The payment may not be synced yet. It can't be made anymore once the invoice expired.
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let expired = parse_invoice(invoice)
+                .is_none_or(|details| details.timestamp.saturating_add(details.expiry) <= now);
+            return Ok(if expired {
+                WithdrawOutcome::Failed
+            } else {
+                WithdrawOutcome::InFlight
+            });
+        };
+
+        let preimage = match payment.details {
+            Some(PaymentDetails::Lightning { htlc_details, .. }) => htlc_details.preimage,
+            _ => None,
+        };
+        Ok(match (payment.status, preimage) {
+            (PaymentStatus::Completed, Some(preimage)) => WithdrawOutcome::Paid { preimage },
+            (PaymentStatus::Failed, _) => WithdrawOutcome::Failed,
+            _ => WithdrawOutcome::InFlight,
+        })
+    }
+}
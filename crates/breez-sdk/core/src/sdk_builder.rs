@@ -17,6 +17,7 @@ use tracing::{debug, info};
 
 use crate::{
     Credentials, EventEmitter, FiatService, FiatServiceWrapper, KeySetType, Network, Seed,
+    TokenRegistryService,
     chain::{
         BitcoinChainService,
         rest_client::{BasicAuth, ChainApiType, RestClientChainService},
@@ -29,8 +30,8 @@ use crate::{
     realtime_sync::{RealTimeSyncParams, init_and_start_real_time_sync},
     sdk::{BreezSdk, BreezSdkParams},
     signer::{
-        breez::BreezSignerImpl, lnurl_auth::LnurlAuthSignerAdapter, rtsync::RTSyncSigner,
-        spark::SparkSigner,
+        SigningAuditLog, SparkSigningAuditLog, backup::BackupSigner, breez::BreezSignerImpl,
+        lnurl_auth::LnurlAuthSignerAdapter, rtsync::RTSyncSigner, spark::SparkSigner,
     },
 };
 
@@ -53,17 +54,31 @@ pub struct SdkBuilder {
     signer_source: SignerSource,
 
     storage_dir: Option<String>,
+    #[cfg(all(
+        feature = "sqlcipher",
+        not(all(target_family = "wasm", target_os = "unknown"))
+    ))]
+    storage_encryption_key_version: Option<u32>,
     storage: Option<Arc<dyn Storage>>,
     #[cfg(all(
         feature = "postgres",
         not(all(target_family = "wasm", target_os = "unknown"))
     ))]
     postgres_config: Option<crate::persist::postgres::PostgresStorageConfig>,
+    #[cfg(all(
+        feature = "postgres",
+        not(all(target_family = "wasm", target_os = "unknown"))
+    ))]
+    postgres_tenant_storage: Option<crate::persist::postgres::PostgresStorage>,
     chain_service: Option<Arc<dyn BitcoinChainService>>,
     fiat_service: Option<Arc<dyn FiatService>>,
+    token_registry_service: Option<Arc<dyn TokenRegistryService>>,
     lnurl_client: Option<Arc<dyn platform_utils::HttpClient>>,
     lnurl_server_client: Option<Arc<dyn LnurlServerClient>>,
     payment_observer: Option<Arc<dyn PaymentObserver>>,
+    signing_audit_log: Option<Arc<dyn SigningAuditLog>>,
+    breez_server: Option<Arc<BreezServer>>,
+    connection_manager: Option<Arc<dyn spark_wallet::ConnectionManager>>,
 }
 
 impl SdkBuilder {
@@ -85,17 +100,31 @@ impl SdkBuilder {
                 account_number: None,
             },
             storage_dir: None,
+            #[cfg(all(
+                feature = "sqlcipher",
+                not(all(target_family = "wasm", target_os = "unknown"))
+            ))]
+            storage_encryption_key_version: None,
             storage: None,
             #[cfg(all(
                 feature = "postgres",
                 not(all(target_family = "wasm", target_os = "unknown"))
             ))]
             postgres_config: None,
+            #[cfg(all(
+                feature = "postgres",
+                not(all(target_family = "wasm", target_os = "unknown"))
+            ))]
+            postgres_tenant_storage: None,
             chain_service: None,
             fiat_service: None,
+            token_registry_service: None,
             lnurl_client: None,
             lnurl_server_client: None,
             payment_observer: None,
+            signing_audit_log: None,
+            breez_server: None,
+            connection_manager: None,
         }
     }
 
@@ -110,17 +139,31 @@ impl SdkBuilder {
             config,
             signer_source: SignerSource::External(signer),
             storage_dir: None,
+            #[cfg(all(
+                feature = "sqlcipher",
+                not(all(target_family = "wasm", target_os = "unknown"))
+            ))]
+            storage_encryption_key_version: None,
             storage: None,
             #[cfg(all(
                 feature = "postgres",
                 not(all(target_family = "wasm", target_os = "unknown"))
             ))]
             postgres_config: None,
+            #[cfg(all(
+                feature = "postgres",
+                not(all(target_family = "wasm", target_os = "unknown"))
+            ))]
+            postgres_tenant_storage: None,
             chain_service: None,
             fiat_service: None,
+            token_registry_service: None,
             lnurl_client: None,
             lnurl_server_client: None,
             payment_observer: None,
+            signing_audit_log: None,
+            breez_server: None,
+            connection_manager: None,
         }
     }
 
@@ -158,6 +201,21 @@ impl SdkBuilder {
         self
     }
 
+    /// Encrypts the default storage at rest, with a key derived from the wallet seed through the
+    /// signer. An existing plaintext database is encrypted when it's opened.
+    /// Arguments:
+    /// - `key_version`: The version of the encryption key. Increasing it rotates the key, the
+    ///   database is re-encrypted with the new key when it's opened.
+    #[must_use]
+    #[cfg(all(
+        feature = "sqlcipher",
+        not(all(target_family = "wasm", target_os = "unknown"))
+    ))]
+    pub fn with_default_storage_encryption(mut self, key_version: u32) -> Self {
+        self.storage_encryption_key_version = Some(key_version);
+        self
+    }
+
     #[must_use]
     /// Sets the storage implementation to be used by the SDK.
     /// Arguments:
@@ -184,6 +242,24 @@ impl SdkBuilder {
         self
     }
 
+    /// Sets a shared `PostgreSQL` storage to be used by the SDK in tenant-scoped mode.
+    /// The wallet's data is scoped to its identity public key, so many wallets can share the
+    /// same database and connection pool.
+    /// Arguments:
+    /// - `storage`: The shared `PostgreSQL` storage.
+    #[must_use]
+    #[cfg(all(
+        feature = "postgres",
+        not(all(target_family = "wasm", target_os = "unknown"))
+    ))]
+    pub fn with_postgres_tenant_storage(
+        mut self,
+        storage: crate::persist::postgres::PostgresStorage,
+    ) -> Self {
+        self.postgres_tenant_storage = Some(storage);
+        self
+    }
+
     /// Sets the chain service to be used by the SDK.
     /// Arguments:
     /// - `chain_service`: The chain service to be used.
@@ -225,6 +301,19 @@ impl SdkBuilder {
         self
     }
 
+    /// Sets the token registry service used to resolve token icons, issuer info
+    /// and verification status.
+    /// Arguments:
+    /// - `token_registry_service`: The token registry service to be used.
+    #[must_use]
+    pub fn with_token_registry_service(
+        mut self,
+        token_registry_service: Arc<dyn TokenRegistryService>,
+    ) -> Self {
+        self.token_registry_service = Some(token_registry_service);
+        self
+    }
+
     #[must_use]
     pub fn with_lnurl_client(mut self, lnurl_client: Arc<dyn crate::RestClient>) -> Self {
         self.lnurl_client = Some(Arc::new(crate::common::rest::RestClientWrapper::new(
@@ -254,14 +343,45 @@ impl SdkBuilder {
         self
     }
 
-    /// Builds the `BreezSdk` instance with the configured components.
-    #[allow(clippy::too_many_lines)]
-    pub async fn build(self) -> Result<BreezSdk, SdkError> {
-        // Validate configuration
-        self.config.validate()?;
+    /// Sets the signing audit log to be used by the SDK.
+    /// Every signature the wallet makes is appended to it, with the operation it was made for.
+    /// Arguments:
+    /// - `signing_audit_log`: The signing audit log to be used.
+    #[must_use]
+    #[allow(unused)]
+    pub fn with_signing_audit_log(mut self, signing_audit_log: Arc<dyn SigningAuditLog>) -> Self {
+        self.signing_audit_log = Some(signing_audit_log);
+        self
+    }
 
-        // Create the base signer based on the signer source
-        let signer: Arc<dyn crate::signer::BreezSigner> = match self.signer_source {
+    /// Shares the Breez server client, used for fiat rates and buying bitcoin, with other
+    /// instances.
+    #[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+    #[must_use]
+    pub(crate) fn with_breez_server(mut self, breez_server: Arc<BreezServer>) -> Self {
+        self.breez_server = Some(breez_server);
+        self
+    }
+
+    /// Shares the connections to the Spark operators with other instances.
+    #[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+    #[must_use]
+    pub(crate) fn with_connection_manager(
+        mut self,
+        connection_manager: Arc<dyn spark_wallet::ConnectionManager>,
+    ) -> Self {
+        self.connection_manager = Some(connection_manager);
+        self
+    }
+
+    /// Returns the identity public key of the wallet the builder builds.
+    pub(crate) fn identity_public_key(&self) -> Result<bitcoin::secp256k1::PublicKey, SdkError> {
+        self.signer()?.identity_public_key()
+    }
+
+    /// Creates the base signer based on the signer source.
+    fn signer(&self) -> Result<Arc<dyn crate::signer::BreezSigner>, SdkError> {
+        Ok(match &self.signer_source {
             SignerSource::Seed {
                 seed,
                 key_set_type,
@@ -270,18 +390,28 @@ impl SdkBuilder {
             } => Arc::new(
                 BreezSignerImpl::new(
                     &self.config,
-                    &seed,
-                    key_set_type.into(),
-                    use_address_index,
-                    account_number,
+                    seed,
+                    (*key_set_type).into(),
+                    *use_address_index,
+                    *account_number,
                 )
                 .map_err(|e| SdkError::Generic(e.to_string()))?,
             ),
             SignerSource::External(external_signer) => {
                 use crate::signer::ExternalSignerAdapter;
-                Arc::new(ExternalSignerAdapter::new(external_signer))
+                Arc::new(ExternalSignerAdapter::new(Arc::clone(external_signer)))
             }
-        };
+        })
+    }
+
+    /// Builds the `BreezSdk` instance with the configured components.
+    #[allow(clippy::too_many_lines)]
+    pub async fn build(self) -> Result<BreezSdk, SdkError> {
+        // Validate configuration
+        self.config.validate()?;
+
+        // Create the base signer based on the signer source
+        let signer = self.signer()?;
 
         // Create the specialized signers
         let spark_signer = Arc::new(SparkSigner::new(signer.clone()));
@@ -290,38 +420,14 @@ impl SdkBuilder {
                 .map_err(|e| SdkError::Generic(e.to_string()))?,
         );
         let lnurl_auth_signer = Arc::new(LnurlAuthSignerAdapter::new(signer.clone()));
+        let backup_signer = Arc::new(
+            BackupSigner::new(signer.clone(), self.config.network)
+                .map_err(|e| SdkError::Generic(e.to_string()))?,
+        );
 
-        let chain_service = if let Some(service) = self.chain_service {
-            service
-        } else {
-            let inner_client = DefaultHttpClient::default();
-            match self.config.network {
-                Network::Mainnet => Arc::new(RestClientChainService::new(
-                    "https://blockstream.info/api".to_string(),
-                    self.config.network,
-                    5,
-                    Box::new(inner_client),
-                    None,
-                    ChainApiType::Esplora,
-                )),
-                Network::Regtest => Arc::new(RestClientChainService::new(
-                    "https://regtest-mempool.us-west-2.sparkinfra.net/api".to_string(),
-                    self.config.network,
-                    5,
-                    Box::new(inner_client),
-                    match (
-                        std::env::var("CHAIN_SERVICE_USERNAME"),
-                        std::env::var("CHAIN_SERVICE_PASSWORD"),
-                    ) {
-                        (Ok(username), Ok(password)) => Some(BasicAuth::new(username, password)),
-                        _ => Some(BasicAuth::new(
-                            "spark-sdk".to_string(),
-                            "mCMk1JqlBNtetUNy".to_string(),
-                        )),
-                    },
-                    ChainApiType::MempoolSpace,
-                )),
-            }
+        let chain_service = match self.chain_service {
+            Some(service) => service,
+            None => default_chain_service(self.config.network),
         };
 
         // Validate storage configuration
@@ -329,21 +435,34 @@ impl SdkBuilder {
             feature = "postgres",
             not(all(target_family = "wasm", target_os = "unknown"))
         ))]
-        let has_postgres = self.postgres_config.is_some();
+        let (has_postgres, has_postgres_tenant) = (
+            self.postgres_config.is_some(),
+            self.postgres_tenant_storage.is_some(),
+        );
         #[cfg(not(all(
             feature = "postgres",
             not(all(target_family = "wasm", target_os = "unknown"))
         )))]
-        let has_postgres = false;
+        let (has_postgres, has_postgres_tenant) = (false, false);
 
         let storage_count = [
             self.storage.is_some(),
             self.storage_dir.is_some(),
             has_postgres,
+            has_postgres_tenant,
         ]
         .into_iter()
         .filter(|&v| v)
         .count();
+        #[cfg(all(
+            feature = "sqlcipher",
+            not(all(target_family = "wasm", target_os = "unknown"))
+        ))]
+        if self.storage_encryption_key_version.is_some() && self.storage_dir.is_none() {
+            return Err(SdkError::Generic(
+                "Storage encryption requires the default storage".to_string(),
+            ));
+        }
         match storage_count {
             0 => return Err(SdkError::Generic("No storage configured".to_string())),
             2.. => {
@@ -364,6 +483,24 @@ impl SdkBuilder {
                     .get_identity_public_key()
                     .await
                     .map_err(|e| SdkError::Generic(e.to_string()))?;
+                #[cfg(feature = "sqlcipher")]
+                if let Some(key_version) = self.storage_encryption_key_version {
+                    let keys = crate::signer::storage::derive_storage_encryption_keys(
+                        signer.as_ref(),
+                        self.config.network,
+                        key_version,
+                    )
+                    .await?;
+                    encrypted_default_storage(
+                        &storage_dir,
+                        self.config.network,
+                        &identity_pub_key,
+                        &keys,
+                    )?
+                } else {
+                    default_storage(&storage_dir, self.config.network, &identity_pub_key)?
+                }
+                #[cfg(not(feature = "sqlcipher"))]
                 default_storage(&storage_dir, self.config.network, &identity_pub_key)?
             }
             #[cfg(all(target_family = "wasm", target_os = "unknown"))]
@@ -384,6 +521,17 @@ impl SdkBuilder {
                         .await
                         .map_err(|e| SdkError::Generic(e.to_string()))?,
                 )
+            } else if let Some(postgres_storage) = self.postgres_tenant_storage {
+                let identity_pub_key = spark_signer
+                    .get_identity_public_key()
+                    .await
+                    .map_err(|e| SdkError::Generic(e.to_string()))?;
+                Arc::new(
+                    postgres_storage
+                        .for_tenant(&identity_pub_key)
+                        .await
+                        .map_err(|e| SdkError::Generic(e.to_string()))?,
+                )
             } else {
                 return Err(SdkError::Generic("No storage configured".to_string()));
             }
@@ -396,10 +544,10 @@ impl SdkBuilder {
             }
         };
 
-        let breez_server = Arc::new(
-            BreezServer::new(PRODUCTION_BREEZSERVER_URL, None)
-                .map_err(|e| SdkError::Generic(e.to_string()))?,
-        );
+        let breez_server = match self.breez_server {
+            Some(breez_server) => breez_server,
+            None => default_breez_server()?,
+        };
 
         let fiat_service: Arc<dyn breez_sdk_common::fiat::FiatService> = match self.fiat_service {
             Some(service) => Arc::new(FiatServiceWrapper::new(service)),
@@ -433,11 +581,19 @@ impl SdkBuilder {
         let mut wallet_builder =
             spark_wallet::WalletBuilder::new(spark_wallet_config, spark_signer)
                 .with_cancellation_token(shutdown_sender.subscribe());
+        if let Some(connection_manager) = self.connection_manager {
+            wallet_builder = wallet_builder.with_connection_manager(connection_manager);
+        }
         if let Some(observer) = self.payment_observer {
             let observer: Arc<dyn spark_wallet::TransferObserver> =
                 Arc::new(SparkTransferObserver::new(observer));
             wallet_builder = wallet_builder.with_transfer_observer(observer);
         }
+        if let Some(audit_log) = self.signing_audit_log {
+            let audit_log: Arc<dyn spark_wallet::SigningAuditLog> =
+                Arc::new(SparkSigningAuditLog::new(audit_log));
+            wallet_builder = wallet_builder.with_signing_audit_log(audit_log);
+        }
         let spark_wallet = Arc::new(wallet_builder.build().await?);
 
         let lnurl_server_client: Option<Arc<dyn LnurlServerClient>> = match self.lnurl_server_client
@@ -487,9 +643,11 @@ impl SdkBuilder {
             storage,
             chain_service,
             fiat_service,
+            token_registry_service: self.token_registry_service,
             lnurl_client,
             lnurl_server_client,
             lnurl_auth_signer,
+            backup_signer,
             shutdown_sender,
             spark_wallet,
             event_emitter,
@@ -502,6 +660,44 @@ impl SdkBuilder {
     }
 }
 
+pub(crate) fn default_chain_service(network: Network) -> Arc<dyn BitcoinChainService> {
+    let inner_client = DefaultHttpClient::default();
+    match network {
+        Network::Mainnet => Arc::new(RestClientChainService::new(
+            "https://blockstream.info/api".to_string(),
+            network,
+            5,
+            Box::new(inner_client),
+            None,
+            ChainApiType::Esplora,
+        )),
+        Network::Regtest => Arc::new(RestClientChainService::new(
+            "https://regtest-mempool.us-west-2.sparkinfra.net/api".to_string(),
+            network,
+            5,
+            Box::new(inner_client),
+            match (
+                std::env::var("CHAIN_SERVICE_USERNAME"),
+                std::env::var("CHAIN_SERVICE_PASSWORD"),
+            ) {
+                (Ok(username), Ok(password)) => Some(BasicAuth::new(username, password)),
+                _ => Some(BasicAuth::new(
+                    "spark-sdk".to_string(),
+                    "mCMk1JqlBNtetUNy".to_string(),
+                )),
+            },
+            ChainApiType::MempoolSpace,
+        )),
+    }
+}
+
+pub(crate) fn default_breez_server() -> Result<Arc<BreezServer>, SdkError> {
+    Ok(Arc::new(
+        BreezServer::new(PRODUCTION_BREEZSERVER_URL, None)
+            .map_err(|e| SdkError::Generic(e.to_string()))?,
+    ))
+}
+
 #[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
 fn default_storage(
     data_dir: &str,
@@ -512,3 +708,22 @@ fn default_storage(
     let storage = Arc::new(crate::SqliteStorage::new(&db_path)?);
     Ok(storage)
 }
+
+#[cfg(all(
+    feature = "sqlcipher",
+    not(all(target_family = "wasm", target_os = "unknown"))
+))]
+fn encrypted_default_storage(
+    data_dir: &str,
+    network: Network,
+    identity_pub_key: &spark_wallet::PublicKey,
+    keys: &crate::signer::storage::StorageEncryptionKeys,
+) -> Result<Arc<dyn Storage>, SdkError> {
+    let db_path = crate::default_storage_path(data_dir, &network, identity_pub_key)?;
+    let storage = Arc::new(crate::SqliteStorage::new_encrypted(
+        &db_path,
+        keys.key,
+        &keys.previous_keys,
+    )?);
+    Ok(storage)
+}
@@ -1,8 +1,8 @@
 use crate::SdkError;
 use crate::signer::external_types::{
     ExternalAggregateFrostRequest, ExternalEncryptedSecret, ExternalSecretSource,
-    ExternalSecretToSplit, ExternalSignFrostRequest, ExternalTreeNodeId, MessageBytes,
-    PublicKeyBytes, derivation_path_to_string,
+    ExternalSecretToSplit, ExternalSignFrostRequest, ExternalSigningContext, ExternalTreeNodeId,
+    MessageBytes, PublicKeyBytes, derivation_path_to_string,
 };
 use crate::signer::{BreezSigner, ExternalSigner};
 use bitcoin::bip32::DerivationPath;
@@ -53,13 +53,17 @@ impl BreezSigner for ExternalSignerAdapter {
         &self,
         message: secp256k1::Message,
         path: &DerivationPath,
+        context: Option<&spark_wallet::SigningContext>,
     ) -> Result<secp256k1::ecdsa::Signature, SdkError> {
         let path_str = derivation_path_to_string(path);
         // Convert Message digest to MessageBytes
         let msg_bytes = MessageBytes::new(message.as_ref().to_vec());
+        let context_ext = context
+            .map(ExternalSigningContext::from_signing_context)
+            .transpose()?;
         let sig_bytes = self
             .external
-            .sign_ecdsa(msg_bytes, path_str)
+            .sign_ecdsa(msg_bytes, path_str, context_ext)
             .await
             .map_err(|e| SdkError::Signer(format!("External signer sign_ecdsa failed: {e}")))?;
         sig_bytes.to_signature()
@@ -124,11 +128,15 @@ impl BreezSigner for ExternalSignerAdapter {
         &self,
         hash: &[u8],
         path: &DerivationPath,
+        context: Option<&spark_wallet::SigningContext>,
     ) -> Result<secp256k1::schnorr::Signature, SdkError> {
         let path_str = derivation_path_to_string(path);
+        let context_ext = context
+            .map(ExternalSigningContext::from_signing_context)
+            .transpose()?;
         let sig_bytes = self
             .external
-            .sign_hash_schnorr(hash.to_vec(), path_str)
+            .sign_hash_schnorr(hash.to_vec(), path_str, context_ext)
             .await
             .map_err(|e| {
                 SdkError::Signer(format!("External signer sign_hash_schnorr failed: {e}"))
@@ -260,14 +268,16 @@ impl BreezSigner for ExternalSignerAdapter {
         secret: &spark_wallet::SecretToSplit,
         threshold: u32,
         num_shares: usize,
+        context: &spark_wallet::SigningContext,
     ) -> Result<Vec<spark_wallet::VerifiableSecretShare>, SdkError> {
         let secret_ext = ExternalSecretToSplit::from_secret_to_split(secret)?;
+        let context_ext = ExternalSigningContext::from_signing_context(context)?;
         let num_shares_u32 = num_shares
             .try_into()
             .map_err(|_| SdkError::Generic("num_shares value too large".to_string()))?;
         let shares_ext = self
             .external
-            .split_secret_with_proofs(secret_ext, threshold, num_shares_u32)
+            .split_secret_with_proofs(secret_ext, threshold, num_shares_u32, context_ext)
             .await
             .map_err(|e| {
                 SdkError::Signer(format!(
@@ -285,12 +295,14 @@ impl BreezSigner for ExternalSignerAdapter {
         &self,
         private_key: &spark_wallet::EncryptedSecret,
         receiver_public_key: &secp256k1::PublicKey,
+        context: &spark_wallet::SigningContext,
     ) -> Result<Vec<u8>, SdkError> {
         let private_key_ext = ExternalEncryptedSecret::from_encrypted_secret(private_key)?;
         let receiver_pk_bytes = PublicKeyBytes::from_public_key(receiver_public_key);
+        let context_ext = ExternalSigningContext::from_signing_context(context)?;
 
         self.external
-            .encrypt_secret_for_receiver(private_key_ext, receiver_pk_bytes)
+            .encrypt_secret_for_receiver(private_key_ext, receiver_pk_bytes, context_ext)
             .await
             .map_err(|e| {
                 SdkError::Signer(format!(
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use crate::error::SignerError;
+
+use super::external_types::ExternalSigningContext;
+
+/// The signer method an audit entry was recorded for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum SigningMethod {
+    SignMessageEcdsa,
+    SignHashSchnorr,
+    SplitSecret,
+    EncryptSecretForReceiver,
+    SignFrost,
+}
+
+impl From<spark_wallet::SigningMethod> for SigningMethod {
+    fn from(method: spark_wallet::SigningMethod) -> Self {
+        match method {
+            spark_wallet::SigningMethod::SignMessageEcdsa => Self::SignMessageEcdsa,
+            spark_wallet::SigningMethod::SignHashSchnorr => Self::SignHashSchnorr,
+            spark_wallet::SigningMethod::SplitSecret => Self::SplitSecret,
+            spark_wallet::SigningMethod::EncryptSecretForReceiver => Self::EncryptSecretForReceiver,
+            spark_wallet::SigningMethod::SignFrost => Self::SignFrost,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct SigningAuditEntry {
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+    pub method: SigningMethod,
+    /// The wallet operation the signature was made for
+    pub context: ExternalSigningContext,
+    /// The signed digest. `None` for methods sharing secrets, which don't sign anything.
+    pub digest: Option<Vec<u8>>,
+    /// The resulting signature, or signature share for FROST. `None` for methods sharing
+    /// secrets, their results aren't logged.
+    pub signature: Option<Vec<u8>>,
+}
+
+/// Append-only log of the signatures the wallet makes, with the operation each one is for.
+///
+/// Signing results are only used once their entry is appended. If the implementation returns an
+/// error, the operation is cancelled.
+#[cfg_attr(feature = "uniffi", uniffi::export(with_foreign))]
+#[macros::async_trait]
+pub trait SigningAuditLog: Send + Sync {
+    /// Appends an entry. Entries are never updated or removed.
+    async fn append(&self, entry: SigningAuditEntry) -> Result<(), SignerError>;
+}
+
+pub(crate) struct SparkSigningAuditLog {
+    inner: Arc<dyn SigningAuditLog>,
+}
+
+impl SparkSigningAuditLog {
+    pub fn new(inner: Arc<dyn SigningAuditLog>) -> Self {
+        Self { inner }
+    }
+}
+
+#[macros::async_trait]
+impl spark_wallet::SigningAuditLog for SparkSigningAuditLog {
+    async fn append(
+        &self,
+        entry: spark_wallet::SigningAuditEntry,
+    ) -> Result<(), spark_wallet::SignerError> {
+        let context = ExternalSigningContext::from_signing_context(&entry.context)
+            .map_err(|e| spark_wallet::SignerError::AuditLogError(e.to_string()))?;
+        self.inner
+            .append(SigningAuditEntry {
+                timestamp: entry.timestamp,
+                method: entry.method.into(),
+                context,
+                digest: entry.digest,
+                signature: entry.signature,
+            })
+            .await
+            .map_err(|e| spark_wallet::SignerError::AuditLogError(e.to_string()))
+    }
+}
@@ -0,0 +1,39 @@
+use bitcoin::bip32::DerivationPath;
+use std::sync::Arc;
+
+use crate::{Network, SdkError, signer::BreezSigner};
+
+const ENCRYPTION_DERIVATION_PATH: &str = "m/1651209584'/0'/0'/0/0";
+const ENCRYPTION_DERIVATION_PATH_TEST: &str = "m/1651209584'/1'/0'/0/0";
+
+/// Encrypts backups with a key derived from the wallet seed, so only the wallet can read them.
+pub struct BackupSigner {
+    signer: Arc<dyn BreezSigner>,
+    encryption_path: DerivationPath,
+}
+
+impl BackupSigner {
+    pub fn new(
+        signer: Arc<dyn BreezSigner>,
+        network: Network,
+    ) -> Result<Self, bitcoin::bip32::Error> {
+        let encryption_path: DerivationPath = match network {
+            Network::Mainnet => ENCRYPTION_DERIVATION_PATH,
+            Network::Regtest => ENCRYPTION_DERIVATION_PATH_TEST,
+        }
+        .parse()?;
+
+        Ok(Self {
+            signer,
+            encryption_path,
+        })
+    }
+
+    pub async fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, SdkError> {
+        self.signer.encrypt_ecies(data, &self.encryption_path).await
+    }
+
+    pub async fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, SdkError> {
+        self.signer.decrypt_ecies(data, &self.encryption_path).await
+    }
+}
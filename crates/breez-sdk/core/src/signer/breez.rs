@@ -60,6 +60,7 @@ impl BreezSigner for BreezSignerImpl {
         &self,
         message: Message,
         path: &DerivationPath,
+        _context: Option<&spark_wallet::SigningContext>,
     ) -> Result<secp256k1::ecdsa::Signature, SdkError> {
         let derived = self
             .key_set
@@ -118,6 +119,7 @@ impl BreezSigner for BreezSignerImpl {
         &self,
         hash: &[u8],
         path: &DerivationPath,
+        _context: Option<&spark_wallet::SigningContext>,
     ) -> Result<secp256k1::schnorr::Signature, SdkError> {
         let derived = self
             .key_set
@@ -204,9 +206,10 @@ impl BreezSigner for BreezSignerImpl {
         secret: &spark_wallet::SecretToSplit,
         threshold: u32,
         num_shares: usize,
+        context: &spark_wallet::SigningContext,
     ) -> Result<Vec<spark_wallet::VerifiableSecretShare>, SdkError> {
         self.spark_signer
-            .split_secret_with_proofs(secret, threshold, num_shares)
+            .split_secret_with_proofs(secret, threshold, num_shares, context)
             .await
             .map_err(|e| SdkError::Generic(e.to_string()))
     }
@@ -215,9 +218,10 @@ impl BreezSigner for BreezSignerImpl {
         &self,
         private_key: &spark_wallet::EncryptedSecret,
         receiver_public_key: &secp256k1::PublicKey,
+        context: &spark_wallet::SigningContext,
     ) -> Result<Vec<u8>, SdkError> {
         self.spark_signer
-            .encrypt_secret_for_receiver(private_key, receiver_public_key)
+            .encrypt_secret_for_receiver(private_key, receiver_public_key, context)
             .await
             .map_err(|e| SdkError::Generic(e.to_string()))
     }
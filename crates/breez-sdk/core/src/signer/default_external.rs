@@ -4,9 +4,10 @@ use crate::signer::external_types::derivation_path_to_string;
 use crate::signer::external_types::{
     EcdsaSignatureBytes, ExternalAggregateFrostRequest, ExternalEncryptedSecret,
     ExternalFrostCommitments, ExternalFrostSignature, ExternalFrostSignatureShare,
-    ExternalSecretSource, ExternalSecretToSplit, ExternalSignFrostRequest, ExternalTreeNodeId,
-    ExternalVerifiableSecretShare, HashedMessageBytes, MessageBytes, PublicKeyBytes,
-    RecoverableEcdsaSignatureBytes, SchnorrSignatureBytes, SecretBytes, string_to_derivation_path,
+    ExternalSecretSource, ExternalSecretToSplit, ExternalSignFrostRequest, ExternalSigningContext,
+    ExternalTreeNodeId, ExternalVerifiableSecretShare, HashedMessageBytes, MessageBytes,
+    PublicKeyBytes, RecoverableEcdsaSignatureBytes, SchnorrSignatureBytes, SecretBytes,
+    string_to_derivation_path,
 };
 use crate::signer::{BreezSigner, ExternalSigner, breez::BreezSignerImpl};
 use crate::{Network, SdkError, Seed, default_config, models::KeySetType};
@@ -79,6 +80,7 @@ impl ExternalSigner for DefaultExternalSigner {
         &self,
         message: MessageBytes,
         path: String,
+        context: Option<ExternalSigningContext>,
     ) -> Result<EcdsaSignatureBytes, SignerError> {
         use bitcoin::secp256k1::Message;
 
@@ -88,9 +90,13 @@ impl ExternalSigner for DefaultExternalSigner {
             .to_digest()
             .map_err(|e| SignerError::Generic(e.to_string()))?;
         let msg = Message::from_digest(digest);
+        let context = context
+            .map(|c| c.to_signing_context())
+            .transpose()
+            .map_err(|e| SignerError::Generic(e.to_string()))?;
         let sig = self
             .inner
-            .sign_ecdsa(msg, &derivation_path)
+            .sign_ecdsa(msg, &derivation_path, context.as_ref())
             .await
             .map_err(|e| SignerError::Generic(e.to_string()))?;
         Ok(EcdsaSignatureBytes::from_signature(&sig))
@@ -150,12 +156,17 @@ impl ExternalSigner for DefaultExternalSigner {
         &self,
         hash: Vec<u8>,
         path: String,
+        context: Option<ExternalSigningContext>,
     ) -> Result<SchnorrSignatureBytes, SignerError> {
         let derivation_path =
             string_to_derivation_path(&path).map_err(|e| SignerError::Generic(e.to_string()))?;
+        let context = context
+            .map(|c| c.to_signing_context())
+            .transpose()
+            .map_err(|e| SignerError::Generic(e.to_string()))?;
         let sig = self
             .inner
-            .sign_hash_schnorr(&hash, &derivation_path)
+            .sign_hash_schnorr(&hash, &derivation_path, context.as_ref())
             .await
             .map_err(|e| SignerError::Generic(e.to_string()))?;
         Ok(SchnorrSignatureBytes::from_signature(&sig))
@@ -269,13 +280,17 @@ impl ExternalSigner for DefaultExternalSigner {
         secret: ExternalSecretToSplit,
         threshold: u32,
         num_shares: u32,
+        context: ExternalSigningContext,
     ) -> Result<Vec<ExternalVerifiableSecretShare>, SignerError> {
         let sec = secret
             .to_secret_to_split()
             .map_err(|e| SignerError::Generic(e.to_string()))?;
+        let context = context
+            .to_signing_context()
+            .map_err(|e| SignerError::Generic(e.to_string()))?;
         let shares = self
             .inner
-            .split_secret_with_proofs(&sec, threshold, num_shares as usize)
+            .split_secret_with_proofs(&sec, threshold, num_shares as usize, &context)
             .await
             .map_err(|e| SignerError::Frost(e.to_string()))?;
 
@@ -292,6 +307,7 @@ impl ExternalSigner for DefaultExternalSigner {
         &self,
         encrypted_secret: ExternalEncryptedSecret,
         receiver_public_key: PublicKeyBytes,
+        context: ExternalSigningContext,
     ) -> Result<Vec<u8>, SignerError> {
         let pk_internal = encrypted_secret
             .to_encrypted_private_key()
@@ -299,8 +315,11 @@ impl ExternalSigner for DefaultExternalSigner {
         let receiver_pk = receiver_public_key
             .to_public_key()
             .map_err(|e| SignerError::Generic(e.to_string()))?;
+        let context = context
+            .to_signing_context()
+            .map_err(|e| SignerError::Generic(e.to_string()))?;
         self.inner
-            .encrypt_secret_for_receiver(&pk_internal, &receiver_pk)
+            .encrypt_secret_for_receiver(&pk_internal, &receiver_pk, &context)
             .await
             .map_err(|e| SignerError::Generic(e.to_string()))
     }
@@ -442,8 +461,11 @@ mod tests {
         let msg_bytes = MessageBytes::new(hash.to_byte_array().to_vec());
         let msg = Message::from_digest(hash.to_byte_array());
 
-        let external_sig = external.sign_ecdsa(msg_bytes, path_str).await.unwrap();
-        let internal_sig = internal.sign_ecdsa(msg, &path).await.unwrap();
+        let external_sig = external
+            .sign_ecdsa(msg_bytes, path_str, None)
+            .await
+            .unwrap();
+        let internal_sig = internal.sign_ecdsa(msg, &path, None).await.unwrap();
 
         assert_eq!(
             external_sig.to_signature().unwrap(),
@@ -540,10 +562,13 @@ mod tests {
 
         // Sign with both signers
         let external_sig = external
-            .sign_hash_schnorr(hash.clone(), path_str)
+            .sign_hash_schnorr(hash.clone(), path_str, None)
+            .await
+            .unwrap();
+        let internal_sig = internal
+            .sign_hash_schnorr(&hash, &path, None)
             .await
             .unwrap();
-        let internal_sig = internal.sign_hash_schnorr(&hash, &path).await.unwrap();
 
         // Schnorr signatures use random nonces, so they won't be identical
         // but both should be valid signatures over the same message with the same key
@@ -707,6 +732,7 @@ mod tests {
             self_nonce_commitment: &internal_commitments_full,
             statechain_commitments,
             adaptor_public_key: None,
+            context: &spark_wallet::SigningContext::new(spark_wallet::SigningOperation::Transfer),
         };
 
         // Create the external FROST request
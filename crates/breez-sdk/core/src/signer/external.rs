@@ -3,9 +3,9 @@ use crate::error::SignerError;
 use super::external_types::{
     EcdsaSignatureBytes, ExternalAggregateFrostRequest, ExternalEncryptedSecret,
     ExternalFrostCommitments, ExternalFrostSignature, ExternalFrostSignatureShare,
-    ExternalSecretSource, ExternalSecretToSplit, ExternalSignFrostRequest, ExternalTreeNodeId,
-    ExternalVerifiableSecretShare, HashedMessageBytes, MessageBytes, PublicKeyBytes,
-    RecoverableEcdsaSignatureBytes, SchnorrSignatureBytes, SecretBytes,
+    ExternalSecretSource, ExternalSecretToSplit, ExternalSignFrostRequest, ExternalSigningContext,
+    ExternalTreeNodeId, ExternalVerifiableSecretShare, HashedMessageBytes, MessageBytes,
+    PublicKeyBytes, RecoverableEcdsaSignatureBytes, SchnorrSignatureBytes, SecretBytes,
 };
 
 /// External signer trait that can be implemented by users and passed to the SDK.
@@ -45,6 +45,7 @@ pub trait ExternalSigner: Send + Sync {
     /// # Arguments
     /// * `message` - The 32-byte message digest to sign
     /// * `path` - BIP32 derivation path as a string
+    /// * `context` - The wallet operation the signature is for, if any
     ///
     /// # Returns
     /// 64-byte compact ECDSA signature, or a `SignerError`
@@ -52,6 +53,7 @@ pub trait ExternalSigner: Send + Sync {
         &self,
         message: MessageBytes,
         path: String,
+        context: Option<ExternalSigningContext>,
     ) -> Result<EcdsaSignatureBytes, SignerError>;
 
     /// Signs a message using recoverable ECDSA at the given derivation path.
@@ -97,6 +99,7 @@ pub trait ExternalSigner: Send + Sync {
     /// # Arguments
     /// * `hash` - The 32-byte hash to sign (must be 32 bytes)
     /// * `path` - BIP32 derivation path as a string
+    /// * `context` - The wallet operation the signature is for, if any
     ///
     /// # Returns
     /// 64-byte Schnorr signature, or a `SignerError`
@@ -104,6 +107,7 @@ pub trait ExternalSigner: Send + Sync {
         &self,
         hash: Vec<u8>,
         path: String,
+        context: Option<ExternalSigningContext>,
     ) -> Result<SchnorrSignatureBytes, SignerError>;
 
     /// HMAC-SHA256 of a message at the given derivation path.
@@ -223,6 +227,7 @@ pub trait ExternalSigner: Send + Sync {
     /// * `secret` - The secret to split
     /// * `threshold` - Minimum number of shares needed to reconstruct
     /// * `num_shares` - Total number of shares to create
+    /// * `context` - The wallet operation the shares are for
     ///
     /// # Returns
     /// Vector of verifiable secret shares, or a `SignerError`
@@ -233,6 +238,7 @@ pub trait ExternalSigner: Send + Sync {
         secret: ExternalSecretToSplit,
         threshold: u32,
         num_shares: u32,
+        context: ExternalSigningContext,
     ) -> Result<Vec<ExternalVerifiableSecretShare>, SignerError>;
 
     /// Encrypts a secret for a specific receiver's public key.
@@ -240,6 +246,7 @@ pub trait ExternalSigner: Send + Sync {
     /// # Arguments
     /// * `encrypted_secret` - The encrypted secret to re-encrypt
     /// * `receiver_public_key` - The receiver's 33-byte public key
+    /// * `context` - The wallet operation the secret is sent for
     ///
     /// # Returns
     /// Encrypted data for the receiver, or a `SignerError`
@@ -247,6 +254,7 @@ pub trait ExternalSigner: Send + Sync {
         &self,
         encrypted_secret: ExternalEncryptedSecret,
         receiver_public_key: PublicKeyBytes,
+        context: ExternalSigningContext,
     ) -> Result<Vec<u8>, SignerError>;
 
     /// Gets the public key from a secret.
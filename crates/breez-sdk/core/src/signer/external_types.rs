@@ -259,6 +259,132 @@ impl ExternalSecretToSplit {
     }
 }
 
+/// FFI-safe representation of `spark_wallet::SigningOperation`
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum ExternalSigningOperation {
+    /// Authenticating with the Spark operators or the SSP
+    Authentication,
+    /// Sending leaves to another Spark wallet
+    Transfer,
+    /// Claiming leaves sent to this wallet
+    ClaimTransfer,
+    /// Paying a lightning invoice through the SSP
+    LightningSend,
+    /// Creating a lightning invoice, sharing its preimage with the operators
+    LightningReceive,
+    /// Locking leaves in an HTLC
+    Htlc,
+    /// Withdrawing leaves onchain through a cooperative exit with the SSP
+    CoopExit,
+    /// Swapping leaves with the SSP for leaves of other denominations
+    LeafSwap,
+    /// Claiming an onchain deposit into a new leaf
+    DepositClaim,
+    /// Claiming a static deposit through the SSP
+    StaticDepositClaim,
+    /// Refunding a static deposit onchain
+    StaticDepositRefund,
+    /// Renewing the timelocks of leaves
+    TimelockRenewal,
+    /// Creating, minting, burning, freezing or transferring tokens
+    TokenTransaction,
+    /// Creating a Spark invoice
+    SparkInvoice,
+    /// Signing an arbitrary message with the identity key
+    SignMessage,
+}
+
+impl From<spark_wallet::SigningOperation> for ExternalSigningOperation {
+    fn from(operation: spark_wallet::SigningOperation) -> Self {
+        match operation {
+            spark_wallet::SigningOperation::Authentication => Self::Authentication,
+            spark_wallet::SigningOperation::Transfer => Self::Transfer,
+            spark_wallet::SigningOperation::ClaimTransfer => Self::ClaimTransfer,
+            spark_wallet::SigningOperation::LightningSend => Self::LightningSend,
+            spark_wallet::SigningOperation::LightningReceive => Self::LightningReceive,
+            spark_wallet::SigningOperation::Htlc => Self::Htlc,
+            spark_wallet::SigningOperation::CoopExit => Self::CoopExit,
+            spark_wallet::SigningOperation::LeafSwap => Self::LeafSwap,
+            spark_wallet::SigningOperation::DepositClaim => Self::DepositClaim,
+            spark_wallet::SigningOperation::StaticDepositClaim => Self::StaticDepositClaim,
+            spark_wallet::SigningOperation::StaticDepositRefund => Self::StaticDepositRefund,
+            spark_wallet::SigningOperation::TimelockRenewal => Self::TimelockRenewal,
+            spark_wallet::SigningOperation::TokenTransaction => Self::TokenTransaction,
+            spark_wallet::SigningOperation::SparkInvoice => Self::SparkInvoice,
+            spark_wallet::SigningOperation::SignMessage => Self::SignMessage,
+        }
+    }
+}
+
+impl From<ExternalSigningOperation> for spark_wallet::SigningOperation {
+    fn from(operation: ExternalSigningOperation) -> Self {
+        match operation {
+            ExternalSigningOperation::Authentication => Self::Authentication,
+            ExternalSigningOperation::Transfer => Self::Transfer,
+            ExternalSigningOperation::ClaimTransfer => Self::ClaimTransfer,
+            ExternalSigningOperation::LightningSend => Self::LightningSend,
+            ExternalSigningOperation::LightningReceive => Self::LightningReceive,
+            ExternalSigningOperation::Htlc => Self::Htlc,
+            ExternalSigningOperation::CoopExit => Self::CoopExit,
+            ExternalSigningOperation::LeafSwap => Self::LeafSwap,
+            ExternalSigningOperation::DepositClaim => Self::DepositClaim,
+            ExternalSigningOperation::StaticDepositClaim => Self::StaticDepositClaim,
+            ExternalSigningOperation::StaticDepositRefund => Self::StaticDepositRefund,
+            ExternalSigningOperation::TimelockRenewal => Self::TimelockRenewal,
+            ExternalSigningOperation::TokenTransaction => Self::TokenTransaction,
+            ExternalSigningOperation::SparkInvoice => Self::SparkInvoice,
+            ExternalSigningOperation::SignMessage => Self::SignMessage,
+        }
+    }
+}
+
+/// FFI-safe representation of `spark_wallet::SigningContext`
+///
+/// Describes the wallet operation a signer request is for, so signers can apply policies and
+/// keep an audit trail. The context is informational: it can't be verified against the signed
+/// message.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ExternalSigningContext {
+    pub operation: ExternalSigningOperation,
+    /// Amount moved by the operation, in satoshis or token base units for token transactions
+    pub amount: Option<u128>,
+    /// The other side of the operation: the identity public key of the receiving wallet, the
+    /// withdrawal address or the lightning invoice
+    pub counterparty: Option<String>,
+    /// The leaves the operation spends
+    pub leaf_ids: Vec<ExternalTreeNodeId>,
+}
+
+impl ExternalSigningContext {
+    pub fn from_signing_context(context: &spark_wallet::SigningContext) -> Result<Self, SdkError> {
+        Ok(Self {
+            operation: context.operation.into(),
+            amount: context.amount,
+            counterparty: context.counterparty.clone(),
+            leaf_ids: context
+                .leaf_ids
+                .iter()
+                .map(ExternalTreeNodeId::from_tree_node_id)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub fn to_signing_context(&self) -> Result<spark_wallet::SigningContext, SdkError> {
+        Ok(spark_wallet::SigningContext {
+            operation: self.operation.into(),
+            amount: self.amount,
+            counterparty: self.counterparty.clone(),
+            leaf_ids: self
+                .leaf_ids
+                .iter()
+                .map(ExternalTreeNodeId::to_tree_node_id)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
 /// FFI-safe representation of `k256::Scalar` (32 bytes)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
@@ -561,6 +687,8 @@ pub struct ExternalSignFrostRequest {
     pub statechain_commitments: Vec<IdentifierCommitmentPair>,
     /// Optional adaptor public key (33 bytes compressed)
     pub adaptor_public_key: Option<Vec<u8>>,
+    /// The wallet operation the signature is for
+    pub context: ExternalSigningContext,
 }
 
 impl ExternalSignFrostRequest {
@@ -588,6 +716,7 @@ impl ExternalSignFrostRequest {
             )?,
             statechain_commitments,
             adaptor_public_key: request.adaptor_public_key.map(|pk| pk.serialize().to_vec()),
+            context: ExternalSigningContext::from_signing_context(request.context)?,
         })
     }
 
@@ -633,6 +762,7 @@ impl ExternalSignFrostRequest {
             )),
             statechain_commitments,
             adaptor_public_key: adaptor_public_key.map(|pk| Box::leak(Box::new(pk)) as &_),
+            context: Box::leak(Box::new(self.context.to_signing_context()?)),
         })
     }
 }
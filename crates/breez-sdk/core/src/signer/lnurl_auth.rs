@@ -50,7 +50,7 @@ impl LnurlAuthSigner for LnurlAuthSignerAdapter {
         // Delegate to BreezSigner for ECDSA signing
         let sig = self
             .signer
-            .sign_ecdsa(message, &path)
+            .sign_ecdsa(message, &path, None)
             .await
             .map_err(|e| LnurlError::General(e.to_string()))?;
 
@@ -11,10 +11,12 @@ pub trait BreezSigner: Send + Sync {
     /// Signs a pre-hashed message using ECDSA at the given derivation path.
     ///
     /// The caller must create the Message from a 32-byte hash digest before calling this method.
+    /// The context describes the wallet operation the signature is for, if any.
     async fn sign_ecdsa(
         &self,
         message: Message,
         path: &DerivationPath,
+        context: Option<&spark_wallet::SigningContext>,
     ) -> Result<secp256k1::ecdsa::Signature, SdkError>;
 
     /// Signs a pre-hashed message using recoverable ECDSA at the given derivation path.
@@ -42,6 +44,7 @@ pub trait BreezSigner: Send + Sync {
         &self,
         hash: &[u8],
         path: &DerivationPath,
+        context: Option<&spark_wallet::SigningContext>,
     ) -> Result<secp256k1::schnorr::Signature, SdkError>;
 
     async fn derive_public_key(
@@ -83,12 +86,14 @@ pub trait BreezSigner: Send + Sync {
         secret: &spark_wallet::SecretToSplit,
         threshold: u32,
         num_shares: usize,
+        context: &spark_wallet::SigningContext,
     ) -> Result<Vec<spark_wallet::VerifiableSecretShare>, SdkError>;
 
     async fn encrypt_secret_for_receiver(
         &self,
         private_key: &spark_wallet::EncryptedSecret,
         receiver_public_key: &secp256k1::PublicKey,
+        context: &spark_wallet::SigningContext,
     ) -> Result<Vec<u8>, SdkError>;
 
     async fn public_key_from_secret(
@@ -119,17 +124,28 @@ mod adapter;
 mod default_external;
 
 // Public external signer API
+pub mod audit_log;
 pub mod external;
 pub mod external_types;
+pub mod remote;
 
 // Re-export only the external signer trait and types
+pub use audit_log::{SigningAuditEntry, SigningAuditLog, SigningMethod};
 pub use external::ExternalSigner;
 pub use external_types::*;
+pub use remote::{RemoteSigner, RemoteSignerConfig, RemoteSignerRequest};
 
 // Internal-only exports (used by adapter and builder)
 pub(crate) use adapter::ExternalSignerAdapter;
+pub(crate) use audit_log::SparkSigningAuditLog;
 pub(crate) use default_external::DefaultExternalSigner;
+pub mod backup;
 pub mod breez;
 pub mod lnurl_auth;
 pub mod rtsync;
 pub mod spark;
+#[cfg(all(
+    feature = "sqlcipher",
+    not(all(target_family = "wasm", target_os = "unknown"))
+))]
+pub mod storage;
@@ -0,0 +1,562 @@
+use breez_sdk_common::remote_signer::{RemoteSignerClient, RemoteSignerError};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::error::SignerError;
+
+use super::external::ExternalSigner;
+use super::external_types::{
+    EcdsaSignatureBytes, ExternalAggregateFrostRequest, ExternalEncryptedSecret,
+    ExternalFrostCommitments, ExternalFrostSignature, ExternalFrostSignatureShare,
+    ExternalSecretSource, ExternalSecretToSplit, ExternalSignFrostRequest, ExternalSigningContext,
+    ExternalTreeNodeId, ExternalVerifiableSecretShare, HashedMessageBytes, MessageBytes,
+    PublicKeyBytes, RecoverableEcdsaSignatureBytes, SchnorrSignatureBytes, SecretBytes,
+};
+
+/// Configuration to connect to a remote signer server.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct RemoteSignerConfig {
+    /// The URL of the remote signer server, e.g. `https://signer.example.com:50051`
+    pub server_url: String,
+    /// The api key the server authenticates this client with
+    pub api_key: String,
+}
+
+/// A call of an [`ExternalSigner`] method, as sent to a remote signer server.
+///
+/// The server executes the call with [`RemoteSignerRequest::execute`] and returns the JSON encoded
+/// result of the method.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum RemoteSignerRequest {
+    IdentityPublicKey,
+    DerivePublicKey {
+        path: String,
+    },
+    SignEcdsa {
+        message: MessageBytes,
+        path: String,
+        context: Option<ExternalSigningContext>,
+    },
+    SignEcdsaRecoverable {
+        message: MessageBytes,
+        path: String,
+    },
+    EncryptEcies {
+        message: Vec<u8>,
+        path: String,
+    },
+    DecryptEcies {
+        message: Vec<u8>,
+        path: String,
+    },
+    SignHashSchnorr {
+        hash: Vec<u8>,
+        path: String,
+        context: Option<ExternalSigningContext>,
+    },
+    HmacSha256 {
+        message: Vec<u8>,
+        path: String,
+    },
+    GenerateRandomSigningCommitment,
+    GetPublicKeyForNode {
+        id: ExternalTreeNodeId,
+    },
+    GenerateRandomSecret,
+    StaticDepositSecretEncrypted {
+        index: u32,
+    },
+    StaticDepositSecret {
+        index: u32,
+    },
+    StaticDepositSigningKey {
+        index: u32,
+    },
+    SubtractSecrets {
+        signing_key: ExternalSecretSource,
+        new_signing_key: ExternalSecretSource,
+    },
+    SplitSecretWithProofs {
+        secret: ExternalSecretToSplit,
+        threshold: u32,
+        num_shares: u32,
+        context: ExternalSigningContext,
+    },
+    EncryptSecretForReceiver {
+        encrypted_secret: ExternalEncryptedSecret,
+        receiver_public_key: PublicKeyBytes,
+        context: ExternalSigningContext,
+    },
+    PublicKeyFromSecret {
+        secret: ExternalSecretSource,
+    },
+    SignFrost {
+        request: ExternalSignFrostRequest,
+    },
+    AggregateFrost {
+        request: ExternalAggregateFrostRequest,
+    },
+}
+
+impl RemoteSignerRequest {
+    /// The name of the called method, as used in the JSON encoding.
+    pub fn method(&self) -> &'static str {
+        match self {
+            RemoteSignerRequest::IdentityPublicKey => "identity_public_key",
+            RemoteSignerRequest::DerivePublicKey { .. } => "derive_public_key",
+            RemoteSignerRequest::SignEcdsa { .. } => "sign_ecdsa",
+            RemoteSignerRequest::SignEcdsaRecoverable { .. } => "sign_ecdsa_recoverable",
+            RemoteSignerRequest::EncryptEcies { .. } => "encrypt_ecies",
+            RemoteSignerRequest::DecryptEcies { .. } => "decrypt_ecies",
+            RemoteSignerRequest::SignHashSchnorr { .. } => "sign_hash_schnorr",
+            RemoteSignerRequest::HmacSha256 { .. } => "hmac_sha256",
+            RemoteSignerRequest::GenerateRandomSigningCommitment => {
+                "generate_random_signing_commitment"
+            }
+            RemoteSignerRequest::GetPublicKeyForNode { .. } => "get_public_key_for_node",
+            RemoteSignerRequest::GenerateRandomSecret => "generate_random_secret",
+            RemoteSignerRequest::StaticDepositSecretEncrypted { .. } => {
+                "static_deposit_secret_encrypted"
+            }
+            RemoteSignerRequest::StaticDepositSecret { .. } => "static_deposit_secret",
+            RemoteSignerRequest::StaticDepositSigningKey { .. } => "static_deposit_signing_key",
+            RemoteSignerRequest::SubtractSecrets { .. } => "subtract_secrets",
+            RemoteSignerRequest::SplitSecretWithProofs { .. } => "split_secret_with_proofs",
+            RemoteSignerRequest::EncryptSecretForReceiver { .. } => "encrypt_secret_for_receiver",
+            RemoteSignerRequest::PublicKeyFromSecret { .. } => "public_key_from_secret",
+            RemoteSignerRequest::SignFrost { .. } => "sign_frost",
+            RemoteSignerRequest::AggregateFrost { .. } => "aggregate_frost",
+        }
+    }
+
+    /// The derivation path of the key used by the call, for methods that take one.
+    pub fn derivation_path(&self) -> Option<&str> {
+        match self {
+            RemoteSignerRequest::DerivePublicKey { path }
+            | RemoteSignerRequest::SignEcdsa { path, .. }
+            | RemoteSignerRequest::SignEcdsaRecoverable { path, .. }
+            | RemoteSignerRequest::EncryptEcies { path, .. }
+            | RemoteSignerRequest::DecryptEcies { path, .. }
+            | RemoteSignerRequest::SignHashSchnorr { path, .. }
+            | RemoteSignerRequest::HmacSha256 { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// The wallet operation the call is for, for methods that sign or share key material on
+    /// behalf of one.
+    pub fn context(&self) -> Option<&ExternalSigningContext> {
+        match self {
+            RemoteSignerRequest::SignEcdsa { context, .. }
+            | RemoteSignerRequest::SignHashSchnorr { context, .. } => context.as_ref(),
+            RemoteSignerRequest::SplitSecretWithProofs { context, .. }
+            | RemoteSignerRequest::EncryptSecretForReceiver { context, .. } => Some(context),
+            RemoteSignerRequest::SignFrost { request } => Some(&request.context),
+            _ => None,
+        }
+    }
+
+    /// Executes the call on `signer` and returns the JSON encoded result.
+    pub async fn execute(self, signer: &dyn ExternalSigner) -> Result<Vec<u8>, SignerError> {
+        match self {
+            RemoteSignerRequest::IdentityPublicKey => to_payload(&signer.identity_public_key()?),
+            RemoteSignerRequest::DerivePublicKey { path } => {
+                to_payload(&signer.derive_public_key(path).await?)
+            }
+            RemoteSignerRequest::SignEcdsa {
+                message,
+                path,
+                context,
+            } => to_payload(&signer.sign_ecdsa(message, path, context).await?),
+            RemoteSignerRequest::SignEcdsaRecoverable { message, path } => {
+                to_payload(&signer.sign_ecdsa_recoverable(message, path).await?)
+            }
+            RemoteSignerRequest::EncryptEcies { message, path } => {
+                to_payload(&signer.encrypt_ecies(message, path).await?)
+            }
+            RemoteSignerRequest::DecryptEcies { message, path } => {
+                to_payload(&signer.decrypt_ecies(message, path).await?)
+            }
+            RemoteSignerRequest::SignHashSchnorr {
+                hash,
+                path,
+                context,
+            } => to_payload(&signer.sign_hash_schnorr(hash, path, context).await?),
+            RemoteSignerRequest::HmacSha256 { message, path } => {
+                to_payload(&signer.hmac_sha256(message, path).await?)
+            }
+            RemoteSignerRequest::GenerateRandomSigningCommitment => {
+                to_payload(&signer.generate_random_signing_commitment().await?)
+            }
+            RemoteSignerRequest::GetPublicKeyForNode { id } => {
+                to_payload(&signer.get_public_key_for_node(id).await?)
+            }
+            RemoteSignerRequest::GenerateRandomSecret => {
+                to_payload(&signer.generate_random_secret().await?)
+            }
+            RemoteSignerRequest::StaticDepositSecretEncrypted { index } => {
+                to_payload(&signer.static_deposit_secret_encrypted(index).await?)
+            }
+            RemoteSignerRequest::StaticDepositSecret { index } => {
+                to_payload(&signer.static_deposit_secret(index).await?)
+            }
+            RemoteSignerRequest::StaticDepositSigningKey { index } => {
+                to_payload(&signer.static_deposit_signing_key(index).await?)
+            }
+            RemoteSignerRequest::SubtractSecrets {
+                signing_key,
+                new_signing_key,
+            } => to_payload(
+                &signer
+                    .subtract_secrets(signing_key, new_signing_key)
+                    .await?,
+            ),
+            RemoteSignerRequest::SplitSecretWithProofs {
+                secret,
+                threshold,
+                num_shares,
+                context,
+            } => to_payload(
+                &signer
+                    .split_secret_with_proofs(secret, threshold, num_shares, context)
+                    .await?,
+            ),
+            RemoteSignerRequest::EncryptSecretForReceiver {
+                encrypted_secret,
+                receiver_public_key,
+                context,
+            } => to_payload(
+                &signer
+                    .encrypt_secret_for_receiver(encrypted_secret, receiver_public_key, context)
+                    .await?,
+            ),
+            RemoteSignerRequest::PublicKeyFromSecret { secret } => {
+                to_payload(&signer.public_key_from_secret(secret).await?)
+            }
+            RemoteSignerRequest::SignFrost { request } => {
+                to_payload(&signer.sign_frost(request).await?)
+            }
+            RemoteSignerRequest::AggregateFrost { request } => {
+                to_payload(&signer.aggregate_frost(request).await?)
+            }
+        }
+    }
+}
+
+fn to_payload<T: Serialize>(value: &T) -> Result<Vec<u8>, SignerError> {
+    serde_json::to_vec(value)
+        .map_err(|e| SignerError::Generic(format!("Failed to serialize signer result: {e}")))
+}
+
+impl From<RemoteSignerError> for SignerError {
+    fn from(value: RemoteSignerError) -> Self {
+        match value {
+            RemoteSignerError::PolicyViolation(e) => SignerError::PolicyViolation(e),
+            RemoteSignerError::InvalidInput(e) => SignerError::InvalidInput(e),
+            RemoteSignerError::Generic(e) => SignerError::Generic(e),
+        }
+    }
+}
+
+/// An [`ExternalSigner`] that forwards every call to a remote signer server over gRPC.
+///
+/// This keeps the seed on a separate host, which can enforce its own signing policy.
+pub struct RemoteSigner {
+    client: RemoteSignerClient,
+    identity_public_key: PublicKeyBytes,
+}
+
+impl RemoteSigner {
+    /// Connects to the remote signer server and fetches the identity public key of its wallet.
+    pub async fn connect(config: RemoteSignerConfig) -> Result<Self, SignerError> {
+        let client = RemoteSignerClient::new(&config.server_url, &config.api_key)
+            .map_err(|e| SignerError::Generic(format!("Failed to create signer client: {e}")))?;
+        let identity_public_key =
+            Self::call_client(&client, &RemoteSignerRequest::IdentityPublicKey).await?;
+        Ok(Self {
+            client,
+            identity_public_key,
+        })
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        request: &RemoteSignerRequest,
+    ) -> Result<T, SignerError> {
+        Self::call_client(&self.client, request).await
+    }
+
+    async fn call_client<T: DeserializeOwned>(
+        client: &RemoteSignerClient,
+        request: &RemoteSignerRequest,
+    ) -> Result<T, SignerError> {
+        let payload = serde_json::to_vec(request).map_err(|e| {
+            SignerError::Generic(format!("Failed to serialize signer request: {e}"))
+        })?;
+        let reply = client.sign(payload).await?;
+        serde_json::from_slice(&reply).map_err(|e| {
+            SignerError::Generic(format!(
+                "Invalid remote signer reply to {}: {e}",
+                request.method()
+            ))
+        })
+    }
+}
+
+#[macros::async_trait]
+impl ExternalSigner for RemoteSigner {
+    fn identity_public_key(&self) -> Result<PublicKeyBytes, SignerError> {
+        Ok(self.identity_public_key.clone())
+    }
+
+    async fn derive_public_key(&self, path: String) -> Result<PublicKeyBytes, SignerError> {
+        self.call(&RemoteSignerRequest::DerivePublicKey { path })
+            .await
+    }
+
+    async fn sign_ecdsa(
+        &self,
+        message: MessageBytes,
+        path: String,
+        context: Option<ExternalSigningContext>,
+    ) -> Result<EcdsaSignatureBytes, SignerError> {
+        self.call(&RemoteSignerRequest::SignEcdsa {
+            message,
+            path,
+            context,
+        })
+        .await
+    }
+
+    async fn sign_ecdsa_recoverable(
+        &self,
+        message: MessageBytes,
+        path: String,
+    ) -> Result<RecoverableEcdsaSignatureBytes, SignerError> {
+        self.call(&RemoteSignerRequest::SignEcdsaRecoverable { message, path })
+            .await
+    }
+
+    async fn encrypt_ecies(&self, message: Vec<u8>, path: String) -> Result<Vec<u8>, SignerError> {
+        self.call(&RemoteSignerRequest::EncryptEcies { message, path })
+            .await
+    }
+
+    async fn decrypt_ecies(&self, message: Vec<u8>, path: String) -> Result<Vec<u8>, SignerError> {
+        self.call(&RemoteSignerRequest::DecryptEcies { message, path })
+            .await
+    }
+
+    async fn sign_hash_schnorr(
+        &self,
+        hash: Vec<u8>,
+        path: String,
+        context: Option<ExternalSigningContext>,
+    ) -> Result<SchnorrSignatureBytes, SignerError> {
+        self.call(&RemoteSignerRequest::SignHashSchnorr {
+            hash,
+            path,
+            context,
+        })
+        .await
+    }
+
+    async fn hmac_sha256(
+        &self,
+        message: Vec<u8>,
+        path: String,
+    ) -> Result<HashedMessageBytes, SignerError> {
+        self.call(&RemoteSignerRequest::HmacSha256 { message, path })
+            .await
+    }
+
+    async fn generate_random_signing_commitment(
+        &self,
+    ) -> Result<ExternalFrostCommitments, SignerError> {
+        self.call(&RemoteSignerRequest::GenerateRandomSigningCommitment)
+            .await
+    }
+
+    async fn get_public_key_for_node(
+        &self,
+        id: ExternalTreeNodeId,
+    ) -> Result<PublicKeyBytes, SignerError> {
+        self.call(&RemoteSignerRequest::GetPublicKeyForNode { id })
+            .await
+    }
+
+    async fn generate_random_secret(&self) -> Result<ExternalEncryptedSecret, SignerError> {
+        self.call(&RemoteSignerRequest::GenerateRandomSecret).await
+    }
+
+    async fn static_deposit_secret_encrypted(
+        &self,
+        index: u32,
+    ) -> Result<ExternalSecretSource, SignerError> {
+        self.call(&RemoteSignerRequest::StaticDepositSecretEncrypted { index })
+            .await
+    }
+
+    async fn static_deposit_secret(&self, index: u32) -> Result<SecretBytes, SignerError> {
+        self.call(&RemoteSignerRequest::StaticDepositSecret { index })
+            .await
+    }
+
+    async fn static_deposit_signing_key(&self, index: u32) -> Result<PublicKeyBytes, SignerError> {
+        self.call(&RemoteSignerRequest::StaticDepositSigningKey { index })
+            .await
+    }
+
+    async fn subtract_secrets(
+        &self,
+        signing_key: ExternalSecretSource,
+        new_signing_key: ExternalSecretSource,
+    ) -> Result<ExternalSecretSource, SignerError> {
+        self.call(&RemoteSignerRequest::SubtractSecrets {
+            signing_key,
+            new_signing_key,
+        })
+        .await
+    }
+
+    async fn split_secret_with_proofs(
+        &self,
+        secret: ExternalSecretToSplit,
+        threshold: u32,
+        num_shares: u32,
+        context: ExternalSigningContext,
+    ) -> Result<Vec<ExternalVerifiableSecretShare>, SignerError> {
+        self.call(&RemoteSignerRequest::SplitSecretWithProofs {
+            secret,
+            threshold,
+            num_shares,
+            context,
+        })
+        .await
+    }
+
+    async fn encrypt_secret_for_receiver(
+        &self,
+        encrypted_secret: ExternalEncryptedSecret,
+        receiver_public_key: PublicKeyBytes,
+        context: ExternalSigningContext,
+    ) -> Result<Vec<u8>, SignerError> {
+        self.call(&RemoteSignerRequest::EncryptSecretForReceiver {
+            encrypted_secret,
+            receiver_public_key,
+            context,
+        })
+        .await
+    }
+
+    async fn public_key_from_secret(
+        &self,
+        secret: ExternalSecretSource,
+    ) -> Result<PublicKeyBytes, SignerError> {
+        self.call(&RemoteSignerRequest::PublicKeyFromSecret { secret })
+            .await
+    }
+
+    async fn sign_frost(
+        &self,
+        request: ExternalSignFrostRequest,
+    ) -> Result<ExternalFrostSignatureShare, SignerError> {
+        self.call(&RemoteSignerRequest::SignFrost { request }).await
+    }
+
+    async fn aggregate_frost(
+        &self,
+        request: ExternalAggregateFrostRequest,
+    ) -> Result<ExternalFrostSignature, SignerError> {
+        self.call(&RemoteSignerRequest::AggregateFrost { request })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Network,
+        models::KeySetType,
+        signer::{
+            DefaultExternalSigner, ExternalSigner, ExternalSigningContext,
+            ExternalSigningOperation, MessageBytes, PublicKeyBytes, RecoverableEcdsaSignatureBytes,
+        },
+    };
+
+    use super::RemoteSignerRequest;
+
+    fn create_signer() -> DefaultExternalSigner {
+        DefaultExternalSigner::new(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about".to_string(),
+            None,
+            Network::Regtest,
+            KeySetType::Default,
+            false,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn roundtrip(request: &RemoteSignerRequest) -> RemoteSignerRequest {
+        serde_json::from_slice(&serde_json::to_vec(request).unwrap()).unwrap()
+    }
+
+    #[macros::async_test_all]
+    async fn test_execute_matches_local_signer() {
+        let signer = create_signer();
+
+        let request = roundtrip(&RemoteSignerRequest::IdentityPublicKey);
+        let payload = request.execute(&signer).await.unwrap();
+        let identity: PublicKeyBytes = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(identity, signer.identity_public_key().unwrap());
+
+        let message = MessageBytes::new(vec![7; 32]);
+        let path = "m/0'/0'/0'".to_string();
+        let request = roundtrip(&RemoteSignerRequest::SignEcdsaRecoverable {
+            message: message.clone(),
+            path: path.clone(),
+        });
+        let payload = request.execute(&signer).await.unwrap();
+        let remote: RecoverableEcdsaSignatureBytes = serde_json::from_slice(&payload).unwrap();
+        let local = signer.sign_ecdsa_recoverable(message, path).await.unwrap();
+        assert_eq!(remote.bytes, local.bytes);
+    }
+
+    #[macros::test_all]
+    fn test_request_method_and_path() {
+        let request = RemoteSignerRequest::DerivePublicKey {
+            path: "m/8797555'/0'".to_string(),
+        };
+        assert_eq!(request.method(), "derive_public_key");
+        assert_eq!(request.derivation_path(), Some("m/8797555'/0'"));
+
+        let encoded = serde_json::to_value(&request).unwrap();
+        assert_eq!(encoded["method"], "derive_public_key");
+
+        let request = RemoteSignerRequest::GenerateRandomSecret;
+        assert_eq!(request.method(), "generate_random_secret");
+        assert_eq!(request.derivation_path(), None);
+        assert_eq!(request.context(), None);
+    }
+
+    #[macros::test_all]
+    fn test_request_context() {
+        let context = ExternalSigningContext {
+            operation: ExternalSigningOperation::Transfer,
+            amount: Some(1_000),
+            counterparty: Some("receiver".to_string()),
+            leaf_ids: Vec::new(),
+        };
+        let request = roundtrip(&RemoteSignerRequest::SignHashSchnorr {
+            hash: vec![1; 32],
+            path: "m".to_string(),
+            context: Some(context.clone()),
+        });
+        assert_eq!(request.context(), Some(&context));
+
+        let encoded = serde_json::to_value(&request).unwrap();
+        assert_eq!(encoded["params"]["context"]["operation"], "Transfer");
+    }
+}
@@ -1,6 +1,7 @@
 use spark_wallet::{
     AggregateFrostRequest, EncryptedSecret, FrostSigningCommitmentsWithNonces, SecretSource,
-    SecretToSplit, SignFrostRequest, Signer, SignerError, TreeNodeId, VerifiableSecretShare,
+    SecretToSplit, SignFrostRequest, Signer, SignerError, SigningContext, TreeNodeId,
+    VerifiableSecretShare,
 };
 use std::sync::Arc;
 
@@ -27,6 +28,7 @@ impl Signer for SparkSigner {
     async fn sign_message_ecdsa_with_identity_key(
         &self,
         message: &[u8],
+        context: &SigningContext,
     ) -> Result<secp256k1::ecdsa::Signature, SignerError> {
         use bitcoin::hashes::{Hash, sha256};
         use bitcoin::secp256k1::Message;
@@ -35,7 +37,7 @@ impl Signer for SparkSigner {
         let hash = sha256::Hash::hash(message);
         let msg = Message::from_digest(hash.to_byte_array());
         self.signer
-            .sign_ecdsa(msg, &identity_path)
+            .sign_ecdsa(msg, &identity_path, Some(context))
             .await
             .map_err(|e| SignerError::Generic(e.to_string()))
     }
@@ -43,10 +45,11 @@ impl Signer for SparkSigner {
     async fn sign_hash_schnorr_with_identity_key(
         &self,
         hash: &[u8],
+        context: &SigningContext,
     ) -> Result<schnorr::Signature, SignerError> {
         let identity_path = DerivationPath::master();
         self.signer
-            .sign_hash_schnorr(hash, &identity_path)
+            .sign_hash_schnorr(hash, &identity_path, Some(context))
             .await
             .map_err(|e| SignerError::Generic(e.to_string()))
     }
@@ -120,9 +123,10 @@ impl Signer for SparkSigner {
         secret: &SecretToSplit,
         threshold: u32,
         num_shares: usize,
+        context: &SigningContext,
     ) -> Result<Vec<VerifiableSecretShare>, SignerError> {
         self.signer
-            .split_secret_with_proofs(secret, threshold, num_shares)
+            .split_secret_with_proofs(secret, threshold, num_shares, context)
             .await
             .map_err(|e| SignerError::Generic(e.to_string()))
     }
@@ -131,9 +135,10 @@ impl Signer for SparkSigner {
         &self,
         private_key: &EncryptedSecret,
         receiver_public_key: &PublicKey,
+        context: &SigningContext,
     ) -> Result<Vec<u8>, SignerError> {
         self.signer
-            .encrypt_secret_for_receiver(private_key, receiver_public_key)
+            .encrypt_secret_for_receiver(private_key, receiver_public_key, context)
             .await
             .map_err(|e| SignerError::Generic(e.to_string()))
     }
@@ -0,0 +1,51 @@
+use bitcoin::bip32::{ChildNumber, DerivationPath};
+use bitcoin::hashes::Hash;
+
+use crate::{Network, SdkError, signer::BreezSigner};
+
+const STORAGE_ENCRYPTION_DERIVATION_PATH: &str = "m/1937010546'/0'";
+const STORAGE_ENCRYPTION_DERIVATION_PATH_TEST: &str = "m/1937010546'/1'";
+/// Input of the HMAC producing the key, so the key can't be reused for other purposes
+const STORAGE_ENCRYPTION_KEY_LABEL: &[u8] = b"breez-sdk-storage-encryption-key";
+
+/// Keys encrypting the local storage, derived from the wallet seed.
+pub struct StorageEncryptionKeys {
+    /// The key of the requested version
+    pub key: [u8; 32],
+    /// The keys of all lower versions, the storage may still be encrypted with one of them
+    pub previous_keys: Vec<[u8; 32]>,
+}
+
+/// Derives the storage encryption key of `key_version` and all previous versions. Each version
+/// is an HMAC with a key derived at its own hardened path, so external signers can derive it
+/// without exposing private keys.
+pub async fn derive_storage_encryption_keys(
+    signer: &dyn BreezSigner,
+    network: Network,
+    key_version: u32,
+) -> Result<StorageEncryptionKeys, SdkError> {
+    let base_path: DerivationPath = match network {
+        Network::Mainnet => STORAGE_ENCRYPTION_DERIVATION_PATH,
+        Network::Regtest => STORAGE_ENCRYPTION_DERIVATION_PATH_TEST,
+    }
+    .parse()
+    .map_err(|e: bitcoin::bip32::Error| SdkError::Generic(e.to_string()))?;
+
+    let mut keys = Vec::new();
+    for version in 0..=key_version {
+        let child = ChildNumber::from_hardened_idx(version)
+            .map_err(|e| SdkError::InvalidInput(format!("Invalid storage key version: {e}")))?;
+        let hmac = signer
+            .hmac_sha256(&base_path.child(child), STORAGE_ENCRYPTION_KEY_LABEL)
+            .await?;
+        keys.push(hmac.to_byte_array());
+    }
+
+    let key = keys.pop().ok_or_else(|| {
+        SdkError::Generic("Failed to derive the storage encryption key".to_string())
+    })?;
+    Ok(StorageEncryptionKeys {
+        key,
+        previous_keys: keys,
+    })
+}
@@ -4,7 +4,7 @@ use bitcoin::secp256k1::PublicKey;
 use flashnet::{
     CacheStore, ClawbackRequest, ClawbackResponse, ExecuteSwapRequest, FlashnetClient,
     FlashnetConfig, FlashnetError, GetMinAmountsRequest, ListPoolsRequest, PoolSortOrder,
-    SimulateSwapRequest,
+    SimulateSwapRequest, SimulateSwapResponse,
 };
 use spark_wallet::{ListTransfersRequest, SparkWallet, TransferId};
 use tokio::{
@@ -190,6 +190,7 @@ impl FlashnetTokenConverter {
             status: ConversionStatus::RefundNeeded,
             fee,
             purpose,
+            expected_amount_out,
         }) = conversion_info
         else {
             return Err(ConversionError::RefundFailed(
@@ -235,6 +236,7 @@ impl FlashnetTokenConverter {
                                 status: ConversionStatus::Refunded,
                                 fee: *fee,
                                 purpose: purpose.clone(),
+                                expected_amount_out: *expected_amount_out,
                             }),
                             ..Default::default()
                         },
@@ -330,6 +332,25 @@ impl FlashnetTokenConverter {
         conversion_options: &ConversionOptions,
         amount_out: u128,
     ) -> Result<Option<ConversionEstimate>, ConversionError> {
+        let (amount_in, response) = self
+            .simulate_for_amount_out(conversion_pool, conversion_options, amount_out)
+            .await?;
+
+        Ok(response.fee_paid_asset_in.map(|fee| ConversionEstimate {
+            options: conversion_options.clone(),
+            amount: amount_in,
+            fee,
+        }))
+    }
+
+    /// Simulates converting the amount required to receive at least `amount_out`.
+    /// Returns the required amount in, and the simulated swap.
+    async fn simulate_for_amount_out(
+        &self,
+        conversion_pool: &TokenConversionPool,
+        conversion_options: &ConversionOptions,
+        amount_out: u128,
+    ) -> Result<(u128, SimulateSwapResponse), ConversionError> {
         let TokenConversionPool {
             asset_in_address,
             asset_out_address,
@@ -370,11 +391,7 @@ impl FlashnetTokenConverter {
             )));
         }
 
-        Ok(response.fee_paid_asset_in.map(|fee| ConversionEstimate {
-            options: conversion_options.clone(),
-            amount: amount_in,
-            fee,
-        }))
+        Ok((amount_in, response))
     }
 
     /// Fetches a payment by its conversion identifier.
@@ -440,11 +457,13 @@ impl FlashnetTokenConverter {
     /// * `inbound_identifier` - The inbound spark transfer id or token transaction hash if the conversion was successful.
     /// * `refund_identifier` - The inbound refund spark transfer id or token transaction hash if the conversion was refunded.
     /// * `fee` - The fee paid for the conversion.
+    /// * `expected_amount_out` - The amount expected to be received from the conversion.
     /// * `purpose` - The purpose of the conversion.
     ///
     /// Returns:
     /// * The sent payment id of the conversion.
     /// * The received payment id of the conversion.
+    #[allow(clippy::too_many_arguments)]
     async fn update_payment_conversion_info(
         &self,
         pool_id: &PublicKey,
@@ -452,6 +471,7 @@ impl FlashnetTokenConverter {
         inbound_identifier: Option<String>,
         refund_identifier: Option<String>,
         fee: Option<u128>,
+        expected_amount_out: Option<u128>,
         purpose: &ConversionPurpose,
     ) -> Result<(String, Option<String>), ConversionError> {
         debug!(
@@ -482,6 +502,7 @@ impl FlashnetTokenConverter {
                         status: status.clone(),
                         fee,
                         purpose: None,
+                        expected_amount_out,
                     }),
                     ..Default::default()
                 },
@@ -497,6 +518,7 @@ impl FlashnetTokenConverter {
                     status: status.clone(),
                     fee: None,
                     purpose: Some(purpose.clone()),
+                    expected_amount_out: None,
                 }),
                 ..Default::default()
             };
@@ -522,6 +544,7 @@ impl FlashnetTokenConverter {
                     status,
                     fee: None,
                     purpose: None,
+                    expected_amount_out: None,
                 }),
                 ..Default::default()
             };
@@ -549,19 +572,17 @@ impl TokenConverter for FlashnetTokenConverter {
         amount: ConversionAmount,
     ) -> Result<TokenConversionResponse, ConversionError> {
         // Determine amount_in and min_amount_out based on ConversionAmount variant
-        let (amount_in, min_amount_out) = match amount {
+        let (amount_in, min_amount_out, expected_amount_out) = match amount {
             ConversionAmount::MinAmountOut(min_out) => {
                 // Calculate amount_in from min_amount_out
                 let conversion_pool = self
                     .get_conversion_pool(options, token_identifier, min_out)
                     .await?;
-                let estimate = self
-                    .estimate_internal(&conversion_pool, options, min_out)
-                    .await?
-                    .ok_or(ConversionError::ConversionFailed(
-                        "No conversion estimate available".to_string(),
-                    ))?;
-                (estimate.amount, min_out)
+                // Keep the quoted output as the expected amount, min_out only bounds it
+                let (amount_in, simulate_response) = self
+                    .simulate_for_amount_out(&conversion_pool, options, min_out)
+                    .await?;
+                (amount_in, min_out, simulate_response.amount_out)
             }
             ConversionAmount::AmountIn(amount_in) => {
                 // We have the input, simulate to get expected output
@@ -589,7 +610,7 @@ impl TokenConverter for FlashnetTokenConverter {
                     .saturating_mul(10_000u128.saturating_sub(u128::from(max_slippage)))
                     .saturating_div(10_000);
 
-                (amount_in, min_out)
+                (amount_in, min_out, simulate_response.amount_out)
             }
         };
 
@@ -629,6 +650,7 @@ impl TokenConverter for FlashnetTokenConverter {
                         response.outbound_transfer_id,
                         response.refund_transfer_id,
                         response.fee_amount,
+                        Some(expected_amount_out),
                         purpose,
                     )
                     .await?;
@@ -663,6 +685,7 @@ impl TokenConverter for FlashnetTokenConverter {
                             None,
                             None,
                             None,
+                            Some(expected_amount_out),
                             purpose,
                         )
                         .await;
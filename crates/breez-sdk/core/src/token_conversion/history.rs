@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+
+use tracing::warn;
+
+use crate::{
+    Conversion, ConversionInfo, ConversionStep, ConversionSummary, Payment, PaymentDetails,
+    PaymentType,
+};
+
+use super::ConversionStatus;
+
+/// Returns the conversion info of a Spark or token payment, if any.
+pub(crate) fn conversion_info(payment: &Payment) -> Option<&ConversionInfo> {
+    match &payment.details {
+        Some(
+            PaymentDetails::Spark {
+                conversion_info: Some(info),
+                ..
+            }
+            | PaymentDetails::Token {
+                conversion_info: Some(info),
+                ..
+            },
+        ) => Some(info),
+        _ => None,
+    }
+}
+
+fn asset(step: &ConversionStep) -> Option<&String> {
+    step.token_metadata.as_ref().map(|m| &m.identifier)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn rate(from_amount: u128, to_amount: u128) -> Option<f64> {
+    (from_amount > 0).then(|| to_amount as f64 / from_amount as f64)
+}
+
+fn slippage_bps(expected_amount: u128, amount: u128) -> Option<i64> {
+    let expected = i128::try_from(expected_amount).ok()?;
+    let actual = i128::try_from(amount).ok()?;
+    let bps = expected
+        .checked_sub(actual)?
+        .checked_mul(10_000)?
+        .checked_div(expected)?;
+    i64::try_from(bps).ok()
+}
+
+/// Builds the conversions from their payments, grouped by conversion id.
+///
+/// Each conversion has a sent payment, and may have a received payment and a refund
+/// payment. The refund is the received payment in the same asset as the sent one.
+/// Payments without conversion info are ignored.
+pub(crate) fn conversions_from_payments(payments: &[Payment]) -> Vec<Conversion> {
+    let mut payments_by_conversion: HashMap<&str, Vec<&Payment>> = HashMap::new();
+    for payment in payments {
+        if let Some(info) = conversion_info(payment) {
+            payments_by_conversion
+                .entry(info.conversion_id.as_str())
+                .or_default()
+                .push(payment);
+        }
+    }
+
+    let mut conversions: Vec<Conversion> = payments_by_conversion
+        .into_values()
+        .filter_map(|payments| {
+            let sent = payments
+                .iter()
+                .find(|p| p.payment_type == PaymentType::Send)?;
+            let sent_info = conversion_info(sent)?;
+            let from = match ConversionStep::try_from(*sent) {
+                Ok(step) => step,
+                Err(e) => {
+                    warn!("Failed to build conversion step: {e}");
+                    return None;
+                }
+            };
+            let (refunds, received): (Vec<_>, Vec<_>) = payments
+                .iter()
+                .filter(|p| p.payment_type == PaymentType::Receive)
+                .filter_map(|p| ConversionStep::try_from(*p).ok())
+                .partition(|step| asset(step) == asset(&from));
+            let to = received.into_iter().next();
+            let purpose = payments
+                .iter()
+                .find_map(|p| conversion_info(p).and_then(|i| i.purpose.clone()));
+            let effective_rate = to.as_ref().and_then(|to| rate(from.amount, to.amount));
+            let slippage_bps = to.as_ref().and_then(|to| {
+                sent_info
+                    .expected_amount_out
+                    .and_then(|expected| slippage_bps(expected, to.amount))
+            });
+            Some(Conversion {
+                conversion_id: sent_info.conversion_id.clone(),
+                pool_id: sent_info.pool_id.clone(),
+                status: sent_info.status.clone(),
+                purpose,
+                timestamp: sent.timestamp,
+                fee: sent_info.fee.unwrap_or(0),
+                expected_amount_out: sent_info.expected_amount_out,
+                effective_rate,
+                slippage_bps,
+                from,
+                to,
+                refund: refunds.into_iter().next(),
+            })
+        })
+        .collect();
+    conversions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    conversions
+}
+
+/// Totals the completed conversions per asset pair.
+pub(crate) fn summarize_conversions(conversions: &[Conversion]) -> Vec<ConversionSummary> {
+    let mut summaries: HashMap<(Option<&String>, Option<&String>), ConversionSummary> =
+        HashMap::new();
+    for conversion in conversions {
+        let Some(to) = &conversion.to else {
+            continue;
+        };
+        if conversion.status != ConversionStatus::Completed {
+            continue;
+        }
+        let summary = summaries
+            .entry((asset(&conversion.from), asset(to)))
+            .or_insert_with(|| ConversionSummary {
+                from_token_identifier: asset(&conversion.from).cloned(),
+                to_token_identifier: asset(to).cloned(),
+                conversion_count: 0,
+                total_from_amount: 0,
+                total_to_amount: 0,
+                total_fees: 0,
+                average_rate: None,
+            });
+        summary.conversion_count = summary.conversion_count.saturating_add(1);
+        summary.total_from_amount = summary
+            .total_from_amount
+            .saturating_add(conversion.from.amount);
+        summary.total_to_amount = summary.total_to_amount.saturating_add(to.amount);
+        summary.total_fees = summary.total_fees.saturating_add(conversion.fee);
+    }
+
+    summaries
+        .into_values()
+        .map(|mut summary| {
+            summary.average_rate = rate(summary.total_from_amount, summary.total_to_amount);
+            summary
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConversionPurpose, PaymentMethod, PaymentStatus, TokenMetadata};
+
+    const TOKEN: &str = "token";
+
+    fn payment(
+        id: &str,
+        payment_type: PaymentType,
+        token: bool,
+        amount: u128,
+        timestamp: u64,
+        info: ConversionInfo,
+    ) -> Payment {
+        let details = if token {
+            PaymentDetails::Token {
+                metadata: TokenMetadata {
+                    identifier: TOKEN.to_string(),
+                    issuer_public_key: String::new(),
+                    name: String::new(),
+                    ticker: String::new(),
+                    decimals: 6,
+                    max_supply: 0,
+                    is_freezable: false,
+                    registry_info: None,
+                },
+                tx_hash: id.to_string(),
+                tx_type: crate::TokenTransactionType::Transfer,
+                invoice_details: None,
+                conversion_info: Some(info),
+            }
+        } else {
+            PaymentDetails::Spark {
+                invoice_details: None,
+                htlc_details: None,
+                conversion_info: Some(info),
+            }
+        };
+        Payment {
+            id: id.to_string(),
+            payment_type,
+            status: PaymentStatus::Completed,
+            amount,
+            fees: 0,
+            timestamp,
+            method: if token {
+                PaymentMethod::Token
+            } else {
+                PaymentMethod::Spark
+            },
+            details: Some(details),
+            conversion_details: None,
+        }
+    }
+
+    fn info(
+        conversion_id: &str,
+        status: ConversionStatus,
+        fee: Option<u128>,
+        expected_amount_out: Option<u128>,
+        purpose: Option<ConversionPurpose>,
+    ) -> ConversionInfo {
+        ConversionInfo {
+            pool_id: "pool".to_string(),
+            conversion_id: conversion_id.to_string(),
+            status,
+            fee,
+            purpose,
+            expected_amount_out,
+        }
+    }
+
+    #[test]
+    fn test_conversions_from_payments() {
+        let payments = vec![
+            // Completed conversion of 1000 sats to 990 token units, 1000 expected
+            payment(
+                "c1-send",
+                PaymentType::Send,
+                false,
+                1_000,
+                10,
+                info(
+                    "c1",
+                    ConversionStatus::Completed,
+                    Some(5),
+                    Some(1_000),
+                    None,
+                ),
+            ),
+            payment(
+                "c1-receive",
+                PaymentType::Receive,
+                true,
+                990,
+                11,
+                info(
+                    "c1",
+                    ConversionStatus::Completed,
+                    None,
+                    None,
+                    Some(ConversionPurpose::AutoConversion),
+                ),
+            ),
+            // Refunded conversion of 500 sats
+            payment(
+                "c2-send",
+                PaymentType::Send,
+                false,
+                500,
+                20,
+                info("c2", ConversionStatus::Refunded, None, Some(400), None),
+            ),
+            payment(
+                "c2-refund",
+                PaymentType::Receive,
+                false,
+                500,
+                21,
+                info("c2", ConversionStatus::Refunded, None, None, None),
+            ),
+        ];
+
+        let conversions = conversions_from_payments(&payments);
+        assert_eq!(conversions.len(), 2);
+
+        let refunded = &conversions[0];
+        assert_eq!(refunded.conversion_id, "c2");
+        assert!(refunded.to.is_none());
+        assert_eq!(refunded.refund.as_ref().unwrap().payment_id, "c2-refund");
+        assert_eq!(refunded.slippage_bps, None);
+
+        let completed = &conversions[1];
+        assert_eq!(completed.conversion_id, "c1");
+        assert_eq!(completed.status, ConversionStatus::Completed);
+        assert_eq!(completed.purpose, Some(ConversionPurpose::AutoConversion));
+        assert_eq!(completed.to.as_ref().unwrap().payment_id, "c1-receive");
+        assert!(completed.refund.is_none());
+        assert_eq!(completed.fee, 5);
+        assert_eq!(completed.effective_rate, Some(0.99));
+        assert_eq!(completed.slippage_bps, Some(100));
+    }
+
+    #[test]
+    fn test_summarize_conversions() {
+        let conversion = |id: &str, from_amount, to_amount, status: ConversionStatus| {
+            let mut payments = vec![payment(
+                &format!("{id}-send"),
+                PaymentType::Send,
+                false,
+                from_amount,
+                1,
+                info(id, status.clone(), Some(1), None, None),
+            )];
+            if status == ConversionStatus::Completed {
+                payments.push(payment(
+                    &format!("{id}-receive"),
+                    PaymentType::Receive,
+                    true,
+                    to_amount,
+                    1,
+                    info(id, status, None, None, None),
+                ));
+            }
+            conversions_from_payments(&payments).remove(0)
+        };
+        let conversions = vec![
+            conversion("a", 1_000, 2_000, ConversionStatus::Completed),
+            conversion("b", 3_000, 4_000, ConversionStatus::Completed),
+            conversion("c", 5_000, 0, ConversionStatus::RefundNeeded),
+        ];
+
+        let summaries = summarize_conversions(&conversions);
+        assert_eq!(summaries.len(), 1);
+        let summary = &summaries[0];
+        assert_eq!(summary.from_token_identifier, None);
+        assert_eq!(summary.to_token_identifier, Some(TOKEN.to_string()));
+        assert_eq!(summary.conversion_count, 2);
+        assert_eq!(summary.total_from_amount, 4_000);
+        assert_eq!(summary.total_to_amount, 6_000);
+        assert_eq!(summary.total_fees, 2);
+        assert_eq!(summary.average_rate, Some(1.5));
+    }
+}
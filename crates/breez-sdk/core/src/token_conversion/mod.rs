@@ -1,9 +1,11 @@
 mod error;
 mod flashnet;
+mod history;
 mod models;
 
 pub use error::ConversionError;
 pub(crate) use flashnet::FlashnetTokenConverter;
+pub(crate) use history::{conversion_info, conversions_from_payments, summarize_conversions};
 pub use models::*;
 
 /// Trait for conversion implementations.
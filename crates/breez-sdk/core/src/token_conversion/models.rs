@@ -1,7 +1,7 @@
 use flashnet::{BTC_ASSET_ADDRESS, Pool};
 use serde::{Deserialize, Serialize};
 
-use crate::SdkError;
+use crate::{ConversionStep, SdkError};
 
 /// Default maximum slippage for conversions in basis points (0.1%)
 pub const DEFAULT_CONVERSION_MAX_SLIPPAGE_BPS: u32 = 10;
@@ -80,6 +80,11 @@ pub struct ConversionInfo {
     pub fee: Option<u128>,
     /// The purpose of the conversion
     pub purpose: Option<ConversionPurpose>,
+    /// The amount expected to be received when the conversion was executed
+    /// Denominated in satoshis if converting to Bitcoin, otherwise in the token base units.
+    #[serde(default)]
+    #[cfg_attr(feature = "uniffi", uniffi(default = None))]
+    pub expected_amount_out: Option<u128>,
 }
 
 pub(crate) struct TokenConversionPool {
@@ -177,3 +182,79 @@ pub struct FetchConversionLimitsResponse {
     /// Denominated in satoshis if converting to Bitcoin, otherwise in the token base units.
     pub min_to_amount: Option<u128>,
 }
+
+/// Request to list the conversions made by the wallet
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ListConversionsRequest {
+    /// Only include conversions created after this timestamp (inclusive)
+    #[cfg_attr(feature = "uniffi", uniffi(default = None))]
+    pub from_timestamp: Option<u64>,
+    /// Only include conversions created before this timestamp (exclusive)
+    #[cfg_attr(feature = "uniffi", uniffi(default = None))]
+    pub to_timestamp: Option<u64>,
+    /// Number of conversions to skip
+    #[cfg_attr(feature = "uniffi", uniffi(default = None))]
+    pub offset: Option<u32>,
+    /// Maximum number of conversions to return
+    #[cfg_attr(feature = "uniffi", uniffi(default = None))]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ListConversionsResponse {
+    /// The conversions, most recent first
+    pub conversions: Vec<Conversion>,
+    /// Totals of the completed conversions per asset pair, over all conversions in the
+    /// requested time range regardless of the offset and limit
+    pub summaries: Vec<ConversionSummary>,
+}
+
+/// A conversion made by the wallet, built from its underlying payments
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct Conversion {
+    /// The conversion id shared by the payments of the conversion
+    pub conversion_id: String,
+    /// The pool id associated with the conversion
+    pub pool_id: String,
+    pub status: ConversionStatus,
+    /// The purpose of the conversion, known once the converted amount is received
+    pub purpose: Option<ConversionPurpose>,
+    /// The timestamp of the sent payment
+    pub timestamp: u64,
+    /// The sent side of the conversion
+    pub from: ConversionStep,
+    /// The received side of the conversion, set when the conversion completed
+    pub to: Option<ConversionStep>,
+    /// The refund of the sent amount, set when the refund was received
+    pub refund: Option<ConversionStep>,
+    /// The fee paid for the conversion, denominated in the sent asset
+    pub fee: u128,
+    /// The amount expected to be received when the conversion was executed
+    pub expected_amount_out: Option<u128>,
+    /// The received amount per sent unit, in base units of both assets
+    pub effective_rate: Option<f64>,
+    /// The difference between the expected and received amounts, in basis points of the
+    /// expected amount. Positive values mean less than expected was received.
+    pub slippage_bps: Option<i64>,
+}
+
+/// Totals of the completed conversions between two assets
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ConversionSummary {
+    /// The identifier of the sent token, or `None` for Bitcoin
+    pub from_token_identifier: Option<String>,
+    /// The identifier of the received token, or `None` for Bitcoin
+    pub to_token_identifier: Option<String>,
+    pub conversion_count: u32,
+    /// The total sent, including fees
+    pub total_from_amount: u128,
+    pub total_to_amount: u128,
+    /// The total conversion fees, denominated in the sent asset
+    pub total_fees: u128,
+    /// The volume weighted average of the received amount per sent unit
+    pub average_rate: Option<f64>,
+}
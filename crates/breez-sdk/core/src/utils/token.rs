@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use breez_sdk_common::input::{InputType, PaymentRequestSource, parse_spark_address};
 use spark_wallet::{BURN_PUBLIC_KEY, PublicKey, SparkWallet};
@@ -7,9 +7,45 @@ use web_time::UNIX_EPOCH;
 
 use crate::{
     Payment, PaymentDetails, PaymentMethod, PaymentStatus, PaymentType, SdkError, Storage,
-    TokenMetadata, TokenTransactionType, persist::ObjectCacheRepository,
+    TokenBalance, TokenMetadata, TokenTransactionType, persist::ObjectCacheRepository,
 };
 
+/// How long sends of a token found frozen fail early before being attempted again,
+/// as the issuer may have unfrozen the outputs since.
+pub(crate) const FROZEN_TOKEN_RECHECK_SECS: u64 = 10 * 60;
+
+/// Moves the spendable amounts of the tokens recently found frozen to their frozen balance.
+///
+/// Spark doesn't report whether outputs are frozen, so a freeze is only known from a failed send.
+/// Once the recheck period passed the amounts are spendable again, until a send finds the token
+/// frozen again.
+pub(crate) fn apply_frozen_tokens(
+    token_balances: &mut HashMap<String, TokenBalance>,
+    frozen_tokens: &HashMap<String, u64>,
+    now: u64,
+) {
+    for (identifier, balance) in token_balances.iter_mut() {
+        if is_recently_frozen(frozen_tokens, identifier, now) {
+            balance.frozen_balance = balance
+                .available_balance
+                .saturating_add(balance.reserved_for_conversion_balance);
+            balance.available_balance = 0;
+            balance.reserved_for_conversion_balance = 0;
+        }
+    }
+}
+
+/// Returns whether a send of the token should fail early because it was recently found frozen.
+pub(crate) fn is_recently_frozen(
+    frozen_tokens: &HashMap<String, u64>,
+    token_identifier: &str,
+    now: u64,
+) -> bool {
+    frozen_tokens
+        .get(token_identifier)
+        .is_some_and(|frozen_at| now.saturating_sub(*frozen_at) < FROZEN_TOKEN_RECHECK_SECS)
+}
+
 /// Returns the metadata for the given token identifiers.
 ///
 /// Results are not guaranteed to be in the same order as the input token identifiers.
@@ -197,3 +233,81 @@ pub(crate) async fn map_and_persist_token_transaction(
         ))
         .cloned()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance(available: u128, reserved_for_conversion: u128) -> TokenBalance {
+        TokenBalance {
+            balance: available.saturating_add(reserved_for_conversion),
+            available_balance: available,
+            pending_outgoing_balance: 5,
+            reserved_for_conversion_balance: reserved_for_conversion,
+            frozen_balance: 0,
+            token_metadata: TokenMetadata {
+                identifier: String::new(),
+                issuer_public_key: String::new(),
+                name: String::new(),
+                ticker: String::new(),
+                decimals: 0,
+                max_supply: 0,
+                is_freezable: true,
+                registry_info: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_apply_frozen_tokens() {
+        let mut token_balances = HashMap::from([
+            ("frozen".to_string(), balance(100, 20)),
+            ("spendable".to_string(), balance(50, 0)),
+        ]);
+        let frozen_tokens = HashMap::from([("frozen".to_string(), 1_000)]);
+
+        apply_frozen_tokens(&mut token_balances, &frozen_tokens, 1_000);
+
+        let frozen = &token_balances["frozen"];
+        assert_eq!(frozen.balance, 120);
+        assert_eq!(frozen.frozen_balance, 120);
+        assert_eq!(frozen.available_balance, 0);
+        assert_eq!(frozen.reserved_for_conversion_balance, 0);
+        assert_eq!(frozen.pending_outgoing_balance, 5);
+        let spendable = &token_balances["spendable"];
+        assert_eq!(spendable.available_balance, 50);
+        assert_eq!(spendable.frozen_balance, 0);
+    }
+
+    #[test]
+    #[allow(clippy::arithmetic_side_effects)]
+    fn test_apply_frozen_tokens_after_recheck_period() {
+        let mut token_balances = HashMap::from([("frozen".to_string(), balance(100, 20))]);
+        let frozen_tokens = HashMap::from([("frozen".to_string(), 1_000)]);
+
+        apply_frozen_tokens(
+            &mut token_balances,
+            &frozen_tokens,
+            1_000 + FROZEN_TOKEN_RECHECK_SECS,
+        );
+
+        let balance = &token_balances["frozen"];
+        assert_eq!(balance.frozen_balance, 0);
+        assert_eq!(balance.available_balance, 100);
+        assert_eq!(balance.reserved_for_conversion_balance, 20);
+    }
+
+    #[test]
+    #[allow(clippy::arithmetic_side_effects)]
+    fn test_is_recently_frozen() {
+        let frozen_tokens = HashMap::from([("token".to_string(), 1_000)]);
+
+        assert!(is_recently_frozen(&frozen_tokens, "token", 1_000));
+        assert!(!is_recently_frozen(
+            &frozen_tokens,
+            "token",
+            1_000 + FROZEN_TOKEN_RECHECK_SECS
+        ));
+        assert!(!is_recently_frozen(&frozen_tokens, "other", 1_000));
+    }
+}
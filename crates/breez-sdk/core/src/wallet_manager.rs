@@ -0,0 +1,512 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use bitcoin::secp256k1::PublicKey;
+use breez_sdk_common::breez_server::BreezServer;
+use spark_wallet::{ConnectionManager, DefaultConnectionManager};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    AddAccountRequest, BitcoinChainService, BreezSdk, Config, EventListener, FiatService, SdkEvent,
+    WalletEvent, WalletEventListener,
+    error::SdkError,
+    sdk_builder::{SdkBuilder, default_breez_server, default_chain_service},
+    signer::ExternalSigner,
+};
+
+type WalletEventListeners = Arc<RwLock<BTreeMap<String, Box<dyn WalletEventListener>>>>;
+
+/// Storage the accounts of a `WalletManager` are kept in.
+#[derive(Clone)]
+enum AccountStorage {
+    /// Default storage, one database per account in the given directory.
+    Default(String),
+    /// `PostgreSQL` storage shared by all accounts, each scoped to its own tenant.
+    #[cfg(feature = "postgres")]
+    Postgres(crate::persist::postgres::PostgresStorage),
+}
+
+/// Builder for creating `WalletManager` instances with customizable components.
+#[derive(Clone)]
+pub struct WalletManagerBuilder {
+    config: Config,
+    storage_dir: Option<String>,
+    #[cfg(feature = "postgres")]
+    postgres_config: Option<crate::persist::postgres::PostgresStorageConfig>,
+    chain_service: Option<Arc<dyn BitcoinChainService>>,
+    fiat_service: Option<Arc<dyn FiatService>>,
+}
+
+impl WalletManagerBuilder {
+    /// Creates a new `WalletManagerBuilder` with the configuration shared by all accounts.
+    ///
+    /// # Arguments
+    /// - `config`: The configuration to be used.
+    pub fn new(config: Config) -> Self {
+        WalletManagerBuilder {
+            config,
+            storage_dir: None,
+            #[cfg(feature = "postgres")]
+            postgres_config: None,
+            chain_service: None,
+            fiat_service: None,
+        }
+    }
+
+    /// Sets the root storage directory to initialize the default storage of each account with.
+    /// Every account gets its own database in the directory.
+    ///
+    /// # Arguments
+    /// - `storage_dir`: The data directory for storage.
+    #[must_use]
+    pub fn with_default_storage(mut self, storage_dir: String) -> Self {
+        self.storage_dir = Some(storage_dir);
+        self
+    }
+
+    /// Sets `PostgreSQL` storage to be shared by all accounts, each scoped to its own tenant.
+    /// The storage instance will be created during `build()`.
+    ///
+    /// # Arguments
+    /// - `config`: The `PostgreSQL` storage configuration.
+    #[cfg(feature = "postgres")]
+    #[must_use]
+    pub fn with_postgres_storage(
+        mut self,
+        config: crate::persist::postgres::PostgresStorageConfig,
+    ) -> Self {
+        self.postgres_config = Some(config);
+        self
+    }
+
+    /// Sets the chain service to be shared by all accounts.
+    ///
+    /// # Arguments
+    /// - `chain_service`: The chain service to be used.
+    #[must_use]
+    pub fn with_chain_service(mut self, chain_service: Arc<dyn BitcoinChainService>) -> Self {
+        self.chain_service = Some(chain_service);
+        self
+    }
+
+    /// Sets the fiat service to be shared by all accounts.
+    ///
+    /// # Arguments
+    /// - `fiat_service`: The fiat service to be used.
+    #[must_use]
+    pub fn with_fiat_service(mut self, fiat_service: Arc<dyn FiatService>) -> Self {
+        self.fiat_service = Some(fiat_service);
+        self
+    }
+
+    /// Builds the `WalletManager` instance. Accounts are added to it afterwards.
+    pub async fn build(self) -> Result<WalletManager, SdkError> {
+        self.config.validate()?;
+
+        #[cfg(feature = "postgres")]
+        let storage = match (self.storage_dir, self.postgres_config) {
+            (Some(storage_dir), None) => AccountStorage::Default(storage_dir),
+            (None, Some(postgres_config)) => AccountStorage::Postgres(
+                crate::persist::postgres::PostgresStorage::new(postgres_config)
+                    .await
+                    .map_err(|e| SdkError::Generic(e.to_string()))?,
+            ),
+            (None, None) => return Err(SdkError::Generic("No storage configured".to_string())),
+            (Some(_), Some(_)) => {
+                return Err(SdkError::Generic(
+                    "Multiple storage configurations provided".to_string(),
+                ));
+            }
+        };
+        #[cfg(not(feature = "postgres"))]
+        let storage = match self.storage_dir {
+            Some(storage_dir) => AccountStorage::Default(storage_dir),
+            None => return Err(SdkError::Generic("No storage configured".to_string())),
+        };
+
+        let chain_service = match self.chain_service {
+            Some(service) => service,
+            None => default_chain_service(self.config.network),
+        };
+
+        Ok(WalletManager {
+            config: self.config,
+            storage,
+            chain_service,
+            fiat_service: self.fiat_service,
+            breez_server: default_breez_server()?,
+            connection_manager: Arc::new(DefaultConnectionManager::new()),
+            accounts: Mutex::new(Accounts::default()),
+            listener_index: AtomicU64::new(0),
+            listeners: Arc::new(RwLock::new(BTreeMap::new())),
+        })
+    }
+}
+
+struct Account {
+    sdk: Arc<BreezSdk>,
+    listener_id: String,
+    identity_pub_key: PublicKey,
+}
+
+/// The accounts of a `WalletManager`.
+#[derive(Default)]
+struct Accounts {
+    started: BTreeMap<String, Account>,
+    /// Identity public keys of the accounts being started. Their ids and keys are reserved, so
+    /// the same account can't be added twice concurrently.
+    starting: BTreeMap<String, PublicKey>,
+}
+
+impl Accounts {
+    /// Reserves the id and keys of an account before it's started.
+    fn reserve(&mut self, account_id: &str, identity_pub_key: PublicKey) -> Result<(), SdkError> {
+        if self.started.contains_key(account_id) || self.starting.contains_key(account_id) {
+            return Err(SdkError::InvalidInput(format!(
+                "Account {account_id} already exists"
+            )));
+        }
+
+        // Two accounts with the same keys would run the same wallet twice.
+        let existing_id = self
+            .started
+            .iter()
+            .map(|(id, account)| (id, account.identity_pub_key))
+            .chain(self.starting.iter().map(|(id, key)| (id, *key)))
+            .find_map(|(id, key)| (key == identity_pub_key).then_some(id));
+        if let Some(existing_id) = existing_id {
+            return Err(SdkError::InvalidInput(format!(
+                "Account {account_id} has the same keys as account {existing_id}"
+            )));
+        }
+
+        self.starting
+            .insert(account_id.to_string(), identity_pub_key);
+        Ok(())
+    }
+
+    /// Adds a started account in place of its reservation.
+    fn insert(&mut self, account_id: String, account: Account) {
+        self.starting.remove(&account_id);
+        self.started.insert(account_id, account);
+    }
+
+    /// Releases the reservation of an account that failed to start.
+    fn release(&mut self, account_id: &str) {
+        self.starting.remove(account_id);
+    }
+}
+
+/// Runs multiple accounts, from one seed or multiple seeds, in the same process.
+///
+/// Accounts share the connections to the Spark operators, the chain service, the fiat service and
+/// the storage, while each one keeps its own wallet state and background tasks. The events of all
+/// accounts are available through a single listener.
+#[cfg_attr(feature = "uniffi", derive(uniffi::Object))]
+pub struct WalletManager {
+    config: Config,
+    storage: AccountStorage,
+    chain_service: Arc<dyn BitcoinChainService>,
+    fiat_service: Option<Arc<dyn FiatService>>,
+    breez_server: Arc<BreezServer>,
+    connection_manager: Arc<dyn ConnectionManager>,
+    accounts: Mutex<Accounts>,
+    listener_index: AtomicU64,
+    listeners: WalletEventListeners,
+}
+
+#[cfg_attr(feature = "uniffi", uniffi::export(async_runtime = "tokio"))]
+impl WalletManager {
+    /// Adds an account and starts it.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The account id, seed and key set of the account
+    ///
+    /// # Returns
+    ///
+    /// The `BreezSdk` handle of the account
+    pub async fn add_account(&self, request: AddAccountRequest) -> Result<Arc<BreezSdk>, SdkError> {
+        let mut builder = SdkBuilder::new(self.config.clone(), request.seed);
+        if let Some(key_set_config) = request.key_set_config {
+            builder = builder.with_key_set(key_set_config);
+        }
+        self.start_account(request.account_id, builder).await
+    }
+
+    /// Adds an account using an external signer and starts it.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_id` - Identifies the account in the manager and in its events
+    /// * `signer` - The external signer of the account
+    ///
+    /// # Returns
+    ///
+    /// The `BreezSdk` handle of the account
+    pub async fn add_account_with_signer(
+        &self,
+        account_id: String,
+        signer: Arc<dyn ExternalSigner>,
+    ) -> Result<Arc<BreezSdk>, SdkError> {
+        let builder = SdkBuilder::new_with_signer(self.config.clone(), signer);
+        self.start_account(account_id, builder).await
+    }
+
+    /// Returns the `BreezSdk` handle of an account, if it was added.
+    pub async fn get_account(&self, account_id: &str) -> Option<Arc<BreezSdk>> {
+        self.accounts
+            .lock()
+            .await
+            .started
+            .get(account_id)
+            .map(|account| Arc::clone(&account.sdk))
+    }
+
+    /// Returns the ids of all added accounts.
+    pub async fn list_accounts(&self) -> Vec<String> {
+        self.accounts.lock().await.started.keys().cloned().collect()
+    }
+
+    /// Stops an account and removes it from the manager. Its data is kept in storage.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the account was found and removed, `false` otherwise
+    pub async fn remove_account(&self, account_id: &str) -> Result<bool, SdkError> {
+        let Some(account) = self.accounts.lock().await.started.remove(account_id) else {
+            return Ok(false);
+        };
+        account
+            .sdk
+            .remove_event_listener(&account.listener_id)
+            .await;
+        account.sdk.disconnect().await?;
+        info!("Removed account {account_id}");
+        Ok(true)
+    }
+
+    /// Registers a listener for the events of all accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - An object that implements the `WalletEventListener` trait
+    ///
+    /// # Returns
+    ///
+    /// A unique identifier for the listener, which can be used to remove it later
+    pub async fn add_event_listener(&self, listener: Box<dyn WalletEventListener>) -> String {
+        let index = self.listener_index.fetch_add(1, Ordering::Relaxed);
+        let id = format!("listener_{}-{}", index, Uuid::new_v4());
+        self.listeners.write().await.insert(id.clone(), listener);
+        id
+    }
+
+    /// Removes a previously registered event listener
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The listener ID returned from `add_event_listener`
+    ///
+    /// # Returns
+    ///
+    /// `true` if the listener was found and removed, `false` otherwise
+    pub async fn remove_event_listener(&self, id: &str) -> bool {
+        self.listeners.write().await.remove(id).is_some()
+    }
+
+    /// Stops all accounts and removes them from the manager. Every account is disconnected,
+    /// even if disconnecting another one fails.
+    pub async fn disconnect(&self) -> Result<(), SdkError> {
+        let accounts = std::mem::take(&mut self.accounts.lock().await.started);
+        let mut errors = Vec::new();
+        for (account_id, account) in accounts {
+            account
+                .sdk
+                .remove_event_listener(&account.listener_id)
+                .await;
+            match account.sdk.disconnect().await {
+                Ok(()) => info!("Disconnected account {account_id}"),
+                Err(e) => {
+                    warn!("Failed to disconnect account {account_id}: {e}");
+                    errors.push(format!("{account_id}: {e}"));
+                }
+            }
+        }
+        if !errors.is_empty() {
+            return Err(SdkError::Generic(format!(
+                "Failed to disconnect accounts: {}",
+                errors.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl WalletManager {
+    async fn start_account(
+        &self,
+        account_id: String,
+        builder: SdkBuilder,
+    ) -> Result<Arc<BreezSdk>, SdkError> {
+        let identity_pub_key = builder.identity_public_key()?;
+        self.accounts
+            .lock()
+            .await
+            .reserve(&account_id, identity_pub_key)?;
+
+        let sdk = match self.build_account(builder).await {
+            Ok(sdk) => Arc::new(sdk),
+            Err(e) => {
+                self.accounts.lock().await.release(&account_id);
+                return Err(e);
+            }
+        };
+
+        let listener_id = sdk
+            .add_event_listener(Box::new(AccountEventForwarder {
+                account_id: account_id.clone(),
+                listeners: Arc::clone(&self.listeners),
+            }))
+            .await;
+        self.accounts.lock().await.insert(
+            account_id.clone(),
+            Account {
+                sdk: Arc::clone(&sdk),
+                listener_id,
+                identity_pub_key,
+            },
+        );
+        info!("Added account {account_id}");
+        Ok(sdk)
+    }
+
+    async fn build_account(&self, builder: SdkBuilder) -> Result<BreezSdk, SdkError> {
+        let mut builder = builder
+            .with_chain_service(Arc::clone(&self.chain_service))
+            .with_breez_server(Arc::clone(&self.breez_server))
+            .with_connection_manager(Arc::clone(&self.connection_manager));
+        if let Some(fiat_service) = &self.fiat_service {
+            builder = builder.with_fiat_service(Arc::clone(fiat_service));
+        }
+        builder = match &self.storage {
+            AccountStorage::Default(storage_dir) => {
+                builder.with_default_storage(storage_dir.clone())
+            }
+            #[cfg(feature = "postgres")]
+            AccountStorage::Postgres(storage) => {
+                builder.with_postgres_tenant_storage(storage.clone())
+            }
+        };
+        builder.build().await
+    }
+}
+
+/// Forwards the events of an account to the listeners of the manager.
+struct AccountEventForwarder {
+    account_id: String,
+    listeners: WalletEventListeners,
+}
+
+#[macros::async_trait]
+impl EventListener for AccountEventForwarder {
+    async fn on_event(&self, event: SdkEvent) {
+        let listeners = self.listeners.read().await;
+        for listener in listeners.values() {
+            listener
+                .on_event(WalletEvent {
+                    account_id: self.account_id.clone(),
+                    event: event.clone(),
+                })
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, sync::Arc};
+
+    use bitcoin::secp256k1::PublicKey;
+    use tokio::sync::{Mutex, RwLock};
+
+    use super::{AccountEventForwarder, Accounts};
+    use crate::{EventListener, SdkEvent, WalletEvent, WalletEventListener, error::SdkError};
+
+    fn public_key(hex: &str) -> PublicKey {
+        PublicKey::from_str(hex).unwrap()
+    }
+
+    struct RecordingListener {
+        events: Arc<Mutex<Vec<WalletEvent>>>,
+    }
+
+    #[macros::async_trait]
+    impl WalletEventListener for RecordingListener {
+        async fn on_event(&self, event: WalletEvent) {
+            self.events.lock().await.push(event);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_events_are_tagged_with_account() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let listeners = Arc::new(RwLock::new(std::collections::BTreeMap::new()));
+        listeners.write().await.insert(
+            "listener".to_string(),
+            Box::new(RecordingListener {
+                events: Arc::clone(&events),
+            }) as Box<dyn WalletEventListener>,
+        );
+        let business = AccountEventForwarder {
+            account_id: "business".to_string(),
+            listeners: Arc::clone(&listeners),
+        };
+        let personal = AccountEventForwarder {
+            account_id: "personal".to_string(),
+            listeners: Arc::clone(&listeners),
+        };
+
+        business.on_event(SdkEvent::Synced).await;
+        personal.on_event(SdkEvent::Synced).await;
+
+        let account_ids: Vec<String> = events
+            .lock()
+            .await
+            .iter()
+            .map(|event| event.account_id.clone())
+            .collect();
+        assert_eq!(account_ids, vec!["business", "personal"]);
+    }
+
+    #[test]
+    fn test_reserve_rejects_duplicate_accounts() {
+        let key = public_key("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+        let other_key =
+            public_key("031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f");
+        let mut accounts = Accounts::default();
+
+        accounts.reserve("business", key).unwrap();
+        // The id and keys are taken while the account starts
+        assert!(matches!(
+            accounts.reserve("business", other_key),
+            Err(SdkError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            accounts.reserve("personal", key),
+            Err(SdkError::InvalidInput(_))
+        ));
+
+        // Failing to start frees them
+        accounts.release("business");
+        accounts.reserve("personal", key).unwrap();
+        accounts.reserve("business", other_key).unwrap();
+    }
+}
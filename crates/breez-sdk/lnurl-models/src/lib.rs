@@ -17,6 +17,13 @@ pub struct RecoverLnurlPayResponse {
     pub lightning_address: String,
     pub username: String,
     pub description: String,
+    #[serde(default, flatten)]
+    pub pay_settings: LnurlPaySettings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nostr: Option<NostrIdentity>,
+    /// The BIP-353 address payment instructions are published at, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bip353_address: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -29,6 +36,50 @@ pub struct RegisterLnurlPayRequest {
     /// invoice payments for this user (LUD-21 and NIP-57 disabled).
     #[serde(default)]
     pub lnurl_private_mode_enabled: bool,
+    #[serde(default, flatten)]
+    pub pay_settings: LnurlPaySettings,
+    /// Nostr identity served for the username as a NIP-05 identifier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nostr: Option<NostrIdentity>,
+    /// When true, the server publishes BIP-353 payment instructions for the username in DNS
+    #[serde(default)]
+    pub publish_bip353: bool,
+    /// BOLT12 offer to include in the BIP-353 payment instructions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bolt12_offer: Option<String>,
+}
+
+/// A nostr identity linked to a lightning address, served as a NIP-05 identifier.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NostrIdentity {
+    /// Hex encoded nostr public key
+    pub pubkey: String,
+    /// Relays the nostr public key can be found on
+    #[serde(default)]
+    pub relays: Vec<String>,
+    /// Proof of ownership of the nostr public key: a nostr event signed by the key, with the
+    /// content returned by [`nostr_proof_content`]
+    pub proof: String,
+}
+
+/// Per-user LNURL-pay settings. Unset values fall back to the server defaults.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LnurlPaySettings {
+    /// Minimum amount (in millisatoshi) that can be sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_sendable: Option<u64>,
+    /// Maximum amount (in millisatoshi) that can be sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_sendable: Option<u64>,
+    /// Maximum length of the payer comment (LUD-12)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment_allowed: Option<u16>,
+    /// Base64 encoded PNG image, added to the metadata as `image/png;base64` (LUD-06)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// Long description, added to the metadata as `text/long-desc` (LUD-06)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub long_description: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -42,6 +93,9 @@ pub struct UnregisterLnurlPayRequest {
 pub struct RegisterLnurlPayResponse {
     pub lnurl: String,
     pub lightning_address: String,
+    /// The BIP-353 address payment instructions are published at, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bip353_address: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -70,6 +124,9 @@ pub struct ListMetadataMetadata {
     pub updated_at: i64,
     /// The payment preimage if invoice has been paid
     pub preimage: Option<String>,
+    /// The payer data json sent by the payer (LUD-18)
+    #[serde(default)]
+    pub payer_data: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -92,6 +149,186 @@ pub struct PublishZapReceiptResponse {
     pub zap_receipt: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateWithdrawLinkRequest {
+    pub signature: String,
+    pub timestamp: Option<u64>,
+    pub description: String,
+    /// Minimum amount (in millisatoshi) that can be withdrawn per use
+    pub min_withdrawable: u64,
+    /// Maximum amount (in millisatoshi) that can be withdrawn per use
+    pub max_withdrawable: u64,
+    /// Number of times the link can be used. Unlimited if not set.
+    pub max_uses: Option<u32>,
+    /// Unix timestamp (seconds) after which the link can no longer be used
+    pub expires_at: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateWithdrawLinkResponse {
+    pub link: WithdrawLink,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListWithdrawLinksRequest {
+    pub signature: String,
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListWithdrawLinksResponse {
+    pub links: Vec<WithdrawLink>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteWithdrawLinkRequest {
+    pub signature: String,
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WithdrawLink {
+    pub id: String,
+    pub lnurl: String,
+    pub description: String,
+    pub min_withdrawable: u64,
+    pub max_withdrawable: u64,
+    pub max_uses: Option<u32>,
+    /// Number of times the link has been used, including pending withdrawals
+    pub uses: u32,
+    pub expires_at: Option<u64>,
+    /// Unix timestamp (milliseconds) when the link was created
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListWithdrawRequestsRequest {
+    pub signature: String,
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListWithdrawRequestsResponse {
+    pub requests: Vec<PendingWithdrawRequest>,
+}
+
+/// An invoice submitted by a payer through a withdraw link, waiting to be paid by the wallet
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingWithdrawRequest {
+    pub payment_hash: String,
+    pub link_id: String,
+    pub invoice: String,
+    pub amount_msat: u64,
+    /// Unix timestamp (milliseconds) when the invoice was submitted
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompleteWithdrawRequest {
+    pub signature: String,
+    pub timestamp: Option<u64>,
+    /// The payment preimage if the invoice was paid, unset if the payment failed
+    pub preimage: Option<String>,
+}
+
+/// Events a webhook can be notified about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// An invoice was requested from the user's lightning address
+    InvoiceRequested,
+    /// An invoice of the user's lightning address was paid
+    InvoicePaid,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::InvoiceRequested => "invoice_requested",
+            WebhookEvent::InvoicePaid => "invoice_paid",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub signature: String,
+    pub timestamp: Option<u64>,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateWebhookResponse {
+    pub webhook: Webhook,
+    /// Hex encoded secret the deliveries are signed with. Only returned when the webhook is
+    /// created.
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListWebhooksRequest {
+    pub signature: String,
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListWebhooksResponse {
+    pub webhooks: Vec<Webhook>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteWebhookRequest {
+    pub signature: String,
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    /// Unix timestamp (milliseconds) when the webhook was created
+    pub created_at: i64,
+}
+
+/// The body of a webhook delivery. Deliveries are retried until acknowledged with a 2xx status,
+/// so the same delivery id can be received more than once.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    pub id: String,
+    pub event: WebhookEvent,
+    pub user_pubkey: String,
+    pub payment_hash: String,
+    pub invoice: String,
+    pub amount_msat: Option<u64>,
+    /// The payment preimage, set for `invoice_paid` events
+    pub preimage: Option<String>,
+    /// The comment sent by the payer (LUD-12)
+    pub comment: Option<String>,
+    /// The payer data json sent by the payer (LUD-18)
+    pub payer_data: Option<String>,
+    /// Unix timestamp (milliseconds) when the event occurred
+    pub created_at: i64,
+}
+
 pub fn sanitize_username(username: &str) -> String {
     username.trim().to_lowercase()
 }
+
+/// The content of the nostr event proving ownership of a nostr identity linked to the lightning
+/// address of the wallet with the given identity public key.
+pub fn nostr_proof_content(lightning_address: &str, pubkey: &str) -> String {
+    format!("nip05:{}:{pubkey}", lightning_address.to_lowercase())
+}
+
+/// The message signed to register a webhook. It covers the subscribed events as well as the url,
+/// so neither can be changed by someone relaying the request. The events are sorted and
+/// deduplicated, so the order they are passed in doesn't matter.
+pub fn webhook_signing_message(url: &str, events: &[WebhookEvent]) -> String {
+    let mut events = events.to_vec();
+    events.sort_unstable();
+    events.dedup();
+    let events: Vec<&str> = events.iter().map(WebhookEvent::as_str).collect();
+    format!("{url}:{}", events.join(","))
+}
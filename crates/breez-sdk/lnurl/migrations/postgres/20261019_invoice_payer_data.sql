@@ -0,0 +1 @@
+ALTER TABLE invoices ADD COLUMN payer_data TEXT;
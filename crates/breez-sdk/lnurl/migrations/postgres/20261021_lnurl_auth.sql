@@ -0,0 +1,18 @@
+CREATE TABLE auth_challenges (
+    k1 VARCHAR(64) PRIMARY KEY,
+    domain TEXT NOT NULL,
+    secret_hash VARCHAR(64) NOT NULL,
+    linking_key VARCHAR(66),
+    expires_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+CREATE INDEX idx_auth_challenges_expires_at ON auth_challenges(expires_at);
+
+CREATE TABLE auth_sessions (
+    token_hash VARCHAR(64) PRIMARY KEY,
+    domain TEXT NOT NULL,
+    linking_key VARCHAR(66) NOT NULL,
+    expires_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+CREATE INDEX idx_auth_sessions_expires_at ON auth_sessions(expires_at);
@@ -0,0 +1,3 @@
+ALTER TABLE users ADD COLUMN nostr_pubkey TEXT;
+ALTER TABLE users ADD COLUMN nostr_relays TEXT;
+ALTER TABLE users ADD COLUMN nostr_proof TEXT;
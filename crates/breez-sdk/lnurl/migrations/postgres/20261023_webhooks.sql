@@ -0,0 +1,22 @@
+CREATE TABLE webhooks (
+    id VARCHAR(64) PRIMARY KEY,
+    domain TEXT NOT NULL,
+    user_pubkey VARCHAR(66) NOT NULL,
+    url TEXT NOT NULL,
+    events TEXT NOT NULL,
+    secret VARCHAR(64) NOT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+CREATE INDEX idx_webhooks_user ON webhooks(user_pubkey);
+
+CREATE TABLE webhook_deliveries (
+    id VARCHAR(64) PRIMARY KEY,
+    webhook_id VARCHAR(64) NOT NULL,
+    payload TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    retry_count INTEGER NOT NULL DEFAULT 0,
+    next_retry_at BIGINT NOT NULL
+);
+CREATE INDEX idx_webhook_deliveries_next_retry_at ON webhook_deliveries(next_retry_at);
+CREATE INDEX idx_webhook_deliveries_webhook_id ON webhook_deliveries(webhook_id);
@@ -0,0 +1,16 @@
+CREATE TABLE rate_limits (
+    limit_key TEXT NOT NULL,
+    window_start BIGINT NOT NULL,
+    hits BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    PRIMARY KEY (limit_key, window_start)
+);
+CREATE INDEX idx_rate_limits_expires_at ON rate_limits(expires_at);
+
+CREATE TABLE username_reservations (
+    domain TEXT NOT NULL,
+    name TEXT NOT NULL,
+    pubkey VARCHAR(66),
+    created_at BIGINT NOT NULL,
+    PRIMARY KEY (domain, name)
+);
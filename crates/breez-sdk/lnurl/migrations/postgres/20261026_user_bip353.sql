@@ -0,0 +1,2 @@
+ALTER TABLE users ADD COLUMN bip353_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE users ADD COLUMN bolt12_offer TEXT;
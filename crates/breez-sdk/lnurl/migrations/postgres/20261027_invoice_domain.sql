@@ -0,0 +1 @@
+ALTER TABLE invoices ADD COLUMN domain TEXT;
@@ -0,0 +1,5 @@
+ALTER TABLE users ADD COLUMN min_sendable BIGINT;
+ALTER TABLE users ADD COLUMN max_sendable BIGINT;
+ALTER TABLE users ADD COLUMN comment_allowed INTEGER;
+ALTER TABLE users ADD COLUMN image TEXT;
+ALTER TABLE users ADD COLUMN long_description TEXT;
@@ -0,0 +1,26 @@
+CREATE TABLE withdraw_links (
+    id TEXT PRIMARY KEY,
+    domain TEXT NOT NULL,
+    user_pubkey TEXT NOT NULL,
+    description TEXT NOT NULL,
+    min_withdrawable BIGINT NOT NULL,
+    max_withdrawable BIGINT NOT NULL,
+    max_uses INTEGER,
+    uses INTEGER NOT NULL DEFAULT 0,
+    expires_at BIGINT,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+CREATE INDEX idx_withdraw_links_user ON withdraw_links(domain, user_pubkey);
+
+CREATE TABLE withdraw_requests (
+    payment_hash TEXT PRIMARY KEY,
+    link_id TEXT NOT NULL,
+    user_pubkey TEXT NOT NULL,
+    invoice TEXT NOT NULL,
+    amount_msat BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+CREATE INDEX idx_withdraw_requests_user_status ON withdraw_requests(user_pubkey, status);
@@ -0,0 +1,22 @@
+CREATE TABLE webhooks (
+    id TEXT PRIMARY KEY,
+    domain TEXT NOT NULL,
+    user_pubkey TEXT NOT NULL,
+    url TEXT NOT NULL,
+    events TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+CREATE INDEX idx_webhooks_user ON webhooks(user_pubkey);
+
+CREATE TABLE webhook_deliveries (
+    id TEXT PRIMARY KEY,
+    webhook_id TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    retry_count INTEGER NOT NULL DEFAULT 0,
+    next_retry_at BIGINT NOT NULL
+);
+CREATE INDEX idx_webhook_deliveries_next_retry_at ON webhook_deliveries(next_retry_at);
+CREATE INDEX idx_webhook_deliveries_webhook_id ON webhook_deliveries(webhook_id);
@@ -0,0 +1,7 @@
+CREATE TABLE payer_data_challenges (
+    k1 TEXT PRIMARY KEY,
+    domain TEXT NOT NULL,
+    username TEXT NOT NULL,
+    expires_at BIGINT NOT NULL
+);
+CREATE INDEX idx_payer_data_challenges_expires_at ON payer_data_challenges(expires_at);
@@ -0,0 +1,360 @@
+use std::str::FromStr;
+
+use axum::{
+    Extension, Json,
+    extract::Path,
+    http::{StatusCode, header::CONTENT_TYPE},
+    response::IntoResponse,
+};
+use bitcoin::secp256k1::PublicKey;
+use lnurl_models::{LnurlPaySettings, NostrIdentity, sanitize_username};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, error, info};
+
+use crate::{
+    bip353,
+    metrics::{METRICS, METRICS_CONTENT_TYPE},
+    repository::{LnurlRepository, LnurlRepositoryError, NewlyPaid},
+    routes::LnurlServer,
+    state::State,
+    time::now_millis,
+    user::{User, UsernameReservation},
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListDomainsResponse {
+    pub domains: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddDomainRequest {
+    pub domain: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminUserResponse {
+    pub domain: String,
+    pub pubkey: String,
+    pub name: String,
+    pub lightning_address: String,
+    pub description: String,
+    pub lnurl_private_mode_enabled: bool,
+    #[serde(flatten)]
+    pub pay_settings: LnurlPaySettings,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nostr: Option<NostrIdentity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bip353_address: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReserveUsernameRequest {
+    pub name: String,
+    /// The pubkey the username is reserved for. If not set, nobody can register the username.
+    pub pubkey: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListUsernameReservationsResponse {
+    pub reservations: Vec<UsernameReservationResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsernameReservationResponse {
+    pub name: String,
+    pub pubkey: Option<String>,
+    /// Unix timestamp (milliseconds) when the username was reserved
+    pub created_at: i64,
+}
+
+impl From<UsernameReservation> for UsernameReservationResponse {
+    fn from(reservation: UsernameReservation) -> Self {
+        UsernameReservationResponse {
+            name: reservation.name,
+            pubkey: reservation.pubkey,
+            created_at: reservation.created_at,
+        }
+    }
+}
+
+impl From<User> for AdminUserResponse {
+    fn from(user: User) -> Self {
+        AdminUserResponse {
+            lightning_address: format!("{}@{}", user.name, user.domain),
+            bip353_address: user.bip353_enabled.then(|| bip353::bip353_address(&user)),
+            domain: user.domain,
+            pubkey: user.pubkey,
+            name: user.name,
+            description: user.description,
+            lnurl_private_mode_enabled: user.lnurl_private_mode_enabled,
+            pay_settings: user.pay_settings,
+            nostr: user.nostr,
+        }
+    }
+}
+
+impl<DB> LnurlServer<DB>
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    /// Prometheus metrics endpoint.
+    pub async fn metrics(
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+        // Gauges backed by the database or shared state are refreshed on scrape
+        match state.db.count_newly_paid().await {
+            Ok(depth) => METRICS.newly_paid_queue_depth.set(depth),
+            Err(e) => error!("failed to count newly paid: {}", e),
+        }
+        let is_leader = *state.leader.borrow();
+        METRICS.leader.set(i64::from(is_leader));
+        let subscriptions = state.subscribed_keys.lock().await.len();
+        METRICS
+            .spark_subscriptions
+            .set(i64::try_from(subscriptions).unwrap_or(i64::MAX));
+
+        let metrics = METRICS.render().map_err(|e| {
+            error!("failed to render metrics: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Value::String("internal server error".into())),
+            )
+        })?;
+        Ok(([(CONTENT_TYPE, METRICS_CONTENT_TYPE)], metrics))
+    }
+
+    pub async fn admin_list_domains(
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<Json<ListDomainsResponse>, (StatusCode, Json<Value>)> {
+        let mut domains = state.db.list_domains().await.map_err(internal_error)?;
+        domains.sort();
+        Ok(Json(ListDomainsResponse { domains }))
+    }
+
+    /// Adds an allowed domain. The domain is served right away by this instance, without
+    /// restarting the server, and by other instances once they reload the allowed domains.
+    pub async fn admin_add_domain(
+        Extension(state): Extension<State<DB>>,
+        Json(payload): Json<AddDomainRequest>,
+    ) -> Result<(), (StatusCode, Json<Value>)> {
+        let domain = payload.domain.trim().to_lowercase();
+        let is_invalid_char = |c: char| c.is_whitespace() || c == '/' || c == '@';
+        if domain.is_empty() || domain.contains(is_invalid_char) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(Value::String("invalid domain".into())),
+            ));
+        }
+
+        state.db.add_domain(&domain).await.map_err(internal_error)?;
+        state
+            .domains
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(domain.clone());
+        info!("added domain '{}' through the admin api", domain);
+        Ok(())
+    }
+
+    /// Looks up a user of a domain by username or pubkey.
+    pub async fn admin_get_user(
+        Path((domain, identifier)): Path<(String, String)>,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<Json<AdminUserResponse>, (StatusCode, Json<Value>)> {
+        let domain = domain.trim().to_lowercase();
+        let user = if let Ok(pubkey) = PublicKey::from_str(&identifier) {
+            state
+                .db
+                .get_user_by_pubkey(&domain, &pubkey.to_string())
+                .await
+        } else {
+            state
+                .db
+                .get_user_by_name(&domain, &sanitize_username(&identifier))
+                .await
+        }
+        .map_err(internal_error)?;
+
+        let Some(user) = user else {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(Value::String("user not found".into())),
+            ));
+        };
+        Ok(Json(user.into()))
+    }
+
+    pub async fn admin_delete_user(
+        Path((domain, pubkey)): Path<(String, String)>,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<(), (StatusCode, Json<Value>)> {
+        let domain = domain.trim().to_lowercase();
+        let user = state
+            .db
+            .get_user_by_pubkey(&domain, &pubkey)
+            .await
+            .map_err(internal_error)?;
+        state
+            .db
+            .delete_user(&domain, &pubkey)
+            .await
+            .map_err(internal_error)?;
+        if let Some(user) = user {
+            bip353::unpublish(&state, &user).await;
+        }
+        info!(
+            "deleted user for pubkey {} of domain '{}' through the admin api",
+            pubkey, domain
+        );
+        Ok(())
+    }
+
+    pub async fn admin_list_username_reservations(
+        Path(domain): Path<String>,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<Json<ListUsernameReservationsResponse>, (StatusCode, Json<Value>)> {
+        let reservations = state
+            .db
+            .list_username_reservations(&domain.trim().to_lowercase())
+            .await
+            .map_err(internal_error)?;
+        Ok(Json(ListUsernameReservationsResponse {
+            reservations: reservations.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    /// Reserves a username, either for a pubkey or for nobody. Users that already registered the
+    /// username keep it.
+    pub async fn admin_reserve_username(
+        Path(domain): Path<String>,
+        Extension(state): Extension<State<DB>>,
+        Json(payload): Json<ReserveUsernameRequest>,
+    ) -> Result<(), (StatusCode, Json<Value>)> {
+        let name = sanitize_username(&payload.name);
+        if name.is_empty() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(Value::String("invalid username".into())),
+            ));
+        }
+        let pubkey = payload
+            .pubkey
+            .map(|pubkey| PublicKey::from_str(&pubkey).map(|pubkey| pubkey.to_string()))
+            .transpose()
+            .map_err(|_| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(Value::String("invalid pubkey".into())),
+                )
+            })?;
+
+        let domain = domain.trim().to_lowercase();
+        state
+            .db
+            .upsert_username_reservation(&UsernameReservation {
+                domain: domain.clone(),
+                name: name.clone(),
+                pubkey,
+                created_at: now_millis(),
+            })
+            .await
+            .map_err(internal_error)?;
+        info!("reserved username '{}' of domain '{}'", name, domain);
+        Ok(())
+    }
+
+    pub async fn admin_delete_username_reservation(
+        Path((domain, name)): Path<(String, String)>,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<(), (StatusCode, Json<Value>)> {
+        let deleted = state
+            .db
+            .delete_username_reservation(&domain.trim().to_lowercase(), &sanitize_username(&name))
+            .await
+            .map_err(internal_error)?;
+        if !deleted {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(Value::String("reservation not found".into())),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Queues the zap receipt of a paid invoice for publishing again, resetting its retries.
+    /// Useful when publishing gave up, or the relays were unreachable for a long time.
+    pub async fn admin_republish_zap(
+        Path(payment_hash): Path<String>,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+        if state.nostr_keys.is_none() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(Value::String("nostr zaps not enabled".into())),
+            ));
+        }
+
+        let Some(zap) = state
+            .db
+            .get_zap_by_payment_hash(&payment_hash)
+            .await
+            .map_err(internal_error)?
+        else {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(Value::String("zap not found".into())),
+            ));
+        };
+        if zap.zap_event.is_some() {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(Value::String("zap receipt already published".into())),
+            ));
+        }
+
+        let invoice = state
+            .db
+            .get_invoice_by_payment_hash(&payment_hash)
+            .await
+            .map_err(internal_error)?;
+        if invoice.and_then(|invoice| invoice.preimage).is_none() {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(Value::String("invoice not paid".into())),
+            ));
+        }
+
+        // Requeue from scratch, so an item that exceeded its retry duration isn't dropped again
+        let now = now_millis();
+        state
+            .db
+            .delete_newly_paid(&payment_hash)
+            .await
+            .map_err(internal_error)?;
+        state
+            .db
+            .insert_newly_paid(&NewlyPaid {
+                payment_hash: payment_hash.clone(),
+                created_at: now,
+                retry_count: 0,
+                next_retry_at: now,
+            })
+            .await
+            .map_err(internal_error)?;
+        if state.background_trigger.send(()).is_err() {
+            error!("Failed to trigger background processor - receiver dropped");
+        }
+
+        debug!("requeued zap receipt for payment hash {}", payment_hash);
+        Ok(StatusCode::ACCEPTED)
+    }
+}
+
+fn internal_error(e: LnurlRepositoryError) -> (StatusCode, Json<Value>) {
+    error!("failed to execute query: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(Value::String("internal server error".into())),
+    )
+}
@@ -8,6 +8,7 @@ use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use tracing::{debug, error};
 use x509_parser::prelude::{FromDer, X509Certificate};
 
+use crate::lnurl_auth::hash_secret;
 use crate::state::State;
 
 pub async fn auth<DB>(
@@ -54,6 +55,38 @@ where
     Ok(next.run(req).await)
 }
 
+/// Guards the admin api and metrics with the configured admin api key. These endpoints don't exist
+/// when no admin api key is configured.
+pub async fn admin_auth<DB>(
+    extract::State(state): extract::State<State<DB>>,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode>
+where
+    DB: Send + Sync + 'static,
+{
+    let Some(admin_api_key) = state.admin_api_key.as_ref() else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    let Some(auth_header) = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|header| header.to_str().ok())
+    else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+
+    // Compare hashes, so the comparison doesn't leak the key through timing
+    let key = auth_header.trim_start_matches("Bearer ").trim();
+    if hash_secret(key) != hash_secret(admin_api_key) {
+        debug!("Invalid admin api key");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    Ok(next.run(req).await)
+}
+
 fn verify_cert_against_ca(
     cert: &X509Certificate,
     ca: &X509Certificate,
@@ -5,32 +5,46 @@ use std::sync::Arc;
 use lightning_invoice::Bolt11Invoice;
 use nostr::{JsonUtil, TagStandard};
 use spark::operator::OperatorConfig;
-use spark::operator::rpc::spark::SubscribeToEventsRequest;
 use spark::operator::rpc::spark::subscribe_to_events_response::Event;
+use spark::operator::rpc::spark::transfer_filter::Participant;
+use spark::operator::rpc::spark::{SubscribeToEventsRequest, TransferFilter, TransferType};
 use spark::operator::rpc::{ConnectionManager, OperatorRpcError, SparkRpcClient};
 use spark::services::Transfer;
 use spark::session_manager::InMemorySessionManager;
 use spark::ssp::ServiceProvider;
-use spark_wallet::DefaultSigner;
+use spark_wallet::{DefaultSigner, Network};
 use tokio::sync::Mutex;
 use tokio::sync::watch;
 use tracing::{debug, error, trace, warn};
 
 use crate::invoice_paid::handle_invoice_paid;
+use crate::leader::lost_leadership;
+use crate::metrics::METRICS;
 use crate::repository::LnurlRepository;
-use crate::time::now_millis;
+use crate::state::State;
+use crate::time::{now, now_millis};
+use crate::webhook::{process_webhook_queue, webhook_http_client};
 use crate::zap::Zap;
 
 /// Retry configuration for zap receipt publishing
 const BASE_RETRY_DELAY_MS: i64 = 30_000; // 30 seconds
 const RETRY_MULTIPLIER: f64 = 1.5;
 const MAX_RETRY_DURATION_MS: i64 = 14 * 24 * 60 * 60 * 1000; // 14 days
+/// How long a claimed item is reserved for the instance processing it
+const CLAIM_TIMEOUT_MS: i64 = 5 * 60 * 1000; // 5 minutes
+/// How often the leader looks for new users to subscribe to
+const SUBSCRIPTION_REFRESH_INTERVAL_SECS: u64 = 10;
+/// How many of the latest lightning payments to a user are checked when subscribing to the user
+const CATCH_UP_TRANSFER_LIMIT: i64 = 100;
+/// How often the allowed domains are reloaded, to pick up domains added on other instances
+const DOMAIN_REFRESH_INTERVAL_SECS: u64 = 30;
 
 /// Helper function to create an RPC client and subscribe to a user for invoice payments.
 #[allow(clippy::too_many_arguments)]
 pub async fn create_rpc_client_and_subscribe<DB>(
     db: DB,
     user_pubkey: bitcoin::secp256k1::PublicKey,
+    network: Network,
     connection_manager: &Arc<dyn ConnectionManager>,
     coordinator: &OperatorConfig,
     signer: Arc<DefaultSigner>,
@@ -38,6 +52,7 @@ pub async fn create_rpc_client_and_subscribe<DB>(
     service_provider: Arc<ServiceProvider>,
     subscribed_keys: Arc<Mutex<HashSet<String>>>,
     trigger: watch::Sender<()>,
+    leader: watch::Receiver<bool>,
 ) -> Result<(), anyhow::Error>
 where
     DB: LnurlRepository + Clone + Send + Sync + 'static,
@@ -48,24 +63,28 @@ where
     subscribe_to_user_for_invoices(
         db,
         user_pubkey,
+        network,
         rpc_client,
         service_provider,
         subscribed_keys,
         trigger,
+        leader,
     );
 
     Ok(())
 }
 
 /// Subscribe to a user's payment events and handle invoice payments.
-#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
 fn subscribe_to_user_for_invoices<DB>(
     db: DB,
     user_pk: bitcoin::secp256k1::PublicKey,
+    network: Network,
     rpc: SparkRpcClient,
     ssp_client: Arc<ServiceProvider>,
     subscribed_keys: Arc<Mutex<HashSet<String>>>,
     trigger: watch::Sender<()>,
+    mut leader: watch::Receiver<bool>,
 ) where
     DB: LnurlRepository + Clone + Send + Sync + 'static,
 {
@@ -81,6 +100,13 @@ fn subscribe_to_user_for_invoices<DB>(
 
         // Outer reconnection loop
         loop {
+            // Only the leader subscribes to users
+            if !*leader.borrow() {
+                debug!("Not the leader, unsubscribing from user {user_pk}");
+                subscribed_keys.lock().await.remove(&user_pk.to_string());
+                return;
+            }
+
             debug!("Connecting to event stream for user {user_pk}");
             let mut stream = match rpc
                 .subscribe_to_events(SubscribeToEventsRequest {
@@ -100,15 +126,30 @@ fn subscribe_to_user_for_invoices<DB>(
                         return;
                     }
                     error!("Failed to subscribe to events for user {user_pk}: {e}, retrying in 5s");
+                    METRICS.spark_subscription_errors.inc();
                     tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
                     continue;
                 }
             };
 
+            // The stream only delivers transfers made while connected. Payments received before
+            // subscribing, e.g. to invoices created on other instances, or while reconnecting are
+            // caught up on here, once the stream is open so no payment falls in between.
+            if let Err(e) =
+                catch_up_invoice_payments(&db, user_pk, &rpc, &ssp_client, network, &trigger).await
+            {
+                error!("Failed to catch up on invoice payments for user {user_pk}: {e}");
+            }
+
             // Inner event processing loop
             loop {
                 let message = tokio::select! {
                     message = stream.message() => message,
+                    () = lost_leadership(&mut leader) => {
+                        debug!("No longer the leader, unsubscribing from user {user_pk}");
+                        subscribed_keys.lock().await.remove(&user_pk.to_string());
+                        return;
+                    }
                     () = tokio::time::sleep(tokio::time::Duration::from_secs(60)) => {
                         // Periodically check if user still has unexpired invoices
                         let mut subscribed = subscribed_keys.lock().await;
@@ -144,6 +185,7 @@ fn subscribe_to_user_for_invoices<DB>(
                             return;
                         }
                         error!("Error receiving event for user {user_pk}: {e}, reconnecting...");
+                        METRICS.spark_subscription_errors.inc();
                         break;
                     }
                 };
@@ -184,52 +226,13 @@ fn subscribe_to_user_for_invoices<DB>(
                     continue;
                 }
 
-                let ssp_transfer = ssp_client
-                    .get_transfers(vec![transfer.id.to_string()])
-                    .await
-                    .unwrap_or_default()
-                    .into_iter()
-                    .next();
-
-                let Some(req) = ssp_transfer.and_then(|s| s.user_request) else {
-                    debug!(
-                        "No SSP transfer found for transfer {}, skipping",
-                        transfer.id
-                    );
-                    continue;
-                };
-
-                let Some(inv) = req.get_lightning_invoice() else {
-                    debug!(
-                        "No lightning invoice found in user request for transfer {}, skipping",
-                        transfer.id
-                    );
-                    continue;
-                };
-
-                let Ok(invoice) = Bolt11Invoice::from_str(&inv) else {
-                    error!(
-                        "Failed to parse lightning invoice from user request for transfer {}, skipping",
-                        transfer.id
-                    );
-                    continue;
-                };
-
-                let payment_hash = invoice.payment_hash().to_string();
-
-                // Get the preimage from the SSP transfer
-                let Some(preimage) = req.get_lightning_preimage() else {
-                    debug!("No preimage found for transfer {}, skipping", transfer.id);
-                    continue;
-                };
-
-                // Use the central invoice paid handler
-                if let Err(e) = handle_invoice_paid(&db, &payment_hash, &preimage, &trigger).await {
-                    error!(
-                        "Failed to handle invoice paid for payment hash {}: {}",
-                        payment_hash, e
-                    );
-                }
+                handle_lightning_receives(
+                    &db,
+                    &ssp_client,
+                    vec![transfer.id.to_string()],
+                    &trigger,
+                )
+                .await;
 
                 // Check if user still has unexpired invoices
                 let mut subscribed = subscribed_keys.lock().await;
@@ -256,24 +259,140 @@ fn subscribe_to_user_for_invoices<DB>(
     });
 }
 
-/// Start the background processor that handles the `newly_paid` queue.
-/// This processor publishes zap receipts for paid invoices with retry logic.
+/// Check the latest lightning payments to the user for payments to invoices not marked paid yet.
+async fn catch_up_invoice_payments<DB>(
+    db: &DB,
+    user_pk: bitcoin::secp256k1::PublicKey,
+    rpc: &SparkRpcClient,
+    ssp_client: &ServiceProvider,
+    network: Network,
+    trigger: &watch::Sender<()>,
+) -> Result<(), anyhow::Error>
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    let response = rpc
+        .query_all_transfers(TransferFilter {
+            participant: Some(Participant::ReceiverIdentityPublicKey(
+                user_pk.serialize().to_vec(),
+            )),
+            network: network.to_proto_network() as i32,
+            limit: CATCH_UP_TRANSFER_LIMIT,
+            types: vec![TransferType::PreimageSwap.into()],
+            ..Default::default()
+        })
+        .await?;
+    let transfer_ids: Vec<String> = response.transfers.into_iter().map(|t| t.id).collect();
+    if transfer_ids.is_empty() {
+        return Ok(());
+    }
+
+    debug!(
+        "Catching up on {} lightning payments for user {user_pk}",
+        transfer_ids.len()
+    );
+    handle_lightning_receives(db, ssp_client, transfer_ids, trigger).await;
+    Ok(())
+}
+
+/// Mark the invoices paid by the given lightning receive transfers as paid. Invoices that are
+/// already paid are skipped, so transfers can be handled more than once.
+async fn handle_lightning_receives<DB>(
+    db: &DB,
+    ssp_client: &ServiceProvider,
+    transfer_ids: Vec<String>,
+    trigger: &watch::Sender<()>,
+) where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    let ssp_transfers = match ssp_client.get_transfers(transfer_ids.clone()).await {
+        Ok(ssp_transfers) => ssp_transfers,
+        Err(e) => {
+            error!("Failed to get SSP transfers {transfer_ids:?}: {e}");
+            return;
+        }
+    };
+    if ssp_transfers.is_empty() {
+        debug!("No SSP transfers found for transfers {transfer_ids:?}, skipping");
+        return;
+    }
+
+    for ssp_transfer in ssp_transfers {
+        let transfer_id = ssp_transfer.spark_id.unwrap_or_default();
+        let Some(req) = ssp_transfer.user_request else {
+            debug!("No user request found for transfer {transfer_id}, skipping");
+            continue;
+        };
+
+        let Some(inv) = req.get_lightning_invoice() else {
+            debug!(
+                "No lightning invoice found in user request for transfer {transfer_id}, skipping"
+            );
+            continue;
+        };
+
+        let Ok(invoice) = Bolt11Invoice::from_str(&inv) else {
+            error!(
+                "Failed to parse lightning invoice from user request for transfer {transfer_id}, skipping"
+            );
+            continue;
+        };
+
+        let payment_hash = invoice.payment_hash().to_string();
+
+        // Get the preimage from the SSP transfer
+        let Some(preimage) = req.get_lightning_preimage() else {
+            debug!("No preimage found for transfer {transfer_id}, skipping");
+            continue;
+        };
+
+        // Use the central invoice paid handler
+        if let Err(e) = handle_invoice_paid(db, &payment_hash, &preimage, trigger).await {
+            error!(
+                "Failed to handle invoice paid for payment hash {}: {}",
+                payment_hash, e
+            );
+        }
+    }
+}
+
+/// Start the background processor that handles the `newly_paid` and webhook delivery queues.
+/// This processor publishes zap receipts for paid invoices and delivers webhooks with retry logic.
+/// Only the leader processes the queues.
 pub fn start_background_processor<DB>(
     db: DB,
     nostr_keys: Option<nostr::Keys>,
     mut trigger_rx: watch::Receiver<()>,
+    mut leader: watch::Receiver<bool>,
 ) where
     DB: LnurlRepository + Clone + Send + Sync + 'static,
 {
     tokio::spawn(async move {
         debug!("Background processor started");
+        let http_client = match webhook_http_client() {
+            Ok(http_client) => http_client,
+            Err(e) => {
+                error!("Failed to create webhook http client: {}", e);
+                return;
+            }
+        };
 
-        // Process any pending items on startup
-        process_newly_paid_queue(&db, nostr_keys.as_ref()).await;
-
-        // Wait for triggers
         loop {
-            // Wait for a trigger or timeout after 60 seconds to check for any missed items
+            let is_leader = *leader.borrow_and_update();
+            if is_leader {
+                process_newly_paid_queue(&db, nostr_keys.as_ref()).await;
+                process_webhook_queue(&db, &http_client).await;
+
+                if let Err(e) = db.delete_expired_rate_limits(now()).await {
+                    error!("Failed to delete expired rate limits: {}", e);
+                }
+                if let Err(e) = db.delete_expired_payer_data_challenges(now()).await {
+                    error!("Failed to delete expired payer data challenges: {}", e);
+                }
+            }
+
+            // Wait for a trigger, a leadership change or timeout after 60 seconds to check for
+            // any missed items. Items queued by other instances are picked up by the timeout.
             tokio::select! {
                 result = trigger_rx.changed() => {
                     if result.is_err() {
@@ -281,16 +400,130 @@ pub fn start_background_processor<DB>(
                         return;
                     }
                 }
+                result = leader.changed() => {
+                    if result.is_err() {
+                        debug!("Leader election stopped, exiting background processor");
+                        return;
+                    }
+                }
                 () = tokio::time::sleep(tokio::time::Duration::from_secs(60)) => {
                     // Periodic check for any items that need processing
                 }
             }
+        }
+    });
+}
+
+/// Start subscribing to all users with invoices or zaps to monitor, whenever this instance is the
+/// leader. Runs periodically, to pick up users that requested invoices from other instances.
+pub fn start_subscription_manager<DB>(state: State<DB>)
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    tokio::spawn(async move {
+        let mut leader = state.leader.clone();
+        let refresh_interval = tokio::time::Duration::from_secs(SUBSCRIPTION_REFRESH_INTERVAL_SECS);
+        loop {
+            let is_leader = *leader.borrow_and_update();
+            if is_leader && let Err(e) = subscribe_to_monitored_users(&state).await {
+                error!("Failed to subscribe to monitored users: {e}");
+            }
+
+            tokio::select! {
+                result = leader.changed() => {
+                    if result.is_err() {
+                        debug!("Leader election stopped, exiting subscription manager");
+                        return;
+                    }
+                }
+                () = tokio::time::sleep(refresh_interval) => {}
+            }
+        }
+    });
+}
 
-            process_newly_paid_queue(&db, nostr_keys.as_ref()).await;
+/// Periodically reload the allowed domains from the database, so domains added through the admin
+/// api of another instance are served by this instance as well.
+pub fn start_domain_refresher<DB>(state: State<DB>)
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    tokio::spawn(async move {
+        let refresh_interval = tokio::time::Duration::from_secs(DOMAIN_REFRESH_INTERVAL_SECS);
+        loop {
+            tokio::time::sleep(refresh_interval).await;
+            let domains: HashSet<String> = match state.db.list_domains().await {
+                Ok(domains) => domains.into_iter().collect(),
+                Err(e) => {
+                    error!("Failed to reload allowed domains: {e}");
+                    continue;
+                }
+            };
+            trace!("Reloaded {} allowed domains", domains.len());
+            *state
+                .domains
+                .write()
+                .unwrap_or_else(std::sync::PoisonError::into_inner) = domains;
         }
     });
 }
 
+async fn subscribe_to_monitored_users<DB>(state: &State<DB>) -> Result<(), anyhow::Error>
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    // Subscribe to users with unexpired invoices for payment monitoring
+    for user in state.db.get_invoice_monitored_users().await? {
+        if state.subscribed_keys.lock().await.contains(&user) {
+            continue;
+        }
+        let user_pubkey = bitcoin::secp256k1::PublicKey::from_str(&user)
+            .map_err(|e| anyhow::anyhow!("failed to parse user pubkey: {e:?}"))?;
+
+        create_rpc_client_and_subscribe(
+            state.db.clone(),
+            user_pubkey,
+            state.network,
+            &state.connection_manager,
+            &state.coordinator,
+            state.signer.clone(),
+            state.session_manager.clone(),
+            Arc::clone(&state.service_provider),
+            Arc::clone(&state.subscribed_keys),
+            state.background_trigger.clone(),
+            state.leader.clone(),
+        )
+        .await?;
+    }
+
+    if let Some(nostr_keys) = &state.nostr_keys {
+        // Also subscribe for legacy zap monitoring (users with unexpired zaps)
+        for user in state.db.get_zap_monitored_users().await? {
+            if state.subscribed_keys.lock().await.contains(&user) {
+                continue;
+            }
+            let user_pubkey = bitcoin::secp256k1::PublicKey::from_str(&user)
+                .map_err(|e| anyhow::anyhow!("failed to parse user pubkey: {e:?}"))?;
+
+            crate::zap::create_rpc_client_and_subscribe(
+                state.db.clone(),
+                user_pubkey,
+                &state.connection_manager,
+                &state.coordinator,
+                state.signer.clone(),
+                state.session_manager.clone(),
+                Arc::clone(&state.service_provider),
+                nostr_keys.clone(),
+                Arc::clone(&state.subscribed_keys),
+                state.leader.clone(),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
 /// Process all pending items in the `newly_paid` queue.
 async fn process_newly_paid_queue<DB>(db: &DB, nostr_keys: Option<&nostr::Keys>)
 where
@@ -325,6 +558,27 @@ async fn process_newly_paid_item<DB>(
 {
     let payment_hash = &item.payment_hash;
 
+    // Claim the item, so it isn't processed twice when another instance took over in the
+    // meantime. If processing is interrupted, the item is retried once the claim times out.
+    let now = now_millis();
+    match db
+        .claim_newly_paid(payment_hash, now, now.saturating_add(CLAIM_TIMEOUT_MS))
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            debug!(
+                "Newly paid {} claimed by another run, skipping",
+                payment_hash
+            );
+            return;
+        }
+        Err(e) => {
+            error!("Failed to claim newly paid {}: {}", payment_hash, e);
+            return;
+        }
+    }
+
     let Some(nostr_keys) = nostr_keys else {
         if let Err(e) = db.delete_newly_paid(payment_hash).await {
             error!(
@@ -337,7 +591,6 @@ async fn process_newly_paid_item<DB>(
     };
 
     // Check if we've exceeded max retry duration (14 days)
-    let now = now_millis();
     if now.saturating_sub(item.created_at) > MAX_RETRY_DURATION_MS {
         debug!(
             "Payment hash {} exceeded max retry duration, removing from queue",
@@ -428,6 +681,7 @@ async fn process_newly_paid_item<DB>(
                 "Failed to publish zap receipt for {}: {}, will retry",
                 payment_hash, e
             );
+            METRICS.zap_publish_failures.inc();
 
             // Calculate next retry time with exponential backoff
             let retry_count = item.retry_count.saturating_add(1);
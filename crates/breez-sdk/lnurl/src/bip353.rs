@@ -0,0 +1,176 @@
+use std::str::FromStr;
+
+use bitcoin::{
+    bech32::{self, Bech32, Hrp},
+    secp256k1::PublicKey,
+};
+use spark::address::SparkAddress;
+use tracing::{debug, error};
+
+use crate::{dns::DnsError, repository::LnurlRepository, state::State, user::User};
+
+const LNURL_HRP: Hrp = Hrp::parse_unchecked("lnurl");
+const BOLT12_OFFER_PREFIX: &str = "lno1";
+const MAX_BOLT12_OFFER_LEN: usize = 2000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Bip353Error {
+    #[error("bip353 is not enabled")]
+    NotEnabled,
+    #[error("bip353 is not supported for this domain")]
+    UnsupportedDomain,
+    #[error("bip353 is not supported for this username")]
+    UnsupportedUsername,
+    #[error("invalid bolt12 offer")]
+    InvalidOffer,
+    #[error("failed to build payment instructions: {0}")]
+    PaymentInstructions(String),
+    #[error("failed to publish payment instructions: {0}")]
+    Dns(#[from] DnsError),
+}
+
+/// The BIP-353 address of a user, as shown to payers.
+pub fn bip353_address(user: &User) -> String {
+    format!("₿{}@{}", user.name, user.domain)
+}
+
+/// The DNS name the payment instructions of a user are published at. Domains with a port aren't
+/// DNS names, so they can't have BIP-353 addresses. The username has to be a single DNS label,
+/// so usernames containing dots can't either.
+pub fn record_name(name: &str, domain: &str) -> Result<String, Bip353Error> {
+    if domain.contains(':') {
+        return Err(Bip353Error::UnsupportedDomain);
+    }
+    if name.is_empty() || name.contains('.') {
+        return Err(Bip353Error::UnsupportedUsername);
+    }
+    Ok(format!("{name}.user._bitcoin-payment.{domain}"))
+}
+
+/// The DNS zone the payment instructions of users of the domain are published in: the most
+/// specific configured zone containing the domain, or the domain itself if there is none.
+pub fn record_zone(zones: &[String], domain: &str) -> String {
+    zones
+        .iter()
+        .filter(|zone| {
+            domain == zone.as_str()
+                || domain
+                    .strip_suffix(zone.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+        .max_by_key(|zone| zone.len())
+        .cloned()
+        .unwrap_or_else(|| domain.to_string())
+}
+
+/// Only a basic sanity check. The offer is published as is, and parsed by the payer.
+pub fn validate_bolt12_offer(offer: &str) -> Result<(), Bip353Error> {
+    let has_prefix = offer
+        .get(..BOLT12_OFFER_PREFIX.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(BOLT12_OFFER_PREFIX));
+    if !has_prefix
+        || offer.len() > MAX_BOLT12_OFFER_LEN
+        || !offer.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return Err(Bip353Error::InvalidOffer);
+    }
+    Ok(())
+}
+
+/// Builds the BIP-21 uri published for a user: the user's Spark address, with the lnurl of the
+/// lightning address as a fallback for payers without Spark support, and the user's BOLT12 offer
+/// if set.
+fn payment_instructions<DB>(state: &State<DB>, user: &User) -> Result<String, Bip353Error> {
+    let pubkey = PublicKey::from_str(&user.pubkey)
+        .map_err(|e| Bip353Error::PaymentInstructions(e.to_string()))?;
+    let spark_address = SparkAddress::new(pubkey, state.network, None)
+        .to_address_string()
+        .map_err(|e| Bip353Error::PaymentInstructions(e.to_string()))?;
+    let lnurl_url = format!("{}://{}/lnurlp/{}", state.scheme, user.domain, user.name);
+    let lnurl = bech32::encode_upper::<Bech32>(LNURL_HRP, lnurl_url.as_bytes())
+        .map_err(|e| Bip353Error::PaymentInstructions(e.to_string()))?;
+
+    let mut uri = format!("bitcoin:?spark={spark_address}&lnurl={lnurl}");
+    if let Some(offer) = &user.bolt12_offer {
+        uri.push_str("&lno=");
+        uri.push_str(offer);
+    }
+    Ok(uri)
+}
+
+/// Publishes the payment instructions of a user, replacing any previously published ones.
+/// Returns the BIP-353 address of the user.
+pub async fn publish<DB>(state: &State<DB>, user: &User) -> Result<String, Bip353Error>
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    let Some(dns_provider) = &state.dns_provider else {
+        return Err(Bip353Error::NotEnabled);
+    };
+
+    let name = record_name(&user.name, &user.domain)?;
+    let zone = record_zone(&state.dns_zones, &user.domain);
+    let record = payment_instructions(state, user)?;
+    dns_provider.set_txt_record(&zone, &name, &record).await?;
+    debug!("published bip353 payment instructions at {}", name);
+    Ok(bip353_address(user))
+}
+
+/// Removes the published payment instructions of a user, if any. Failures are only logged, a
+/// stale record still points to the user's wallet.
+pub async fn unpublish<DB>(state: &State<DB>, user: &User)
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    let Some(dns_provider) = &state.dns_provider else {
+        return;
+    };
+    if !user.bip353_enabled {
+        return;
+    }
+
+    let Ok(name) = record_name(&user.name, &user.domain) else {
+        return;
+    };
+    let zone = record_zone(&state.dns_zones, &user.domain);
+    if let Err(e) = dns_provider.remove_txt_records(&zone, &name).await {
+        error!(
+            "failed to remove bip353 payment instructions at {}: {}",
+            name, e
+        );
+        return;
+    }
+    debug!("removed bip353 payment instructions at {}", name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_name() {
+        assert_eq!(
+            record_name("alice", "example.com").unwrap(),
+            "alice.user._bitcoin-payment.example.com"
+        );
+        assert!(matches!(
+            record_name("alice.bob", "example.com"),
+            Err(Bip353Error::UnsupportedUsername)
+        ));
+        assert!(matches!(
+            record_name("alice", "localhost:8080"),
+            Err(Bip353Error::UnsupportedDomain)
+        ));
+    }
+
+    #[test]
+    fn test_record_zone() {
+        let zones = vec!["example.com".to_string(), "pay.example.com".to_string()];
+        assert_eq!(record_zone(&zones, "example.com"), "example.com");
+        assert_eq!(record_zone(&zones, "pay.example.com"), "pay.example.com");
+        assert_eq!(record_zone(&zones, "ln.pay.example.com"), "pay.example.com");
+        assert_eq!(record_zone(&zones, "ln.example.com"), "example.com");
+        assert_eq!(record_zone(&zones, "badexample.com"), "badexample.com");
+        assert_eq!(record_zone(&[], "example.org"), "example.org");
+    }
+}
@@ -0,0 +1,30 @@
+mod rfc2136;
+
+pub use rfc2136::{Rfc2136Provider, TsigKey};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DnsError {
+    #[error("invalid dns name: {0}")]
+    InvalidName(String),
+    #[error("record too long")]
+    RecordTooLong,
+    #[error("dns server rejected the update: {0}")]
+    Rejected(String),
+    #[error("invalid response from dns server: {0}")]
+    InvalidResponse(String),
+    #[error("dns update timed out")]
+    Timeout,
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Publishes TXT records in DNS zones the server is authoritative for.
+#[async_trait::async_trait]
+pub trait DnsProvider: Send + Sync {
+    /// Replaces all TXT records at `name` with a single record containing `value`. `name` is a
+    /// fully qualified name inside `zone`.
+    async fn set_txt_record(&self, zone: &str, name: &str, value: &str) -> Result<(), DnsError>;
+
+    /// Removes all TXT records at `name`. Removing records that don't exist succeeds.
+    async fn remove_txt_records(&self, zone: &str, name: &str) -> Result<(), DnsError>;
+}
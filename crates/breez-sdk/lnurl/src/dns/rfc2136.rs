@@ -0,0 +1,342 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use bitcoin::hashes::{Hash, HashEngine, Hmac, HmacEngine, sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tracing::debug;
+
+use super::{DnsError, DnsProvider};
+use crate::time::now_u64;
+
+/// Header flags of an update message: the opcode is 5 (UPDATE)
+const FLAGS_UPDATE: u16 = 0x2800;
+const FLAG_RESPONSE: u16 = 0x8000;
+const OPCODE_MASK: u16 = 0x7800;
+const RCODE_MASK: u16 = 0x000f;
+const TYPE_SOA: u16 = 6;
+const TYPE_TXT: u16 = 16;
+const TYPE_TSIG: u16 = 250;
+const CLASS_IN: u16 = 1;
+const CLASS_ANY: u16 = 255;
+const HEADER_LEN: usize = 12;
+const ADDITIONAL_COUNT_OFFSET: usize = 10;
+const TSIG_ALGORITHM: &str = "hmac-sha256";
+/// Allowed clock difference between the server and the dns server, in seconds
+const TSIG_FUDGE_SECS: u16 = 300;
+const MAX_LABEL_LEN: usize = 63;
+const MAX_NAME_LEN: usize = 255;
+const MAX_CHARACTER_STRING_LEN: usize = 255;
+const UPDATE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A TSIG key authenticating updates to the dns server, using HMAC-SHA256.
+#[derive(Clone)]
+pub struct TsigKey {
+    pub name: String,
+    pub secret: Vec<u8>,
+}
+
+/// Publishes records through DNS UPDATE messages (RFC 2136), sent over TCP to the primary
+/// server of the zone. Updates are authenticated with TSIG (RFC 8945) when a key is configured.
+pub struct Rfc2136Provider {
+    server: SocketAddr,
+    tsig_key: Option<TsigKey>,
+    ttl: u32,
+}
+
+impl Rfc2136Provider {
+    pub fn new(server: SocketAddr, tsig_key: Option<TsigKey>, ttl: u32) -> Self {
+        Self {
+            server,
+            tsig_key,
+            ttl,
+        }
+    }
+
+    /// Builds an update message for the zone. The existing TXT records at `name` are always
+    /// deleted, and replaced by a record with `value` if set.
+    fn build_update(
+        &self,
+        id: u16,
+        zone: &str,
+        name: &str,
+        value: Option<&str>,
+    ) -> Result<Vec<u8>, DnsError> {
+        let update_count = if value.is_some() { 2 } else { 1 };
+        let mut msg = Vec::with_capacity(512);
+        msg.extend_from_slice(&id.to_be_bytes());
+        msg.extend_from_slice(&FLAGS_UPDATE.to_be_bytes());
+        msg.extend_from_slice(&1u16.to_be_bytes()); // zone count
+        msg.extend_from_slice(&0u16.to_be_bytes()); // prerequisite count
+        msg.extend_from_slice(&u16::to_be_bytes(update_count));
+        msg.extend_from_slice(&0u16.to_be_bytes()); // additional count
+
+        // Zone section
+        encode_name(&mut msg, zone)?;
+        msg.extend_from_slice(&TYPE_SOA.to_be_bytes());
+        msg.extend_from_slice(&CLASS_IN.to_be_bytes());
+
+        // Delete the TXT RRset at the name
+        encode_name(&mut msg, name)?;
+        msg.extend_from_slice(&TYPE_TXT.to_be_bytes());
+        msg.extend_from_slice(&CLASS_ANY.to_be_bytes());
+        msg.extend_from_slice(&0u32.to_be_bytes());
+        msg.extend_from_slice(&0u16.to_be_bytes());
+
+        // Add the new TXT record
+        if let Some(value) = value {
+            let rdata = encode_txt_rdata(value);
+            let rdata_len = u16::try_from(rdata.len()).map_err(|_| DnsError::RecordTooLong)?;
+            encode_name(&mut msg, name)?;
+            msg.extend_from_slice(&TYPE_TXT.to_be_bytes());
+            msg.extend_from_slice(&CLASS_IN.to_be_bytes());
+            msg.extend_from_slice(&self.ttl.to_be_bytes());
+            msg.extend_from_slice(&rdata_len.to_be_bytes());
+            msg.extend_from_slice(&rdata);
+        }
+
+        if let Some(key) = &self.tsig_key {
+            sign_message(&mut msg, id, key, now_u64())?;
+        }
+        Ok(msg)
+    }
+
+    async fn send_update(&self, id: u16, msg: &[u8]) -> Result<(), DnsError> {
+        let msg_len = u16::try_from(msg.len()).map_err(|_| DnsError::RecordTooLong)?;
+        let response = tokio::time::timeout(UPDATE_TIMEOUT, async {
+            let mut stream = TcpStream::connect(self.server).await?;
+            stream.write_all(&msg_len.to_be_bytes()).await?;
+            stream.write_all(msg).await?;
+
+            let response_len = stream.read_u16().await?;
+            let mut response = vec![0u8; usize::from(response_len)];
+            stream.read_exact(&mut response).await?;
+            Ok::<_, std::io::Error>(response)
+        })
+        .await
+        .map_err(|_| DnsError::Timeout)??;
+
+        // The response is not authenticated. It comes over TCP from the configured server, and a
+        // forged success only means a record is missing until the user registers again.
+        if response.len() < HEADER_LEN {
+            return Err(DnsError::InvalidResponse("response too short".to_string()));
+        }
+        if u16::from_be_bytes([response[0], response[1]]) != id {
+            return Err(DnsError::InvalidResponse(
+                "response id mismatch".to_string(),
+            ));
+        }
+        let flags = u16::from_be_bytes([response[2], response[3]]);
+        if flags & FLAG_RESPONSE == 0 || flags & OPCODE_MASK != FLAGS_UPDATE {
+            return Err(DnsError::InvalidResponse(
+                "not an update response".to_string(),
+            ));
+        }
+        match flags & RCODE_MASK {
+            0 => Ok(()),
+            rcode => Err(DnsError::Rejected(rcode_name(rcode))),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DnsProvider for Rfc2136Provider {
+    async fn set_txt_record(&self, zone: &str, name: &str, value: &str) -> Result<(), DnsError> {
+        let id = rand::random();
+        let msg = self.build_update(id, zone, name, Some(value))?;
+        self.send_update(id, &msg).await?;
+        debug!("published txt record at {}", name);
+        Ok(())
+    }
+
+    async fn remove_txt_records(&self, zone: &str, name: &str) -> Result<(), DnsError> {
+        let id = rand::random();
+        let msg = self.build_update(id, zone, name, None)?;
+        self.send_update(id, &msg).await?;
+        debug!("removed txt records at {}", name);
+        Ok(())
+    }
+}
+
+/// Appends a TSIG record signing the message. The MAC covers the message as it is before the
+/// TSIG record is added, followed by the TSIG variables.
+fn sign_message(
+    msg: &mut Vec<u8>,
+    id: u16,
+    key: &TsigKey,
+    time_signed: u64,
+) -> Result<(), DnsError> {
+    let key_name = key.name.to_lowercase();
+    let time_signed = time_signed.to_be_bytes();
+
+    let mut engine = HmacEngine::<sha256::Hash>::new(&key.secret);
+    engine.input(msg);
+    let mut variables = Vec::new();
+    encode_name(&mut variables, &key_name)?;
+    variables.extend_from_slice(&CLASS_ANY.to_be_bytes());
+    variables.extend_from_slice(&0u32.to_be_bytes()); // ttl
+    encode_name(&mut variables, TSIG_ALGORITHM)?;
+    variables.extend_from_slice(&time_signed[2..]); // 48 bit time
+    variables.extend_from_slice(&TSIG_FUDGE_SECS.to_be_bytes());
+    variables.extend_from_slice(&0u16.to_be_bytes()); // error
+    variables.extend_from_slice(&0u16.to_be_bytes()); // other data length
+    engine.input(&variables);
+    let mac = Hmac::<sha256::Hash>::from_engine(engine);
+    let mac = mac.as_byte_array();
+
+    let mut rdata = Vec::new();
+    encode_name(&mut rdata, TSIG_ALGORITHM)?;
+    rdata.extend_from_slice(&time_signed[2..]);
+    rdata.extend_from_slice(&TSIG_FUDGE_SECS.to_be_bytes());
+    rdata.extend_from_slice(&u16::to_be_bytes(32)); // mac size
+    rdata.extend_from_slice(mac);
+    rdata.extend_from_slice(&id.to_be_bytes()); // original id
+    rdata.extend_from_slice(&0u16.to_be_bytes()); // error
+    rdata.extend_from_slice(&0u16.to_be_bytes()); // other data length
+    let rdata_len = u16::try_from(rdata.len()).map_err(|_| DnsError::RecordTooLong)?;
+
+    encode_name(msg, &key_name)?;
+    msg.extend_from_slice(&TYPE_TSIG.to_be_bytes());
+    msg.extend_from_slice(&CLASS_ANY.to_be_bytes());
+    msg.extend_from_slice(&0u32.to_be_bytes());
+    msg.extend_from_slice(&rdata_len.to_be_bytes());
+    msg.extend_from_slice(&rdata);
+
+    // The TSIG record is the only additional record
+    msg[ADDITIONAL_COUNT_OFFSET..HEADER_LEN].copy_from_slice(&1u16.to_be_bytes());
+    Ok(())
+}
+
+/// Encodes a domain name in uncompressed wire format.
+fn encode_name(buf: &mut Vec<u8>, name: &str) -> Result<(), DnsError> {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    let mut encoded_len = 1usize; // root label
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > MAX_LABEL_LEN {
+            return Err(DnsError::InvalidName(name.to_string()));
+        }
+        encoded_len = encoded_len.saturating_add(label.len()).saturating_add(1);
+        if encoded_len > MAX_NAME_LEN {
+            return Err(DnsError::InvalidName(name.to_string()));
+        }
+        // Checked above, labels are at most 63 bytes
+        #[allow(clippy::cast_possible_truncation)]
+        buf.push(label.len() as u8);
+        buf.extend_from_slice(label.as_bytes());
+    }
+    buf.push(0);
+    Ok(())
+}
+
+/// Encodes a TXT record value as a sequence of character strings of at most 255 bytes. Readers
+/// concatenate the strings back together.
+fn encode_txt_rdata(value: &str) -> Vec<u8> {
+    let mut rdata = Vec::new();
+    for chunk in value.as_bytes().chunks(MAX_CHARACTER_STRING_LEN) {
+        // Chunks are at most 255 bytes
+        #[allow(clippy::cast_possible_truncation)]
+        rdata.push(chunk.len() as u8);
+        rdata.extend_from_slice(chunk);
+    }
+    rdata
+}
+
+fn rcode_name(rcode: u16) -> String {
+    match rcode {
+        1 => "FORMERR".to_string(),
+        2 => "SERVFAIL".to_string(),
+        3 => "NXDOMAIN".to_string(),
+        4 => "NOTIMP".to_string(),
+        5 => "REFUSED".to_string(),
+        6 => "YXDOMAIN".to_string(),
+        7 => "YXRRSET".to_string(),
+        8 => "NXRRSET".to_string(),
+        9 => "NOTAUTH".to_string(),
+        10 => "NOTZONE".to_string(),
+        rcode => format!("rcode {rcode}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The expected messages and MAC were encoded independently of this module, following
+    // RFC 1035, RFC 2136 and RFC 8945, and the MAC computed with a separate HMAC-SHA256
+    // implementation.
+    const ZONE: &str = "example.com";
+    const NAME: &str = "alice.user._bitcoin-payment.example.com";
+    const VALUE: &str = "bitcoin:?lno=lno1abc";
+    const ID: u16 = 0x1234;
+    const TIME_SIGNED: u64 = 1_700_000_000;
+    const SET_UPDATE: &str = "123428000001000000020000076578616d706c6503636f6d000006000105616c69\
+        63650475736572105f626974636f696e2d7061796d656e74076578616d706c6503636f6d00001000ff00000000\
+        000005616c6963650475736572105f626974636f696e2d7061796d656e74076578616d706c6503636f6d000010\
+        000100000e10001514626974636f696e3a3f6c6e6f3d6c6e6f31616263";
+    const REMOVE_UPDATE: &str = "123428000001000000010000076578616d706c6503636f6d000006000105616c\
+        6963650475736572105f626974636f696e2d7061796d656e74076578616d706c6503636f6d00001000ff000000\
+        000000";
+    const TSIG_MAC: &str = "2ba54579b18335a65a6c34b745afc702c9903c979cf657d07b84ec1c95885acf";
+    const TSIG_RECORD: &str = "0a7570646174652d6b65790000fa00ff00000000003d0b686d61632d73686132\
+        35360000006553f100012c00202ba54579b18335a65a6c34b745afc702c9903c979cf657d07b84ec1c95885acf\
+        123400000000";
+
+    fn provider() -> Rfc2136Provider {
+        Rfc2136Provider::new(SocketAddr::from(([127, 0, 0, 1], 53)), None, 3600)
+    }
+
+    fn tsig_key() -> TsigKey {
+        TsigKey {
+            name: "Update-Key".to_string(),
+            secret: b"0123456789abcdef0123456789abcdef".to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_build_set_update() {
+        let msg = provider()
+            .build_update(ID, ZONE, NAME, Some(VALUE))
+            .unwrap();
+        assert_eq!(hex::encode(msg), SET_UPDATE);
+    }
+
+    #[test]
+    fn test_build_remove_update() {
+        let msg = provider().build_update(ID, ZONE, NAME, None).unwrap();
+        assert_eq!(hex::encode(msg), REMOVE_UPDATE);
+    }
+
+    #[test]
+    fn test_sign_message() {
+        let mut msg = hex::decode(SET_UPDATE).unwrap();
+        sign_message(&mut msg, ID, &tsig_key(), TIME_SIGNED).unwrap();
+
+        let (signed, tsig) = msg.split_at(SET_UPDATE.len() / 2);
+        assert_eq!(hex::encode(tsig), TSIG_RECORD);
+        assert!(hex::encode(tsig).contains(TSIG_MAC));
+        // Only the additional count changes in the signed part of the message
+        let mut expected = hex::decode(SET_UPDATE).unwrap();
+        expected[ADDITIONAL_COUNT_OFFSET..HEADER_LEN].copy_from_slice(&1u16.to_be_bytes());
+        assert_eq!(signed, expected.as_slice());
+    }
+
+    #[test]
+    fn test_encode_txt_rdata_splits_long_values() {
+        let value = "a".repeat(300);
+        let rdata = encode_txt_rdata(&value);
+        assert_eq!(rdata.len(), 302);
+        assert_eq!(rdata[0], 255);
+        assert_eq!(rdata[256], 45);
+    }
+
+    #[test]
+    fn test_encode_name_rejects_invalid_names() {
+        let mut buf = Vec::new();
+        assert!(encode_name(&mut buf, "example..com").is_err());
+        assert!(encode_name(&mut buf, &format!("{}.com", "a".repeat(64))).is_err());
+        assert!(encode_name(&mut buf, &format!("{0}.{0}.{0}.{0}", "a".repeat(63))).is_err());
+
+        encode_name(&mut buf, "example.com.").unwrap();
+        assert_eq!(hex::encode(buf), "076578616d706c6503636f6d00");
+    }
+}
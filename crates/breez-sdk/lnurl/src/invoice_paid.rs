@@ -1,9 +1,12 @@
 use bitcoin::hashes::{Hash, sha256};
+use lnurl_models::WebhookEvent;
 use tokio::sync::watch;
 use tracing::{debug, error};
 
+use crate::metrics::METRICS;
 use crate::repository::{Invoice, LnurlRepository, LnurlRepositoryError, NewlyPaid};
 use crate::time::now_millis;
+use crate::webhook::{InvoiceEvent, build_webhook_deliveries};
 
 #[derive(Debug, thiserror::Error)]
 pub enum HandleInvoicePaidError {
@@ -43,7 +46,7 @@ where
     let now = now_millis();
 
     // Get the existing invoice
-    let Some(mut invoice) = db.get_invoice_by_payment_hash(payment_hash).await? else {
+    let Some(invoice) = db.get_invoice_by_payment_hash(payment_hash).await? else {
         debug!(
             "Invoice not found for payment hash {}, cannot mark as paid",
             payment_hash
@@ -51,27 +54,49 @@ where
         return Ok(());
     };
 
-    // Check if already paid
-    if invoice.preimage.is_some() {
-        debug!("Invoice {} already has preimage, skipping", payment_hash);
-        return Ok(());
-    }
-
-    // Store the preimage
-    invoice.preimage = Some(preimage.to_string());
-    invoice.updated_at = now;
-    db.upsert_invoice(&invoice).await?;
-    debug!("Stored preimage for invoice {}", payment_hash);
+    // Webhooks are only known for invoices that recorded the domain they were requested on
+    let deliveries = match &invoice.domain {
+        Some(domain) => {
+            build_webhook_deliveries(
+                db,
+                &InvoiceEvent {
+                    event: WebhookEvent::InvoicePaid,
+                    domain,
+                    user_pubkey: &invoice.user_pubkey,
+                    payment_hash,
+                    invoice: &invoice.invoice,
+                    preimage: Some(preimage),
+                    comment: None,
+                    payer_data: invoice.payer_data.as_deref(),
+                },
+            )
+            .await?
+        }
+        None => Vec::new(),
+    };
 
-    // Queue for background processing (zap receipt publishing)
+    // Store the preimage, and queue the invoice for background processing (zap receipt
+    // publishing) together with the webhook deliveries, so none of them is lost if storing
+    // fails halfway. Payments can be reported more than once, and concurrently by different
+    // instances, so only the report that stores the preimage queues the follow-up work.
     let newly_paid = NewlyPaid {
         payment_hash: payment_hash.to_string(),
         created_at: now,
         retry_count: 0,
         next_retry_at: now, // Process immediately
     };
-    db.insert_newly_paid(&newly_paid).await?;
-    debug!("Queued invoice {} for background processing", payment_hash);
+    if !db
+        .mark_invoice_paid(payment_hash, preimage, &newly_paid, &deliveries)
+        .await?
+    {
+        debug!("Invoice {} already has preimage, skipping", payment_hash);
+        return Ok(());
+    }
+    debug!(
+        "Stored preimage for invoice {} and queued it for background processing",
+        payment_hash
+    );
+    METRICS.invoices_paid.inc();
 
     // Trigger the background processor
     // Using watch channel so multiple triggers result in a single processing run
@@ -85,10 +110,12 @@ where
 /// Create a new invoice record for LUD-21 and NIP-57 support.
 pub async fn create_invoice<DB>(
     db: &DB,
+    domain: &str,
     payment_hash: &str,
     user_pubkey: &str,
     invoice: &str,
     invoice_expiry: i64,
+    payer_data: Option<&str>,
 ) -> Result<(), LnurlRepositoryError>
 where
     DB: LnurlRepository + Clone + Send + Sync + 'static,
@@ -99,6 +126,8 @@ where
         user_pubkey: user_pubkey.to_string(),
         invoice: invoice.to_string(),
         preimage: None,
+        payer_data: payer_data.map(ToString::to_string),
+        domain: Some(domain.to_string()),
         invoice_expiry,
         created_at: now,
         updated_at: now,
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tracing::{debug, error, info};
+
+use crate::repository::LnurlRepository;
+use crate::time::now_millis;
+
+/// Name of the lease held by the instance running the background jobs
+pub const LEADER_LEASE: &str = "leader";
+
+/// Lease configuration. The lease is renewed well before it expires, so a single failed renewal
+/// doesn't cause a failover.
+const LEASE_TTL_MS: i64 = 30_000; // 30 seconds
+const LEASE_RENEW_INTERVAL_SECS: u64 = 10;
+
+/// Start electing a leader among the instances sharing the database. Exactly one instance holds
+/// the leader lease at a time, and only that instance subscribes to users and processes the
+/// queues. When the leader stops renewing its lease, another instance takes over once it expires.
+///
+/// The returned receiver tells whether this instance is the leader.
+pub fn start_leader_election<DB>(db: DB, instance_id: String) -> watch::Receiver<bool>
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    let (leader_tx, leader_rx) = watch::channel(false);
+
+    tokio::spawn(async move {
+        debug!("Leader election started for instance {instance_id}");
+        loop {
+            let now = now_millis();
+            let is_leader = match db
+                .try_acquire_lease(
+                    LEADER_LEASE,
+                    &instance_id,
+                    now,
+                    now.saturating_add(LEASE_TTL_MS),
+                )
+                .await
+            {
+                Ok(is_leader) => is_leader,
+                Err(e) => {
+                    // Step down, the lease may expire before it can be renewed
+                    error!("Failed to renew leader lease: {e}");
+                    false
+                }
+            };
+
+            leader_tx.send_if_modified(|was_leader| {
+                if *was_leader == is_leader {
+                    return false;
+                }
+                if is_leader {
+                    info!("Instance {instance_id} became the leader");
+                } else {
+                    info!("Instance {instance_id} is no longer the leader");
+                }
+                *was_leader = is_leader;
+                true
+            });
+
+            if leader_tx.is_closed() {
+                debug!("Leader election receivers dropped, exiting");
+                return;
+            }
+            tokio::time::sleep(Duration::from_secs(LEASE_RENEW_INTERVAL_SECS)).await;
+        }
+    });
+
+    leader_rx
+}
+
+/// Resolves once this instance is no longer the leader.
+pub async fn lost_leadership(leader: &mut watch::Receiver<bool>) {
+    // An error means the election stopped, which also ends the leadership
+    let _ = leader.wait_for(|is_leader| !is_leader).await;
+}
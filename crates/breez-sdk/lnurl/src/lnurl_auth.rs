@@ -0,0 +1,86 @@
+use std::str::FromStr;
+
+use bitcoin::hashes::{Hash, sha256};
+use bitcoin::secp256k1::{Message, PublicKey, Secp256k1, ecdsa::Signature};
+use serde::{Deserialize, Serialize};
+
+/// How long a login challenge can be signed and exchanged for a session.
+pub const AUTH_CHALLENGE_TTL_SECS: u64 = 300;
+
+#[derive(Debug, Clone)]
+pub struct AuthChallenge {
+    pub k1: String,
+    pub domain: String,
+    /// Hash of the secret handed to the relying app, required to exchange the challenge
+    pub secret_hash: String,
+    /// The linking key that signed the challenge, once signed
+    pub linking_key: Option<String>,
+    /// Unix timestamp (seconds) after which the challenge can no longer be used
+    pub expires_at: i64,
+    /// Unix timestamp (seconds) the challenge was created at
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthSession {
+    pub token_hash: String,
+    pub domain: String,
+    pub linking_key: String,
+    /// Unix timestamp (seconds) after which the session is no longer valid
+    pub expires_at: i64,
+    /// Unix timestamp (seconds) the session was created at
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthChallengeResponse {
+    pub k1: String,
+    /// The LNURL-auth url to present to the wallet, usually bech32 encoded in a QR code
+    pub url: String,
+    /// Secret to exchange the signed challenge for a session, only known to the relying app
+    pub secret: String,
+    pub expires_at: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LnurlAuthCallbackParams {
+    pub k1: Option<String>,
+    pub sig: Option<String>,
+    pub key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateAuthSessionRequest {
+    pub k1: String,
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthSessionResponse {
+    /// Bearer token identifying the session, only returned when the session is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    pub linking_key: String,
+    pub expires_at: i64,
+}
+
+/// Generates a random 32 byte hex encoded value, used for challenges, secrets and tokens.
+pub fn random_hex() -> String {
+    hex::encode(rand::random::<[u8; 32]>())
+}
+
+/// Secrets and tokens are only stored hashed.
+pub fn hash_secret(secret: &str) -> String {
+    sha256::Hash::hash(secret.as_bytes()).to_string()
+}
+
+/// Verifies the signature of a linking key over a k1 challenge, as per LUD-04.
+pub fn verify_k1_signature(k1: &str, sig: &str, key: &str) -> Result<(), anyhow::Error> {
+    let key = PublicKey::from_str(key)?;
+    let k1: [u8; 32] = hex::decode(k1)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("invalid k1 length"))?;
+    let sig = Signature::from_der(&hex::decode(sig)?)?;
+    Secp256k1::verification_only().verify_ecdsa(&Message::from_digest(k1), &sig, &key)?;
+    Ok(())
+}
@@ -1,4 +1,10 @@
-use crate::{repository::LnurlRepository, routes::LnurlServer, state::State};
+use crate::{
+    dns::{DnsProvider, Rfc2136Provider, TsigKey},
+    rate_limit::RateLimits,
+    repository::LnurlRepository,
+    routes::LnurlServer,
+    state::State,
+};
 use anyhow::anyhow;
 use axum::{
     Extension, Router,
@@ -22,25 +28,40 @@ use spark::tree::InMemoryTreeStore;
 use spark_wallet::{DefaultSigner, Network, SparkWalletConfig};
 use sqlx::{PgPool, SqlitePool, sqlite::SqlitePoolOptions};
 use std::collections::HashSet;
+use std::net::SocketAddr;
 use std::str::FromStr;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
 use tokio::sync::{Mutex, watch};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{debug, error, info};
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 use x509_parser::prelude::{FromDer, X509Certificate};
 
+mod admin;
 mod auth;
 mod background;
+mod bip353;
+mod dns;
 mod error;
 mod invoice_paid;
+mod leader;
+mod lnurl_auth;
+mod metrics;
+mod nip05;
+mod payer_data;
 mod postgresql;
+mod rate_limit;
 mod repository;
 mod routes;
 mod sqlite;
 mod state;
 mod time;
 mod user;
+mod webhook;
+mod withdraw;
 mod zap;
 
 #[derive(Clone, Parser, Debug, Serialize, Deserialize)]
@@ -75,10 +96,12 @@ struct Args {
     pub scheme: String,
 
     /// Minimum amount (in millisatoshi) that can be sent in a lnurl payment.
+    /// Used for users that did not set their own minimum.
     #[arg(long, default_value = "1000")]
     pub min_sendable: u64,
 
     /// Maximum amount (in millisatoshi) that can be sent in a lnurl payment.
+    /// Used for users that did not set their own maximum.
     #[arg(long, default_value = "4000000000")]
     pub max_sendable: u64,
 
@@ -102,6 +125,75 @@ struct Args {
     /// If set, the server will use this certificate to validate api keys.
     #[arg(long)]
     pub ca_cert: Option<String>,
+
+    /// How long (in seconds) an LNURL-auth login session stays valid.
+    #[arg(long, default_value = "3600")]
+    pub auth_session_ttl_secs: u64,
+
+    /// Hex encoded secret authenticating the LUD-18 payer data auth challenges. Replicas must
+    /// share it to accept the challenges handed out by each other. If not set, a random secret
+    /// is used, so challenges don't survive a restart.
+    #[arg(long)]
+    pub payer_data_secret: Option<String>,
+
+    /// Api key for the admin api and the metrics endpoint, passed as a bearer token.
+    /// If not set, the admin api and the metrics endpoint are disabled.
+    #[arg(long)]
+    pub admin_api_key: Option<String>,
+
+    /// Maximum number of requests per minute per client ip. 0 disables the limit.
+    #[arg(long, default_value = "120")]
+    pub rate_limit_per_ip: u32,
+
+    /// Maximum number of invoices per hour per client ip. 0 disables the limit.
+    #[arg(long, default_value = "100")]
+    pub invoice_limit_per_ip: u32,
+
+    /// Maximum number of invoices per hour per user. 0 disables the limit.
+    #[arg(long, default_value = "1000")]
+    pub invoice_limit_per_user: u32,
+
+    /// Maximum number of username registrations per hour per client ip. 0 disables the limit.
+    #[arg(long, default_value = "10")]
+    pub register_limit_per_ip: u32,
+
+    /// Maximum number of username registrations per day per user. 0 disables the limit.
+    #[arg(long, default_value = "5")]
+    pub register_limit_per_pubkey: u32,
+
+    /// Whether the server runs behind a proxy that sets the `X-Forwarded-For` header.
+    /// If set, the last address in the header is used as the client ip for rate limiting.
+    #[arg(long, default_value = "false")]
+    pub trust_forwarded_for: bool,
+
+    /// List of usernames that can't be registered on any domain. Comma separated.
+    /// Usernames can also be reserved per domain through the admin api.
+    #[arg(long, default_value = "")]
+    pub blocked_usernames: String,
+
+    /// Address of the primary dns server of the domains, accepting dynamic updates (RFC 2136).
+    /// If set, users can have BIP-353 payment instructions published for their username.
+    #[arg(long)]
+    pub dns_update_server: Option<SocketAddr>,
+
+    /// Name of the TSIG key authenticating dynamic dns updates.
+    #[arg(long)]
+    pub dns_tsig_key_name: Option<String>,
+
+    /// Base64 encoded secret of the TSIG key authenticating dynamic dns updates, using
+    /// HMAC-SHA256.
+    #[arg(long)]
+    pub dns_tsig_secret: Option<String>,
+
+    /// Time to live (in seconds) of the published BIP-353 records.
+    #[arg(long, default_value = "3600")]
+    pub dns_ttl: u32,
+
+    /// DNS zones the BIP-353 records are published in. Comma separated. The records of a domain
+    /// are published in the most specific listed zone containing the domain, or in a zone named
+    /// after the domain if none does.
+    #[arg(long, default_value = "")]
+    pub dns_zones: String,
 }
 
 #[tokio::main]
@@ -223,6 +315,13 @@ where
     let domains: HashSet<String> = repository.list_domains().await?.into_iter().collect();
     info!("loaded {} allowed domains from database", domains.len());
 
+    let blocked_usernames: HashSet<String> = args
+        .blocked_usernames
+        .split(',')
+        .map(lnurl_models::sanitize_username)
+        .filter(|u| !u.is_empty())
+        .collect();
+
     let ca_cert = args
         .ca_cert
         .map(|ca_cert_str| {
@@ -244,62 +343,62 @@ where
         })
         .transpose()?;
 
-    let subscribed_keys = Arc::new(Mutex::new(HashSet::new()));
-
-    // Create watch channel for triggering background processing
-    let (invoice_paid_trigger, invoice_paid_rx) = watch::channel(());
+    let payer_data_secret = match args.payer_data_secret {
+        Some(secret) => hex::decode(secret.trim())
+            .map_err(|e| anyhow!("failed to decode hex payer data secret: {:?}", e))?,
+        None => rand::random::<[u8; 32]>().to_vec(),
+    };
 
-    // Start background processor for handling paid invoices.
-    background::start_background_processor(repository.clone(), nostr_keys.clone(), invoice_paid_rx);
+    let tsig_key = match (args.dns_tsig_key_name, args.dns_tsig_secret) {
+        (Some(name), Some(secret)) => {
+            let secret = BASE64_STANDARD
+                .decode(secret.trim())
+                .map_err(|e| anyhow!("failed to decode base64 dns tsig secret: {:?}", e))?;
+            Some(TsigKey { name, secret })
+        }
+        (None, None) => None,
+        _ => {
+            return Err(anyhow!("dns tsig key name and secret must be set together"));
+        }
+    };
+    let dns_provider = args.dns_update_server.map(|server| {
+        info!("publishing bip353 records through dns server {}", server);
+        Arc::new(Rfc2136Provider::new(server, tsig_key, args.dns_ttl)) as Arc<dyn DnsProvider>
+    });
+    let dns_zones: Vec<String> = args
+        .dns_zones
+        .split(',')
+        .map(|zone| zone.trim().trim_end_matches('.').to_lowercase())
+        .filter(|zone| !zone.is_empty())
+        .collect();
 
-    // Subscribe to users with unexpired invoices for payment monitoring
-    for user in repository.get_invoice_monitored_users().await? {
-        let user_pubkey = bitcoin::secp256k1::PublicKey::from_str(&user)
-            .map_err(|e| anyhow!("failed to parse user pubkey: {e:?}"))?;
+    let subscribed_keys = Arc::new(Mutex::new(HashSet::new()));
 
-        background::create_rpc_client_and_subscribe(
-            repository.clone(),
-            user_pubkey,
-            &connection_manager,
-            &coordinator,
-            signer.clone(),
-            session_manager.clone(),
-            Arc::clone(&service_provider),
-            Arc::clone(&subscribed_keys),
-            invoice_paid_trigger.clone(),
-        )
-        .await?;
-    }
+    // Elect the instance that runs the subscriptions and background jobs, so replicas sharing the
+    // database don't do the same work twice.
+    let instance_id = hex::encode(rand::random::<[u8; 16]>());
+    let leader = leader::start_leader_election(repository.clone(), instance_id.clone());
 
-    if let Some(nostr_keys) = &nostr_keys {
-        // Also subscribe for legacy zap monitoring (users with unexpired zaps)
-        for user in repository.get_zap_monitored_users().await? {
-            let user_pubkey = bitcoin::secp256k1::PublicKey::from_str(&user)
-                .map_err(|e| anyhow!("failed to parse user pubkey: {e:?}"))?;
-
-            zap::create_rpc_client_and_subscribe(
-                repository.clone(),
-                user_pubkey,
-                &connection_manager,
-                &coordinator,
-                signer.clone(),
-                session_manager.clone(),
-                Arc::clone(&service_provider),
-                nostr_keys.clone(),
-                Arc::clone(&subscribed_keys),
-            )
-            .await?;
-        }
-    }
+    // Create watch channel for triggering background processing
+    let (background_trigger, background_rx) = watch::channel(());
+
+    // Start background processor for handling paid invoices and webhook deliveries.
+    background::start_background_processor(
+        repository.clone(),
+        nostr_keys.clone(),
+        background_rx,
+        leader.clone(),
+    );
 
     let state = State {
         db: repository,
         wallet,
+        network: args.network,
         scheme: args.scheme,
         min_sendable: args.min_sendable,
         max_sendable: args.max_sendable,
         include_spark_address: args.include_spark_address,
-        domains,
+        domains: Arc::new(RwLock::new(domains)),
         nostr_keys,
         ca_cert,
         connection_manager,
@@ -308,9 +407,62 @@ where
         session_manager,
         service_provider,
         subscribed_keys,
-        invoice_paid_trigger,
+        background_trigger,
+        leader,
+        auth_session_ttl_secs: args.auth_session_ttl_secs,
+        payer_data_secret: Arc::new(payer_data_secret),
+        admin_api_key: args.admin_api_key,
+        rate_limits: RateLimits {
+            requests_per_ip: args.rate_limit_per_ip,
+            invoices_per_ip: args.invoice_limit_per_ip,
+            invoices_per_user: args.invoice_limit_per_user,
+            registrations_per_ip: args.register_limit_per_ip,
+            registrations_per_pubkey: args.register_limit_per_pubkey,
+        },
+        trust_forwarded_for: args.trust_forwarded_for,
+        blocked_usernames: Arc::new(blocked_usernames),
+        dns_provider,
+        dns_zones: Arc::new(dns_zones),
     };
 
+    // Subscribe to users with unexpired invoices or zaps for payment monitoring
+    background::start_subscription_manager(state.clone());
+    // Pick up domains added on other instances
+    background::start_domain_refresher(state.clone());
+
+    let admin_router = Router::new()
+        .route("/metrics", get(LnurlServer::<DB>::metrics))
+        .route("/admin/domains", get(LnurlServer::<DB>::admin_list_domains))
+        .route("/admin/domains", post(LnurlServer::<DB>::admin_add_domain))
+        .route(
+            "/admin/domains/{domain}/users/{identifier}",
+            get(LnurlServer::<DB>::admin_get_user),
+        )
+        .route(
+            "/admin/domains/{domain}/users/{identifier}",
+            delete(LnurlServer::<DB>::admin_delete_user),
+        )
+        .route(
+            "/admin/domains/{domain}/reserved-usernames",
+            get(LnurlServer::<DB>::admin_list_username_reservations),
+        )
+        .route(
+            "/admin/domains/{domain}/reserved-usernames",
+            post(LnurlServer::<DB>::admin_reserve_username),
+        )
+        .route(
+            "/admin/domains/{domain}/reserved-usernames/{name}",
+            delete(LnurlServer::<DB>::admin_delete_username_reservation),
+        )
+        .route(
+            "/admin/zaps/{payment_hash}/publish",
+            post(LnurlServer::<DB>::admin_republish_zap),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::admin_auth::<DB>,
+        ));
+
     let server_router = Router::new()
         .route(
             "/lnurlpay/available/{identifier}",
@@ -334,6 +486,38 @@ where
             "/lnurlpay/{pubkey}/invoice-paid",
             post(LnurlServer::<DB>::invoice_paid),
         )
+        .route(
+            "/lnurlpay/{pubkey}/withdraw-links",
+            post(LnurlServer::<DB>::create_withdraw_link),
+        )
+        .route(
+            "/lnurlpay/{pubkey}/withdraw-links",
+            get(LnurlServer::<DB>::list_withdraw_links),
+        )
+        .route(
+            "/lnurlpay/{pubkey}/withdraw-links/{id}",
+            delete(LnurlServer::<DB>::delete_withdraw_link),
+        )
+        .route(
+            "/lnurlpay/{pubkey}/withdraw-requests",
+            get(LnurlServer::<DB>::list_withdraw_requests),
+        )
+        .route(
+            "/lnurlpay/{pubkey}/withdraw-requests/{payment_hash}",
+            post(LnurlServer::<DB>::complete_withdraw_request),
+        )
+        .route(
+            "/lnurlpay/{pubkey}/webhooks",
+            post(LnurlServer::<DB>::create_webhook),
+        )
+        .route(
+            "/lnurlpay/{pubkey}/webhooks",
+            get(LnurlServer::<DB>::list_webhooks),
+        )
+        .route(
+            "/lnurlpay/{pubkey}/webhooks/{id}",
+            delete(LnurlServer::<DB>::delete_webhook),
+        )
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
             auth::auth::<DB>,
@@ -342,6 +526,10 @@ where
             "/.well-known/lnurlp/{identifier}",
             get(LnurlServer::<DB>::handle_lnurl_pay),
         )
+        .route(
+            "/.well-known/nostr.json",
+            get(LnurlServer::<DB>::handle_nip05),
+        )
         .route(
             "/lnurlp/{identifier}",
             get(LnurlServer::<DB>::handle_lnurl_pay),
@@ -350,8 +538,42 @@ where
             "/lnurlp/{identifier}/invoice",
             get(LnurlServer::<DB>::handle_invoice),
         )
+        .route(
+            "/lnurlw/{id}",
+            get(LnurlServer::<DB>::handle_lnurl_withdraw),
+        )
+        .route(
+            "/lnurlw/{id}/callback",
+            get(LnurlServer::<DB>::handle_withdraw_callback),
+        )
         .route("/verify/{payment_hash}", get(LnurlServer::<DB>::verify))
-        .layer(Extension(state))
+        .route(
+            "/lnurl-auth/challenge",
+            post(LnurlServer::<DB>::create_auth_challenge),
+        )
+        .route(
+            "/lnurl-auth/callback",
+            get(LnurlServer::<DB>::handle_auth_callback),
+        )
+        .route(
+            "/lnurl-auth/session",
+            post(LnurlServer::<DB>::create_auth_session),
+        )
+        .route(
+            "/lnurl-auth/session",
+            get(LnurlServer::<DB>::get_auth_session),
+        )
+        .route(
+            "/lnurl-auth/session",
+            delete(LnurlServer::<DB>::delete_auth_session),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit::limit_requests::<DB>,
+        ))
+        .merge(admin_router)
+        .route_layer(middleware::from_fn(metrics::track_metrics))
+        .layer(Extension(state.clone()))
         .layer(
             CorsLayer::new()
                 .allow_origin(Any)
@@ -361,7 +583,10 @@ where
         .layer(DefaultBodyLimit::max(1_000_000));
 
     let listener = tokio::net::TcpListener::bind(args.address).await?;
-    let server = axum::serve(listener, server_router.into_make_service());
+    let server = axum::serve(
+        listener,
+        server_router.into_make_service_with_connect_info::<SocketAddr>(),
+    );
 
     let graceful = server.with_graceful_shutdown(async {
         tokio::signal::ctrl_c()
@@ -374,6 +599,15 @@ where
         error!("shutdown error: {e}");
     }
 
+    // Hand over the leadership right away, rather than when the lease expires
+    if let Err(e) = state
+        .db
+        .release_lease(leader::LEADER_LEASE, &instance_id)
+        .await
+    {
+        error!("failed to release leader lease: {e}");
+    }
+
     info!("lnurl server stopped");
     Ok(())
 }
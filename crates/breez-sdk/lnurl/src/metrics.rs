@@ -0,0 +1,167 @@
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use prometheus::{
+    HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+
+/// Content type of the prometheus text exposition format
+pub const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+pub struct Metrics {
+    registry: Registry,
+    pub http_requests: IntCounterVec,
+    pub http_request_duration: HistogramVec,
+    pub invoices_created: IntCounter,
+    pub invoices_paid: IntCounter,
+    pub zap_publish_failures: IntCounter,
+    /// Updated when the metrics are scraped
+    pub newly_paid_queue_depth: IntGauge,
+    /// Updated when the metrics are scraped
+    pub spark_subscriptions: IntGauge,
+    pub spark_subscription_errors: IntCounter,
+    pub rate_limited_requests: IntCounterVec,
+    /// Updated when the metrics are scraped
+    pub leader: IntGauge,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry =
+            Registry::new_custom(Some("lnurl".to_string()), None).expect("valid metrics registry");
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "Number of handled http requests"),
+            &["method", "route", "status"],
+        )
+        .expect("valid metric");
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Time spent handling http requests",
+            ),
+            &["method", "route"],
+        )
+        .expect("valid metric");
+        let invoices_created =
+            IntCounter::new("invoices_created_total", "Number of invoices created")
+                .expect("valid metric");
+        let invoices_paid = IntCounter::new("invoices_paid_total", "Number of invoices paid")
+            .expect("valid metric");
+        let zap_publish_failures = IntCounter::new(
+            "zap_publish_failures_total",
+            "Number of failed attempts to publish a zap receipt",
+        )
+        .expect("valid metric");
+        let newly_paid_queue_depth = IntGauge::new(
+            "newly_paid_queue_depth",
+            "Number of paid invoices waiting for background processing",
+        )
+        .expect("valid metric");
+        let spark_subscriptions = IntGauge::new(
+            "spark_subscriptions",
+            "Number of users subscribed to for payment events over Spark RPC",
+        )
+        .expect("valid metric");
+        let spark_subscription_errors = IntCounter::new(
+            "spark_subscription_errors_total",
+            "Number of failed or dropped Spark RPC event subscriptions",
+        )
+        .expect("valid metric");
+        let rate_limited_requests = IntCounterVec::new(
+            Opts::new(
+                "rate_limited_requests_total",
+                "Number of requests rejected by a rate limit",
+            ),
+            &["limit"],
+        )
+        .expect("valid metric");
+        let leader = IntGauge::new(
+            "leader",
+            "Whether this instance is the leader running the background jobs",
+        )
+        .expect("valid metric");
+
+        registry
+            .register(Box::new(http_requests.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(http_request_duration.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(invoices_created.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(invoices_paid.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(zap_publish_failures.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(newly_paid_queue_depth.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(spark_subscriptions.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(spark_subscription_errors.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(rate_limited_requests.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(leader.clone()))
+            .expect("unique metric");
+
+        Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            invoices_created,
+            invoices_paid,
+            zap_publish_failures,
+            newly_paid_queue_depth,
+            spark_subscriptions,
+            spark_subscription_errors,
+            rate_limited_requests,
+            leader,
+        }
+    }
+
+    /// Renders all metrics in the prometheus text exposition format.
+    pub fn render(&self) -> Result<String, prometheus::Error> {
+        TextEncoder::new().encode_to_string(&self.registry.gather())
+    }
+}
+
+/// Middleware recording the count and latency of requests per route. Routes are labeled by their
+/// path template rather than the requested path, to keep the number of series bounded.
+pub async fn track_metrics(req: Request, next: Next) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| "unmatched".to_string(), |path| path.as_str().to_string());
+    let method = req.method().to_string();
+
+    let start = Instant::now();
+    let response = next.run(req).await;
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let status = response.status().as_u16().to_string();
+    METRICS
+        .http_requests
+        .with_label_values(&[method.as_str(), route.as_str(), status.as_str()])
+        .inc();
+    METRICS
+        .http_request_duration
+        .with_label_values(&[method.as_str(), route.as_str()])
+        .observe(elapsed);
+    response
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use lnurl_models::{NostrIdentity, nostr_proof_content};
+use nostr::{Event, JsonUtil, RelayUrl};
+use serde::{Deserialize, Serialize};
+
+const MAX_RELAYS: usize = 10;
+const MAX_RELAY_URL_LEN: usize = 255;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Nip05Error {
+    #[error("invalid nostr pubkey")]
+    InvalidPubkey,
+    #[error("too many nostr relays")]
+    TooManyRelays,
+    #[error("invalid nostr relay")]
+    InvalidRelay,
+    #[error("invalid nostr proof")]
+    InvalidProof,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Nip05Params {
+    pub name: Option<String>,
+}
+
+/// The `/.well-known/nostr.json` document, as per NIP-05.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Nip05Response {
+    pub names: HashMap<String, String>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub relays: HashMap<String, Vec<String>>,
+}
+
+impl Nip05Response {
+    pub fn new(name: String, identity: NostrIdentity) -> Self {
+        let mut response = Nip05Response::default();
+        if !identity.relays.is_empty() {
+            response
+                .relays
+                .insert(identity.pubkey.clone(), identity.relays);
+        }
+        response.names.insert(name, identity.pubkey);
+        response
+    }
+}
+
+/// Validates a nostr identity linked to a lightning address. The proof must be an event signed by
+/// the nostr key, binding the key to both the lightning address and the wallet's pubkey, so it
+/// can't be replayed by whoever registers the username next.
+pub fn validate_nostr_identity(
+    identity: &NostrIdentity,
+    lightning_address: &str,
+    pubkey: &str,
+) -> Result<(), Nip05Error> {
+    let nostr_pubkey =
+        nostr::PublicKey::from_hex(&identity.pubkey).map_err(|_| Nip05Error::InvalidPubkey)?;
+    // Served as is, so it must be in the canonical lowercase hex form
+    if nostr_pubkey.to_hex() != identity.pubkey {
+        return Err(Nip05Error::InvalidPubkey);
+    }
+
+    if identity.relays.len() > MAX_RELAYS {
+        return Err(Nip05Error::TooManyRelays);
+    }
+    for relay in &identity.relays {
+        if relay.len() > MAX_RELAY_URL_LEN || RelayUrl::parse(relay).is_err() {
+            return Err(Nip05Error::InvalidRelay);
+        }
+    }
+
+    let event = Event::from_json(&identity.proof).map_err(|_| Nip05Error::InvalidProof)?;
+    if event.verify().is_err()
+        || event.pubkey != nostr_pubkey
+        || event.content != nostr_proof_content(lightning_address, pubkey)
+    {
+        return Err(Nip05Error::InvalidProof);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,248 @@
+use std::str::FromStr;
+
+use bitcoin::hashes::{Hash, HashEngine, Hmac, HmacEngine, sha256};
+use bitcoin::secp256k1::PublicKey;
+use serde::{Deserialize, Serialize};
+
+use crate::lnurl_auth::verify_k1_signature;
+use crate::repository::{LnurlRepository, LnurlRepositoryError};
+use crate::time::now;
+
+/// How long an auth challenge handed out with the pay request can be used.
+const AUTH_CHALLENGE_TTL_SECS: u64 = 600;
+/// A challenge is 32 bytes, as per LUD-04: a random nonce, the expiry and a truncated MAC.
+const CHALLENGE_LEN: usize = 32;
+const CHALLENGE_NONCE_LEN: usize = 8;
+const CHALLENGE_MAC_START: usize = CHALLENGE_NONCE_LEN + 8;
+const CHALLENGE_MAC_LEN: usize = CHALLENGE_LEN - CHALLENGE_MAC_START;
+const MAX_PAYER_DATA_LEN: usize = 1000;
+const MAX_PAYER_DATA_FIELD_LEN: usize = 256;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PayerDataError {
+    #[error("payer data too long")]
+    TooLong,
+    #[error("invalid payer data")]
+    Invalid,
+    #[error("payer data auth expired or already used")]
+    AuthExpired,
+    #[error("invalid payer data auth")]
+    InvalidAuth,
+    #[error(transparent)]
+    Repository(#[from] LnurlRepositoryError),
+}
+
+/// An auth challenge handed out to payers of a user, as per LUD-18.
+///
+/// Challenges aren't stored when handed out: the k1 carries its expiry and a MAC binding it to
+/// the user, keyed with the server secret. Only used challenges are stored, until they expire, so
+/// each can only be used once.
+#[derive(Debug, Clone)]
+pub struct PayerDataChallenge {
+    pub k1: String,
+    pub domain: String,
+    pub username: String,
+    /// Unix timestamp (seconds) after which the challenge can no longer be used
+    pub expires_at: i64,
+}
+
+impl PayerDataChallenge {
+    pub fn new(secret: &[u8], domain: &str, username: &str) -> Self {
+        let nonce: [u8; CHALLENGE_NONCE_LEN] = rand::random();
+        let expires_at = now().saturating_add_unsigned(AUTH_CHALLENGE_TTL_SECS);
+        let mut k1 = Vec::with_capacity(CHALLENGE_LEN);
+        k1.extend_from_slice(&nonce);
+        k1.extend_from_slice(&expires_at.to_be_bytes());
+        k1.extend_from_slice(&challenge_mac(secret, &k1, domain, username));
+        PayerDataChallenge {
+            k1: hex::encode(k1),
+            domain: domain.to_string(),
+            username: username.to_string(),
+            expires_at,
+        }
+    }
+
+    /// Parses a challenge handed out to payers of the user. Returns `None` if the server didn't
+    /// issue the k1 for this user.
+    fn parse(secret: &[u8], k1: &str, domain: &str, username: &str) -> Option<Self> {
+        let bytes = hex::decode(k1).ok()?;
+        if bytes.len() != CHALLENGE_LEN {
+            return None;
+        }
+        let (data, mac) = bytes.split_at(CHALLENGE_MAC_START);
+        let expected_mac = challenge_mac(secret, data, domain, username);
+        // Compared in constant time, so the MAC can't be guessed byte by byte.
+        let diff = mac
+            .iter()
+            .zip(expected_mac)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b));
+        if diff != 0 {
+            return None;
+        }
+        let expires_at = i64::from_be_bytes(data[CHALLENGE_NONCE_LEN..].try_into().ok()?);
+        Some(PayerDataChallenge {
+            k1: k1.to_string(),
+            domain: domain.to_string(),
+            username: username.to_string(),
+            expires_at,
+        })
+    }
+}
+
+/// MAC of the nonce and expiry of a challenge, binding them to the user it was issued for.
+fn challenge_mac(
+    secret: &[u8],
+    data: &[u8],
+    domain: &str,
+    username: &str,
+) -> [u8; CHALLENGE_MAC_LEN] {
+    let mut engine = HmacEngine::<sha256::Hash>::new(secret);
+    engine.input(data);
+    engine.input(format!("{domain}\n{username}").as_bytes());
+    let mac = Hmac::<sha256::Hash>::from_engine(engine);
+    let mut truncated = [0u8; CHALLENGE_MAC_LEN];
+    truncated.copy_from_slice(&mac.as_byte_array()[..CHALLENGE_MAC_LEN]);
+    truncated
+}
+
+/// The payer data the service accepts, as per LUD-18
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PayerDataRequest {
+    pub name: PayerDataField,
+    pub pubkey: PayerDataField,
+    pub identifier: PayerDataField,
+    pub auth: PayerDataAuthField,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PayerDataField {
+    pub mandatory: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PayerDataAuthField {
+    pub mandatory: bool,
+    pub k1: String,
+}
+
+/// The payer data sent by the payer in the callback, as per LUD-18
+#[derive(Debug, Deserialize)]
+struct PayerData {
+    name: Option<String>,
+    pubkey: Option<String>,
+    identifier: Option<String>,
+    auth: Option<PayerDataAuth>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PayerDataAuth {
+    key: String,
+    k1: String,
+    sig: String,
+}
+
+impl PayerDataRequest {
+    /// All payer data is optional.
+    pub fn new(challenge: &PayerDataChallenge) -> Self {
+        let optional = PayerDataField { mandatory: false };
+        PayerDataRequest {
+            name: optional.clone(),
+            pubkey: optional.clone(),
+            identifier: optional,
+            auth: PayerDataAuthField {
+                mandatory: false,
+                k1: challenge.k1.clone(),
+            },
+        }
+    }
+}
+
+/// Validates the raw payer data json sent by the payer. An auth challenge signed by the payer is
+/// used up, so the signed payer data can't be replayed.
+pub async fn validate_payer_data<DB>(
+    db: &DB,
+    secret: &[u8],
+    payer_data: &str,
+    domain: &str,
+    username: &str,
+) -> Result<(), PayerDataError>
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    if payer_data.len() > MAX_PAYER_DATA_LEN {
+        return Err(PayerDataError::TooLong);
+    }
+
+    let payer_data: PayerData =
+        serde_json::from_str(payer_data).map_err(|_| PayerDataError::Invalid)?;
+    for field in [&payer_data.name, &payer_data.identifier]
+        .into_iter()
+        .flatten()
+    {
+        if field.chars().count() > MAX_PAYER_DATA_FIELD_LEN {
+            return Err(PayerDataError::TooLong);
+        }
+    }
+
+    if let Some(pubkey) = &payer_data.pubkey {
+        PublicKey::from_str(pubkey).map_err(|_| PayerDataError::Invalid)?;
+    }
+
+    if let Some(auth) = &payer_data.auth {
+        let challenge = PayerDataChallenge::parse(secret, &auth.k1, domain, username)
+            .ok_or(PayerDataError::InvalidAuth)?;
+        if challenge.expires_at <= now() {
+            return Err(PayerDataError::AuthExpired);
+        }
+        verify_k1_signature(&auth.k1, &auth.sig, &auth.key)
+            .map_err(|_| PayerDataError::InvalidAuth)?;
+        if !db.use_payer_data_challenge(&challenge).await? {
+            return Err(PayerDataError::AuthExpired);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PayerDataChallenge;
+
+    const SECRET: &[u8] = b"secret";
+
+    #[test]
+    fn test_challenge_parses_for_issuing_user() {
+        let challenge = PayerDataChallenge::new(SECRET, "example.com", "alice");
+        assert_eq!(challenge.k1.len(), 64);
+
+        let parsed = PayerDataChallenge::parse(SECRET, &challenge.k1, "example.com", "alice")
+            .expect("challenge should parse");
+        assert_eq!(parsed.expires_at, challenge.expires_at);
+    }
+
+    #[test]
+    fn test_challenge_rejected_for_other_user_or_secret() {
+        let challenge = PayerDataChallenge::new(SECRET, "example.com", "alice");
+
+        assert!(PayerDataChallenge::parse(SECRET, &challenge.k1, "example.com", "bob").is_none());
+        assert!(PayerDataChallenge::parse(SECRET, &challenge.k1, "other.com", "alice").is_none());
+        assert!(
+            PayerDataChallenge::parse(b"other", &challenge.k1, "example.com", "alice").is_none()
+        );
+    }
+
+    #[test]
+    fn test_challenge_rejected_when_tampered() {
+        let challenge = PayerDataChallenge::new(SECRET, "example.com", "alice");
+        let mut bytes = hex::decode(&challenge.k1).unwrap();
+        // Extend the expiry
+        bytes[8] ^= 1;
+
+        assert!(
+            PayerDataChallenge::parse(SECRET, &hex::encode(bytes), "example.com", "alice")
+                .is_none()
+        );
+        assert!(PayerDataChallenge::parse(SECRET, "00", "example.com", "alice").is_none());
+        assert!(PayerDataChallenge::parse(SECRET, "not hex", "example.com", "alice").is_none());
+    }
+}
@@ -0,0 +1,168 @@
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+
+use axum::{
+    Json,
+    extract::{self, ConnectInfo, Request},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::Value;
+use tracing::{debug, error};
+
+use crate::metrics::METRICS;
+use crate::repository::LnurlRepository;
+use crate::state::State;
+use crate::time::now;
+
+const FORWARDED_FOR_HEADER: &str = "X-Forwarded-For";
+
+/// Configured rate limits. A limit of 0 disables it.
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimits {
+    /// Requests per minute per client ip
+    pub requests_per_ip: u32,
+    /// Invoices per hour per client ip
+    pub invoices_per_ip: u32,
+    /// Invoices per hour per user
+    pub invoices_per_user: u32,
+    /// Username registrations per hour per client ip
+    pub registrations_per_ip: u32,
+    /// Username registrations per day per user pubkey
+    pub registrations_per_pubkey: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum RateLimit {
+    Requests,
+    IpInvoices,
+    UserInvoices,
+    IpRegistrations,
+    PubkeyRegistrations,
+}
+
+impl RateLimit {
+    fn name(self) -> &'static str {
+        match self {
+            RateLimit::Requests => "requests",
+            RateLimit::IpInvoices => "ip_invoices",
+            RateLimit::UserInvoices => "user_invoices",
+            RateLimit::IpRegistrations => "ip_registrations",
+            RateLimit::PubkeyRegistrations => "pubkey_registrations",
+        }
+    }
+
+    fn window_secs(self) -> i64 {
+        match self {
+            RateLimit::Requests => 60,
+            RateLimit::IpInvoices | RateLimit::UserInvoices | RateLimit::IpRegistrations => 3600,
+            RateLimit::PubkeyRegistrations => 86_400,
+        }
+    }
+
+    fn max_hits(self, limits: &RateLimits) -> u32 {
+        match self {
+            RateLimit::Requests => limits.requests_per_ip,
+            RateLimit::IpInvoices => limits.invoices_per_ip,
+            RateLimit::UserInvoices => limits.invoices_per_user,
+            RateLimit::IpRegistrations => limits.registrations_per_ip,
+            RateLimit::PubkeyRegistrations => limits.registrations_per_pubkey,
+        }
+    }
+}
+
+/// The ip address of the client, as determined by the rate limiting middleware.
+#[derive(Clone, Copy, Debug)]
+pub struct ClientIp(pub IpAddr);
+
+/// Counts a hit against a fixed window rate limit for the subject, and returns whether the hit
+/// is within the limit. The counters live in the repository, so the limits hold across replicas.
+/// The limit fails open when the repository is unavailable.
+pub async fn check_rate_limit<DB>(state: &State<DB>, limit: RateLimit, subject: &str) -> bool
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    let max_hits = limit.max_hits(&state.rate_limits);
+    if max_hits == 0 {
+        return true;
+    }
+
+    let now = now();
+    let window_secs = limit.window_secs();
+    let window_start = now.saturating_sub(now.rem_euclid(window_secs));
+    let key = format!("{}:{subject}", limit.name());
+    match state
+        .db
+        .increment_rate_limit(&key, window_start, window_start.saturating_add(window_secs))
+        .await
+    {
+        Ok(hits) if hits > i64::from(max_hits) => {
+            debug!("rate limit exceeded for {}", key);
+            METRICS
+                .rate_limited_requests
+                .with_label_values(&[limit.name()])
+                .inc();
+            false
+        }
+        Ok(_) => true,
+        Err(e) => {
+            error!("failed to increment rate limit {}: {}", key, e);
+            true
+        }
+    }
+}
+
+/// Middleware limiting the number of requests per client ip. Makes the client ip available to
+/// handlers as a [`ClientIp`] extension.
+pub async fn limit_requests<DB>(
+    extract::State(state): extract::State<State<DB>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<Value>)>
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    let ip = client_ip(req.headers(), addr, state.trust_forwarded_for);
+    if !check_rate_limit(&state, RateLimit::Requests, &rate_limit_subject(ip)).await {
+        return Err(too_many_requests());
+    }
+
+    req.extensions_mut().insert(ClientIp(ip));
+    Ok(next.run(req).await)
+}
+
+/// The subject ip addresses are rate limited by. IPv6 clients usually get a whole /64 prefix,
+/// so they are limited by prefix.
+pub fn rate_limit_subject(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => ip.to_string(),
+        IpAddr::V6(ip) => {
+            let [a, b, c, d, ..] = ip.segments();
+            format!("{}/64", Ipv6Addr::new(a, b, c, d, 0, 0, 0, 0))
+        }
+    }
+}
+
+pub fn too_many_requests() -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(Value::String("too many requests".into())),
+    )
+}
+
+/// Determines the client ip. Behind a trusted proxy, this is the last address in the
+/// `X-Forwarded-For` header, the one added by the proxy. Earlier addresses are set by the client
+/// and can't be trusted.
+fn client_ip(headers: &HeaderMap, addr: SocketAddr, trust_forwarded_for: bool) -> IpAddr {
+    if trust_forwarded_for
+        && let Some(ip) = headers
+            .get(FORWARDED_FOR_HEADER)
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| header.rsplit(',').next())
+            .and_then(|ip| ip.trim().parse::<IpAddr>().ok())
+    {
+        return ip;
+    }
+    addr.ip()
+}
@@ -1,6 +1,10 @@
 use lnurl_models::ListMetadataMetadata;
 
-use crate::user::User;
+use crate::lnurl_auth::{AuthChallenge, AuthSession};
+use crate::payer_data::PayerDataChallenge;
+use crate::user::{User, UsernameReservation};
+use crate::webhook::{PendingWebhookDelivery, Webhook, WebhookDelivery};
+use crate::withdraw::{WithdrawLink, WithdrawRequest, WithdrawRequestStatus};
 use crate::zap::Zap;
 
 #[derive(Debug, thiserror::Error)]
@@ -24,6 +28,11 @@ pub struct Invoice {
     pub user_pubkey: String,
     pub invoice: String,
     pub preimage: Option<String>,
+    /// Payer data json sent by the payer, as per LUD-18
+    pub payer_data: Option<String>,
+    /// The domain the invoice was requested on. Unknown for invoices created before the domain
+    /// was recorded.
+    pub domain: Option<String>,
     pub invoice_expiry: i64,
     pub created_at: i64,
     pub updated_at: i64,
@@ -82,6 +91,17 @@ pub trait LnurlRepository {
     /// Insert or update an invoice
     async fn upsert_invoice(&self, invoice: &Invoice) -> Result<(), LnurlRepositoryError>;
 
+    /// Mark an unpaid invoice as paid: store its preimage, and queue it and the given webhook
+    /// deliveries for background processing, in a single transaction. Returns false, queueing
+    /// nothing, if the invoice doesn't exist or already has a preimage.
+    async fn mark_invoice_paid(
+        &self,
+        payment_hash: &str,
+        preimage: &str,
+        newly_paid: &NewlyPaid,
+        deliveries: &[WebhookDelivery],
+    ) -> Result<bool, LnurlRepositoryError>;
+
     /// Get an invoice by payment hash
     async fn get_invoice_by_payment_hash(
         &self,
@@ -103,6 +123,18 @@ pub trait LnurlRepository {
     /// Get all newly paid invoices ready for processing (`next_retry_at` <= now)
     async fn get_pending_newly_paid(&self) -> Result<Vec<NewlyPaid>, LnurlRepositoryError>;
 
+    /// Claim a newly paid invoice for processing by postponing its next retry, so no other
+    /// instance processes it concurrently. Returns false if it isn't due or was claimed already.
+    async fn claim_newly_paid(
+        &self,
+        payment_hash: &str,
+        now: i64,
+        claimed_until: i64,
+    ) -> Result<bool, LnurlRepositoryError>;
+
+    /// Count all newly paid invoices in the queue, including those waiting for a retry
+    async fn count_newly_paid(&self) -> Result<i64, LnurlRepositoryError>;
+
     /// Update retry count and next retry time for a newly paid invoice
     async fn update_newly_paid_retry(
         &self,
@@ -113,4 +145,218 @@ pub trait LnurlRepository {
 
     /// Delete a newly paid invoice from the queue
     async fn delete_newly_paid(&self, payment_hash: &str) -> Result<(), LnurlRepositoryError>;
+
+    /// Insert a new withdraw link
+    async fn insert_withdraw_link(&self, link: &WithdrawLink) -> Result<(), LnurlRepositoryError>;
+
+    /// Get a withdraw link by id
+    async fn get_withdraw_link(
+        &self,
+        id: &str,
+    ) -> Result<Option<WithdrawLink>, LnurlRepositoryError>;
+
+    /// Get all withdraw links of a user
+    async fn list_withdraw_links(
+        &self,
+        domain: &str,
+        user_pubkey: &str,
+    ) -> Result<Vec<WithdrawLink>, LnurlRepositoryError>;
+
+    /// Delete a withdraw link of a user. Returns false if the link was not found.
+    async fn delete_withdraw_link(
+        &self,
+        domain: &str,
+        user_pubkey: &str,
+        id: &str,
+    ) -> Result<bool, LnurlRepositoryError>;
+
+    /// Claim a use of a withdraw link if it has uses left and is not expired at `now` (seconds).
+    /// Returns false if the link could not be claimed.
+    async fn claim_withdraw_link_use(
+        &self,
+        id: &str,
+        now: u64,
+    ) -> Result<bool, LnurlRepositoryError>;
+
+    /// Give back a previously claimed use of a withdraw link
+    async fn release_withdraw_link_use(&self, id: &str) -> Result<(), LnurlRepositoryError>;
+
+    /// Insert a new withdraw request
+    async fn insert_withdraw_request(
+        &self,
+        request: &WithdrawRequest,
+    ) -> Result<(), LnurlRepositoryError>;
+
+    /// Get a withdraw request by payment hash
+    async fn get_withdraw_request(
+        &self,
+        payment_hash: &str,
+    ) -> Result<Option<WithdrawRequest>, LnurlRepositoryError>;
+
+    /// Get all pending withdraw requests of a user
+    async fn get_pending_withdraw_requests(
+        &self,
+        user_pubkey: &str,
+    ) -> Result<Vec<WithdrawRequest>, LnurlRepositoryError>;
+
+    /// Set the final status of a pending withdraw request. Returns false if the request was not
+    /// pending anymore.
+    async fn complete_pending_withdraw_request(
+        &self,
+        payment_hash: &str,
+        status: WithdrawRequestStatus,
+        updated_at: i64,
+    ) -> Result<bool, LnurlRepositoryError>;
+
+    /// Insert a new LNURL-auth challenge
+    async fn insert_auth_challenge(
+        &self,
+        challenge: &AuthChallenge,
+    ) -> Result<(), LnurlRepositoryError>;
+
+    /// Get an LNURL-auth challenge by k1
+    async fn get_auth_challenge(
+        &self,
+        k1: &str,
+    ) -> Result<Option<AuthChallenge>, LnurlRepositoryError>;
+
+    /// Store the linking key that signed a challenge, if it is unsigned and not expired at `now`
+    /// (seconds). Returns false if the challenge could not be signed.
+    async fn sign_auth_challenge(
+        &self,
+        k1: &str,
+        linking_key: &str,
+        now: i64,
+    ) -> Result<bool, LnurlRepositoryError>;
+
+    /// Delete an LNURL-auth challenge. Returns false if the challenge was not found.
+    async fn delete_auth_challenge(&self, k1: &str) -> Result<bool, LnurlRepositoryError>;
+
+    /// Record a LUD-18 payer data auth challenge as used. Returns false if it was already used,
+    /// so each can be used only once.
+    async fn use_payer_data_challenge(
+        &self,
+        challenge: &PayerDataChallenge,
+    ) -> Result<bool, LnurlRepositoryError>;
+
+    /// Delete the used payer data auth challenges expired at `now` (seconds)
+    async fn delete_expired_payer_data_challenges(
+        &self,
+        now: i64,
+    ) -> Result<(), LnurlRepositoryError>;
+
+    /// Insert a new LNURL-auth session
+    async fn insert_auth_session(&self, session: &AuthSession) -> Result<(), LnurlRepositoryError>;
+
+    /// Get an LNURL-auth session by token hash
+    async fn get_auth_session(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<AuthSession>, LnurlRepositoryError>;
+
+    /// Delete an LNURL-auth session
+    async fn delete_auth_session(&self, token_hash: &str) -> Result<(), LnurlRepositoryError>;
+
+    /// Delete the LNURL-auth challenges and sessions expired at `now` (seconds)
+    async fn delete_expired_auth(&self, now: i64) -> Result<(), LnurlRepositoryError>;
+
+    /// Insert a new webhook
+    async fn insert_webhook(&self, webhook: &Webhook) -> Result<(), LnurlRepositoryError>;
+
+    /// Get all webhooks of a user
+    async fn list_webhooks(
+        &self,
+        domain: &str,
+        user_pubkey: &str,
+    ) -> Result<Vec<Webhook>, LnurlRepositoryError>;
+
+    /// Delete a webhook of a user and its pending deliveries. Returns false if the webhook was not
+    /// found.
+    async fn delete_webhook(
+        &self,
+        domain: &str,
+        user_pubkey: &str,
+        id: &str,
+    ) -> Result<bool, LnurlRepositoryError>;
+
+    /// Insert a webhook delivery into the queue
+    async fn insert_webhook_delivery(
+        &self,
+        delivery: &WebhookDelivery,
+    ) -> Result<(), LnurlRepositoryError>;
+
+    /// Get all webhook deliveries ready for processing (`next_retry_at` <= now)
+    async fn get_pending_webhook_deliveries(
+        &self,
+    ) -> Result<Vec<PendingWebhookDelivery>, LnurlRepositoryError>;
+
+    /// Update retry count and next retry time for a webhook delivery
+    async fn update_webhook_delivery_retry(
+        &self,
+        id: &str,
+        retry_count: i32,
+        next_retry_at: i64,
+    ) -> Result<(), LnurlRepositoryError>;
+
+    /// Delete a webhook delivery from the queue
+    async fn delete_webhook_delivery(&self, id: &str) -> Result<(), LnurlRepositoryError>;
+
+    /// Claim a webhook delivery for sending by postponing its next retry, so no other instance
+    /// sends it concurrently. Returns false if it isn't due or was claimed already.
+    async fn claim_webhook_delivery(
+        &self,
+        id: &str,
+        now: i64,
+        claimed_until: i64,
+    ) -> Result<bool, LnurlRepositoryError>;
+
+    /// Count a hit against a rate limit window, returning the number of hits in the window
+    async fn increment_rate_limit(
+        &self,
+        key: &str,
+        window_start: i64,
+        expires_at: i64,
+    ) -> Result<i64, LnurlRepositoryError>;
+
+    /// Delete rate limit windows that ended before the given time
+    async fn delete_expired_rate_limits(&self, now: i64) -> Result<(), LnurlRepositoryError>;
+
+    /// Acquire or renew a lease for the holder. A lease held by another holder can only be
+    /// acquired once it expired. Returns whether the holder holds the lease.
+    async fn try_acquire_lease(
+        &self,
+        name: &str,
+        holder: &str,
+        now: i64,
+        expires_at: i64,
+    ) -> Result<bool, LnurlRepositoryError>;
+
+    /// Release a lease, if held by the holder
+    async fn release_lease(&self, name: &str, holder: &str) -> Result<(), LnurlRepositoryError>;
+
+    /// Insert or update a username reservation
+    async fn upsert_username_reservation(
+        &self,
+        reservation: &UsernameReservation,
+    ) -> Result<(), LnurlRepositoryError>;
+
+    /// Get the reservation of a username, if any
+    async fn get_username_reservation(
+        &self,
+        domain: &str,
+        name: &str,
+    ) -> Result<Option<UsernameReservation>, LnurlRepositoryError>;
+
+    /// List the username reservations of a domain
+    async fn list_username_reservations(
+        &self,
+        domain: &str,
+    ) -> Result<Vec<UsernameReservation>, LnurlRepositoryError>;
+
+    /// Delete a username reservation. Returns false if it didn't exist.
+    async fn delete_username_reservation(
+        &self,
+        domain: &str,
+        name: &str,
+    ) -> Result<bool, LnurlRepositoryError>;
 }
@@ -1,20 +1,25 @@
 use axum::{
     Extension, Json,
     extract::{Path, Query},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
     response::IntoResponse,
 };
 use axum_extra::extract::Host;
+use base64::{Engine, prelude::BASE64_STANDARD};
 use bitcoin::{
     hashes::{Hash, sha256},
     secp256k1::{PublicKey, XOnlyPublicKey, ecdsa::Signature},
 };
 use lightning_invoice::Bolt11Invoice;
 use lnurl_models::{
-    CheckUsernameAvailableResponse, InvoicePaidRequest, ListMetadataRequest, ListMetadataResponse,
-    PublishZapReceiptRequest, PublishZapReceiptResponse, RecoverLnurlPayRequest,
+    CheckUsernameAvailableResponse, CompleteWithdrawRequest, CreateWebhookRequest,
+    CreateWebhookResponse, CreateWithdrawLinkRequest, CreateWithdrawLinkResponse,
+    DeleteWebhookRequest, DeleteWithdrawLinkRequest, InvoicePaidRequest, ListMetadataRequest,
+    ListMetadataResponse, ListWebhooksRequest, ListWebhooksResponse, ListWithdrawLinksRequest,
+    ListWithdrawLinksResponse, ListWithdrawRequestsRequest, ListWithdrawRequestsResponse,
+    LnurlPaySettings, PublishZapReceiptRequest, PublishZapReceiptResponse, RecoverLnurlPayRequest,
     RecoverLnurlPayResponse, RegisterLnurlPayRequest, RegisterLnurlPayResponse,
-    UnregisterLnurlPayRequest, sanitize_username,
+    UnregisterLnurlPayRequest, WebhookEvent, sanitize_username, webhook_signing_message,
 };
 use nostr::{Alphabet, Event, EventBuilder, JsonUtil, Kind, TagStandard, key::Keys};
 use regex::Regex;
@@ -26,20 +31,40 @@ use std::sync::Arc;
 use tracing::{debug, error, trace, warn};
 
 use crate::{
+    bip353::{self, Bip353Error},
     invoice_paid::{HandleInvoicePaidError, create_invoice, handle_invoice_paid},
+    lnurl_auth::{
+        AUTH_CHALLENGE_TTL_SECS, AuthChallenge, AuthChallengeResponse, AuthSession,
+        AuthSessionResponse, CreateAuthSessionRequest, LnurlAuthCallbackParams, hash_secret,
+        random_hex, verify_k1_signature,
+    },
+    metrics::METRICS,
+    nip05::{Nip05Params, Nip05Response, validate_nostr_identity},
+    payer_data::{PayerDataChallenge, PayerDataError, PayerDataRequest, validate_payer_data},
+    rate_limit::{ClientIp, RateLimit, check_rate_limit, rate_limit_subject, too_many_requests},
     repository::LnurlSenderComment,
-    time::{now_millis, now_u64},
+    time::{now, now_millis, now_u64},
     zap::Zap,
 };
 use crate::{
     repository::{LnurlRepository, LnurlRepositoryError},
     state::State,
     user::{USERNAME_VALIDATION_REGEX, User},
+    webhook::{
+        InvoiceEvent, MAX_WEBHOOKS_PER_USER, Webhook, WebhookError, queue_webhook_deliveries,
+        validate_webhook_url,
+    },
+    withdraw::{WithdrawError, WithdrawLink, complete_withdraw_request, submit_withdraw_request},
 };
 
 const ACCEPTABLE_TIME_DIFF_SECS: u64 = 60;
 const DEFAULT_METADATA_OFFSET: u32 = 0;
 const DEFAULT_METADATA_LIMIT: u32 = 100;
+/// Comments are stored in a column of 255 characters.
+const MAX_COMMENT_ALLOWED: u16 = 255;
+const MAX_LONG_DESCRIPTION_LEN: usize = 2000;
+const MAX_IMAGE_BYTES: usize = 100_000;
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct LnurlPayCallbackParams {
@@ -47,6 +72,14 @@ pub struct LnurlPayCallbackParams {
     pub comment: Option<String>,
     pub nostr: Option<String>,
     pub expiry: Option<u32>,
+    /// Payer data json, as per LUD-18
+    pub payerdata: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LnurlWithdrawCallbackParams {
+    pub k1: Option<String>,
+    pub pr: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -92,6 +125,30 @@ pub struct PayResponse {
     #[serde(rename = "nostrPubkey")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nostr_pubkey: Option<XOnlyPublicKey>,
+
+    /// Optional, the payer data the service accepts (LUD-18)
+    #[serde(rename = "payerData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer_data: Option<PayerDataRequest>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WithdrawResponse {
+    /// the url the wallet sends the invoice to
+    pub callback: String,
+    /// the challenge identifying this withdraw link
+    pub k1: String,
+    /// tag of the request
+    pub tag: Tag,
+    /// description to put in the invoice
+    #[serde(rename = "defaultDescription")]
+    pub default_description: String,
+    /// min withdrawable amount in millisatoshi
+    #[serde(rename = "minWithdrawable")]
+    pub min_withdrawable: u64,
+    /// max withdrawable amount in millisatoshi
+    #[serde(rename = "maxWithdrawable")]
+    pub max_withdrawable: u64,
 }
 
 pub struct LnurlServer<DB> {
@@ -109,9 +166,10 @@ where
     ) -> Result<Json<CheckUsernameAvailableResponse>, (StatusCode, Json<Value>)> {
         let username = sanitize_username(&identifier);
         validate_username(&username)?;
+        let domain = sanitize_domain(&state, &host)?;
         let user = state
             .db
-            .get_user_by_name(&sanitize_domain(&state, &host)?, &username)
+            .get_user_by_name(&domain, &username)
             .await
             .map_err(|e| {
                 error!("failed to execute query: {}", e);
@@ -120,9 +178,13 @@ where
                     Json(Value::String("internal server error".into())),
                 )
             })?;
+        if user.is_some() {
+            return Ok(Json(CheckUsernameAvailableResponse { available: false }));
+        }
 
+        let reserved = is_username_reserved(&state, &domain, &username, None).await?;
         Ok(Json(CheckUsernameAvailableResponse {
-            available: user.is_none(),
+            available: !reserved,
         }))
     }
 
@@ -130,6 +192,7 @@ where
         Host(host): Host,
         Path(pubkey): Path<String>,
         Extension(state): Extension<State<DB>>,
+        Extension(ClientIp(client_ip)): Extension<ClientIp>,
         Json(payload): Json<RegisterLnurlPayRequest>,
     ) -> Result<Json<RegisterLnurlPayResponse>, (StatusCode, Json<Value>)> {
         let username = sanitize_username(&payload.username);
@@ -148,13 +211,76 @@ where
                 Json(Value::String("description too long".into())),
             ));
         }
+        validate_pay_settings(&payload.pay_settings, &state)?;
+
+        let domain = sanitize_domain(&state, &host)?;
+        if is_username_reserved(&state, &domain, &username, Some(&pubkey.to_string())).await? {
+            trace!("name reserved: {}", username);
+            return Err((
+                StatusCode::CONFLICT,
+                Json(Value::String("name already taken".into())),
+            ));
+        }
+
+        // Only taking a new name counts against the registration limits, updating the settings
+        // of a registered name doesn't
+        let registered = state
+            .db
+            .get_user_by_pubkey(&domain, &pubkey.to_string())
+            .await
+            .map_err(|e| {
+                error!("failed to execute query: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("internal server error".into())),
+                )
+            })?;
+        if registered
+            .as_ref()
+            .is_none_or(|registered| registered.name != username)
+        {
+            let ip = rate_limit_subject(client_ip);
+            let pubkey = pubkey.to_string();
+            if !check_rate_limit(&state, RateLimit::IpRegistrations, &ip).await
+                || !check_rate_limit(&state, RateLimit::PubkeyRegistrations, &pubkey).await
+            {
+                return Err(too_many_requests());
+            }
+        }
+
+        if let Some(nostr) = &payload.nostr {
+            let lightning_address = format!("{username}@{domain}");
+            validate_nostr_identity(nostr, &lightning_address, &pubkey.to_string()).map_err(
+                |e| {
+                    trace!("invalid nostr identity: {}", e);
+                    (StatusCode::BAD_REQUEST, Json(Value::String(e.to_string())))
+                },
+            )?;
+        }
+
+        if payload.publish_bip353 {
+            if state.dns_provider.is_none() {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(Value::String("bip353 not supported".into())),
+                ));
+            }
+            bip353::record_name(&username, &domain).map_err(bip353_error)?;
+            if let Some(offer) = &payload.bolt12_offer {
+                bip353::validate_bolt12_offer(offer).map_err(bip353_error)?;
+            }
+        }
 
         let user = User {
-            domain: sanitize_domain(&state, &host)?,
+            domain,
             pubkey: pubkey.to_string(),
             name: username,
             description: payload.description,
             lnurl_private_mode_enabled: payload.lnurl_private_mode_enabled,
+            pay_settings: payload.pay_settings,
+            nostr: payload.nostr,
+            bip353_enabled: payload.publish_bip353,
+            bolt12_offer: payload.bolt12_offer.filter(|_| payload.publish_bip353),
         };
 
         if let Err(e) = state.db.upsert_user(&user).await {
@@ -174,10 +300,31 @@ where
         }
 
         debug!("registered user '{}' for pubkey {}", user.name, pubkey);
+
+        // Remove the payment instructions published for a previous username, or that are no
+        // longer wanted
+        if let Some(registered) = registered
+            && (registered.name != user.name || !user.bip353_enabled)
+        {
+            bip353::unpublish(&state, &registered).await;
+        }
+        let bip353_address = if user.bip353_enabled {
+            Some(bip353::publish(&state, &user).await.map_err(|e| {
+                error!("failed to publish bip353 record for '{}': {}", user.name, e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("failed to publish bip353 record".into())),
+                )
+            })?)
+        } else {
+            None
+        };
+
         let lnurl = format!("lnurlp://{}/lnurlp/{}", user.domain, user.name);
         Ok(Json(RegisterLnurlPayResponse {
             lnurl,
             lightning_address: format!("{}@{}", user.name, user.domain),
+            bip353_address,
         }))
     }
 
@@ -197,17 +344,27 @@ where
         )
         .await?;
 
+        let domain = sanitize_domain(&state, &host)?;
+        let internal_error = |e: LnurlRepositoryError| {
+            error!("failed to execute query: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Value::String("internal server error".into())),
+            )
+        };
+        let user = state
+            .db
+            .get_user_by_pubkey(&domain, &pubkey.to_string())
+            .await
+            .map_err(internal_error)?;
         state
             .db
-            .delete_user(&sanitize_domain(&state, &host)?, &pubkey.to_string())
+            .delete_user(&domain, &pubkey.to_string())
             .await
-            .map_err(|e| {
-                error!("failed to execute query: {}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(Value::String("internal server error".into())),
-                )
-            })?;
+            .map_err(internal_error)?;
+        if let Some(user) = user {
+            bip353::unpublish(&state, &user).await;
+        }
         debug!("unregistered user for pubkey {}", pubkey);
         Ok(())
     }
@@ -242,11 +399,15 @@ where
         match user {
             Some(user) => {
                 let lnurl = format!("lnurlp://{}/lnurlp/{}", &user.domain, user.name);
+                let bip353_address = user.bip353_enabled.then(|| bip353::bip353_address(&user));
                 Ok(Json(RecoverLnurlPayResponse {
                     lnurl,
                     lightning_address: format!("{}@{}", user.name, &user.domain),
+                    bip353_address,
                     username: user.name,
                     description: user.description,
+                    pay_settings: user.pay_settings,
+                    nostr: user.nostr,
                 }))
             }
             None => Err((
@@ -377,7 +538,7 @@ where
                 &state.db,
                 &payment_hash,
                 preimage,
-                &state.invoice_paid_trigger,
+                &state.background_trigger,
             )
             .await
             {
@@ -564,7 +725,7 @@ where
             return Err((StatusCode::NOT_FOUND, Json(Value::String(String::new()))));
         };
 
-        // In LNURL private mode, omit nostr fields entirely
+        // In LNURL private mode, omit nostr and payer data fields entirely
         // Otherwise, always return server's nostrPubkey for zap receipt signing
         let (allows_nostr, nostr_pubkey) = if let Some(nostr_keys) = state.nostr_keys.as_ref()
             && !user.lnurl_private_mode_enabled
@@ -580,18 +741,28 @@ where
         } else {
             (None, None)
         };
+        let payer_data = if user.lnurl_private_mode_enabled {
+            None
+        } else {
+            let challenge =
+                PayerDataChallenge::new(&state.payer_data_secret, &user.domain, &user.name);
+            Some(PayerDataRequest::new(&challenge))
+        };
         Ok(Json(PayResponse {
             callback: format!(
                 "{}://{}/lnurlp/{}/invoice",
                 state.scheme, &user.domain, user.name
             ),
-            max_sendable: state.max_sendable,
-            min_sendable: state.min_sendable,
+            max_sendable: max_sendable(&user, &state),
+            min_sendable: min_sendable(&user, &state),
             tag: Tag::Pay,
             metadata: get_metadata(&user.domain, &user),
-            comment_allowed: Some(255),
+            comment_allowed: Some(comment_allowed(&user))
+                .filter(|len| *len > 0)
+                .map(u32::from),
             allows_nostr,
             nostr_pubkey,
+            payer_data,
         }))
     }
 
@@ -601,6 +772,7 @@ where
         Path(identifier): Path<String>,
         Query(params): Query<LnurlPayCallbackParams>,
         Extension(state): Extension<State<DB>>,
+        Extension(ClientIp(client_ip)): Extension<ClientIp>,
     ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
         if identifier.is_empty() {
             return Err((StatusCode::NOT_FOUND, Json(Value::String(String::new()))));
@@ -630,6 +802,54 @@ where
             return Err(lnurl_error("amount must be a whole sat amount"));
         }
 
+        if amount_msat < min_sendable(&user, &state) || amount_msat > max_sendable(&user, &state) {
+            trace!("amount out of range: {}", amount_msat);
+            return Err(lnurl_error("amount out of range"));
+        }
+
+        if let Some(comment) = &params.comment
+            && comment.trim().chars().count() > usize::from(comment_allowed(&user))
+        {
+            trace!("comment too long");
+            return Err(lnurl_error("comment too long"));
+        }
+
+        // The client ip is checked first, so a single client can't use up the user's invoices.
+        // Limits are checked before the payer data, so a rejected request doesn't use up its
+        // auth challenge.
+        let ip = rate_limit_subject(client_ip);
+        if !check_rate_limit(&state, RateLimit::IpInvoices, &ip).await
+            || !check_rate_limit(&state, RateLimit::UserInvoices, &user.pubkey).await
+        {
+            trace!("invoice limit exceeded for user {}", user.pubkey);
+            return Err(lnurl_error("too many invoice requests, try again later"));
+        }
+
+        if let Some(payer_data) = &params.payerdata {
+            if user.lnurl_private_mode_enabled {
+                trace!("payer data not supported");
+                return Err(lnurl_error("payer data not supported"));
+            }
+            validate_payer_data(
+                &state.db,
+                &state.payer_data_secret,
+                payer_data,
+                &user.domain,
+                &user.name,
+            )
+            .await
+            .map_err(|e| match e {
+                PayerDataError::Repository(e) => {
+                    error!("failed to execute query: {}", e);
+                    lnurl_error("internal server error")
+                }
+                e => {
+                    trace!("invalid payer data: {}", e);
+                    lnurl_error(&e.to_string())
+                }
+            })?;
+        }
+
         let nostr_pubkey = state
             .nostr_keys
             .as_ref()
@@ -657,8 +877,10 @@ where
             validate_nostr_zap_request(amount_msat, &event)?;
             sha256::Hash::hash(event.as_json().as_bytes())
         } else {
+            // With payer data, the description hash commits to it too (LUD-18)
             let metadata = get_metadata(&user.domain, &user);
-            sha256::Hash::hash(metadata.as_bytes())
+            let payer_data = params.payerdata.as_deref().unwrap_or_default();
+            sha256::Hash::hash(format!("{metadata}{payer_data}").as_bytes())
         };
 
         let pubkey = parse_pubkey(&user.pubkey)?;
@@ -680,6 +902,7 @@ where
             })?;
 
         debug!("Created lightning invoice: {:?}", res);
+        METRICS.invoices_created.inc();
 
         let invoice = Bolt11Invoice::from_str(&res.invoice).map_err(|e| {
             error!("failed to parse invoice: {}", e);
@@ -724,7 +947,7 @@ where
             }
         }
 
-        if let Some(comment) = params.comment {
+        if let Some(comment) = &params.comment {
             let comment = comment.trim();
             if !comment.is_empty()
                 && let Err(e) = state
@@ -749,10 +972,12 @@ where
             // Store invoice in invoices table
             if let Err(e) = create_invoice(
                 &state.db,
+                &user.domain,
                 &payment_hash,
                 &user.pubkey,
                 &res.invoice,
                 invoice_expiry,
+                params.payerdata.as_deref(),
             )
             .await
             {
@@ -760,22 +985,30 @@ where
                 return Err(lnurl_error("internal server error"));
             }
 
-            crate::background::create_rpc_client_and_subscribe(
-                state.db.clone(),
-                pubkey,
-                &state.connection_manager,
-                &state.coordinator,
-                state.signer.clone(),
-                state.session_manager.clone(),
-                state.service_provider.clone(),
-                Arc::clone(&state.subscribed_keys),
-                state.invoice_paid_trigger.clone(),
-            )
-            .await
-            .map_err(|e| {
-                error!("failed to subscribe to user for invoice monitoring: {}", e);
-                lnurl_error("internal server error")
-            })?;
+            // Only the leader monitors payments. Other instances leave the subscription to the
+            // leader, which polls the invoices table for users to subscribe to. Payments made
+            // before the leader subscribes are caught up on when it subscribes.
+            let is_leader = *state.leader.borrow();
+            if is_leader {
+                crate::background::create_rpc_client_and_subscribe(
+                    state.db.clone(),
+                    pubkey,
+                    state.network,
+                    &state.connection_manager,
+                    &state.coordinator,
+                    state.signer.clone(),
+                    state.session_manager.clone(),
+                    state.service_provider.clone(),
+                    Arc::clone(&state.subscribed_keys),
+                    state.background_trigger.clone(),
+                    state.leader.clone(),
+                )
+                .await
+                .map_err(|e| {
+                    error!("failed to subscribe to user for invoice monitoring: {}", e);
+                    lnurl_error("internal server error")
+                })?;
+            }
 
             // Build verify URL
             Some(format!(
@@ -784,6 +1017,36 @@ where
             ))
         };
 
+        let comment = params
+            .comment
+            .as_deref()
+            .map(str::trim)
+            .filter(|comment| !comment.is_empty());
+        match queue_webhook_deliveries(
+            &state.db,
+            &InvoiceEvent {
+                event: WebhookEvent::InvoiceRequested,
+                domain: &user.domain,
+                user_pubkey: &user.pubkey,
+                payment_hash: &payment_hash,
+                invoice: &res.invoice,
+                preimage: None,
+                comment,
+                payer_data: params.payerdata.as_deref(),
+            },
+        )
+        .await
+        {
+            Ok(0) => {}
+            Ok(_) => {
+                if state.background_trigger.send(()).is_err() {
+                    error!("Failed to trigger background processor - receiver dropped");
+                }
+            }
+            // The invoice is still handed out, the webhook is best effort
+            Err(e) => error!("Failed to queue webhook deliveries: {}", e),
+        }
+
         let mut response = json!({
             "pr": res.invoice,
             "routes": Vec::<String>::new(),
@@ -886,7 +1149,7 @@ where
             &state.db,
             &payment_hash_hex,
             &payload.preimage,
-            &state.invoice_paid_trigger,
+            &state.background_trigger,
         )
         .await
         .map_err(|e| {
@@ -903,92 +1166,757 @@ where
         );
         Ok(())
     }
-}
-
-fn validate_nostr_zap_request(
-    amount_msat: u64,
-    event: &Event,
-) -> Result<(), (StatusCode, Json<Value>)> {
-    if event.kind != Kind::ZapRequest {
-        trace!("nostr event is incorrect kind");
-        return Err(lnurl_error("invalid nostr event"));
-    }
 
-    // 1. It MUST have a valid nostr signature
-    if event.verify().is_err() {
-        trace!("invalid nostr event, does not verify");
-        return Err(lnurl_error("invalid nostr event"));
-    }
+    pub async fn create_withdraw_link(
+        Host(host): Host,
+        Path(pubkey): Path<String>,
+        Extension(state): Extension<State<DB>>,
+        Json(payload): Json<CreateWithdrawLinkRequest>,
+    ) -> Result<Json<CreateWithdrawLinkResponse>, (StatusCode, Json<Value>)> {
+        let pubkey = validate(
+            &pubkey,
+            &payload.signature,
+            &payload.description,
+            payload.timestamp,
+            &state,
+        )
+        .await?;
+        validate_withdraw_link(&payload)?;
 
-    // 2. It MUST have tags
-    if event.tags.is_empty() {
-        trace!("invalid nostr event, missing tags");
-        return Err(lnurl_error("invalid nostr event"));
-    }
+        // Only registered users can issue withdraw links
+        let domain = sanitize_domain(&state, &host)?;
+        let user = state
+            .db
+            .get_user_by_pubkey(&domain, &pubkey.to_string())
+            .await
+            .map_err(|e| {
+                error!("failed to execute query: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("internal server error".into())),
+                )
+            })?;
+        if user.is_none() {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(Value::String("user not found".into())),
+            ));
+        }
 
-    // 3. It MUST have only one p tag
-    if event
-        .tags
-        .iter()
-        .filter_map(nostr::Tag::single_letter_tag)
-        .filter(|t| t.is_lowercase() && t.character == Alphabet::P)
-        .count()
-        != 1
-    {
-        trace!("invalid nostr event, missing or multiple 'p' tags");
-        return Err(lnurl_error("invalid nostr event"));
-    }
+        let now = now_millis();
+        let link = WithdrawLink {
+            id: hex::encode(rand::random::<[u8; 16]>()),
+            domain,
+            user_pubkey: pubkey.to_string(),
+            description: payload.description,
+            min_withdrawable: payload.min_withdrawable,
+            max_withdrawable: payload.max_withdrawable,
+            max_uses: payload.max_uses,
+            uses: 0,
+            expires_at: payload.expires_at,
+            created_at: now,
+            updated_at: now,
+        };
+        state.db.insert_withdraw_link(&link).await.map_err(|e| {
+            error!("failed to save withdraw link: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Value::String("internal server error".into())),
+            )
+        })?;
 
-    // 4. It MUST have 0 or 1 e tags
-    if event
-        .tags
-        .iter()
-        .filter_map(nostr::Tag::single_letter_tag)
-        .filter(|t| t.is_lowercase() && t.character == Alphabet::E)
-        .count()
-        > 1
-    {
-        trace!("invalid nostr event, multiple 'e' tags");
-        return Err(lnurl_error("invalid nostr event"));
+        debug!("created withdraw link {} for pubkey {}", link.id, pubkey);
+        Ok(Json(CreateWithdrawLinkResponse {
+            link: link.into_model(&state.scheme),
+        }))
     }
 
-    // 5. There should be a relays tag with the relays to send the zap receipt to.
-    if !event
-        .tags
-        .iter()
-        .any(|t| matches!(t.as_standardized(), Some(TagStandard::Relays(_))))
-    {
-        trace!("invalid nostr event, missing relay tag");
-        return Err(lnurl_error("invalid nostr event"));
+    pub async fn list_withdraw_links(
+        Host(host): Host,
+        Path(pubkey): Path<String>,
+        Query(params): Query<ListWithdrawLinksRequest>,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<Json<ListWithdrawLinksResponse>, (StatusCode, Json<Value>)> {
+        let pubkey = validate(
+            &pubkey,
+            &params.signature,
+            &pubkey,
+            params.timestamp,
+            &state,
+        )
+        .await?;
+        let links = state
+            .db
+            .list_withdraw_links(&sanitize_domain(&state, &host)?, &pubkey.to_string())
+            .await
+            .map_err(|e| {
+                error!("failed to execute query: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("internal server error".into())),
+                )
+            })?;
+        Ok(Json(ListWithdrawLinksResponse {
+            links: links
+                .into_iter()
+                .map(|link| link.into_model(&state.scheme))
+                .collect(),
+        }))
     }
 
-    // 6. If there is an amount tag, it MUST be equal to the amount query parameter.
-    if let Some(millisats) = event.tags.iter().find_map(|t| {
-        if let Some(TagStandard::Amount { millisats, .. }) = t.as_standardized() {
-            Some(millisats)
-        } else {
-            None
+    pub async fn delete_withdraw_link(
+        Host(host): Host,
+        Path((pubkey, id)): Path<(String, String)>,
+        Extension(state): Extension<State<DB>>,
+        Json(payload): Json<DeleteWithdrawLinkRequest>,
+    ) -> Result<(), (StatusCode, Json<Value>)> {
+        let pubkey = validate(&pubkey, &payload.signature, &id, payload.timestamp, &state).await?;
+        let deleted = state
+            .db
+            .delete_withdraw_link(&sanitize_domain(&state, &host)?, &pubkey.to_string(), &id)
+            .await
+            .map_err(|e| {
+                error!("failed to execute query: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("internal server error".into())),
+                )
+            })?;
+        if !deleted {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(Value::String("withdraw link not found".into())),
+            ));
         }
-    }) && *millisats != amount_msat
-    {
-        trace!("invalid nostr event, amount does not match");
-        return Err(lnurl_error("invalid nostr event"));
+        debug!("deleted withdraw link {} for pubkey {}", id, pubkey);
+        Ok(())
     }
 
-    // 7. If there is an 'a' tag, it MUST be a valid event coordinate
-    // NOTE: Assuming the tag is well-formed and contains the necessary fields, because it's standard.
-
-    // 8. There MUST be 0 or 1 P tags. If there is one, it MUST be equal to the zap receipt's pubkey.
-    // TODO: Implement this check.
-    Ok(())
-}
-
-fn validate_username(username: &str) -> Result<(), (StatusCode, Json<Value>)> {
-    if username.chars().take(65).count() > 64 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(Value::String("username too long".into())),
-        ));
+    /// Lists the invoices submitted through the user's withdraw links that still need to be paid.
+    pub async fn list_withdraw_requests(
+        Path(pubkey): Path<String>,
+        Query(params): Query<ListWithdrawRequestsRequest>,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<Json<ListWithdrawRequestsResponse>, (StatusCode, Json<Value>)> {
+        let pubkey = validate(
+            &pubkey,
+            &params.signature,
+            &pubkey,
+            params.timestamp,
+            &state,
+        )
+        .await?;
+        let requests = state
+            .db
+            .get_pending_withdraw_requests(&pubkey.to_string())
+            .await
+            .map_err(|e| {
+                error!("failed to execute query: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("internal server error".into())),
+                )
+            })?;
+        Ok(Json(ListWithdrawRequestsResponse {
+            requests: requests.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    /// Withdraw completion endpoint.
+    /// Client notifies server whether it paid the invoice of a withdraw request.
+    pub async fn complete_withdraw_request(
+        Path((pubkey, payment_hash)): Path<(String, String)>,
+        Extension(state): Extension<State<DB>>,
+        Json(payload): Json<CompleteWithdrawRequest>,
+    ) -> Result<(), (StatusCode, Json<Value>)> {
+        let pubkey = validate(
+            &pubkey,
+            &payload.signature,
+            &payment_hash,
+            payload.timestamp,
+            &state,
+        )
+        .await?;
+
+        let request = state
+            .db
+            .get_withdraw_request(&payment_hash)
+            .await
+            .map_err(|e| {
+                error!("Failed to get withdraw request: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("internal server error".into())),
+                )
+            })?
+            .filter(|request| request.user_pubkey == pubkey.to_string())
+            .ok_or_else(|| {
+                trace!(
+                    "withdraw request not found for payment hash: {}",
+                    payment_hash
+                );
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(Value::String("withdraw request not found".into())),
+                )
+            })?;
+
+        complete_withdraw_request(&state.db, &request, payload.preimage.as_deref())
+            .await
+            .map_err(|e| match e {
+                WithdrawError::InvalidPreimage => {
+                    trace!("invalid preimage for withdraw request {}", payment_hash);
+                    (
+                        StatusCode::BAD_REQUEST,
+                        Json(Value::String("invalid preimage".into())),
+                    )
+                }
+                e => {
+                    error!("Failed to complete withdraw request: {}", e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(Value::String("internal server error".into())),
+                    )
+                }
+            })?;
+        Ok(())
+    }
+
+    /// LUD-03 withdraw endpoint
+    pub async fn handle_lnurl_withdraw(
+        Host(host): Host,
+        Path(id): Path<String>,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<Json<WithdrawResponse>, (StatusCode, Json<Value>)> {
+        let link = get_withdraw_link(&state, &host, &id).await?;
+        if link.is_expired() {
+            return Err(lnurl_error("withdraw link expired"));
+        }
+        if link.is_exhausted() {
+            return Err(lnurl_error("withdraw link has no uses left"));
+        }
+
+        Ok(Json(WithdrawResponse {
+            callback: format!(
+                "{}://{}/lnurlw/{}/callback",
+                state.scheme, link.domain, link.id
+            ),
+            k1: link.id,
+            tag: Tag::Withdraw,
+            default_description: link.description,
+            min_withdrawable: link.min_withdrawable,
+            max_withdrawable: link.max_withdrawable,
+        }))
+    }
+
+    /// LUD-03 withdraw callback. The invoice is queued for the owning wallet, which pays it
+    /// on its next sync.
+    pub async fn handle_withdraw_callback(
+        Host(host): Host,
+        Path(id): Path<String>,
+        Query(params): Query<LnurlWithdrawCallbackParams>,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+        let link = get_withdraw_link(&state, &host, &id).await?;
+        if params.k1.as_deref() != Some(link.id.as_str()) {
+            trace!("invalid k1 for withdraw link {}", link.id);
+            return Err(lnurl_error("invalid k1"));
+        }
+        let Some(pr) = params.pr else {
+            trace!("missing invoice");
+            return Err(lnurl_error("missing invoice"));
+        };
+        let invoice = Bolt11Invoice::from_str(&pr).map_err(|e| {
+            trace!("invalid invoice, could not parse: {}", e);
+            lnurl_error("invalid invoice")
+        })?;
+
+        submit_withdraw_request(&state.db, &link, &invoice)
+            .await
+            .map_err(|e| match e {
+                WithdrawError::Repository(e) => {
+                    error!("Failed to submit withdraw request: {}", e);
+                    lnurl_error("internal server error")
+                }
+                e => {
+                    trace!("withdraw request rejected: {}", e);
+                    lnurl_error(&e.to_string())
+                }
+            })?;
+
+        Ok(Json(json!({ "status": "OK" })))
+    }
+
+    /// Registers a webhook notified about the user's invoices. The returned secret is used to sign
+    /// the deliveries and is not returned again.
+    pub async fn create_webhook(
+        Host(host): Host,
+        Path(pubkey): Path<String>,
+        Extension(state): Extension<State<DB>>,
+        Json(payload): Json<CreateWebhookRequest>,
+    ) -> Result<Json<CreateWebhookResponse>, (StatusCode, Json<Value>)> {
+        let pubkey = validate(
+            &pubkey,
+            &payload.signature,
+            &webhook_signing_message(&payload.url, &payload.events),
+            payload.timestamp,
+            &state,
+        )
+        .await?;
+        let bad_request = |e: WebhookError| {
+            trace!("invalid webhook: {}", e);
+            (StatusCode::BAD_REQUEST, Json(Value::String(e.to_string())))
+        };
+        validate_webhook_url(&payload.url, &state.scheme)
+            .await
+            .map_err(bad_request)?;
+        let mut events = payload.events;
+        events.sort_unstable();
+        events.dedup();
+        if events.is_empty() {
+            return Err(bad_request(WebhookError::NoEvents));
+        }
+
+        // Only registered users can register webhooks
+        let domain = sanitize_domain(&state, &host)?;
+        let internal_error = |e: LnurlRepositoryError| {
+            error!("failed to execute query: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Value::String("internal server error".into())),
+            )
+        };
+        let user = state
+            .db
+            .get_user_by_pubkey(&domain, &pubkey.to_string())
+            .await
+            .map_err(internal_error)?;
+        if user.is_none() {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(Value::String("user not found".into())),
+            ));
+        }
+        let webhooks = state
+            .db
+            .list_webhooks(&domain, &pubkey.to_string())
+            .await
+            .map_err(internal_error)?;
+        if webhooks.len() >= MAX_WEBHOOKS_PER_USER {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(Value::String("too many webhooks".into())),
+            ));
+        }
+
+        let now = now_millis();
+        let webhook = Webhook {
+            id: hex::encode(rand::random::<[u8; 16]>()),
+            domain,
+            user_pubkey: pubkey.to_string(),
+            url: payload.url,
+            events,
+            secret: random_hex(),
+            created_at: now,
+            updated_at: now,
+        };
+        state
+            .db
+            .insert_webhook(&webhook)
+            .await
+            .map_err(internal_error)?;
+
+        debug!("created webhook {} for pubkey {}", webhook.id, pubkey);
+        Ok(Json(CreateWebhookResponse {
+            secret: webhook.secret.clone(),
+            webhook: webhook.into(),
+        }))
+    }
+
+    pub async fn list_webhooks(
+        Host(host): Host,
+        Path(pubkey): Path<String>,
+        Query(params): Query<ListWebhooksRequest>,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<Json<ListWebhooksResponse>, (StatusCode, Json<Value>)> {
+        let pubkey = validate(
+            &pubkey,
+            &params.signature,
+            &pubkey,
+            params.timestamp,
+            &state,
+        )
+        .await?;
+        let webhooks = state
+            .db
+            .list_webhooks(&sanitize_domain(&state, &host)?, &pubkey.to_string())
+            .await
+            .map_err(|e| {
+                error!("failed to execute query: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("internal server error".into())),
+                )
+            })?;
+        Ok(Json(ListWebhooksResponse {
+            webhooks: webhooks.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    pub async fn delete_webhook(
+        Host(host): Host,
+        Path((pubkey, id)): Path<(String, String)>,
+        Extension(state): Extension<State<DB>>,
+        Json(payload): Json<DeleteWebhookRequest>,
+    ) -> Result<(), (StatusCode, Json<Value>)> {
+        let pubkey = validate(&pubkey, &payload.signature, &id, payload.timestamp, &state).await?;
+        let deleted = state
+            .db
+            .delete_webhook(&sanitize_domain(&state, &host)?, &pubkey.to_string(), &id)
+            .await
+            .map_err(|e| {
+                error!("failed to execute query: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("internal server error".into())),
+                )
+            })?;
+        if !deleted {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(Value::String("webhook not found".into())),
+            ));
+        }
+        debug!("deleted webhook {} for pubkey {}", id, pubkey);
+        Ok(())
+    }
+
+    /// NIP-05 endpoint, mapping a username to the nostr identity linked to it.
+    pub async fn handle_nip05(
+        Host(host): Host,
+        Query(params): Query<Nip05Params>,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<Json<Nip05Response>, (StatusCode, Json<Value>)> {
+        let domain = sanitize_domain(&state, &host)?;
+        // Listing all names is not supported, clients always query a single name
+        let Some(name) = params.name else {
+            return Ok(Json(Nip05Response::default()));
+        };
+        let username = sanitize_username(&name);
+        let user = state
+            .db
+            .get_user_by_name(&domain, &username)
+            .await
+            .map_err(|e| {
+                error!("failed to execute query: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("internal server error".into())),
+                )
+            })?;
+
+        Ok(Json(
+            user.and_then(|user| Some(Nip05Response::new(user.name, user.nostr?)))
+                .unwrap_or_default(),
+        ))
+    }
+
+    /// Creates an LNURL-auth login challenge. The relying app shows the returned url to the user
+    /// and keeps the secret to exchange the signed challenge for a session.
+    pub async fn create_auth_challenge(
+        Host(host): Host,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<Json<AuthChallengeResponse>, (StatusCode, Json<Value>)> {
+        let domain = sanitize_domain(&state, &host)?;
+        let now = now();
+        if let Err(e) = state.db.delete_expired_auth(now).await {
+            // Not fatal, expired entries are cleaned up on the next challenge
+            warn!("failed to delete expired auth challenges: {}", e);
+        }
+
+        let secret = random_hex();
+        let challenge = AuthChallenge {
+            k1: random_hex(),
+            domain: domain.clone(),
+            secret_hash: hash_secret(&secret),
+            linking_key: None,
+            expires_at: now.saturating_add_unsigned(AUTH_CHALLENGE_TTL_SECS),
+            created_at: now,
+        };
+        state
+            .db
+            .insert_auth_challenge(&challenge)
+            .await
+            .map_err(|e| {
+                error!("failed to save auth challenge: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("internal server error".into())),
+                )
+            })?;
+
+        Ok(Json(AuthChallengeResponse {
+            url: format!(
+                "{}://{}/lnurl-auth/callback?tag=login&k1={}&action=login",
+                state.scheme, domain, challenge.k1
+            ),
+            k1: challenge.k1,
+            secret,
+            expires_at: challenge.expires_at,
+        }))
+    }
+
+    /// LUD-04 auth callback. The wallet signs the k1 challenge with its linking key.
+    pub async fn handle_auth_callback(
+        Host(host): Host,
+        Query(params): Query<LnurlAuthCallbackParams>,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+        let domain = sanitize_domain(&state, &host)?;
+        let (Some(k1), Some(sig), Some(key)) = (params.k1, params.sig, params.key) else {
+            trace!("missing auth callback parameters");
+            return Err(lnurl_error("missing k1, sig or key"));
+        };
+
+        let challenge = state
+            .db
+            .get_auth_challenge(&k1)
+            .await
+            .map_err(|e| {
+                error!("failed to execute query: {}", e);
+                lnurl_error("internal server error")
+            })?
+            .filter(|challenge| challenge.domain == domain && challenge.expires_at > now())
+            .ok_or_else(|| {
+                trace!("auth challenge not found or expired: {}", k1);
+                lnurl_error("unknown or expired k1")
+            })?;
+
+        if let Err(e) = verify_k1_signature(&challenge.k1, &sig, &key) {
+            trace!("invalid auth signature: {}", e);
+            return Err(lnurl_error("invalid signature"));
+        }
+
+        let signed = state
+            .db
+            .sign_auth_challenge(&challenge.k1, &key, now())
+            .await
+            .map_err(|e| {
+                error!("failed to sign auth challenge: {}", e);
+                lnurl_error("internal server error")
+            })?;
+        if !signed {
+            trace!("auth challenge already signed or expired: {}", k1);
+            return Err(lnurl_error("k1 already used"));
+        }
+
+        debug!("auth challenge {} signed by {}", challenge.k1, key);
+        Ok(Json(json!({ "status": "OK" })))
+    }
+
+    /// Exchanges a signed LNURL-auth challenge for a session token. The challenge can only be
+    /// exchanged once.
+    pub async fn create_auth_session(
+        Host(host): Host,
+        Extension(state): Extension<State<DB>>,
+        Json(payload): Json<CreateAuthSessionRequest>,
+    ) -> Result<Json<AuthSessionResponse>, (StatusCode, Json<Value>)> {
+        let domain = sanitize_domain(&state, &host)?;
+        let challenge = state
+            .db
+            .get_auth_challenge(&payload.k1)
+            .await
+            .map_err(|e| {
+                error!("failed to execute query: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("internal server error".into())),
+                )
+            })?
+            .filter(|challenge| {
+                challenge.domain == domain
+                    && challenge.expires_at > now()
+                    && challenge.secret_hash == hash_secret(&payload.secret)
+            })
+            .ok_or_else(|| {
+                trace!("auth challenge not found for k1: {}", payload.k1);
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(Value::String("auth challenge not found".into())),
+                )
+            })?;
+        let Some(linking_key) = challenge.linking_key else {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(Value::String("auth challenge not signed yet".into())),
+            ));
+        };
+
+        // Deleting the challenge makes sure it is exchanged for a single session
+        let deleted = state
+            .db
+            .delete_auth_challenge(&challenge.k1)
+            .await
+            .map_err(|e| {
+                error!("failed to delete auth challenge: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("internal server error".into())),
+                )
+            })?;
+        if !deleted {
+            trace!("auth challenge exchanged concurrently: {}", challenge.k1);
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(Value::String("auth challenge not found".into())),
+            ));
+        }
+
+        let token = random_hex();
+        let now = now();
+        let session = AuthSession {
+            token_hash: hash_secret(&token),
+            domain,
+            linking_key,
+            expires_at: now.saturating_add_unsigned(state.auth_session_ttl_secs),
+            created_at: now,
+        };
+        state.db.insert_auth_session(&session).await.map_err(|e| {
+            error!("failed to save auth session: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Value::String("internal server error".into())),
+            )
+        })?;
+
+        debug!("created auth session for {}", session.linking_key);
+        Ok(Json(AuthSessionResponse {
+            token: Some(token),
+            linking_key: session.linking_key,
+            expires_at: session.expires_at,
+        }))
+    }
+
+    /// Returns the linking key of the session identified by the bearer token.
+    pub async fn get_auth_session(
+        Host(host): Host,
+        headers: HeaderMap,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<Json<AuthSessionResponse>, (StatusCode, Json<Value>)> {
+        let session = authenticate_session(&state, &host, &headers).await?;
+        Ok(Json(AuthSessionResponse {
+            token: None,
+            linking_key: session.linking_key,
+            expires_at: session.expires_at,
+        }))
+    }
+
+    /// Ends the session identified by the bearer token.
+    pub async fn delete_auth_session(
+        Host(host): Host,
+        headers: HeaderMap,
+        Extension(state): Extension<State<DB>>,
+    ) -> Result<(), (StatusCode, Json<Value>)> {
+        let session = authenticate_session(&state, &host, &headers).await?;
+        state
+            .db
+            .delete_auth_session(&session.token_hash)
+            .await
+            .map_err(|e| {
+                error!("failed to delete auth session: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(Value::String("internal server error".into())),
+                )
+            })?;
+        Ok(())
+    }
+}
+
+fn validate_nostr_zap_request(
+    amount_msat: u64,
+    event: &Event,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    if event.kind != Kind::ZapRequest {
+        trace!("nostr event is incorrect kind");
+        return Err(lnurl_error("invalid nostr event"));
+    }
+
+    // 1. It MUST have a valid nostr signature
+    if event.verify().is_err() {
+        trace!("invalid nostr event, does not verify");
+        return Err(lnurl_error("invalid nostr event"));
+    }
+
+    // 2. It MUST have tags
+    if event.tags.is_empty() {
+        trace!("invalid nostr event, missing tags");
+        return Err(lnurl_error("invalid nostr event"));
+    }
+
+    // 3. It MUST have only one p tag
+    if event
+        .tags
+        .iter()
+        .filter_map(nostr::Tag::single_letter_tag)
+        .filter(|t| t.is_lowercase() && t.character == Alphabet::P)
+        .count()
+        != 1
+    {
+        trace!("invalid nostr event, missing or multiple 'p' tags");
+        return Err(lnurl_error("invalid nostr event"));
+    }
+
+    // 4. It MUST have 0 or 1 e tags
+    if event
+        .tags
+        .iter()
+        .filter_map(nostr::Tag::single_letter_tag)
+        .filter(|t| t.is_lowercase() && t.character == Alphabet::E)
+        .count()
+        > 1
+    {
+        trace!("invalid nostr event, multiple 'e' tags");
+        return Err(lnurl_error("invalid nostr event"));
+    }
+
+    // 5. There should be a relays tag with the relays to send the zap receipt to.
+    if !event
+        .tags
+        .iter()
+        .any(|t| matches!(t.as_standardized(), Some(TagStandard::Relays(_))))
+    {
+        trace!("invalid nostr event, missing relay tag");
+        return Err(lnurl_error("invalid nostr event"));
+    }
+
+    // 6. If there is an amount tag, it MUST be equal to the amount query parameter.
+    if let Some(millisats) = event.tags.iter().find_map(|t| {
+        if let Some(TagStandard::Amount { millisats, .. }) = t.as_standardized() {
+            Some(millisats)
+        } else {
+            None
+        }
+    }) && *millisats != amount_msat
+    {
+        trace!("invalid nostr event, amount does not match");
+        return Err(lnurl_error("invalid nostr event"));
+    }
+
+    // 7. If there is an 'a' tag, it MUST be a valid event coordinate
+    // NOTE: Assuming the tag is well-formed and contains the necessary fields, because it's standard.
+
+    // 8. There MUST be 0 or 1 P tags. If there is one, it MUST be equal to the zap receipt's pubkey.
+    // TODO: Implement this check.
+    Ok(())
+}
+
+fn validate_username(username: &str) -> Result<(), (StatusCode, Json<Value>)> {
+    if username.chars().take(65).count() > 64 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(Value::String("username too long".into())),
+        ));
     }
 
     let regex = Regex::new(USERNAME_VALIDATION_REGEX).map_err(|e| {
@@ -1094,12 +2022,182 @@ fn parse_pubkey(pubkey: &str) -> Result<PublicKey, (StatusCode, Json<Value>)> {
     Ok(pubkey)
 }
 
+fn validate_pay_settings<DB>(
+    settings: &LnurlPaySettings,
+    state: &State<DB>,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let bad_request = |message: &str| {
+        trace!("invalid pay settings: {}", message);
+        Err((StatusCode::BAD_REQUEST, Json(Value::String(message.into()))))
+    };
+
+    let min_sendable = settings.min_sendable.unwrap_or(state.min_sendable);
+    let max_sendable = settings.max_sendable.unwrap_or(state.max_sendable);
+    // Invoices are created for whole sat amounts only
+    if min_sendable < 1000 {
+        return bad_request("min sendable too low");
+    }
+    if min_sendable > max_sendable {
+        return bad_request("min sendable exceeds max sendable");
+    }
+    if i64::try_from(max_sendable).is_err() {
+        return bad_request("max sendable too high");
+    }
+
+    if settings
+        .comment_allowed
+        .is_some_and(|len| len > MAX_COMMENT_ALLOWED)
+    {
+        return bad_request("comment allowed too long");
+    }
+
+    if let Some(long_description) = &settings.long_description
+        && long_description
+            .chars()
+            .take(MAX_LONG_DESCRIPTION_LEN.saturating_add(1))
+            .count()
+            > MAX_LONG_DESCRIPTION_LEN
+    {
+        return bad_request("long description too long");
+    }
+
+    if let Some(image) = &settings.image {
+        let Ok(bytes) = BASE64_STANDARD.decode(image) else {
+            return bad_request("invalid image");
+        };
+        if !bytes.starts_with(&PNG_SIGNATURE) {
+            return bad_request("image must be a png");
+        }
+        if bytes.len() > MAX_IMAGE_BYTES {
+            return bad_request("image too large");
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_withdraw_link(
+    payload: &CreateWithdrawLinkRequest,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let bad_request = |message: &str| {
+        trace!("invalid withdraw link: {}", message);
+        Err((StatusCode::BAD_REQUEST, Json(Value::String(message.into()))))
+    };
+
+    if payload.description.chars().take(256).count() > 255 {
+        return bad_request("description too long");
+    }
+    // Invoices are paid for whole sat amounts only
+    if payload.min_withdrawable < 1000 {
+        return bad_request("min withdrawable too low");
+    }
+    if payload.min_withdrawable > payload.max_withdrawable {
+        return bad_request("min withdrawable exceeds max withdrawable");
+    }
+    if i64::try_from(payload.max_withdrawable).is_err() {
+        return bad_request("max withdrawable too high");
+    }
+    if payload
+        .max_uses
+        .is_some_and(|max_uses| max_uses == 0 || i32::try_from(max_uses).is_err())
+    {
+        return bad_request("invalid max uses");
+    }
+    if payload
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= now_u64() || i64::try_from(expires_at).is_err())
+    {
+        return bad_request("invalid expiry");
+    }
+
+    Ok(())
+}
+
+/// Authenticates the request by its bearer token, returning the unexpired session of the given
+/// domain the token belongs to.
+async fn authenticate_session<DB>(
+    state: &State<DB>,
+    host: &str,
+    headers: &HeaderMap,
+) -> Result<AuthSession, (StatusCode, Json<Value>)>
+where
+    DB: LnurlRepository,
+{
+    let domain = sanitize_domain(state, host)?;
+    let unauthorized = || {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(Value::String("invalid session".into())),
+        )
+    };
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .map(str::trim)
+        .ok_or_else(unauthorized)?;
+
+    state
+        .db
+        .get_auth_session(&hash_secret(token))
+        .await
+        .map_err(|e| {
+            error!("failed to execute query: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Value::String("internal server error".into())),
+            )
+        })?
+        .filter(|session| session.domain == domain && session.expires_at > now())
+        .ok_or_else(unauthorized)
+}
+
+/// Gets a withdraw link issued on the given domain.
+async fn get_withdraw_link<DB>(
+    state: &State<DB>,
+    host: &str,
+    id: &str,
+) -> Result<WithdrawLink, (StatusCode, Json<Value>)>
+where
+    DB: LnurlRepository,
+{
+    let domain = sanitize_domain(state, host)?;
+    let link = state.db.get_withdraw_link(id).await.map_err(|e| {
+        error!("failed to execute query: {}", e);
+        lnurl_error("internal server error")
+    })?;
+    match link {
+        Some(link) if link.domain == domain => Ok(link),
+        _ => Err((StatusCode::NOT_FOUND, Json(Value::String(String::new())))),
+    }
+}
+
+fn min_sendable<DB>(user: &User, state: &State<DB>) -> u64 {
+    user.pay_settings.min_sendable.unwrap_or(state.min_sendable)
+}
+
+fn max_sendable<DB>(user: &User, state: &State<DB>) -> u64 {
+    user.pay_settings.max_sendable.unwrap_or(state.max_sendable)
+}
+
+fn comment_allowed(user: &User) -> u16 {
+    user.pay_settings
+        .comment_allowed
+        .unwrap_or(MAX_COMMENT_ALLOWED)
+}
+
 fn get_metadata(domain: &str, user: &User) -> String {
-    json!(vec![
+    let mut metadata = vec![
         vec!["text/plain", &user.description],
         vec!["text/identifier", &format!("{}@{}", user.name, domain)],
-    ])
-    .to_string()
+    ];
+    if let Some(long_description) = &user.pay_settings.long_description {
+        metadata.push(vec!["text/long-desc", long_description]);
+    }
+    if let Some(image) = &user.pay_settings.image {
+        metadata.push(vec!["image/png;base64", image]);
+    }
+    json!(metadata).to_string()
 }
 
 fn lnurl_error(message: &str) -> (StatusCode, Json<Value>) {
@@ -1116,15 +2214,55 @@ fn lnurl_error(message: &str) -> (StatusCode, Json<Value>) {
     )
 }
 
+fn bip353_error(e: Bip353Error) -> (StatusCode, Json<Value>) {
+    trace!("invalid bip353 settings: {}", e);
+    (StatusCode::BAD_REQUEST, Json(Value::String(e.to_string())))
+}
+
+/// Whether the username is blocked, or reserved for someone else than the given pubkey.
+async fn is_username_reserved<DB>(
+    state: &State<DB>,
+    domain: &str,
+    username: &str,
+    pubkey: Option<&str>,
+) -> Result<bool, (StatusCode, Json<Value>)>
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    if state.blocked_usernames.contains(username) {
+        return Ok(true);
+    }
+
+    let reservation = state
+        .db
+        .get_username_reservation(domain, username)
+        .await
+        .map_err(|e| {
+            error!("failed to execute query: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Value::String("internal server error".into())),
+            )
+        })?;
+    Ok(reservation.is_some_and(|reservation| {
+        reservation.pubkey.is_none() || reservation.pubkey.as_deref() != pubkey
+    }))
+}
+
 fn sanitize_domain<DB>(
     state: &State<DB>,
     domain: &str,
 ) -> Result<String, (StatusCode, Json<Value>)> {
     let domain = domain.trim().to_lowercase();
+    let domains = state
+        .domains
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
     // If domains list is empty allow all domains (for testing)
-    if state.domains.is_empty() || state.domains.contains(&domain) {
+    if domains.is_empty() || domains.contains(&domain) {
         return Ok(domain);
     }
+    drop(domains);
     warn!("domain not allowed: {}", domain);
     Err((StatusCode::NOT_FOUND, Json(Value::String(String::new()))))
 }
@@ -1,12 +1,16 @@
-use lnurl_models::ListMetadataMetadata;
-use sqlx::{Row, SqlitePool};
+use lnurl_models::{ListMetadataMetadata, LnurlPaySettings, NostrIdentity};
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
 
+use crate::lnurl_auth::{AuthChallenge, AuthSession};
+use crate::payer_data::PayerDataChallenge;
 use crate::repository::{Invoice, LnurlSenderComment, NewlyPaid};
 use crate::zap::Zap;
 use crate::{
     repository::LnurlRepositoryError,
     time::{now, now_millis},
-    user::User,
+    user::{User, UsernameReservation},
+    webhook::{PendingWebhookDelivery, Webhook, WebhookDelivery},
+    withdraw::{WithdrawLink, WithdrawRequest, WithdrawRequestStatus},
 };
 
 #[derive(Clone)]
@@ -38,6 +42,8 @@ impl crate::repository::LnurlRepository for LnurlRepository {
     ) -> Result<Option<User>, LnurlRepositoryError> {
         let maybe_user = sqlx::query(
             "SELECT pubkey, name, description, lnurl_private_mode_enabled
+            , min_sendable, max_sendable, comment_allowed, image, long_description
+            , nostr_pubkey, nostr_relays, nostr_proof, bip353_enabled, bolt12_offer
             FROM users
             WHERE domain = $1 AND name = $2",
         )
@@ -52,6 +58,10 @@ impl crate::repository::LnurlRepository for LnurlRepository {
                 name: row.try_get(1)?,
                 description: row.try_get(2)?,
                 lnurl_private_mode_enabled: row.try_get::<i32, _>(3)? != 0,
+                pay_settings: pay_settings_from_row(&row)?,
+                nostr: nostr_from_row(&row)?,
+                bip353_enabled: row.try_get::<i32, _>(12)? != 0,
+                bolt12_offer: row.try_get(13)?,
             })
         })
         .transpose()?;
@@ -65,6 +75,8 @@ impl crate::repository::LnurlRepository for LnurlRepository {
     ) -> Result<Option<User>, LnurlRepositoryError> {
         let maybe_user = sqlx::query(
             "SELECT pubkey, name, description, lnurl_private_mode_enabled
+                , min_sendable, max_sendable, comment_allowed, image, long_description
+                , nostr_pubkey, nostr_relays, nostr_proof, bip353_enabled, bolt12_offer
                 FROM users
                 WHERE domain = $1 AND pubkey = $2",
         )
@@ -79,6 +91,10 @@ impl crate::repository::LnurlRepository for LnurlRepository {
                 name: row.try_get(1)?,
                 description: row.try_get(2)?,
                 lnurl_private_mode_enabled: row.try_get::<i32, _>(3)? != 0,
+                pay_settings: pay_settings_from_row(&row)?,
+                nostr: nostr_from_row(&row)?,
+                bip353_enabled: row.try_get::<i32, _>(12)? != 0,
+                bolt12_offer: row.try_get(13)?,
             })
         })
         .transpose()?;
@@ -87,8 +103,10 @@ impl crate::repository::LnurlRepository for LnurlRepository {
 
     async fn upsert_user(&self, user: &User) -> Result<(), LnurlRepositoryError> {
         sqlx::query(
-            "REPLACE INTO users (domain, pubkey, name, description, lnurl_private_mode_enabled, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6)",
+            "REPLACE INTO users (domain, pubkey, name, description, lnurl_private_mode_enabled, updated_at
+            , min_sendable, max_sendable, comment_allowed, image, long_description
+            , nostr_pubkey, nostr_relays, nostr_proof, bip353_enabled, bolt12_offer)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)",
         )
         .bind(&user.domain)
         .bind(&user.pubkey)
@@ -96,6 +114,22 @@ impl crate::repository::LnurlRepository for LnurlRepository {
         .bind(&user.description)
         .bind(i32::from(user.lnurl_private_mode_enabled))
         .bind(now())
+        .bind(sendable_to_i64(user.pay_settings.min_sendable)?)
+        .bind(sendable_to_i64(user.pay_settings.max_sendable)?)
+        .bind(user.pay_settings.comment_allowed.map(i32::from))
+        .bind(&user.pay_settings.image)
+        .bind(&user.pay_settings.long_description)
+        .bind(user.nostr.as_ref().map(|nostr| &nostr.pubkey))
+        .bind(
+            user.nostr
+                .as_ref()
+                .map(|nostr| serde_json::to_string(&nostr.relays))
+                .transpose()
+                .map_err(|e| LnurlRepositoryError::General(e.into()))?,
+        )
+        .bind(user.nostr.as_ref().map(|nostr| &nostr.proof))
+        .bind(i32::from(user.bip353_enabled))
+        .bind(&user.bolt12_offer)
         .execute(&self.pool)
         .await?;
         Ok(())
@@ -214,6 +248,7 @@ impl crate::repository::LnurlRepository for LnurlRepository {
              ,      z.zap_event
              ,      MAX(COALESCE(z.updated_at, 0), COALESCE(sc.updated_at, 0), COALESCE(i.updated_at, 0)) AS updated_at
              ,      i.preimage
+             ,      i.payer_data
              FROM (
                  SELECT payment_hash FROM invoices WHERE user_pubkey = $1
                  UNION
@@ -244,6 +279,7 @@ impl crate::repository::LnurlRepository for LnurlRepository {
                     nostr_zap_receipt: row.try_get(3)?,
                     updated_at: row.try_get(4)?,
                     preimage: row.try_get(5)?,
+                    payer_data: row.try_get(6)?,
                 })
             })
             .collect::<Result<Vec<_>, sqlx::Error>>()?;
@@ -277,14 +313,16 @@ impl crate::repository::LnurlRepository for LnurlRepository {
 
     async fn upsert_invoice(&self, invoice: &Invoice) -> Result<(), LnurlRepositoryError> {
         sqlx::query(
-            "INSERT INTO invoices (payment_hash, user_pubkey, invoice, preimage, invoice_expiry, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "INSERT INTO invoices (payment_hash, user_pubkey, invoice, preimage, invoice_expiry, created_at, updated_at, payer_data, domain)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             ON CONFLICT(payment_hash) DO UPDATE SET
                 user_pubkey = excluded.user_pubkey,
                 invoice = excluded.invoice,
                 preimage = excluded.preimage,
                 invoice_expiry = excluded.invoice_expiry,
-                updated_at = excluded.updated_at",
+                updated_at = excluded.updated_at,
+                payer_data = excluded.payer_data,
+                domain = excluded.domain",
         )
         .bind(&invoice.payment_hash)
         .bind(&invoice.user_pubkey)
@@ -293,17 +331,69 @@ impl crate::repository::LnurlRepository for LnurlRepository {
         .bind(invoice.invoice_expiry)
         .bind(invoice.created_at)
         .bind(invoice.updated_at)
+        .bind(&invoice.payer_data)
+        .bind(&invoice.domain)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
+    async fn mark_invoice_paid(
+        &self,
+        payment_hash: &str,
+        preimage: &str,
+        newly_paid: &NewlyPaid,
+        deliveries: &[WebhookDelivery],
+    ) -> Result<bool, LnurlRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            "UPDATE invoices
+             SET preimage = $2, updated_at = $3
+             WHERE payment_hash = $1 AND preimage IS NULL",
+        )
+        .bind(payment_hash)
+        .bind(preimage)
+        .bind(newly_paid.created_at)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        sqlx::query(
+            "INSERT INTO newly_paid (payment_hash, created_at, retry_count, next_retry_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT(payment_hash) DO NOTHING",
+        )
+        .bind(&newly_paid.payment_hash)
+        .bind(newly_paid.created_at)
+        .bind(newly_paid.retry_count)
+        .bind(newly_paid.next_retry_at)
+        .execute(&mut *tx)
+        .await?;
+        for delivery in deliveries {
+            sqlx::query(
+                "INSERT INTO webhook_deliveries (id, webhook_id, payload, created_at, retry_count, next_retry_at)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(&delivery.id)
+            .bind(&delivery.webhook_id)
+            .bind(&delivery.payload)
+            .bind(delivery.created_at)
+            .bind(delivery.retry_count)
+            .bind(delivery.next_retry_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(true)
+    }
+
     async fn get_invoice_by_payment_hash(
         &self,
         payment_hash: &str,
     ) -> Result<Option<Invoice>, LnurlRepositoryError> {
         let maybe_invoice = sqlx::query(
-            "SELECT payment_hash, user_pubkey, invoice, preimage, invoice_expiry, created_at, updated_at
+            "SELECT payment_hash, user_pubkey, invoice, preimage, invoice_expiry, created_at, updated_at, payer_data, domain
              FROM invoices
              WHERE payment_hash = $1",
         )
@@ -319,6 +409,8 @@ impl crate::repository::LnurlRepository for LnurlRepository {
                 invoice_expiry: row.try_get(4)?,
                 created_at: row.try_get(5)?,
                 updated_at: row.try_get(6)?,
+                payer_data: row.try_get(7)?,
+                domain: row.try_get(8)?,
             })
         })
         .transpose()?;
@@ -401,6 +493,32 @@ impl crate::repository::LnurlRepository for LnurlRepository {
         Ok(newly_paid)
     }
 
+    async fn claim_newly_paid(
+        &self,
+        payment_hash: &str,
+        now: i64,
+        claimed_until: i64,
+    ) -> Result<bool, LnurlRepositoryError> {
+        let result = sqlx::query(
+            "UPDATE newly_paid
+             SET next_retry_at = $3
+             WHERE payment_hash = $1 AND next_retry_at <= $2",
+        )
+        .bind(payment_hash)
+        .bind(now)
+        .bind(claimed_until)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn count_newly_paid(&self) -> Result<i64, LnurlRepositoryError> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM newly_paid")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
     async fn update_newly_paid_retry(
         &self,
         payment_hash: &str,
@@ -427,4 +545,773 @@ impl crate::repository::LnurlRepository for LnurlRepository {
             .await?;
         Ok(())
     }
+
+    async fn insert_withdraw_link(&self, link: &WithdrawLink) -> Result<(), LnurlRepositoryError> {
+        sqlx::query(
+            "INSERT INTO withdraw_links (id, domain, user_pubkey, description, min_withdrawable, max_withdrawable, max_uses, uses, expires_at, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+        )
+        .bind(&link.id)
+        .bind(&link.domain)
+        .bind(&link.user_pubkey)
+        .bind(&link.description)
+        .bind(to_i64(link.min_withdrawable)?)
+        .bind(to_i64(link.max_withdrawable)?)
+        .bind(link.max_uses.map(to_i32).transpose()?)
+        .bind(to_i32(link.uses)?)
+        .bind(link.expires_at.map(to_i64).transpose()?)
+        .bind(link.created_at)
+        .bind(link.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_withdraw_link(
+        &self,
+        id: &str,
+    ) -> Result<Option<WithdrawLink>, LnurlRepositoryError> {
+        let maybe_link = sqlx::query(
+            "SELECT id, domain, user_pubkey, description, min_withdrawable, max_withdrawable, max_uses, uses, expires_at, created_at, updated_at
+             FROM withdraw_links
+             WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(|row| withdraw_link_from_row(&row))
+        .transpose()?;
+        Ok(maybe_link)
+    }
+
+    async fn list_withdraw_links(
+        &self,
+        domain: &str,
+        user_pubkey: &str,
+    ) -> Result<Vec<WithdrawLink>, LnurlRepositoryError> {
+        let rows = sqlx::query(
+            "SELECT id, domain, user_pubkey, description, min_withdrawable, max_withdrawable, max_uses, uses, expires_at, created_at, updated_at
+             FROM withdraw_links
+             WHERE domain = $1 AND user_pubkey = $2
+             ORDER BY created_at DESC",
+        )
+        .bind(domain)
+        .bind(user_pubkey)
+        .fetch_all(&self.pool)
+        .await?;
+        let links = rows
+            .iter()
+            .map(withdraw_link_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(links)
+    }
+
+    async fn delete_withdraw_link(
+        &self,
+        domain: &str,
+        user_pubkey: &str,
+        id: &str,
+    ) -> Result<bool, LnurlRepositoryError> {
+        let result = sqlx::query(
+            "DELETE FROM withdraw_links WHERE domain = $1 AND user_pubkey = $2 AND id = $3",
+        )
+        .bind(domain)
+        .bind(user_pubkey)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn claim_withdraw_link_use(
+        &self,
+        id: &str,
+        now: u64,
+    ) -> Result<bool, LnurlRepositoryError> {
+        let result = sqlx::query(
+            "UPDATE withdraw_links
+             SET uses = uses + 1, updated_at = $3
+             WHERE id = $1
+               AND (max_uses IS NULL OR uses < max_uses)
+               AND (expires_at IS NULL OR expires_at > $2)",
+        )
+        .bind(id)
+        .bind(to_i64(now)?)
+        .bind(now_millis())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn release_withdraw_link_use(&self, id: &str) -> Result<(), LnurlRepositoryError> {
+        sqlx::query(
+            "UPDATE withdraw_links
+             SET uses = uses - 1, updated_at = $2
+             WHERE id = $1 AND uses > 0",
+        )
+        .bind(id)
+        .bind(now_millis())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn insert_withdraw_request(
+        &self,
+        request: &WithdrawRequest,
+    ) -> Result<(), LnurlRepositoryError> {
+        sqlx::query(
+            "INSERT INTO withdraw_requests (payment_hash, link_id, user_pubkey, invoice, amount_msat, status, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(&request.payment_hash)
+        .bind(&request.link_id)
+        .bind(&request.user_pubkey)
+        .bind(&request.invoice)
+        .bind(to_i64(request.amount_msat)?)
+        .bind(request.status.as_str())
+        .bind(request.created_at)
+        .bind(request.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_withdraw_request(
+        &self,
+        payment_hash: &str,
+    ) -> Result<Option<WithdrawRequest>, LnurlRepositoryError> {
+        let maybe_request = sqlx::query(
+            "SELECT payment_hash, link_id, user_pubkey, invoice, amount_msat, status, created_at, updated_at
+             FROM withdraw_requests
+             WHERE payment_hash = $1",
+        )
+        .bind(payment_hash)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(|row| withdraw_request_from_row(&row))
+        .transpose()?;
+        Ok(maybe_request)
+    }
+
+    async fn get_pending_withdraw_requests(
+        &self,
+        user_pubkey: &str,
+    ) -> Result<Vec<WithdrawRequest>, LnurlRepositoryError> {
+        let rows = sqlx::query(
+            "SELECT payment_hash, link_id, user_pubkey, invoice, amount_msat, status, created_at, updated_at
+             FROM withdraw_requests
+             WHERE user_pubkey = $1 AND status = $2
+             ORDER BY created_at ASC",
+        )
+        .bind(user_pubkey)
+        .bind(WithdrawRequestStatus::Pending.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+        let requests = rows
+            .iter()
+            .map(withdraw_request_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(requests)
+    }
+
+    async fn complete_pending_withdraw_request(
+        &self,
+        payment_hash: &str,
+        status: WithdrawRequestStatus,
+        updated_at: i64,
+    ) -> Result<bool, LnurlRepositoryError> {
+        let result = sqlx::query(
+            "UPDATE withdraw_requests
+             SET status = $2, updated_at = $3
+             WHERE payment_hash = $1 AND status = $4",
+        )
+        .bind(payment_hash)
+        .bind(status.as_str())
+        .bind(updated_at)
+        .bind(WithdrawRequestStatus::Pending.as_str())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn insert_auth_challenge(
+        &self,
+        challenge: &AuthChallenge,
+    ) -> Result<(), LnurlRepositoryError> {
+        sqlx::query(
+            "INSERT INTO auth_challenges (k1, domain, secret_hash, linking_key, expires_at, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(&challenge.k1)
+        .bind(&challenge.domain)
+        .bind(&challenge.secret_hash)
+        .bind(&challenge.linking_key)
+        .bind(challenge.expires_at)
+        .bind(challenge.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_auth_challenge(
+        &self,
+        k1: &str,
+    ) -> Result<Option<AuthChallenge>, LnurlRepositoryError> {
+        let maybe_challenge = sqlx::query(
+            "SELECT k1, domain, secret_hash, linking_key, expires_at, created_at
+             FROM auth_challenges
+             WHERE k1 = $1",
+        )
+        .bind(k1)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(|row| {
+            Ok::<_, sqlx::Error>(AuthChallenge {
+                k1: row.try_get(0)?,
+                domain: row.try_get(1)?,
+                secret_hash: row.try_get(2)?,
+                linking_key: row.try_get(3)?,
+                expires_at: row.try_get(4)?,
+                created_at: row.try_get(5)?,
+            })
+        })
+        .transpose()?;
+        Ok(maybe_challenge)
+    }
+
+    async fn sign_auth_challenge(
+        &self,
+        k1: &str,
+        linking_key: &str,
+        now: i64,
+    ) -> Result<bool, LnurlRepositoryError> {
+        let result = sqlx::query(
+            "UPDATE auth_challenges
+             SET linking_key = $2
+             WHERE k1 = $1 AND linking_key IS NULL AND expires_at > $3",
+        )
+        .bind(k1)
+        .bind(linking_key)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_auth_challenge(&self, k1: &str) -> Result<bool, LnurlRepositoryError> {
+        let result = sqlx::query("DELETE FROM auth_challenges WHERE k1 = $1")
+            .bind(k1)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn use_payer_data_challenge(
+        &self,
+        challenge: &PayerDataChallenge,
+    ) -> Result<bool, LnurlRepositoryError> {
+        let result = sqlx::query(
+            "INSERT INTO payer_data_challenges (k1, domain, username, expires_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT(k1) DO NOTHING",
+        )
+        .bind(&challenge.k1)
+        .bind(&challenge.domain)
+        .bind(&challenge.username)
+        .bind(challenge.expires_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_expired_payer_data_challenges(
+        &self,
+        now: i64,
+    ) -> Result<(), LnurlRepositoryError> {
+        sqlx::query("DELETE FROM payer_data_challenges WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn insert_auth_session(&self, session: &AuthSession) -> Result<(), LnurlRepositoryError> {
+        sqlx::query(
+            "INSERT INTO auth_sessions (token_hash, domain, linking_key, expires_at, created_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&session.token_hash)
+        .bind(&session.domain)
+        .bind(&session.linking_key)
+        .bind(session.expires_at)
+        .bind(session.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_auth_session(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<AuthSession>, LnurlRepositoryError> {
+        let maybe_session = sqlx::query(
+            "SELECT token_hash, domain, linking_key, expires_at, created_at
+             FROM auth_sessions
+             WHERE token_hash = $1",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(|row| {
+            Ok::<_, sqlx::Error>(AuthSession {
+                token_hash: row.try_get(0)?,
+                domain: row.try_get(1)?,
+                linking_key: row.try_get(2)?,
+                expires_at: row.try_get(3)?,
+                created_at: row.try_get(4)?,
+            })
+        })
+        .transpose()?;
+        Ok(maybe_session)
+    }
+
+    async fn delete_auth_session(&self, token_hash: &str) -> Result<(), LnurlRepositoryError> {
+        sqlx::query("DELETE FROM auth_sessions WHERE token_hash = $1")
+            .bind(token_hash)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_expired_auth(&self, now: i64) -> Result<(), LnurlRepositoryError> {
+        sqlx::query("DELETE FROM auth_challenges WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM auth_sessions WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn insert_webhook(&self, webhook: &Webhook) -> Result<(), LnurlRepositoryError> {
+        sqlx::query(
+            "INSERT INTO webhooks (id, domain, user_pubkey, url, events, secret, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(&webhook.id)
+        .bind(&webhook.domain)
+        .bind(&webhook.user_pubkey)
+        .bind(&webhook.url)
+        .bind(
+            serde_json::to_string(&webhook.events)
+                .map_err(|e| LnurlRepositoryError::General(e.into()))?,
+        )
+        .bind(&webhook.secret)
+        .bind(webhook.created_at)
+        .bind(webhook.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_webhooks(
+        &self,
+        domain: &str,
+        user_pubkey: &str,
+    ) -> Result<Vec<Webhook>, LnurlRepositoryError> {
+        let rows = sqlx::query(
+            "SELECT id, domain, user_pubkey, url, events, secret, created_at, updated_at
+             FROM webhooks
+             WHERE domain = $1 AND user_pubkey = $2
+             ORDER BY created_at DESC",
+        )
+        .bind(domain)
+        .bind(user_pubkey)
+        .fetch_all(&self.pool)
+        .await?;
+        let webhooks = rows
+            .iter()
+            .map(webhook_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(webhooks)
+    }
+
+    async fn delete_webhook(
+        &self,
+        domain: &str,
+        user_pubkey: &str,
+        id: &str,
+    ) -> Result<bool, LnurlRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        let result =
+            sqlx::query("DELETE FROM webhooks WHERE domain = $1 AND user_pubkey = $2 AND id = $3")
+                .bind(domain)
+                .bind(user_pubkey)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        sqlx::query("DELETE FROM webhook_deliveries WHERE webhook_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn insert_webhook_delivery(
+        &self,
+        delivery: &WebhookDelivery,
+    ) -> Result<(), LnurlRepositoryError> {
+        sqlx::query(
+            "INSERT INTO webhook_deliveries (id, webhook_id, payload, created_at, retry_count, next_retry_at)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(&delivery.id)
+        .bind(&delivery.webhook_id)
+        .bind(&delivery.payload)
+        .bind(delivery.created_at)
+        .bind(delivery.retry_count)
+        .bind(delivery.next_retry_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_pending_webhook_deliveries(
+        &self,
+    ) -> Result<Vec<PendingWebhookDelivery>, LnurlRepositoryError> {
+        let now = now_millis();
+        let rows = sqlx::query(
+            "SELECT d.id, d.webhook_id, d.payload, d.created_at, d.retry_count, d.next_retry_at
+             , w.url, w.secret
+             FROM webhook_deliveries d
+             JOIN webhooks w ON w.id = d.webhook_id
+             WHERE d.next_retry_at <= $1
+             ORDER BY d.next_retry_at ASC",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+        let deliveries = rows
+            .into_iter()
+            .map(|row| {
+                Ok::<_, sqlx::Error>(PendingWebhookDelivery {
+                    delivery: WebhookDelivery {
+                        id: row.try_get(0)?,
+                        webhook_id: row.try_get(1)?,
+                        payload: row.try_get(2)?,
+                        created_at: row.try_get(3)?,
+                        retry_count: row.try_get(4)?,
+                        next_retry_at: row.try_get(5)?,
+                    },
+                    url: row.try_get(6)?,
+                    secret: row.try_get(7)?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(deliveries)
+    }
+
+    async fn update_webhook_delivery_retry(
+        &self,
+        id: &str,
+        retry_count: i32,
+        next_retry_at: i64,
+    ) -> Result<(), LnurlRepositoryError> {
+        sqlx::query(
+            "UPDATE webhook_deliveries
+             SET retry_count = $2, next_retry_at = $3
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(retry_count)
+        .bind(next_retry_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_webhook_delivery(&self, id: &str) -> Result<(), LnurlRepositoryError> {
+        sqlx::query("DELETE FROM webhook_deliveries WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn claim_webhook_delivery(
+        &self,
+        id: &str,
+        now: i64,
+        claimed_until: i64,
+    ) -> Result<bool, LnurlRepositoryError> {
+        let result = sqlx::query(
+            "UPDATE webhook_deliveries
+             SET next_retry_at = $3
+             WHERE id = $1 AND next_retry_at <= $2",
+        )
+        .bind(id)
+        .bind(now)
+        .bind(claimed_until)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn increment_rate_limit(
+        &self,
+        key: &str,
+        window_start: i64,
+        expires_at: i64,
+    ) -> Result<i64, LnurlRepositoryError> {
+        let hits: i64 = sqlx::query_scalar(
+            "INSERT INTO rate_limits (limit_key, window_start, hits, expires_at)
+             VALUES ($1, $2, 1, $3)
+             ON CONFLICT(limit_key, window_start) DO UPDATE SET hits = rate_limits.hits + 1
+             RETURNING hits",
+        )
+        .bind(key)
+        .bind(window_start)
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(hits)
+    }
+
+    async fn delete_expired_rate_limits(&self, now: i64) -> Result<(), LnurlRepositoryError> {
+        sqlx::query("DELETE FROM rate_limits WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn try_acquire_lease(
+        &self,
+        name: &str,
+        holder: &str,
+        now: i64,
+        expires_at: i64,
+    ) -> Result<bool, LnurlRepositoryError> {
+        let result = sqlx::query(
+            "INSERT INTO leases (name, holder, expires_at)
+             VALUES ($1, $2, $4)
+             ON CONFLICT(name) DO UPDATE SET holder = excluded.holder, expires_at = excluded.expires_at
+             WHERE leases.holder = excluded.holder OR leases.expires_at <= $3",
+        )
+        .bind(name)
+        .bind(holder)
+        .bind(now)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn release_lease(&self, name: &str, holder: &str) -> Result<(), LnurlRepositoryError> {
+        sqlx::query("DELETE FROM leases WHERE name = $1 AND holder = $2")
+            .bind(name)
+            .bind(holder)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn upsert_username_reservation(
+        &self,
+        reservation: &UsernameReservation,
+    ) -> Result<(), LnurlRepositoryError> {
+        sqlx::query(
+            "INSERT INTO username_reservations (domain, name, pubkey, created_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT(domain, name) DO UPDATE SET pubkey = excluded.pubkey",
+        )
+        .bind(&reservation.domain)
+        .bind(&reservation.name)
+        .bind(&reservation.pubkey)
+        .bind(reservation.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_username_reservation(
+        &self,
+        domain: &str,
+        name: &str,
+    ) -> Result<Option<UsernameReservation>, LnurlRepositoryError> {
+        let reservation = sqlx::query(
+            "SELECT domain, name, pubkey, created_at
+             FROM username_reservations
+             WHERE domain = $1 AND name = $2",
+        )
+        .bind(domain)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(|row| username_reservation_from_row(&row))
+        .transpose()?;
+        Ok(reservation)
+    }
+
+    async fn list_username_reservations(
+        &self,
+        domain: &str,
+    ) -> Result<Vec<UsernameReservation>, LnurlRepositoryError> {
+        let rows = sqlx::query(
+            "SELECT domain, name, pubkey, created_at
+             FROM username_reservations
+             WHERE domain = $1
+             ORDER BY name ASC",
+        )
+        .bind(domain)
+        .fetch_all(&self.pool)
+        .await?;
+        let reservations = rows
+            .iter()
+            .map(username_reservation_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(reservations)
+    }
+
+    async fn delete_username_reservation(
+        &self,
+        domain: &str,
+        name: &str,
+    ) -> Result<bool, LnurlRepositoryError> {
+        let result =
+            sqlx::query("DELETE FROM username_reservations WHERE domain = $1 AND name = $2")
+                .bind(domain)
+                .bind(name)
+                .execute(&self.pool)
+                .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Reads the pay settings from columns 4 to 8 of a user row.
+fn pay_settings_from_row(row: &SqliteRow) -> Result<LnurlPaySettings, sqlx::Error> {
+    let decode_err = |e: std::num::TryFromIntError| sqlx::Error::Decode(Box::new(e));
+    Ok(LnurlPaySettings {
+        min_sendable: row
+            .try_get::<Option<i64>, _>(4)?
+            .map(u64::try_from)
+            .transpose()
+            .map_err(decode_err)?,
+        max_sendable: row
+            .try_get::<Option<i64>, _>(5)?
+            .map(u64::try_from)
+            .transpose()
+            .map_err(decode_err)?,
+        comment_allowed: row
+            .try_get::<Option<i32>, _>(6)?
+            .map(u16::try_from)
+            .transpose()
+            .map_err(decode_err)?,
+        image: row.try_get(7)?,
+        long_description: row.try_get(8)?,
+    })
+}
+
+/// Reads the nostr identity from columns 9 to 11 of a user row.
+fn nostr_from_row(row: &SqliteRow) -> Result<Option<NostrIdentity>, sqlx::Error> {
+    let Some(pubkey) = row.try_get::<Option<String>, _>(9)? else {
+        return Ok(None);
+    };
+    let relays = row
+        .try_get::<Option<String>, _>(10)?
+        .map(|relays| serde_json::from_str(&relays))
+        .transpose()
+        .map_err(|e| sqlx::Error::Decode(Box::new(e)))?
+        .unwrap_or_default();
+    Ok(Some(NostrIdentity {
+        pubkey,
+        relays,
+        proof: row.try_get(11)?,
+    }))
+}
+
+fn sendable_to_i64(sendable: Option<u64>) -> Result<Option<i64>, LnurlRepositoryError> {
+    sendable
+        .map(i64::try_from)
+        .transpose()
+        .map_err(|e| LnurlRepositoryError::General(e.into()))
+}
+
+fn withdraw_link_from_row(row: &SqliteRow) -> Result<WithdrawLink, sqlx::Error> {
+    let decode_err = |e: std::num::TryFromIntError| sqlx::Error::Decode(Box::new(e));
+    Ok(WithdrawLink {
+        id: row.try_get(0)?,
+        domain: row.try_get(1)?,
+        user_pubkey: row.try_get(2)?,
+        description: row.try_get(3)?,
+        min_withdrawable: u64::try_from(row.try_get::<i64, _>(4)?).map_err(decode_err)?,
+        max_withdrawable: u64::try_from(row.try_get::<i64, _>(5)?).map_err(decode_err)?,
+        max_uses: row
+            .try_get::<Option<i32>, _>(6)?
+            .map(u32::try_from)
+            .transpose()
+            .map_err(decode_err)?,
+        uses: u32::try_from(row.try_get::<i32, _>(7)?).map_err(decode_err)?,
+        expires_at: row
+            .try_get::<Option<i64>, _>(8)?
+            .map(u64::try_from)
+            .transpose()
+            .map_err(decode_err)?,
+        created_at: row.try_get(9)?,
+        updated_at: row.try_get(10)?,
+    })
+}
+
+fn withdraw_request_from_row(row: &SqliteRow) -> Result<WithdrawRequest, sqlx::Error> {
+    let status: String = row.try_get(5)?;
+    Ok(WithdrawRequest {
+        payment_hash: row.try_get(0)?,
+        link_id: row.try_get(1)?,
+        user_pubkey: row.try_get(2)?,
+        invoice: row.try_get(3)?,
+        amount_msat: u64::try_from(row.try_get::<i64, _>(4)?)
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+        status: WithdrawRequestStatus::parse(&status)
+            .ok_or_else(|| sqlx::Error::Decode(format!("invalid status: {status}").into()))?,
+        created_at: row.try_get(6)?,
+        updated_at: row.try_get(7)?,
+    })
+}
+
+fn webhook_from_row(row: &SqliteRow) -> Result<Webhook, sqlx::Error> {
+    let events: String = row.try_get(4)?;
+    Ok(Webhook {
+        id: row.try_get(0)?,
+        domain: row.try_get(1)?,
+        user_pubkey: row.try_get(2)?,
+        url: row.try_get(3)?,
+        events: serde_json::from_str(&events).map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+        secret: row.try_get(5)?,
+        created_at: row.try_get(6)?,
+        updated_at: row.try_get(7)?,
+    })
+}
+
+fn username_reservation_from_row(row: &SqliteRow) -> Result<UsernameReservation, sqlx::Error> {
+    Ok(UsernameReservation {
+        domain: row.try_get(0)?,
+        name: row.try_get(1)?,
+        pubkey: row.try_get(2)?,
+        created_at: row.try_get(3)?,
+    })
+}
+
+fn to_i64(value: u64) -> Result<i64, LnurlRepositoryError> {
+    i64::try_from(value).map_err(|e| LnurlRepositoryError::General(e.into()))
+}
+
+fn to_i32(value: u32) -> Result<i32, LnurlRepositoryError> {
+    i32::try_from(value).map_err(|e| LnurlRepositoryError::General(e.into()))
 }
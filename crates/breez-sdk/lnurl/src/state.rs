@@ -1,19 +1,27 @@
+use crate::dns::DnsProvider;
+use crate::rate_limit::RateLimits;
 use spark::operator::OperatorConfig;
 use spark::operator::rpc::ConnectionManager;
 use spark::session_manager::InMemorySessionManager;
 use spark::ssp::ServiceProvider;
-use spark_wallet::DefaultSigner;
-use std::{collections::HashSet, sync::Arc};
+use spark_wallet::{DefaultSigner, Network};
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
 use tokio::sync::{Mutex, watch};
 
 pub struct State<DB> {
     pub db: DB,
     pub wallet: Arc<spark_wallet::SparkWallet>,
+    pub network: Network,
     pub scheme: String,
     pub min_sendable: u64,
     pub max_sendable: u64,
     pub include_spark_address: bool,
-    pub domains: HashSet<String>,
+    /// Allowed domains. Domains can be added at runtime through the admin api, and are reloaded
+    /// from the database periodically.
+    pub domains: Arc<RwLock<HashSet<String>>>,
     pub nostr_keys: Option<nostr::Keys>,
     pub ca_cert: Option<Vec<u8>>,
     pub connection_manager: Arc<dyn ConnectionManager>,
@@ -22,7 +30,23 @@ pub struct State<DB> {
     pub session_manager: Arc<InMemorySessionManager>,
     pub service_provider: Arc<ServiceProvider>,
     pub subscribed_keys: Arc<Mutex<HashSet<String>>>,
-    pub invoice_paid_trigger: watch::Sender<()>,
+    pub background_trigger: watch::Sender<()>,
+    /// Whether this instance is the leader, running the subscriptions and background jobs
+    pub leader: watch::Receiver<bool>,
+    pub auth_session_ttl_secs: u64,
+    /// Secret authenticating the LUD-18 payer data auth challenges
+    pub payer_data_secret: Arc<Vec<u8>>,
+    pub admin_api_key: Option<String>,
+    pub rate_limits: RateLimits,
+    /// Whether the server runs behind a proxy setting the `X-Forwarded-For` header
+    pub trust_forwarded_for: bool,
+    /// Usernames that can't be registered on any domain
+    pub blocked_usernames: Arc<HashSet<String>>,
+    /// Publishes BIP-353 payment instructions. If not set, BIP-353 is disabled.
+    pub dns_provider: Option<Arc<dyn DnsProvider>>,
+    /// DNS zones the BIP-353 records are published in. Domains not in any of them are their own
+    /// zone.
+    pub dns_zones: Arc<Vec<String>>,
 }
 
 impl<DB> Clone for State<DB>
@@ -33,11 +57,12 @@ where
         Self {
             db: self.db.clone(),
             wallet: Arc::clone(&self.wallet),
+            network: self.network,
             scheme: self.scheme.clone(),
             min_sendable: self.min_sendable,
             max_sendable: self.max_sendable,
             include_spark_address: self.include_spark_address,
-            domains: self.domains.clone(),
+            domains: Arc::clone(&self.domains),
             nostr_keys: self.nostr_keys.clone(),
             ca_cert: self.ca_cert.clone(),
             connection_manager: self.connection_manager.clone(),
@@ -46,7 +71,16 @@ where
             session_manager: self.session_manager.clone(),
             service_provider: self.service_provider.clone(),
             subscribed_keys: Arc::clone(&self.subscribed_keys),
-            invoice_paid_trigger: self.invoice_paid_trigger.clone(),
+            background_trigger: self.background_trigger.clone(),
+            leader: self.leader.clone(),
+            auth_session_ttl_secs: self.auth_session_ttl_secs,
+            payer_data_secret: Arc::clone(&self.payer_data_secret),
+            admin_api_key: self.admin_api_key.clone(),
+            rate_limits: self.rate_limits,
+            trust_forwarded_for: self.trust_forwarded_for,
+            blocked_usernames: Arc::clone(&self.blocked_usernames),
+            dns_provider: self.dns_provider.clone(),
+            dns_zones: Arc::clone(&self.dns_zones),
         }
     }
 }
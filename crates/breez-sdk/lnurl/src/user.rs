@@ -1,3 +1,5 @@
+use lnurl_models::{LnurlPaySettings, NostrIdentity};
+
 pub const USERNAME_VALIDATION_REGEX: &str = "^(?:[a-zA-Z0-9!#$%&'*+\\/=?^_`{|}~-]+(?:\\.[a-z0-9!#$%&'*+\\/=?^_`{|}~-]+)*|\"(?:[\x01-\x08\x0b\x0c\x0e-\x1f\x21\x23-\x5b\x5d-\x7f]|\\[\x01-\x09\x0b\x0c\x0e-\x7f])*\")$";
 
 pub struct User {
@@ -8,4 +10,22 @@ pub struct User {
     /// When true, the SDK operates in LNURL private mode: the LNURL server won't track
     /// invoice payments for this user (LUD-21 and NIP-57 disabled).
     pub lnurl_private_mode_enabled: bool,
+    /// The user's own LNURL-pay settings, overriding the server defaults.
+    pub pay_settings: LnurlPaySettings,
+    /// Nostr identity served for the username as a NIP-05 identifier.
+    pub nostr: Option<NostrIdentity>,
+    /// Whether BIP-353 payment instructions are published in DNS for the username.
+    pub bip353_enabled: bool,
+    /// BOLT12 offer included in the published payment instructions.
+    pub bolt12_offer: Option<String>,
+}
+
+/// A username that can't be registered by anyone but the pubkey it is reserved for, if any.
+/// Reservations without a pubkey block the username altogether.
+#[derive(Debug, Clone)]
+pub struct UsernameReservation {
+    pub domain: String,
+    pub name: String,
+    pub pubkey: Option<String>,
+    pub created_at: i64,
 }
@@ -0,0 +1,430 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bitcoin::hashes::{Hash, HashEngine, Hmac, HmacEngine, sha256};
+use lightning_invoice::Bolt11Invoice;
+use lnurl_models::{WebhookEvent, WebhookPayload};
+use reqwest::{
+    Url,
+    dns::{Addrs, Name, Resolve, Resolving},
+    header::CONTENT_TYPE,
+    redirect,
+};
+use tracing::{debug, error, warn};
+
+use crate::repository::{LnurlRepository, LnurlRepositoryError};
+use crate::time::{now_millis, now_u64};
+
+/// Retry configuration for webhook deliveries
+const BASE_RETRY_DELAY_MS: i64 = 30_000; // 30 seconds
+const RETRY_MULTIPLIER: f64 = 1.5;
+const MAX_RETRY_DURATION_MS: i64 = 3 * 24 * 60 * 60 * 1000; // 3 days
+const DELIVERY_TIMEOUT_SECS: u64 = 10;
+/// How long a claimed delivery is reserved for the instance sending it
+const CLAIM_TIMEOUT_MS: i64 = 5 * 60 * 1000; // 5 minutes
+
+pub const MAX_WEBHOOKS_PER_USER: usize = 5;
+const MAX_URL_LEN: usize = 2048;
+
+/// Unix timestamp (seconds) of the delivery attempt
+pub const TIMESTAMP_HEADER: &str = "X-Lnurl-Timestamp";
+/// Hex encoded HMAC-SHA256 of `{timestamp}.{body}`, keyed with the webhook secret
+pub const SIGNATURE_HEADER: &str = "X-Lnurl-Signature";
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    #[error("invalid webhook url")]
+    InvalidUrl,
+    #[error("webhook url must use https")]
+    InsecureUrl,
+    #[error("no webhook events")]
+    NoEvents,
+    #[error("could not resolve webhook host")]
+    UnresolvableHost,
+    #[error("webhook host must resolve to public addresses only")]
+    NonPublicAddress,
+}
+
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    pub id: String,
+    pub domain: String,
+    pub user_pubkey: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    /// Secret the deliveries are signed with
+    pub secret: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<Webhook> for lnurl_models::Webhook {
+    fn from(webhook: Webhook) -> Self {
+        lnurl_models::Webhook {
+            id: webhook.id,
+            url: webhook.url,
+            events: webhook.events,
+            created_at: webhook.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub webhook_id: String,
+    /// The json body to deliver
+    pub payload: String,
+    pub created_at: i64,
+    pub retry_count: i32,
+    pub next_retry_at: i64,
+}
+
+/// A delivery ready to be sent, together with the webhook it is sent to.
+#[derive(Debug, Clone)]
+pub struct PendingWebhookDelivery {
+    pub delivery: WebhookDelivery,
+    pub url: String,
+    pub secret: String,
+}
+
+/// Something that happened to an invoice of a user, to notify the user's webhooks about.
+pub struct InvoiceEvent<'a> {
+    pub event: WebhookEvent,
+    /// The domain the invoice was requested on. Only the user's webhooks on this domain are
+    /// notified.
+    pub domain: &'a str,
+    pub user_pubkey: &'a str,
+    pub payment_hash: &'a str,
+    pub invoice: &'a str,
+    pub preimage: Option<&'a str>,
+    pub comment: Option<&'a str>,
+    pub payer_data: Option<&'a str>,
+}
+
+/// Validates a webhook url. Plain http is only accepted when the server itself runs on http,
+/// for testing. The host has to resolve to public addresses only, so webhooks can't be used to
+/// reach the internal network of the server.
+pub async fn validate_webhook_url(url: &str, scheme: &str) -> Result<(), WebhookError> {
+    if url.len() > MAX_URL_LEN {
+        return Err(WebhookError::InvalidUrl);
+    }
+    let url = Url::parse(url).map_err(|_| WebhookError::InvalidUrl)?;
+    let Some(host) = url.host_str() else {
+        return Err(WebhookError::InvalidUrl);
+    };
+    match url.scheme() {
+        "https" => {}
+        "http" if scheme == "http" => {}
+        "http" => return Err(WebhookError::InsecureUrl),
+        _ => return Err(WebhookError::InvalidUrl),
+    }
+    resolve_public_addrs(host).await?;
+    Ok(())
+}
+
+/// Builds the http client webhooks are delivered with. Hosts are resolved when connecting and
+/// refused if they resolve to non-public addresses, so a host can't be pointed to an internal
+/// address after the webhook was registered. Redirects are not followed for the same reason.
+pub fn webhook_http_client() -> Result<reqwest::Client, reqwest::Error> {
+    reqwest::Client::builder()
+        .dns_resolver(Arc::new(PublicAddrResolver))
+        .redirect(redirect::Policy::none())
+        .build()
+}
+
+struct PublicAddrResolver;
+
+impl Resolve for PublicAddrResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs = resolve_public_addrs(&host).await?;
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// Resolves a webhook host, failing if any of its addresses is not public.
+async fn resolve_public_addrs(host: &str) -> Result<Vec<SocketAddr>, WebhookError> {
+    let addrs: Vec<SocketAddr> = match parse_ip_host(host) {
+        Some(ip) => vec![SocketAddr::new(ip, 0)],
+        None => tokio::net::lookup_host((host, 0))
+            .await
+            .map_err(|_| WebhookError::UnresolvableHost)?
+            .collect(),
+    };
+    if addrs.is_empty() {
+        return Err(WebhookError::UnresolvableHost);
+    }
+    if addrs.iter().any(|addr| !is_public_ip(addr.ip())) {
+        return Err(WebhookError::NonPublicAddress);
+    }
+    Ok(addrs)
+}
+
+/// Parses a url host that is an ip address, with ipv6 addresses in brackets.
+fn parse_ip_host(host: &str) -> Option<IpAddr> {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public_ipv4(ip);
+            }
+            let segments = ip.segments();
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // Unique local
+                || (segments[0] & 0xfe00) == 0xfc00
+                // Link-local
+                || (segments[0] & 0xffc0) == 0xfe80
+                // Documentation
+                || (segments[0] == 0x2001 && segments[1] == 0x0db8)
+                // NAT64, which can embed any ipv4 address
+                || (segments[0] == 0x0064 && segments[1] == 0xff9b))
+        }
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        // Includes the cloud metadata address 169.254.169.254
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || octets[0] == 0
+        // Shared address space
+        || (octets[0] == 100 && (octets[1] & 0xc0) == 64)
+        // IETF protocol assignments
+        || (octets[0] == 192 && octets[1] == 0 && octets[2] == 0)
+        // Benchmarking
+        || (octets[0] == 198 && (octets[1] & 0xfe) == 18)
+        // Reserved
+        || octets[0] >= 240)
+}
+
+/// Queues a delivery for every webhook of the user subscribed to the event. Returns the number
+/// of queued deliveries.
+pub async fn queue_webhook_deliveries<DB>(
+    db: &DB,
+    event: &InvoiceEvent<'_>,
+) -> Result<usize, LnurlRepositoryError>
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    let deliveries = build_webhook_deliveries(db, event).await?;
+    for delivery in &deliveries {
+        db.insert_webhook_delivery(delivery).await?;
+    }
+
+    if !deliveries.is_empty() {
+        debug!(
+            "Queued {} webhook deliveries for payment hash {}",
+            deliveries.len(),
+            event.payment_hash
+        );
+    }
+    Ok(deliveries.len())
+}
+
+/// Builds a delivery for every webhook of the user subscribed to the event, without queueing
+/// them.
+pub async fn build_webhook_deliveries<DB>(
+    db: &DB,
+    event: &InvoiceEvent<'_>,
+) -> Result<Vec<WebhookDelivery>, LnurlRepositoryError>
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    let webhooks = db.list_webhooks(event.domain, event.user_pubkey).await?;
+    let amount_msat = Bolt11Invoice::from_str(event.invoice)
+        .ok()
+        .and_then(|invoice| invoice.amount_milli_satoshis());
+
+    let now = now_millis();
+    let mut deliveries = Vec::new();
+    for webhook in webhooks
+        .iter()
+        .filter(|webhook| webhook.events.contains(&event.event))
+    {
+        let id = hex::encode(rand::random::<[u8; 16]>());
+        let payload = WebhookPayload {
+            id: id.clone(),
+            event: event.event,
+            user_pubkey: event.user_pubkey.to_string(),
+            payment_hash: event.payment_hash.to_string(),
+            invoice: event.invoice.to_string(),
+            amount_msat,
+            preimage: event.preimage.map(ToString::to_string),
+            comment: event.comment.map(ToString::to_string),
+            payer_data: event.payer_data.map(ToString::to_string),
+            created_at: now,
+        };
+        let payload =
+            serde_json::to_string(&payload).map_err(|e| LnurlRepositoryError::General(e.into()))?;
+        deliveries.push(WebhookDelivery {
+            id,
+            webhook_id: webhook.id.clone(),
+            payload,
+            created_at: now,
+            retry_count: 0,
+            next_retry_at: now, // Deliver immediately
+        });
+    }
+    Ok(deliveries)
+}
+
+/// Process all pending items in the webhook delivery queue.
+pub async fn process_webhook_queue<DB>(db: &DB, http_client: &reqwest::Client)
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    let pending = match db.get_pending_webhook_deliveries().await {
+        Ok(pending) => pending,
+        Err(e) => {
+            error!("Failed to get pending webhook deliveries: {}", e);
+            return;
+        }
+    };
+
+    if !pending.is_empty() {
+        debug!(
+            "Background processor: found {} pending webhook deliveries",
+            pending.len()
+        );
+    }
+
+    for item in pending {
+        process_webhook_delivery(db, http_client, &item).await;
+    }
+}
+
+/// Process a single webhook delivery: send it, and reschedule it with backoff if that fails.
+async fn process_webhook_delivery<DB>(
+    db: &DB,
+    http_client: &reqwest::Client,
+    item: &PendingWebhookDelivery,
+) where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    let delivery = &item.delivery;
+
+    // Claim the delivery, so it isn't sent twice when another instance took over in the
+    // meantime. If sending is interrupted, the delivery is retried once the claim times out.
+    let now = now_millis();
+    match db
+        .claim_webhook_delivery(&delivery.id, now, now.saturating_add(CLAIM_TIMEOUT_MS))
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            debug!(
+                "Webhook delivery {} claimed by another run, skipping",
+                delivery.id
+            );
+            return;
+        }
+        Err(e) => {
+            error!("Failed to claim webhook delivery {}: {}", delivery.id, e);
+            return;
+        }
+    }
+
+    if now.saturating_sub(delivery.created_at) > MAX_RETRY_DURATION_MS {
+        debug!(
+            "Webhook delivery {} exceeded max retry duration, removing from queue",
+            delivery.id
+        );
+        if let Err(e) = db.delete_webhook_delivery(&delivery.id).await {
+            error!(
+                "Failed to delete expired webhook delivery {}: {}",
+                delivery.id, e
+            );
+        }
+        return;
+    }
+
+    match deliver(http_client, &item.url, &item.secret, &delivery.payload).await {
+        Ok(()) => {
+            debug!("Successfully delivered webhook delivery {}", delivery.id);
+            if let Err(e) = db.delete_webhook_delivery(&delivery.id).await {
+                error!("Failed to delete webhook delivery {}: {}", delivery.id, e);
+            }
+        }
+        Err(e) => {
+            warn!(
+                "Failed to deliver webhook delivery {}: {}, will retry",
+                delivery.id, e
+            );
+
+            // Calculate next retry time with exponential backoff
+            let retry_count = delivery.retry_count.saturating_add(1);
+            #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+            let delay_ms = (BASE_RETRY_DELAY_MS as f64 * RETRY_MULTIPLIER.powi(retry_count)) as i64;
+            let next_retry_at = now.saturating_add(delay_ms);
+
+            if let Err(e) = db
+                .update_webhook_delivery_retry(&delivery.id, retry_count, next_retry_at)
+                .await
+            {
+                error!(
+                    "Failed to update retry for webhook delivery {}: {}",
+                    delivery.id, e
+                );
+            }
+        }
+    }
+}
+
+async fn deliver(
+    http_client: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    payload: &str,
+) -> Result<(), anyhow::Error> {
+    // Ip address hosts are connected to without going through the resolver, so check them here
+    let host = Url::parse(url)?.host_str().and_then(parse_ip_host);
+    if host.is_some_and(|ip| !is_public_ip(ip)) {
+        return Err(WebhookError::NonPublicAddress.into());
+    }
+
+    let timestamp = now_u64();
+    let response = http_client
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign_payload(secret, timestamp, payload))
+        .body(payload.to_string())
+        .timeout(Duration::from_secs(DELIVERY_TIMEOUT_SECS))
+        .send()
+        .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow::anyhow!("unexpected status code {status}"));
+    }
+    Ok(())
+}
+
+/// Signs a delivery, so the receiver can verify it was sent by this server. The timestamp is
+/// signed too, so receivers can reject replayed deliveries.
+fn sign_payload(secret: &str, timestamp: u64, payload: &str) -> String {
+    let mut engine = HmacEngine::<sha256::Hash>::new(secret.as_bytes());
+    engine.input(format!("{timestamp}.{payload}").as_bytes());
+    Hmac::<sha256::Hash>::from_engine(engine).to_string()
+}
@@ -0,0 +1,414 @@
+use bitcoin::hashes::{Hash, sha256};
+use lightning_invoice::Bolt11Invoice;
+use tracing::{debug, error};
+
+use crate::repository::{LnurlRepository, LnurlRepositoryError};
+use crate::time::{now_millis, now_u64};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WithdrawError {
+    #[error("withdraw link expired")]
+    Expired,
+    #[error("withdraw link has no uses left")]
+    Exhausted,
+    #[error("invoice has no amount")]
+    MissingAmount,
+    #[error("amount out of range")]
+    AmountOutOfRange,
+    #[error("invoice already submitted")]
+    AlreadySubmitted,
+    #[error("invalid preimage")]
+    InvalidPreimage,
+    #[error(transparent)]
+    Repository(#[from] LnurlRepositoryError),
+}
+
+#[derive(Debug, Clone)]
+pub struct WithdrawLink {
+    pub id: String,
+    pub domain: String,
+    pub user_pubkey: String,
+    pub description: String,
+    pub min_withdrawable: u64,
+    pub max_withdrawable: u64,
+    pub max_uses: Option<u32>,
+    /// Number of uses claimed so far, including withdrawals still pending
+    pub uses: u32,
+    /// Unix timestamp (seconds) after which the link can no longer be used
+    pub expires_at: Option<u64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl WithdrawLink {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= now_u64())
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.max_uses.is_some_and(|max_uses| self.uses >= max_uses)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WithdrawRequestStatus {
+    Pending,
+    Paid,
+    Failed,
+}
+
+impl WithdrawRequestStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WithdrawRequestStatus::Pending => "pending",
+            WithdrawRequestStatus::Paid => "paid",
+            WithdrawRequestStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(status: &str) -> Option<Self> {
+        match status {
+            "pending" => Some(WithdrawRequestStatus::Pending),
+            "paid" => Some(WithdrawRequestStatus::Paid),
+            "failed" => Some(WithdrawRequestStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// An invoice submitted by a payer through a withdraw link.
+#[derive(Debug, Clone)]
+pub struct WithdrawRequest {
+    pub payment_hash: String,
+    pub link_id: String,
+    pub user_pubkey: String,
+    pub invoice: String,
+    pub amount_msat: u64,
+    pub status: WithdrawRequestStatus,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Handle an invoice submitted to a withdraw link by claiming a use of the link and storing the
+/// request. The owning wallet picks up pending requests on its next sync and pays them.
+pub async fn submit_withdraw_request<DB>(
+    db: &DB,
+    link: &WithdrawLink,
+    invoice: &Bolt11Invoice,
+) -> Result<(), WithdrawError>
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    if link.is_expired() {
+        return Err(WithdrawError::Expired);
+    }
+
+    let amount_msat = invoice
+        .amount_milli_satoshis()
+        .ok_or(WithdrawError::MissingAmount)?;
+    if amount_msat < link.min_withdrawable || amount_msat > link.max_withdrawable {
+        return Err(WithdrawError::AmountOutOfRange);
+    }
+
+    let payment_hash = invoice.payment_hash().to_string();
+    if db.get_withdraw_request(&payment_hash).await?.is_some() {
+        return Err(WithdrawError::AlreadySubmitted);
+    }
+
+    // Claiming is done in a single statement so concurrent callbacks can't exceed the max uses
+    if !db.claim_withdraw_link_use(&link.id, now_u64()).await? {
+        return Err(WithdrawError::Exhausted);
+    }
+
+    let now = now_millis();
+    let request = WithdrawRequest {
+        payment_hash: payment_hash.clone(),
+        link_id: link.id.clone(),
+        user_pubkey: link.user_pubkey.clone(),
+        invoice: invoice.to_string(),
+        amount_msat,
+        status: WithdrawRequestStatus::Pending,
+        created_at: now,
+        updated_at: now,
+    };
+    if let Err(e) = db.insert_withdraw_request(&request).await {
+        // Give the use back, the payer can retry
+        if let Err(e) = db.release_withdraw_link_use(&link.id).await {
+            error!("Failed to release use of withdraw link {}: {}", link.id, e);
+        }
+        return Err(e.into());
+    }
+
+    debug!(
+        "Queued withdraw request {} for link {}",
+        payment_hash, link.id
+    );
+    Ok(())
+}
+
+/// Handle the wallet reporting the outcome of a withdraw request. A failed payment gives the use
+/// back to the link.
+pub async fn complete_withdraw_request<DB>(
+    db: &DB,
+    request: &WithdrawRequest,
+    preimage: Option<&str>,
+) -> Result<(), WithdrawError>
+where
+    DB: LnurlRepository + Clone + Send + Sync + 'static,
+{
+    if request.status != WithdrawRequestStatus::Pending {
+        debug!(
+            "Withdraw request {} already completed, skipping",
+            request.payment_hash
+        );
+        return Ok(());
+    }
+
+    let status = match preimage {
+        Some(preimage) => {
+            let preimage = hex::decode(preimage).map_err(|_| WithdrawError::InvalidPreimage)?;
+            if sha256::Hash::hash(&preimage).to_string() != request.payment_hash {
+                return Err(WithdrawError::InvalidPreimage);
+            }
+            WithdrawRequestStatus::Paid
+        }
+        None => WithdrawRequestStatus::Failed,
+    };
+
+    let updated = db
+        .complete_pending_withdraw_request(&request.payment_hash, status, now_millis())
+        .await?;
+    if !updated {
+        debug!(
+            "Withdraw request {} was completed concurrently, skipping",
+            request.payment_hash
+        );
+        return Ok(());
+    }
+    if status == WithdrawRequestStatus::Failed {
+        db.release_withdraw_link_use(&request.link_id).await?;
+    }
+
+    debug!(
+        "Withdraw request {} completed with status {}",
+        request.payment_hash,
+        status.as_str()
+    );
+    Ok(())
+}
+
+impl WithdrawLink {
+    /// Converts the link to its api model, with the lnurl served over the given scheme.
+    pub fn into_model(self, scheme: &str) -> lnurl_models::WithdrawLink {
+        lnurl_models::WithdrawLink {
+            lnurl: withdraw_lnurl(scheme, &self.domain, &self.id),
+            id: self.id,
+            description: self.description,
+            min_withdrawable: self.min_withdrawable,
+            max_withdrawable: self.max_withdrawable,
+            max_uses: self.max_uses,
+            uses: self.uses,
+            expires_at: self.expires_at,
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// The lnurl of a withdraw link. LUD-17 `lnurlw://` urls are resolved over https, so they're only
+/// used when the server is served over https.
+fn withdraw_lnurl(scheme: &str, domain: &str, id: &str) -> String {
+    let scheme = if scheme == "https" { "lnurlw" } else { scheme };
+    format!("{scheme}://{domain}/lnurlw/{id}")
+}
+
+impl From<WithdrawRequest> for lnurl_models::PendingWithdrawRequest {
+    fn from(request: WithdrawRequest) -> Self {
+        lnurl_models::PendingWithdrawRequest {
+            payment_hash: request.payment_hash,
+            link_id: request.link_id,
+            invoice: request.invoice,
+            amount_msat: request.amount_msat,
+            created_at: request.created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::secp256k1::{Secp256k1, SecretKey};
+    use lightning_invoice::{Currency, InvoiceBuilder, PaymentSecret};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+    use crate::sqlite::{LnurlRepository as SqliteRepository, run_migrations};
+
+    async fn repository() -> SqliteRepository {
+        // A single connection, as every connection to an in-memory database has its own database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        SqliteRepository::new(pool)
+    }
+
+    async fn insert_link(db: &SqliteRepository, max_uses: Option<u32>) -> WithdrawLink {
+        let link = WithdrawLink {
+            id: "link".to_string(),
+            domain: "example.com".to_string(),
+            user_pubkey: "pubkey".to_string(),
+            description: "Withdraw".to_string(),
+            min_withdrawable: 1_000,
+            max_withdrawable: 10_000,
+            max_uses,
+            uses: 0,
+            expires_at: None,
+            created_at: 0,
+            updated_at: 0,
+        };
+        db.insert_withdraw_link(&link).await.unwrap();
+        link
+    }
+
+    fn invoice(amount_msat: u64, preimage: [u8; 32]) -> Bolt11Invoice {
+        let secp = Secp256k1::new();
+        let key = SecretKey::from_slice(&[1; 32]).unwrap();
+        InvoiceBuilder::new(Currency::Regtest)
+            .description("withdraw".to_string())
+            .payment_hash(sha256::Hash::hash(&preimage))
+            .payment_secret(PaymentSecret([0; 32]))
+            .current_timestamp()
+            .min_final_cltv_expiry_delta(144)
+            .amount_milli_satoshis(amount_msat)
+            .build_signed(|hash| secp.sign_ecdsa_recoverable(hash, &key))
+            .unwrap()
+    }
+
+    async fn get_request(db: &SqliteRepository, invoice: &Bolt11Invoice) -> WithdrawRequest {
+        db.get_withdraw_request(&invoice.payment_hash().to_string())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    async fn uses(db: &SqliteRepository) -> u32 {
+        db.get_withdraw_link("link").await.unwrap().unwrap().uses
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_callback_flow() {
+        let db = repository().await;
+        let link = insert_link(&db, None).await;
+
+        let paid = invoice(5_000, [1; 32]);
+        submit_withdraw_request(&db, &link, &paid).await.unwrap();
+        let request = get_request(&db, &paid).await;
+        assert_eq!(request.status, WithdrawRequestStatus::Pending);
+        assert_eq!(request.amount_msat, 5_000);
+        assert_eq!(
+            db.get_pending_withdraw_requests("pubkey")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(uses(&db).await, 1);
+        assert!(matches!(
+            submit_withdraw_request(&db, &link, &paid).await,
+            Err(WithdrawError::AlreadySubmitted)
+        ));
+
+        assert!(matches!(
+            complete_withdraw_request(&db, &request, Some(&hex::encode([2; 32]))).await,
+            Err(WithdrawError::InvalidPreimage)
+        ));
+        complete_withdraw_request(&db, &request, Some(&hex::encode([1; 32])))
+            .await
+            .unwrap();
+        assert_eq!(
+            get_request(&db, &paid).await.status,
+            WithdrawRequestStatus::Paid
+        );
+        assert!(
+            db.get_pending_withdraw_requests("pubkey")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(uses(&db).await, 1);
+
+        // Completing again doesn't change the outcome
+        complete_withdraw_request(&db, &request, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            get_request(&db, &paid).await.status,
+            WithdrawRequestStatus::Paid
+        );
+        assert_eq!(uses(&db).await, 1);
+
+        assert!(matches!(
+            submit_withdraw_request(&db, &link, &invoice(20_000, [3; 32])).await,
+            Err(WithdrawError::AmountOutOfRange)
+        ));
+        assert_eq!(uses(&db).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_link_single_use() {
+        let db = repository().await;
+        let link = insert_link(&db, Some(1)).await;
+
+        let first = invoice(5_000, [1; 32]);
+        submit_withdraw_request(&db, &link, &first).await.unwrap();
+        let second = invoice(5_000, [2; 32]);
+        assert!(matches!(
+            submit_withdraw_request(&db, &link, &second).await,
+            Err(WithdrawError::Exhausted)
+        ));
+        assert!(
+            db.get_withdraw_request(&second.payment_hash().to_string())
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // A failed payment gives the use back
+        complete_withdraw_request(&db, &get_request(&db, &first).await, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            get_request(&db, &first).await.status,
+            WithdrawRequestStatus::Failed
+        );
+        assert_eq!(uses(&db).await, 0);
+
+        submit_withdraw_request(&db, &link, &second).await.unwrap();
+        complete_withdraw_request(
+            &db,
+            &get_request(&db, &second).await,
+            Some(&hex::encode([2; 32])),
+        )
+        .await
+        .unwrap();
+        assert_eq!(uses(&db).await, 1);
+        assert!(matches!(
+            submit_withdraw_request(&db, &link, &invoice(5_000, [3; 32])).await,
+            Err(WithdrawError::Exhausted)
+        ));
+    }
+
+    #[test]
+    fn test_withdraw_lnurl() {
+        assert_eq!(
+            withdraw_lnurl("https", "example.com", "id"),
+            "lnurlw://example.com/lnurlw/id"
+        );
+        assert_eq!(
+            withdraw_lnurl("http", "localhost:8080", "id"),
+            "http://localhost:8080/lnurlw/id"
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::leader::lost_leadership;
+use crate::metrics::METRICS;
 use crate::repository::LnurlRepository;
 use crate::time::now_millis;
 use lightning_invoice::Bolt11Invoice;
@@ -13,7 +15,7 @@ use spark_wallet::DefaultSigner;
 use std::collections::HashSet;
 use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, watch};
 use tracing::{debug, error, trace, warn};
 
 #[derive(Debug, Clone)]
@@ -41,6 +43,7 @@ pub async fn create_rpc_client_and_subscribe<DB>(
     service_provider: Arc<ServiceProvider>,
     nostr_keys: Keys,
     subscribed_keys: Arc<Mutex<HashSet<String>>>,
+    leader: watch::Receiver<bool>,
 ) -> Result<(), anyhow::Error>
 where
     DB: LnurlRepository + Clone + Send + Sync + 'static,
@@ -55,6 +58,7 @@ where
         service_provider,
         nostr_keys,
         subscribed_keys,
+        leader,
     );
 
     Ok(())
@@ -68,6 +72,7 @@ pub fn subscribe_to_user_for_zaps<DB>(
     ssp_client: Arc<ServiceProvider>,
     nostr_keys: Keys,
     subscribed_keys: Arc<Mutex<HashSet<String>>>,
+    mut leader: watch::Receiver<bool>,
 ) where
     DB: LnurlRepository + Clone + Send + Sync + 'static,
 {
@@ -83,6 +88,13 @@ pub fn subscribe_to_user_for_zaps<DB>(
 
         // Outer reconnection loop
         loop {
+            // Only the leader subscribes to users
+            if !*leader.borrow() {
+                debug!("Not the leader, unsubscribing from user {user_pk}");
+                subscribed_keys.lock().await.remove(&user_pk.to_string());
+                return;
+            }
+
             debug!("Connecting to event stream for user {user_pk}");
             let mut stream = match rpc
                 .subscribe_to_events(SubscribeToEventsRequest {
@@ -102,6 +114,7 @@ pub fn subscribe_to_user_for_zaps<DB>(
                         return;
                     }
                     error!("Failed to subscribe to events for user {user_pk}: {e}, retrying in 5s");
+                    METRICS.spark_subscription_errors.inc();
                     tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
                     continue;
                 }
@@ -113,6 +126,11 @@ pub fn subscribe_to_user_for_zaps<DB>(
                 // We check every 60 seconds to avoid keeping subscriptions active after expiry
                 let message = tokio::select! {
                     message = stream.message() => message,
+                    () = lost_leadership(&mut leader) => {
+                        debug!("No longer the leader, unsubscribing from user {user_pk}");
+                        subscribed_keys.lock().await.remove(&user_pk.to_string());
+                        return;
+                    }
                     () = tokio::time::sleep(tokio::time::Duration::from_secs(60)) => {
                         // Periodically check if user still has unexpired invoices
                         // Hold the lock while checking to prevent race condition with new subscriptions
@@ -149,6 +167,7 @@ pub fn subscribe_to_user_for_zaps<DB>(
                             return;
                         }
                         error!("Error receiving event for user {user_pk}: {e}, reconnecting...");
+                        METRICS.spark_subscription_errors.inc();
                         break; // Break inner loop to reconnect
                     }
                 };
@@ -297,6 +316,7 @@ pub fn subscribe_to_user_for_zaps<DB>(
 
                 if let Err(e) = nostr_client.send_event(&zap_event).await {
                     error!("Failed to send zap event to nostr relay: {e}",);
+                    METRICS.zap_publish_failures.inc();
                 } else {
                     debug!("Sent zap event to nostr relay");
                 }
@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use breez_sdk_spark::signer::ExternalSigningOperation;
+use serde::Serialize;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::AsyncWriteExt,
+    sync::Mutex,
+};
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// The request was executed by the signer.
+    Signed,
+    /// The request was rejected by the signing policy.
+    Denied,
+    /// The signer failed to execute the request.
+    Failed,
+}
+
+/// An entry of the audit log. Secrets and messages aren't logged, only a hash of the request.
+#[derive(Debug, Serialize)]
+pub struct AuditEntry {
+    /// Unix timestamp in seconds.
+    pub time: u64,
+    pub client_id: String,
+    pub peer_address: Option<String>,
+    pub method: &'static str,
+    pub derivation_path: Option<String>,
+    /// The wallet operation the request was made for, as reported by the client.
+    pub operation: Option<ExternalSigningOperation>,
+    /// Amount moved by the operation, in sats or token base units for token transactions.
+    pub amount: Option<u128>,
+    pub counterparty: Option<String>,
+    /// Hex encoded sha256 of the request payload.
+    pub request_hash: String,
+    pub outcome: AuditOutcome,
+    pub error: Option<String>,
+}
+
+/// Append-only audit log, one JSON encoded entry per line.
+pub struct AuditLog {
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    pub async fn open(path: &Path) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Appends an entry and waits until it's written to disk.
+    pub async fn append(&self, entry: &AuditEntry) -> anyhow::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        let mut file = self.file.lock().await;
+        file.write_all(&line).await?;
+        file.sync_data().await?;
+        Ok(())
+    }
+}
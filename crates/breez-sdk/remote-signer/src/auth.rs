@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use bitcoin::hashes::{Hash, sha256};
+use tonic::{Request, Status, service::Interceptor};
+use tracing::debug;
+
+/// Identifies the client that made a request in the audit log: the start of the hash of its
+/// api key, so the key itself isn't logged.
+#[derive(Clone, Debug)]
+pub struct ClientId(pub String);
+
+/// Validates the api key passed as bearer token against the configured api keys. Only hashes of
+/// the keys are kept in memory.
+#[derive(Clone)]
+pub struct ApiKeyInterceptor {
+    api_key_hashes: Arc<Vec<sha256::Hash>>,
+}
+
+impl ApiKeyInterceptor {
+    pub fn new(api_keys: &[String]) -> Self {
+        let api_key_hashes = api_keys
+            .iter()
+            .map(|key| sha256::Hash::hash(key.as_bytes()))
+            .collect();
+        Self {
+            api_key_hashes: Arc::new(api_key_hashes),
+        }
+    }
+}
+
+impl Interceptor for ApiKeyInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let Some(api_key) = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+        else {
+            debug!("request without api key");
+            return Err(Status::unauthenticated("missing api key"));
+        };
+
+        // Comparing hashes avoids leaking the keys through the comparison time.
+        let hash = sha256::Hash::hash(api_key.as_bytes());
+        if !self.api_key_hashes.contains(&hash) {
+            debug!("request with invalid api key");
+            return Err(Status::unauthenticated("invalid api key"));
+        }
+
+        let mut client_id = hash.to_string();
+        client_id.truncate(16);
+        request.extensions_mut().insert(ClientId(client_id));
+        Ok(request)
+    }
+}
@@ -0,0 +1,211 @@
+use crate::{
+    audit::AuditLog, auth::ApiKeyInterceptor, policy::SigningPolicy,
+    proto::remote_signer_server::RemoteSignerServer, server::SignerServer,
+};
+use anyhow::anyhow;
+use breez_sdk_spark::{KeySetConfig, Network, default_external_signer};
+use clap::{Parser, ValueEnum};
+use figment::{
+    Figment,
+    providers::{Env, Format, Serialized, Toml},
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tonic::transport::{Identity, Server, ServerTlsConfig};
+use tracing::{info, warn};
+use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+
+mod audit;
+mod auth;
+mod policy;
+mod server;
+mod time;
+
+#[allow(clippy::doc_markdown, clippy::pedantic)]
+mod proto {
+    tonic::include_proto!("remote_signer");
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum NetworkArg {
+    Mainnet,
+    Regtest,
+}
+
+impl From<NetworkArg> for Network {
+    fn from(value: NetworkArg) -> Self {
+        match value {
+            NetworkArg::Mainnet => Network::Mainnet,
+            NetworkArg::Regtest => Network::Regtest,
+        }
+    }
+}
+
+#[derive(Clone, Parser, Serialize, Deserialize)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Address the remote signer will listen on.
+    #[arg(long, default_value = "0.0.0.0:50051")]
+    pub address: core::net::SocketAddr,
+
+    #[arg(long, default_value = "remote-signer.conf")]
+    pub config: PathBuf,
+
+    /// Loglevel to use. Can be used to filter logs through the env filter
+    /// format.
+    #[arg(long, default_value = "info")]
+    pub log_level: String,
+
+    /// BIP39 mnemonic of the wallet. Prefer setting it in the config file or through the
+    /// `BREEZ_SIGNER_MNEMONIC` environment variable, command line arguments are visible to
+    /// other processes.
+    #[arg(long, default_value = "")]
+    pub mnemonic: String,
+
+    /// Optional passphrase of the mnemonic.
+    #[arg(long)]
+    pub passphrase: Option<String>,
+
+    #[arg(long, value_enum, default_value = "mainnet")]
+    pub network: NetworkArg,
+
+    /// Account number for key derivation, if the wallet doesn't use the default account.
+    #[arg(long)]
+    pub account_number: Option<u32>,
+
+    /// Api keys of the clients allowed to use the signer. At least one is required.
+    #[arg(long = "api-key")]
+    pub api_keys: Vec<String>,
+
+    /// PEM encoded TLS certificate. Required unless `--insecure` is set.
+    #[arg(long)]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM encoded private key of the TLS certificate.
+    #[arg(long)]
+    pub tls_key: Option<PathBuf>,
+
+    /// Serve requests without TLS. Only use this when the signer is reachable on a private
+    /// network or behind a proxy terminating TLS.
+    #[arg(long)]
+    pub insecure: bool,
+
+    /// File every request is appended to, one JSON entry per line.
+    #[arg(long, default_value = "remote-signer-audit.log")]
+    pub audit_log: PathBuf,
+
+    /// Derivation paths keys can be used at, including their children. Keys of all paths can
+    /// be used if none is set.
+    #[arg(long = "allowed-derivation-path")]
+    pub allowed_derivation_paths: Vec<String>,
+
+    /// Maximum number of requests per minute.
+    #[arg(long)]
+    pub max_requests_per_minute: Option<u32>,
+
+    /// Maximum number of FROST signature shares per minute. Each transferred leaf takes a
+    /// signature share, so this bounds how fast funds can leave the wallet.
+    #[arg(long)]
+    pub max_frost_signatures_per_minute: Option<u32>,
+
+    /// Maximum amount in sats a single request can move, as stated in its signing context.
+    /// Requests that can move sats are denied when they don't state an amount.
+    #[arg(long)]
+    pub max_amount_per_request: Option<u64>,
+
+    /// Maximum amount in sats a single FROST signing session can move, as stated in its signing
+    /// context. Every transaction moving a leaf is signed in its own session, and sessions without
+    /// an amount are denied.
+    #[arg(long)]
+    pub max_amount_per_frost_session: Option<u64>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    let args = Args::parse();
+    let config_file = std::fs::canonicalize(&args.config).ok();
+    let mut figment = Figment::new().merge(Serialized::defaults(args));
+    if let Some(config_file) = &config_file {
+        figment = figment.merge(Toml::file(config_file));
+    }
+
+    let args: Args = figment.merge(Env::prefixed("BREEZ_SIGNER_")).extract()?;
+
+    tracing_subscriber::registry()
+        .with(EnvFilter::new(&args.log_level))
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
+
+    if let Some(config_file) = &config_file {
+        info!(
+            "starting remote signer with config file: {}",
+            config_file.display()
+        );
+    } else {
+        info!("starting remote signer without config file");
+    }
+
+    if args.mnemonic.trim().is_empty() {
+        return Err(anyhow!("mnemonic is required"));
+    }
+    if args.api_keys.is_empty() {
+        return Err(anyhow!("at least one api key is required"));
+    }
+    if args.allowed_derivation_paths.is_empty() {
+        warn!("no allowed derivation paths configured, keys of all paths can be used");
+    }
+
+    let signer = default_external_signer(
+        args.mnemonic,
+        args.passphrase,
+        args.network.into(),
+        Some(KeySetConfig {
+            account_number: args.account_number,
+            ..Default::default()
+        }),
+    )?;
+    let policy = SigningPolicy::new(
+        &args.allowed_derivation_paths,
+        args.max_requests_per_minute,
+        args.max_frost_signatures_per_minute,
+        args.max_amount_per_request,
+        args.max_amount_per_frost_session,
+    )?;
+    let audit_log = AuditLog::open(&args.audit_log).await?;
+
+    let mut server = Server::builder();
+    match (args.tls_cert, args.tls_key) {
+        (Some(tls_cert), Some(tls_key)) => {
+            let identity = Identity::from_pem(std::fs::read(tls_cert)?, std::fs::read(tls_key)?);
+            server = server.tls_config(ServerTlsConfig::new().identity(identity))?;
+        }
+        (None, None) if args.insecure => {
+            warn!("serving without TLS, only use the signer on a private network");
+        }
+        (None, None) => {
+            return Err(anyhow!(
+                "tls_cert and tls_key are required unless insecure is set"
+            ));
+        }
+        _ => return Err(anyhow!("tls_cert and tls_key must be set together")),
+    }
+
+    let service = RemoteSignerServer::with_interceptor(
+        SignerServer::new(signer, policy, audit_log),
+        ApiKeyInterceptor::new(&args.api_keys),
+    );
+
+    info!("Server listening at {}", args.address);
+    server
+        .add_service(service)
+        .serve_with_shutdown(args.address, async {
+            tokio::signal::ctrl_c()
+                .await
+                .expect("failed to create Ctrl+C shutdown signal");
+        })
+        .await?;
+
+    info!("remote signer stopped");
+    Ok(())
+}
@@ -0,0 +1,419 @@
+use std::{
+    collections::VecDeque,
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use bitcoin::bip32::DerivationPath;
+use breez_sdk_spark::signer::{
+    ExternalSigningContext, ExternalSigningOperation, RemoteSignerRequest,
+};
+
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, thiserror::Error)]
+pub enum PolicyError {
+    #[error("derivation path {0} is not allowed")]
+    DerivationPathNotAllowed(String),
+    #[error("invalid derivation path {0}")]
+    InvalidDerivationPath(String),
+    #[error("rate limit of {0} requests per minute exceeded")]
+    RateLimited(u32),
+    #[error("limit of {0} frost signatures per minute exceeded")]
+    FrostLimited(u32),
+    #[error("{0} with the identity key requires a signing context when amounts are limited")]
+    MissingContext(&'static str),
+    #[error("{0:?} requires an amount in sats in the signing context when amounts are limited")]
+    MissingAmount(ExternalSigningOperation),
+    #[error("amount of {amount} sats exceeds the limit of {limit} sats per request")]
+    AmountLimited { amount: u128, limit: u64 },
+    #[error("amount of {amount} sats exceeds the limit of {limit} sats per frost signing session")]
+    FrostAmountLimited { amount: u128, limit: u64 },
+}
+
+/// Decides which requests the signer executes.
+pub struct SigningPolicy {
+    /// Keys can only be used at these paths or their children. All paths are allowed if empty.
+    allowed_derivation_paths: Vec<DerivationPath>,
+    requests: Option<RateLimiter>,
+    /// FROST signature shares are what moves funds, limiting them bounds how many leaves can be
+    /// transferred in a period of time.
+    frost_signatures: Option<RateLimiter>,
+    /// Maximum amount in sats a single request can move.
+    ///
+    /// Amounts come from the signing context, which the signed data doesn't reveal. So when a
+    /// limit is set, requests that can move sats are denied unless they state an amount.
+    max_amount_per_request: Option<u64>,
+    /// Maximum amount in sats a single FROST signing session can move. Each session produces the
+    /// signature share for one transaction moving a leaf, so every session must state an amount.
+    max_amount_per_frost_session: Option<u64>,
+}
+
+impl SigningPolicy {
+    pub fn new(
+        allowed_derivation_paths: &[String],
+        max_requests_per_minute: Option<u32>,
+        max_frost_signatures_per_minute: Option<u32>,
+        max_amount_per_request: Option<u64>,
+        max_amount_per_frost_session: Option<u64>,
+    ) -> anyhow::Result<Self> {
+        let allowed_derivation_paths = allowed_derivation_paths
+            .iter()
+            .map(|path| {
+                DerivationPath::from_str(path)
+                    .map_err(|e| anyhow!("invalid allowed derivation path {path}: {e}"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            allowed_derivation_paths,
+            requests: max_requests_per_minute.map(RateLimiter::new),
+            frost_signatures: max_frost_signatures_per_minute.map(RateLimiter::new),
+            max_amount_per_request,
+            max_amount_per_frost_session,
+        })
+    }
+
+    pub fn check(&self, request: &RemoteSignerRequest) -> Result<(), PolicyError> {
+        if let Some(path) = request.derivation_path() {
+            self.check_derivation_path(path)?;
+        }
+        self.check_amount(request)?;
+        self.check_frost_session_amount(request)?;
+        if let Some(requests) = &self.requests
+            && !requests.try_acquire()
+        {
+            return Err(PolicyError::RateLimited(requests.limit));
+        }
+        if matches!(request, RemoteSignerRequest::SignFrost { .. })
+            && let Some(frost_signatures) = &self.frost_signatures
+            && !frost_signatures.try_acquire()
+        {
+            return Err(PolicyError::FrostLimited(frost_signatures.limit));
+        }
+        Ok(())
+    }
+
+    fn check_amount(&self, request: &RemoteSignerRequest) -> Result<(), PolicyError> {
+        let Some(limit) = self.max_amount_per_request else {
+            return Ok(());
+        };
+        let Some(context) = request.context() else {
+            // Signatures of the identity key authorize transfers, so without a context the
+            // amount they move can't be checked.
+            if signs_with_identity_key(request) {
+                return Err(PolicyError::MissingContext(request.method()));
+            }
+            return Ok(());
+        };
+        if let Some(amount) = limited_amount(request, context)?
+            && amount > u128::from(limit)
+        {
+            return Err(PolicyError::AmountLimited { amount, limit });
+        }
+        Ok(())
+    }
+
+    fn check_frost_session_amount(&self, request: &RemoteSignerRequest) -> Result<(), PolicyError> {
+        let Some(limit) = self.max_amount_per_frost_session else {
+            return Ok(());
+        };
+        let RemoteSignerRequest::SignFrost { request: frost } = request else {
+            return Ok(());
+        };
+        if let Some(amount) = limited_amount(request, &frost.context)?
+            && amount > u128::from(limit)
+        {
+            return Err(PolicyError::FrostAmountLimited { amount, limit });
+        }
+        Ok(())
+    }
+
+    fn check_derivation_path(&self, path: &str) -> Result<(), PolicyError> {
+        if self.allowed_derivation_paths.is_empty() {
+            return Ok(());
+        }
+        let derivation_path = DerivationPath::from_str(path)
+            .map_err(|_| PolicyError::InvalidDerivationPath(path.to_string()))?;
+        let allowed = self
+            .allowed_derivation_paths
+            .iter()
+            .any(|allowed| derivation_path.as_ref().starts_with(allowed.as_ref()));
+        if !allowed {
+            return Err(PolicyError::DerivationPathNotAllowed(path.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// The amount in sats to check against the limits, or `None` if the request moves no sats.
+///
+/// The operation is reported by the client, so it's only trusted to exempt requests that can't
+/// move leaves: every FROST signature share moves one, whatever operation it's labelled with.
+/// Any other operation must state its amount.
+fn limited_amount(
+    request: &RemoteSignerRequest,
+    context: &ExternalSigningContext,
+) -> Result<Option<u128>, PolicyError> {
+    let is_frost = matches!(request, RemoteSignerRequest::SignFrost { .. });
+    match context.operation {
+        // Token amounts are in base units of the token and can't be compared to a sats limit.
+        ExternalSigningOperation::TokenTransaction if is_frost => {
+            Err(PolicyError::MissingAmount(context.operation))
+        }
+        ExternalSigningOperation::Authentication
+        | ExternalSigningOperation::LightningReceive
+        | ExternalSigningOperation::SparkInvoice
+        | ExternalSigningOperation::SignMessage
+        | ExternalSigningOperation::TokenTransaction
+            if !is_frost =>
+        {
+            Ok(None)
+        }
+        _ => context
+            .amount
+            .map(Some)
+            .ok_or(PolicyError::MissingAmount(context.operation)),
+    }
+}
+
+/// Whether the request signs with the identity key, whose signatures authorize transfers. Paths
+/// are relative to the identity key, so the identity key itself is the master path.
+fn signs_with_identity_key(request: &RemoteSignerRequest) -> bool {
+    match request {
+        RemoteSignerRequest::SignEcdsa { path, .. }
+        | RemoteSignerRequest::SignHashSchnorr { path, .. } => {
+            DerivationPath::from_str(path).map_or(true, |path| path.is_master())
+        }
+        _ => false,
+    }
+}
+
+/// Allows up to `limit` calls per sliding window of a minute.
+struct RateLimiter {
+    limit: u32,
+    calls: Mutex<VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            calls: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn try_acquire(&self) -> bool {
+        let now = Instant::now();
+        let mut calls = self.calls.lock().expect("rate limiter lock poisoned");
+        while calls
+            .front()
+            .is_some_and(|call| now.duration_since(*call) >= RATE_LIMIT_WINDOW)
+        {
+            calls.pop_front();
+        }
+        if u32::try_from(calls.len()).unwrap_or(u32::MAX) >= self.limit {
+            return false;
+        }
+        calls.push_back(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use breez_sdk_spark::signer::{
+        ExternalEncryptedSecret, ExternalFrostCommitments, ExternalSecretSource,
+        ExternalSignFrostRequest, ExternalSigningContext, ExternalSigningOperation, MessageBytes,
+        RemoteSignerRequest,
+    };
+
+    use super::{PolicyError, SigningPolicy};
+
+    fn context(operation: ExternalSigningOperation, amount: u128) -> ExternalSigningContext {
+        ExternalSigningContext {
+            operation,
+            amount: Some(amount),
+            counterparty: None,
+            leaf_ids: Vec::new(),
+        }
+    }
+
+    fn derive(path: &str) -> RemoteSignerRequest {
+        RemoteSignerRequest::DerivePublicKey {
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_allowed_derivation_paths() {
+        let policy =
+            SigningPolicy::new(&["m/8797555'".to_string()], None, None, None, None).unwrap();
+
+        assert!(policy.check(&derive("m/8797555'")).is_ok());
+        assert!(policy.check(&derive("m/8797555'/1'/0'")).is_ok());
+        assert!(matches!(
+            policy.check(&derive("m/44'/0'/0'")),
+            Err(PolicyError::DerivationPathNotAllowed(_))
+        ));
+        assert!(matches!(
+            policy.check(&derive("not a path")),
+            Err(PolicyError::InvalidDerivationPath(_))
+        ));
+        // Requests without a derivation path aren't restricted by the allowed paths.
+        assert!(
+            policy
+                .check(&RemoteSignerRequest::GenerateRandomSecret)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_all_derivation_paths_allowed_by_default() {
+        let policy = SigningPolicy::new(&[], None, None, None, None).unwrap();
+
+        assert!(policy.check(&derive("m/44'/0'/0'")).is_ok());
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let policy = SigningPolicy::new(&[], Some(2), None, None, None).unwrap();
+
+        assert!(policy.check(&derive("m/0'")).is_ok());
+        assert!(policy.check(&derive("m/0'")).is_ok());
+        assert!(matches!(
+            policy.check(&derive("m/0'")),
+            Err(PolicyError::RateLimited(2))
+        ));
+    }
+
+    #[test]
+    fn test_amount_limit() {
+        let policy = SigningPolicy::new(&[], None, None, Some(1_000), None).unwrap();
+        let sign = |operation, amount| RemoteSignerRequest::SignHashSchnorr {
+            hash: vec![0; 32],
+            path: "m/0'".to_string(),
+            context: Some(context(operation, amount)),
+        };
+
+        assert!(
+            policy
+                .check(&sign(ExternalSigningOperation::Transfer, 1_000))
+                .is_ok()
+        );
+        assert!(matches!(
+            policy.check(&sign(ExternalSigningOperation::Transfer, 1_001)),
+            Err(PolicyError::AmountLimited {
+                amount: 1_001,
+                limit: 1_000
+            })
+        ));
+        assert!(matches!(
+            policy.check(&RemoteSignerRequest::SignHashSchnorr {
+                hash: vec![0; 32],
+                path: "m".to_string(),
+                context: None,
+            }),
+            Err(PolicyError::MissingContext("sign_hash_schnorr"))
+        ));
+        // Keys other than the identity key can sign without a context, like for lnurl-auth.
+        assert!(
+            policy
+                .check(&RemoteSignerRequest::SignEcdsa {
+                    message: MessageBytes::new(vec![0; 32]),
+                    path: "m/138'/0".to_string(),
+                    context: None,
+                })
+                .is_ok()
+        );
+        // Incoming operations move leaves too, only operations moving no sats aren't limited.
+        assert!(matches!(
+            policy.check(&sign(ExternalSigningOperation::ClaimTransfer, 5_000)),
+            Err(PolicyError::AmountLimited { .. })
+        ));
+        assert!(
+            policy
+                .check(&sign(ExternalSigningOperation::TokenTransaction, 5_000))
+                .is_ok()
+        );
+        // Operations moving sats must state their amount.
+        assert!(matches!(
+            policy.check(&RemoteSignerRequest::SignHashSchnorr {
+                hash: vec![0; 32],
+                path: "m".to_string(),
+                context: Some(ExternalSigningContext {
+                    amount: None,
+                    ..context(ExternalSigningOperation::Transfer, 0)
+                }),
+            }),
+            Err(PolicyError::MissingAmount(
+                ExternalSigningOperation::Transfer
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_frost_session_amount_limit() {
+        let policy = SigningPolicy::new(&[], None, None, None, Some(1_000)).unwrap();
+        let sign_frost = |operation, amount: Option<u128>| RemoteSignerRequest::SignFrost {
+            request: ExternalSignFrostRequest {
+                message: vec![0; 32],
+                public_key: vec![2; 33],
+                secret: ExternalSecretSource::Encrypted {
+                    key: ExternalEncryptedSecret {
+                        ciphertext: Vec::new(),
+                    },
+                },
+                verifying_key: vec![2; 33],
+                self_nonce_commitment: ExternalFrostCommitments {
+                    hiding_commitment: Vec::new(),
+                    binding_commitment: Vec::new(),
+                    nonces_ciphertext: Vec::new(),
+                },
+                statechain_commitments: Vec::new(),
+                adaptor_public_key: None,
+                context: ExternalSigningContext {
+                    amount,
+                    ..context(operation, 0)
+                },
+            },
+        };
+
+        assert!(
+            policy
+                .check(&sign_frost(ExternalSigningOperation::Transfer, Some(1_000)))
+                .is_ok()
+        );
+        assert!(matches!(
+            policy.check(&sign_frost(ExternalSigningOperation::CoopExit, Some(1_001))),
+            Err(PolicyError::FrostAmountLimited {
+                amount: 1_001,
+                limit: 1_000
+            })
+        ));
+        assert!(matches!(
+            policy.check(&sign_frost(
+                ExternalSigningOperation::ClaimTransfer,
+                Some(5_000)
+            )),
+            Err(PolicyError::FrostAmountLimited { .. })
+        ));
+        // Every signature share moves a leaf, whatever operation it's labelled with.
+        assert!(matches!(
+            policy.check(&sign_frost(ExternalSigningOperation::Authentication, None)),
+            Err(PolicyError::MissingAmount(
+                ExternalSigningOperation::Authentication
+            ))
+        ));
+        assert!(matches!(
+            policy.check(&sign_frost(
+                ExternalSigningOperation::TokenTransaction,
+                Some(1)
+            )),
+            Err(PolicyError::MissingAmount(
+                ExternalSigningOperation::TokenTransaction
+            ))
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use bitcoin::hashes::{Hash, sha256};
+use breez_sdk_spark::{
+    SignerError,
+    signer::{ExternalSigner, RemoteSignerRequest},
+};
+use tonic::{Request, Response, Status};
+use tracing::{debug, error, warn};
+
+use crate::{
+    audit::{AuditEntry, AuditLog, AuditOutcome},
+    auth::ClientId,
+    policy::{PolicyError, SigningPolicy},
+    proto::{SignReply, SignRequest, remote_signer_server::RemoteSigner},
+    time::now,
+};
+
+pub struct SignerServer {
+    signer: Arc<dyn ExternalSigner>,
+    policy: SigningPolicy,
+    audit_log: AuditLog,
+}
+
+impl SignerServer {
+    pub fn new(
+        signer: Arc<dyn ExternalSigner>,
+        policy: SigningPolicy,
+        audit_log: AuditLog,
+    ) -> Self {
+        Self {
+            signer,
+            policy,
+            audit_log,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl RemoteSigner for SignerServer {
+    async fn sign(&self, request: Request<SignRequest>) -> Result<Response<SignReply>, Status> {
+        let peer_address = request.remote_addr().map(|addr| addr.to_string());
+        let client_id = request
+            .extensions()
+            .get::<ClientId>()
+            .map(|client_id| client_id.0.clone())
+            .unwrap_or_default();
+        let payload = request.into_inner().payload;
+        let signer_request: RemoteSignerRequest = serde_json::from_slice(&payload)
+            .map_err(|e| Status::invalid_argument(format!("invalid signer request: {e}")))?;
+
+        let mut entry = AuditEntry {
+            time: now(),
+            client_id,
+            peer_address,
+            method: signer_request.method(),
+            derivation_path: signer_request.derivation_path().map(ToString::to_string),
+            operation: signer_request.context().map(|context| context.operation),
+            amount: signer_request.context().and_then(|context| context.amount),
+            counterparty: signer_request
+                .context()
+                .and_then(|context| context.counterparty.clone()),
+            request_hash: sha256::Hash::hash(&payload).to_string(),
+            outcome: AuditOutcome::Signed,
+            error: None,
+        };
+        debug!(
+            "{} requested {} at path {:?}",
+            entry.client_id, entry.method, entry.derivation_path
+        );
+
+        let result = match self.policy.check(&signer_request) {
+            Ok(()) => signer_request
+                .execute(self.signer.as_ref())
+                .await
+                .map_err(|e| {
+                    entry.outcome = AuditOutcome::Failed;
+                    entry.error = Some(e.to_string());
+                    signer_error_to_status(e)
+                }),
+            Err(e) => {
+                warn!(
+                    "denied {} request of {}: {e}",
+                    entry.method, entry.client_id
+                );
+                entry.outcome = AuditOutcome::Denied;
+                entry.error = Some(e.to_string());
+                Err(policy_error_to_status(&e))
+            }
+        };
+
+        // No signature leaves the server without an audit trail.
+        self.audit_log.append(&entry).await.map_err(|e| {
+            error!("failed to write audit log: {e}");
+            Status::internal("failed to write audit log")
+        })?;
+
+        Ok(Response::new(SignReply { payload: result? }))
+    }
+}
+
+fn policy_error_to_status(e: &PolicyError) -> Status {
+    match e {
+        PolicyError::RateLimited(_) | PolicyError::FrostLimited(_) => {
+            Status::resource_exhausted(e.to_string())
+        }
+        PolicyError::MissingContext(_) | PolicyError::MissingAmount(_) => {
+            Status::invalid_argument(e.to_string())
+        }
+        PolicyError::DerivationPathNotAllowed(_)
+        | PolicyError::InvalidDerivationPath(_)
+        | PolicyError::AmountLimited { .. }
+        | PolicyError::FrostAmountLimited { .. } => Status::permission_denied(e.to_string()),
+    }
+}
+
+fn signer_error_to_status(e: SignerError) -> Status {
+    match e {
+        SignerError::InvalidInput(e) => Status::invalid_argument(e),
+        SignerError::PolicyViolation(e) => Status::permission_denied(e),
+        e => Status::internal(e.to_string()),
+    }
+}
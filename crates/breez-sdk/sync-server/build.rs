@@ -0,0 +1,10 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // The protocol is shared with the sdk client, see common/src/sync/proto/sync.proto
+    let proto = "../common/src/sync/proto/sync.proto";
+    println!("cargo:rerun-if-changed={proto}");
+    tonic_build::configure()
+        .build_server(true)
+        .build_client(false)
+        .compile_protos(&[proto], &["../common/src/sync/proto"])?;
+    Ok(())
+}
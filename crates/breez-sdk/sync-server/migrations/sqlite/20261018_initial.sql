@@ -0,0 +1,23 @@
+CREATE TABLE user_revisions (
+    user_id TEXT PRIMARY KEY,
+    revision BIGINT NOT NULL
+);
+
+CREATE TABLE records (
+    user_id TEXT NOT NULL,
+    id TEXT NOT NULL,
+    revision BIGINT NOT NULL,
+    schema_version TEXT NOT NULL,
+    data BLOB NOT NULL,
+    PRIMARY KEY (user_id, id)
+);
+CREATE INDEX records_user_id_revision_idx ON records(user_id, revision);
+
+CREATE TABLE locks (
+    user_id TEXT NOT NULL,
+    lock_name TEXT NOT NULL,
+    instance_id TEXT NOT NULL,
+    exclusive INTEGER NOT NULL,
+    expires_at BIGINT NOT NULL,
+    PRIMARY KEY (user_id, lock_name, instance_id)
+);
@@ -0,0 +1,166 @@
+use std::sync::{Arc, LazyLock};
+
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use bitcoin::{
+    hashes::{Hash, sha256d},
+    secp256k1::{
+        Message, Secp256k1, VerifyOnly,
+        ecdsa::{RecoverableSignature, RecoveryId},
+    },
+};
+use tonic::{Request, Status, service::Interceptor};
+use tracing::{debug, error};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+use crate::{time::now, zbase32::decode_zbase32};
+
+const MESSAGE_PREFIX: &[u8; 13] = b"realtimesync:";
+
+/// Header byte of a compact recoverable signature of a compressed public key, the recovery id
+/// is added to it.
+const COMPRESSED_HEADER: u8 = 31;
+
+static SECP: LazyLock<Secp256k1<VerifyOnly>> = LazyLock::new(Secp256k1::verification_only);
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuthError {
+    #[error("request time out of range")]
+    RequestTime,
+    #[error("invalid signature")]
+    InvalidSignature,
+}
+
+/// Verifies the signature of a request and returns the id of the user that signed it, the hex
+/// encoded public key recovered from the signature.
+///
+/// The signature is made over the double sha256 of the prefixed message, see
+/// `common/src/sync/signing_client.rs` for the messages signed per request type.
+pub fn verify_request(
+    msg: &str,
+    signature: &str,
+    request_time: u32,
+    max_request_age_secs: u32,
+) -> Result<String, AuthError> {
+    if now().abs_diff(i64::from(request_time)) > u64::from(max_request_age_secs) {
+        return Err(AuthError::RequestTime);
+    }
+    recover_user_id(msg, signature)
+}
+
+/// Recovers the hex encoded public key that signed the message.
+fn recover_user_id(msg: &str, signature: &str) -> Result<String, AuthError> {
+    let signature = decode_zbase32(signature).ok_or(AuthError::InvalidSignature)?;
+    let Some((&header, compact)) = signature.split_first() else {
+        return Err(AuthError::InvalidSignature);
+    };
+    let recovery_id = header
+        .checked_sub(COMPRESSED_HEADER)
+        .and_then(|id| RecoveryId::from_i32(i32::from(id)).ok())
+        .ok_or(AuthError::InvalidSignature)?;
+    let signature = RecoverableSignature::from_compact(compact, recovery_id)
+        .map_err(|_| AuthError::InvalidSignature)?;
+
+    let hash = sha256d::Hash::hash(&[MESSAGE_PREFIX, msg.as_bytes()].concat());
+    let message = Message::from_digest(hash.to_byte_array());
+    let pubkey = SECP
+        .recover_ecdsa(&message, &signature)
+        .map_err(|_| AuthError::InvalidSignature)?;
+    Ok(pubkey.to_string())
+}
+
+/// Validates the api key passed as bearer token against the configured CA certificate. All
+/// requests are allowed if no CA certificate is configured.
+#[derive(Clone)]
+pub struct ApiKeyInterceptor {
+    pub ca_cert: Option<Arc<Vec<u8>>>,
+}
+
+impl Interceptor for ApiKeyInterceptor {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let Some(ca_cert) = self.ca_cert.as_ref() else {
+            return Ok(request);
+        };
+
+        let Ok((_, ca_cert)) = X509Certificate::from_der(ca_cert.as_ref()) else {
+            error!("Failed to parse CA certificate");
+            return Err(Status::internal("internal server error"));
+        };
+
+        let Some(auth_header) = request
+            .metadata()
+            .get("authorization")
+            .and_then(|header| header.to_str().ok())
+        else {
+            return Err(Status::unauthenticated("missing api key"));
+        };
+
+        let Ok(cert_bytes) = BASE64.decode(auth_header.trim_start_matches("Bearer ").trim()) else {
+            debug!("Failed to decode base64 certificate");
+            return Err(Status::unauthenticated("invalid api key"));
+        };
+
+        let (_, cert) = X509Certificate::from_der(&cert_bytes).map_err(|e| {
+            debug!("Failed to parse client certificate: {}", e);
+            Status::unauthenticated("invalid api key")
+        })?;
+
+        if cert.issuer() != ca_cert.subject() {
+            debug!("Client certificate issuer does not match CA subject");
+            return Err(Status::unauthenticated("invalid api key"));
+        }
+        if let Err(e) = cert.verify_signature(Some(ca_cert.public_key())) {
+            debug!("Failed to verify client certificate: {e:?}");
+            return Err(Status::unauthenticated("invalid api key"));
+        }
+
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthError, recover_user_id, verify_request};
+
+    /// Public key of the secret key 0x0101..01
+    const USER_ID: &str = "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f";
+    /// Signature of the listen changes message "1700000000" by the secret key 0x0101..01
+    const SIGNATURE: &str = "ry31j1y9hq4x6yq5p33y4pim6affnqrzw1yj1q9jxczo7r4k7yiuh36sk1bgs4isqwmoebb74m9ddn74qbeerr9npj9tzzpkaqir68fd";
+
+    #[test]
+    fn test_recover_user_id() {
+        assert_eq!(recover_user_id("1700000000", SIGNATURE).unwrap(), USER_ID);
+        // A signature of another message recovers another key
+        assert_ne!(recover_user_id("1700000001", SIGNATURE).unwrap(), USER_ID);
+    }
+
+    #[test]
+    fn test_recover_user_id_invalid_signature() {
+        assert!(matches!(
+            recover_user_id("1700000000", "not zbase32!"),
+            Err(AuthError::InvalidSignature)
+        ));
+        assert!(matches!(
+            recover_user_id("1700000000", ""),
+            Err(AuthError::InvalidSignature)
+        ));
+        // Header byte out of the range of compressed key headers
+        let uncompressed = format!("o{}", &SIGNATURE[1..]);
+        assert!(matches!(
+            recover_user_id("1700000000", &uncompressed),
+            Err(AuthError::InvalidSignature)
+        ));
+        // Truncated signature
+        assert!(matches!(
+            recover_user_id("1700000000", &SIGNATURE[..100]),
+            Err(AuthError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_verify_request_time() {
+        assert!(matches!(
+            verify_request("1700000000", SIGNATURE, 1_700_000_000, 60),
+            Err(AuthError::RequestTime)
+        ));
+    }
+}
@@ -0,0 +1,36 @@
+use sqlx::migrate::MigrateError;
+use tonic::Status;
+use tracing::error;
+
+use crate::{auth::AuthError, repository::SyncRepositoryError};
+
+impl From<sqlx::Error> for SyncRepositoryError {
+    fn from(err: sqlx::Error) -> Self {
+        SyncRepositoryError::General(err.into())
+    }
+}
+
+impl From<MigrateError> for SyncRepositoryError {
+    fn from(err: MigrateError) -> Self {
+        SyncRepositoryError::General(err.into())
+    }
+}
+
+impl From<SyncRepositoryError> for Status {
+    fn from(err: SyncRepositoryError) -> Self {
+        match err {
+            SyncRepositoryError::Conflict => Status::aborted(err.to_string()),
+            SyncRepositoryError::LockHeld => Status::failed_precondition(err.to_string()),
+            SyncRepositoryError::General(e) => {
+                error!("database error: {e:?}");
+                Status::internal("internal server error")
+            }
+        }
+    }
+}
+
+impl From<AuthError> for Status {
+    fn from(err: AuthError) -> Self {
+        Status::unauthenticated(err.to_string())
+    }
+}
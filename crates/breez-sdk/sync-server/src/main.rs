@@ -0,0 +1,171 @@
+use crate::{
+    auth::ApiKeyInterceptor, proto::syncer_server::SyncerServer, repository::SyncRepository,
+    server::SyncServer,
+};
+use anyhow::anyhow;
+use base64::{Engine, prelude::BASE64_STANDARD};
+use clap::Parser;
+use figment::{
+    Figment,
+    providers::{Env, Format, Serialized, Toml},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, SqlitePool, sqlite::SqlitePoolOptions};
+use std::{path::PathBuf, sync::Arc};
+use tracing::{debug, info};
+use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+mod auth;
+mod error;
+mod postgresql;
+mod repository;
+mod server;
+mod sqlite;
+mod time;
+mod zbase32;
+
+#[allow(clippy::doc_markdown, clippy::pedantic)]
+mod proto {
+    tonic::include_proto!("sync");
+}
+
+#[derive(Clone, Parser, Debug, Serialize, Deserialize)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Address the sync server will listen on.
+    #[arg(long, default_value = "0.0.0.0:8080")]
+    pub address: core::net::SocketAddr,
+
+    #[arg(long, default_value = "sync-server.conf")]
+    pub config: PathBuf,
+
+    /// Automatically apply migrations to the database.
+    #[arg(long)]
+    pub auto_migrate: bool,
+
+    /// Connection string to the database. Connection strings starting with `postgres` use
+    /// postgres, anything else is a sqlite database.
+    #[arg(long, default_value = "")]
+    pub db_url: String,
+
+    /// Loglevel to use. Can be used to filter logs through the env filter
+    /// format.
+    #[arg(long, default_value = "info")]
+    pub log_level: String,
+
+    /// Base64 encoded DER format CA certificate without begin/end certificate markers.
+    /// If set, the server will use this certificate to validate api keys.
+    #[arg(long)]
+    pub ca_cert: Option<String>,
+
+    /// Maximum difference (in seconds) between the signed request time and the server time.
+    /// Older requests are rejected, so signed requests can't be replayed later.
+    #[arg(long, default_value = "300")]
+    pub max_request_age_secs: u32,
+
+    /// How long (in seconds) a lock is held when the client doesn't set a ttl. Locks of
+    /// instances that go away without releasing them expire after this time.
+    #[arg(long, default_value = "300")]
+    pub lock_ttl_secs: u32,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    let args = Args::parse();
+    let config_file = std::fs::canonicalize(&args.config).ok();
+    let mut figment = Figment::new().merge(Serialized::defaults(args));
+    if let Some(config_file) = &config_file {
+        figment = figment.merge(Toml::file(config_file));
+    }
+
+    let args: Args = figment.merge(Env::prefixed("BREEZ_SYNC_")).extract()?;
+
+    tracing_subscriber::registry()
+        .with(EnvFilter::new(&args.log_level))
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
+
+    if let Some(config_file) = &config_file {
+        info!(
+            "starting sync server with config file: {}",
+            config_file.display()
+        );
+    } else {
+        info!("starting sync server without config file");
+    }
+
+    if args.db_url.trim().to_lowercase().starts_with("postgres") {
+        let pool = PgPool::connect(&args.db_url)
+            .await
+            .map_err(|e| anyhow!("failed to create connection pool: {e:?}"))?;
+
+        if args.auto_migrate {
+            debug!("running postgres database migrations");
+            postgresql::run_migrations(&pool).await?;
+            debug!("finished running postgres database migrations");
+        } else {
+            debug!("skipping postgres database migrations");
+        }
+        let repository = postgresql::SyncRepository::new(pool);
+        run_server(args, repository).await?;
+    } else {
+        // For in-memory databases, limit to 1 connection so all queries share
+        // the same database. Each separate connection to `:memory:` creates its
+        // own independent database.
+        let pool = if args.db_url.contains(":memory:") {
+            SqlitePoolOptions::new()
+                .max_connections(1)
+                .connect(&args.db_url)
+                .await
+        } else {
+            SqlitePool::connect(&args.db_url).await
+        }
+        .map_err(|e| anyhow!("failed to create connection pool: {e:?}"))?;
+
+        if args.auto_migrate {
+            debug!("running sqlite database migrations");
+            sqlite::run_migrations(&pool).await?;
+            debug!("finished running sqlite database migrations");
+        } else {
+            debug!("skipping sqlite database migrations");
+        }
+        let repository = sqlite::SyncRepository::new(pool);
+        run_server(args, repository).await?;
+    }
+
+    Ok(())
+}
+
+async fn run_server<DB>(args: Args, repository: DB) -> Result<(), anyhow::Error>
+where
+    DB: SyncRepository + Send + Sync + 'static,
+{
+    let ca_cert = args
+        .ca_cert
+        .map(|ca_cert_str| {
+            let raw_ca = BASE64_STANDARD
+                .decode(ca_cert_str.trim())
+                .map_err(|e| anyhow!("failed to decode base64 ca_cert: {e:?}"))?;
+            let (_, ca_cert) = X509Certificate::from_der(&raw_ca)
+                .map_err(|e| anyhow!("failed to parse ca certificate: {e:?}"))?;
+            Ok::<_, anyhow::Error>(Arc::new(ca_cert.as_raw().to_vec()))
+        })
+        .transpose()?;
+
+    let server = SyncServer::new(repository, args.max_request_age_secs, args.lock_ttl_secs);
+    let service = SyncerServer::with_interceptor(server, ApiKeyInterceptor { ca_cert });
+
+    info!("Server listening at {}", args.address);
+    tonic::transport::Server::builder()
+        .add_service(service)
+        .serve_with_shutdown(args.address, async {
+            tokio::signal::ctrl_c()
+                .await
+                .expect("failed to create Ctrl+C shutdown signal");
+        })
+        .await?;
+
+    info!("sync server stopped");
+    Ok(())
+}
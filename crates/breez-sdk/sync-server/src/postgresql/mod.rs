@@ -0,0 +1,12 @@
+use sqlx::PgPool;
+
+use crate::repository::SyncRepositoryError;
+
+mod repository;
+
+pub async fn run_migrations(pool: &PgPool) -> Result<(), SyncRepositoryError> {
+    let migrator = sqlx::migrate!("migrations/postgres");
+    Ok(migrator.run(pool).await?)
+}
+
+pub use repository::SyncRepository;
@@ -0,0 +1,75 @@
+#[derive(Debug, thiserror::Error)]
+pub enum SyncRepositoryError {
+    #[error("record revision conflict")]
+    Conflict,
+    #[error("lock held by another instance")]
+    LockHeld,
+    #[error("database error: {0}")]
+    General(anyhow::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub id: String,
+    pub revision: u64,
+    pub schema_version: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LockRequest {
+    pub lock_name: String,
+    pub instance_id: String,
+    pub exclusive: bool,
+    /// Unix timestamp (seconds) after which the lock is released if not acquired again
+    pub expires_at: i64,
+}
+
+#[async_trait::async_trait]
+pub trait SyncRepository {
+    /// Stores the record if `record.revision` matches the revision of the stored record, or the
+    /// record is new. Returns the new revision of the record, which is the next revision of the
+    /// user.
+    async fn set_record(&self, user_id: &str, record: &Record) -> Result<u64, SyncRepositoryError>;
+
+    /// Lists the records changed after the given revision, ordered by revision.
+    async fn list_changes(
+        &self,
+        user_id: &str,
+        since_revision: u64,
+    ) -> Result<Vec<Record>, SyncRepositoryError>;
+
+    /// Acquires the lock for the instance. Fails with [`SyncRepositoryError::LockHeld`] if
+    /// another instance holds the lock exclusively, or the lock is requested exclusively while
+    /// another instance holds it.
+    async fn acquire_lock(
+        &self,
+        user_id: &str,
+        request: &LockRequest,
+        now: i64,
+    ) -> Result<(), SyncRepositoryError>;
+
+    async fn release_lock(
+        &self,
+        user_id: &str,
+        lock_name: &str,
+        instance_id: &str,
+    ) -> Result<(), SyncRepositoryError>;
+
+    /// Whether any instance holds the lock.
+    async fn is_locked(
+        &self,
+        user_id: &str,
+        lock_name: &str,
+        now: i64,
+    ) -> Result<bool, SyncRepositoryError>;
+}
+
+/// Revisions are stored as signed integers, the databases don't support unsigned ones.
+pub fn to_revision(revision: u64) -> Result<i64, SyncRepositoryError> {
+    i64::try_from(revision).map_err(|e| SyncRepositoryError::General(e.into()))
+}
+
+pub fn from_revision(revision: i64) -> Result<u64, SyncRepositoryError> {
+    u64::try_from(revision).map_err(|e| SyncRepositoryError::General(e.into()))
+}
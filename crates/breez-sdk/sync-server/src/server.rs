@@ -0,0 +1,293 @@
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::broadcast;
+use tokio_stream::{Stream, StreamExt, wrappers::BroadcastStream};
+use tonic::{Request, Response, Status};
+use tracing::{debug, trace};
+
+use crate::{
+    auth::{AuthError, verify_request},
+    proto::{
+        GetLockReply, GetLockRequest, ListChangesReply, ListChangesRequest, ListenChangesRequest,
+        Notification, SetLockReply, SetLockRequest, SetRecordReply, SetRecordRequest,
+        SetRecordStatus, syncer_server::Syncer,
+    },
+    repository::{LockRequest, Record, SyncRepository, SyncRepositoryError},
+    time::now,
+};
+
+/// Number of notifications buffered per user. A listener that falls further behind gets a single
+/// notification instead, which makes it fetch all changes anyway.
+const NOTIFICATION_BUFFER: usize = 32;
+
+pub struct SyncServer<DB> {
+    db: DB,
+    notifier: Notifier,
+    max_request_age_secs: u32,
+    lock_ttl_secs: u32,
+}
+
+impl<DB> SyncServer<DB> {
+    pub fn new(db: DB, max_request_age_secs: u32, lock_ttl_secs: u32) -> Self {
+        SyncServer {
+            db,
+            notifier: Notifier::default(),
+            max_request_age_secs,
+            lock_ttl_secs,
+        }
+    }
+
+    fn verify(&self, msg: &str, signature: &str, request_time: u32) -> Result<String, AuthError> {
+        verify_request(msg, signature, request_time, self.max_request_age_secs)
+    }
+}
+
+#[tonic::async_trait]
+impl<DB> Syncer for SyncServer<DB>
+where
+    DB: SyncRepository + Send + Sync + 'static,
+{
+    async fn set_record(
+        &self,
+        request: Request<SetRecordRequest>,
+    ) -> Result<Response<SetRecordReply>, Status> {
+        let request = request.into_inner();
+        let Some(record) = request.record else {
+            return Err(Status::invalid_argument("missing record"));
+        };
+        let msg = format!(
+            "{}-{}-{}-{}-{}",
+            record.id,
+            hex::encode(&record.data),
+            record.revision,
+            record.schema_version,
+            request.request_time
+        );
+        let user_id = self.verify(&msg, &request.signature, request.request_time)?;
+
+        let record = Record {
+            id: record.id,
+            revision: record.revision,
+            schema_version: record.schema_version,
+            data: record.data,
+        };
+        let reply = match self.db.set_record(&user_id, &record).await {
+            Ok(new_revision) => {
+                trace!(
+                    "user {user_id} set record {} with revision {new_revision}",
+                    record.id
+                );
+                self.notifier.notify(
+                    &user_id,
+                    Notification {
+                        client_id: request.client_id,
+                    },
+                );
+                SetRecordReply {
+                    status: SetRecordStatus::Success.into(),
+                    new_revision,
+                }
+            }
+            Err(SyncRepositoryError::Conflict) => {
+                debug!(
+                    "user {user_id} set record {} with outdated revision {}",
+                    record.id, record.revision
+                );
+                SetRecordReply {
+                    status: SetRecordStatus::Conflict.into(),
+                    new_revision: 0,
+                }
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Response::new(reply))
+    }
+
+    async fn list_changes(
+        &self,
+        request: Request<ListChangesRequest>,
+    ) -> Result<Response<ListChangesReply>, Status> {
+        let request = request.into_inner();
+        let msg = format!("{}-{}", request.since_revision, request.request_time);
+        let user_id = self.verify(&msg, &request.signature, request.request_time)?;
+
+        let changes = self
+            .db
+            .list_changes(&user_id, request.since_revision)
+            .await?
+            .into_iter()
+            .map(|record| crate::proto::Record {
+                id: record.id,
+                revision: record.revision,
+                schema_version: record.schema_version,
+                data: record.data,
+            })
+            .collect();
+        Ok(Response::new(ListChangesReply { changes }))
+    }
+
+    type ListenChangesStream =
+        Pin<Box<dyn Stream<Item = Result<Notification, Status>> + Send + 'static>>;
+
+    async fn listen_changes(
+        &self,
+        request: Request<ListenChangesRequest>,
+    ) -> Result<Response<Self::ListenChangesStream>, Status> {
+        let request = request.into_inner();
+        let msg = request.request_time.to_string();
+        let user_id = self.verify(&msg, &request.signature, request.request_time)?;
+
+        debug!("user {user_id} started listening to changes");
+        let stream = self.notifier.listen(&user_id).map(Ok);
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn set_lock(
+        &self,
+        request: Request<SetLockRequest>,
+    ) -> Result<Response<SetLockReply>, Status> {
+        let request = request.into_inner();
+        let msg = format!(
+            "{}-{}-{}-{}-{}",
+            request.lock_name,
+            request.instance_id,
+            request.acquire,
+            request.exclusive,
+            request.request_time
+        );
+        let user_id = self.verify(&msg, &request.signature, request.request_time)?;
+
+        if !request.acquire {
+            self.db
+                .release_lock(&user_id, &request.lock_name, &request.instance_id)
+                .await?;
+            return Ok(Response::new(SetLockReply {}));
+        }
+
+        let now = now();
+        let ttl_seconds = request.ttl_seconds.unwrap_or(self.lock_ttl_secs);
+        let lock = LockRequest {
+            lock_name: request.lock_name,
+            instance_id: request.instance_id,
+            exclusive: request.exclusive,
+            expires_at: now.saturating_add(i64::from(ttl_seconds)),
+        };
+        self.db.acquire_lock(&user_id, &lock, now).await?;
+        Ok(Response::new(SetLockReply {}))
+    }
+
+    async fn get_lock(
+        &self,
+        request: Request<GetLockRequest>,
+    ) -> Result<Response<GetLockReply>, Status> {
+        let request = request.into_inner();
+        let msg = format!("{}-{}", request.lock_name, request.request_time);
+        let user_id = self.verify(&msg, &request.signature, request.request_time)?;
+
+        let locked = self
+            .db
+            .is_locked(&user_id, &request.lock_name, now())
+            .await?;
+        Ok(Response::new(GetLockReply { locked }))
+    }
+}
+
+/// Notifies the listeners of a user about changes. Listeners are kept in memory, so only
+/// listeners connected to the same server instance as the writer are notified.
+#[derive(Clone, Default)]
+struct Notifier {
+    channels: Arc<Mutex<HashMap<String, broadcast::Sender<Notification>>>>,
+}
+
+impl Notifier {
+    fn subscribe(&self, user_id: &str) -> broadcast::Receiver<Notification> {
+        let mut channels = self.channels.lock().expect("notifier lock poisoned");
+        channels
+            .entry(user_id.to_string())
+            .or_insert_with(|| broadcast::channel(NOTIFICATION_BUFFER).0)
+            .subscribe()
+    }
+
+    /// Streams the notifications of a user.
+    fn listen(&self, user_id: &str) -> impl Stream<Item = Notification> + Send + Unpin + use<> {
+        BroadcastStream::new(self.subscribe(user_id))
+            // A lagging listener missed notifications, the client id is left out so the client
+            // fetches the changes regardless of who made them.
+            .map(|notification| notification.unwrap_or(Notification { client_id: None }))
+    }
+
+    fn notify(&self, user_id: &str, notification: Notification) {
+        let mut channels = self.channels.lock().expect("notifier lock poisoned");
+        let Some(sender) = channels.get(user_id) else {
+            return;
+        };
+
+        // Sending only fails when all listeners are gone
+        if sender.send(notification).is_err() {
+            channels.remove(user_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_stream::StreamExt;
+
+    use super::{NOTIFICATION_BUFFER, Notifier};
+    use crate::proto::Notification;
+
+    fn notification(client_id: &str) -> Notification {
+        Notification {
+            client_id: Some(client_id.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notifier_notifies_listeners_of_user() {
+        let notifier = Notifier::default();
+        let mut first = notifier.listen("user");
+        let mut second = notifier.listen("user");
+        let mut other_user = notifier.listen("other");
+
+        notifier.notify("user", notification("client"));
+
+        assert_eq!(first.next().await, Some(notification("client")));
+        assert_eq!(second.next().await, Some(notification("client")));
+        notifier.notify("other", notification("other client"));
+        assert_eq!(other_user.next().await, Some(notification("other client")));
+    }
+
+    #[tokio::test]
+    async fn test_notifier_lagging_listener() {
+        let notifier = Notifier::default();
+        let mut listener = notifier.listen("user");
+
+        for i in 0..=NOTIFICATION_BUFFER {
+            notifier.notify("user", notification(&i.to_string()));
+        }
+
+        // The missed notifications are replaced by one without client id, then the buffered
+        // ones follow.
+        assert_eq!(
+            listener.next().await,
+            Some(Notification { client_id: None })
+        );
+        assert_eq!(listener.next().await, Some(notification("1")));
+    }
+
+    #[test]
+    fn test_notifier_removes_users_without_listeners() {
+        let notifier = Notifier::default();
+        drop(notifier.subscribe("user"));
+
+        // Users without listeners are ignored
+        notifier.notify("other", notification("client"));
+        notifier.notify("user", notification("client"));
+
+        assert!(notifier.channels.lock().unwrap().is_empty());
+    }
+}
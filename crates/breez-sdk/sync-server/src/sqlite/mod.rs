@@ -0,0 +1,12 @@
+use sqlx::SqlitePool;
+
+use crate::repository::SyncRepositoryError;
+
+mod repository;
+
+pub async fn run_migrations(pool: &SqlitePool) -> Result<(), SyncRepositoryError> {
+    let migrator = sqlx::migrate!("migrations/sqlite");
+    Ok(migrator.run(pool).await?)
+}
+
+pub use repository::SyncRepository;
@@ -0,0 +1,265 @@
+use sqlx::{Row, SqlitePool};
+
+use crate::repository::{LockRequest, Record, SyncRepositoryError, from_revision, to_revision};
+
+#[derive(Clone)]
+pub struct SyncRepository {
+    pool: SqlitePool,
+}
+
+impl SyncRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        SyncRepository { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::repository::SyncRepository for SyncRepository {
+    async fn set_record(&self, user_id: &str, record: &Record) -> Result<u64, SyncRepositoryError> {
+        let parent_revision = to_revision(record.revision)?;
+
+        // Bumping the user revision first takes the write lock, so concurrent writes of the same
+        // user are serialized.
+        let mut tx = self.pool.begin().await?;
+        let new_revision: i64 = sqlx::query_scalar(
+            "INSERT INTO user_revisions (user_id, revision) VALUES ($1, 1)
+            ON CONFLICT (user_id) DO UPDATE SET revision = user_revisions.revision + 1
+            RETURNING revision",
+        )
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let existing_revision: Option<i64> =
+            sqlx::query_scalar("SELECT revision FROM records WHERE user_id = $1 AND id = $2")
+                .bind(user_id)
+                .bind(&record.id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        if existing_revision.is_some_and(|revision| revision != parent_revision) {
+            return Err(SyncRepositoryError::Conflict);
+        }
+
+        sqlx::query(
+            "INSERT INTO records (user_id, id, revision, schema_version, data)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id, id) DO UPDATE SET
+                revision = excluded.revision
+            ,   schema_version = excluded.schema_version
+            ,   data = excluded.data",
+        )
+        .bind(user_id)
+        .bind(&record.id)
+        .bind(new_revision)
+        .bind(&record.schema_version)
+        .bind(&record.data)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        from_revision(new_revision)
+    }
+
+    async fn list_changes(
+        &self,
+        user_id: &str,
+        since_revision: u64,
+    ) -> Result<Vec<Record>, SyncRepositoryError> {
+        let rows = sqlx::query(
+            "SELECT id, revision, schema_version, data
+            FROM records
+            WHERE user_id = $1 AND revision > $2
+            ORDER BY revision",
+        )
+        .bind(user_id)
+        .bind(to_revision(since_revision)?)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter()
+            .map(|row| {
+                Ok::<_, SyncRepositoryError>(Record {
+                    id: row.try_get(0)?,
+                    revision: from_revision(row.try_get(1)?)?,
+                    schema_version: row.try_get(2)?,
+                    data: row.try_get(3)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn acquire_lock(
+        &self,
+        user_id: &str,
+        request: &LockRequest,
+        now: i64,
+    ) -> Result<(), SyncRepositoryError> {
+        // Sqlite serializes write transactions, the delete takes the write lock before checking
+        // the other holders.
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM locks WHERE user_id = $1 AND lock_name = $2 AND expires_at <= $3")
+            .bind(user_id)
+            .bind(&request.lock_name)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+
+        let held: bool = sqlx::query_scalar(
+            "SELECT EXISTS(
+                SELECT 1 FROM locks
+                WHERE user_id = $1 AND lock_name = $2 AND instance_id <> $3
+                AND (exclusive OR $4)
+            )",
+        )
+        .bind(user_id)
+        .bind(&request.lock_name)
+        .bind(&request.instance_id)
+        .bind(request.exclusive)
+        .fetch_one(&mut *tx)
+        .await?;
+        if held {
+            return Err(SyncRepositoryError::LockHeld);
+        }
+
+        sqlx::query(
+            "INSERT INTO locks (user_id, lock_name, instance_id, exclusive, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id, lock_name, instance_id) DO UPDATE SET
+                exclusive = excluded.exclusive
+            ,   expires_at = excluded.expires_at",
+        )
+        .bind(user_id)
+        .bind(&request.lock_name)
+        .bind(&request.instance_id)
+        .bind(request.exclusive)
+        .bind(request.expires_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn release_lock(
+        &self,
+        user_id: &str,
+        lock_name: &str,
+        instance_id: &str,
+    ) -> Result<(), SyncRepositoryError> {
+        sqlx::query("DELETE FROM locks WHERE user_id = $1 AND lock_name = $2 AND instance_id = $3")
+            .bind(user_id)
+            .bind(lock_name)
+            .bind(instance_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn is_locked(
+        &self,
+        user_id: &str,
+        lock_name: &str,
+        now: i64,
+    ) -> Result<bool, SyncRepositoryError> {
+        let locked: bool = sqlx::query_scalar(
+            "SELECT EXISTS(
+                SELECT 1 FROM locks
+                WHERE user_id = $1 AND lock_name = $2 AND expires_at > $3
+            )",
+        )
+        .bind(user_id)
+        .bind(lock_name)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(locked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::SyncRepository;
+    use crate::repository::{Record, SyncRepository as _, SyncRepositoryError};
+
+    async fn repository() -> SyncRepository {
+        // A single connection, every connection to `:memory:` opens its own database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(":memory:")
+            .await
+            .unwrap();
+        crate::sqlite::run_migrations(&pool).await.unwrap();
+        SyncRepository::new(pool)
+    }
+
+    fn record(id: &str, revision: u64, data: &[u8]) -> Record {
+        Record {
+            id: id.to_string(),
+            revision,
+            schema_version: "0.2.6".to_string(),
+            data: data.to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_record_revision_conflict() {
+        let repository = repository().await;
+
+        assert_eq!(
+            repository
+                .set_record("user", &record("a", 0, b"1"))
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            repository
+                .set_record("user", &record("b", 0, b"1"))
+                .await
+                .unwrap(),
+            2
+        );
+        // Updating from the stored revision succeeds
+        assert_eq!(
+            repository
+                .set_record("user", &record("a", 1, b"2"))
+                .await
+                .unwrap(),
+            3
+        );
+        // Updating from an outdated revision conflicts and leaves the record unchanged
+        assert!(matches!(
+            repository.set_record("user", &record("a", 1, b"3")).await,
+            Err(SyncRepositoryError::Conflict)
+        ));
+        // The conflicting write doesn't use up a revision
+        assert_eq!(
+            repository
+                .set_record("user", &record("c", 0, b"1"))
+                .await
+                .unwrap(),
+            4
+        );
+        // Revisions are counted per user
+        assert_eq!(
+            repository
+                .set_record("other", &record("a", 0, b"1"))
+                .await
+                .unwrap(),
+            1
+        );
+
+        let changes = repository.list_changes("user", 1).await.unwrap();
+        let changes: Vec<_> = changes
+            .iter()
+            .map(|record| (record.id.as_str(), record.revision, record.data.as_slice()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("b", 2, &b"1"[..]),
+                ("a", 3, &b"2"[..]),
+                ("c", 4, &b"1"[..])
+            ]
+        );
+    }
+}
@@ -0,0 +1,40 @@
+/// Z-base-32 encoding alphabet, matching the sdk encoder in `common/src/utils/zbase32.rs`
+const ZBASE32_ALPHABET: &[u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
+
+/// Decode a z-base-32 string into bytes. Trailing bits that don't fill a byte are dropped.
+/// Returns `None` if the string contains characters outside the alphabet.
+#[allow(clippy::arithmetic_side_effects)]
+pub fn decode_zbase32(data: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::with_capacity(data.len() * 5 / 8);
+    let mut buffer: u16 = 0;
+    let mut bits_in_buffer: u8 = 0;
+
+    for c in data.bytes() {
+        let value = ZBASE32_ALPHABET.iter().position(|&a| a == c)?;
+        buffer = (buffer << 5) | u16::try_from(value).ok()?;
+        bits_in_buffer += 5;
+
+        if bits_in_buffer >= 8 {
+            bits_in_buffer -= 8;
+            result.push(u8::try_from((buffer >> bits_in_buffer) & 0xFF).ok()?);
+        }
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_zbase32;
+
+    #[test]
+    fn test_decode_zbase32() {
+        // Test vectors of the sdk encoder
+        assert_eq!(decode_zbase32(""), Some(Vec::new()));
+        assert_eq!(decode_zbase32("yy"), Some(vec![0x00]));
+        assert_eq!(decode_zbase32("pb1sa5dx"), Some(b"hello".to_vec()));
+        assert_eq!(decode_zbase32("c3zs6aubqe"), Some(b"foobar".to_vec()));
+        assert_eq!(decode_zbase32("6n9hq"), Some(vec![0xf0, 0xbf, 0xc7]));
+        assert_eq!(decode_zbase32("invalid!"), None);
+    }
+}
@@ -63,7 +63,8 @@ const SELECT_PAYMENT_SQL = `
            lrm.nostr_zap_receipt AS lnurl_nostr_zap_receipt,
            lrm.sender_comment AS lnurl_sender_comment,
            lrm.payment_hash AS lnurl_payment_hash,
-           pm.parent_payment_id
+           pm.parent_payment_id,
+           lrm.payer_data AS lnurl_payer_data
       FROM payments p
       LEFT JOIN payment_details_lightning l ON p.id = l.payment_id
       LEFT JOIN payment_details_token t ON p.id = t.payment_id
@@ -71,6 +72,20 @@ const SELECT_PAYMENT_SQL = `
       LEFT JOIN payment_metadata pm ON p.id = pm.payment_id
       LEFT JOIN lnurl_receive_metadata lrm ON l.payment_hash = lrm.payment_hash`;
 
+/**
+ * Parses the LUD-18 payer data json sent by the payer. Invalid payer data is ignored.
+ */
+function parsePayerData(payerData) {
+  if (!payerData) {
+    return null;
+  }
+  try {
+    return JSON.parse(payerData);
+  } catch (e) {
+    return null;
+  }
+}
+
 class SqliteStorage {
   constructor(dbPath, logger = null) {
     this.dbPath = dbPath;
@@ -161,6 +176,21 @@ class SqliteStorage {
     }
   }
 
+  listCachedItems() {
+    try {
+      const stmt = this.db.prepare("SELECT key, value FROM settings");
+      const items = {};
+      for (const row of stmt.all()) {
+        items[row.key] = row.value;
+      }
+      return Promise.resolve(items);
+    } catch (error) {
+      return Promise.reject(
+        new StorageError(`Failed to list cached items: ${error.message}`, error)
+      );
+    }
+  }
+
   // ===== Payment Operations =====
 
   listPayments(request) {
@@ -299,8 +329,21 @@ class SqliteStorage {
         }
       }
 
-      // Exclude child payments (those with a parent_payment_id)
-      whereClauses.push("pm.parent_payment_id IS NULL");
+      if (request.conversionFilter) {
+        // Conversions made for a payment are its child payments, so they are included
+        whereClauses.push("pm.conversion_info IS NOT NULL");
+        const conversionIds = request.conversionFilter.conversionIds;
+        if (conversionIds && conversionIds.length > 0) {
+          const placeholders = conversionIds.map(() => "?").join(", ");
+          whereClauses.push(
+            `json_extract(pm.conversion_info, '$.conversionId') IN (${placeholders})`
+          );
+          params.push(...conversionIds);
+        }
+      } else {
+        // Exclude child payments (those with a parent_payment_id)
+        whereClauses.push("pm.parent_payment_id IS NULL");
+      }
 
       // Build the WHERE clause
       const whereSql =
@@ -593,6 +636,40 @@ class SqliteStorage {
     }
   }
 
+  listPaymentMetadata() {
+    try {
+      const stmt = this.db.prepare(`
+                SELECT payment_id, parent_payment_id, lnurl_pay_info, lnurl_withdraw_info, lnurl_description, conversion_info
+                FROM payment_metadata
+            `);
+
+      const metadata = {};
+      for (const row of stmt.all()) {
+        metadata[row.payment_id] = {
+          parentPaymentId: row.parent_payment_id,
+          lnurlPayInfo: row.lnurl_pay_info
+            ? JSON.parse(row.lnurl_pay_info)
+            : null,
+          lnurlWithdrawInfo: row.lnurl_withdraw_info
+            ? JSON.parse(row.lnurl_withdraw_info)
+            : null,
+          lnurlDescription: row.lnurl_description,
+          conversionInfo: row.conversion_info
+            ? JSON.parse(row.conversion_info)
+            : null,
+        };
+      }
+      return Promise.resolve(metadata);
+    } catch (error) {
+      return Promise.reject(
+        new StorageError(
+          `Failed to list payment metadata: ${error.message}`,
+          error
+        )
+      );
+    }
+  }
+
   // ===== Deposit Operations =====
 
   addDeposit(txid, vout, amountSats) {
@@ -695,7 +772,7 @@ class SqliteStorage {
   setLnurlMetadata(metadata) {
     try {
       const stmt = this.db.prepare(
-        "INSERT OR REPLACE INTO lnurl_receive_metadata (payment_hash, nostr_zap_request, nostr_zap_receipt, sender_comment, preimage) VALUES (?, ?, ?, ?, ?)"
+        "INSERT OR REPLACE INTO lnurl_receive_metadata (payment_hash, nostr_zap_request, nostr_zap_receipt, sender_comment, preimage, payer_data) VALUES (?, ?, ?, ?, ?, ?)"
       );
 
       const transaction = this.db.transaction(() => {
@@ -705,7 +782,8 @@ class SqliteStorage {
             item.nostrZapRequest || null,
             item.nostrZapReceipt || null,
             item.senderComment || null,
-            item.preimage || null
+            item.preimage || null,
+            item.payerData || null
           );
         }
       });
@@ -722,6 +800,33 @@ class SqliteStorage {
     }
   }
 
+  listLnurlMetadata() {
+    try {
+      const stmt = this.db.prepare(
+        "SELECT payment_hash, sender_comment, nostr_zap_request, nostr_zap_receipt, preimage, payer_data FROM lnurl_receive_metadata"
+      );
+
+      const rows = stmt.all();
+      return Promise.resolve(
+        rows.map((row) => ({
+          paymentHash: row.payment_hash,
+          senderComment: row.sender_comment,
+          nostrZapRequest: row.nostr_zap_request,
+          nostrZapReceipt: row.nostr_zap_receipt,
+          preimage: row.preimage,
+          payerData: row.payer_data,
+        }))
+      );
+    } catch (error) {
+      return Promise.reject(
+        new StorageError(
+          `Failed to list lnurl metadata: ${error.message}`,
+          error
+        )
+      );
+    }
+  }
+
   // ===== Private Helper Methods =====
 
   _rowToPayment(row) {
@@ -769,6 +874,7 @@ class SqliteStorage {
           nostrZapRequest: row.lnurl_nostr_zap_request || null,
           nostrZapReceipt: row.lnurl_nostr_zap_receipt || null,
           senderComment: row.lnurl_sender_comment || null,
+          payerData: parsePayerData(row.lnurl_payer_data),
         };
       }
     } else if (row.withdraw_tx_id) {
@@ -1255,6 +1361,40 @@ class SqliteStorage {
       );
     }
   }
+
+  syncGetStateRecords() {
+    try {
+      const stmt = this.db.prepare(`
+        SELECT  record_type
+        ,       data_id
+        ,       schema_version
+        ,       data
+        ,       CAST(revision AS TEXT) AS revision
+         FROM sync_state
+         ORDER BY revision ASC
+      `);
+
+      const rows = stmt.all();
+      return Promise.resolve(
+        rows.map((row) => ({
+          id: {
+            type: row.record_type,
+            dataId: row.data_id,
+          },
+          revision: BigInt(row.revision),
+          schemaVersion: row.schema_version,
+          data: JSON.parse(row.data),
+        }))
+      );
+    } catch (error) {
+      return Promise.reject(
+        new StorageError(
+          `Failed to get sync state records: ${error.message}`,
+          error
+        )
+      );
+    }
+  }
 }
 
 async function createDefaultStorage(dataDir, logger = null) {
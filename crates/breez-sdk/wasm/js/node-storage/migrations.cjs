@@ -382,6 +382,10 @@ class MigrationManager {
         name: "Add index on payment_hash for JOIN with lnurl_receive_metadata",
         sql: `CREATE INDEX IF NOT EXISTS idx_payment_details_lightning_payment_hash ON payment_details_lightning(payment_hash)`
       },
+      {
+        name: "Add payer_data column to lnurl_receive_metadata for LUD-18",
+        sql: `ALTER TABLE lnurl_receive_metadata ADD COLUMN payer_data TEXT`
+      },
     ];
   }
 }
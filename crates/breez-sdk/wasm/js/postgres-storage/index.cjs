@@ -60,7 +60,8 @@ const SELECT_PAYMENT_SQL = `
            lrm.nostr_zap_receipt AS lnurl_nostr_zap_receipt,
            lrm.sender_comment AS lnurl_sender_comment,
            lrm.payment_hash AS lnurl_payment_hash,
-           pm.parent_payment_id
+           pm.parent_payment_id,
+           lrm.payer_data AS lnurl_payer_data
       FROM payments p
       LEFT JOIN payment_details_lightning l ON p.id = l.payment_id
       LEFT JOIN payment_details_token t ON p.id = t.payment_id
@@ -68,6 +69,20 @@ const SELECT_PAYMENT_SQL = `
       LEFT JOIN payment_metadata pm ON p.id = pm.payment_id
       LEFT JOIN lnurl_receive_metadata lrm ON l.payment_hash = lrm.payment_hash`;
 
+/**
+ * Parses the LUD-18 payer data json sent by the payer. Invalid payer data is ignored.
+ */
+function parsePayerData(payerData) {
+  if (!payerData) {
+    return null;
+  }
+  try {
+    return JSON.parse(payerData);
+  } catch (e) {
+    return null;
+  }
+}
+
 class PostgresStorage {
   constructor(pool, logger = null) {
     this.pool = pool;
@@ -164,6 +179,22 @@ class PostgresStorage {
     }
   }
 
+  async listCachedItems() {
+    try {
+      const result = await this.pool.query("SELECT key, value FROM settings");
+      const items = {};
+      for (const row of result.rows) {
+        items[row.key] = row.value;
+      }
+      return items;
+    } catch (error) {
+      throw new StorageError(
+        `Failed to list cached items: ${error.message}`,
+        error
+      );
+    }
+  }
+
   // ===== Payment Operations =====
 
   async listPayments(request) {
@@ -325,8 +356,21 @@ class PostgresStorage {
         }
       }
 
-      // Exclude child payments
-      whereClauses.push("pm.parent_payment_id IS NULL");
+      if (request.conversionFilter) {
+        // Conversions made for a payment are its child payments, so they are included
+        whereClauses.push("pm.conversion_info IS NOT NULL");
+        const conversionIds = request.conversionFilter.conversionIds;
+        if (conversionIds && conversionIds.length > 0) {
+          const placeholders = conversionIds.map(() => `$${paramIdx++}`);
+          whereClauses.push(
+            `pm.conversion_info::jsonb->>'conversionId' IN (${placeholders.join(", ")})`
+          );
+          params.push(...conversionIds);
+        }
+      } else {
+        // Exclude child payments
+        whereClauses.push("pm.parent_payment_id IS NULL");
+      }
 
       const whereSql =
         whereClauses.length > 0
@@ -595,6 +639,34 @@ class PostgresStorage {
     }
   }
 
+  async listPaymentMetadata() {
+    try {
+      const result = await this.pool.query(
+        `SELECT payment_id, parent_payment_id, lnurl_pay_info, lnurl_withdraw_info, lnurl_description, conversion_info
+         FROM payment_metadata`
+      );
+
+      const parseJson = (value) =>
+        value ? (typeof value === "string" ? JSON.parse(value) : value) : null;
+      const metadata = {};
+      for (const row of result.rows) {
+        metadata[row.payment_id] = {
+          parentPaymentId: row.parent_payment_id,
+          lnurlPayInfo: parseJson(row.lnurl_pay_info),
+          lnurlWithdrawInfo: parseJson(row.lnurl_withdraw_info),
+          lnurlDescription: row.lnurl_description,
+          conversionInfo: parseJson(row.conversion_info),
+        };
+      }
+      return metadata;
+    } catch (error) {
+      throw new StorageError(
+        `Failed to list payment metadata: ${error.message}`,
+        error
+      );
+    }
+  }
+
   // ===== Deposit Operations =====
 
   async addDeposit(txid, vout, amountSats) {
@@ -682,19 +754,21 @@ class PostgresStorage {
       await this._withTransaction(async (client) => {
         for (const item of metadata) {
           await client.query(
-            `INSERT INTO lnurl_receive_metadata (payment_hash, nostr_zap_request, nostr_zap_receipt, sender_comment, preimage)
-             VALUES ($1, $2, $3, $4, $5)
+            `INSERT INTO lnurl_receive_metadata (payment_hash, nostr_zap_request, nostr_zap_receipt, sender_comment, preimage, payer_data)
+             VALUES ($1, $2, $3, $4, $5, $6)
              ON CONFLICT(payment_hash) DO UPDATE SET
                nostr_zap_request = EXCLUDED.nostr_zap_request,
                nostr_zap_receipt = EXCLUDED.nostr_zap_receipt,
                sender_comment = EXCLUDED.sender_comment,
-               preimage = EXCLUDED.preimage`,
+               preimage = EXCLUDED.preimage,
+               payer_data = EXCLUDED.payer_data`,
             [
               item.paymentHash,
               item.nostrZapRequest || null,
               item.nostrZapReceipt || null,
               item.senderComment || null,
               item.preimage || null,
+              item.payerData || null,
             ]
           );
         }
@@ -708,6 +782,28 @@ class PostgresStorage {
     }
   }
 
+  async listLnurlMetadata() {
+    try {
+      const result = await this.pool.query(
+        "SELECT payment_hash, sender_comment, nostr_zap_request, nostr_zap_receipt, preimage, payer_data FROM lnurl_receive_metadata"
+      );
+
+      return result.rows.map((row) => ({
+        paymentHash: row.payment_hash,
+        senderComment: row.sender_comment,
+        nostrZapRequest: row.nostr_zap_request,
+        nostrZapReceipt: row.nostr_zap_receipt,
+        preimage: row.preimage,
+        payerData: row.payer_data,
+      }));
+    } catch (error) {
+      throw new StorageError(
+        `Failed to list lnurl metadata: ${error.message}`,
+        error
+      );
+    }
+  }
+
   // ===== Private Helper Methods =====
 
   _rowToPayment(row) {
@@ -752,6 +848,7 @@ class PostgresStorage {
           nostrZapRequest: row.lnurl_nostr_zap_request || null,
           nostrZapReceipt: row.lnurl_nostr_zap_receipt || null,
           senderComment: row.lnurl_sender_comment || null,
+          payerData: parsePayerData(row.lnurl_payer_data),
         };
       }
     } else if (row.withdraw_tx_id) {
@@ -1234,6 +1331,31 @@ class PostgresStorage {
       );
     }
   }
+
+  async syncGetStateRecords() {
+    try {
+      const result = await this.pool.query(
+        `SELECT record_type, data_id, schema_version, data, revision
+         FROM sync_state
+         ORDER BY revision ASC`
+      );
+
+      return result.rows.map((row) => ({
+        id: {
+          type: row.record_type,
+          dataId: row.data_id,
+        },
+        revision: BigInt(row.revision),
+        schemaVersion: row.schema_version,
+        data: typeof row.data === "string" ? JSON.parse(row.data) : row.data,
+      }));
+    } catch (error) {
+      throw new StorageError(
+        `Failed to get sync state records: ${error.message}`,
+        error
+      );
+    }
+  }
 }
 
 /**
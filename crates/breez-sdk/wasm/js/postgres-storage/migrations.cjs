@@ -94,7 +94,7 @@ class PostgresMigrationManager {
   }
 
   /**
-   * Single migration creating all tables at their final schema.
+   * The first migration creates all tables at their schema at the time.
    * This mirrors the Rust-native PostgresStorage schema but uses camelCase
    * enum values (as produced by the WASM bridge).
    */
@@ -222,6 +222,12 @@ class PostgresMigrationManager {
           `CREATE INDEX IF NOT EXISTS idx_sync_incoming_revision ON sync_incoming(revision)`,
         ],
       },
+      {
+        name: "Add payer_data column to lnurl_receive_metadata for LUD-18",
+        sql: [
+          `ALTER TABLE lnurl_receive_metadata ADD COLUMN IF NOT EXISTS payer_data TEXT`,
+        ],
+      },
     ];
   }
 }
@@ -435,6 +435,20 @@ class StorageError extends Error {
   }
 }
 
+/**
+ * Parses the LUD-18 payer data json sent by the payer. Invalid payer data is ignored.
+ */
+function parsePayerData(payerData) {
+  if (!payerData) {
+    return null;
+  }
+  try {
+    return JSON.parse(payerData);
+  } catch (e) {
+    return null;
+  }
+}
+
 class IndexedDBStorage {
   constructor(dbName = "BreezSDK", logger = null) {
     this.dbName = dbName;
@@ -597,6 +611,37 @@ class IndexedDBStorage {
     });
   }
 
+  async listCachedItems() {
+    if (!this.db) {
+      throw new StorageError("Database not initialized");
+    }
+
+    return new Promise((resolve, reject) => {
+      const transaction = this.db.transaction("settings", "readonly");
+      const store = transaction.objectStore("settings");
+      const request = store.getAll();
+
+      request.onsuccess = () => {
+        const items = {};
+        for (const row of request.result) {
+          items[row.key] = row.value;
+        }
+        resolve(items);
+      };
+
+      request.onerror = () => {
+        reject(
+          new StorageError(
+            `Failed to list cached items: ${
+              request.error?.message || "Unknown error"
+            }`,
+            request.error
+          )
+        );
+      };
+    });
+  }
+
   // ===== Payment Operations =====
 
   /**
@@ -695,19 +740,24 @@ class IndexedDBStorage {
 
         const payment = cursor.value;
 
-        // Skip related payments (those with a parentPaymentId)
-        if (relatedPaymentIds.has(payment.id)) {
+        // Skip related payments (those with a parentPaymentId), unless listing
+        // conversions, as conversions made for a payment are its related payments
+        if (!request.conversionFilter && relatedPaymentIds.has(payment.id)) {
           cursor.continue();
           return;
         }
 
-        if (skipped < actualOffset) {
-          skipped++;
-          cursor.continue();
-          return;
-        }
+        // Apply the offset to the payments matching the filters
+        const addPayment = (matchingPayment) => {
+          if (skipped < actualOffset) {
+            skipped++;
+            return;
+          }
+          payments.push(matchingPayment);
+          count++;
+        };
 
-        // Get metadata for this payment (now only for non-related payments)
+        // Get metadata for this payment
         const metadataRequest = metadataStore.get(payment.id);
         metadataRequest.onsuccess = () => {
           const metadata = metadataRequest.result;
@@ -729,8 +779,7 @@ class IndexedDBStorage {
                 cursor.continue();
                 return;
               }
-              payments.push(mergedPayment);
-              count++;
+              addPayment(mergedPayment);
               cursor.continue();
             })
             .catch(() => {
@@ -739,16 +788,14 @@ class IndexedDBStorage {
                 cursor.continue();
                 return;
               }
-              payments.push(paymentWithMetadata);
-              count++;
+              addPayment(paymentWithMetadata);
               cursor.continue();
             });
         };
         metadataRequest.onerror = () => {
           // Continue without metadata if it fails
           if (this._matchesFilters(payment, request)) {
-            payments.push(payment);
-            count++;
+            addPayment(payment);
           }
 
           cursor.continue();
@@ -1150,6 +1197,47 @@ class IndexedDBStorage {
     });
   }
 
+  async listPaymentMetadata() {
+    if (!this.db) {
+      throw new StorageError("Database not initialized");
+    }
+
+    return new Promise((resolve, reject) => {
+      const transaction = this.db.transaction("payment_metadata", "readonly");
+      const store = transaction.objectStore("payment_metadata");
+      const request = store.getAll();
+
+      request.onsuccess = () => {
+        const metadata = {};
+        for (const row of request.result) {
+          metadata[row.paymentId] = {
+            parentPaymentId: row.parentPaymentId ?? null,
+            lnurlPayInfo: row.lnurlPayInfo ? JSON.parse(row.lnurlPayInfo) : null,
+            lnurlWithdrawInfo: row.lnurlWithdrawInfo
+              ? JSON.parse(row.lnurlWithdrawInfo)
+              : null,
+            lnurlDescription: row.lnurlDescription ?? null,
+            conversionInfo: row.conversionInfo
+              ? JSON.parse(row.conversionInfo)
+              : null,
+          };
+        }
+        resolve(metadata);
+      };
+
+      request.onerror = () => {
+        reject(
+          new StorageError(
+            `Failed to list payment metadata: ${
+              request.error?.message || "Unknown error"
+            }`,
+            request.error
+          )
+        );
+      };
+    });
+  }
+
   // ===== Deposit Operations =====
 
   async addDeposit(txid, vout, amountSats) {
@@ -1342,6 +1430,7 @@ class IndexedDBStorage {
           nostrZapReceipt: item.nostrZapReceipt || null,
           senderComment: item.senderComment || null,
           preimage: item.preimage || null,
+          payerData: item.payerData || null,
         });
 
         request.onsuccess = () => {
@@ -1365,6 +1454,41 @@ class IndexedDBStorage {
     });
   }
 
+  async listLnurlMetadata() {
+    if (!this.db) {
+      throw new StorageError("Database not initialized");
+    }
+
+    return new Promise((resolve, reject) => {
+      const transaction = this.db.transaction("lnurl_receive_metadata", "readonly");
+      const store = transaction.objectStore("lnurl_receive_metadata");
+      const request = store.getAll();
+
+      request.onsuccess = () => {
+        const metadata = request.result.map((row) => ({
+          paymentHash: row.paymentHash,
+          senderComment: row.senderComment ?? null,
+          nostrZapRequest: row.nostrZapRequest ?? null,
+          nostrZapReceipt: row.nostrZapReceipt ?? null,
+          preimage: row.preimage ?? null,
+          payerData: row.payerData ?? null,
+        }));
+        resolve(metadata);
+      };
+
+      request.onerror = () => {
+        reject(
+          new StorageError(
+            `Failed to list lnurl metadata: ${
+              request.error?.message || "Unknown error"
+            }`,
+            request.error
+          )
+        );
+      };
+    });
+  }
+
   async syncAddOutgoingChange(record) {
     if (!this.db) {
       throw new StorageError("Database not initialized");
@@ -1841,6 +1965,39 @@ class IndexedDBStorage {
     });
   }
 
+  async syncGetStateRecords() {
+    if (!this.db) {
+      throw new StorageError("Database not initialized");
+    }
+
+    return new Promise((resolve, reject) => {
+      const transaction = this.db.transaction("sync_state", "readonly");
+      const store = transaction.objectStore("sync_state");
+      const request = store.getAll();
+
+      request.onsuccess = () => {
+        const records = request.result
+          .map((storeRecord) => storeRecord.record)
+          .sort((a, b) => {
+            const diff = BigInt(a.revision) - BigInt(b.revision);
+            return diff < 0n ? -1 : diff > 0n ? 1 : 0;
+          });
+        resolve(records);
+      };
+
+      request.onerror = () => {
+        reject(
+          new StorageError(
+            `Failed to get sync state records: ${
+              request.error?.message || "Unknown error"
+            }`,
+            request.error
+          )
+        );
+      };
+    });
+  }
+
   // ===== Private Helper Methods =====
 
   _matchesFilters(payment, request) {
@@ -2038,6 +2195,36 @@ class IndexedDBStorage {
       }
     }
 
+    // Filter by conversion, including the conversions made for a payment
+    if (request.conversionFilter) {
+      let details = null;
+
+      // Parse details if it's a string (stored in IndexedDB)
+      if (payment.details && typeof payment.details === "string") {
+        try {
+          details = JSON.parse(payment.details);
+        } catch (e) {
+          // If parsing fails, treat as no details
+          details = null;
+        }
+      } else {
+        details = payment.details;
+      }
+
+      if (!details || !details.conversionInfo) {
+        return false;
+      }
+
+      const conversionIds = request.conversionFilter.conversionIds;
+      if (
+        conversionIds &&
+        conversionIds.length > 0 &&
+        !conversionIds.includes(details.conversionInfo.conversionId)
+      ) {
+        return false;
+      }
+    }
+
     return true;
   }
 
@@ -2153,6 +2340,7 @@ class IndexedDBStorage {
             nostrZapReceipt: lnurlReceiveMetadata.nostrZapReceipt || null,
             senderComment: lnurlReceiveMetadata.senderComment || null,
             preimage: lnurlReceiveMetadata.preimage || null,
+            payerData: parsePayerData(lnurlReceiveMetadata.payerData),
           };
         }
         resolve(payment);
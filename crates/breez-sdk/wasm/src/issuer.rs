@@ -7,9 +7,11 @@ use crate::{
     models::{
         Payment, TokenBalance, TokenMetadata,
         issuer::{
-            BurnIssuerTokenRequest, CreateIssuerTokenRequest, FreezeIssuerTokenRequest,
-            FreezeIssuerTokenResponse, MintIssuerTokenRequest, UnfreezeIssuerTokenRequest,
-            UnfreezeIssuerTokenResponse,
+            BurnIssuerTokenRequest, CreateIssuerTokenRequest, DistributeIssuerTokenRequest,
+            DistributeIssuerTokenResponse, FreezeIssuerTokenRequest, FreezeIssuerTokenResponse,
+            IssuerTokenSupply, ListFrozenTokenHoldersRequest, ListFrozenTokenHoldersResponse,
+            ListIssuerTokenTransactionsRequest, ListIssuerTokenTransactionsResponse,
+            MintIssuerTokenRequest, UnfreezeIssuerTokenRequest, UnfreezeIssuerTokenResponse,
         },
     },
 };
@@ -84,4 +86,45 @@ impl TokenIssuer {
             .await?
             .into())
     }
+
+    #[wasm_bindgen(js_name = "distributeIssuerToken")]
+    pub async fn distribute_issuer_token(
+        &self,
+        request: DistributeIssuerTokenRequest,
+    ) -> WasmResult<DistributeIssuerTokenResponse> {
+        Ok(self
+            .token_issuer
+            .distribute_issuer_token(request.into())
+            .await?
+            .into())
+    }
+
+    #[wasm_bindgen(js_name = "getIssuerTokenSupply")]
+    pub async fn get_issuer_token_supply(&self) -> WasmResult<IssuerTokenSupply> {
+        Ok(self.token_issuer.get_issuer_token_supply().await?.into())
+    }
+
+    #[wasm_bindgen(js_name = "listIssuerTokenTransactions")]
+    pub async fn list_issuer_token_transactions(
+        &self,
+        request: ListIssuerTokenTransactionsRequest,
+    ) -> WasmResult<ListIssuerTokenTransactionsResponse> {
+        Ok(self
+            .token_issuer
+            .list_issuer_token_transactions(request.into())
+            .await?
+            .into())
+    }
+
+    #[wasm_bindgen(js_name = "listFrozenTokenHolders")]
+    pub async fn list_frozen_token_holders(
+        &self,
+        request: ListFrozenTokenHoldersRequest,
+    ) -> WasmResult<ListFrozenTokenHoldersResponse> {
+        Ok(self
+            .token_issuer
+            .list_frozen_token_holders(request.into())
+            .await?
+            .into())
+    }
 }
@@ -19,6 +19,13 @@ pub(crate) fn js_error_to_payment_observer_error(
     breez_sdk_spark::PaymentObserverError::Generic(error_message)
 }
 
+pub(crate) fn js_error_to_signer_error(js_error: JsValue) -> breez_sdk_spark::SignerError {
+    let error_message = js_error
+        .as_string()
+        .unwrap_or_else(|| "Signing audit log error occurred".to_string());
+    breez_sdk_spark::SignerError::Generic(error_message)
+}
+
 pub(crate) fn js_error_to_service_connectivity_error(
     js_error: JsValue,
 ) -> ServiceConnectivityError {
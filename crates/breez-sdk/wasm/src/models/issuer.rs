@@ -1,3 +1,5 @@
+use crate::models::{Payment, PaymentStatus, TokenTransactionType};
+
 #[macros::extern_wasm_bindgen(breez_sdk_spark::CreateIssuerTokenRequest)]
 pub struct CreateIssuerTokenRequest {
     pub name: String,
@@ -20,6 +22,7 @@ pub struct BurnIssuerTokenRequest {
 #[macros::extern_wasm_bindgen(breez_sdk_spark::FreezeIssuerTokenRequest)]
 pub struct FreezeIssuerTokenRequest {
     pub address: String,
+    pub reason: Option<String>,
 }
 
 #[macros::extern_wasm_bindgen(breez_sdk_spark::FreezeIssuerTokenResponse)]
@@ -38,3 +41,95 @@ pub struct UnfreezeIssuerTokenResponse {
     pub impacted_output_ids: Vec<String>,
     pub impacted_token_amount: u128,
 }
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::TokenDistributionRecipient)]
+pub struct TokenDistributionRecipient {
+    pub address: String,
+    pub amount: u128,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::DistributeIssuerTokenRequest)]
+pub struct DistributeIssuerTokenRequest {
+    pub recipients: Vec<TokenDistributionRecipient>,
+    pub idempotency_key: Option<String>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::TokenDistributionStatus)]
+pub enum TokenDistributionStatus {
+    Sent,
+    Failed,
+    Unknown,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::TokenDistributionRecipientResult)]
+pub struct TokenDistributionRecipientResult {
+    pub address: String,
+    pub amount: u128,
+    pub status: TokenDistributionStatus,
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::DistributeIssuerTokenResponse)]
+pub struct DistributeIssuerTokenResponse {
+    pub idempotency_key: String,
+    pub mint_payment: Option<Payment>,
+    pub recipients: Vec<TokenDistributionRecipientResult>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::IssuerTokenSupply)]
+pub struct IssuerTokenSupply {
+    pub max_supply: u128,
+    pub total_minted: u128,
+    pub total_burned: u128,
+    pub total_supply: u128,
+    pub issuer_balance: u128,
+    pub circulating_supply: u128,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::ListIssuerTokenTransactionsRequest)]
+pub struct ListIssuerTokenTransactionsRequest {
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::ListIssuerTokenTransactionsResponse)]
+pub struct ListIssuerTokenTransactionsResponse {
+    pub transactions: Vec<IssuerTokenTransaction>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::IssuerTokenTransaction)]
+pub struct IssuerTokenTransaction {
+    pub tx_hash: String,
+    pub tx_type: TokenTransactionType,
+    pub status: PaymentStatus,
+    pub timestamp: u64,
+    pub outputs: Vec<IssuerTokenTransactionOutput>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::IssuerTokenTransactionOutput)]
+pub struct IssuerTokenTransactionOutput {
+    pub owner_public_key: String,
+    pub amount: u128,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::ListFrozenTokenHoldersRequest)]
+pub struct ListFrozenTokenHoldersRequest {
+    pub include_unfrozen: Option<bool>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::ListFrozenTokenHoldersResponse)]
+pub struct ListFrozenTokenHoldersResponse {
+    pub holders: Vec<FrozenTokenHolder>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::FrozenTokenHolder)]
+pub struct FrozenTokenHolder {
+    pub address: String,
+    pub owner_public_key: String,
+    pub reason: Option<String>,
+    pub frozen_at: u64,
+    pub impacted_output_ids: Vec<String>,
+    pub impacted_token_amount: u128,
+    pub unfrozen_at: Option<u64>,
+}
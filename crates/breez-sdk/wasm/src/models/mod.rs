@@ -4,6 +4,8 @@ pub mod fiat_service;
 pub mod issuer;
 pub mod payment_observer;
 pub mod rest_client;
+pub mod signing_audit_log;
+pub mod token_registry_service;
 
 use std::collections::HashMap;
 
@@ -361,6 +363,41 @@ pub struct LnurlPayRequestDetails {
     pub address: Option<String>,
     pub allows_nostr: Option<bool>,
     pub nostr_pubkey: Option<String>,
+    pub payer_data: Option<LnurlPayerDataRequest>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::LnurlPayerDataRequest)]
+pub struct LnurlPayerDataRequest {
+    pub name: Option<LnurlPayerDataField>,
+    pub pubkey: Option<LnurlPayerDataField>,
+    pub identifier: Option<LnurlPayerDataField>,
+    pub auth: Option<LnurlPayerDataAuthField>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::LnurlPayerDataField)]
+pub struct LnurlPayerDataField {
+    pub mandatory: bool,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::LnurlPayerDataAuthField)]
+pub struct LnurlPayerDataAuthField {
+    pub mandatory: bool,
+    pub k1: String,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::LnurlPayerData)]
+pub struct LnurlPayerData {
+    pub name: Option<String>,
+    pub pubkey: Option<String>,
+    pub identifier: Option<String>,
+    pub auth: Option<LnurlPayerDataAuth>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::LnurlPayerDataAuth)]
+pub struct LnurlPayerDataAuth {
+    pub key: String,
+    pub k1: String,
+    pub sig: String,
 }
 
 #[macros::extern_wasm_bindgen(breez_sdk_spark::SilentPaymentAddressDetails)]
@@ -685,6 +722,10 @@ pub struct GetInfoResponse {
 #[macros::extern_wasm_bindgen(breez_sdk_spark::TokenBalance)]
 pub struct TokenBalance {
     pub balance: u128,
+    pub available_balance: u128,
+    pub pending_outgoing_balance: u128,
+    pub reserved_for_conversion_balance: u128,
+    pub frozen_balance: u128,
     pub token_metadata: TokenMetadata,
 }
 
@@ -702,6 +743,21 @@ pub struct TokenMetadata {
     #[serde(with = "serde_u128_as_string")]
     pub max_supply: u128,
     pub is_freezable: bool,
+    pub registry_info: Option<TokenRegistryInfo>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::TokenRegistryEntry)]
+pub struct TokenRegistryEntry {
+    pub identifier: String,
+    pub info: TokenRegistryInfo,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::TokenRegistryInfo)]
+pub struct TokenRegistryInfo {
+    pub icon_url: Option<String>,
+    pub issuer_name: Option<String>,
+    pub issuer_url: Option<String>,
+    pub verified: bool,
 }
 
 #[macros::extern_wasm_bindgen(breez_sdk_spark::SyncWalletRequest)]
@@ -802,6 +858,14 @@ pub struct PrepareLnurlPayRequest {
     pub validate_success_action_url: Option<bool>,
     pub conversion_options: Option<ConversionOptions>,
     pub fee_policy: Option<FeePolicy>,
+    pub payer_data: Option<LnurlPayerDataOptions>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::LnurlPayerDataOptions)]
+pub struct LnurlPayerDataOptions {
+    pub name: Option<String>,
+    pub identifier: Option<String>,
+    pub share_linking_key: bool,
 }
 
 #[macros::extern_wasm_bindgen(breez_sdk_spark::PrepareLnurlPayResponse)]
@@ -814,6 +878,7 @@ pub struct PrepareLnurlPayResponse {
     pub success_action: Option<SuccessAction>,
     pub conversion_estimate: Option<ConversionEstimate>,
     pub fee_policy: FeePolicy,
+    pub payer_data: Option<LnurlPayerData>,
 }
 
 #[macros::extern_wasm_bindgen(breez_sdk_spark::LnurlPayRequest)]
@@ -944,12 +1009,18 @@ pub struct ListPaymentsRequest {
     pub sort_ascending: Option<bool>,
 }
 
+#[macros::extern_wasm_bindgen(breez_sdk_spark::StorageConversionFilter)]
+pub struct StorageConversionFilter {
+    pub conversion_ids: Option<Vec<String>>,
+}
+
 #[macros::extern_wasm_bindgen(breez_sdk_spark::StorageListPaymentsRequest)]
 pub struct StorageListPaymentsRequest {
     pub type_filter: Option<Vec<PaymentType>>,
     pub status_filter: Option<Vec<PaymentStatus>>,
     pub asset_filter: Option<AssetFilter>,
     pub payment_details_filter: Option<Vec<StoragePaymentDetailsFilter>>,
+    pub conversion_filter: Option<StorageConversionFilter>,
     pub from_timestamp: Option<u64>,
     pub to_timestamp: Option<u64>,
     pub offset: Option<u32>,
@@ -993,6 +1064,18 @@ pub struct PaymentMetadata {
     pub conversion_info: Option<ConversionInfo>,
 }
 
+#[macros::extern_wasm_bindgen(breez_sdk_spark::StorageMigrationSummary)]
+pub struct StorageMigrationSummary {
+    pub payments: u32,
+    pub payment_metadata: u32,
+    pub deposits: u32,
+    pub lnurl_metadata: u32,
+    pub cached_items: u32,
+    pub sync_records: u32,
+    pub sync_incoming_records: u32,
+    pub sync_outgoing_changes: u32,
+}
+
 #[macros::extern_wasm_bindgen(breez_sdk_spark::SetLnurlMetadataItem)]
 pub struct SetLnurlMetadataItem {
     pub payment_hash: String,
@@ -1000,6 +1083,7 @@ pub struct SetLnurlMetadataItem {
     pub nostr_zap_request: Option<String>,
     pub nostr_zap_receipt: Option<String>,
     pub preimage: Option<String>,
+    pub payer_data: Option<String>,
 }
 
 #[macros::extern_wasm_bindgen(breez_sdk_spark::UpdateDepositPayload)]
@@ -1022,6 +1106,20 @@ pub struct CheckLightningAddressRequest {
 pub struct RegisterLightningAddressRequest {
     pub username: String,
     pub description: Option<String>,
+    pub min_sendable: Option<u64>,
+    pub max_sendable: Option<u64>,
+    pub comment_allowed: Option<u16>,
+    pub image: Option<String>,
+    pub long_description: Option<String>,
+    pub nostr_identity: Option<NostrIdentity>,
+    pub publish_bip353: Option<bool>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::NostrIdentity)]
+pub struct NostrIdentity {
+    pub pubkey: String,
+    pub relays: Vec<String>,
+    pub proof: String,
 }
 
 #[macros::extern_wasm_bindgen(breez_sdk_spark::LnurlInfo)]
@@ -1036,6 +1134,86 @@ pub struct LightningAddressInfo {
     pub lightning_address: String,
     pub lnurl: LnurlInfo,
     pub username: String,
+    pub min_sendable: Option<u64>,
+    pub max_sendable: Option<u64>,
+    pub comment_allowed: Option<u16>,
+    pub image: Option<String>,
+    pub long_description: Option<String>,
+    pub nostr_identity: Option<NostrIdentity>,
+    pub bip353_address: Option<String>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::CreateLnurlWithdrawLinkRequest)]
+pub struct CreateLnurlWithdrawLinkRequest {
+    pub min_withdrawable_sat: u64,
+    pub max_withdrawable_sat: u64,
+    pub description: Option<String>,
+    pub max_uses: Option<u32>,
+    pub expires_at: Option<u64>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::CreateLnurlWithdrawLinkResponse)]
+pub struct CreateLnurlWithdrawLinkResponse {
+    pub link: LnurlWithdrawLink,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::ListLnurlWithdrawLinksResponse)]
+pub struct ListLnurlWithdrawLinksResponse {
+    pub links: Vec<LnurlWithdrawLink>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::DeleteLnurlWithdrawLinkRequest)]
+pub struct DeleteLnurlWithdrawLinkRequest {
+    pub id: String,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::LnurlWithdrawLink)]
+pub struct LnurlWithdrawLink {
+    pub id: String,
+    pub lnurl: LnurlInfo,
+    pub description: String,
+    pub min_withdrawable_sat: u64,
+    pub max_withdrawable_sat: u64,
+    pub max_uses: Option<u32>,
+    pub uses: u32,
+    pub expires_at: Option<u64>,
+    pub created_at: u64,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::LnurlWebhookEvent)]
+pub enum LnurlWebhookEvent {
+    InvoiceRequested,
+    InvoicePaid,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::RegisterLnurlWebhookRequest)]
+pub struct RegisterLnurlWebhookRequest {
+    pub url: String,
+    pub events: Vec<LnurlWebhookEvent>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::RegisterLnurlWebhookResponse)]
+pub struct RegisterLnurlWebhookResponse {
+    pub webhook: LnurlWebhook,
+    pub secret: String,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::ListLnurlWebhooksResponse)]
+pub struct ListLnurlWebhooksResponse {
+    pub webhooks: Vec<LnurlWebhook>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::DeleteLnurlWebhookRequest)]
+pub struct DeleteLnurlWebhookRequest {
+    pub id: String,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::LnurlWebhook)]
+pub struct LnurlWebhook {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<LnurlWebhookEvent>,
+    pub created_at: u64,
 }
 
 #[macros::extern_wasm_bindgen(breez_sdk_spark::ListFiatCurrenciesResponse)]
@@ -1126,6 +1304,25 @@ pub enum ProvisionalPaymentDetails {
     },
 }
 
+#[macros::extern_wasm_bindgen(breez_sdk_spark::ExportBackupResponse)]
+pub struct ExportBackupResponse {
+    pub backup: Vec<u8>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::ImportBackupRequest)]
+pub struct ImportBackupRequest {
+    pub backup: Vec<u8>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::ImportBackupResponse)]
+pub struct ImportBackupResponse {
+    pub payments: u32,
+    pub payment_metadata: u32,
+    pub deposits: u32,
+    pub lnurl_metadata: u32,
+    pub cached_items: u32,
+}
+
 #[macros::extern_wasm_bindgen(breez_sdk_spark::SignMessageRequest)]
 pub struct SignMessageRequest {
     pub message: String,
@@ -1202,6 +1399,24 @@ pub struct UpdateUserSettingsRequest {
     pub spark_private_mode_enabled: Option<bool>,
 }
 
+#[macros::extern_wasm_bindgen(breez_sdk_spark::SetSyncedValueRequest)]
+pub struct SetSyncedValueRequest {
+    pub namespace: String,
+    pub key: String,
+    pub value: Option<String>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::GetSyncedValueRequest)]
+pub struct GetSyncedValueRequest {
+    pub namespace: String,
+    pub key: String,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::GetSyncedValueResponse)]
+pub struct GetSyncedValueResponse {
+    pub value: Option<String>,
+}
+
 #[macros::extern_wasm_bindgen(breez_sdk_spark::ClaimHtlcPaymentRequest)]
 pub struct ClaimHtlcPaymentRequest {
     pub preimage: String,
@@ -1217,6 +1432,7 @@ pub struct LnurlReceiveMetadata {
     pub nostr_zap_request: Option<String>,
     pub nostr_zap_receipt: Option<String>,
     pub sender_comment: Option<String>,
+    pub payer_data: Option<LnurlPayerData>,
 }
 
 #[macros::extern_wasm_bindgen(breez_sdk_spark::OptimizationProgress)]
@@ -1256,6 +1472,9 @@ pub struct ConversionInfo {
     #[serde(default, with = "serde_option_u128_as_string")]
     pub fee: Option<u128>,
     pub purpose: Option<ConversionPurpose>,
+    #[tsify(type = "string")]
+    #[serde(default, with = "serde_option_u128_as_string")]
+    pub expected_amount_out: Option<u128>,
 }
 
 #[macros::extern_wasm_bindgen(breez_sdk_spark::ConversionOptions)]
@@ -1283,6 +1502,47 @@ pub struct FetchConversionLimitsResponse {
     pub min_to_amount: Option<u128>,
 }
 
+#[macros::extern_wasm_bindgen(breez_sdk_spark::ListConversionsRequest)]
+pub struct ListConversionsRequest {
+    pub from_timestamp: Option<u64>,
+    pub to_timestamp: Option<u64>,
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::ListConversionsResponse)]
+pub struct ListConversionsResponse {
+    pub conversions: Vec<Conversion>,
+    pub summaries: Vec<ConversionSummary>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::Conversion)]
+pub struct Conversion {
+    pub conversion_id: String,
+    pub pool_id: String,
+    pub status: ConversionStatus,
+    pub purpose: Option<ConversionPurpose>,
+    pub timestamp: u64,
+    pub from: ConversionStep,
+    pub to: Option<ConversionStep>,
+    pub refund: Option<ConversionStep>,
+    pub fee: u128,
+    pub expected_amount_out: Option<u128>,
+    pub effective_rate: Option<f64>,
+    pub slippage_bps: Option<i64>,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::ConversionSummary)]
+pub struct ConversionSummary {
+    pub from_token_identifier: Option<String>,
+    pub to_token_identifier: Option<String>,
+    pub conversion_count: u32,
+    pub total_from_amount: u128,
+    pub total_to_amount: u128,
+    pub total_fees: u128,
+    pub average_rate: Option<f64>,
+}
+
 #[macros::extern_wasm_bindgen(breez_sdk_spark::ServiceStatus)]
 pub enum ServiceStatus {
     Operational,
@@ -0,0 +1,60 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{JsFuture, js_sys::Promise};
+
+use crate::{models::error::js_error_to_signer_error, signer::ExternalSigningContext};
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::signer::SigningMethod)]
+pub enum SigningMethod {
+    SignMessageEcdsa,
+    SignHashSchnorr,
+    SplitSecret,
+    EncryptSecretForReceiver,
+    SignFrost,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::signer::SigningAuditEntry)]
+pub struct SigningAuditEntry {
+    pub timestamp: u64,
+    pub method: SigningMethod,
+    pub context: ExternalSigningContext,
+    pub digest: Option<Vec<u8>>,
+    pub signature: Option<Vec<u8>>,
+}
+
+pub struct WasmSigningAuditLog {
+    pub signing_audit_log: SigningAuditLog,
+}
+
+// This assumes that we'll always be running in a single thread (true for Wasm environments)
+unsafe impl Send for WasmSigningAuditLog {}
+unsafe impl Sync for WasmSigningAuditLog {}
+
+#[macros::async_trait]
+impl breez_sdk_spark::signer::SigningAuditLog for WasmSigningAuditLog {
+    async fn append(
+        &self,
+        entry: breez_sdk_spark::signer::SigningAuditEntry,
+    ) -> Result<(), breez_sdk_spark::SignerError> {
+        let promise = self
+            .signing_audit_log
+            .append(entry.into())
+            .map_err(js_error_to_signer_error)?;
+        let future = JsFuture::from(promise);
+        future.await.map_err(js_error_to_signer_error)?;
+        Ok(())
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const EVENT_INTERFACE: &'static str = r#"export interface SigningAuditLog {
+    append: (entry: SigningAuditEntry) => Promise<void>;
+}"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "SigningAuditLog")]
+    pub type SigningAuditLog;
+
+    #[wasm_bindgen(structural, method, js_name = append, catch)]
+    pub fn append(this: &SigningAuditLog, entry: SigningAuditEntry) -> Result<Promise, JsValue>;
+}
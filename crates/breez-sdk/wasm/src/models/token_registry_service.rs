@@ -0,0 +1,50 @@
+use breez_sdk_spark::ServiceConnectivityError;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{JsFuture, js_sys::Promise};
+
+use crate::models::{TokenRegistryEntry, error::js_error_to_service_connectivity_error};
+
+pub struct WasmTokenRegistryService {
+    pub inner: TokenRegistryService,
+}
+
+// This assumes that we'll always be running in a single thread (true for Wasm environments)
+unsafe impl Send for WasmTokenRegistryService {}
+unsafe impl Sync for WasmTokenRegistryService {}
+
+#[macros::async_trait]
+impl breez_sdk_spark::TokenRegistryService for WasmTokenRegistryService {
+    async fn fetch_token_registry_entries(
+        &self,
+        token_identifiers: Vec<String>,
+    ) -> Result<Vec<breez_sdk_spark::TokenRegistryEntry>, ServiceConnectivityError> {
+        let promise = self
+            .inner
+            .fetch_token_registry_entries(token_identifiers)
+            .map_err(js_error_to_service_connectivity_error)?;
+        let future = JsFuture::from(promise);
+        let result = future
+            .await
+            .map_err(js_error_to_service_connectivity_error)?;
+        let entries: Vec<TokenRegistryEntry> = serde_wasm_bindgen::from_value(result)
+            .map_err(|e| ServiceConnectivityError::Other(e.to_string()))?;
+        Ok(entries.into_iter().map(|e| e.into()).collect())
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const EVENT_INTERFACE: &'static str = r#"export interface TokenRegistryService {
+    fetchTokenRegistryEntries(tokenIdentifiers: string[]): Promise<TokenRegistryEntry[]>;
+}"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "TokenRegistryService")]
+    pub type TokenRegistryService;
+
+    #[wasm_bindgen(structural, method, js_name = "fetchTokenRegistryEntries", catch)]
+    pub fn fetch_token_registry_entries(
+        this: &TokenRegistryService,
+        token_identifiers: Vec<String>,
+    ) -> Result<Promise, JsValue>;
+}
@@ -113,6 +113,20 @@ impl breez_sdk_spark::Storage for WasmStorage {
         Ok(())
     }
 
+    async fn list_cached_items(&self) -> Result<HashMap<String, String>, StorageError> {
+        let promise = self
+            .storage
+            .list_cached_items()
+            .map_err(js_error_to_storage_error)?;
+        let future = JsFuture::from(promise);
+        let result = future.await.map_err(js_error_to_storage_error)?;
+
+        // JS returns { key: value }
+        let items: HashMap<String, String> = serde_wasm_bindgen::from_value(result)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        Ok(items)
+    }
+
     async fn list_payments(
         &self,
         request: breez_sdk_spark::StorageListPaymentsRequest,
@@ -154,6 +168,25 @@ impl breez_sdk_spark::Storage for WasmStorage {
         Ok(())
     }
 
+    async fn list_payment_metadata(
+        &self,
+    ) -> Result<HashMap<String, breez_sdk_spark::PaymentMetadata>, StorageError> {
+        let promise = self
+            .storage
+            .list_payment_metadata()
+            .map_err(js_error_to_storage_error)?;
+        let future = JsFuture::from(promise);
+        let result = future.await.map_err(js_error_to_storage_error)?;
+
+        // JS returns { paymentId: PaymentMetadata }
+        let metadata: HashMap<String, PaymentMetadata> = serde_wasm_bindgen::from_value(result)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        Ok(metadata
+            .into_iter()
+            .map(|(payment_id, metadata)| (payment_id, metadata.into()))
+            .collect())
+    }
+
     async fn get_payment_by_id(
         &self,
         id: String,
@@ -253,6 +286,21 @@ impl breez_sdk_spark::Storage for WasmStorage {
         Ok(())
     }
 
+    async fn list_lnurl_metadata(
+        &self,
+    ) -> Result<Vec<breez_sdk_spark::SetLnurlMetadataItem>, StorageError> {
+        let promise = self
+            .storage
+            .list_lnurl_metadata()
+            .map_err(js_error_to_storage_error)?;
+        let future = JsFuture::from(promise);
+        let result = future.await.map_err(js_error_to_storage_error)?;
+
+        let metadata: Vec<SetLnurlMetadataItem> = serde_wasm_bindgen::from_value(result)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        Ok(metadata.into_iter().map(|m| m.into()).collect())
+    }
+
     async fn get_payments_by_parent_ids(
         &self,
         parent_payment_ids: Vec<String>,
@@ -412,6 +460,21 @@ impl breez_sdk_spark::Storage for WasmStorage {
         future.await.map_err(js_error_to_storage_error)?;
         Ok(())
     }
+
+    async fn get_sync_state_records(
+        &self,
+    ) -> Result<Vec<breez_sdk_spark::sync_storage::Record>, StorageError> {
+        let promise = self
+            .storage
+            .sync_get_state_records()
+            .map_err(js_error_to_storage_error)?;
+        let future = JsFuture::from(promise);
+        let result = future.await.map_err(js_error_to_storage_error)?;
+
+        let records: Vec<Record> = serde_wasm_bindgen::from_value(result)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        Ok(records.into_iter().map(|r| r.into()).collect())
+    }
 }
 
 #[wasm_bindgen(typescript_custom_section)]
@@ -419,9 +482,11 @@ const STORAGE_INTERFACE: &'static str = r#"export interface Storage {
     getCachedItem: (key: string) => Promise<string | null>;
     setCachedItem: (key: string, value: string) => Promise<void>;
     deleteCachedItem: (key: string) => Promise<void>;
+    listCachedItems: () => Promise<{ [key: string]: string }>;
     listPayments: (request: StorageListPaymentsRequest) => Promise<Payment[]>;
     insertPayment: (payment: Payment) => Promise<void>;
     insertPaymentMetadata: (paymentId: string, metadata: PaymentMetadata) => Promise<void>;
+    listPaymentMetadata: () => Promise<{ [paymentId: string]: PaymentMetadata }>;
     getPaymentById: (id: string) => Promise<Payment>;
     getPaymentByInvoice: (invoice: string) => Promise<Payment>;
     addDeposit: (txid: string, vout: number, amount_sats: number) => Promise<void>;
@@ -429,6 +494,7 @@ const STORAGE_INTERFACE: &'static str = r#"export interface Storage {
     listDeposits: () => Promise<DepositInfo[]>;
     updateDeposit: (txid: string, vout: number, payload: UpdateDepositPayload) => Promise<void>;
     setLnurlMetadata: (metadata: SetLnurlMetadataItem[]) => Promise<void>;
+    listLnurlMetadata: () => Promise<SetLnurlMetadataItem[]>;
     getPaymentsByParentIds: (parentPaymentIds: string[]) => Promise<{ [parentId: string]: RelatedPayment[] }>;
     syncAddOutgoingChange: (record: UnversionedRecordChange) => Promise<number>;
     syncCompleteOutgoingSync: (record: Record) => Promise<void>;
@@ -439,6 +505,7 @@ const STORAGE_INTERFACE: &'static str = r#"export interface Storage {
     syncGetIncomingRecords: (limit: number) => Promise<IncomingChange[]>;
     syncGetLatestOutgoingChange: () => Promise<OutgoingChange | null>;
     syncUpdateRecordFromIncoming: (record: Record) => Promise<void>;
+    syncGetStateRecords: () => Promise<Record[]>;
 }"#;
 
 #[wasm_bindgen]
@@ -455,6 +522,9 @@ extern "C" {
     #[wasm_bindgen(structural, method, js_name = deleteCachedItem, catch)]
     pub fn delete_cached_item(this: &Storage, key: String) -> Result<Promise, JsValue>;
 
+    #[wasm_bindgen(structural, method, js_name = listCachedItems, catch)]
+    pub fn list_cached_items(this: &Storage) -> Result<Promise, JsValue>;
+
     #[wasm_bindgen(structural, method, js_name = listPayments, catch)]
     pub fn list_payments(
         this: &Storage,
@@ -471,6 +541,9 @@ extern "C" {
         metadata: PaymentMetadata,
     ) -> Result<Promise, JsValue>;
 
+    #[wasm_bindgen(structural, method, js_name = listPaymentMetadata, catch)]
+    pub fn list_payment_metadata(this: &Storage) -> Result<Promise, JsValue>;
+
     #[wasm_bindgen(structural, method, js_name = getPaymentById, catch)]
     pub fn get_payment_by_id(this: &Storage, id: String) -> Result<Promise, JsValue>;
 
@@ -505,6 +578,9 @@ extern "C" {
         metadata: Vec<SetLnurlMetadataItem>,
     ) -> Result<Promise, JsValue>;
 
+    #[wasm_bindgen(structural, method, js_name = listLnurlMetadata, catch)]
+    pub fn list_lnurl_metadata(this: &Storage) -> Result<Promise, JsValue>;
+
     #[wasm_bindgen(structural, method, js_name = getPaymentsByParentIds, catch)]
     pub fn get_payments_by_parent_ids(
         this: &Storage,
@@ -553,4 +629,7 @@ extern "C" {
         this: &Storage,
         record: Record,
     ) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(structural, method, js_name = syncGetStateRecords, catch)]
+    pub fn sync_get_state_records(this: &Storage) -> Result<Promise, JsValue>;
 }
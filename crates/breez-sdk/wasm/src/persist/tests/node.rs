@@ -163,6 +163,13 @@ async fn test_conversion_refund_needed_filtering() {
         .await;
 }
 
+#[wasm_bindgen_test]
+async fn test_conversion_filtering() {
+    let storage = create_test_storage("test_conversion_filtering").await;
+
+    breez_sdk_spark::storage_tests::test_conversion_filtering(Box::new(storage)).await;
+}
+
 #[wasm_bindgen_test]
 async fn test_token_transaction_type_filtering() {
     let storage = create_test_storage("token_tx_type_filtering").await;
@@ -177,6 +184,23 @@ async fn test_sync_storage() {
     breez_sdk_spark::storage_tests::test_sync_storage(Box::new(storage)).await;
 }
 
+#[wasm_bindgen_test]
+async fn test_migrate_storage() {
+    let source = create_test_storage("migrate_storage_source").await;
+    let target = create_test_storage("migrate_storage_target").await;
+
+    breez_sdk_spark::storage_tests::test_migrate_storage(Box::new(source), Box::new(target)).await;
+}
+
+#[wasm_bindgen_test]
+async fn test_resume_migrate_storage() {
+    let source = create_test_storage("resume_migrate_storage_source").await;
+    let target = create_test_storage("resume_migrate_storage_target").await;
+
+    breez_sdk_spark::storage_tests::test_resume_migrate_storage(Box::new(source), Box::new(target))
+        .await;
+}
+
 #[wasm_bindgen_test]
 async fn test_migration_from_v17_to_v18() {
     let data_dir = "/tmp/breez-sdk-node-migration-v17-to-v18-test";
@@ -292,6 +316,7 @@ async fn test_migration_from_v17_to_v18() {
                 decimals: 6,
                 max_supply: 2000000,
                 is_freezable: true,
+                registry_info: None,
             },
             tx_hash: "0x1111222233334444".to_string(),
             tx_type: breez_sdk_spark::TokenTransactionType::Mint,
@@ -311,6 +336,7 @@ async fn test_migration_from_v17_to_v18() {
         status_filter: None,
         asset_filter: None,
         payment_details_filter: None,
+        conversion_filter: None,
         from_timestamp: None,
         to_timestamp: None,
         offset: None,
@@ -362,6 +388,7 @@ async fn test_migration_from_v17_to_v18() {
             tx_hash: None,
             tx_type: Some(breez_sdk_spark::TokenTransactionType::Transfer),
         }]),
+        conversion_filter: None,
         from_timestamp: None,
         to_timestamp: None,
         offset: None,
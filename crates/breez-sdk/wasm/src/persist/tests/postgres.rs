@@ -140,6 +140,12 @@ async fn test_conversion_refund_needed_filtering() {
         .await;
 }
 
+#[wasm_bindgen_test]
+async fn test_conversion_filtering() {
+    let storage = create_test_storage("pg_conversion_filtering").await;
+    breez_sdk_spark::storage_tests::test_conversion_filtering(Box::new(storage)).await;
+}
+
 #[wasm_bindgen_test]
 async fn test_token_transaction_type_filtering() {
     let storage = create_test_storage("pg_token_tx_type_filtering").await;
@@ -151,3 +157,18 @@ async fn test_sync_storage() {
     let storage = create_test_storage("pg_sync_storage").await;
     breez_sdk_spark::storage_tests::test_sync_storage(Box::new(storage)).await;
 }
+
+#[wasm_bindgen_test]
+async fn test_migrate_storage() {
+    let source = create_test_storage("pg_migrate_storage_source").await;
+    let target = create_test_storage("pg_migrate_storage_target").await;
+    breez_sdk_spark::storage_tests::test_migrate_storage(Box::new(source), Box::new(target)).await;
+}
+
+#[wasm_bindgen_test]
+async fn test_resume_migrate_storage() {
+    let source = create_test_storage("pg_resume_migrate_storage_source").await;
+    let target = create_test_storage("pg_resume_migrate_storage_target").await;
+    breez_sdk_spark::storage_tests::test_resume_migrate_storage(Box::new(source), Box::new(target))
+        .await;
+}
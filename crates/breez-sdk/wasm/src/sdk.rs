@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::{rc::Rc, sync::Arc};
 
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 use wasm_bindgen::prelude::*;
@@ -9,6 +9,7 @@ use crate::{
     issuer::TokenIssuer,
     logger::{Logger, WasmTracingLayer},
     models::{chain_service::RecommendedFees, *},
+    persist::WasmStorage,
     sdk_builder::SdkBuilder,
 };
 
@@ -85,6 +86,22 @@ pub fn default_external_signer(
     Ok(crate::signer::DefaultSigner::new(signer))
 }
 
+/// Copies the complete state of a wallet from one storage to another, for example from
+/// `createDefaultStorage` to `createPostgresStorage`. The SDK must not run on either storage
+/// during the migration. An interrupted migration is resumed when run again.
+#[wasm_bindgen(js_name = "migrateStorage")]
+pub async fn migrate_storage(
+    source: crate::persist::Storage,
+    target: crate::persist::Storage,
+) -> WasmResult<StorageMigrationSummary> {
+    let summary = breez_sdk_spark::migrate_storage(
+        Arc::new(WasmStorage { storage: source }),
+        Arc::new(WasmStorage { storage: target }),
+    )
+    .await?;
+    Ok(summary.into())
+}
+
 #[wasm_bindgen]
 impl BreezSdk {
     #[wasm_bindgen(js_name = "addEventListener")]
@@ -258,6 +275,53 @@ impl BreezSdk {
         Ok(self.sdk.delete_lightning_address().await?)
     }
 
+    #[wasm_bindgen(js_name = "createLnurlWithdrawLink")]
+    pub async fn create_lnurl_withdraw_link(
+        &self,
+        request: CreateLnurlWithdrawLinkRequest,
+    ) -> WasmResult<CreateLnurlWithdrawLinkResponse> {
+        Ok(self
+            .sdk
+            .create_lnurl_withdraw_link(request.into())
+            .await?
+            .into())
+    }
+
+    #[wasm_bindgen(js_name = "listLnurlWithdrawLinks")]
+    pub async fn list_lnurl_withdraw_links(&self) -> WasmResult<ListLnurlWithdrawLinksResponse> {
+        Ok(self.sdk.list_lnurl_withdraw_links().await?.into())
+    }
+
+    #[wasm_bindgen(js_name = "deleteLnurlWithdrawLink")]
+    pub async fn delete_lnurl_withdraw_link(
+        &self,
+        request: DeleteLnurlWithdrawLinkRequest,
+    ) -> WasmResult<()> {
+        Ok(self.sdk.delete_lnurl_withdraw_link(request.into()).await?)
+    }
+
+    #[wasm_bindgen(js_name = "registerLnurlWebhook")]
+    pub async fn register_lnurl_webhook(
+        &self,
+        request: RegisterLnurlWebhookRequest,
+    ) -> WasmResult<RegisterLnurlWebhookResponse> {
+        Ok(self
+            .sdk
+            .register_lnurl_webhook(request.into())
+            .await?
+            .into())
+    }
+
+    #[wasm_bindgen(js_name = "listLnurlWebhooks")]
+    pub async fn list_lnurl_webhooks(&self) -> WasmResult<ListLnurlWebhooksResponse> {
+        Ok(self.sdk.list_lnurl_webhooks().await?.into())
+    }
+
+    #[wasm_bindgen(js_name = "deleteLnurlWebhook")]
+    pub async fn delete_lnurl_webhook(&self, request: DeleteLnurlWebhookRequest) -> WasmResult<()> {
+        Ok(self.sdk.delete_lnurl_webhook(request.into()).await?)
+    }
+
     #[wasm_bindgen(js_name = "listFiatCurrencies")]
     pub async fn list_fiat_currencies(&self) -> WasmResult<ListFiatCurrenciesResponse> {
         Ok(self.sdk.list_fiat_currencies().await?.into())
@@ -297,6 +361,19 @@ impl BreezSdk {
         Ok(self.sdk.check_message(request.into()).await?.into())
     }
 
+    #[wasm_bindgen(js_name = "exportBackup")]
+    pub async fn export_backup(&self) -> WasmResult<ExportBackupResponse> {
+        Ok(self.sdk.export_backup().await?.into())
+    }
+
+    #[wasm_bindgen(js_name = "importBackup")]
+    pub async fn import_backup(
+        &self,
+        request: ImportBackupRequest,
+    ) -> WasmResult<ImportBackupResponse> {
+        Ok(self.sdk.import_backup(request.into()).await?.into())
+    }
+
     #[wasm_bindgen(js_name = "getUserSettings")]
     pub async fn get_user_settings(&self) -> WasmResult<UserSettings> {
         Ok(self.sdk.get_user_settings().await?.into())
@@ -307,6 +384,19 @@ impl BreezSdk {
         Ok(self.sdk.update_user_settings(request.into()).await?)
     }
 
+    #[wasm_bindgen(js_name = "setSyncedValue")]
+    pub async fn set_synced_value(&self, request: SetSyncedValueRequest) -> WasmResult<()> {
+        Ok(self.sdk.set_synced_value(request.into()).await?)
+    }
+
+    #[wasm_bindgen(js_name = "getSyncedValue")]
+    pub async fn get_synced_value(
+        &self,
+        request: GetSyncedValueRequest,
+    ) -> WasmResult<GetSyncedValueResponse> {
+        Ok(self.sdk.get_synced_value(request.into()).await?.into())
+    }
+
     #[wasm_bindgen(js_name = "getTokenIssuer")]
     pub fn get_token_issuer(&self) -> TokenIssuer {
         let token_issuer = self.sdk.get_token_issuer();
@@ -342,6 +432,14 @@ impl BreezSdk {
             .into())
     }
 
+    #[wasm_bindgen(js_name = "listConversions")]
+    pub async fn list_conversions(
+        &self,
+        request: ListConversionsRequest,
+    ) -> WasmResult<ListConversionsResponse> {
+        Ok(self.sdk.list_conversions(request.into()).await?.into())
+    }
+
     #[wasm_bindgen(js_name = "buyBitcoin")]
     pub async fn buy_bitcoin(&self, request: BuyBitcoinRequest) -> WasmResult<BuyBitcoinResponse> {
         Ok(self.sdk.buy_bitcoin(request.into()).await?.into())
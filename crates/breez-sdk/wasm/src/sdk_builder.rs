@@ -9,6 +9,8 @@ use crate::{
         fiat_service::{FiatService, WasmFiatService},
         payment_observer::{PaymentObserver, WasmPaymentObserver},
         rest_client::{RestClient, WasmRestClient},
+        signing_audit_log::{SigningAuditLog, WasmSigningAuditLog},
+        token_registry_service::{TokenRegistryService, WasmTokenRegistryService},
     },
     persist::{Storage, WasmStorage},
     sdk::BreezSdk,
@@ -168,6 +170,19 @@ impl SdkBuilder {
         self
     }
 
+    #[wasm_bindgen(js_name = "withTokenRegistryService")]
+    pub fn with_token_registry_service(
+        mut self,
+        token_registry_service: TokenRegistryService,
+    ) -> Self {
+        self.builder =
+            self.builder
+                .with_token_registry_service(Arc::new(WasmTokenRegistryService {
+                    inner: token_registry_service,
+                }));
+        self
+    }
+
     #[wasm_bindgen(js_name = "withLnurlClient")]
     pub fn with_lnurl_client(mut self, lnurl_client: RestClient) -> Self {
         self.builder = self.builder.with_lnurl_client(Arc::new(WasmRestClient {
@@ -184,6 +199,14 @@ impl SdkBuilder {
         self
     }
 
+    #[wasm_bindgen(js_name = "withSigningAuditLog")]
+    pub fn with_signing_audit_log(mut self, signing_audit_log: SigningAuditLog) -> Self {
+        self.builder = self
+            .builder
+            .with_signing_audit_log(Arc::new(WasmSigningAuditLog { signing_audit_log }));
+        self
+    }
+
     #[wasm_bindgen(js_name = "build")]
     pub async fn build(mut self) -> WasmResult<BreezSdk> {
         match (self.default_storage_dir, self.storage, self.postgres_config) {
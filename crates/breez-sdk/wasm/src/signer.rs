@@ -66,6 +66,33 @@ pub enum ExternalSecretToSplit {
     Preimage { data: Vec<u8> },
 }
 
+#[macros::extern_wasm_bindgen(breez_sdk_spark::signer::external_types::ExternalSigningOperation)]
+pub enum ExternalSigningOperation {
+    Authentication,
+    Transfer,
+    ClaimTransfer,
+    LightningSend,
+    LightningReceive,
+    Htlc,
+    CoopExit,
+    LeafSwap,
+    DepositClaim,
+    StaticDepositClaim,
+    StaticDepositRefund,
+    TimelockRenewal,
+    TokenTransaction,
+    SparkInvoice,
+    SignMessage,
+}
+
+#[macros::extern_wasm_bindgen(breez_sdk_spark::signer::external_types::ExternalSigningContext)]
+pub struct ExternalSigningContext {
+    pub operation: ExternalSigningOperation,
+    pub amount: Option<u128>,
+    pub counterparty: Option<String>,
+    pub leaf_ids: Vec<ExternalTreeNodeId>,
+}
+
 #[macros::extern_wasm_bindgen(breez_sdk_spark::signer::external_types::ExternalScalar)]
 pub struct ExternalScalar {
     pub bytes: Vec<u8>,
@@ -131,6 +158,7 @@ pub struct ExternalSignFrostRequest {
     pub self_nonce_commitment: ExternalFrostCommitments,
     pub statechain_commitments: Vec<IdentifierCommitmentPair>,
     pub adaptor_public_key: Option<Vec<u8>>,
+    pub context: ExternalSigningContext,
 }
 
 #[macros::extern_wasm_bindgen(
@@ -213,9 +241,10 @@ impl DefaultSigner {
         &self,
         message: MessageBytes,
         path: String,
+        context: Option<ExternalSigningContext>,
     ) -> Result<EcdsaSignatureBytes, JsValue> {
         self.inner
-            .sign_ecdsa(message.into(), path)
+            .sign_ecdsa(message.into(), path, context.map(Into::into))
             .await
             .map(|sig| sig.into())
             .map_err(|e| JsValue::from_str(&format!("{e:?}")))
@@ -255,9 +284,10 @@ impl DefaultSigner {
         &self,
         hash: Vec<u8>,
         path: String,
+        context: Option<ExternalSigningContext>,
     ) -> Result<SchnorrSignatureBytes, JsValue> {
         self.inner
-            .sign_hash_schnorr(hash, path)
+            .sign_hash_schnorr(hash, path, context.map(Into::into))
             .await
             .map(|sig| sig.into())
             .map_err(|e| JsValue::from_str(&format!("{e:?}")))
@@ -344,9 +374,10 @@ impl DefaultSigner {
         secret: ExternalSecretToSplit,
         threshold: u32,
         num_shares: u32,
+        context: ExternalSigningContext,
     ) -> Result<Box<[ExternalVerifiableSecretShare]>, JsValue> {
         self.inner
-            .split_secret_with_proofs(secret.into(), threshold, num_shares)
+            .split_secret_with_proofs(secret.into(), threshold, num_shares, context.into())
             .await
             .map(|shares| {
                 shares
@@ -363,9 +394,14 @@ impl DefaultSigner {
         &self,
         private_key: ExternalEncryptedSecret,
         receiver_public_key: PublicKeyBytes,
+        context: ExternalSigningContext,
     ) -> Result<Vec<u8>, JsValue> {
         self.inner
-            .encrypt_secret_for_receiver(private_key.into(), receiver_public_key.into())
+            .encrypt_secret_for_receiver(
+                private_key.into(),
+                receiver_public_key.into(),
+                context.into(),
+            )
             .await
             .map_err(|e| JsValue::from_str(&format!("{e:?}")))
     }
@@ -439,8 +475,9 @@ impl breez_sdk_spark::signer::ExternalSigner for DefaultSigner {
         &self,
         message: core_types::MessageBytes,
         path: String,
+        context: Option<core_types::ExternalSigningContext>,
     ) -> Result<core_types::EcdsaSignatureBytes, SignerError> {
-        self.inner.sign_ecdsa(message, path).await
+        self.inner.sign_ecdsa(message, path, context).await
     }
 
     async fn sign_ecdsa_recoverable(
@@ -463,8 +500,9 @@ impl breez_sdk_spark::signer::ExternalSigner for DefaultSigner {
         &self,
         hash: Vec<u8>,
         path: String,
+        context: Option<core_types::ExternalSigningContext>,
     ) -> Result<core_types::SchnorrSignatureBytes, SignerError> {
-        self.inner.sign_hash_schnorr(hash, path).await
+        self.inner.sign_hash_schnorr(hash, path, context).await
     }
 
     async fn generate_random_signing_commitment(
@@ -522,9 +560,10 @@ impl breez_sdk_spark::signer::ExternalSigner for DefaultSigner {
         secret: core_types::ExternalSecretToSplit,
         threshold: u32,
         num_shares: u32,
+        context: core_types::ExternalSigningContext,
     ) -> Result<Vec<core_types::ExternalVerifiableSecretShare>, SignerError> {
         self.inner
-            .split_secret_with_proofs(secret, threshold, num_shares)
+            .split_secret_with_proofs(secret, threshold, num_shares, context)
             .await
     }
 
@@ -532,9 +571,10 @@ impl breez_sdk_spark::signer::ExternalSigner for DefaultSigner {
         &self,
         private_key: core_types::ExternalEncryptedSecret,
         receiver_public_key: core_types::PublicKeyBytes,
+        context: core_types::ExternalSigningContext,
     ) -> Result<Vec<u8>, SignerError> {
         self.inner
-            .encrypt_secret_for_receiver(private_key, receiver_public_key)
+            .encrypt_secret_for_receiver(private_key, receiver_public_key, context)
             .await
     }
 
@@ -601,11 +641,12 @@ impl breez_sdk_spark::signer::ExternalSigner for WasmExternalSigner {
         &self,
         message: core_types::MessageBytes,
         path: String,
+        context: Option<core_types::ExternalSigningContext>,
     ) -> Result<core_types::EcdsaSignatureBytes, SignerError> {
         let wasm_msg: MessageBytes = message.into();
         let promise = self
             .inner
-            .sign_ecdsa(wasm_msg, path)
+            .sign_ecdsa(wasm_msg, path, context.map(Into::into))
             .map_err(|e| SignerError::Generic(format!("JS error: {e:?}")))?;
         let future = JsFuture::from(promise);
         let result = future
@@ -670,10 +711,11 @@ impl breez_sdk_spark::signer::ExternalSigner for WasmExternalSigner {
         &self,
         hash: Vec<u8>,
         path: String,
+        context: Option<core_types::ExternalSigningContext>,
     ) -> Result<core_types::SchnorrSignatureBytes, SignerError> {
         let promise = self
             .inner
-            .sign_hash_schnorr(hash, path)
+            .sign_hash_schnorr(hash, path, context.map(Into::into))
             .map_err(|e| SignerError::Generic(format!("JS error: {e:?}")))?;
         let future = JsFuture::from(promise);
         let result = future
@@ -823,11 +865,12 @@ impl breez_sdk_spark::signer::ExternalSigner for WasmExternalSigner {
         secret: core_types::ExternalSecretToSplit,
         threshold: u32,
         num_shares: u32,
+        context: core_types::ExternalSigningContext,
     ) -> Result<Vec<core_types::ExternalVerifiableSecretShare>, SignerError> {
         let wasm_secret: ExternalSecretToSplit = secret.into();
         let promise = self
             .inner
-            .split_secret_with_proofs(wasm_secret, threshold, num_shares)
+            .split_secret_with_proofs(wasm_secret, threshold, num_shares, context.into())
             .map_err(|e| SignerError::Generic(format!("JS error: {e:?}")))?;
         let future = JsFuture::from(promise);
         let result = future
@@ -844,12 +887,13 @@ impl breez_sdk_spark::signer::ExternalSigner for WasmExternalSigner {
         &self,
         private_key: core_types::ExternalEncryptedSecret,
         receiver_public_key: core_types::PublicKeyBytes,
+        context: core_types::ExternalSigningContext,
     ) -> Result<Vec<u8>, SignerError> {
         let wasm_private_key: ExternalEncryptedSecret = private_key.into();
         let wasm_receiver_pubkey: PublicKeyBytes = receiver_public_key.into();
         let promise = self
             .inner
-            .encrypt_secret_for_receiver(wasm_private_key, wasm_receiver_pubkey)
+            .encrypt_secret_for_receiver(wasm_private_key, wasm_receiver_pubkey, context.into())
             .map_err(|e| SignerError::Generic(format!("JS error: {e:?}")))?;
         let future = JsFuture::from(promise);
         let result = future
@@ -947,11 +991,11 @@ impl breez_sdk_spark::signer::ExternalSigner for WasmExternalSigner {
 const SIGNER_INTERFACE: &'static str = r#"export interface ExternalSigner {
     identityPublicKey(): PublicKeyBytes;
     derivePublicKey(path: string): Promise<PublicKeyBytes>;
-    signEcdsa(message: MessageBytes, path: string): Promise<EcdsaSignatureBytes>;
+    signEcdsa(message: MessageBytes, path: string, context?: ExternalSigningContext): Promise<EcdsaSignatureBytes>;
     signEcdsaRecoverable(message: MessageBytes, path: string): Promise<RecoverableEcdsaSignatureBytes>;
     encryptEcies(message: Uint8Array, path: string): Promise<Uint8Array>;
     decryptEcies(message: Uint8Array, path: string): Promise<Uint8Array>;
-    signHashSchnorr(hash: Uint8Array, path: string): Promise<SchnorrSignatureBytes>;
+    signHashSchnorr(hash: Uint8Array, path: string, context?: ExternalSigningContext): Promise<SchnorrSignatureBytes>;
     generateRandomSigningCommitment(): Promise<ExternalFrostCommitments>;
     getPublicKeyForNode(id: ExternalTreeNodeId): Promise<PublicKeyBytes>;
     generateRandomSecret(): Promise<ExternalEncryptedSecret>;
@@ -959,8 +1003,8 @@ const SIGNER_INTERFACE: &'static str = r#"export interface ExternalSigner {
     staticDepositSecret(index: number): Promise<SecretBytes>;
     staticDepositSigningKey(index: number): Promise<PublicKeyBytes>;
     subtractSecrets(signingKey: ExternalSecretSource, newSigningKey: ExternalSecretSource): Promise<ExternalSecretSource>;
-    splitSecretWithProofs(secret: ExternalSecretToSplit, threshold: number, numShares: number): Promise<ExternalVerifiableSecretShare[]>;
-    encryptPrivateKeyForReceiver(privateKey: ExternalEncryptedSecret, receiverPublicKey: PublicKeyBytes): Promise<Uint8Array>;
+    splitSecretWithProofs(secret: ExternalSecretToSplit, threshold: number, numShares: number, context: ExternalSigningContext): Promise<ExternalVerifiableSecretShare[]>;
+    encryptPrivateKeyForReceiver(privateKey: ExternalEncryptedSecret, receiverPublicKey: PublicKeyBytes, context: ExternalSigningContext): Promise<Uint8Array>;
     publicKeyFromSecret(privateKey: ExternalSecretSource): Promise<PublicKeyBytes>;
     signFrost(request: ExternalSignFrostRequest): Promise<ExternalFrostSignatureShare>;
     aggregateFrost(request: ExternalAggregateFrostRequest): Promise<ExternalFrostSignature>;
@@ -983,6 +1027,7 @@ extern "C" {
         this: &JsExternalSigner,
         message: MessageBytes,
         path: String,
+        context: Option<ExternalSigningContext>,
     ) -> Result<Promise, JsValue>;
 
     #[wasm_bindgen(structural, method, js_name = "signEcdsaRecoverable", catch)]
@@ -1011,6 +1056,7 @@ extern "C" {
         this: &JsExternalSigner,
         hash: Vec<u8>,
         path: String,
+        context: Option<ExternalSigningContext>,
     ) -> Result<Promise, JsValue>;
 
     #[wasm_bindgen(structural, method, js_name = "generateFrostSigningCommitments", catch)]
@@ -1053,6 +1099,7 @@ extern "C" {
         secret: ExternalSecretToSplit,
         threshold: u32,
         num_shares: u32,
+        context: ExternalSigningContext,
     ) -> Result<Promise, JsValue>;
 
     #[wasm_bindgen(structural, method, js_name = "encryptPrivateKeyForReceiver", catch)]
@@ -1060,6 +1107,7 @@ extern "C" {
         this: &JsExternalSigner,
         private_key: ExternalEncryptedSecret,
         receiver_public_key: PublicKeyBytes,
+        context: ExternalSigningContext,
     ) -> Result<Promise, JsValue>;
 
     #[wasm_bindgen(structural, method, js_name = "getPublicKeyFromSecretSource", catch)]
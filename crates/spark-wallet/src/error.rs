@@ -8,6 +8,9 @@ pub enum SparkWalletError {
     #[error("Insufficient funds")]
     InsufficientFunds,
 
+    #[error("Token outputs are frozen by the issuer")]
+    TokenOutputsFrozen,
+
     #[error("Invalid network")]
     InvalidNetwork,
 
@@ -48,7 +51,7 @@ pub enum SparkWalletError {
     TokenOutputServiceError(#[from] spark::token::TokenOutputServiceError),
 
     #[error("Service error: {0}")]
-    ServiceError(#[from] spark::services::ServiceError),
+    ServiceError(spark::services::ServiceError),
 
     #[error("SSP error: {0}")]
     SspError(#[from] spark::ssp::ServiceProviderError),
@@ -57,6 +60,17 @@ pub enum SparkWalletError {
     Generic(String),
 }
 
+impl From<spark::services::ServiceError> for SparkWalletError {
+    fn from(error: spark::services::ServiceError) -> Self {
+        match error {
+            spark::services::ServiceError::TokenOutputsFrozen => {
+                SparkWalletError::TokenOutputsFrozen
+            }
+            e => SparkWalletError::ServiceError(e),
+        }
+    }
+}
+
 impl From<spark::operator::rpc::OperatorRpcError> for SparkWalletError {
     fn from(error: spark::operator::rpc::OperatorRpcError) -> Self {
         SparkWalletError::OperatorRpcError(Box::new(error))
@@ -18,19 +18,22 @@ pub use spark::{
     services::{
         BURN_PUBLIC_KEY, CoopExitFeeQuote, CoopExitSpeedFeeQuote, CpfpUtxo, ExitSpeed, Fee,
         FreezeIssuerTokenResponse, InvoiceDescription, LeafOptimizationOptions,
-        LightningSendPayment, LightningSendStatus, OptimizationEvent, OptimizationProgress,
-        Preimage, PreimageRequestStatus, ReceiverTokenOutput, TokenInputs, TokenTransaction,
-        TokenTransactionStatus, TransferId, TransferObserver, TransferObserverError,
-        TransferStatus, TransferTokenOutput, TransferType, Utxo,
+        LightningSendPayment, LightningSendStatus, MAX_TOKEN_TX_OUTPUTS, OptimizationEvent,
+        OptimizationProgress, Preimage, PreimageRequestStatus, ReceiverTokenOutput, TokenInputs,
+        TokenMintInput, TokenTransaction, TokenTransactionObserver, TokenTransactionStatus,
+        TokenTransferInput, TransferId, TransferObserver, TransferObserverError, TransferStatus,
+        TransferTokenOutput, TransferType, Utxo,
     },
     session_manager::*,
     signer::{
-        AggregateFrostRequest, DefaultSigner, DefaultSignerError, EncryptedSecret,
-        FrostSigningCommitmentsWithNonces, KeySet, KeySetType, SecretShare, SecretSource,
-        SecretToSplit, SignFrostRequest, Signer, SignerError, VerifiableSecretShare,
+        AggregateFrostRequest, AuditedSigner, DefaultSigner, DefaultSignerError, EncryptedSecret,
+        FrostSigningCommitmentsWithNonces, InMemorySigningAuditLog, KeySet, KeySetType,
+        SecretShare, SecretSource, SecretToSplit, SignFrostRequest, Signer, SignerError,
+        SigningAuditEntry, SigningAuditLog, SigningContext, SigningMethod, SigningOperation,
+        VerifiableSecretShare,
     },
     ssp::*,
-    token::{SelectionStrategy, TokenMetadata, TokenOutputWithPrevOut},
+    token::{SelectionStrategy, TokenMetadata, TokenOutput, TokenOutputWithPrevOut},
     tree::{SigningKeyshare, TreeNodeId},
     utils::{
         paging::{Order, PagingFilter, PagingResult},
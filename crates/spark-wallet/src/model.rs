@@ -271,6 +271,9 @@ pub enum TransferDirection {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TokenBalance {
     pub balance: u128,
+    pub available_balance: u128,
+    pub pending_outgoing_balance: u128,
+    pub reserved_for_swap_balance: u128,
     pub token_metadata: TokenMetadata,
 }
 
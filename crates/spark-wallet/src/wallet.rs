@@ -29,11 +29,12 @@ use spark::{
         OptimizationEvent, OptimizationEventHandler, OptimizationProgress, Preimage,
         PreimageRequestStatus, PreimageRequestWithTransfer, QueryHtlcFilter,
         QueryTokenTransactionsFilter, ServiceError, StaticDepositQuote, Swap, TimelockManager,
-        TokenService, TokenTransaction, Transfer, TransferId, TransferObserver, TransferService,
-        TransferStatus, TransferTokenOutput, TransferType, UnilateralExitService, Utxo,
+        TokenService, TokenTransaction, TokenTransactionObserver, Transfer, TransferId,
+        TransferObserver, TransferService, TransferStatus, TransferTokenOutput, TransferType,
+        UnilateralExitService, Utxo,
     },
     session_manager::{InMemorySessionManager, SessionManager},
-    signer::Signer,
+    signer::{Signer, SigningContext, SigningOperation},
     ssp::{ServiceProvider, SspTransfer, SspUserRequest},
     token::{
         InMemoryTokenOutputStore, SelectionStrategy, SynchronousTokenOutputService, TokenMetadata,
@@ -1078,7 +1079,10 @@ impl SparkWallet {
     pub async fn sign_message(&self, message: &str) -> Result<Signature, SparkWalletError> {
         Ok(self
             .signer
-            .sign_message_ecdsa_with_identity_key(message.as_bytes())
+            .sign_message_ecdsa_with_identity_key(
+                message.as_bytes(),
+                &SigningContext::new(SigningOperation::SignMessage),
+            )
             .await?)
     }
 
@@ -1245,11 +1249,13 @@ impl SparkWallet {
         let balances = token_outputs
             .into_iter()
             .map(|token_outputs| {
-                let balance = token_outputs.balance();
                 (
                     token_outputs.metadata.identifier.clone(),
                     TokenBalance {
-                        balance,
+                        balance: token_outputs.balance(),
+                        available_balance: token_outputs.available_balance(),
+                        pending_outgoing_balance: token_outputs.reserved_for_payment_balance(),
+                        reserved_for_swap_balance: token_outputs.reserved_for_swap_balance(),
                         token_metadata: token_outputs.metadata,
                     },
                 )
@@ -1267,6 +1273,21 @@ impl SparkWallet {
         outputs: Vec<TransferTokenOutput>,
         selected_outputs: Option<Vec<TokenOutputWithPrevOut>>,
         selection_strategy: Option<SelectionStrategy>,
+    ) -> Result<TokenTransaction, SparkWalletError> {
+        self.transfer_tokens_with_observer(outputs, selected_outputs, selection_strategy, None)
+            .await
+    }
+
+    /// Transfers tokens to another Spark user, notifying the observer of the hash of the token
+    /// transaction before it is committed.
+    ///
+    /// Multiple outputs may be provided but they must share the same token id.
+    pub async fn transfer_tokens_with_observer(
+        &self,
+        outputs: Vec<TransferTokenOutput>,
+        selected_outputs: Option<Vec<TokenOutputWithPrevOut>>,
+        selection_strategy: Option<SelectionStrategy>,
+        observer: Option<&dyn TokenTransactionObserver>,
     ) -> Result<TokenTransaction, SparkWalletError> {
         if outputs.iter().any(|o| o.spark_invoice.is_some()) {
             return Err(SparkWalletError::Generic(
@@ -1284,7 +1305,7 @@ impl SparkWallet {
 
         let tx = self
             .token_service
-            .transfer_tokens(outputs, selected_outputs, selection_strategy)
+            .transfer_tokens(outputs, selected_outputs, selection_strategy, observer)
             .await?;
         Ok(tx)
     }
@@ -1489,6 +1510,7 @@ impl SparkWallet {
                         }],
                         None,
                         None,
+                        None,
                     )
                     .await?;
 
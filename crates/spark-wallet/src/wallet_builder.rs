@@ -4,7 +4,7 @@ use spark::{
     operator::rpc::{ConnectionManager, DefaultConnectionManager},
     services::TransferObserver,
     session_manager::{InMemorySessionManager, SessionManager},
-    signer::Signer,
+    signer::{AuditedSigner, Signer, SigningAuditLog},
     token::{InMemoryTokenOutputStore, TokenOutputStore},
     tree::{InMemoryTreeStore, TreeStore},
 };
@@ -21,6 +21,7 @@ pub struct WalletBuilder {
     token_output_store: Option<Arc<dyn TokenOutputStore>>,
     connection_manager: Option<Arc<dyn ConnectionManager>>,
     transfer_observer: Option<Arc<dyn TransferObserver>>,
+    signing_audit_log: Option<Arc<dyn SigningAuditLog>>,
     with_background_processing: bool,
 }
 
@@ -35,6 +36,7 @@ impl WalletBuilder {
             token_output_store: None,
             connection_manager: None,
             transfer_observer: None,
+            signing_audit_log: None,
             with_background_processing: true,
         }
     }
@@ -83,6 +85,13 @@ impl WalletBuilder {
         self
     }
 
+    /// Records every signing operation of the wallet, with its context, in the given audit log.
+    #[must_use]
+    pub fn with_signing_audit_log(mut self, signing_audit_log: Arc<dyn SigningAuditLog>) -> Self {
+        self.signing_audit_log = Some(signing_audit_log);
+        self
+    }
+
     #[must_use]
     pub fn with_background_processing(mut self, with_background_processing: bool) -> Self {
         self.with_background_processing = with_background_processing;
@@ -90,9 +99,13 @@ impl WalletBuilder {
     }
 
     pub async fn build(self) -> Result<SparkWallet, SparkWalletError> {
+        let signer: Arc<dyn Signer> = match self.signing_audit_log {
+            Some(audit_log) => Arc::new(AuditedSigner::new(self.signer, audit_log)),
+            None => self.signer,
+        };
         SparkWallet::new(
             self.config,
-            self.signer,
+            signer,
             self.session_manager
                 .unwrap_or(Arc::new(InMemorySessionManager::default())),
             self.tree_store
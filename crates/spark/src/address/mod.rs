@@ -9,7 +9,7 @@ use crate::{
         spark_invoice_fields::PaymentType as ProtoPaymentType,
     },
     services::{bech32m_decode_token_id, bech32m_encode_token_id},
-    signer::Signer,
+    signer::{Signer, SigningContext, SigningOperation},
     utils::byte_padding::BytePadding,
 };
 use bitcoin::{
@@ -302,7 +302,10 @@ impl SparkAddress {
         let invoice_hash = self.compute_invoice_hash()?;
 
         let signature = signer
-            .sign_hash_schnorr_with_identity_key(&invoice_hash)
+            .sign_hash_schnorr_with_identity_key(
+                &invoice_hash,
+                &SigningContext::new(SigningOperation::SparkInvoice),
+            )
             .await
             .map_err(|e| AddressError::Other(format!("Failed to sign invoice hash: {e}")))?;
 
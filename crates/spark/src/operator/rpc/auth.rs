@@ -8,7 +8,7 @@ use super::spark_authn::{
 };
 use crate::operator::rpc::transport::grpc_client::Transport;
 use crate::session_manager::Session;
-use crate::signer::Signer;
+use crate::signer::{Signer, SigningContext, SigningOperation};
 use prost::Message;
 use tonic::Request;
 
@@ -70,7 +70,10 @@ impl OperatorAuth {
 
         let signature = self
             .signer
-            .sign_message_ecdsa_with_identity_key(&challenge_bytes)
+            .sign_message_ecdsa_with_identity_key(
+                &challenge_bytes,
+                &SigningContext::new(SigningOperation::Authentication),
+            )
             .await?;
 
         let verify_req = VerifyChallengeRequest {
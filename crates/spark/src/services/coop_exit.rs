@@ -25,7 +25,10 @@ use crate::utils::refund::{
 };
 use crate::utils::time::web_time_to_prost_timestamp;
 use crate::utils::transactions::{ConnectorRefundTxsParams, create_connector_refund_txs};
-use crate::{signer::Signer, tree::TreeNode};
+use crate::{
+    signer::{Signer, SigningContext, SigningOperation},
+    tree::TreeNode,
+};
 
 const COOP_EXIT_EXPIRY_DURATION_MAINNET: Duration = Duration::from_secs(7 * 24 * 60 * 60 + 5 * 60); // 1 week + 5 minutes
 const COOP_EXIT_EXPIRY_DURATION: Duration = Duration::from_secs(35 * 60); // 35 minutes
@@ -176,6 +179,8 @@ impl CoopExitService {
 
         // Build leaf key tweaks for all leaves with new signing keys
         let all_leaves = [leaves, fee_leaves.unwrap_or_default()].concat();
+        let context = SigningContext::for_leaves(SigningOperation::CoopExit, &all_leaves)
+            .with_counterparty(withdrawal_address);
         let leaf_key_tweaks =
             prepare_leaf_key_tweaks_to_send(&self.signer, all_leaves, None).await?;
 
@@ -215,6 +220,7 @@ impl CoopExitService {
                 coop_exit_input,
                 unwrapped_transfer_id,
                 raw_connector_transaction_bytes.clone(),
+                &context,
             )
             .await;
         let coop_exit_refund_signatures = match (&transfer_id, res) {
@@ -246,6 +252,7 @@ impl CoopExitService {
         exit_txid: Txid,
         transfer_id: TransferId,
         connector_tx: Vec<u8>,
+        context: &SigningContext,
     ) -> Result<CoopExitRefundSignatures, ServiceError> {
         debug!(
             "Getting connector refund signatures for connector_txid: {connector_txid}, exit_txid: {exit_txid}",
@@ -257,6 +264,7 @@ impl CoopExitService {
                 exit_txid,
                 transfer_id,
                 connector_tx,
+                context,
             )
             .await?;
 
@@ -267,6 +275,7 @@ impl CoopExitService {
                 &coop_exit_refund_signatures.transfer,
                 &leaf_key_tweaks,
                 coop_exit_refund_signatures.refund_signatures.clone(),
+                context,
             )
             .await?;
 
@@ -283,6 +292,7 @@ impl CoopExitService {
         exit_txid: Txid,
         transfer_id: TransferId,
         connector_tx: Vec<u8>,
+        context: &SigningContext,
     ) -> Result<CoopExitRefundSignatures, ServiceError> {
         debug!(
             "Signing cooperative exit refunds for connector_txid: {connector_txid}, exit_txid: {exit_txid}",
@@ -371,6 +381,7 @@ impl CoopExitService {
             None,
             None,
             None,
+            context,
         )
         .await?;
 
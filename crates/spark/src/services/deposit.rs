@@ -22,7 +22,7 @@ use crate::{
         },
     },
     services::{Transfer, Utxo},
-    signer::{SecretSource, Signer},
+    signer::{SecretSource, Signer, SigningContext, SigningOperation},
     ssp::{ClaimStaticDepositInput, ClaimStaticDepositRequestType, ServiceProvider},
     tree::{TreeNode, TreeNodeId},
     utils::{
@@ -230,7 +230,11 @@ impl DepositService {
         // Sign the payload with the identity key
         let signature = self
             .signer
-            .sign_message_ecdsa_with_identity_key(&payload)
+            .sign_message_ecdsa_with_identity_key(
+                &payload,
+                &SigningContext::new(SigningOperation::StaticDepositClaim)
+                    .with_amount(u128::from(credit_amount_sats)),
+            )
             .await?;
 
         // TODO: Seems unavoidable to use the static deposit secret key here
@@ -336,6 +340,9 @@ impl DepositService {
             "Refunding static deposit txid: {txid}, output_index: {output_index}, credit_amount_sats: {credit_amount_sats}, fee_sats: {fee_sats}"
         );
 
+        let context = SigningContext::new(SigningOperation::StaticDepositRefund)
+            .with_amount(u128::from(credit_amount_sats))
+            .with_counterparty(&refund_address);
         let spend_tx_sighash = sighash_from_tx(&refund_tx, 0, tx_out)?;
         let spend_nonce_commitment = self.signer.generate_random_signing_commitment().await?;
 
@@ -350,7 +357,7 @@ impl DepositService {
         // Sign the payload with the identity key
         let signature = self
             .signer
-            .sign_message_ecdsa_with_identity_key(&payload)
+            .sign_message_ecdsa_with_identity_key(&payload, &context)
             .await?;
 
         // Create the UTXO swap request
@@ -409,6 +416,7 @@ impl DepositService {
         let spend_signature = sign_aggregate_frost(SignAggregateFrostParams {
             signer: &self.signer,
             sighash: &spend_tx_sighash,
+            context: &context,
             signing_public_key: &verifying_public_key,
             aggregating_public_key: &static_deposit_public_key,
             signing_private_key: &static_deposit_private_key_source,
@@ -622,6 +630,10 @@ impl DepositService {
             return Err(ServiceError::InvalidVerifyingKey);
         }
 
+        let mut context = SigningContext::new(SigningOperation::DepositClaim)
+            .with_amount(u128::from(deposit_tx_out.value.to_sat()));
+        context.leaf_ids.push(deposit_leaf_id.clone());
+
         // Compute sighashes for all transactions
         let cpfp_root_sighash = sighash_from_tx(&cpfp_root_tx, 0, deposit_tx_out)?;
         let cpfp_refund_sighash = sighash_from_tx(&cpfp_refund_tx, 0, &cpfp_root_tx.output[0])?;
@@ -631,6 +643,7 @@ impl DepositService {
         let cpfp_root_signature = sign_aggregate_frost(SignAggregateFrostParams {
             signer: &self.signer,
             sighash: &cpfp_root_sighash,
+            context: &context,
             signing_public_key: &signing_public_key,
             aggregating_public_key: &signing_public_key,
             signing_private_key: &signing_private_key,
@@ -644,6 +657,7 @@ impl DepositService {
         let cpfp_refund_signature = sign_aggregate_frost(SignAggregateFrostParams {
             signer: &self.signer,
             sighash: &cpfp_refund_sighash,
+            context: &context,
             signing_public_key: &signing_public_key,
             aggregating_public_key: &signing_public_key,
             signing_private_key: &signing_private_key,
@@ -657,6 +671,7 @@ impl DepositService {
         let direct_from_cpfp_refund_signature = sign_aggregate_frost(SignAggregateFrostParams {
             signer: &self.signer,
             sighash: &direct_from_cpfp_refund_sighash,
+            context: &context,
             signing_public_key: &signing_public_key,
             aggregating_public_key: &signing_public_key,
             signing_private_key: &signing_private_key,
@@ -92,6 +92,8 @@ pub enum ServiceError {
     // Token related errors
     #[error("needed too many outputs to transfer tokens")]
     NeededTooManyOutputs,
+    #[error("token outputs are frozen by the issuer")]
+    TokenOutputsFrozen,
 
     // Common errors
     #[error("bitcoin error: {0}")]
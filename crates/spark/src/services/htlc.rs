@@ -9,6 +9,7 @@ use crate::address::SparkAddress;
 use crate::operator::rpc as operator_rpc;
 use crate::operator::rpc::spark::ProvidePreimageRequest;
 use crate::services::{Preimage, Transfer, TransferId, TransferObserver};
+use crate::signer::{SigningContext, SigningOperation};
 use crate::tree::TreeNode;
 use crate::utils::leaf_key_tweak::prepare_leaf_key_tweaks_to_send;
 use crate::utils::preimage_swap::{SwapNodesForPreimageRequest, swap_nodes_for_preimage};
@@ -75,6 +76,8 @@ impl HtlcService {
             }
         }
 
+        let context = SigningContext::for_leaves(SigningOperation::Htlc, &leaves)
+            .with_counterparty(receiver_id);
         let leaf_key_tweaks = prepare_leaf_key_tweaks_to_send(&self.signer, leaves, None).await?;
 
         let prepared_transfer_request = self
@@ -87,6 +90,7 @@ impl HtlcService {
                 Some(payment_hash),
                 Some(expiry_time),
                 None, // No adaptor public key for HTLC transfers
+                &context,
             )
             .await?;
 
@@ -107,6 +111,7 @@ impl HtlcService {
                 is_inbound_payment: false,
                 transfer_request: Some(prepared_transfer_request.transfer_request),
                 expiry_time: &expiry_time,
+                context: &context,
             },
         )
         .await
@@ -3,7 +3,7 @@ use crate::core::Network;
 use crate::operator::OperatorPool;
 use crate::operator::rpc::spark::{SecretShare, StorePreimageShareRequest};
 use crate::services::{ServiceError, Transfer, TransferId, TransferObserver, TransferService};
-use crate::signer::SecretToSplit;
+use crate::signer::{SecretToSplit, SigningContext, SigningOperation};
 use crate::ssp::{
     LightningReceiveRequestStatus, RequestLightningReceiveInput, RequestLightningSendInput,
     ServiceProvider,
@@ -362,6 +362,8 @@ impl LightningService {
                     &SecretToSplit::Preimage(preimage),
                     self.split_secret_threshold,
                     self.operator_pool.len(),
+                    &SigningContext::new(SigningOperation::LightningReceive)
+                        .with_amount(u128::from(amount_sats)),
                 )
                 .await?;
 
@@ -422,6 +424,8 @@ impl LightningService {
                 .await?;
         }
 
+        let context = SigningContext::for_leaves(SigningOperation::LightningSend, leaves)
+            .with_counterparty(invoice);
         // Prepare leaf tweaks
         let leaf_tweaks =
             prepare_leaf_key_tweaks_to_send(&self.signer, leaves.to_vec(), None).await?;
@@ -436,6 +440,7 @@ impl LightningService {
                 Some(payment_hash),
                 Some(expiry_time),
                 None, // No adaptor public key for lightning transfers
+                &context,
             )
             .await?;
 
@@ -454,6 +459,7 @@ impl LightningService {
                 is_inbound_payment: false,
                 transfer_request: Some(prepared_transfer_request.transfer_request),
                 expiry_time: &expiry_time,
+                context: &context,
             },
         )
         .await;
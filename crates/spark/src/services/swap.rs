@@ -17,7 +17,7 @@ use crate::{
         },
     },
     services::{LeafKeyTweak, ServiceError, SigningResult, Transfer, TransferId, TransferService},
-    signer::{SecretSource, Signer},
+    signer::{SecretSource, Signer, SigningContext, SigningOperation},
     ssp::{RequestSwapInput, ServiceProvider, UserLeafInput},
     tree::{TreeNode, TreeNodeId},
     utils::{frost::sign_aggregate_frost, refund::RefundSignatures},
@@ -111,6 +111,8 @@ impl Swap {
                     "failed to compute swap expiry time".to_string(),
                 ))?;
         let receiver_public_key = self.ssp_client.identity_public_key();
+        let context = SigningContext::for_leaves(SigningOperation::LeafSwap, leaves)
+            .with_counterparty(receiver_public_key);
 
         // Pre-generate adaptor key (only CPFP path is used for swap v3)
         let secp = Secp256k1::new();
@@ -131,6 +133,7 @@ impl Swap {
                 None,
                 Some(expiry_time),
                 Some(&cpfp_adaptor_public_key),
+                &context,
             )
             .await?;
 
@@ -218,6 +221,7 @@ impl Swap {
                 sign_aggregate_frost(crate::utils::frost::SignAggregateFrostParams {
                     signer: &self.signer,
                     sighash: &sighash,
+                    context: &context,
                     signing_public_key: &signed_tx.signing_public_key,
                     aggregating_public_key: &signed_tx.signing_public_key,
                     signing_private_key: &leaf_key_tweak.signing_key,
@@ -14,7 +14,7 @@ use crate::{
         },
     },
     services::{ServiceError, map_signing_nonce_commitments},
-    signer::{SecretSource, Signer},
+    signer::{SecretSource, Signer, SigningContext, SigningOperation},
     tree::{TreeNode, TreeNodeId},
     utils::{
         signing_job::{SigningJob, SigningJobType, sign_signing_jobs},
@@ -297,6 +297,7 @@ impl TimelockManager {
             signing_jobs,
             signing_commitments,
             self.network,
+            &SigningContext::for_leaves(SigningOperation::TimelockRenewal, [node]),
         )
         .await?;
 
@@ -455,6 +456,7 @@ impl TimelockManager {
             signing_jobs,
             signing_commitments,
             self.network,
+            &SigningContext::for_leaves(SigningOperation::TimelockRenewal, [node]),
         )
         .await?;
 
@@ -587,6 +589,7 @@ impl TimelockManager {
             signing_jobs,
             signing_commitments,
             self.network,
+            &SigningContext::for_leaves(SigningOperation::TimelockRenewal, [node]),
         )
         .await?;
 
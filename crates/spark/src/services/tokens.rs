@@ -26,9 +26,9 @@ use crate::{
     },
     services::{
         FreezeIssuerTokenResponse, QueryTokenTransactionsFilter, ReceiverTokenOutput, ServiceError,
-        TokenTransaction, TransferObserver, TransferTokenOutput,
+        TokenTransaction, TokenTransactionObserver, TransferObserver, TransferTokenOutput,
     },
-    signer::Signer,
+    signer::{Signer, SigningContext, SigningOperation},
     token::{
         GetTokenOutputsFilter, ReservationPurpose, ReservationTarget, SelectionStrategy,
         TokenMetadata, TokenOutputService, TokenOutputWithPrevOut, TokenOutputs,
@@ -41,6 +41,9 @@ use crate::{
 };
 
 const MAX_TOKEN_TX_INPUTS: usize = 500;
+/// Maximum number of outputs of a token transaction, including the change output. The operators
+/// apply the same limit to the inputs and to the outputs of a token transaction.
+pub const MAX_TOKEN_TX_OUTPUTS: usize = MAX_TOKEN_TX_INPUTS;
 const MAX_TRANSFER_TOKEN_TOO_MANY_OUTPUTS_RETRY_ATTEMPTS: usize = 3;
 const MAX_TOKEN_PREEMPTED_RETRY_ATTEMPTS: usize = 3;
 
@@ -381,8 +384,13 @@ impl TokenService {
             receiver_address: burn_spark_address,
             spark_invoice: None,
         }];
-        self.transfer_tokens(receiver_outputs, preferred_outputs, selection_strategy)
-            .await
+        self.transfer_tokens(
+            receiver_outputs,
+            preferred_outputs,
+            selection_strategy,
+            None,
+        )
+        .await
     }
 
     pub async fn freeze_issuer_token(
@@ -421,7 +429,11 @@ impl TokenService {
             let payload_hash = hash_freeze_tokens_payload(&freeze_tokens_payload)?;
             let issuer_signature = self
                 .signer
-                .sign_hash_schnorr_with_identity_key(&payload_hash)
+                .sign_hash_schnorr_with_identity_key(
+                    &payload_hash,
+                    &SigningContext::new(SigningOperation::TokenTransaction)
+                        .with_counterparty(spark_address),
+                )
                 .await?
                 .serialize()
                 .to_vec();
@@ -452,6 +464,7 @@ impl TokenService {
         receiver_outputs: Vec<TransferTokenOutput>,
         preferred_outputs: Option<Vec<TokenOutputWithPrevOut>>,
         selection_strategy: Option<SelectionStrategy>,
+        observer: Option<&dyn TokenTransactionObserver>,
     ) -> Result<TokenTransaction, ServiceError> {
         // Validate parameters
         if receiver_outputs.is_empty() {
@@ -492,6 +505,7 @@ impl TokenService {
                     &token_id,
                     reservation.token_outputs.outputs.clone(),
                     receiver_outputs.clone(),
+                    observer,
                 ),
                 &reservation,
             )
@@ -549,6 +563,7 @@ impl TokenService {
         token_id: &str,
         inputs: Vec<TokenOutputWithPrevOut>,
         receiver_outputs: Vec<TransferTokenOutput>,
+        observer: Option<&dyn TokenTransactionObserver>,
     ) -> Result<TokenTransaction, ServiceError> {
         if inputs.len() > MAX_TOKEN_TX_INPUTS {
             return Err(ServiceError::NeededTooManyOutputs);
@@ -582,6 +597,9 @@ impl TokenService {
                 )
                 .await?;
         }
+        if let Some(observer) = observer {
+            observer.before_commit_token_transaction(&txid).await?;
+        }
 
         self.commit_transaction(final_tx.clone()).await?;
 
@@ -843,6 +861,40 @@ impl TokenService {
         })
     }
 
+    /// Builds the signing context of a token transaction. The amount and counterparty come from
+    /// the outputs owned by other wallets, or from all outputs when minting to this wallet.
+    async fn token_signing_context(
+        &self,
+        tx: &rpc::spark_token::TokenTransaction,
+    ) -> Result<SigningContext, ServiceError> {
+        let identity_public_key = self.signer.get_identity_public_key().await?.serialize();
+        let mut context = SigningContext::new(SigningOperation::TokenTransaction);
+        let mut sent_amount = 0;
+        let mut total_amount = 0;
+        for output in &tx.token_outputs {
+            let amount = u128::from_be_bytes(
+                output
+                    .token_amount
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| ServiceError::Generic("Invalid token amount".to_string()))?,
+            );
+            total_amount += amount;
+            if output.owner_public_key != identity_public_key {
+                sent_amount += amount;
+                if context.counterparty.is_none() {
+                    context.counterparty = Some(hex::encode(&output.owner_public_key));
+                }
+            }
+        }
+        let amount = if context.counterparty.is_some() {
+            sent_amount
+        } else {
+            total_amount
+        };
+        Ok(context.with_amount(amount))
+    }
+
     fn get_operator_identity_public_keys(&self) -> Result<Vec<Vec<u8>>, ServiceError> {
         let operators = self.operator_pool.get_all_operators();
         let keys = operators
@@ -856,12 +908,13 @@ impl TokenService {
         partial_tx: rpc::spark_token::TokenTransaction,
     ) -> Result<rpc::spark_token::TokenTransaction, ServiceError> {
         let partial_tx_hash = partial_tx.compute_hash(true)?;
+        let context = self.token_signing_context(&partial_tx).await?;
 
         // Sign inputs
         let mut owner_signatures: Vec<SignatureWithIndex> = Vec::new();
         let signature = self
             .signer
-            .sign_hash_schnorr_with_identity_key(&partial_tx_hash)
+            .sign_hash_schnorr_with_identity_key(&partial_tx_hash, &context)
             .await?
             .serialize()
             .to_vec();
@@ -906,7 +959,13 @@ impl TokenService {
                 partial_token_transaction_owner_signatures: owner_signatures,
                 validity_duration_seconds: self.tokens_config.transaction_validity_duration_seconds,
             })
-            .await?;
+            .await
+            .map_err(|e| match &e {
+                OperatorRpcError::Connection(status) if is_frozen_outputs_status(status) => {
+                    ServiceError::TokenOutputsFrozen
+                }
+                _ => e.into(),
+            })?;
 
         let Some(final_tx) = start_response.final_token_transaction else {
             return Err(ServiceError::Generic(
@@ -1143,6 +1202,7 @@ impl TokenService {
         tx_hash: &[u8],
     ) -> Result<Vec<rpc::spark_token::InputTtxoSignaturesPerOperator>, ServiceError> {
         let mut per_operator_signatures = Vec::new();
+        let context = self.token_signing_context(tx).await?;
 
         for operator in self.operator_pool.get_all_operators() {
             let operator_identity_public_key_bytes =
@@ -1159,7 +1219,7 @@ impl TokenService {
             let mut signatures = Vec::new();
             let signature = self
                 .signer
-                .sign_hash_schnorr_with_identity_key(&final_hash)
+                .sign_hash_schnorr_with_identity_key(&final_hash, &context)
                 .await?
                 .serialize()
                 .to_vec();
@@ -1651,6 +1711,13 @@ fn hash_freeze_tokens_payload(
     Ok(final_hash)
 }
 
+/// Operators reject transactions spending outputs frozen by the issuer with an
+/// error mentioning the freeze. There is no dedicated error code or output status
+/// to check instead, so this is best-effort.
+fn is_frozen_outputs_status(status: &tonic::Status) -> bool {
+    status.message().to_lowercase().contains("frozen")
+}
+
 #[cfg(test)]
 mod tests {
     use macros::test_all;
@@ -1957,4 +2024,14 @@ mod tests {
         let decoded = super::bech32m_decode_token_id(&encoded, Some(Network::Regtest)).unwrap();
         assert_eq!(decoded, raw_token_id);
     }
+
+    #[test_all]
+    fn test_is_frozen_outputs_status() {
+        assert!(super::is_frozen_outputs_status(
+            &tonic::Status::failed_precondition("token output abc is Frozen by the issuer")
+        ));
+        assert!(!super::is_frozen_outputs_status(
+            &tonic::Status::invalid_argument("insufficient inputs")
+        ));
+    }
 }
@@ -13,7 +13,8 @@ use crate::operator::rpc::{self as operator_rpc, OperatorRpcError};
 use crate::services::models::{LeafKeyTweak, Transfer, map_signing_nonce_commitments};
 use crate::services::{ProofMap, TransferId, TransferObserver, TransferStatus};
 use crate::signer::{
-    FrostSigningCommitmentsWithNonces, SecretSource, SecretToSplit, VerifiableSecretShare,
+    FrostSigningCommitmentsWithNonces, SecretSource, SecretToSplit, SigningContext,
+    SigningOperation, VerifiableSecretShare,
 };
 use crate::utils::leaf_key_tweak::prepare_leaf_key_tweaks_to_send;
 use crate::utils::paging::{PagingFilter, PagingResult, pager};
@@ -147,6 +148,8 @@ impl TransferService {
             }
         }
 
+        let context = SigningContext::for_leaves(SigningOperation::Transfer, &leaves)
+            .with_counterparty(receiver_id);
         // build leaf key tweaks with new signing keys that we will send to the receiver
         let leaf_key_tweaks =
             prepare_leaf_key_tweaks_to_send(&self.signer, leaves, signing_key_source).await?;
@@ -156,6 +159,7 @@ impl TransferService {
                 &leaf_key_tweaks,
                 receiver_id,
                 spark_invoice,
+                &context,
             )
             .await;
         let transfer = match (&transfer_id, transfer_res) {
@@ -217,6 +221,7 @@ impl TransferService {
         leaf_key_tweaks: &[LeafKeyTweak],
         receiver_id: &PublicKey,
         spark_invoice: Option<String>,
+        context: &SigningContext,
     ) -> Result<Transfer, ServiceError> {
         let key_tweak_input_map = self
             .prepare_send_transfer_key_tweaks(
@@ -224,6 +229,7 @@ impl TransferService {
                 receiver_id,
                 leaf_key_tweaks,
                 Default::default(),
+                context,
             )
             .await?;
 
@@ -235,6 +241,7 @@ impl TransferService {
                 receiver_id,
                 None,
                 None, // No adaptor public key for regular transfers
+                context,
             )
             .await?;
 
@@ -276,6 +283,7 @@ impl TransferService {
         receiver_public_key: &PublicKey,
         leaves: &[LeafKeyTweak],
         refund_signatures: RefundSignatures,
+        context: &SigningContext,
     ) -> Result<HashMap<Identifier, Vec<operator_rpc::spark::SendLeafKeyTweak>>, ServiceError> {
         let mut leaves_tweaks_map = HashMap::new();
 
@@ -301,6 +309,7 @@ impl TransferService {
                     cpfp_refund_signature,
                     direct_refund_signature,
                     direct_from_cpfp_refund_signature,
+                    context,
                 )
                 .await?;
 
@@ -325,6 +334,7 @@ impl TransferService {
         cpfp_refund_signature: Option<Signature>,
         direct_refund_signature: Option<Signature>,
         direct_from_cpfp_refund_signature: Option<Signature>,
+        context: &SigningContext,
     ) -> Result<HashMap<Identifier, operator_rpc::spark::SendLeafKeyTweak>, ServiceError> {
         // Calculate the key tweak by subtracting keys
         let privkey_tweak = self
@@ -339,6 +349,7 @@ impl TransferService {
                 &SecretToSplit::SecretSource(privkey_tweak),
                 self.split_secret_threshold,
                 self.operator_pool.len(),
+                context,
             )
             .await?;
 
@@ -374,7 +385,7 @@ impl TransferService {
             }
             SecretSource::Encrypted(private_key) => {
                 self.signer
-                    .encrypt_secret_for_receiver(private_key, receiver_public_key)
+                    .encrypt_secret_for_receiver(private_key, receiver_public_key, context)
                     .await?
             }
         };
@@ -388,7 +399,7 @@ impl TransferService {
         // Sign the hash with identity key
         let signature = self
             .signer
-            .sign_message_ecdsa_with_identity_key(&payload)
+            .sign_message_ecdsa_with_identity_key(&payload, context)
             .await?;
 
         trace!(
@@ -444,6 +455,7 @@ impl TransferService {
         receiver_public_key: &PublicKey,
         payment_hash: Option<&sha256::Hash>,
         cpfp_adaptor_public_key: Option<&PublicKey>,
+        context: &SigningContext,
     ) -> Result<PreparedTransferPackage, ServiceError> {
         let signing_commitments = self
             .operator_pool
@@ -491,6 +503,7 @@ impl TransferService {
             payment_hash,
             network: self.network,
             cpfp_adaptor_public_key,
+            context,
         })
         .await?;
 
@@ -517,7 +530,7 @@ impl TransferService {
         };
 
         let signed_transfer_package = self
-            .sign_transfer_package(transfer_id, unsigned_transfer_package)
+            .sign_transfer_package(transfer_id, unsigned_transfer_package, context)
             .await?;
 
         Ok(PreparedTransferPackage {
@@ -536,6 +549,7 @@ impl TransferService {
         payment_hash: Option<&sha256::Hash>,
         expiry_time: Option<SystemTime>,
         cpfp_adaptor_public_key: Option<&PublicKey>,
+        context: &SigningContext,
     ) -> Result<PreparedTransferRequest, ServiceError> {
         let key_tweak_input_map = self
             .prepare_send_transfer_key_tweaks(
@@ -543,6 +557,7 @@ impl TransferService {
                 receiver_public_key,
                 leaves,
                 refund_signatures,
+                context,
             )
             .await?;
 
@@ -554,6 +569,7 @@ impl TransferService {
                 receiver_public_key,
                 payment_hash,
                 cpfp_adaptor_public_key,
+                context,
             )
             .await?;
 
@@ -629,13 +645,14 @@ impl TransferService {
         &self,
         transfer_id: &TransferId,
         transfer_package: operator_rpc::spark::TransferPackage,
+        context: &SigningContext,
     ) -> Result<operator_rpc::spark::TransferPackage, ServiceError> {
         let signing_payload =
             self.get_transfer_package_signing_payload(transfer_id, &transfer_package)?;
 
         let signature = self
             .signer
-            .sign_message_ecdsa_with_identity_key(&signing_payload)
+            .sign_message_ecdsa_with_identity_key(&signing_payload, context)
             .await
             .map_err(ServiceError::SignerError)?;
 
@@ -776,10 +793,15 @@ impl TransferService {
         leaves_to_claim: Vec<LeafKeyTweak>,
     ) -> Result<Vec<TreeNode>, ServiceError> {
         trace!("Claiming transfer with leaves: {:?}", leaves_to_claim);
+        let context = SigningContext::for_leaves(
+            SigningOperation::ClaimTransfer,
+            leaves_to_claim.iter().map(|l| &l.node),
+        )
+        .with_counterparty(transfer.sender_identity_public_key);
         // Check if we need to apply key tweaks first
         let proof_map = if transfer.status == TransferStatus::SenderKeyTweaked {
             Some(
-                self.claim_transfer_tweak_keys(transfer, &leaves_to_claim)
+                self.claim_transfer_tweak_keys(transfer, &leaves_to_claim, &context)
                     .await
                     .map_err(|e| {
                         debug!("Failed to claim transfer tweak keys: {}", e);
@@ -793,7 +815,7 @@ impl TransferService {
         debug!("Claim transfer tweak keys successful.");
         // Sign refunds and get node signatures
         let node_signatures_result = self
-            .claim_transfer_sign_refunds(transfer, &leaves_to_claim, proof_map.as_ref())
+            .claim_transfer_sign_refunds(transfer, &leaves_to_claim, proof_map.as_ref(), &context)
             .await;
 
         let node_signatures = match node_signatures_result {
@@ -846,8 +868,11 @@ impl TransferService {
         &self,
         transfer: &Transfer,
         leaves: &[LeafKeyTweak],
+        context: &SigningContext,
     ) -> Result<ProofMap, ServiceError> {
-        let (leaves_tweaks_map, proof_map) = self.prepare_claim_leaves_key_tweaks(leaves).await?;
+        let (leaves_tweaks_map, proof_map) = self
+            .prepare_claim_leaves_key_tweaks(leaves, context)
+            .await?;
 
         // Send claim transfer tweak keys to all signing operators in parallel
         let mut tasks = Vec::new();
@@ -951,6 +976,7 @@ impl TransferService {
     async fn prepare_claim_leaves_key_tweaks(
         &self,
         leaves: &[LeafKeyTweak],
+        context: &SigningContext,
     ) -> Result<
         (
             HashMap<Identifier, Vec<operator_rpc::spark::ClaimLeafKeyTweak>>,
@@ -962,7 +988,8 @@ impl TransferService {
         let mut proof_map = HashMap::new();
 
         for leaf in leaves {
-            let (leaf_key_tweaks, proof) = self.prepare_claim_leaf_key_tweaks(leaf).await?;
+            let (leaf_key_tweaks, proof) =
+                self.prepare_claim_leaf_key_tweaks(leaf, context).await?;
             proof_map.insert(leaf.node.id.clone(), proof);
 
             for (identifier, leaf_tweak) in leaf_key_tweaks {
@@ -980,6 +1007,7 @@ impl TransferService {
     async fn prepare_claim_leaf_key_tweaks(
         &self,
         leaf: &LeafKeyTweak,
+        context: &SigningContext,
     ) -> Result<
         (
             HashMap<Identifier, operator_rpc::spark::ClaimLeafKeyTweak>,
@@ -1000,6 +1028,7 @@ impl TransferService {
                 &SecretToSplit::SecretSource(privkey_tweak),
                 self.split_secret_threshold,
                 self.operator_pool.len(),
+                context,
             )
             .await?;
 
@@ -1061,6 +1090,7 @@ impl TransferService {
         leaf_keys: &[LeafKeyTweak],
         // TODO: do something with proofs? Currently not used in js implementation
         _proof_map: Option<&ProofMap>,
+        context: &SigningContext,
     ) -> Result<Vec<operator_rpc::spark::NodeSignatures>, ServiceError> {
         // Prepare leaf data map with refund signing information
         let mut leaf_data_map = HashMap::new();
@@ -1139,6 +1169,7 @@ impl TransferService {
             None,
             None,
             None,
+            context,
         )
         .await?;
 
@@ -1428,6 +1459,7 @@ impl TransferService {
         transfer: &Transfer,
         leaves: &[LeafKeyTweak],
         refund_signatures: RefundSignatures,
+        context: &SigningContext,
     ) -> Result<Transfer, ServiceError> {
         let prepared_transfer_request = self
             .prepare_transfer_request(
@@ -1438,6 +1470,7 @@ impl TransferService {
                 None,
                 None,
                 None, // No adaptor public key for regular transfers
+                context,
             )
             .await?;
 
@@ -43,3 +43,14 @@ pub trait TransferObserver: Send + Sync {
         amount_sats: u64,
     ) -> Result<(), TransferObserverError>;
 }
+
+/// Observes a single token transfer. Called with the hash of each final token transaction right
+/// before it is committed, so callers can look the transaction up by its hash if the outcome of
+/// the transfer is unknown. Returning an error aborts the transfer before it is committed.
+#[macros::async_trait]
+pub trait TokenTransactionObserver: Send + Sync {
+    async fn before_commit_token_transaction(
+        &self,
+        tx_hash: &str,
+    ) -> Result<(), TransferObserverError>;
+}
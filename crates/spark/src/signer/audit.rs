@@ -0,0 +1,318 @@
+use std::sync::{Arc, Mutex};
+
+use bitcoin::hashes::{Hash, sha256};
+use bitcoin::secp256k1::{PublicKey, SecretKey, ecdsa::Signature, schnorr};
+use frost_secp256k1_tr::round2::SignatureShare;
+use serde::{Deserialize, Serialize};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use crate::signer::{
+    AggregateFrostRequest, EncryptedSecret, FrostSigningCommitmentsWithNonces, SecretSource,
+    SecretToSplit, SignFrostRequest, Signer, SignerError, SigningContext, VerifiableSecretShare,
+};
+use crate::tree::TreeNodeId;
+
+/// The [`Signer`] method an audit entry was recorded for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SigningMethod {
+    SignMessageEcdsa,
+    SignHashSchnorr,
+    SplitSecret,
+    EncryptSecretForReceiver,
+    SignFrost,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningAuditEntry {
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+    pub method: SigningMethod,
+    pub context: SigningContext,
+    /// The signed digest. `None` for methods sharing secrets, which don't sign anything.
+    pub digest: Option<Vec<u8>>,
+    /// The resulting signature, or signature share for FROST. `None` for methods sharing
+    /// secrets, their results aren't logged.
+    pub signature: Option<Vec<u8>>,
+}
+
+/// Append-only log of the signatures made by an [`AuditedSigner`].
+#[macros::async_trait]
+pub trait SigningAuditLog: Send + Sync {
+    /// Appends an entry. Entries are never updated or removed.
+    async fn append(&self, entry: SigningAuditEntry) -> Result<(), SignerError>;
+}
+
+#[derive(Default)]
+pub struct InMemorySigningAuditLog {
+    entries: Mutex<Vec<SigningAuditEntry>>,
+}
+
+impl InMemorySigningAuditLog {
+    pub fn entries(&self) -> Vec<SigningAuditEntry> {
+        self.entries
+            .lock()
+            .expect("audit log lock poisoned")
+            .clone()
+    }
+}
+
+#[macros::async_trait]
+impl SigningAuditLog for InMemorySigningAuditLog {
+    async fn append(&self, entry: SigningAuditEntry) -> Result<(), SignerError> {
+        self.entries
+            .lock()
+            .map_err(|_| SignerError::AuditLogError("audit log lock poisoned".to_string()))?
+            .push(entry);
+        Ok(())
+    }
+}
+
+/// Wraps a [`Signer`], recording every signature and shared secret in a [`SigningAuditLog`].
+///
+/// Results are only returned once their entry is appended, so nothing leaves the signer
+/// without an audit trail.
+pub struct AuditedSigner {
+    inner: Arc<dyn Signer>,
+    audit_log: Arc<dyn SigningAuditLog>,
+}
+
+impl AuditedSigner {
+    pub fn new(inner: Arc<dyn Signer>, audit_log: Arc<dyn SigningAuditLog>) -> Self {
+        Self { inner, audit_log }
+    }
+
+    async fn record(
+        &self,
+        method: SigningMethod,
+        context: &SigningContext,
+        digest: Option<Vec<u8>>,
+        signature: Option<Vec<u8>>,
+    ) -> Result<(), SignerError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| SignerError::AuditLogError(e.to_string()))?
+            .as_secs();
+        self.audit_log
+            .append(SigningAuditEntry {
+                timestamp,
+                method,
+                context: context.clone(),
+                digest,
+                signature,
+            })
+            .await
+    }
+}
+
+#[macros::async_trait]
+impl Signer for AuditedSigner {
+    async fn sign_message_ecdsa_with_identity_key(
+        &self,
+        message: &[u8],
+        context: &SigningContext,
+    ) -> Result<Signature, SignerError> {
+        let signature = self
+            .inner
+            .sign_message_ecdsa_with_identity_key(message, context)
+            .await?;
+        let digest = sha256::Hash::hash(message).to_byte_array().to_vec();
+        self.record(
+            SigningMethod::SignMessageEcdsa,
+            context,
+            Some(digest),
+            Some(signature.serialize_compact().to_vec()),
+        )
+        .await?;
+        Ok(signature)
+    }
+
+    async fn sign_hash_schnorr_with_identity_key(
+        &self,
+        hash: &[u8],
+        context: &SigningContext,
+    ) -> Result<schnorr::Signature, SignerError> {
+        let signature = self
+            .inner
+            .sign_hash_schnorr_with_identity_key(hash, context)
+            .await?;
+        self.record(
+            SigningMethod::SignHashSchnorr,
+            context,
+            Some(hash.to_vec()),
+            Some(signature.serialize().to_vec()),
+        )
+        .await?;
+        Ok(signature)
+    }
+
+    async fn generate_random_signing_commitment(
+        &self,
+    ) -> Result<FrostSigningCommitmentsWithNonces, SignerError> {
+        self.inner.generate_random_signing_commitment().await
+    }
+
+    async fn get_public_key_for_node(&self, id: &TreeNodeId) -> Result<PublicKey, SignerError> {
+        self.inner.get_public_key_for_node(id).await
+    }
+
+    async fn generate_random_secret(&self) -> Result<EncryptedSecret, SignerError> {
+        self.inner.generate_random_secret().await
+    }
+
+    async fn get_identity_public_key(&self) -> Result<PublicKey, SignerError> {
+        self.inner.get_identity_public_key().await
+    }
+
+    async fn static_deposit_secret_encrypted(
+        &self,
+        index: u32,
+    ) -> Result<SecretSource, SignerError> {
+        self.inner.static_deposit_secret_encrypted(index).await
+    }
+
+    async fn static_deposit_secret(&self, index: u32) -> Result<SecretKey, SignerError> {
+        self.inner.static_deposit_secret(index).await
+    }
+
+    async fn static_deposit_signing_key(&self, index: u32) -> Result<PublicKey, SignerError> {
+        self.inner.static_deposit_signing_key(index).await
+    }
+
+    async fn subtract_secrets(
+        &self,
+        signing_key: &SecretSource,
+        new_signing_key: &SecretSource,
+    ) -> Result<SecretSource, SignerError> {
+        self.inner
+            .subtract_secrets(signing_key, new_signing_key)
+            .await
+    }
+
+    async fn split_secret_with_proofs(
+        &self,
+        secret: &SecretToSplit,
+        threshold: u32,
+        num_shares: usize,
+        context: &SigningContext,
+    ) -> Result<Vec<VerifiableSecretShare>, SignerError> {
+        let shares = self
+            .inner
+            .split_secret_with_proofs(secret, threshold, num_shares, context)
+            .await?;
+        self.record(SigningMethod::SplitSecret, context, None, None)
+            .await?;
+        Ok(shares)
+    }
+
+    async fn encrypt_secret_for_receiver(
+        &self,
+        private_key: &EncryptedSecret,
+        receiver_public_key: &PublicKey,
+        context: &SigningContext,
+    ) -> Result<Vec<u8>, SignerError> {
+        let ciphertext = self
+            .inner
+            .encrypt_secret_for_receiver(private_key, receiver_public_key, context)
+            .await?;
+        self.record(SigningMethod::EncryptSecretForReceiver, context, None, None)
+            .await?;
+        Ok(ciphertext)
+    }
+
+    async fn public_key_from_secret(
+        &self,
+        private_key: &SecretSource,
+    ) -> Result<PublicKey, SignerError> {
+        self.inner.public_key_from_secret(private_key).await
+    }
+
+    async fn sign_frost<'a>(
+        &self,
+        request: SignFrostRequest<'a>,
+    ) -> Result<SignatureShare, SignerError> {
+        let context = request.context;
+        let message = request.message;
+        let signature_share = self.inner.sign_frost(request).await?;
+        self.record(
+            SigningMethod::SignFrost,
+            context,
+            Some(message.to_vec()),
+            Some(signature_share.serialize()),
+        )
+        .await?;
+        Ok(signature_share)
+    }
+
+    async fn aggregate_frost<'a>(
+        &self,
+        request: AggregateFrostRequest<'a>,
+    ) -> Result<frost_secp256k1_tr::Signature, SignerError> {
+        self.inner.aggregate_frost(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bitcoin::hashes::{Hash, sha256};
+    use macros::async_test_all;
+
+    use crate::signer::{
+        AuditedSigner, InMemorySigningAuditLog, Signer, SignerError, SigningAuditEntry,
+        SigningAuditLog, SigningContext, SigningMethod, SigningOperation, create_test_signer,
+    };
+
+    #[cfg(feature = "browser-tests")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    struct FailingAuditLog;
+
+    #[macros::async_trait]
+    impl SigningAuditLog for FailingAuditLog {
+        async fn append(&self, _entry: SigningAuditEntry) -> Result<(), SignerError> {
+            Err(SignerError::AuditLogError("disk full".to_string()))
+        }
+    }
+
+    #[async_test_all]
+    async fn test_audited_signer_records_context() {
+        let audit_log = Arc::new(InMemorySigningAuditLog::default());
+        let signer = AuditedSigner::new(Arc::new(create_test_signer()), audit_log.clone());
+        let context = SigningContext::new(SigningOperation::Transfer)
+            .with_amount(1_000)
+            .with_counterparty("receiver");
+
+        let signature = signer
+            .sign_message_ecdsa_with_identity_key(b"message", &context)
+            .await
+            .unwrap();
+        // Calls that don't sign aren't recorded.
+        signer.get_identity_public_key().await.unwrap();
+
+        let entries = audit_log.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].method, SigningMethod::SignMessageEcdsa);
+        assert_eq!(entries[0].context, context);
+        let digest = sha256::Hash::hash(b"message").to_byte_array().to_vec();
+        assert_eq!(entries[0].digest, Some(digest));
+        assert_eq!(
+            entries[0].signature,
+            Some(signature.serialize_compact().to_vec())
+        );
+    }
+
+    #[async_test_all]
+    async fn test_audited_signer_fails_without_audit_trail() {
+        let signer = AuditedSigner::new(Arc::new(create_test_signer()), Arc::new(FailingAuditLog));
+
+        let result = signer
+            .sign_message_ecdsa_with_identity_key(
+                b"message",
+                &SigningContext::new(SigningOperation::SignMessage),
+            )
+            .await;
+        assert!(matches!(result, Err(SignerError::AuditLogError(_))));
+    }
+}
@@ -20,7 +20,7 @@ use thiserror::Error;
 
 use crate::signer::{
     AggregateFrostRequest, EncryptedSecret, FrostSigningCommitmentsWithNonces, SignFrostRequest,
-    secret_sharing,
+    SigningContext, secret_sharing,
 };
 use crate::signer::{SecretSource, SecretToSplit};
 use crate::tree::TreeNodeId;
@@ -388,6 +388,7 @@ impl Signer for DefaultSigner {
     async fn sign_message_ecdsa_with_identity_key(
         &self,
         message: &[u8],
+        _context: &SigningContext,
     ) -> Result<Signature, SignerError> {
         let digest = sha256::Hash::hash(message);
         let sig = self.secp.sign_ecdsa(
@@ -400,6 +401,7 @@ impl Signer for DefaultSigner {
     async fn sign_hash_schnorr_with_identity_key(
         &self,
         hash: &[u8],
+        _context: &SigningContext,
     ) -> Result<schnorr::Signature, SignerError> {
         if hash.len() != 32 {
             return Err(SignerError::Generic(
@@ -519,6 +521,7 @@ impl Signer for DefaultSigner {
         &self,
         private_key: &EncryptedSecret,
         receiver_public_key: &PublicKey,
+        _context: &SigningContext,
     ) -> Result<Vec<u8>, SignerError> {
         let private_key = SecretSource::Encrypted(private_key.clone()).to_secret_key(self)?;
 
@@ -538,6 +541,7 @@ impl Signer for DefaultSigner {
         secret: &SecretToSplit,
         threshold: u32,
         num_shares: usize,
+        _context: &SigningContext,
     ) -> Result<Vec<VerifiableSecretShare>, SignerError> {
         let secret_bytes = match secret {
             SecretToSplit::SecretSource(privkey_source) => {
@@ -762,7 +766,9 @@ pub(crate) mod tests {
     use macros::async_test_all;
     use std::str::FromStr;
 
-    use crate::signer::{EncryptedSecret, SecretSource, Signer, SignerError};
+    use crate::signer::{
+        EncryptedSecret, SecretSource, Signer, SignerError, SigningContext, SigningOperation,
+    };
     use crate::tree::TreeNodeId;
     use crate::utils::verify_signature::verify_signature_ecdsa;
     use crate::{Network, signer::default_signer::DefaultSigner};
@@ -780,7 +786,10 @@ pub(crate) mod tests {
         let signer = create_test_signer();
         let message = "test message";
         let signature = signer
-            .sign_message_ecdsa_with_identity_key(message.as_bytes())
+            .sign_message_ecdsa_with_identity_key(
+                message.as_bytes(),
+                &SigningContext::new(SigningOperation::SignMessage),
+            )
             .await
             .expect("Failed to sign message");
 
@@ -797,7 +806,10 @@ pub(crate) mod tests {
     async fn test_verify_signature_ecdsa_invalid_signature() {
         let signer = create_test_signer();
         let signature = signer
-            .sign_message_ecdsa_with_identity_key("signed message".as_bytes())
+            .sign_message_ecdsa_with_identity_key(
+                "signed message".as_bytes(),
+                &SigningContext::new(SigningOperation::SignMessage),
+            )
             .await
             .expect("Failed to sign message");
 
@@ -964,6 +976,7 @@ pub(crate) mod tests {
             .encrypt_secret_for_receiver(
                 &EncryptedSecret::new(encrypted_private_key),
                 &receiver_public_key,
+                &SigningContext::new(SigningOperation::Transfer),
             )
             .await
             .expect("Failed to encrypt for receiver");
@@ -19,6 +19,9 @@ pub enum SignerError {
     #[error("taproot builder error: {0}")]
     TaprootBuilderError(String),
 
+    #[error("audit log error: {0}")]
+    AuditLogError(String),
+
     #[error("unknown key")]
     UnknownKey,
     #[error("unknown nonce commitment")]
@@ -1,3 +1,4 @@
+mod audit;
 mod default_signer;
 mod error;
 mod models;
@@ -8,6 +9,7 @@ use bitcoin::secp256k1::ecdsa::Signature;
 use bitcoin::secp256k1::{PublicKey, SecretKey, schnorr};
 use frost_secp256k1_tr::round2::SignatureShare;
 
+pub use audit::*;
 pub use default_signer::{DefaultSigner, DefaultSignerError, KeySet, KeySetType};
 pub use error::SignerError;
 pub use models::*;
@@ -15,16 +17,22 @@ pub use models::*;
 #[cfg(test)]
 pub(crate) use default_signer::tests::create_test_signer;
 
+/// Signs on behalf of the wallet.
+///
+/// Methods that sign or share key material receive a [`SigningContext`] describing the wallet
+/// operation they are called for.
 #[macros::async_trait]
 pub trait Signer: Send + Sync + 'static {
     async fn sign_message_ecdsa_with_identity_key(
         &self,
         message: &[u8],
+        context: &SigningContext,
     ) -> Result<Signature, SignerError>;
 
     async fn sign_hash_schnorr_with_identity_key(
         &self,
         hash: &[u8],
+        context: &SigningContext,
     ) -> Result<schnorr::Signature, SignerError>;
 
     async fn generate_random_signing_commitment(
@@ -61,6 +69,7 @@ pub trait Signer: Send + Sync + 'static {
         secret: &SecretToSplit,
         threshold: u32,
         num_shares: usize,
+        context: &SigningContext,
     ) -> Result<Vec<VerifiableSecretShare>, SignerError>;
 
     /// Takes an encrypted private key (encrypted for us) and returns an encrypted private key (encrypted for receiver)
@@ -68,6 +77,7 @@ pub trait Signer: Send + Sync + 'static {
         &self,
         private_key: &EncryptedSecret,
         receiver_public_key: &PublicKey,
+        context: &SigningContext,
     ) -> Result<Vec<u8>, SignerError>;
 
     async fn public_key_from_secret(
@@ -83,6 +93,7 @@ pub trait Signer: Send + Sync + 'static {
     ///
     /// # Parameters
     /// * `message` - The message being signed
+    /// * `context` - The wallet operation the signature is for
     /// * `public_key` - The public key associated with the local signing key
     /// * `private_key` - Private key used for signing
     /// * `verifying_key` - The group's verifying key (threshold public key)
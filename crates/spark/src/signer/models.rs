@@ -3,8 +3,9 @@ use std::collections::BTreeMap;
 use bitcoin::secp256k1::PublicKey;
 use frost_secp256k1_tr::{Identifier, round1::SigningCommitments, round2::SignatureShare};
 use k256::{PublicKey as k256PublicKey, Scalar};
+use serde::{Deserialize, Serialize};
 
-use crate::tree::TreeNodeId;
+use crate::tree::{TreeNode, TreeNodeId};
 
 #[derive(Debug, Clone)]
 pub struct SecretShare {
@@ -65,6 +66,7 @@ pub struct FrostSigningCommitmentsWithNonces {
 
 pub struct SignFrostRequest<'a> {
     pub message: &'a [u8],
+    pub context: &'a SigningContext,
     pub public_key: &'a PublicKey,
     pub private_key: &'a SecretSource,
     pub verifying_key: &'a PublicKey,
@@ -84,3 +86,92 @@ pub struct AggregateFrostRequest<'a> {
     pub self_signature: &'a SignatureShare,
     pub adaptor_public_key: Option<&'a PublicKey>,
 }
+
+/// The wallet operation a signer request is made for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SigningOperation {
+    /// Authenticating with the Spark operators or the SSP
+    Authentication,
+    /// Sending leaves to another Spark wallet
+    Transfer,
+    /// Claiming leaves sent to this wallet
+    ClaimTransfer,
+    /// Paying a lightning invoice through the SSP
+    LightningSend,
+    /// Creating a lightning invoice, sharing its preimage with the operators
+    LightningReceive,
+    /// Locking leaves in an HTLC
+    Htlc,
+    /// Withdrawing leaves onchain through a cooperative exit with the SSP
+    CoopExit,
+    /// Swapping leaves with the SSP for leaves of other denominations
+    LeafSwap,
+    /// Claiming an onchain deposit into a new leaf
+    DepositClaim,
+    /// Claiming a static deposit through the SSP
+    StaticDepositClaim,
+    /// Refunding a static deposit onchain
+    StaticDepositRefund,
+    /// Renewing the timelocks of leaves
+    TimelockRenewal,
+    /// Creating, minting, burning, freezing or transferring tokens
+    TokenTransaction,
+    /// Creating a Spark invoice
+    SparkInvoice,
+    /// Signing an arbitrary message with the identity key
+    SignMessage,
+}
+
+/// Describes what a signer request is for, so signers can apply policies and keep an audit trail.
+///
+/// The context is informational: signers can't verify it against the signed message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningContext {
+    pub operation: SigningOperation,
+    /// Amount moved by the operation, in satoshis or token base units for token transactions
+    pub amount: Option<u128>,
+    /// The other side of the operation: the identity public key of the receiving wallet, the
+    /// withdrawal address or the lightning invoice
+    pub counterparty: Option<String>,
+    /// The leaves the operation spends
+    pub leaf_ids: Vec<TreeNodeId>,
+}
+
+impl SigningContext {
+    pub fn new(operation: SigningOperation) -> Self {
+        Self {
+            operation,
+            amount: None,
+            counterparty: None,
+            leaf_ids: Vec::new(),
+        }
+    }
+
+    /// Creates a context spending the given leaves, with their total value as amount.
+    pub fn for_leaves<'a>(
+        operation: SigningOperation,
+        leaves: impl IntoIterator<Item = &'a TreeNode>,
+    ) -> Self {
+        let mut context = Self::new(operation);
+        let mut amount = 0;
+        for leaf in leaves {
+            amount += u128::from(leaf.value);
+            context.leaf_ids.push(leaf.id.clone());
+        }
+        context.amount = Some(amount);
+        context
+    }
+
+    #[must_use]
+    pub fn with_amount(mut self, amount: u128) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    #[must_use]
+    pub fn with_counterparty(mut self, counterparty: impl ToString) -> Self {
+        self.counterparty = Some(counterparty.to_string());
+        self
+    }
+}
@@ -10,7 +10,7 @@ use platform_utils::{ContentType, HttpClient, add_content_type_header, create_ht
 
 use crate::default_user_agent;
 use crate::session_manager::{Session, SessionManager};
-use crate::signer::Signer;
+use crate::signer::{Signer, SigningContext, SigningOperation};
 use crate::ssp::graphql::error::{GraphQLError, GraphQLResult};
 use crate::ssp::graphql::queries::{
     self, claim_static_deposit, complete_coop_exit, coop_exit_fee_quote, get_challenge,
@@ -180,7 +180,10 @@ impl GraphQLClient {
         // Sign the challenge with the identity key
         let signature = self
             .signer
-            .sign_message_ecdsa_with_identity_key(&challenge_bytes)
+            .sign_message_ecdsa_with_identity_key(
+                &challenge_bytes,
+                &SigningContext::new(SigningOperation::Authentication),
+            )
             .await?
             .serialize_der()
             .to_vec();
@@ -82,6 +82,13 @@ impl TokenOutputsPerStatus {
             .sum()
     }
 
+    pub fn reserved_for_payment_balance(&self) -> u128 {
+        self.reserved_for_payment
+            .iter()
+            .map(|o| o.output.token_amount)
+            .sum()
+    }
+
     pub fn balance(&self) -> u128 {
         self.available_balance() + self.reserved_for_swap_balance()
     }
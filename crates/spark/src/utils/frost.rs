@@ -8,11 +8,12 @@ use crate::services::SigningResult;
 use crate::signer::{
     AggregateFrostRequest, FrostSigningCommitmentsWithNonces, SecretSource, SignerError,
 };
-use crate::signer::{SignFrostRequest, Signer};
+use crate::signer::{SignFrostRequest, Signer, SigningContext};
 
 pub struct SignAggregateFrostParams<'a> {
     pub signer: &'a Arc<dyn Signer>,
     pub sighash: &'a TapSighash,
+    pub context: &'a SigningContext,
     pub signing_public_key: &'a PublicKey,
     pub aggregating_public_key: &'a PublicKey,
     pub signing_private_key: &'a SecretSource,
@@ -37,6 +38,7 @@ pub struct SignAggregateFrostParams<'a> {
 /// * `params` - A `SignAggregateFrostParams` struct containing:
 ///   - `signer`: Reference to the signer implementation
 ///   - `sighash`: Pre-computed taproot sighash for the transaction
+///   - `context`: The wallet operation the signature is for
 ///   - `signing_public_key`: The public key to use for signing (user's key)
 ///   - `aggregating_public_key`: The public key to use for aggregation
 ///   - `signing_private_key`: The private key source for signing
@@ -58,6 +60,7 @@ pub async fn sign_aggregate_frost(
         .signer
         .sign_frost(SignFrostRequest {
             message: params.sighash.as_byte_array(),
+            context: params.context,
             public_key: params.signing_public_key,
             private_key: params.signing_private_key,
             verifying_key: params.verifying_key,
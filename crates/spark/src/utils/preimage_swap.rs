@@ -17,7 +17,7 @@ use crate::{
         },
     },
     services::{LeafKeyTweak, ServiceError, TransferId, map_signing_nonce_commitments},
-    signer::Signer,
+    signer::{Signer, SigningContext},
     utils::{
         refund::{SignRefundsParams, SignedRefundTransactions, sign_refunds},
         time::web_time_to_prost_timestamp,
@@ -35,6 +35,7 @@ pub(crate) struct SwapNodesForPreimageRequest<'a> {
     pub is_inbound_payment: bool,
     pub transfer_request: Option<StartTransferRequest>,
     pub expiry_time: &'a SystemTime,
+    pub context: &'a SigningContext,
 }
 
 pub(crate) async fn swap_nodes_for_preimage(
@@ -54,6 +55,7 @@ pub(crate) async fn swap_nodes_for_preimage(
         is_inbound_payment,
         transfer_request,
         expiry_time,
+        context,
     } = req;
     // get signing commitments
     let node_ids: Vec<String> = leaves
@@ -100,6 +102,7 @@ pub(crate) async fn swap_nodes_for_preimage(
         payment_hash: None,
         network,
         cpfp_adaptor_public_key: None, // Preimage swaps don't use adaptor signatures
+        context,
     })
     .await?;
 
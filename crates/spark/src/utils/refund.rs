@@ -11,7 +11,7 @@ use tracing::{info, trace};
 
 use crate::core::{current_sequence, enforce_timelock, next_lightning_htlc_sequence};
 use crate::services::{LeafRefundSigningData, ServiceError, SignedTx};
-use crate::signer::{SignFrostRequest, Signer, SignerError};
+use crate::signer::{SignFrostRequest, Signer, SignerError, SigningContext};
 use crate::tree::{TreeNode, TreeNodeId};
 use crate::utils::frost::{SignAggregateFrostParams, sign_aggregate_frost};
 use crate::utils::htlc_transactions::{
@@ -51,6 +51,7 @@ pub struct SignRefundsParams<'a> {
     pub network: Network,
     /// Optional adaptor public key for creating adaptor signatures (used in swap v3)
     pub cpfp_adaptor_public_key: Option<&'a PublicKey>,
+    pub context: &'a SigningContext,
 }
 
 pub struct SignedRefundTransactions {
@@ -107,6 +108,7 @@ pub async fn sign_refunds(
         payment_hash,
         network,
         cpfp_adaptor_public_key,
+        context,
     } = params;
     let identity_pubkey = signer.get_identity_public_key().await?;
 
@@ -178,6 +180,7 @@ pub async fn sign_refunds(
             cpfp_signing_commitments[i].clone(),
             network,
             cpfp_adaptor_public_key,
+            context,
         )
         .await?;
         cpfp_signed_refunds.push(cpfp_signed_tx);
@@ -198,6 +201,7 @@ pub async fn sign_refunds(
                 direct_signing_commitments[i].clone(),
                 network,
                 None, // Direct transactions don't use adaptor signatures
+                context,
             )
             .await?;
             direct_signed_refunds.push(direct_refund_tx);
@@ -215,6 +219,7 @@ pub async fn sign_refunds(
                 direct_from_cpfp_signing_commitments[i].clone(),
                 network,
                 None, // Direct transactions don't use adaptor signatures
+                context,
             )
             .await?;
             direct_from_cpfp_signed_refunds.push(direct_from_cpfp_signed_tx);
@@ -248,6 +253,8 @@ pub async fn sign_refunds(
 /// * `signing_public_key` - The public key corresponding to the user's signing key
 /// * `spark_commitments` - The FROST signing commitments from the Spark operators
 /// * `network` - The Bitcoin network being used
+/// * `adaptor_public_key` - Optional adaptor public key for adaptor signatures
+/// * `context` - The wallet operation the refund is signed for
 ///
 /// # Returns
 ///
@@ -263,6 +270,7 @@ async fn sign_refund(
     spark_commitments: BTreeMap<Identifier, SigningCommitments>,
     network: Network,
     adaptor_public_key: Option<&PublicKey>,
+    context: &SigningContext,
 ) -> Result<SignedTx, SignerError> {
     let sighash = sighash_from_tx(&refund_tx, 0, &tx.output[0])
         .map_err(|e| SignerError::Generic(e.to_string()))?;
@@ -270,6 +278,7 @@ async fn sign_refund(
     let user_signature = signer
         .sign_frost(SignFrostRequest {
             message: sighash.to_raw_hash().to_byte_array().as_ref(),
+            context,
             public_key: &signing_public_key,
             private_key: &leaf.signing_key,
             verifying_key: &leaf.node.verifying_public_key,
@@ -298,6 +307,7 @@ pub async fn sign_aggregate_refunds(
     cpfp_adaptor_pubkey: Option<&PublicKey>,
     direct_adaptor_pubkey: Option<&PublicKey>,
     direct_from_cpfp_adaptor_pubkey: Option<&PublicKey>,
+    context: &SigningContext,
 ) -> Result<Vec<crate::operator::rpc::spark::NodeSignatures>, ServiceError> {
     let mut node_signatures = Vec::new();
 
@@ -358,6 +368,7 @@ pub async fn sign_aggregate_refunds(
         let refund_tx_signature = sign_aggregate_frost(SignAggregateFrostParams {
             signer,
             sighash: &refund_sighash,
+            context,
             signing_public_key: &leaf_data.signing_public_key,
             aggregating_public_key: &leaf_data.signing_public_key,
             signing_private_key: &leaf_data.signing_private_key,
@@ -405,6 +416,7 @@ pub async fn sign_aggregate_refunds(
             let signature = sign_aggregate_frost(SignAggregateFrostParams {
                 signer,
                 sighash: &direct_refund_sighash,
+                context,
                 signing_public_key: &leaf_data.signing_public_key,
                 aggregating_public_key: &leaf_data.signing_public_key,
                 signing_private_key: &leaf_data.signing_private_key,
@@ -445,6 +457,7 @@ pub async fn sign_aggregate_refunds(
             let signature = sign_aggregate_frost(SignAggregateFrostParams {
                 signer,
                 sighash: &direct_from_cpfp_sighash,
+                context,
                 signing_public_key: &leaf_data.signing_public_key,
                 aggregating_public_key: &leaf_data.signing_public_key,
                 signing_private_key: &leaf_data.signing_private_key,
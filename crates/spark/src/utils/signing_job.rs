@@ -11,7 +11,9 @@ use crate::Network;
 use crate::bitcoin::sighash_from_tx;
 use crate::operator::rpc as operator_rpc;
 use crate::services::{ServiceError, SignedTx};
-use crate::signer::{FrostSigningCommitmentsWithNonces, SecretSource, SignFrostRequest};
+use crate::signer::{
+    FrostSigningCommitmentsWithNonces, SecretSource, SignFrostRequest, SigningContext,
+};
 use crate::{
     signer::{Signer, SignerError},
     tree::TreeNodeId,
@@ -67,6 +69,7 @@ pub async fn sign_signing_jobs(
     signing_jobs: Vec<SigningJob>,
     signing_commitments: Vec<BTreeMap<Identifier, SigningCommitments>>,
     network: Network,
+    context: &SigningContext,
 ) -> Result<Vec<SignedJob>, SignerError> {
     let mut signed_txs = Vec::new();
 
@@ -77,6 +80,7 @@ pub async fn sign_signing_jobs(
         let user_signature = signer
             .sign_frost(SignFrostRequest {
                 message: sighash.to_raw_hash().to_byte_array().as_ref(),
+                context,
                 public_key: &signing_job.signing_public_key,
                 private_key: &private_key,
                 verifying_key: &signing_job.verifying_public_key,
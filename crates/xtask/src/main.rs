@@ -13,7 +13,11 @@ use xshell::{Shell, cmd};
 use crate::docs::{DocSnippetsPackage, check_doc_snippets_cmd};
 use crate::package::{TargetPackage, package_cmd};
 
-const OUT_OF_WORKSPACE_PACKAGES: &[&str] = &["crates/breez-sdk/lnurl/Cargo.toml"];
+const OUT_OF_WORKSPACE_PACKAGES: &[&str] = &[
+    "crates/breez-sdk/lnurl/Cargo.toml",
+    "crates/breez-sdk/remote-signer/Cargo.toml",
+    "crates/breez-sdk/sync-server/Cargo.toml",
+];
 #[derive(Parser, Debug)]
 #[command(name = "xtask")]
 #[command(about = "Workspace tasks")]
@@ -34,6 +38,10 @@ enum Commands {
         #[arg(long)]
         doc: bool,
 
+        /// Features to enable, e.g. `sqlcipher` for the encrypted sqlite storage
+        #[arg(long)]
+        features: Option<String>,
+
         /// Extra args passed after `--` to cargo test
         #[arg(last = true)]
         rest: Vec<String>,
@@ -119,7 +127,12 @@ enum Commands {
 fn main() -> Result<()> {
     let cli = Cli::parse();
     match cli.command {
-        Commands::Test { package, doc, rest } => test_cmd(package, doc, rest),
+        Commands::Test {
+            package,
+            doc,
+            features,
+            rest,
+        } => test_cmd(package, doc, features, rest),
         Commands::WasmTest {
             package,
             browser,
@@ -154,7 +167,12 @@ fn workspace_exclude_wasm() -> Vec<String> {
     vec!["--exclude".to_string(), "breez-sdk-spark-wasm".to_string()]
 }
 
-fn test_cmd(package: Option<String>, doc: bool, rest: Vec<String>) -> Result<()> {
+fn test_cmd(
+    package: Option<String>,
+    doc: bool,
+    features: Option<String>,
+    rest: Vec<String>,
+) -> Result<()> {
     let mut c = Command::new("cargo");
     c.arg("test");
     c.arg("--no-fail-fast");
@@ -170,6 +188,9 @@ fn test_cmd(package: Option<String>, doc: bool, rest: Vec<String>) -> Result<()>
     if doc {
         c.arg("--doc");
     }
+    if let Some(features) = features {
+        c.args(["--features", &features]);
+    }
     if !rest.is_empty() {
         c.arg("--").args(&rest);
     }
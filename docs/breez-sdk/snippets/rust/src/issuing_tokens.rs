@@ -115,6 +115,7 @@ async fn freeze_token(token_issuer: &TokenIssuer) -> Result<()> {
     // Freeze the tokens held at the specified Spark address
     let freeze_request = FreezeIssuerTokenRequest {
         address: spark_address.clone(),
+        reason: None,
     };
     let freeze_response = token_issuer.freeze_issuer_token(freeze_request).await?;
 
@@ -23,6 +23,7 @@ pub enum _DepositClaimError {
 pub enum _SdkError {
     SparkError(String),
     InsufficientFunds,
+    TokenOutputsFrozen,
     InvalidUuid(String),
     InvalidInput(String),
     NetworkError(String),
@@ -1,9 +1,12 @@
 use std::sync::Arc;
 
 use breez_sdk_spark::{
-    BurnIssuerTokenRequest, CreateIssuerTokenRequest, FreezeIssuerTokenRequest,
-    FreezeIssuerTokenResponse, MintIssuerTokenRequest, Payment, SdkError, TokenBalance,
-    TokenMetadata, UnfreezeIssuerTokenRequest, UnfreezeIssuerTokenResponse,
+    BurnIssuerTokenRequest, CreateIssuerTokenRequest, DistributeIssuerTokenRequest,
+    DistributeIssuerTokenResponse, FreezeIssuerTokenRequest, FreezeIssuerTokenResponse,
+    IssuerTokenSupply, ListFrozenTokenHoldersRequest, ListFrozenTokenHoldersResponse,
+    ListIssuerTokenTransactionsRequest, ListIssuerTokenTransactionsResponse,
+    MintIssuerTokenRequest, Payment, SdkError, TokenBalance, TokenMetadata,
+    UnfreezeIssuerTokenRequest, UnfreezeIssuerTokenResponse,
 };
 
 pub struct TokenIssuer {
@@ -53,4 +56,29 @@ impl TokenIssuer {
     ) -> Result<UnfreezeIssuerTokenResponse, SdkError> {
         self.token_issuer.unfreeze_issuer_token(request).await
     }
+
+    pub async fn distribute_issuer_token(
+        &self,
+        request: DistributeIssuerTokenRequest,
+    ) -> Result<DistributeIssuerTokenResponse, SdkError> {
+        self.token_issuer.distribute_issuer_token(request).await
+    }
+
+    pub async fn get_issuer_token_supply(&self) -> Result<IssuerTokenSupply, SdkError> {
+        self.token_issuer.get_issuer_token_supply().await
+    }
+
+    pub async fn list_issuer_token_transactions(
+        &self,
+        request: ListIssuerTokenTransactionsRequest,
+    ) -> Result<ListIssuerTokenTransactionsResponse, SdkError> {
+        self.token_issuer.list_issuer_token_transactions(request).await
+    }
+
+    pub async fn list_frozen_token_holders(
+        &self,
+        request: ListFrozenTokenHoldersRequest,
+    ) -> Result<ListFrozenTokenHoldersResponse, SdkError> {
+        self.token_issuer.list_frozen_token_holders(request).await
+    }
 }
@@ -98,6 +98,25 @@ pub struct _CheckMessageResponse {
     pub is_valid: bool,
 }
 
+#[frb(mirror(ExportBackupResponse))]
+pub struct _ExportBackupResponse {
+    pub backup: Vec<u8>,
+}
+
+#[frb(mirror(ImportBackupRequest))]
+pub struct _ImportBackupRequest {
+    pub backup: Vec<u8>,
+}
+
+#[frb(mirror(ImportBackupResponse))]
+pub struct _ImportBackupResponse {
+    pub payments: u32,
+    pub payment_metadata: u32,
+    pub deposits: u32,
+    pub lnurl_metadata: u32,
+    pub cached_items: u32,
+}
+
 #[frb(mirror(ClaimDepositRequest))]
 pub struct _ClaimDepositRequest {
     pub txid: String,
@@ -154,6 +173,10 @@ pub struct _GetInfoResponse {
 #[frb(mirror(TokenBalance))]
 pub struct _TokenBalance {
     pub balance: u128,
+    pub available_balance: u128,
+    pub pending_outgoing_balance: u128,
+    pub reserved_for_conversion_balance: u128,
+    pub frozen_balance: u128,
     pub token_metadata: TokenMetadata,
 }
 
@@ -166,6 +189,15 @@ pub struct _TokenMetadata {
     pub decimals: u32,
     pub max_supply: u128,
     pub is_freezable: bool,
+    pub registry_info: Option<TokenRegistryInfo>,
+}
+
+#[frb(mirror(TokenRegistryInfo))]
+pub struct _TokenRegistryInfo {
+    pub icon_url: Option<String>,
+    pub issuer_name: Option<String>,
+    pub issuer_url: Option<String>,
+    pub verified: bool,
 }
 
 #[frb(mirror(GetPaymentRequest))]
@@ -276,6 +308,7 @@ pub struct _LnurlReceiveMetadata {
     pub nostr_zap_request: Option<String>,
     pub nostr_zap_receipt: Option<String>,
     pub sender_comment: Option<String>,
+    pub payer_data: Option<LnurlPayerData>,
 }
 
 #[frb(mirror(LnurlWithdrawRequest))]
@@ -325,6 +358,14 @@ pub struct _PrepareLnurlPayRequest {
     pub validate_success_action_url: Option<bool>,
     pub conversion_options: Option<ConversionOptions>,
     pub fee_policy: Option<FeePolicy>,
+    pub payer_data: Option<LnurlPayerDataOptions>,
+}
+
+#[frb(mirror(LnurlPayerDataOptions))]
+pub struct _LnurlPayerDataOptions {
+    pub name: Option<String>,
+    pub identifier: Option<String>,
+    pub share_linking_key: bool,
 }
 
 #[frb(mirror(PrepareLnurlPayResponse))]
@@ -337,6 +378,7 @@ pub struct _PrepareLnurlPayResponse {
     pub success_action: Option<SuccessAction>,
     pub conversion_estimate: Option<ConversionEstimate>,
     pub fee_policy: FeePolicy,
+    pub payer_data: Option<LnurlPayerData>,
 }
 
 #[frb(mirror(PrepareSendPaymentRequest))]
@@ -833,6 +875,41 @@ pub struct _LnurlPayRequestDetails {
     pub address: Option<String>,
     pub allows_nostr: Option<bool>,
     pub nostr_pubkey: Option<String>,
+    pub payer_data: Option<LnurlPayerDataRequest>,
+}
+
+#[frb(mirror(LnurlPayerDataRequest))]
+pub struct _LnurlPayerDataRequest {
+    pub name: Option<LnurlPayerDataField>,
+    pub pubkey: Option<LnurlPayerDataField>,
+    pub identifier: Option<LnurlPayerDataField>,
+    pub auth: Option<LnurlPayerDataAuthField>,
+}
+
+#[frb(mirror(LnurlPayerDataField))]
+pub struct _LnurlPayerDataField {
+    pub mandatory: bool,
+}
+
+#[frb(mirror(LnurlPayerDataAuthField))]
+pub struct _LnurlPayerDataAuthField {
+    pub mandatory: bool,
+    pub k1: String,
+}
+
+#[frb(mirror(LnurlPayerData))]
+pub struct _LnurlPayerData {
+    pub name: Option<String>,
+    pub pubkey: Option<String>,
+    pub identifier: Option<String>,
+    pub auth: Option<LnurlPayerDataAuth>,
+}
+
+#[frb(mirror(LnurlPayerDataAuth))]
+pub struct _LnurlPayerDataAuth {
+    pub key: String,
+    pub k1: String,
+    pub sig: String,
 }
 
 #[frb(mirror(LnurlWithdrawRequestDetails))]
@@ -860,6 +937,20 @@ pub struct _CheckLightningAddressRequest {
 pub struct _RegisterLightningAddressRequest {
     pub username: String,
     pub description: Option<String>,
+    pub min_sendable: Option<u64>,
+    pub max_sendable: Option<u64>,
+    pub comment_allowed: Option<u16>,
+    pub image: Option<String>,
+    pub long_description: Option<String>,
+    pub nostr_identity: Option<NostrIdentity>,
+    pub publish_bip353: Option<bool>,
+}
+
+#[frb(mirror(NostrIdentity))]
+pub struct _NostrIdentity {
+    pub pubkey: String,
+    pub relays: Vec<String>,
+    pub proof: String,
 }
 
 #[frb(mirror(LnurlInfo))]
@@ -874,6 +965,86 @@ pub struct _LightningAddressInfo {
     pub lightning_address: String,
     pub lnurl: LnurlInfo,
     pub username: String,
+    pub min_sendable: Option<u64>,
+    pub max_sendable: Option<u64>,
+    pub comment_allowed: Option<u16>,
+    pub image: Option<String>,
+    pub long_description: Option<String>,
+    pub nostr_identity: Option<NostrIdentity>,
+    pub bip353_address: Option<String>,
+}
+
+#[frb(mirror(LnurlWebhookEvent))]
+pub enum _LnurlWebhookEvent {
+    InvoiceRequested,
+    InvoicePaid,
+}
+
+#[frb(mirror(RegisterLnurlWebhookRequest))]
+pub struct _RegisterLnurlWebhookRequest {
+    pub url: String,
+    pub events: Vec<LnurlWebhookEvent>,
+}
+
+#[frb(mirror(RegisterLnurlWebhookResponse))]
+pub struct _RegisterLnurlWebhookResponse {
+    pub webhook: LnurlWebhook,
+    pub secret: String,
+}
+
+#[frb(mirror(ListLnurlWebhooksResponse))]
+pub struct _ListLnurlWebhooksResponse {
+    pub webhooks: Vec<LnurlWebhook>,
+}
+
+#[frb(mirror(DeleteLnurlWebhookRequest))]
+pub struct _DeleteLnurlWebhookRequest {
+    pub id: String,
+}
+
+#[frb(mirror(LnurlWebhook))]
+pub struct _LnurlWebhook {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<LnurlWebhookEvent>,
+    pub created_at: u64,
+}
+
+#[frb(mirror(CreateLnurlWithdrawLinkRequest))]
+pub struct _CreateLnurlWithdrawLinkRequest {
+    pub min_withdrawable_sat: u64,
+    pub max_withdrawable_sat: u64,
+    pub description: Option<String>,
+    pub max_uses: Option<u32>,
+    pub expires_at: Option<u64>,
+}
+
+#[frb(mirror(CreateLnurlWithdrawLinkResponse))]
+pub struct _CreateLnurlWithdrawLinkResponse {
+    pub link: LnurlWithdrawLink,
+}
+
+#[frb(mirror(ListLnurlWithdrawLinksResponse))]
+pub struct _ListLnurlWithdrawLinksResponse {
+    pub links: Vec<LnurlWithdrawLink>,
+}
+
+#[frb(mirror(DeleteLnurlWithdrawLinkRequest))]
+pub struct _DeleteLnurlWithdrawLinkRequest {
+    pub id: String,
+}
+
+#[frb(mirror(LnurlWithdrawLink))]
+pub struct _LnurlWithdrawLink {
+    pub id: String,
+    pub lnurl: LnurlInfo,
+    pub description: String,
+    pub min_withdrawable_sat: u64,
+    pub max_withdrawable_sat: u64,
+    pub max_uses: Option<u32>,
+    pub uses: u32,
+    pub expires_at: Option<u64>,
+    pub created_at: u64,
 }
 
 #[frb(mirror(KeySetType))]
@@ -1001,6 +1172,24 @@ pub struct _UpdateUserSettingsRequest {
     pub spark_private_mode_enabled: Option<bool>,
 }
 
+#[frb(mirror(SetSyncedValueRequest))]
+pub struct _SetSyncedValueRequest {
+    pub namespace: String,
+    pub key: String,
+    pub value: Option<String>,
+}
+
+#[frb(mirror(GetSyncedValueRequest))]
+pub struct _GetSyncedValueRequest {
+    pub namespace: String,
+    pub key: String,
+}
+
+#[frb(mirror(GetSyncedValueResponse))]
+pub struct _GetSyncedValueResponse {
+    pub value: Option<String>,
+}
+
 #[frb(mirror(CreateIssuerTokenRequest))]
 pub struct _CreateIssuerTokenRequest {
     pub name: String,
@@ -1023,6 +1212,7 @@ pub struct _BurnIssuerTokenRequest {
 #[frb(mirror(FreezeIssuerTokenRequest))]
 pub struct _FreezeIssuerTokenRequest {
     pub address: String,
+    pub reason: Option<String>,
 }
 
 #[frb(mirror(FreezeIssuerTokenResponse))]
@@ -1042,6 +1232,98 @@ pub struct _UnfreezeIssuerTokenResponse {
     pub impacted_token_amount: u128,
 }
 
+#[frb(mirror(TokenDistributionRecipient))]
+pub struct _TokenDistributionRecipient {
+    pub address: String,
+    pub amount: u128,
+}
+
+#[frb(mirror(DistributeIssuerTokenRequest))]
+pub struct _DistributeIssuerTokenRequest {
+    pub recipients: Vec<TokenDistributionRecipient>,
+    pub idempotency_key: Option<String>,
+}
+
+#[frb(mirror(TokenDistributionStatus))]
+pub enum _TokenDistributionStatus {
+    Sent,
+    Failed,
+    Unknown,
+}
+
+#[frb(mirror(TokenDistributionRecipientResult))]
+pub struct _TokenDistributionRecipientResult {
+    pub address: String,
+    pub amount: u128,
+    pub status: TokenDistributionStatus,
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+}
+
+#[frb(mirror(DistributeIssuerTokenResponse))]
+pub struct _DistributeIssuerTokenResponse {
+    pub idempotency_key: String,
+    pub mint_payment: Option<Payment>,
+    pub recipients: Vec<TokenDistributionRecipientResult>,
+}
+
+#[frb(mirror(IssuerTokenSupply))]
+pub struct _IssuerTokenSupply {
+    pub max_supply: u128,
+    pub total_minted: u128,
+    pub total_burned: u128,
+    pub total_supply: u128,
+    pub issuer_balance: u128,
+    pub circulating_supply: u128,
+}
+
+#[frb(mirror(ListIssuerTokenTransactionsRequest))]
+pub struct _ListIssuerTokenTransactionsRequest {
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[frb(mirror(ListIssuerTokenTransactionsResponse))]
+pub struct _ListIssuerTokenTransactionsResponse {
+    pub transactions: Vec<IssuerTokenTransaction>,
+}
+
+#[frb(mirror(IssuerTokenTransaction))]
+pub struct _IssuerTokenTransaction {
+    pub tx_hash: String,
+    pub tx_type: TokenTransactionType,
+    pub status: PaymentStatus,
+    pub timestamp: u64,
+    pub outputs: Vec<IssuerTokenTransactionOutput>,
+}
+
+#[frb(mirror(IssuerTokenTransactionOutput))]
+pub struct _IssuerTokenTransactionOutput {
+    pub owner_public_key: String,
+    pub amount: u128,
+}
+
+#[frb(mirror(ListFrozenTokenHoldersRequest))]
+pub struct _ListFrozenTokenHoldersRequest {
+    pub include_unfrozen: Option<bool>,
+}
+
+#[frb(mirror(ListFrozenTokenHoldersResponse))]
+pub struct _ListFrozenTokenHoldersResponse {
+    pub holders: Vec<FrozenTokenHolder>,
+}
+
+#[frb(mirror(FrozenTokenHolder))]
+pub struct _FrozenTokenHolder {
+    pub address: String,
+    pub owner_public_key: String,
+    pub reason: Option<String>,
+    pub frozen_at: u64,
+    pub impacted_output_ids: Vec<String>,
+    pub impacted_token_amount: u128,
+    pub unfrozen_at: Option<u64>,
+}
+
 #[frb(mirror(RecommendedFees))]
 pub struct _RecommendedFees {
     pub fastest_fee: u64,
@@ -1102,6 +1384,7 @@ pub struct _ConversionInfo {
     pub status: ConversionStatus,
     pub fee: Option<u128>,
     pub purpose: Option<ConversionPurpose>,
+    pub expected_amount_out: Option<u128>,
 }
 
 #[frb(mirror(ConversionOptions))]
@@ -1129,6 +1412,47 @@ pub struct _FetchConversionLimitsResponse {
     pub min_to_amount: Option<u128>,
 }
 
+#[frb(mirror(ListConversionsRequest))]
+pub struct _ListConversionsRequest {
+    pub from_timestamp: Option<u64>,
+    pub to_timestamp: Option<u64>,
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[frb(mirror(ListConversionsResponse))]
+pub struct _ListConversionsResponse {
+    pub conversions: Vec<Conversion>,
+    pub summaries: Vec<ConversionSummary>,
+}
+
+#[frb(mirror(Conversion))]
+pub struct _Conversion {
+    pub conversion_id: String,
+    pub pool_id: String,
+    pub status: ConversionStatus,
+    pub purpose: Option<ConversionPurpose>,
+    pub timestamp: u64,
+    pub from: ConversionStep,
+    pub to: Option<ConversionStep>,
+    pub refund: Option<ConversionStep>,
+    pub fee: u128,
+    pub expected_amount_out: Option<u128>,
+    pub effective_rate: Option<f64>,
+    pub slippage_bps: Option<i64>,
+}
+
+#[frb(mirror(ConversionSummary))]
+pub struct _ConversionSummary {
+    pub from_token_identifier: Option<String>,
+    pub to_token_identifier: Option<String>,
+    pub conversion_count: u32,
+    pub total_from_amount: u128,
+    pub total_to_amount: u128,
+    pub total_fees: u128,
+    pub average_rate: Option<f64>,
+}
+
 #[frb(mirror(BuyBitcoinRequest))]
 pub struct _BuyBitcoinRequest {
     pub locked_amount_sat: Option<u64>,
@@ -177,6 +177,44 @@ impl BreezSdk {
         self.inner.delete_lightning_address().await
     }
 
+    pub async fn create_lnurl_withdraw_link(
+        &self,
+        request: CreateLnurlWithdrawLinkRequest,
+    ) -> Result<CreateLnurlWithdrawLinkResponse, SdkError> {
+        self.inner.create_lnurl_withdraw_link(request).await
+    }
+
+    pub async fn list_lnurl_withdraw_links(
+        &self,
+    ) -> Result<ListLnurlWithdrawLinksResponse, SdkError> {
+        self.inner.list_lnurl_withdraw_links().await
+    }
+
+    pub async fn delete_lnurl_withdraw_link(
+        &self,
+        request: DeleteLnurlWithdrawLinkRequest,
+    ) -> Result<(), SdkError> {
+        self.inner.delete_lnurl_withdraw_link(request).await
+    }
+
+    pub async fn register_lnurl_webhook(
+        &self,
+        request: RegisterLnurlWebhookRequest,
+    ) -> Result<RegisterLnurlWebhookResponse, SdkError> {
+        self.inner.register_lnurl_webhook(request).await
+    }
+
+    pub async fn list_lnurl_webhooks(&self) -> Result<ListLnurlWebhooksResponse, SdkError> {
+        self.inner.list_lnurl_webhooks().await
+    }
+
+    pub async fn delete_lnurl_webhook(
+        &self,
+        request: DeleteLnurlWebhookRequest,
+    ) -> Result<(), SdkError> {
+        self.inner.delete_lnurl_webhook(request).await
+    }
+
     pub async fn list_fiat_currencies(&self) -> Result<ListFiatCurrenciesResponse, SdkError> {
         self.inner.list_fiat_currencies().await
     }
@@ -210,6 +248,17 @@ impl BreezSdk {
         self.inner.check_message(request).await
     }
 
+    pub async fn export_backup(&self) -> Result<ExportBackupResponse, SdkError> {
+        self.inner.export_backup().await
+    }
+
+    pub async fn import_backup(
+        &self,
+        request: ImportBackupRequest,
+    ) -> Result<ImportBackupResponse, SdkError> {
+        self.inner.import_backup(request).await
+    }
+
     pub async fn get_user_settings(&self) -> Result<UserSettings, SdkError> {
         self.inner.get_user_settings().await
     }
@@ -221,6 +270,17 @@ impl BreezSdk {
         self.inner.update_user_settings(request).await
     }
 
+    pub async fn set_synced_value(&self, request: SetSyncedValueRequest) -> Result<(), SdkError> {
+        self.inner.set_synced_value(request).await
+    }
+
+    pub async fn get_synced_value(
+        &self,
+        request: GetSyncedValueRequest,
+    ) -> Result<GetSyncedValueResponse, SdkError> {
+        self.inner.get_synced_value(request).await
+    }
+
     #[frb(sync)]
     pub fn get_token_issuer(&self) -> crate::issuer::TokenIssuer {
         let token_issuer = self.inner.get_token_issuer();
@@ -242,7 +302,7 @@ impl BreezSdk {
     pub fn get_leaf_optimization_progress(&self) -> OptimizationProgress {
         self.inner.get_leaf_optimization_progress().into()
     }
-    
+
     pub async fn fetch_conversion_limits(
         &self,
         request: FetchConversionLimitsRequest,
@@ -250,6 +310,13 @@ impl BreezSdk {
         self.inner.fetch_conversion_limits(request).await
     }
 
+    pub async fn list_conversions(
+        &self,
+        request: ListConversionsRequest,
+    ) -> Result<ListConversionsResponse, SdkError> {
+        self.inner.list_conversions(request).await
+    }
+
     pub async fn buy_bitcoin(
         &self,
         request: BuyBitcoinRequest,